@@ -108,6 +108,7 @@ fn main() {
                 .call(ctx, &mut args, JsValue::new(*funcc))
             {
                 Ok(_) => {
+                    rt.run_jobs();
                     let elapsed = start.elapsed();
                     eprintln!("Executed in {}ms", elapsed.as_nanos() as f64 / 1000000f64);
                 }
@@ -93,27 +93,6 @@ Array.prototype.includes = function includes(searchElement, fromIndex_) {
     return false;
 }
 
-Array.prototype.map = function map(callback, thisArg) {
-    "use strict";
-    var array = ___toObject(this, "Array.prototype.map requires that |this| not be null or undefined");
-
-    var length = array.length;
-    if (length >= 4294967295)
-        throw new RangeError("Out of memory for array elements.")
-
-    var result = new Array(length);
-    for (var i = 0; i < length; i += 1) {
-
-        if (!(i in array)) {
-            continue;
-        }
-
-        var mappedValue = callback.___call(thisArg, array[i], i, array);
-        result[i] = mappedValue;
-    }
-    return result;
-}
-
 Array.prototype.forEach = function forEach(callback, thisArg) {
     "use strict";
     var array = ___toObject(this, "Array.prototype.forEach requires that |this| not be null or undefined");
@@ -129,27 +108,6 @@ Array.prototype.forEach = function forEach(callback, thisArg) {
     }
 }
 
-Array.prototype.filter = function filter(callback, thisArg) {
-    "use strict";
-    var array = ___toObject(this, "Array.prototype.filter requires that |this| not be null or undefined");
-    var length = ___toLength(array.length);
-
-    var result = [];
-    var nextIndex = 0;
-    for (var i = 0; i < length; i++) {
-        if (!(i in array)) {
-            continue;
-        }
-
-        var current = array[i];
-        if (callback.___call(thisArg, current, i, array)) {
-            result[nextIndex] = current;
-            ++nextIndex;
-        }
-    }
-    return result;
-}
-
 Array.prototype.fill = function fill(value, start, end) {
     "use strict";
     var array = ___toObject(this, "Array.prototype.fill requires that |this| not be null or undefined");
@@ -191,256 +149,6 @@ Array.prototype.fill = function fill(value, start, end) {
 
     return array;
 }
-let min = (left, right) => {
-    if (left < right) {
-        return left;
-    }
-    return right;
-}
-const sortMerge = (dst, src, srcIndex, srcEnd, width, comparator) => {
-    "use strict";
-
-    var left = srcIndex;
-    var leftEnd = min(left + width, srcEnd);
-    var right = leftEnd;
-    var rightEnd = min(right + width, srcEnd);
-
-    for (var dstIndex = left; dstIndex < rightEnd; ++dstIndex) {
-        if (right < rightEnd) {
-            if (left >= leftEnd) {
-                dst[dstIndex] = src[right];
-                ++right;
-                continue;
-            }
-
-            var comparisonResult = comparator(src[right], src[left]);
-            if (comparisonResult === false || comparisonResult < 0) {
-
-                dst[dstIndex] = src[right];
-                ++right;
-                continue;
-            }
-
-        }
-
-        dst[dstIndex] = src[left];
-        ++left;
-    }
-}
-
-
-const sortMergeSort = (array, comparator) => {
-    "use strict";
-
-    var valueCount = array.length;
-    var buffer = new Array(valueCount);
-
-    var dst = buffer;
-    var src = array;
-    for (var width = 1; width < valueCount; width = width * 2) {
-        for (var srcIndex = 0; srcIndex < valueCount; srcIndex = srcIndex + 2 * width)
-            sortMerge(dst, src, srcIndex, valueCount, width, comparator);
-
-        var tmp = src;
-        src = dst;
-        dst = tmp;
-    }
-
-    return src;
-}
-
-const sortStringComparator = (a, b) => {
-    "use strict";
-
-    var aString = a.string;
-    var bString = b.string;
-
-    if (aString === bString)
-        return 0;
-
-    return aString > bString ? 1 : -1;
-}
-
-const sortBucketSort = (array, dst, bucket, depth) => {
-    "use strict";
-
-    if (bucket.length < 32 || depth > 32) {
-        var sorted = sortMergeSort(bucket, sortStringComparator);
-        for (var i = 0; i < sorted.length; ++i) {
-            array[dst] = sorted[i].value;
-            ++dst;
-        }
-        return dst;
-    }
-
-    var buckets = [];
-    for (var i = 0; i < bucket.length; ++i) {
-        var entry = bucket[i];
-        var string = entry.string;
-        if (string.length == depth) {
-
-            array[dst] = entry.value;
-            ++dst;
-            continue;
-        }
-
-        var c = string[depth];
-        var cBucket = buckets[c];
-        if (cBucket)
-            cBucket.push(entry);
-        else
-            buckets[c] = [entry];
-    }
-
-    for (var i = 0; i < buckets.length; ++i) {
-        if (!buckets[i])
-            continue;
-        dst = sortBucketSort(array, dst, buckets[i], depth + 1);
-    }
-
-    return dst;
-}
-
-
-const sortCommit = (receiver, receiverLength, sorted, undefinedCount) => {
-    "use strict";
-
-    // Move undefineds and holes to the end of an array. Result is [values..., undefineds..., holes...].
-
-    var sortedLength = sorted.length;
-
-    var i = 0;
-
-    for (; i < sortedLength; ++i)
-        receiver[i] = sorted[i];
-
-    for (; i < sortedLength + undefinedCount; ++i)
-        receiver[i] = undefined;
-
-    for (; i < receiverLength; ++i)
-        delete receiver[i];
-}
-const sortCompact = (receiver, receiverLength, compacted, isStringSort) => {
-    "use strict";
-
-    var undefinedCount = 0;
-    var compactedIndex = 0;
-
-    for (var i = 0; i < receiverLength; ++i) {
-        if (i in receiver) {
-            var value = receiver[i];
-            if (value === undefined)
-                ++undefinedCount;
-            else {
-                if (isStringSort) {
-                    compacted[compactedIndex] = {
-                        string: toString(value),
-                        value: value
-                    }
-                } else {
-                    compacted[compactedIndex] = value;
-                }
-
-                ++compactedIndex;
-            }
-        }
-    }
-
-    return undefinedCount;
-}
-
-Array.prototype.sort = function (comparator) {
-    "use strict";
-
-    var isStringSort = false;
-    if (comparator === undefined)
-        isStringSort = true;
-
-    var receiver = ___toObject(this, "Array.prototype.sort requires that |this| not be null or undefined");
-    var receiverLength = ___toLength(receiver.length);
-
-    // For compatibility with Firefox and Chrome, do nothing observable
-    // to the target array if it has 0 or 1 sortable properties.
-    if (receiverLength < 2)
-        return receiver;
-
-    var compacted = [];
-    var sorted = null;
-    var undefinedCount = sortCompact(receiver, receiverLength, compacted, isStringSort);
-    if (isStringSort) {
-        sorted = new Array(compacted.length);
-        sortBucketSort(sorted, 0, compacted, 0);
-    } else {
-        sorted = sortMergeSort(compacted, comparator);
-    }
-    sortCommit(receiver, receiverLength, sorted, undefinedCount);
-    return receiver;
-}
-let flatIntoArray = function flatIntoArray(target, source, sourceLength, targetIndex, depth) {
-    "use strict";
-
-    for (var sourceIndex = 0; sourceIndex < sourceLength; ++sourceIndex) {
-        if (sourceIndex in source) {
-            var element = source[sourceIndex];
-            if (depth > 0 && Array.isArray(element))
-                targetIndex = flatIntoArray(target, element, ___toLength(element.length), targetIndex, depth - 1);
-            else {
-
-                target[targetIndex] = element;
-                ++targetIndex;
-            }
-        }
-    }
-    return targetIndex;
-}
-Array.prototype.flat = function (depth) {
-    "use strict";
-
-    var array = ___toObject(this, "Array.prototype.flat requires that |this| not be null or undefined");
-    var length = ___toLength(array.length);
-    var depthNum = 1;
-
-    if (depth !== undefined)
-        depthNum = ___toIntegerOrInfinity(depth);
-
-    var result = []
-
-    flatIntoArray(result, array, length, 0, depthNum);
-    return result;
-}
-let flatIntoArrayWithCallback = function flatIntoArrayWithCallback(target, source, sourceLength, targetIndex, callback, thisArg) {
-    "use strict";
-
-    for (var sourceIndex = 0; sourceIndex < sourceLength; ++sourceIndex) {
-        if (sourceIndex in source) {
-            var element = callback.___call(thisArg, source[sourceIndex], sourceIndex, source);
-            if (Array.isArray(element))
-                targetIndex = flatIntoArray(target, element, ___toLength(element.length), targetIndex, 0);
-            else {
-                target[targetIndex] = element;
-
-                ++targetIndex;
-            }
-        }
-    }
-    return target;
-}
-
-Array.prototype.flatMap = function flatMap(callback, thisArg) {
-    "use strict";
-
-    var array = ___toObject(this, "Array.prototype.flatMap requires that |this| not be null or undefined");
-    var length = ___toLength(array.length);
-
-    if (!___isCallable(callback))
-        throw new TypeError("Array.prototype.flatMap callback must be a function");
-
-
-    var result = []
-
-    return flatIntoArrayWithCallback(result, array, length, 0, callback, thisArg);
-}
-
 Array.prototype.at = function at(index) {
     "use strict";
 
@@ -55,8 +55,6 @@ let getSubstitution = function getSubstitution(matched, str, position, captures,
     var replacementLength = replacement.length;
     var result = "";
     var lastStart = 0;
-    if (strIncludes.___call(replacement, '$'))
-        throw "TODO"
     for (var start = 0; start = strIndexOf.___call(replacement, "$", lastStart) /*replacement.indexOf("$", lastStart)*/, start !== -1; lastStart = start) {
 
         if (start - lastStart > 0)
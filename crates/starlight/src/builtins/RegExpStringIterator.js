@@ -2,7 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
 let defineProperty = Object.defineProperty;
-let charCodeAt = String.charCodeAt;
+let charCodeAt = String.prototype.charCodeAt;
 let regexExec = RegExp.prototype.exec;
 function RegExpStringIterator(regexp, string, global, fullUnicode) {
     "use strict";
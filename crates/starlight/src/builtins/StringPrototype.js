@@ -30,11 +30,11 @@ String.prototype.matchAll = function matchAll(arg) {
     }
 
     if (arg) {
-        if (arg instanceof RegExp && arg.flags.includes("g"))
+        if (arg instanceof RegExp && !arg.flags.includes("g"))
             throw new TypeError("String.prototype.matchAll argument must not be a non-global regular expression")
 
         var matcher = arg[Symbol.matchAll];
-        if (!matcher) {
+        if (matcher) {
             return matcher.___call(arg, this);
         }
     }
@@ -0,0 +1,35 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+(function () {
+    let TypedArrayPrototype = Object.getPrototypeOf(Int8Array.prototype);
+    // `length` getter throws TypeError when receiver is not a typed array.
+    let validate = Object.getOwnPropertyDescriptor(TypedArrayPrototype, 'length').get;
+
+    // Built-in methods are not enumerable.
+    let define = function (key, value) {
+        Object.defineProperty(TypedArrayPrototype, key, {
+            value: value,
+            writable: true,
+            enumerable: false,
+            configurable: true
+        });
+    }
+
+    define('keys', function keys() {
+        validate.___call(this);
+        return new ___ArrayIterator(this, "key");
+    });
+
+    define('entries', function entries() {
+        validate.___call(this);
+        return new ___ArrayIterator(this, "key+value");
+    });
+
+    let values = function values() {
+        validate.___call(this);
+        return new ___ArrayIterator(this, "value");
+    }
+    define('values', values);
+    define(Symbol.iterator, values);
+})();
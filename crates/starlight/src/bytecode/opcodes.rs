@@ -218,6 +218,12 @@ pub enum Opcode {
     OP_LOGICAL_NOT,
     OP_POS,
     OP_NEG,
+    /// Increments ToNumeric(value) by one. Used by `++`.
+    OP_INC,
+    /// Decrements ToNumeric(value) by one. Used by `--`.
+    OP_DEC,
+    /// Replaces value with ToNumeric(value). Postfix `++` and `--` evaluate to it.
+    OP_TO_NUMERIC,
     OP_THROW,
     OP_PUSH_CATCH,
     OP_POP_CATCH,
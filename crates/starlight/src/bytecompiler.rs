@@ -5,13 +5,13 @@ use crate::vm::{code_block::FileLocation, *};
 use crate::{
     bytecode::{opcodes::Opcode, TypeFeedBack},
     prelude::*,
-    vm::{code_block::CodeBlock, context::Context},
+    vm::{bigint::JsBigInt, code_block::CodeBlock, context::Context},
 };
 use std::convert::TryInto;
 use std::u16;
-use std::{cell::RefCell, collections::HashMap, ops::Range, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 use swc_common::{errors::Handler, sync::Lrc};
-use swc_common::{FileName, SourceMap};
+use swc_common::{FileName, SourceMap, Span, Spanned};
 use swc_ecmascript::parser::*;
 pub struct LoopControlInfo {
     breaks: Vec<Box<dyn FnOnce(&mut ByteCompiler)>>,
@@ -130,7 +130,8 @@ pub struct ByteCompiler {
     /// Freelist of unused variable indexes
     pub variable_freelist: Vec<u32>,
 
-    pub info: Option<Vec<(Range<usize>, FileLocation)>>,
+    /// Source map of the compiled file, used to fill [CodeBlock::loc].
+    pub source_map: Option<Lrc<SourceMap>>,
 }
 
 impl ByteCompiler {
@@ -505,7 +506,7 @@ impl ByteCompiler {
             variable_freelist: Vec::with_capacity(4),
             code,
             tail_pos: false,
-            info: None,
+            source_map: None,
             fmap: HashMap::new(),
             val_map: HashMap::new(),
             name_map: HashMap::new(),
@@ -527,6 +528,7 @@ impl ByteCompiler {
         let handler = Handler::with_emitter(true, false, Box::new(MyEmiter::default()));
 
         let fm = cm.new_source_file(FileName::Custom("<anonymous>".into()), body);
+        compiler.source_map = Some(cm.clone());
 
         let mut parser = Parser::new(Syntax::Es(init_es_config()), StringInput::from(&*fm), None);
 
@@ -600,7 +602,7 @@ impl ByteCompiler {
             builtins: self.builtins,
             variable_freelist: Vec::with_capacity(4),
             code,
-            info: None,
+            source_map: self.source_map.clone(),
             tail_pos: false,
             fmap: HashMap::new(),
             val_map: HashMap::new(),
@@ -734,6 +736,7 @@ impl ByteCompiler {
         path: &str,
         name: &str,
         module: &Module,
+        source_map: &Lrc<SourceMap>,
     ) -> Result<GcPointer<CodeBlock>, CompileError> {
         let name = name.intern();
 
@@ -742,7 +745,7 @@ impl ByteCompiler {
         let mut compiler = ByteCompiler {
             lci: Vec::new(),
             top_level: true,
-            info: None,
+            source_map: Some(source_map.clone()),
             tail_pos: false,
             builtins: false,
             scope: Rc::new(RefCell::new(Scope {
@@ -898,6 +901,7 @@ impl ByteCompiler {
         path: &str,
         fname: String,
         builtins: bool,
+        source_map: &Lrc<SourceMap>,
     ) -> Result<GcPointer<CodeBlock>, CompileError> {
         let name = "<script>".intern();
         let mut code = CodeBlock::new(ctx, name, false, path.into());
@@ -905,7 +909,7 @@ impl ByteCompiler {
         let mut compiler = ByteCompiler {
             lci: Vec::new(),
             top_level: true,
-            info: None,
+            source_map: Some(source_map.clone()),
             tail_pos: false,
             builtins,
             scope: Rc::new(RefCell::new(Scope {
@@ -942,6 +946,7 @@ impl ByteCompiler {
         path: &str,
         fname: String,
         builtins: bool,
+        source_map: &Lrc<SourceMap>,
     ) -> Result<GcPointer<CodeBlock>, CompileError> {
         let name = "<script>".intern();
         let mut code = CodeBlock::new(ctx, name, false, path.into());
@@ -949,7 +954,7 @@ impl ByteCompiler {
         let mut compiler = ByteCompiler {
             lci: Vec::new(),
             top_level: true,
-            info: None,
+            source_map: Some(source_map.clone()),
             tail_pos: false,
             builtins,
             scope: Rc::new(RefCell::new(Scope {
@@ -1129,7 +1134,32 @@ impl ByteCompiler {
         }
         Ok(())
     }
+    /// Records source location of `span` for the code emitted since `start`.
+    fn record_location(&mut self, span: Span, start: usize) {
+        let end = self.code.code.len();
+        if span.is_dummy() || start == end {
+            return;
+        }
+        if let Some(ref source_map) = self.source_map {
+            let loc = source_map.lookup_char_pos(span.lo);
+            self.code.loc.push((
+                start..end,
+                FileLocation {
+                    line: loc.line as u32,
+                    col: loc.col.0 as u32 + 1,
+                },
+            ));
+        }
+    }
+
     pub fn stmt(&mut self, ctx: GcPointer<Context>, stmt: &Stmt) -> Result<(), CompileError> {
+        let start = self.code.code.len();
+        self.compile_stmt(ctx, stmt)?;
+        self.record_location(stmt.span(), start);
+        Ok(())
+    }
+
+    fn compile_stmt(&mut self, ctx: GcPointer<Context>, stmt: &Stmt) -> Result<(), CompileError> {
         match stmt {
             Stmt::Switch(switch) => {
                 let d = self.scope.borrow().depth;
@@ -1511,6 +1541,23 @@ impl ByteCompiler {
         expr: &Expr,
         used: bool,
         tail: bool,
+    ) -> Result<(), CompileError> {
+        let start = self.code.code.len();
+        self.compile_expr(ctx, expr, used, tail)?;
+        // Calls get their own location so that stack frames point at the call, not at the
+        // start of the statement.
+        if let Expr::Call(_) | Expr::New(_) = expr {
+            self.record_location(expr.span(), start);
+        }
+        Ok(())
+    }
+
+    fn compile_expr(
+        &mut self,
+        ctx: GcPointer<Context>,
+        expr: &Expr,
+        used: bool,
+        tail: bool,
     ) -> Result<(), CompileError> {
         match expr {
             Expr::Yield(yield_expr) => {
@@ -1579,10 +1626,12 @@ impl ByteCompiler {
                         let val = self.get_val2(regexp);
                         self.emit(Opcode::OP_PUSH_LITERAL, &[val], false);
                     }
-                    Lit::BigInt(_) => {
-                        return Err(CompileError::NotYetImpl(
-                            "Unimplemented JS literal: BigInt".to_string(),
-                        ))
+                    Lit::BigInt(big) => {
+                        // swc uses its own `num-bigint` version, convert through two's complement bytes.
+                        let value =
+                            num::BigInt::from_signed_bytes_le(&big.value.to_signed_bytes_le());
+                        let val = self.get_val2(JsValue::new(JsBigInt::new(ctx, value)));
+                        self.emit(Opcode::OP_PUSH_LITERAL, &[val], false);
                     }
                     Lit::JSXText(_) => {
                         return Err(CompileError::NotYetImpl(
@@ -1797,13 +1846,12 @@ impl ByteCompiler {
             }
             Expr::Update(update) => {
                 let op = match update.op {
-                    UpdateOp::PlusPlus => Opcode::OP_ADD,
-                    UpdateOp::MinusMinus => Opcode::OP_SUB,
+                    UpdateOp::PlusPlus => Opcode::OP_INC,
+                    UpdateOp::MinusMinus => Opcode::OP_DEC,
                 };
                 if update.prefix {
                     self.expr(ctx, &update.arg, true, false)?;
-                    self.emit(Opcode::OP_PUSH_INT, &[1i32 as u32], false);
-                    self.emit(op, &[0], false);
+                    self.emit(op, &[], false);
                     if used {
                         self.emit(Opcode::OP_DUP, &[], false);
                     }
@@ -1812,11 +1860,11 @@ impl ByteCompiler {
                     //self.emit_store_expr(&update.arg);
                 } else {
                     self.expr(ctx, &update.arg, true, false)?;
+                    self.emit(Opcode::OP_TO_NUMERIC, &[], false);
                     if used {
                         self.emit(Opcode::OP_DUP, &[], false);
                     }
-                    self.emit(Opcode::OP_PUSH_INT, &[1i32 as u32], false);
-                    self.emit(op, &[0], false);
+                    self.emit(op, &[], false);
                     let acc = self.compile_access(ctx, &update.arg, false)?;
                     self.access_set(acc)?;
                     //self.emit_store_expr(&update.arg);
@@ -2017,7 +2065,7 @@ impl ByteCompiler {
                     variable_freelist: vec![],
                     val_map: Default::default(),
                     name_map: Default::default(),
-                    info: None,
+                    source_map: self.source_map.clone(),
                     fmap: Default::default(),
                     scope: Rc::new(RefCell::new(Scope {
                         parent: Some(self.scope.clone()),
@@ -47,6 +47,8 @@ pub const S_WEAK_REF: &str = "WeakRef";
 
 pub const S_DEREF: &str = "deref";
 
+pub const S_FINALIZATION_REGISTRY: &str = "FinalizationRegistry";
+
 // Array
 
 pub const S_ARRAY: &str = "Array";
@@ -91,6 +93,8 @@ pub const S_RANGE_ERROR: &str = "RangeError";
 
 pub const S_URI_ERROR: &str = "URIError";
 
+pub const S_AGGREGATE_ERROR: &str = "AggregateError";
+
 // Object
 
 pub const S_OBJECT: &str = "Object";
@@ -14,8 +14,10 @@
 //! `letroot!` is not required to use anymore.
 //!
 #![allow(dead_code, unused_variables)]
+use crate::jsrt::finalization_registry::JsFinalizationRegistry;
 use crate::options::Options;
 use crate::vm::context::Context;
+use crate::vm::object::JsObject;
 use crate::vm::Runtime;
 use crate::{
     gc::cell::*,
@@ -544,6 +546,8 @@ pub struct Heap {
     verbose: bool,
     allocation_color: u8,
     pub(super) weak_refs: Vec<GcPointer<WeakSlot>>,
+    /// All `FinalizationRegistry` instances. This list does not keep registries alive.
+    finalization_registries: Vec<GcPointer<JsObject>>,
 }
 
 impl Heap {
@@ -557,6 +561,7 @@ impl Heap {
             verbose: opts.verbose_gc,
             allocated: 0,
             weak_refs: vec![],
+            finalization_registries: vec![],
             max_heap_size: 256 * 1024,
             threadpool: if opts.parallel_marking {
                 Some(Pool::new(opts.gc_threads as _))
@@ -630,6 +635,26 @@ impl Heap {
             .retain(|weak| bitmap.test(weak.base.as_ptr() as _));
     }
 
+    /// Remove dead registries and let live ones collect cells whose targets were not marked.
+    /// Must be invoked after weak references are updated but before sweeping.
+    fn update_finalization_registries(&mut self) {
+        let bitmap = unsafe { &*(&self.space.mark_bitmap as *const SpaceBitmap<16>) };
+        self.finalization_registries
+            .retain(|registry| bitmap.test(registry.base.as_ptr() as _));
+        for registry in self.finalization_registries.iter_mut() {
+            JsFinalizationRegistry::collect_dead_cells(registry);
+        }
+    }
+
+    /// Queue cleanup jobs of registries that have cleared cells. Cleanup callbacks are never invoked
+    /// while GC cycle is in progress, they run later as ordinary jobs.
+    fn schedule_finalization_cleanups(&mut self) {
+        let registries = self.finalization_registries.clone();
+        for registry in registries {
+            JsFinalizationRegistry::schedule_cleanup(registry);
+        }
+    }
+
     /// This function marks all potential roots. This simply means it executes
     /// all the constraints supplied to GC.
     fn process_roots(&mut self, visitor: &mut SlotVisitor) {
@@ -698,9 +723,11 @@ impl Heap {
         }
 
         self.update_weak_references();
+        self.update_finalization_registries();
         self.reset_weak_references();
         let alloc = self.allocated;
         self.allocated = self.space.sweep();
+        self.schedule_finalization_cleanups();
         logln_if!(
             unlikely(self.verbose),
             "[GC] Sweep {:.4}->{:.4} KB",
@@ -848,6 +875,11 @@ impl Heap {
         }
     }
 
+    /// Register `registry` so its cells are processed after each GC cycle.
+    pub(crate) fn add_finalization_registry(&mut self, registry: GcPointer<JsObject>) {
+        self.finalization_registries.push(registry);
+    }
+
     pub fn make_null_weak<T: GcCell>(&mut self) -> WeakRef<T> {
         let weak = self.allocate(WeakSlot { value: None });
         self.weak_refs.push(weak);
@@ -18,6 +18,7 @@ use crate::{
         self,
         arguments::JsArguments,
         array_storage::ArrayStorage,
+        bigint::JsBigInt,
         code_block::{CodeBlock, FileLocation},
         context::Context,
         function::{
@@ -28,7 +29,8 @@ use crate::{
         interpreter::SpreadValue,
         object::{object_size_with_tag, JsObject, ObjectTag},
         property_descriptor::{Accessor, StoredSlot},
-        string::{JsString, JsStringObject},
+        proxy::JsProxy,
+        string::{JsString, JsStringObject, StringData, StringRepr},
         structure::{
             DeletedEntry, DeletedEntryHolder, MapEntry, Structure, TargetTable, Transition,
             TransitionKey, TransitionsTable,
@@ -42,6 +44,7 @@ use crate::{
 };
 use std::{
     any::TypeId,
+    cell::UnsafeCell,
     collections::HashMap,
     hash::Hash,
     hint::unreachable_unchecked,
@@ -132,14 +135,26 @@ impl<'a> Deserializer<'a> {
         self.symbol_map = vec![DUMMY_SYMBOL; count as usize];
         for _ in 0..count {
             let index = self.get_u32();
+            let kind = self.get_u8();
             let len = self.get_u32();
             /*let mut bytes = vec![];
             for _ in 0..len {
                 bytes.push(self.get_u8());
             }
             let sym = String::from_utf8_unchecked(bytes).intern();*/
-            let sym = std::str::from_utf8_unchecked(&self.reader[self.pc..self.pc + len as usize])
-                .intern();
+            if kind == 3 {
+                let units = (0..len).map(|_| self.get_u16()).collect::<Vec<u16>>();
+                let sym = Symbol::Key(symbol_table().intern_utf16(&units));
+                *self.symbol_map.get_mut(index as usize).unwrap() = sym;
+                continue;
+            }
+            let string =
+                std::str::from_utf8_unchecked(&self.reader[self.pc..self.pc + len as usize]);
+            let sym = match kind {
+                0 => string.intern(),
+                1 => Symbol::Key(symbol_table().unique(Some(string))),
+                _ => Symbol::Key(symbol_table().unique(None)),
+            };
             self.pc += len as usize;
             *self.symbol_map.get_mut(index as usize).unwrap() = sym;
         }
@@ -315,6 +330,45 @@ impl<'a> Deserializer<'a> {
             date_structure: self.read_opt_gc(),
             boolean_structure: self.read_opt_gc(),
             date_prototype: self.read_opt_gc(),
+            finalization_registry_structure: self.read_opt_gc(),
+            finalization_registry_prototype: self.read_opt_gc(),
+            typed_array_prototype: self.read_opt_gc(),
+            int8_array_structure: self.read_opt_gc(),
+            uint8_array_structure: self.read_opt_gc(),
+            uint8_clamped_array_structure: self.read_opt_gc(),
+            int16_array_structure: self.read_opt_gc(),
+            uint16_array_structure: self.read_opt_gc(),
+            int32_array_structure: self.read_opt_gc(),
+            uint32_array_structure: self.read_opt_gc(),
+            float32_array_structure: self.read_opt_gc(),
+            float64_array_structure: self.read_opt_gc(),
+            proxy_structure: self.read_opt_gc(),
+            bigint_structure: self.read_opt_gc(),
+            bigint_prototype: self.read_opt_gc(),
+            string_iterator_structure: self.read_opt_gc(),
+            aggregate_error: self.read_opt_gc(),
+            aggregate_error_structure: self.read_opt_gc(),
+            error_constructor: self.read_opt_gc(),
+            call_site_prototype: self.read_opt_gc(),
+            call_site_structure: self.read_opt_gc(),
+            regexp_constructor: self.read_opt_gc(),
+            temporal_duration_prototype: self.read_opt_gc(),
+            temporal_instant_prototype: self.read_opt_gc(),
+            temporal_plain_date_prototype: self.read_opt_gc(),
+            temporal_plain_date_time_prototype: self.read_opt_gc(),
+            temporal_plain_time_prototype: self.read_opt_gc(),
+            temporal_zoned_date_time_prototype: self.read_opt_gc(),
+            intl_collator_prototype: self.read_opt_gc(),
+            intl_date_time_format_prototype: self.read_opt_gc(),
+            intl_number_format_prototype: self.read_opt_gc(),
+            intl_plural_rules_prototype: self.read_opt_gc(),
+            url_prototype: self.read_opt_gc(),
+            url_search_params_prototype: self.read_opt_gc(),
+            url_search_params_iterator_structure: self.read_opt_gc(),
+            text_encoder_prototype: self.read_opt_gc(),
+            text_decoder_prototype: self.read_opt_gc(),
+            map_iterator_structure: self.read_opt_gc(),
+            set_iterator_structure: self.read_opt_gc(),
         }
     }
     /// Deserialize JS runtime from snapshot buffer. If snapshot has external references that is not part of the VM i.e some native function
@@ -509,33 +563,67 @@ impl<T: GcCell + ?Sized> Deserializable for GcPointer<T> {
 
 impl Deserializable for JsString {
     unsafe fn dummy_read(deser: &mut Deserializer) {
+        let latin1 = deser.get_u8() != 0;
         let sz = deser.get_u32();
         for _ in 0..sz {
-            deser.get_u8();
+            if latin1 {
+                deser.get_u8();
+            } else {
+                deser.get_u16();
+            }
         }
     }
     unsafe fn deserialize_inplace(_deser: &mut Deserializer) -> Self {
         unreachable!()
     }
     unsafe fn deserialize(at: *mut u8, deser: &mut Deserializer) {
+        let latin1 = deser.get_u8() != 0;
         let size = deser.get_u32();
-        let mut bytes = Vec::with_capacity(size as _);
-        for _ in 0..size {
-            bytes.push(deser.get_u8());
-        }
+        let data = if latin1 {
+            StringData::Latin1((0..size).map(|_| deser.get_u8()).collect())
+        } else {
+            StringData::Utf16((0..size).map(|_| deser.get_u16()).collect())
+        };
 
         at.cast::<JsString>().write(JsString {
-            string: String::from_utf8_unchecked(bytes),
+            repr: UnsafeCell::new(StringRepr::Flat(data)),
         })
     }
     unsafe fn allocate(rt: &mut Runtime, deser: &mut Deserializer) -> *mut GcPointerBase {
+        Self::dummy_read(deser);
+        rt.heap().allocate_raw(
+            vtable_of_type::<Self>() as _,
+            size_of::<JsString>(),
+            TypeId::of::<Self>(),
+        )
+    }
+}
+
+impl Deserializable for JsBigInt {
+    unsafe fn dummy_read(deser: &mut Deserializer) {
+        let sz = deser.get_u32();
+        for _ in 0..sz {
+            deser.get_u8();
+        }
+    }
+    unsafe fn deserialize_inplace(deser: &mut Deserializer) -> Self {
         let size = deser.get_u32();
+        let mut bytes = Vec::with_capacity(size as _);
         for _ in 0..size {
-            deser.get_u8();
+            bytes.push(deser.get_u8());
         }
+        Self {
+            value: num::BigInt::from_signed_bytes_le(&bytes),
+        }
+    }
+    unsafe fn deserialize(at: *mut u8, deser: &mut Deserializer) {
+        at.cast::<Self>().write(Self::deserialize_inplace(deser));
+    }
+    unsafe fn allocate(rt: &mut Runtime, deser: &mut Deserializer) -> *mut GcPointerBase {
+        Self::dummy_read(deser);
         rt.heap().allocate_raw(
             vtable_of_type::<Self>() as _,
-            size_of::<JsString>(),
+            size_of::<Self>(),
             TypeId::of::<Self>(),
         )
     }
@@ -811,6 +899,7 @@ impl Deserializable for Context {
         ctx.global_object = deser.read_opt_gc();
 
         ctx.symbol_table = HashMap::<Symbol, GcPointer<JsSymbol>>::deserialize_inplace(deser);
+        ctx.symbols = HashMap::<Symbol, GcPointer<JsSymbol>>::deserialize_inplace(deser);
         ctx.module_loader = deser.read_opt_gc();
         ctx.modules = HashMap::<String, ModuleKind>::deserialize_inplace(deser);
         ctx
@@ -904,6 +993,7 @@ impl Deserializable for JsObject {
                             function: transmute(func),
                         })
                     }
+                    0x05 => FuncType::Proxy(JsProxy::deserialize_inplace(deser)),
                     _ => unreachable!(),
                 };
 
@@ -1280,9 +1370,11 @@ impl Deserializable for MapEntry {
     unsafe fn deserialize_inplace(deser: &mut Deserializer) -> Self {
         let offset = deser.get_u32();
         let attrs = deser.get_u32();
+        let order = deser.get_u32();
         Self {
             offset,
             attrs: transmute(attrs),
+            order,
         }
     }
 
@@ -1451,6 +1543,7 @@ impl Deserializable for Structure {
         let has_been_flattened_before = bool::deserialize_inplace(deser);
         let cached_prototype_chain =
             Option::<GcPointer<StructureChain>>::deserialize_inplace(deser);
+        let next_order = deser.get_u32();
         Self {
             table,
             transitions,
@@ -1463,6 +1556,7 @@ impl Deserializable for Structure {
             id: 0,
             has_been_flattened_before,
             cached_prototype_chain,
+            next_order,
         }
     }
 
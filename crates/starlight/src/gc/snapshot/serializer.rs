@@ -8,6 +8,7 @@ use crate::{
         arguments::JsArguments,
         array_storage::ArrayStorage,
         attributes::AttrSafe,
+        bigint::JsBigInt,
         code_block::CodeBlock,
         context::Context,
         function::{FuncType, JsFunction},
@@ -17,7 +18,7 @@ use crate::{
         object::{JsObject, ObjectTag},
         property_descriptor::{Accessor, StoredSlot},
         slot::*,
-        string::JsString,
+        string::{JsString, StringData},
         structure::{
             DeletedEntry, DeletedEntryHolder, MapEntry, Structure, Transition, TransitionKey,
             TransitionsTable,
@@ -99,12 +100,42 @@ impl SnapshotSerializer {
             let ix = self.symbol_map.len() as u32;
             self.symbol_map.insert(Symbol::Key(SymbolID(*index)), ix);
             self.write_u32(ix);
+            self.write_u8(0);
             self.write_u32(key.len() as u32);
             for byte in key.bytes() {
                 self.write_u8(byte);
             }
             count += 1;
         }
+        // unique symbols are written with their descriptions and recreated on deserialization,
+        // `Symbol::Private` of them is looked up by `Symbol::Key` just like for interned strings.
+        for entry in symtab.unique.iter() {
+            let id = SymbolID(*entry.key());
+            let description = symtab.description(id);
+            let ix = self.symbol_map.len() as u32;
+            self.symbol_map.insert(Symbol::Key(id), ix);
+            self.write_u32(ix);
+            self.write_u8(if *entry.value() { 1 } else { 2 });
+            self.write_u32(description.len() as u32);
+            for byte in description.bytes() {
+                self.write_u8(byte);
+            }
+            count += 1;
+        }
+        // strings with unpaired surrogates are written as UTF-16 code units.
+        for entry in symtab.utf16.iter() {
+            let units = entry.key();
+            let index = entry.value();
+            let ix = self.symbol_map.len() as u32;
+            self.symbol_map.insert(Symbol::Key(SymbolID(*index)), ix);
+            self.write_u32(ix);
+            self.write_u8(3);
+            self.write_u32(units.len() as u32);
+            for unit in units.iter() {
+                self.write_u16(*unit);
+            }
+            count += 1;
+        }
         let count = count.to_le_bytes();
         self.output[patch_at] = count[0];
         self.output[patch_at + 1] = count[1];
@@ -420,9 +451,20 @@ impl<T: GcCell> Serializable for WeakRef<T> {
 
 impl Serializable for JsString {
     fn serialize(&self, serializer: &mut SnapshotSerializer) {
+        serializer.write_u8(self.is_latin1() as u8);
         serializer.write_u32(self.len());
-        for byte in self.as_str().bytes() {
-            serializer.write_u8(byte);
+        // ropes are serialized flattened.
+        match self.flat() {
+            StringData::Latin1(chars) => {
+                for &c in chars.iter() {
+                    serializer.write_u8(c);
+                }
+            }
+            StringData::Utf16(units) => {
+                for &unit in units.iter() {
+                    serializer.write_u16(unit);
+                }
+            }
         }
     }
 }
@@ -433,6 +475,16 @@ impl Serializable for Symbol {
     }
 }
 
+impl Serializable for JsBigInt {
+    fn serialize(&self, serializer: &mut SnapshotSerializer) {
+        let bytes = self.value().to_signed_bytes_le();
+        serializer.write_u32(bytes.len() as _);
+        for byte in bytes {
+            serializer.write_u8(byte);
+        }
+    }
+}
+
 impl<T: Serializable> Serializable for Vec<T> {
     fn serialize(&self, serializer: &mut SnapshotSerializer) {
         serializer.write_u64(self.len() as _);
@@ -534,6 +586,10 @@ impl Serializable for JsFunction {
                 serializer.write_u8(0x04);
                 gen_fn.function.serialize(serializer);
             }
+            FuncType::Proxy(proxy) => {
+                serializer.write_u8(0x05);
+                proxy.serialize(serializer);
+            }
         }
     }
 }
@@ -646,6 +702,7 @@ impl Serializable for MapEntry {
     fn serialize(&self, serializer: &mut SnapshotSerializer) {
         self.offset.serialize(serializer);
         self.attrs.serialize(serializer);
+        self.order.serialize(serializer);
     }
 }
 
@@ -710,6 +767,7 @@ impl Serializable for Structure {
         self.transit_count.serialize(serializer);
         self.has_been_flattened_before.serialize(serializer);
         self.cached_prototype_chain.serialize(serializer);
+        self.next_order.serialize(serializer);
     }
 }
 
@@ -898,6 +956,48 @@ impl Serializable for GlobalData {
         self.date_structure.serialize(serializer);
         self.boolean_structure.serialize(serializer);
         self.date_prototype.serialize(serializer);
+        self.finalization_registry_structure.serialize(serializer);
+        self.finalization_registry_prototype.serialize(serializer);
+        self.typed_array_prototype.serialize(serializer);
+        self.int8_array_structure.serialize(serializer);
+        self.uint8_array_structure.serialize(serializer);
+        self.uint8_clamped_array_structure.serialize(serializer);
+        self.int16_array_structure.serialize(serializer);
+        self.uint16_array_structure.serialize(serializer);
+        self.int32_array_structure.serialize(serializer);
+        self.uint32_array_structure.serialize(serializer);
+        self.float32_array_structure.serialize(serializer);
+        self.float64_array_structure.serialize(serializer);
+        self.proxy_structure.serialize(serializer);
+        self.bigint_structure.serialize(serializer);
+        self.bigint_prototype.serialize(serializer);
+        self.string_iterator_structure.serialize(serializer);
+        self.aggregate_error.serialize(serializer);
+        self.aggregate_error_structure.serialize(serializer);
+        self.error_constructor.serialize(serializer);
+        self.call_site_prototype.serialize(serializer);
+        self.call_site_structure.serialize(serializer);
+        self.regexp_constructor.serialize(serializer);
+        self.temporal_duration_prototype.serialize(serializer);
+        self.temporal_instant_prototype.serialize(serializer);
+        self.temporal_plain_date_prototype.serialize(serializer);
+        self.temporal_plain_date_time_prototype
+            .serialize(serializer);
+        self.temporal_plain_time_prototype.serialize(serializer);
+        self.temporal_zoned_date_time_prototype
+            .serialize(serializer);
+        self.intl_collator_prototype.serialize(serializer);
+        self.intl_date_time_format_prototype.serialize(serializer);
+        self.intl_number_format_prototype.serialize(serializer);
+        self.intl_plural_rules_prototype.serialize(serializer);
+        self.url_prototype.serialize(serializer);
+        self.url_search_params_prototype.serialize(serializer);
+        self.url_search_params_iterator_structure
+            .serialize(serializer);
+        self.text_encoder_prototype.serialize(serializer);
+        self.text_decoder_prototype.serialize(serializer);
+        self.map_iterator_structure.serialize(serializer);
+        self.set_iterator_structure.serialize(serializer);
     }
 }
 
@@ -916,6 +1016,7 @@ impl Serializable for Context {
         self.global_data.serialize(serializer);
         self.global_object.serialize(serializer);
         self.symbol_table.serialize(serializer);
+        self.symbols.serialize(serializer);
         self.module_loader.serialize(serializer);
         self.modules.serialize(serializer);
     }
@@ -10,29 +10,41 @@ use crate::{
         data_view::JsDataView, environment::Environment, error::*, function::*, global::JsGlobal,
         indexed_elements::IndexedElements, interpreter::SpreadValue, number::*, object::*,
         property_descriptor::*, string::*, structure::*, structure_chain::StructureChain,
-        symbol_table::*, value::*, ModuleKind,
+        symbol_table::*, typedarray::TypedArrayKind, value::*, ModuleKind,
     },
 };
 use std::{collections::HashMap, rc::Rc};
 pub mod array;
 pub mod array_buffer;
+pub mod bigint;
 pub mod boolean;
+pub mod console;
 pub mod data_view;
 pub mod date;
+pub mod encoding;
 pub mod error;
 #[cfg(all(target_pointer_width = "64", feature = "ffi"))]
 pub mod ffi;
+pub mod finalization_registry;
 pub mod function;
 pub mod generator;
 pub mod global;
+pub mod intl;
 pub mod jsstd;
+pub mod map;
 pub mod math;
 pub mod number;
 pub mod object;
 pub mod promise;
+pub mod proxy;
+pub mod reflect;
 pub mod regexp;
 pub mod string;
+pub mod structured_clone;
 pub mod symbol;
+pub mod temporal;
+pub mod typed_array;
+pub mod url;
 pub mod weak_ref;
 use array::*;
 use error::*;
@@ -65,10 +77,50 @@ impl GcPointer<Context> {
         def_native_method!(self, global_object, print, print, 0)?;
         def_native_method!(self, global_object, isFinite, global::is_finite, 1)?;
         def_native_method!(self, global_object, isNaN, global::is_nan, 1)?;
-        def_native_method!(self, global_object, parseInt, global::parse_int, 1)?;
+        let mut number = self
+            .global_data
+            .number_prototype
+            .unwrap()
+            .get(self, S_CONSTURCTOR.intern())?
+            .get_jsobject();
+        let parse_int = number.get(self, S_PARSE_INT.intern())?;
+        let parse_float = number.get(self, S_PARSE_FLOAT.intern())?;
+        def_native_property!(self, global_object, parseInt, parse_int, W | C)?;
+        def_native_method!(self, global_object, encodeURI, global::encode_uri, 1)?;
+        def_native_method!(
+            self,
+            global_object,
+            encodeURIComponent,
+            global::encode_uri_component,
+            1
+        )?;
+        def_native_method!(self, global_object, decodeURI, global::decode_uri, 1)?;
+        def_native_method!(
+            self,
+            global_object,
+            decodeURIComponent,
+            global::decode_uri_component,
+            1
+        )?;
+        def_native_method!(self, global_object, escape, global::escape, 1)?;
+        def_native_method!(self, global_object, unescape, global::unescape, 1)?;
         def_native_method!(self, global_object, readLine, global::read_line, 1)?;
-        def_native_method!(self, global_object, parseFloat, global::parse_float, 1)?;
+        def_native_method!(
+            self,
+            global_object,
+            structuredClone,
+            structured_clone::structured_clone,
+            1
+        )?;
+        def_native_property!(self, global_object, parseFloat, parse_float, W | C)?;
         def_native_method!(self, global_object, gc, global::gc, 0)?;
+        def_native_method!(
+            self,
+            global_object,
+            queueMicrotask,
+            global::queue_microtask,
+            1
+        )?;
         def_native_method!(self, global_object, ___trunc, global::___trunc, 1)?;
         def_native_method!(
             self,
@@ -129,8 +181,8 @@ impl GcPointer<Context> {
             include_str!("builtins/ArrayIterator.js"),
         );
         eval(
-            "builtins/StringIterator.js",
-            include_str!("builtins/StringIterator.js"),
+            "builtins/TypedArrayPrototype.js",
+            include_str!("builtins/TypedArrayPrototype.js"),
         );
         eval("builtins/Object.js", include_str!("builtins/Object.js"))
     }
@@ -171,6 +223,18 @@ impl GcPointer<Context> {
         def_native_method!(self, func_proto, apply, function_apply, 0, W | C)?;
         def_native_method!(self, func_proto, call, function_call, 0, W | C)?;
         def_native_method!(self, func_proto, toString, function_to_string, 0, W | C)?;
+        let has_instance = JsNativeFunction::new(
+            self,
+            "[Symbol.hasInstance]".intern(),
+            function_has_instance,
+            1,
+        );
+        func_proto.define_own_property(
+            self,
+            "Symbol.hasInstance".intern().private(),
+            &*DataDescriptor::new(JsValue::new(has_instance), NONE),
+            false,
+        )?;
         Ok(())
     }
     pub(crate) fn init_promise_in_global_object(mut self) -> Result<(), JsValue> {
@@ -195,6 +259,14 @@ impl GcPointer<Context> {
 
         def_native_property!(self, ctor, prototype, proto)?;
         def_native_property!(self, proto, constructor, ctor)?;
+        define_species(self, &mut ctor)?;
+        let tag = JsString::new(self, "Promise");
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
         def_native_property!(self, global_object, Promise, ctor)?;
 
         Ok(())
@@ -228,10 +300,75 @@ impl GcPointer<Context> {
         def_native_property!(self, ctor, prototype, proto)?;
 
         def_native_method!(self, proto, deref, weak_ref::weak_ref_prototype_deref, 0)?;
+        let tag = JsString::new(self, "WeakRef");
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
 
         self.global_data.weak_ref_prototype = Some(proto);
         Ok(())
     }
+    pub(crate) fn init_finalization_registry_in_global_object(mut self) -> Result<(), JsValue> {
+        let mut proto = self.global_data().finalization_registry_prototype.unwrap();
+        let ctor = proto.get(self, S_CONSTURCTOR.intern())?;
+        let mut global_object = self.global_object();
+
+        def_native_property!(self, global_object, FinalizationRegistry, ctor)?;
+        Ok(())
+    }
+    pub(crate) fn init_finalization_registry_in_global_data(mut self) -> Result<(), JsValue> {
+        let obj_proto = self.global_data().object_prototype.unwrap();
+        self.global_data.finalization_registry_structure =
+            Some(Structure::new_indexed(self, None, false));
+        let proto_map = self
+            .global_data
+            .finalization_registry_structure
+            .unwrap()
+            .change_prototype_transition(self, Some(obj_proto));
+        let mut proto = JsObject::new(self, &proto_map, JsObject::get_class(), ObjectTag::Ordinary);
+        self.global_data
+            .finalization_registry_structure
+            .unwrap()
+            .change_prototype_with_no_transition(proto);
+
+        let mut ctor = JsNativeFunction::new(
+            self,
+            S_FINALIZATION_REGISTRY.intern(),
+            finalization_registry::finalization_registry_constructor,
+            1,
+        );
+
+        def_native_property!(self, proto, constructor, ctor)?;
+        def_native_property!(self, ctor, prototype, proto)?;
+
+        def_native_method!(
+            self,
+            proto,
+            register,
+            finalization_registry::finalization_registry_prototype_register,
+            2
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            unregister,
+            finalization_registry::finalization_registry_prototype_unregister,
+            1
+        )?;
+        let tag = JsString::new(self, "FinalizationRegistry");
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+
+        self.global_data.finalization_registry_prototype = Some(proto);
+        Ok(())
+    }
 
     pub(crate) fn init_array_in_global_object(mut self) -> Result<(), JsValue> {
         let mut proto = self.global_data.array_prototype.unwrap();
@@ -264,15 +401,73 @@ impl GcPointer<Context> {
         def_native_method!(self, constructor, isArray, array_is_array, 1)?;
         def_native_method!(self, constructor, of, array_of, 1)?;
         def_native_method!(self, constructor, from, array_from, 1)?;
+        define_species(self, &mut constructor)?;
         def_native_property!(self, proto, constructor, constructor, W | C)?;
-        def_native_method!(self, proto, join, array_join, 1, W | C | E)?;
-        def_native_method!(self, proto, toString, array_to_string, 1, W | C | E)?;
-        def_native_method!(self, proto, push, array_push, 1, W | C | E)?;
-        def_native_method!(self, proto, pop, array_pop, 1, W | C | E)?;
-        def_native_method!(self, proto, reduce, array_reduce, 1, W | C | E)?;
-        def_native_method!(self, proto, slice, array_slice, 1, W | C | E)?;
-        def_native_method!(self, proto, shift, array::array_shift, 0)?;
-        def_native_method!(self, proto, concat, array_concat, 1, W | C | E)?;
+        def_native_method!(self, proto, join, array_join, 1, W | C)?;
+        def_native_method!(self, proto, toString, array_to_string, 0, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toLocaleString,
+            array_to_locale_string,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, proto, push, array_push, 1, W | C)?;
+        def_native_method!(self, proto, pop, array_pop, 0, W | C)?;
+        def_native_method!(self, proto, reduce, array_reduce, 1, W | C)?;
+        def_native_method!(self, proto, reduceRight, array_reduce_right, 1, W | C)?;
+        def_native_method!(self, proto, slice, array_slice, 2, W | C)?;
+        def_native_method!(self, proto, splice, array_splice, 2, W | C)?;
+        def_native_method!(self, proto, shift, array_shift, 0, W | C)?;
+        def_native_method!(self, proto, unshift, array_unshift, 1, W | C)?;
+        def_native_method!(self, proto, concat, array_concat, 1, W | C)?;
+        def_native_method!(self, proto, map, array_map, 1, W | C)?;
+        def_native_method!(self, proto, filter, array_filter, 1, W | C)?;
+        def_native_method!(self, proto, flat, array_flat, 0, W | C)?;
+        def_native_method!(self, proto, flatMap, array_flat_map, 1, W | C)?;
+        def_native_method!(self, proto, indexOf, array_index_of, 1, W | C)?;
+        def_native_method!(self, proto, lastIndexOf, array_last_index_of, 1, W | C)?;
+        def_native_method!(self, proto, reverse, array_reverse, 0, W | C)?;
+        def_native_method!(self, proto, every, array_every, 1, W | C)?;
+        def_native_method!(self, proto, copyWithin, array_copy_within, 2, W | C)?;
+        def_native_method!(self, proto, findLast, array_find_last, 1, W | C)?;
+        def_native_method!(self, proto, findLastIndex, array_find_last_index, 1, W | C)?;
+        def_native_method!(self, proto, sort, array_sort, 1, W | C)?;
+        def_native_method!(self, proto, toSorted, array_to_sorted, 1, W | C)?;
+        def_native_method!(self, proto, toReversed, array_to_reversed, 0, W | C)?;
+        def_native_method!(self, proto, toSpliced, array_to_spliced, 2, W | C)?;
+        def_native_method!(self, proto, with, array_with, 2, W | C)?;
+
+        let structure = Structure::new_unique_indexed(self, None, false);
+        let mut unscopables =
+            JsObject::new(self, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+        for name in &[
+            "at",
+            "copyWithin",
+            "entries",
+            "fill",
+            "find",
+            "findIndex",
+            "findLast",
+            "findLastIndex",
+            "flat",
+            "flatMap",
+            "includes",
+            "keys",
+            "toReversed",
+            "toSorted",
+            "toSpliced",
+            "values",
+        ] {
+            unscopables.put(self, name.intern(), JsValue::new(true), false)?;
+        }
+        proto.define_own_property(
+            self,
+            "Symbol.unscopables".intern().private(),
+            &*DataDescriptor::new(JsValue::new(unscopables), C),
+            false,
+        )?;
         self.global_data.array_prototype = Some(proto);
 
         Ok(())
@@ -286,6 +481,7 @@ impl GcPointer<Context> {
         self.init_reference_error_in_global_object()?;
         self.init_range_error_in_global_object()?;
         self.init_uri_error_in_global_object()?;
+        self.init_aggregate_error_in_global_object()?;
         Ok(())
     }
 
@@ -304,6 +500,79 @@ impl GcPointer<Context> {
 
         def_native_property!(self, proto, message, e, W | C)?;
         def_native_method!(self, proto, toString, error_to_string, 0, W | C)?;
+        def_native_method!(
+            self,
+            ctor,
+            captureStackTrace,
+            error_capture_stack_trace,
+            2,
+            W | C
+        )?;
+        def_native_property!(self, ctor, stackTraceLimit, 10, W | E | C)?;
+        self.global_data.error_constructor = Some(ctor);
+
+        let mut call_site = self.global_data.call_site_prototype.unwrap();
+        def_native_method!(self, call_site, getThis, call_site_get_this, 0, W | C)?;
+        def_native_method!(
+            self,
+            call_site,
+            getTypeName,
+            call_site_get_type_name,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            call_site,
+            getFunction,
+            call_site_get_function,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            call_site,
+            getFunctionName,
+            call_site_get_function_name,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            call_site,
+            getFileName,
+            call_site_get_file_name,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            call_site,
+            getLineNumber,
+            call_site_get_line_number,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            call_site,
+            getColumnNumber,
+            call_site_get_column_number,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, call_site, isToplevel, call_site_is_toplevel, 0, W | C)?;
+        def_native_method!(self, call_site, isEval, call_site_is_eval, 0, W | C)?;
+        def_native_method!(self, call_site, isNative, call_site_is_native, 0, W | C)?;
+        def_native_method!(
+            self,
+            call_site,
+            isConstructor,
+            call_site_is_constructor,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, call_site, toString, call_site_to_string, 0, W | C)?;
 
         let mut global_object = self.global_object();
         def_native_property!(self, global_object, Error, ctor, W | C)?;
@@ -414,7 +683,7 @@ impl GcPointer<Context> {
         def_native_method!(self, sub_proto, toString, error_to_string, 0, W | C)?;
 
         let mut global_object = self.global_object();
-        def_native_property!(self, global_object, RangeError, sub_proto, W | C)?;
+        def_native_property!(self, global_object, RangeError, sub_ctor, W | C)?;
         Ok(())
     }
 
@@ -434,7 +703,27 @@ impl GcPointer<Context> {
         def_native_method!(self, sub_proto, toString, error_to_string, 0, W | C)?;
 
         let mut global_object = self.global_object();
-        def_native_property!(self, global_object, URIError, sub_proto, W | C)?;
+        def_native_property!(self, global_object, URIError, sub_ctor, W | C)?;
+        Ok(())
+    }
+
+    pub(crate) fn init_aggregate_error_in_global_object(mut self) -> Result<(), JsValue> {
+        let mut sub_proto = self.global_data.aggregate_error.unwrap();
+        let sym = S_AGGREGATE_ERROR.intern();
+        let mut sub_ctor = JsNativeFunction::new(self, sym, aggregate_error_constructor, 2);
+
+        def_native_property!(self, sub_ctor, prototype, sub_proto, NONE)?;
+        def_native_property!(self, sub_proto, constructor, sub_ctor, W | C)?;
+
+        let s = JsString::new(self, S_AGGREGATE_ERROR);
+        let e = JsString::new(self, "");
+
+        def_native_property!(self, sub_proto, name, s, C)?;
+        def_native_property!(self, sub_proto, message, e, W | C)?;
+        def_native_method!(self, sub_proto, toString, error_to_string, 0, W | C)?;
+
+        let mut global_object = self.global_object();
+        def_native_property!(self, global_object, AggregateError, sub_ctor, W | C)?;
         Ok(())
     }
 
@@ -450,11 +739,19 @@ impl GcPointer<Context> {
         self.global_data.type_error_structure = Some(Structure::new_indexed(self, None, false));
         self.global_data.syntax_error_structure = Some(Structure::new_indexed(self, None, false));
         self.global_data.uri_error_structure = Some(Structure::new_indexed(self, None, false));
+        self.global_data.aggregate_error_structure =
+            Some(Structure::new_indexed(self, None, false));
 
         let structure = Structure::new_unique_with_proto(self, Some(obj_proto), false);
         let mut proto = JsObject::new(self, &structure, JsError::get_class(), ObjectTag::Ordinary);
         self.global_data.error = Some(proto);
 
+        let structure = Structure::new_unique_with_proto(self, Some(obj_proto), false);
+        let call_site = JsObject::new(self, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+        self.global_data.call_site_prototype = Some(call_site);
+        self.global_data.call_site_structure =
+            Some(Structure::new_indexed(self, Some(call_site), false));
+
         {
             let structure = Structure::new_unique_with_proto(self, Some(proto), false);
             let mut sub_proto = JsObject::new(
@@ -528,7 +825,7 @@ impl GcPointer<Context> {
             let mut sub_proto = JsObject::new(
                 self,
                 &structure,
-                JsReferenceError::get_class(),
+                JsRangeError::get_class(),
                 ObjectTag::Ordinary,
             );
 
@@ -555,6 +852,22 @@ impl GcPointer<Context> {
             self.global_data.uri_error = Some(sub_proto);
         }
 
+        {
+            let structure = Structure::new_unique_with_proto(self, Some(proto), false);
+            let mut sub_proto = JsObject::new(
+                self,
+                &structure,
+                JsAggregateError::get_class(),
+                ObjectTag::Ordinary,
+            );
+
+            self.global_data
+                .aggregate_error_structure
+                .unwrap()
+                .change_prototype_with_no_transition(sub_proto);
+            self.global_data.aggregate_error = Some(sub_proto);
+        }
+
         Ok(())
     }
 }
@@ -583,47 +896,91 @@ impl GcPointer<Context> {
         let mut ctor = JsNativeFunction::new(self, name, object_constructor, 1);
         self.global_data.object_constructor = Some(ctor);
 
-        def_native_method!(self, ctor, defineProperty, object_define_property, 3, NONE)?;
-
-        def_native_method!(self, ctor, seal, object_seal, 1, NONE)?;
-
-        def_native_method!(self, ctor, freeze, object_freeze, 1, NONE)?;
-
-        def_native_method!(self, ctor, isSealed, object_is_sealed, 1, NONE)?;
-
-        def_native_method!(self, ctor, isFrozen, object_is_frozen, 1, NONE)?;
-
-        def_native_method!(self, ctor, isExtensible, object_is_extensible, 1, NONE)?;
-
-        def_native_method!(self, ctor, getPrototypeOf, object_get_prototype_of, 1, NONE)?;
-
+        def_native_method!(self, ctor, defineProperty, object_define_property, 3, W | C)?;
+        def_native_method!(self, ctor, seal, object_seal, 1, W | C)?;
+        def_native_method!(self, ctor, freeze, object_freeze, 1, W | C)?;
+        def_native_method!(self, ctor, isSealed, object_is_sealed, 1, W | C)?;
+        def_native_method!(self, ctor, isFrozen, object_is_frozen, 1, W | C)?;
+        def_native_method!(self, ctor, isExtensible, object_is_extensible, 1, W | C)?;
+        def_native_method!(
+            self,
+            ctor,
+            getPrototypeOf,
+            object_get_prototype_of,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            ctor,
+            setPrototypeOf,
+            object_set_prototype_of,
+            2,
+            W | C
+        )?;
         def_native_method!(
             self,
             ctor,
             preventExtensions,
             object_prevent_extensions,
             1,
-            NONE
+            W | C
         )?;
-
-        def_native_method!(self, ctor, keys, object_keys, 1, NONE)?;
-
+        def_native_method!(self, ctor, keys, object_keys, 1, W | C)?;
+        def_native_method!(self, ctor, values, object_values, 1, W | C)?;
+        def_native_method!(self, ctor, entries, object_entries, 1, W | C)?;
+        def_native_method!(self, ctor, fromEntries, object_from_entries, 1, W | C)?;
+        def_native_method!(self, ctor, assign, object_assign, 2, W | C)?;
+        def_native_method!(self, ctor, hasOwn, object_has_own, 2, W | C)?;
+        def_native_method!(self, ctor, groupBy, object_group_by, 2, W | C)?;
         def_native_method!(
             self,
             ctor,
             getOwnPropertyDescriptor,
             object_get_own_property_descriptor,
             2,
-            NONE
+            W | C
         )?;
-
-        def_native_method!(self, ctor, create, object_create, 3, NONE)?;
+        def_native_method!(
+            self,
+            ctor,
+            getOwnPropertyDescriptors,
+            object_get_own_property_descriptors,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            ctor,
+            getOwnPropertyNames,
+            object_get_own_property_names,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            ctor,
+            getOwnPropertySymbols,
+            object_get_own_property_symbols,
+            1,
+            W | C
+        )?;
+        def_native_method!(self, ctor, create, object_create, 2, W | C)?;
 
         def_native_property!(self, ctor, prototype, proto, NONE)?;
 
         def_native_property!(self, proto, constructor, ctor, W | C)?;
 
         def_native_method!(self, proto, toString, object_to_string, 0, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toLocaleString,
+            object_to_locale_string,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, proto, valueOf, object_value_of, 0, W | C)?;
 
         def_native_method!(
             self,
@@ -633,6 +990,7 @@ impl GcPointer<Context> {
             1,
             W | C
         )?;
+        def_native_method!(self, proto, isPrototypeOf, object_is_prototype_of, 1, W | C)?;
 
         def_native_method!(
             self,
@@ -642,6 +1000,39 @@ impl GcPointer<Context> {
             1,
             W | C
         )?;
+        def_native_method!(
+            self,
+            proto,
+            __defineGetter__,
+            object_define_getter,
+            2,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            __defineSetter__,
+            object_define_setter,
+            2,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            __lookupGetter__,
+            object_lookup_getter,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            __lookupSetter__,
+            object_lookup_setter,
+            1,
+            W | C
+        )?;
+        define_proto_accessor(self, &mut proto)?;
         Ok(())
     }
 }
@@ -680,6 +1071,8 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         DeletedEntry::allocate as _,
         JsString::deserialize as _,
         JsString::allocate as _,
+        crate::vm::bigint::JsBigInt::deserialize as _,
+        crate::vm::bigint::JsBigInt::allocate as _,
         u8::deserialize as _,
         u8::allocate as _,
         u16::deserialize as _,
@@ -719,6 +1112,7 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         function::function_to_string as usize,
         function::function_apply as usize,
         function::function_call as usize,
+        function::function_has_instance as _,
         object::object_constructor as usize,
         object::object_create as usize,
         object::object_to_string as usize,
@@ -734,6 +1128,25 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         object::object_is_sealed as _,
         object::object_is_frozen as _,
         object::object_prevent_extensions as _,
+        object::object_values as _,
+        object::object_entries as _,
+        object::object_assign as _,
+        object::object_from_entries as _,
+        object::object_get_own_property_names as _,
+        object::object_get_own_property_symbols as _,
+        object::object_get_own_property_descriptors as _,
+        object::object_set_prototype_of as _,
+        object::object_has_own as _,
+        object::object_group_by as _,
+        object::object_is_prototype_of as _,
+        object::object_value_of as _,
+        object::object_to_locale_string as _,
+        object::object_proto_getter as _,
+        object::object_proto_setter as _,
+        object::object_define_getter as _,
+        object::object_define_setter as _,
+        object::object_lookup_getter as _,
+        object::object_lookup_setter as _,
         array::array_ctor as usize,
         array::array_from as usize,
         array::array_is_array as usize,
@@ -743,12 +1156,30 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         array::array_push as usize,
         array::array_reduce as usize,
         array::array_to_string as usize,
+        array::array_to_locale_string as usize,
         array::array_concat as usize,
         array::array_for_each as _,
         array::array_filter as _,
         array::array_map as _,
+        array::array_flat as _,
+        array::array_flat_map as _,
         array::array_shift as _,
         array::array_slice as _,
+        array::array_index_of as _,
+        array::array_last_index_of as _,
+        array::array_reverse as _,
+        array::array_every as _,
+        array::array_unshift as _,
+        array::array_splice as _,
+        array::array_reduce_right as _,
+        array::array_copy_within as _,
+        array::array_find_last as _,
+        array::array_find_last_index as _,
+        array::array_sort as _,
+        array::array_to_sorted as _,
+        array::array_to_reversed as _,
+        array::array_to_spliced as _,
+        array::array_with as _,
         error::error_constructor as usize,
         error::error_to_string as usize,
         error::eval_error_constructor as usize,
@@ -757,13 +1188,34 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         error::syntax_error_constructor as usize,
         error::type_error_constructor as usize,
         error::uri_error_constructor as usize,
+        error::aggregate_error_constructor as usize,
+        error::error_capture_stack_trace as usize,
+        error::call_site_get_this as usize,
+        error::call_site_get_type_name as usize,
+        error::call_site_get_function as usize,
+        error::call_site_get_function_name as usize,
+        error::call_site_get_file_name as usize,
+        error::call_site_get_line_number as usize,
+        error::call_site_get_column_number as usize,
+        error::call_site_is_toplevel as usize,
+        error::call_site_is_eval as usize,
+        error::call_site_is_native as usize,
+        error::call_site_is_constructor as usize,
+        error::call_site_to_string as usize,
         print as usize,
         global::is_finite as _,
         global::is_nan as _,
         global::parse_float as _,
         global::parse_int as _,
         global::read_line as _,
+        global::encode_uri as _,
+        global::encode_uri_component as _,
+        global::decode_uri as _,
+        global::decode_uri_component as _,
+        global::escape as _,
+        global::unescape as _,
         global::gc as _,
+        global::queue_microtask as _,
         global::___is_constructor as _,
         global::___is_callable as _,
         global::___trunc as _,
@@ -793,7 +1245,21 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         string::string_to_uppercase as _,
         string::string_includes as _,
         string::string_slice as _,
+        string::string_replace_all as _,
+        string::string_search as _,
+        string::string_at as _,
+        string::string_normalize as _,
+        string::string_locale_compare as _,
+        string::string_is_well_formed as _,
+        string::string_to_well_formed as _,
+        string::string_iterator as _,
+        string::string_iterator_next as _,
+        string::string_iterator_iterator as _,
+        string::string_from_char_code as _,
+        string::string_from_code_point as _,
+        string::string_raw as _,
         JsStringObject::get_class() as *const _ as usize,
+        JsStringIterator::get_class() as *const _ as usize,
         NumberObject::get_class() as *const _ as usize,
         Environment::deserialize as _,
         Environment::allocate as _,
@@ -803,9 +1269,12 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         number::number_is_integer as _,
         number::number_is_nan as _,
         number::number_to_int as _,
-        number::number_to_precisiion as _,
+        number::number_to_precision as _,
+        number::number_to_exponential as _,
+        number::number_is_safe_integer as _,
         number::number_to_fixed as _,
         number::number_to_string as _,
+        number::number_to_locale_string as _,
         number::number_value_of as _,
         math::math_trunc as _,
         math::math_floor as _,
@@ -817,6 +1286,31 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         math::math_abs as _,
         math::math_sqrt as _,
         math::math_random as _,
+        math::math_acos as _,
+        math::math_acosh as _,
+        math::math_asin as _,
+        math::math_asinh as _,
+        math::math_atan as _,
+        math::math_atanh as _,
+        math::math_atan2 as _,
+        math::math_cbrt as _,
+        math::math_clz32 as _,
+        math::math_cosh as _,
+        math::math_expm1 as _,
+        math::math_fround as _,
+        math::math_hypot as _,
+        math::math_imul as _,
+        math::math_log1p as _,
+        math::math_log10 as _,
+        math::math_log2 as _,
+        math::math_max as _,
+        math::math_min as _,
+        math::math_pow as _,
+        math::math_round as _,
+        math::math_sign as _,
+        math::math_sinh as _,
+        math::math_tan as _,
+        math::math_tanh as _,
         StructureChain::deserialize as _,
         StructureChain::allocate as _,
         HashValueZero::deserialize as _,
@@ -827,11 +1321,24 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         regexp::regexp_to_string as _,
         regexp::regexp_match as _,
         regexp::regexp_split_fast as _,
+        regexp::regexp_search as _,
+        regexp::regexp_compile as _,
+        regexp::regexp_flags as _,
+        regexp::regexp_source as _,
+        regexp::regexp_has_indices as _,
+        regexp::regexp_global as _,
+        regexp::regexp_ignore_case as _,
+        regexp::regexp_multiline as _,
+        regexp::regexp_dot_all as _,
+        regexp::regexp_unicode as _,
+        regexp::regexp_sticky as _,
         symbol::symbol_ctor as _,
         symbol::symbol_for as _,
         symbol::symbol_key_for as _,
         symbol::symbol_to_string as _,
         symbol::symbol_value_of as _,
+        symbol::symbol_description as _,
+        species_getter as _,
         JsSymbol::deserialize as _,
         JsSymbol::allocate as _,
         Accessor::deserialize as _,
@@ -893,14 +1400,95 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         weak_ref::weak_ref_prototype_deref as _,
         WeakSlot::deserialize as _,
         WeakSlot::allocate as _,
+        finalization_registry::JsFinalizationRegistry::get_class() as *const _ as _,
+        finalization_registry::finalization_registry_constructor as _,
+        finalization_registry::finalization_registry_prototype_register as _,
+        finalization_registry::finalization_registry_prototype_unregister as _,
+        TypedArrayKind::Int8.class() as *const _ as _,
+        TypedArrayKind::Uint8.class() as *const _ as _,
+        TypedArrayKind::Uint8Clamped.class() as *const _ as _,
+        TypedArrayKind::Int16.class() as *const _ as _,
+        TypedArrayKind::Uint16.class() as *const _ as _,
+        TypedArrayKind::Int32.class() as *const _ as _,
+        TypedArrayKind::Uint32.class() as *const _ as _,
+        TypedArrayKind::Float32.class() as *const _ as _,
+        TypedArrayKind::Float64.class() as *const _ as _,
+        typed_array::typed_array_constructor as _,
+        typed_array::int8_array_constructor as _,
+        typed_array::uint8_array_constructor as _,
+        typed_array::uint8_clamped_array_constructor as _,
+        typed_array::int16_array_constructor as _,
+        typed_array::uint16_array_constructor as _,
+        typed_array::int32_array_constructor as _,
+        typed_array::uint32_array_constructor as _,
+        typed_array::float32_array_constructor as _,
+        typed_array::float64_array_constructor as _,
+        typed_array::typed_array_prototype_buffer as _,
+        typed_array::typed_array_prototype_byte_length as _,
+        typed_array::typed_array_prototype_byte_offset as _,
+        typed_array::typed_array_prototype_length as _,
+        typed_array::typed_array_prototype_to_string_tag as _,
+        typed_array::typed_array_prototype_subarray as _,
+        typed_array::typed_array_prototype_set as _,
+        typed_array::typed_array_prototype_slice as _,
+        typed_array::typed_array_prototype_fill as _,
+        typed_array::typed_array_prototype_map as _,
+        typed_array::typed_array_prototype_sort as _,
+        typed_array::typed_array_prototype_at as _,
+        typed_array::typed_array_prototype_copy_within as _,
+        typed_array::typed_array_prototype_every as _,
+        typed_array::typed_array_prototype_some as _,
+        typed_array::typed_array_prototype_for_each as _,
+        typed_array::typed_array_prototype_find as _,
+        typed_array::typed_array_prototype_find_index as _,
+        typed_array::typed_array_prototype_find_last as _,
+        typed_array::typed_array_prototype_find_last_index as _,
+        typed_array::typed_array_prototype_filter as _,
+        typed_array::typed_array_prototype_includes as _,
+        typed_array::typed_array_prototype_index_of as _,
+        typed_array::typed_array_prototype_last_index_of as _,
+        typed_array::typed_array_prototype_join as _,
+        typed_array::typed_array_prototype_to_locale_string as _,
+        typed_array::typed_array_prototype_reduce as _,
+        typed_array::typed_array_prototype_reduce_right as _,
+        typed_array::typed_array_prototype_reverse as _,
+        typed_array::typed_array_prototype_to_reversed as _,
+        typed_array::typed_array_prototype_to_sorted as _,
+        typed_array::typed_array_prototype_with as _,
+        typed_array::typed_array_from as _,
+        typed_array::typed_array_of as _,
+        reflect::reflect_apply as _,
+        reflect::reflect_construct as _,
+        reflect::reflect_define_property as _,
+        reflect::reflect_delete_property as _,
+        reflect::reflect_get as _,
+        reflect::reflect_set as _,
+        reflect::reflect_get_own_property_descriptor as _,
+        reflect::reflect_get_prototype_of as _,
+        reflect::reflect_set_prototype_of as _,
+        reflect::reflect_has as _,
+        reflect::reflect_is_extensible as _,
+        reflect::reflect_own_keys as _,
+        reflect::reflect_prevent_extensions as _,
+        proxy::proxy_constructor as _,
+        proxy::proxy_revocable as _,
+        crate::vm::proxy::JsProxy::get_class() as *const _ as _,
         boolean::boolean_constructor as _,
         boolean::boolean_to_string as _,
         boolean::boolean_value_of as _,
         boolean::BooleanObject::get_class() as *const _ as _,
+        bigint::bigint_constructor as _,
+        bigint::bigint_as_int_n as _,
+        bigint::bigint_as_uint_n as _,
+        bigint::bigint_to_string as _,
+        bigint::bigint_to_locale_string as _,
+        bigint::bigint_value_of as _,
+        crate::vm::bigint::BigIntObject::get_class() as *const _ as _,
         date::date_constructor as _,
         date::date_to_string as _,
         date::Date::get_class() as *const _ as _,
         date::date_now as _,
+        date::date_to_primitive as _,
         date::date_set_date as _,
         date::date_set_full_year as _,
         date::date_set_hours as _,
@@ -926,6 +1514,7 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         date::date_get_seconds as _,
         date::date_get_seconds as _,
         date::date_get_time as _,
+        date::date_get_timezone_offset as _,
         date::date_get_year as _,
         date::date_get_utc_date as _,
         date::date_get_utc_day as _,
@@ -942,8 +1531,291 @@ pub static VM_NATIVE_REFERENCES: Lazy<&'static [usize]> = Lazy::new(|| {
         date::date_to_iso_string as _,
         date::date_to_utc_string as _,
         date::date_to_date_string as _,
+        date::date_to_locale_string as _,
+        date::date_to_locale_date_string as _,
+        date::date_to_locale_time_string as _,
         date::date_parse as _,
         date::date_utc as _,
+        temporal::duration::duration_constructor as _,
+        temporal::duration::duration_from as _,
+        temporal::duration::duration_compare as _,
+        temporal::duration::duration_sign as _,
+        temporal::duration::duration_blank as _,
+        temporal::duration::duration_with as _,
+        temporal::duration::duration_negated as _,
+        temporal::duration::duration_abs as _,
+        temporal::duration::duration_add as _,
+        temporal::duration::duration_subtract as _,
+        temporal::duration::duration_round as _,
+        temporal::duration::duration_total as _,
+        temporal::duration::duration_to_string as _,
+        temporal::duration::duration_to_json as _,
+        temporal::duration::duration_years as _,
+        temporal::duration::duration_months as _,
+        temporal::duration::duration_weeks as _,
+        temporal::duration::duration_days as _,
+        temporal::duration::duration_hours as _,
+        temporal::duration::duration_minutes as _,
+        temporal::duration::duration_seconds as _,
+        temporal::duration::duration_milliseconds as _,
+        temporal::duration::duration_microseconds as _,
+        temporal::duration::duration_nanoseconds as _,
+        temporal::instant::instant_constructor as _,
+        temporal::instant::instant_from as _,
+        temporal::instant::instant_from_epoch_milliseconds as _,
+        temporal::instant::instant_from_epoch_nanoseconds as _,
+        temporal::instant::instant_compare as _,
+        temporal::instant::instant_epoch_milliseconds as _,
+        temporal::instant::instant_epoch_nanoseconds as _,
+        temporal::instant::instant_add as _,
+        temporal::instant::instant_subtract as _,
+        temporal::instant::instant_until as _,
+        temporal::instant::instant_since as _,
+        temporal::instant::instant_round as _,
+        temporal::instant::instant_equals as _,
+        temporal::instant::instant_to_string as _,
+        temporal::instant::instant_to_json as _,
+        temporal::instant::instant_to_zoned_date_time_iso as _,
+        temporal::now::now_instant as _,
+        temporal::now::now_time_zone_id as _,
+        temporal::now::now_zoned_date_time_iso as _,
+        temporal::now::now_plain_date_time_iso as _,
+        temporal::now::now_plain_date_iso as _,
+        temporal::now::now_plain_time_iso as _,
+        temporal::plain_date::plain_date_constructor as _,
+        temporal::plain_date::plain_date_from as _,
+        temporal::plain_date::plain_date_compare as _,
+        temporal::plain_date::plain_date_with as _,
+        temporal::plain_date::plain_date_add as _,
+        temporal::plain_date::plain_date_subtract as _,
+        temporal::plain_date::plain_date_until as _,
+        temporal::plain_date::plain_date_since as _,
+        temporal::plain_date::plain_date_equals as _,
+        temporal::plain_date::plain_date_to_plain_date_time as _,
+        temporal::plain_date::plain_date_to_zoned_date_time as _,
+        temporal::plain_date::plain_date_to_string as _,
+        temporal::plain_date::plain_date_to_json as _,
+        temporal::plain_date::plain_date_calendar_id as _,
+        temporal::plain_date::plain_date_year as _,
+        temporal::plain_date::plain_date_month as _,
+        temporal::plain_date::plain_date_month_code as _,
+        temporal::plain_date::plain_date_day as _,
+        temporal::plain_date::plain_date_day_of_week as _,
+        temporal::plain_date::plain_date_day_of_year as _,
+        temporal::plain_date::plain_date_week_of_year as _,
+        temporal::plain_date::plain_date_year_of_week as _,
+        temporal::plain_date::plain_date_days_in_week as _,
+        temporal::plain_date::plain_date_days_in_month as _,
+        temporal::plain_date::plain_date_days_in_year as _,
+        temporal::plain_date::plain_date_months_in_year as _,
+        temporal::plain_date::plain_date_in_leap_year as _,
+        temporal::plain_date_time::plain_date_time_constructor as _,
+        temporal::plain_date_time::plain_date_time_from as _,
+        temporal::plain_date_time::plain_date_time_compare as _,
+        temporal::plain_date_time::plain_date_time_with as _,
+        temporal::plain_date_time::plain_date_time_with_plain_time as _,
+        temporal::plain_date_time::plain_date_time_add as _,
+        temporal::plain_date_time::plain_date_time_subtract as _,
+        temporal::plain_date_time::plain_date_time_until as _,
+        temporal::plain_date_time::plain_date_time_since as _,
+        temporal::plain_date_time::plain_date_time_round as _,
+        temporal::plain_date_time::plain_date_time_equals as _,
+        temporal::plain_date_time::plain_date_time_to_plain_date as _,
+        temporal::plain_date_time::plain_date_time_to_plain_time as _,
+        temporal::plain_date_time::plain_date_time_to_zoned_date_time as _,
+        temporal::plain_date_time::plain_date_time_to_string as _,
+        temporal::plain_date_time::plain_date_time_to_json as _,
+        temporal::plain_date_time::plain_date_time_calendar_id as _,
+        temporal::plain_date_time::plain_date_time_year as _,
+        temporal::plain_date_time::plain_date_time_month as _,
+        temporal::plain_date_time::plain_date_time_month_code as _,
+        temporal::plain_date_time::plain_date_time_day as _,
+        temporal::plain_date_time::plain_date_time_day_of_week as _,
+        temporal::plain_date_time::plain_date_time_day_of_year as _,
+        temporal::plain_date_time::plain_date_time_week_of_year as _,
+        temporal::plain_date_time::plain_date_time_year_of_week as _,
+        temporal::plain_date_time::plain_date_time_days_in_week as _,
+        temporal::plain_date_time::plain_date_time_days_in_month as _,
+        temporal::plain_date_time::plain_date_time_days_in_year as _,
+        temporal::plain_date_time::plain_date_time_months_in_year as _,
+        temporal::plain_date_time::plain_date_time_in_leap_year as _,
+        temporal::plain_date_time::plain_date_time_hour as _,
+        temporal::plain_date_time::plain_date_time_minute as _,
+        temporal::plain_date_time::plain_date_time_second as _,
+        temporal::plain_date_time::plain_date_time_millisecond as _,
+        temporal::plain_date_time::plain_date_time_microsecond as _,
+        temporal::plain_date_time::plain_date_time_nanosecond as _,
+        temporal::plain_time::plain_time_constructor as _,
+        temporal::plain_time::plain_time_from as _,
+        temporal::plain_time::plain_time_compare as _,
+        temporal::plain_time::plain_time_with as _,
+        temporal::plain_time::plain_time_add as _,
+        temporal::plain_time::plain_time_subtract as _,
+        temporal::plain_time::plain_time_until as _,
+        temporal::plain_time::plain_time_since as _,
+        temporal::plain_time::plain_time_round as _,
+        temporal::plain_time::plain_time_equals as _,
+        temporal::plain_time::plain_time_to_string as _,
+        temporal::plain_time::plain_time_to_json as _,
+        temporal::plain_time::plain_time_hour as _,
+        temporal::plain_time::plain_time_minute as _,
+        temporal::plain_time::plain_time_second as _,
+        temporal::plain_time::plain_time_millisecond as _,
+        temporal::plain_time::plain_time_microsecond as _,
+        temporal::plain_time::plain_time_nanosecond as _,
+        temporal::zoned_date_time::zoned_date_time_constructor as _,
+        temporal::zoned_date_time::zoned_date_time_from as _,
+        temporal::zoned_date_time::zoned_date_time_compare as _,
+        temporal::zoned_date_time::zoned_date_time_time_zone_id as _,
+        temporal::zoned_date_time::zoned_date_time_epoch_milliseconds as _,
+        temporal::zoned_date_time::zoned_date_time_epoch_nanoseconds as _,
+        temporal::zoned_date_time::zoned_date_time_offset as _,
+        temporal::zoned_date_time::zoned_date_time_offset_nanoseconds as _,
+        temporal::zoned_date_time::zoned_date_time_hours_in_day as _,
+        temporal::zoned_date_time::zoned_date_time_with as _,
+        temporal::zoned_date_time::zoned_date_time_with_plain_time as _,
+        temporal::zoned_date_time::zoned_date_time_with_time_zone as _,
+        temporal::zoned_date_time::zoned_date_time_add as _,
+        temporal::zoned_date_time::zoned_date_time_subtract as _,
+        temporal::zoned_date_time::zoned_date_time_until as _,
+        temporal::zoned_date_time::zoned_date_time_since as _,
+        temporal::zoned_date_time::zoned_date_time_round as _,
+        temporal::zoned_date_time::zoned_date_time_equals as _,
+        temporal::zoned_date_time::zoned_date_time_start_of_day as _,
+        temporal::zoned_date_time::zoned_date_time_to_instant as _,
+        temporal::zoned_date_time::zoned_date_time_to_plain_date as _,
+        temporal::zoned_date_time::zoned_date_time_to_plain_time as _,
+        temporal::zoned_date_time::zoned_date_time_to_plain_date_time as _,
+        temporal::zoned_date_time::zoned_date_time_to_string as _,
+        temporal::zoned_date_time::zoned_date_time_to_json as _,
+        temporal::zoned_date_time::zoned_date_time_calendar_id as _,
+        temporal::zoned_date_time::zoned_date_time_year as _,
+        temporal::zoned_date_time::zoned_date_time_month as _,
+        temporal::zoned_date_time::zoned_date_time_month_code as _,
+        temporal::zoned_date_time::zoned_date_time_day as _,
+        temporal::zoned_date_time::zoned_date_time_day_of_week as _,
+        temporal::zoned_date_time::zoned_date_time_day_of_year as _,
+        temporal::zoned_date_time::zoned_date_time_week_of_year as _,
+        temporal::zoned_date_time::zoned_date_time_year_of_week as _,
+        temporal::zoned_date_time::zoned_date_time_days_in_week as _,
+        temporal::zoned_date_time::zoned_date_time_days_in_month as _,
+        temporal::zoned_date_time::zoned_date_time_days_in_year as _,
+        temporal::zoned_date_time::zoned_date_time_months_in_year as _,
+        temporal::zoned_date_time::zoned_date_time_in_leap_year as _,
+        temporal::zoned_date_time::zoned_date_time_hour as _,
+        temporal::zoned_date_time::zoned_date_time_minute as _,
+        temporal::zoned_date_time::zoned_date_time_second as _,
+        temporal::zoned_date_time::zoned_date_time_millisecond as _,
+        temporal::zoned_date_time::zoned_date_time_microsecond as _,
+        temporal::zoned_date_time::zoned_date_time_nanosecond as _,
+        temporal::temporal_value_of as _,
+        intl::intl_get_canonical_locales as _,
+        intl::intl_supported_locales_of as _,
+        intl::collator::collator_constructor as _,
+        intl::collator::collator_compare as _,
+        intl::collator::collator_bound_compare as _,
+        intl::collator::collator_resolved_options as _,
+        intl::date_time_format::date_time_format_constructor as _,
+        intl::date_time_format::date_time_format_format as _,
+        intl::date_time_format::date_time_format_bound_format as _,
+        intl::date_time_format::date_time_format_format_to_parts as _,
+        intl::date_time_format::date_time_format_resolved_options as _,
+        intl::number_format::number_format_constructor as _,
+        intl::number_format::number_format_format as _,
+        intl::number_format::number_format_bound_format as _,
+        intl::number_format::number_format_format_to_parts as _,
+        intl::number_format::number_format_resolved_options as _,
+        intl::plural_rules::plural_rules_constructor as _,
+        intl::plural_rules::plural_rules_select as _,
+        intl::plural_rules::plural_rules_resolved_options as _,
+        map::map_constructor as _,
+        map::map_prototype_get as _,
+        map::map_prototype_set as _,
+        map::map_prototype_has as _,
+        map::map_prototype_delete as _,
+        map::map_prototype_clear as _,
+        map::map_prototype_size as _,
+        map::map_prototype_for_each as _,
+        map::map_prototype_keys as _,
+        map::map_prototype_values as _,
+        map::map_prototype_entries as _,
+        map::map_group_by as _,
+        map::set_constructor as _,
+        map::set_prototype_add as _,
+        map::set_prototype_has as _,
+        map::set_prototype_delete as _,
+        map::set_prototype_clear as _,
+        map::set_prototype_size as _,
+        map::set_prototype_for_each as _,
+        map::set_prototype_values as _,
+        map::set_prototype_entries as _,
+        map::map_iterator_next as _,
+        map::map_iterator_iterator as _,
+        url::url_constructor as _,
+        url::url_can_parse as _,
+        url::url_parse as _,
+        url::url_href as _,
+        url::url_set_href as _,
+        url::url_origin as _,
+        url::url_protocol as _,
+        url::url_set_protocol as _,
+        url::url_username as _,
+        url::url_set_username as _,
+        url::url_password as _,
+        url::url_set_password as _,
+        url::url_host as _,
+        url::url_set_host as _,
+        url::url_hostname as _,
+        url::url_set_hostname as _,
+        url::url_port as _,
+        url::url_set_port as _,
+        url::url_pathname as _,
+        url::url_set_pathname as _,
+        url::url_search as _,
+        url::url_set_search as _,
+        url::url_search_params as _,
+        url::url_hash as _,
+        url::url_set_hash as _,
+        url::url_to_string as _,
+        url::url_search_params_constructor as _,
+        url::url_search_params_size as _,
+        url::url_search_params_append as _,
+        url::url_search_params_delete as _,
+        url::url_search_params_get as _,
+        url::url_search_params_get_all as _,
+        url::url_search_params_has as _,
+        url::url_search_params_set as _,
+        url::url_search_params_sort as _,
+        url::url_search_params_to_string as _,
+        url::url_search_params_for_each as _,
+        url::url_search_params_keys as _,
+        url::url_search_params_values as _,
+        url::url_search_params_entries as _,
+        url::url_search_params_iterator_next as _,
+        url::url_search_params_iterator_iterator as _,
+        encoding::text_encoder_constructor as _,
+        encoding::text_encoder_encoding as _,
+        encoding::text_encoder_encode as _,
+        encoding::text_encoder_encode_into as _,
+        encoding::text_decoder_constructor as _,
+        encoding::text_decoder_encoding as _,
+        encoding::text_decoder_fatal as _,
+        encoding::text_decoder_ignore_bom as _,
+        encoding::text_decoder_decode as _,
+        structured_clone::structured_clone as _,
+        console::console_log as _,
+        console::console_error as _,
+        console::console_trace as _,
+        console::console_assert as _,
+        console::console_count as _,
+        console::console_count_reset as _,
+        console::console_time as _,
+        console::console_time_log as _,
+        console::console_time_end as _,
+        console::console_group as _,
+        console::console_group_end as _,
+        console::console_dir as _,
+        console::console_table as _,
     ];
     #[cfg(all(target_pointer_width = "64", feature = "ffi"))]
     {
@@ -1078,6 +1950,68 @@ pub fn to_property_descriptor(
     }
 }
 
+/// Convert property descriptor to JS object, as `Object.getOwnPropertyDescriptor` returns it
+pub fn from_property_descriptor(
+    ctx: GcPointer<Context>,
+    desc: &PropertyDescriptor,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(res = stack, JsObject::new_empty(ctx));
+    // fields absent from `desc` are not reported, see FromPropertyDescriptor.
+    if desc.is_data() {
+        if !desc.is_value_absent() {
+            res.define_own_property(
+                ctx,
+                S_VALUE.intern(),
+                &*DataDescriptor::new(desc.value(), W | C | E),
+                false,
+            )?;
+        }
+        if !desc.is_writable_absent() {
+            res.define_own_property(
+                ctx,
+                S_WRITABLE.intern(),
+                &*DataDescriptor::new(JsValue::new(desc.is_writable()), W | C | E),
+                false,
+            )?;
+        }
+    } else if desc.is_accessor() {
+        if !desc.is_getter_absent() {
+            res.define_own_property(
+                ctx,
+                S_GET.intern(),
+                &*DataDescriptor::new(desc.getter(), W | C | E),
+                false,
+            )?;
+        }
+        if !desc.is_setter_absent() {
+            res.define_own_property(
+                ctx,
+                S_SET.intern(),
+                &*DataDescriptor::new(desc.setter(), W | C | E),
+                false,
+            )?;
+        }
+    }
+    if !desc.is_enumerable_absent() {
+        res.define_own_property(
+            ctx,
+            S_ENUMERABLE.intern(),
+            &*DataDescriptor::new(JsValue::new(desc.is_enumerable()), W | C | E),
+            false,
+        )?;
+    }
+    if !desc.is_configurable_absent() {
+        res.define_own_property(
+            ctx,
+            S_CONFIGURABLE.intern(),
+            &*DataDescriptor::new(JsValue::new(desc.is_configurable()), W | C | E),
+            false,
+        )?;
+    }
+    Ok(JsValue::new(*res))
+}
+
 pub(crate) fn module_load(
     mut ctx: GcPointer<Context>,
     args: &Arguments,
@@ -1212,3 +2146,47 @@ pub fn define_lazy_property(
     object.define_own_property(ctx, name, &*desc, throwable)?;
     Ok(())
 }
+
+/// Defines configurable accessor `name` of `object` with native `getter` and without setter.
+pub(crate) fn define_getter(
+    ctx: GcPointer<Context>,
+    object: &mut GcPointer<JsObject>,
+    name: Symbol,
+    getter: JsAPI,
+) -> Result<bool, JsValue> {
+    let getter = JsNativeFunction::new(ctx, name, getter, 0);
+    object.define_own_property(
+        ctx,
+        name,
+        &*AccessorDescriptor::new(JsValue::new(getter), JsValue::encode_undefined_value(), C),
+        false,
+    )
+}
+
+/// Returns `true` if `value` is a native function implemented by `func`.
+pub(crate) fn is_native_function(value: JsValue, func: JsAPI) -> bool {
+    if !value.is_callable() {
+        return false;
+    }
+    let function = value.get_jsobject();
+    let function = function.as_function();
+    function.is_native() && function.as_native().func as usize == func as usize
+}
+
+/// `get [Symbol.species]` of built-in constructors.
+pub fn species_getter(_ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(args.this)
+}
+
+/// Defines `[Symbol.species]` getter of built-in constructor `ctor`.
+pub(crate) fn define_species(
+    ctx: GcPointer<Context>,
+    ctor: &mut GcPointer<JsObject>,
+) -> Result<bool, JsValue> {
+    define_getter(
+        ctx,
+        ctor,
+        "Symbol.species".intern().private(),
+        species_getter,
+    )
+}
@@ -1,14 +1,16 @@
 /* This Source Code Form is subject to the terms of the Mozilla Public
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
-use std::intrinsics::unlikely;
+use std::{cmp::Ordering, intrinsics::unlikely};
 
-use super::object::object_to_string;
+use super::{number::to_integer_or_infinity, object::object_to_string, typed_array::merge_sort};
 use crate::{
+    constant::S_CONSTURCTOR,
     gc::cell::GcPointer,
     jsrt::get_length,
     vm::{
-        arguments::*, array::*, attributes::*, context::Context, error::*, object::*,
+        arguments::*, array::*, attributes::*, context::Context, error::*,
+        indexed_elements::MAX_VECTOR_SIZE, object::*, operations,
         property_descriptor::DataDescriptor, string::*, symbol_table::*, value::*,
     },
 };
@@ -51,17 +53,8 @@ pub fn array_ctor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue,
     }
 }
 
-pub fn array_is_array(_ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    if args.size() == 0 {
-        return Ok(JsValue::encode_bool_value(false));
-    }
-    let val = args.at(0);
-    if !val.is_jsobject() {
-        return Ok(JsValue::encode_bool_value(false));
-    }
-    Ok(JsValue::encode_bool_value(
-        val.get_object().downcast::<JsObject>().unwrap().tag() == ObjectTag::Array,
-    ))
+pub fn array_is_array(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    operations::is_array(ctx, args.at(0)).map(JsValue::encode_bool_value)
 }
 
 pub fn array_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
@@ -149,6 +142,30 @@ pub fn array_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsVa
     object_to_string(ctx, &args)
 }
 
+pub fn array_to_locale_string(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(array = stack, args.this.to_object(ctx)?);
+    let len = get_length(ctx, &mut array)?;
+    let mut fmt = String::new();
+    for k in 0..len {
+        if k > 0 {
+            fmt.push(',');
+        }
+        let element = array.get(ctx, Symbol::Index(k))?;
+        if element.is_undefined() || element.is_null() {
+            continue;
+        }
+        letroot!(object = stack, element.to_object(ctx)?);
+        let method = object.get(ctx, "toLocaleString".intern())?;
+        let str = operations::call(ctx, method, element, &mut [args.at(0), args.at(1)])?;
+        fmt.push_str(&str.to_string(ctx)?);
+    }
+    Ok(JsValue::encode_object_value(JsString::new(ctx, fmt)))
+}
+
 // TODO(playX): Allow to push up to 2^53-1 values
 pub fn array_push(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     let mut obj = args.this.to_object(ctx)?;
@@ -277,52 +294,54 @@ pub fn array_reduce(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue
     Ok(*acc)
 }
 
-pub fn array_concat(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    if args.size() == 0 {
-        return Ok(args.this);
-    }
-
-    let mut ix = 0;
-    if !args.this.is_jsobject() {
-        let msg = JsString::new(ctx, "Array.prototype.concat requires array-like object");
-        return Err(JsValue::encode_object_value(JsTypeError::new(
-            ctx, msg, None,
-        )));
+/// IsConcatSpreadable
+fn is_concat_spreadable(ctx: GcPointer<Context>, value: JsValue) -> Result<bool, JsValue> {
+    if !value.is_jsobject() {
+        return Ok(false);
     }
     let stack = ctx.shadowstack();
-    letroot!(this = stack, args.this.get_jsobject());
-    let this_length = super::get_length(ctx, &mut this)?;
-
-    let mut new_values = JsArray::new(ctx, this_length);
-    for n in 0..this_length {
-        let val = this.get(ctx, Symbol::Index(n))?;
-        new_values.put(ctx, Symbol::Index(ix), val, false)?;
-        ix += 1;
+    letroot!(object = stack, value.get_jsobject());
+    let spreadable = object.get(ctx, "Symbol.isConcatSpreadable".intern().private())?;
+    if !spreadable.is_undefined() {
+        return Ok(spreadable.to_boolean());
     }
+    operations::is_array(ctx, value)
+}
 
-    for ai in 0..args.size() {
-        let arg = args.at(ai);
-        if !arg.is_jsobject() {
-            let msg = JsString::new(ctx, "Array.prototype.concat requires array-like arguments");
-            return Err(JsValue::encode_object_value(JsTypeError::new(
-                ctx, msg, None,
-            )));
-        }
-        letroot!(arg = stack, arg.get_jsobject());
-        let len = super::get_length(ctx, &mut arg)?;
-        if unlikely(len >= u32::MAX - 1) {
-            return Err(JsValue::new(ctx.new_type_error(
-                "Array-like object length exceeds array length limit in Array.prototype.concat",
-            )));
+pub fn array_concat(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, args.this.to_object(ctx)?);
+    letroot!(result = stack, array_species_create(ctx, &mut this, 0)?);
+    letroot!(item = stack, JsValue::new(*this));
+    let mut n = 0u32;
+    for i in 0..=args.size() {
+        if i != 0 {
+            *item = args.at(i - 1);
         }
-        for n in 0..len {
-            let val = arg.get(ctx, Symbol::Index(n))?;
-            new_values.put(ctx, Symbol::Index(ix), val, false)?;
-            ix += 1;
+        if is_concat_spreadable(ctx, *item)? {
+            letroot!(element = stack, item.get_jsobject());
+            let length = get_length(ctx, &mut element)?;
+            if unlikely(n as u64 + length as u64 >= u32::MAX as u64) {
+                return Err(invalid_length(ctx));
+            }
+            for k in 0..length {
+                let key = Symbol::Index(k);
+                if element.has_property(ctx, key) {
+                    let value = element.get(ctx, key)?;
+                    create_data_property(ctx, &mut result, n + k, value)?;
+                }
+            }
+            n += length;
+        } else {
+            if unlikely(n == u32::MAX - 1) {
+                return Err(invalid_length(ctx));
+            }
+            create_data_property(ctx, &mut result, n, *item)?;
+            n += 1;
         }
     }
-
-    Ok(JsValue::encode_object_value(new_values))
+    result.put(ctx, "length".intern(), JsValue::new(n), true)?;
+    Ok(JsValue::new(*result))
 }
 
 pub fn array_for_each(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
@@ -360,36 +379,23 @@ pub fn array_for_each(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsVal
 pub fn array_filter(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     let stack = ctx.shadowstack();
     letroot!(array = stack, args.this.to_object(ctx)?);
-    let length = super::get_length(ctx, &mut array)?;
-
-    let callback = args.at(0);
-    if !callback.is_callable() {
-        return Err(JsValue::new(ctx.new_type_error(
-            "Array.prototype.forEach callback must be a function",
-        )));
-    }
-
-    letroot!(callback = stack, callback.to_object(ctx)?);
-    letroot!(cb2 = stack, *callback);
-    letroot!(result = stack, JsArray::new(ctx, 0));
-    letroot!(this_arg = stack, args.at(1));
+    let length = get_length(ctx, &mut array)?;
+    letroot!(
+        callback = stack,
+        callback_function(ctx, args.at(0), "filter")?
+    );
+    letroot!(result = stack, array_species_create(ctx, &mut array, 0)?);
+    let this_arg = args.at(1);
 
     let mut next_index = 0;
-    let mut buf = [JsValue::encode_undefined_value(); 3];
     for i in 0..length {
-        if !array.has_own_property(ctx, Symbol::Index(i)) {
+        let key = Symbol::Index(i);
+        if !array.has_property(ctx, key) {
             continue;
         }
-        let current = array.get(ctx, Symbol::Index(i))?;
-        buf[0] = current;
-        buf[1] = JsValue::new(i);
-        buf[2] = JsValue::new(*array);
-        let mut args = Arguments::new(*this_arg, &mut buf);
-        let val = callback
-            .as_function_mut()
-            .call(ctx, &mut args, JsValue::new(*cb2))?;
-        if val.to_boolean() {
-            result.put(ctx, Symbol::Index(next_index), current, true)?;
+        letroot!(current = stack, array.get(ctx, key)?);
+        if call_predicate(ctx, &mut callback, this_arg, *current, i, *array)? {
+            create_data_property(ctx, &mut result, next_index, *current)?;
             next_index += 1;
         }
     }
@@ -399,33 +405,24 @@ pub fn array_filter(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue
 pub fn array_map(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     let stack = ctx.shadowstack();
     letroot!(array = stack, args.this.to_object(ctx)?);
-    let length = super::get_length(ctx, &mut array)?;
-
-    let callback = args.at(0);
-    if !callback.is_callable() {
-        return Err(JsValue::new(ctx.new_type_error(
-            "Array.prototype.forEach callback must be a function",
-        )));
-    }
+    let length = get_length(ctx, &mut array)?;
+    letroot!(callback = stack, callback_function(ctx, args.at(0), "map")?);
+    letroot!(
+        result = stack,
+        array_species_create(ctx, &mut array, length)?
+    );
+    let this_arg = args.at(1);
 
-    letroot!(callback = stack, callback.to_object(ctx)?);
-    letroot!(cb2 = stack, *callback);
-    letroot!(result = stack, JsArray::new(ctx, 0));
-    letroot!(this_arg = stack, args.at(1));
-    let mut buf = [JsValue::encode_undefined_value(); 3];
     for i in 0..length {
-        if !array.has_own_property(ctx, Symbol::Index(i)) {
+        let key = Symbol::Index(i);
+        if !array.has_property(ctx, key) {
             continue;
         }
-
-        buf[0] = array.get(ctx, Symbol::Index(i))?;
-        buf[1] = JsValue::new(i);
-        buf[2] = JsValue::new(*array);
-        let mut args = Arguments::new(*this_arg, &mut buf);
-        let mapped_value = callback
-            .as_function_mut()
-            .call(ctx, &mut args, JsValue::new(*cb2))?;
-        result.put(ctx, Symbol::Index(i), mapped_value, true)?;
+        let mut buf = [array.get(ctx, key)?, JsValue::new(i), JsValue::new(*array)];
+        letroot!(args = stack, Arguments::new(this_arg, &mut buf));
+        let func = JsValue::new(*callback);
+        let mapped_value = callback.as_function_mut().call(ctx, &mut args, func)?;
+        create_data_property(ctx, &mut result, i, mapped_value)?;
     }
     Ok(JsValue::new(*result))
 }
@@ -472,34 +469,21 @@ pub fn array_slice(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue,
         let msg = JsString::new(ctx, "Out of memory for array values");
         return Err(JsValue::new(JsRangeError::new(ctx, msg, None)));
     }
-    if result_len > (1024 << 6) {
-        letroot!(ary = stack, JsArray::new(ctx, result_len));
-
-        let mut n = 0;
-        while k < fin {
-            let kval = obj.get(ctx, Symbol::Index(k))?;
-            ary.define_own_property(
-                ctx,
-                Symbol::Index(n),
-                &*DataDescriptor::new(kval, W | E | C),
-                false,
-            )?;
-            k += 1;
-            n += 1;
-        }
-        return Ok(JsValue::new(*ary));
-    }
-    letroot!(ary = stack, JsArray::new(ctx, result_len));
+    letroot!(
+        ary = stack,
+        array_species_create(ctx, &mut obj, result_len)?
+    );
     let mut n = 0;
     while k < fin {
         if obj.has_property(ctx, Symbol::Index(k)) {
             let val = obj.get(ctx, Symbol::Index(k))?;
-            ary.put(ctx, Symbol::Index(n), val, false)?;
+            create_data_property(ctx, &mut ary, n, val)?;
         }
         k += 1;
         n += 1;
     }
-    return Ok(JsValue::new(*ary));
+    ary.put(ctx, "length".intern(), JsValue::new(n), true)?;
+    Ok(JsValue::new(*ary))
 }
 
 pub fn array_shift(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
@@ -533,3 +517,972 @@ pub fn array_shift(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue,
     )?;
     Ok(first)
 }
+
+/// Returns the constructor `ArraySpeciesCreate` would call for `original`, `None` means that a
+/// plain array is created.
+fn array_species_constructor(
+    ctx: GcPointer<Context>,
+    original: &mut GcPointer<JsObject>,
+) -> Result<Option<JsValue>, JsValue> {
+    if !operations::is_array(ctx, JsValue::new(*original))? {
+        return Ok(None);
+    }
+    let mut constructor = original.get(ctx, S_CONSTURCTOR.intern())?;
+    if constructor.is_jsobject() {
+        let stack = ctx.shadowstack();
+        letroot!(object = stack, constructor.get_jsobject());
+        constructor = object.get(ctx, "Symbol.species".intern().private())?;
+        if constructor.is_null() {
+            return Ok(None);
+        }
+    }
+    if constructor.is_undefined() {
+        return Ok(None);
+    }
+    if unlikely(!operations::is_constructor(constructor)) {
+        return Err(JsValue::new(
+            ctx.new_type_error("Array species is not a constructor"),
+        ));
+    }
+    // `Array` itself creates a plain array, skip the constructor call.
+    if super::is_native_function(constructor, array_ctor) {
+        return Ok(None);
+    }
+    Ok(Some(constructor))
+}
+
+/// Creates array of `length` with constructor returned by [array_species_constructor].
+fn species_create(
+    ctx: GcPointer<Context>,
+    constructor: Option<JsValue>,
+    length: u32,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    let constructor = match constructor {
+        Some(constructor) => constructor,
+        None => return Ok(JsArray::new(ctx, length)),
+    };
+    let result = operations::construct(ctx, constructor, &mut [JsValue::new(length)], None)?;
+    if unlikely(!result.is_jsobject()) {
+        return Err(JsValue::new(
+            ctx.new_type_error("Array species constructor returned non-object"),
+        ));
+    }
+    Ok(result.get_jsobject())
+}
+
+/// ArraySpeciesCreate
+fn array_species_create(
+    ctx: GcPointer<Context>,
+    original: &mut GcPointer<JsObject>,
+    length: u32,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    let constructor = array_species_constructor(ctx, original)?;
+    species_create(ctx, constructor, length)
+}
+
+/// CreateDataPropertyOrThrow
+fn create_data_property(
+    ctx: GcPointer<Context>,
+    object: &mut GcPointer<JsObject>,
+    index: u32,
+    value: JsValue,
+) -> Result<(), JsValue> {
+    object
+        .define_own_property(
+            ctx,
+            Symbol::Index(index),
+            &*DataDescriptor::new(value, W | C | E),
+            true,
+        )
+        .map(|_| ())
+}
+
+/// Returns `true` if `object` is an array whose length is still `length` and elements
+/// `0..length` are all present in dense `ArrayStorage` and growing it can't hit indexed properties
+/// of prototypes. Reads and writes of such arrays can't be observed by user code so they can be
+/// done on the storage directly. `length` is the value read before coercing the other arguments,
+/// which may have run user code that resized the array.
+fn is_dense_array(object: &GcPointer<JsObject>, length: u32) -> bool {
+    if object.tag() != ObjectTag::Array
+        || !object.indexed.dense()
+        || !object.indexed.writable()
+        || !object.is_extensible()
+        || object.indexed.length() != length
+        || object.indexed.vector.size() != length
+    {
+        return false;
+    }
+    if let Some(proto) = object.prototype() {
+        if proto.has_indexed_property() {
+            return false;
+        }
+    }
+    object
+        .indexed
+        .vector
+        .as_slice()
+        .iter()
+        .all(|value| !value.is_empty())
+}
+
+/// Replaces elements of dense array `object` with `values`.
+fn set_dense_elements(
+    mut ctx: GcPointer<Context>,
+    object: &mut GcPointer<JsObject>,
+    values: &[JsValue],
+) {
+    let stack = ctx.shadowstack();
+    letroot!(vector = stack, object.indexed.vector);
+    vector.mut_handle().resize(ctx.heap(), values.len() as u32);
+    vector.as_slice_mut().copy_from_slice(values);
+    object.indexed.vector = *vector;
+    object.indexed.non_gc &= !values.iter().any(|value| value.is_object());
+    object.indexed.set_length(values.len() as u32);
+}
+
+/// Pushes elements `start..end` of `object` with length `length` to `values`, holes are read as
+/// `undefined`.
+fn collect_elements(
+    ctx: GcPointer<Context>,
+    object: &mut GcPointer<JsObject>,
+    length: u32,
+    start: u32,
+    end: u32,
+    values: &mut Vec<JsValue>,
+) -> Result<(), JsValue> {
+    if is_dense_array(object, length) {
+        let elements = object.indexed.vector.as_slice();
+        values.extend_from_slice(&elements[start as usize..end as usize]);
+        return Ok(());
+    }
+    for k in start..end {
+        values.push(object.get(ctx, Symbol::Index(k))?);
+    }
+    Ok(())
+}
+
+/// Resolves relative index argument of `slice` like methods, negative values count from the end.
+fn relative_index(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    length: u32,
+    default: u32,
+) -> Result<u32, JsValue> {
+    if value.is_undefined() {
+        return Ok(default);
+    }
+    let relative = to_integer_or_infinity(ctx, value)?;
+    Ok(if relative < 0.0 {
+        (length as f64 + relative).max(0.0) as u32
+    } else {
+        relative.min(length as f64) as u32
+    })
+}
+
+fn callback_function(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    name: &str,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    if unlikely(!value.is_callable()) {
+        return Err(JsValue::new(ctx.new_type_error(format!(
+            "Array.prototype.{} callback must be a function",
+            name
+        ))));
+    }
+    Ok(value.get_jsobject())
+}
+
+fn invalid_length(ctx: GcPointer<Context>) -> JsValue {
+    JsValue::new(ctx.new_range_error("Invalid array length"))
+}
+
+/// LengthOfArrayLike for methods that copy `object` to a new array of the same length. Unlike
+/// `get_length` lengths that don't fit into u32 are not wrapped around, they throw RangeError like
+/// ArrayCreate would.
+fn get_new_array_length(
+    ctx: GcPointer<Context>,
+    object: &mut GcPointer<JsObject>,
+) -> Result<u32, JsValue> {
+    if object.is_class(JsArray::get_class()) {
+        return Ok(object.indexed.length());
+    }
+    let length = object.get(ctx, "length".intern())?;
+    let length = to_integer_or_infinity(ctx, length)?.max(0.0);
+    if unlikely(length > u32::MAX as f64) {
+        return Err(invalid_length(ctx));
+    }
+    Ok(length as u32)
+}
+
+/// Calls predicate of `every`, `findLast` and friends with `(value, index, object)`.
+fn call_predicate(
+    ctx: GcPointer<Context>,
+    callback: &mut GcPointer<JsObject>,
+    this_arg: JsValue,
+    value: JsValue,
+    index: u32,
+    object: GcPointer<JsObject>,
+) -> Result<bool, JsValue> {
+    let stack = ctx.shadowstack();
+    let mut buf = [value, JsValue::new(index), JsValue::new(object)];
+    letroot!(args = stack, Arguments::new(this_arg, &mut buf));
+    let func = JsValue::new(*callback);
+    Ok(callback
+        .as_function_mut()
+        .call(ctx, &mut args, func)?
+        .to_boolean())
+}
+
+pub fn array_index_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    if length == 0 {
+        return Ok(JsValue::new(-1));
+    }
+    let n = to_integer_or_infinity(ctx, args.at(1))?;
+    if n >= length as f64 {
+        return Ok(JsValue::new(-1));
+    }
+    let start = if n >= 0.0 {
+        n as u32
+    } else {
+        (length as f64 + n).max(0.0) as u32
+    };
+    let search_element = args.at(0);
+    if is_dense_array(&object, length) {
+        let elements = &object.indexed.vector.as_slice()[start as usize..];
+        return Ok(
+            match elements
+                .iter()
+                .position(|element| element.strict_equal(search_element))
+            {
+                Some(position) => JsValue::new(start + position as u32),
+                None => JsValue::new(-1),
+            },
+        );
+    }
+    for k in start..length {
+        if object.has_property(ctx, Symbol::Index(k))
+            && object
+                .get(ctx, Symbol::Index(k))?
+                .strict_equal(search_element)
+        {
+            return Ok(JsValue::new(k));
+        }
+    }
+    Ok(JsValue::new(-1))
+}
+
+pub fn array_last_index_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    if length == 0 {
+        return Ok(JsValue::new(-1));
+    }
+    let n = if args.size() > 1 {
+        to_integer_or_infinity(ctx, args.at(1))?
+    } else {
+        length as f64 - 1.0
+    };
+    let start = if n >= 0.0 {
+        n.min(length as f64 - 1.0)
+    } else {
+        length as f64 + n
+    };
+    if start < 0.0 {
+        return Ok(JsValue::new(-1));
+    }
+    let start = start as u32;
+    let search_element = args.at(0);
+    if is_dense_array(&object, length) {
+        let elements = &object.indexed.vector.as_slice()[..=start as usize];
+        return Ok(
+            match elements
+                .iter()
+                .rposition(|element| element.strict_equal(search_element))
+            {
+                Some(position) => JsValue::new(position as u32),
+                None => JsValue::new(-1),
+            },
+        );
+    }
+    for k in (0..=start).rev() {
+        if object.has_property(ctx, Symbol::Index(k))
+            && object
+                .get(ctx, Symbol::Index(k))?
+                .strict_equal(search_element)
+        {
+            return Ok(JsValue::new(k));
+        }
+    }
+    Ok(JsValue::new(-1))
+}
+
+pub fn array_reverse(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    if is_dense_array(&object, length) {
+        object.indexed.vector.as_slice_mut().reverse();
+        return Ok(JsValue::new(*object));
+    }
+    let middle = length / 2;
+    let mut lower = 0;
+    while lower != middle {
+        let upper = length - lower - 1;
+        let (lower_key, upper_key) = (Symbol::Index(lower), Symbol::Index(upper));
+        let lower_exists = object.has_property(ctx, lower_key);
+        let lower_value = if lower_exists {
+            object.get(ctx, lower_key)?
+        } else {
+            JsValue::encode_undefined_value()
+        };
+        let upper_exists = object.has_property(ctx, upper_key);
+        let upper_value = if upper_exists {
+            object.get(ctx, upper_key)?
+        } else {
+            JsValue::encode_undefined_value()
+        };
+        if upper_exists {
+            object.put(ctx, lower_key, upper_value, true)?;
+        } else if lower_exists {
+            object.delete(ctx, lower_key, true)?;
+        }
+        if lower_exists {
+            object.put(ctx, upper_key, lower_value, true)?;
+        } else if upper_exists {
+            object.delete(ctx, upper_key, true)?;
+        }
+        lower += 1;
+    }
+    Ok(JsValue::new(*object))
+}
+
+pub fn array_every(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    letroot!(
+        callback = stack,
+        callback_function(ctx, args.at(0), "every")?
+    );
+    let this_arg = args.at(1);
+    for k in 0..length {
+        if !object.has_property(ctx, Symbol::Index(k)) {
+            continue;
+        }
+        let value = object.get(ctx, Symbol::Index(k))?;
+        if !call_predicate(ctx, &mut callback, this_arg, value, k, *object)? {
+            return Ok(JsValue::new(false));
+        }
+    }
+    Ok(JsValue::new(true))
+}
+
+pub fn array_unshift(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    let arg_count = args.size() as u32;
+    if arg_count == 0 {
+        object.put(ctx, "length".intern(), JsValue::new(length), true)?;
+        return Ok(JsValue::new(length));
+    }
+    if unlikely(length as u64 + arg_count as u64 >= u32::MAX as u64) {
+        return Err(invalid_length(ctx));
+    }
+    let new_length = length + arg_count;
+    if is_dense_array(&object, length) && new_length <= MAX_VECTOR_SIZE as u32 {
+        letroot!(
+            values = stack,
+            Vec::<JsValue>::with_capacity(new_length as usize)
+        );
+        for i in 0..args.size() {
+            values.push(args.at(i));
+        }
+        values.extend_from_slice(object.indexed.vector.as_slice());
+        set_dense_elements(ctx, &mut object, &values);
+        return Ok(JsValue::new(new_length));
+    }
+    let mut k = length;
+    while k > 0 {
+        let from = Symbol::Index(k - 1);
+        let to = Symbol::Index(k + arg_count - 1);
+        if object.has_property(ctx, from) {
+            let value = object.get(ctx, from)?;
+            object.put(ctx, to, value, true)?;
+        } else {
+            object.delete(ctx, to, true)?;
+        }
+        k -= 1;
+    }
+    for j in 0..arg_count {
+        object.put(ctx, Symbol::Index(j), args.at(j as usize), true)?;
+    }
+    object.put(ctx, "length".intern(), JsValue::new(new_length), true)?;
+    Ok(JsValue::new(new_length))
+}
+
+/// Resolves `start` and `deleteCount` arguments of `splice` and `toSpliced`.
+fn splice_range(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    length: u32,
+) -> Result<(u32, u32), JsValue> {
+    let start = relative_index(ctx, args.at(0), length, 0)?;
+    let count = match args.size() {
+        0 => 0,
+        1 => length - start,
+        _ => to_integer_or_infinity(ctx, args.at(1))?
+            .max(0.0)
+            .min((length - start) as f64) as u32,
+    };
+    Ok((start, count))
+}
+
+pub fn array_splice(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    let (start, delete_count) = splice_range(ctx, args, length)?;
+    let item_count = args.size().saturating_sub(2) as u32;
+    if unlikely(length as u64 + item_count as u64 - delete_count as u64 >= u32::MAX as u64) {
+        return Err(invalid_length(ctx));
+    }
+    let new_length = length + item_count - delete_count;
+    let species = array_species_constructor(ctx, &mut object)?;
+
+    if species.is_none() && is_dense_array(&object, length) && new_length <= MAX_VECTOR_SIZE as u32
+    {
+        let elements = object.indexed.vector.as_slice();
+        let (start, end) = (start as usize, (start + delete_count) as usize);
+        letroot!(
+            removed = stack,
+            JsArray::from_slice(ctx, &elements[start..end])
+        );
+        letroot!(
+            values = stack,
+            Vec::<JsValue>::with_capacity(new_length as usize)
+        );
+        let elements = object.indexed.vector.as_slice();
+        values.extend_from_slice(&elements[..start]);
+        for i in 2..args.size() {
+            values.push(args.at(i));
+        }
+        values.extend_from_slice(&elements[end..]);
+        set_dense_elements(ctx, &mut object, &values);
+        return Ok(JsValue::new(*removed));
+    }
+
+    letroot!(removed = stack, species_create(ctx, species, delete_count)?);
+    for k in 0..delete_count {
+        let from = Symbol::Index(start + k);
+        if object.has_property(ctx, from) {
+            let value = object.get(ctx, from)?;
+            create_data_property(ctx, &mut removed, k, value)?;
+        }
+    }
+    removed.put(ctx, "length".intern(), JsValue::new(delete_count), true)?;
+    let move_element =
+        |object: &mut GcPointer<JsObject>, from: u32, to: u32| -> Result<(), JsValue> {
+            let (from, to) = (Symbol::Index(from), Symbol::Index(to));
+            if object.has_property(ctx, from) {
+                let value = object.get(ctx, from)?;
+                object.put(ctx, to, value, true)
+            } else {
+                object.delete(ctx, to, true).map(|_| ())
+            }
+        };
+    if item_count < delete_count {
+        for k in start..length - delete_count {
+            move_element(&mut object, k + delete_count, k + item_count)?;
+        }
+        let mut k = length;
+        while k > new_length {
+            object.delete(ctx, Symbol::Index(k - 1), true)?;
+            k -= 1;
+        }
+    } else if item_count > delete_count {
+        let mut k = length - delete_count;
+        while k > start {
+            move_element(&mut object, k + delete_count - 1, k + item_count - 1)?;
+            k -= 1;
+        }
+    }
+    for (k, i) in (2..args.size()).enumerate() {
+        object.put(ctx, Symbol::Index(start + k as u32), args.at(i), true)?;
+    }
+    object.put(ctx, "length".intern(), JsValue::new(new_length), true)?;
+    Ok(JsValue::new(*removed))
+}
+
+pub fn array_reduce_right(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    letroot!(
+        callback = stack,
+        callback_function(ctx, args.at(0), "reduceRight")?
+    );
+    let mut k = length;
+    letroot!(acc = stack, JsValue::encode_undefined_value());
+    if args.size() > 1 {
+        *acc = args.at(1);
+    } else {
+        loop {
+            if k == 0 {
+                return Err(JsValue::new(ctx.new_type_error(
+                    "Array.prototype.reduceRight of empty array with no initial value",
+                )));
+            }
+            k -= 1;
+            if object.has_property(ctx, Symbol::Index(k)) {
+                *acc = object.get(ctx, Symbol::Index(k))?;
+                break;
+            }
+        }
+    }
+    while k > 0 {
+        k -= 1;
+        if !object.has_property(ctx, Symbol::Index(k)) {
+            continue;
+        }
+        let mut buf = [
+            *acc,
+            object.get(ctx, Symbol::Index(k))?,
+            JsValue::new(k),
+            JsValue::new(*object),
+        ];
+        letroot!(
+            args = stack,
+            Arguments::new(JsValue::encode_undefined_value(), &mut buf)
+        );
+        let func = JsValue::new(*callback);
+        *acc = callback.as_function_mut().call(ctx, &mut args, func)?;
+    }
+    Ok(*acc)
+}
+
+pub fn array_copy_within(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    let to = relative_index(ctx, args.at(0), length, 0)?;
+    let from = relative_index(ctx, args.at(1), length, 0)?;
+    let end = relative_index(ctx, args.at(2), length, length)?;
+    let count = end.saturating_sub(from).min(length - to);
+    if count == 0 {
+        return Ok(JsValue::new(*object));
+    }
+    if is_dense_array(&object, length) {
+        let (from, to, count) = (from as usize, to as usize, count as usize);
+        object
+            .indexed
+            .vector
+            .as_slice_mut()
+            .copy_within(from..from + count, to);
+        return Ok(JsValue::new(*object));
+    }
+    let backwards = from < to && to < from + count;
+    for i in 0..count {
+        let offset = if backwards { count - i - 1 } else { i };
+        let (from_key, to_key) = (Symbol::Index(from + offset), Symbol::Index(to + offset));
+        if object.has_property(ctx, from_key) {
+            let value = object.get(ctx, from_key)?;
+            object.put(ctx, to_key, value, true)?;
+        } else {
+            object.delete(ctx, to_key, true)?;
+        }
+    }
+    Ok(JsValue::new(*object))
+}
+
+/// FindViaPredicate with descending direction. Returns index and value of the last element
+/// that satisfies predicate.
+fn find_last(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    name: &str,
+) -> Result<Option<(u32, JsValue)>, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    letroot!(callback = stack, callback_function(ctx, args.at(0), name)?);
+    let this_arg = args.at(1);
+    for k in (0..length).rev() {
+        let value = object.get(ctx, Symbol::Index(k))?;
+        if call_predicate(ctx, &mut callback, this_arg, value, k, *object)? {
+            return Ok(Some((k, value)));
+        }
+    }
+    Ok(None)
+}
+
+pub fn array_find_last(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(match find_last(ctx, args, "findLast")? {
+        Some((_, value)) => value,
+        None => JsValue::encode_undefined_value(),
+    })
+}
+
+pub fn array_find_last_index(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Ok(match find_last(ctx, args, "findLastIndex")? {
+        Some((index, _)) => JsValue::new(index),
+        None => JsValue::new(-1),
+    })
+}
+
+/// Sorts `values` with SortCompare, `undefined` values are moved to the end.
+fn sort_values(
+    ctx: GcPointer<Context>,
+    values: &mut Vec<JsValue>,
+    comparator: JsValue,
+) -> Result<(), JsValue> {
+    let stack = ctx.shadowstack();
+    let undefined_count = values.iter().filter(|value| value.is_undefined()).count();
+    values.retain(|value| !value.is_undefined());
+    if comparator.is_undefined() {
+        // convert every value to string once instead of on each comparison.
+        letroot!(strings = stack, Vec::<JsValue>::with_capacity(values.len()));
+        for value in values.iter() {
+            strings.push(JsValue::new(value.to_jsstring(ctx)?));
+        }
+        let mut order = (0..values.len()).collect::<Vec<_>>();
+        merge_sort(&mut order, &mut |x, y| {
+            let (x, y) = (strings[x].get_jsstring(), strings[y].get_jsstring());
+            Ok((*x).cmp(&*y))
+        })?;
+        let sorted = order.iter().map(|&i| values[i]).collect::<Vec<_>>();
+        values.copy_from_slice(&sorted);
+    } else {
+        letroot!(comparator = stack, comparator);
+        merge_sort(values, &mut |x, y| {
+            let mut tmp = [x, y];
+            letroot!(
+                args = stack,
+                Arguments::new(JsValue::encode_undefined_value(), &mut tmp)
+            );
+            let result = comparator
+                .get_jsobject()
+                .as_function_mut()
+                .call(ctx, &mut args, *comparator)?
+                .to_number(ctx)?;
+            Ok(if result < 0.0 {
+                Ordering::Less
+            } else if result > 0.0 {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            })
+        })?;
+    }
+    values.resize(
+        values.len() + undefined_count,
+        JsValue::encode_undefined_value(),
+    );
+    Ok(())
+}
+
+fn check_comparator(
+    ctx: GcPointer<Context>,
+    comparator: JsValue,
+    name: &str,
+) -> Result<(), JsValue> {
+    if unlikely(!comparator.is_undefined() && !comparator.is_callable()) {
+        return Err(JsValue::new(ctx.new_type_error(format!(
+            "Array.prototype.{} comparator must be either a function or undefined",
+            name
+        ))));
+    }
+    Ok(())
+}
+
+pub fn array_sort(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let comparator = args.at(0);
+    check_comparator(ctx, comparator, "sort")?;
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    letroot!(values = stack, Vec::<JsValue>::new());
+    if is_dense_array(&object, length) {
+        values.extend_from_slice(object.indexed.vector.as_slice());
+    } else {
+        for k in 0..length {
+            if object.has_property(ctx, Symbol::Index(k)) {
+                values.push(object.get(ctx, Symbol::Index(k))?);
+            }
+        }
+    }
+    sort_values(ctx, &mut values, comparator)?;
+    // comparator may have changed the array so results are written back with [[Set]].
+    for (k, value) in values.iter().enumerate() {
+        object.put(ctx, Symbol::Index(k as u32), *value, true)?;
+    }
+    for k in values.len() as u32..length {
+        object.delete(ctx, Symbol::Index(k), true)?;
+    }
+    Ok(JsValue::new(*object))
+}
+
+pub fn array_to_sorted(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let comparator = args.at(0);
+    check_comparator(ctx, comparator, "toSorted")?;
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_new_array_length(ctx, &mut object)?;
+    letroot!(values = stack, Vec::<JsValue>::new());
+    collect_elements(ctx, &mut object, length, 0, length, &mut values)?;
+    sort_values(ctx, &mut values, comparator)?;
+    Ok(JsValue::new(JsArray::from_slice(ctx, &values)))
+}
+
+pub fn array_to_reversed(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_new_array_length(ctx, &mut object)?;
+    letroot!(values = stack, Vec::<JsValue>::new());
+    collect_elements(ctx, &mut object, length, 0, length, &mut values)?;
+    values.reverse();
+    Ok(JsValue::new(JsArray::from_slice(ctx, &values)))
+}
+
+pub fn array_to_spliced(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    let (start, skip_count) = splice_range(ctx, args, length)?;
+    let insert_count = args.size().saturating_sub(2) as u32;
+    if unlikely(length as u64 + insert_count as u64 - skip_count as u64 >= u32::MAX as u64) {
+        return Err(invalid_length(ctx));
+    }
+    letroot!(values = stack, Vec::<JsValue>::new());
+    collect_elements(ctx, &mut object, length, 0, start, &mut values)?;
+    for i in 2..args.size() {
+        values.push(args.at(i));
+    }
+    collect_elements(
+        ctx,
+        &mut object,
+        length,
+        start + skip_count,
+        length,
+        &mut values,
+    )?;
+    Ok(JsValue::new(JsArray::from_slice(ctx, &values)))
+}
+
+pub fn array_with(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_new_array_length(ctx, &mut object)?;
+    let relative = to_integer_or_infinity(ctx, args.at(0))?;
+    let index = if relative >= 0.0 {
+        relative
+    } else {
+        length as f64 + relative
+    };
+    if unlikely(index < 0.0 || index >= length as f64) {
+        return Err(JsValue::new(ctx.new_range_error("Invalid index")));
+    }
+    letroot!(values = stack, Vec::<JsValue>::new());
+    collect_elements(ctx, &mut object, length, 0, length, &mut values)?;
+    values[index as usize] = args.at(1);
+    Ok(JsValue::new(JsArray::from_slice(ctx, &values)))
+}
+
+/// Stores `element` at `index` of `target` or, if it is an array and `depth > 0`, its elements
+/// flattened by `depth - 1` levels. Returns the next index. Part of FlattenIntoArray.
+fn flatten_element(
+    ctx: GcPointer<Context>,
+    target: &mut GcPointer<JsObject>,
+    element: JsValue,
+    index: u32,
+    depth: f64,
+) -> Result<u32, JsValue> {
+    if depth > 0.0 && operations::is_array(ctx, element)? {
+        let stack = ctx.shadowstack();
+        letroot!(source = stack, element.get_jsobject());
+        let length = get_length(ctx, &mut source)?;
+        return flatten_into_array(ctx, target, &mut source, length, index, depth - 1.0);
+    }
+    if unlikely(index == u32::MAX - 1) {
+        return Err(invalid_length(ctx));
+    }
+    create_data_property(ctx, target, index, element)?;
+    Ok(index + 1)
+}
+
+/// FlattenIntoArray without mapper function.
+fn flatten_into_array(
+    ctx: GcPointer<Context>,
+    target: &mut GcPointer<JsObject>,
+    source: &mut GcPointer<JsObject>,
+    length: u32,
+    mut index: u32,
+    depth: f64,
+) -> Result<u32, JsValue> {
+    let stack = ctx.shadowstack();
+    for k in 0..length {
+        let key = Symbol::Index(k);
+        if !source.has_property(ctx, key) {
+            continue;
+        }
+        letroot!(element = stack, source.get(ctx, key)?);
+        index = flatten_element(ctx, target, *element, index, depth)?;
+    }
+    Ok(index)
+}
+
+pub fn array_flat(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    let depth = if args.at(0).is_undefined() {
+        1.0
+    } else {
+        to_integer_or_infinity(ctx, args.at(0))?.max(0.0)
+    };
+    letroot!(result = stack, array_species_create(ctx, &mut object, 0)?);
+    flatten_into_array(ctx, &mut result, &mut object, length, 0, depth)?;
+    Ok(JsValue::new(*result))
+}
+
+pub fn array_flat_map(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let length = get_length(ctx, &mut object)?;
+    letroot!(
+        callback = stack,
+        callback_function(ctx, args.at(0), "flatMap")?
+    );
+    letroot!(result = stack, array_species_create(ctx, &mut object, 0)?);
+    let this_arg = args.at(1);
+
+    let mut index = 0;
+    for k in 0..length {
+        let key = Symbol::Index(k);
+        if !object.has_property(ctx, key) {
+            continue;
+        }
+        let mut buf = [
+            object.get(ctx, key)?,
+            JsValue::new(k),
+            JsValue::new(*object),
+        ];
+        letroot!(args = stack, Arguments::new(this_arg, &mut buf));
+        let func = JsValue::new(*callback);
+        letroot!(
+            mapped = stack,
+            callback.as_function_mut().call(ctx, &mut args, func)?
+        );
+        index = flatten_element(ctx, &mut result, *mapped, index, 1.0)?;
+    }
+    Ok(JsValue::new(*result))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_length_changed_by_coercion() {
+        assert_eval_cases(&[
+            (
+                "var a = [1, 2, 3]; a.indexOf(1, { valueOf() { a.length = 0; return 2 } })",
+                "-1",
+            ),
+            (
+                "var a = [1, 2, 3]; a.lastIndexOf(3, { valueOf() { a.length = 0; return 2 } })",
+                "-1",
+            ),
+            (
+                "var a = [1, 2, 3, 4]; var r = a.splice({ valueOf() { a.length = 1; return 1 } }, 2); \
+                 [r.length, 1 in r, a.length]",
+                "2,false,2",
+            ),
+            (
+                "var a = [1, 2, 3]; a.copyWithin(0, 2, { valueOf() { a.length = 1; return 4 } }); \
+                 [a.length, 0 in a]",
+                "1,false",
+            ),
+            (
+                "var a = [1, 2, 3]; a.toSpliced({ valueOf() { a.length = 1; return 1 } }, 1)",
+                "1,",
+            ),
+            (
+                "var a = [1, 2, 3]; a.with({ valueOf() { a.length = 0; return 0 } }, 1)",
+                "1,,",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_copy_of_huge_array_like() {
+        assert_eval_cases(&[
+            (
+                "try { Array.prototype.toSorted.call({ length: 4294967296 }) } catch (e) { e.name }",
+                "RangeError",
+            ),
+            (
+                "try { Array.prototype.toReversed.call({ length: 4294967297 }) } catch (e) { e.name }",
+                "RangeError",
+            ),
+            (
+                "try { Array.prototype.with.call({ length: 9007199254740992 }, 0, 0) } catch (e) { e.name }",
+                "RangeError",
+            ),
+            (
+                "Array.prototype.toReversed.call({ length: 2, 0: 'a', 1: 'b' })",
+                "b,a",
+            ),
+            (
+                "try { Array.prototype.with.call({ length: -1 }, 0, 0) } catch (e) { e.name }",
+                "RangeError",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_holes_and_negative_indices() {
+        assert_eval_cases(&[
+            ("[1, [2, [3, [4]]]].flat(Infinity)", "1,2,3,4"),
+            ("[1, , 3].flat().length", "2"),
+            ("[NaN].indexOf(NaN)", "-1"),
+            ("[1, 2, 3].lastIndexOf(3, -1) + ',' + [1, 2, 3].lastIndexOf(1, -4)", "2,-1"),
+            (
+                "[{ k: 1, v: 'a' }, { k: 0, v: 'b' }, { k: 1, v: 'c' }, { k: 0, v: 'd' }] \
+                 .sort(function (x, y) { return x.k - y.k }).map(function (x) { return x.v }).join('')",
+                "bdac",
+            ),
+            (
+                "var a = [3, undefined, , 1]; a.sort(); [a.length, a[0], a[1], a[2], 3 in a].join()",
+                "4,1,3,,false",
+            ),
+            ("[1, 2, 3].toSpliced(-1, 1, 'x')", "1,2,x"),
+            ("[1, 2, 3].with(-1, 0)", "1,2,0"),
+            (
+                "try { [1, 2, 3].with(3, 0) } catch (e) { e.name }",
+                "RangeError",
+            ),
+            ("1 in [1, , 3].toReversed()", "true"),
+            (
+                "try { [].reduce(function () {}) } catch (e) { e.name }",
+                "TypeError",
+            ),
+            ("[1, , 3].reduceRight(function (acc, x) { return acc + x }, '')", "31"),
+            ("[1, 2, 3].copyWithin(-2)", "1,1,2"),
+            ("['a', 'b', 'c'].splice(-2)", "b,c"),
+            (
+                "var o = { length: 1, 0: 2 }; o[Symbol.isConcatSpreadable] = true; [1].concat(o)",
+                "1,2",
+            ),
+            ("[1, 2, 3].findLastIndex(function (x) { return x < 3 })", "1"),
+        ]);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     prelude::*,
     vm::{
-        array_buffer::JsArrayBuffer, context::Context, object::TypedJsObject,
+        array_buffer::JsArrayBuffer, context::Context, object::TypedJsObject, operations,
         structure_builder::StructureBuilder,
     },
 };
@@ -75,14 +75,41 @@ pub fn array_buffer_slice(ctx: GcPointer<Context>, args: &Arguments) -> Result<J
         std::cmp::min(relative_end, len as i64) as usize
     };
     let new_len = std::cmp::max(finale as i64 - first as i64, 0) as usize;
-    let mut new_buf = TypedJsObject::<JsArrayBuffer>::new(JsArrayBuffer::new(ctx));
-    new_buf.create_data_block(ctx, new_len, true)?;
-    // 17. If new does not have an [[ArrayBufferData]] internal slot, throw a
-    // TypeError exception.
+    let constructor =
+        operations::species_constructor(ctx, &mut this, JsValue::encode_undefined_value())?;
+    let new_buf = if constructor.is_undefined()
+        || super::is_native_function(constructor, array_buffer_constructor)
+    {
+        let mut new_buf = TypedJsObject::<JsArrayBuffer>::new(JsArrayBuffer::new(ctx));
+        new_buf.create_data_block(ctx, new_len, true)?;
+        new_buf
+    } else {
+        let result =
+            operations::construct(ctx, constructor, &mut [JsValue::new(new_len as f64)], None)?;
+        // 17. If new does not have an [[ArrayBufferData]] internal slot, throw a
+        // TypeError exception.
+        if !result.is_jsobject() || !result.get_jsobject().is_class(JsArrayBuffer::get_class()) {
+            return Err(JsValue::new(ctx.new_type_error(
+                "ArrayBuffer species constructor didn't return an ArrayBuffer",
+            )));
+        }
+        // 19. If SameValue(new, O) is true, throw a TypeError exception.
+        if GcPointer::ptr_eq(&result.get_jsobject(), &this) {
+            return Err(JsValue::new(ctx.new_type_error(
+                "ArrayBuffer species constructor returned the same buffer",
+            )));
+        }
+        let new_buf = TypedJsObject::<JsArrayBuffer>::new(result.get_jsobject());
+        // 20. If the value of new’s [[ArrayBufferByteLength]] internal
+        // slot < newLen, throw a TypeError exception.
+        if new_buf.size() < new_len {
+            return Err(JsValue::new(ctx.new_type_error(
+                "ArrayBuffer species constructor returned too small buffer",
+            )));
+        }
+        new_buf
+    };
     // 18. If IsDetachedBuffer(new) is true, throw a TypeError exception.
-    // 19. If SameValue(new, O) is true, throw a TypeError exception.
-    // 20. If the value of new’s [[ArrayBufferByteLength]] internal
-    // slot < newLen, throw a TypeError exception.
     // 21. NOTE: Side-effects of the above steps may have detached O.
     // 22. If IsDetachedBuffer(O) is true, throw a TypeError exception.
     if !buf.attached() || !new_buf.attached() {
@@ -147,6 +174,14 @@ impl GcPointer<Context> {
         def_native_property!(self, ctor, prototype, proto)?;
         def_native_property!(self, proto, constructor, ctor)?;
         def_native_method!(self, proto, slice, array_buffer_slice, 2)?;
+        super::define_species(self, &mut ctor)?;
+        let tag = JsString::new(self, "ArrayBuffer");
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
         Ok(())
     }
 }
@@ -0,0 +1,243 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+use std::intrinsics::unlikely;
+
+use num::BigInt;
+
+use super::intl::number_format::{Number, NumberFormatter};
+use crate::{
+    constant::S_CONSTURCTOR,
+    prelude::*,
+    vm::{
+        bigint::{BigIntObject, JsBigInt},
+        context::Context,
+    },
+};
+
+/// ToBigInt(value)
+pub(crate) fn to_bigint(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+) -> Result<GcPointer<JsBigInt>, JsValue> {
+    let prim = value.to_primitive(ctx, JsHint::Number)?;
+    if prim.is_bigint() {
+        return Ok(prim.get_bigint());
+    }
+    if prim.is_bool() {
+        return Ok(JsBigInt::new(ctx, BigInt::from(prim.get_bool() as u8)));
+    }
+    if prim.is_jsstring() {
+        let string = prim.get_jsstring();
+        return match JsBigInt::parse(&string.as_str()) {
+            Some(value) => Ok(JsBigInt::new(ctx, value)),
+            None => Err(JsValue::new(ctx.new_syntax_error(format!(
+                "Cannot convert {} to a BigInt",
+                string.as_str()
+            )))),
+        };
+    }
+    if prim.is_symbol() {
+        return Err(JsValue::new(
+            ctx.new_type_error("Cannot convert a Symbol value to a BigInt"),
+        ));
+    }
+    Err(JsValue::new(ctx.new_type_error(format!(
+        "Cannot convert {} to a BigInt",
+        prim.to_string(ctx)?
+    ))))
+}
+
+fn this_bigint_value(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    name: &str,
+) -> Result<GcPointer<JsBigInt>, JsValue> {
+    if value.is_bigint() {
+        return Ok(value.get_bigint());
+    }
+    if value.is_jsobject() {
+        let object = value.get_jsobject();
+        if object.is_class(BigIntObject::get_class()) {
+            return Ok(object.data::<BigIntObject>().value());
+        }
+    }
+    Err(JsValue::new(ctx.new_type_error(format!(
+        "BigInt.prototype.{} requires that 'this' be a BigInt",
+        name
+    ))))
+}
+
+pub fn bigint_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if unlikely(args.ctor_call) {
+        return Err(JsValue::new(
+            ctx.new_type_error("BigInt is not a constructor"),
+        ));
+    }
+    let prim = args.at(0).to_primitive(ctx, JsHint::Number)?;
+    if prim.is_number() {
+        let number = prim.get_number();
+        return match JsBigInt::from_f64(number) {
+            Some(value) => Ok(JsValue::new(JsBigInt::new(ctx, value))),
+            None => Err(JsValue::new(ctx.new_range_error(format!(
+                "The number {} cannot be converted to a BigInt because it is not an integer",
+                number
+            )))),
+        };
+    }
+    to_bigint(ctx, prim).map(JsValue::new)
+}
+
+pub fn bigint_as_int_n(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let bits = super::to_index(ctx, args.at(0))?;
+    let bigint = to_bigint(ctx, args.at(1))?;
+    match JsBigInt::as_int_n(bigint.value(), bits as _) {
+        Some(value) => Ok(JsValue::new(JsBigInt::new(ctx, value))),
+        None => Err(JsValue::new(
+            ctx.new_range_error("Maximum BigInt size exceeded"),
+        )),
+    }
+}
+
+pub fn bigint_as_uint_n(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let bits = super::to_index(ctx, args.at(0))?;
+    let bigint = to_bigint(ctx, args.at(1))?;
+    match JsBigInt::as_uint_n(bigint.value(), bits as _) {
+        Some(value) => Ok(JsValue::new(JsBigInt::new(ctx, value))),
+        None => Err(JsValue::new(
+            ctx.new_range_error("Maximum BigInt size exceeded"),
+        )),
+    }
+}
+
+pub fn bigint_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let bigint = this_bigint_value(ctx, args.this, "toString")?;
+    let radix = if args.at(0).is_undefined() {
+        10.0
+    } else {
+        args.at(0).to_number(ctx)?.trunc()
+    };
+    if unlikely(!(2.0..=36.0).contains(&radix)) {
+        return Err(JsValue::new(
+            ctx.new_range_error("toString() radix must be between 2 and 36"),
+        ));
+    }
+    Ok(JsValue::new(JsString::new(
+        ctx,
+        bigint.value().to_str_radix(radix as u32),
+    )))
+}
+
+pub fn bigint_to_locale_string(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let bigint = this_bigint_value(ctx, args.this, "toLocaleString")?;
+    let formatter = NumberFormatter::new(ctx, args.at(0), args.at(1))?;
+    Ok(JsValue::new(JsString::new(
+        ctx,
+        formatter.format(Number::from_bigint(bigint.value())),
+    )))
+}
+
+pub fn bigint_value_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    this_bigint_value(ctx, args.this, "valueOf").map(JsValue::new)
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_bigint_in_global_object(mut self) -> Result<(), JsValue> {
+        let ctor = self
+            .global_data
+            .bigint_prototype
+            .unwrap()
+            .get(self, S_CONSTURCTOR.intern())
+            .unwrap_or_else(|_| unreachable!());
+
+        let mut global_object = self.global_object();
+        def_native_property!(self, global_object, BigInt, ctor, W | C)?;
+        Ok(())
+    }
+
+    pub(crate) fn init_bigint_in_global_data(mut self) -> Result<(), JsValue> {
+        let mut map = Structure::new_indexed(self, None, false);
+        self.global_data.bigint_structure = Some(map);
+        let obj_proto = self.global_data().get_object_prototype();
+        let structure = Structure::new_unique_indexed(self, Some(obj_proto), false);
+        let mut proto = JsObject::new(self, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+        map.change_prototype_with_no_transition(proto);
+
+        let mut ctor = JsNativeFunction::new(self, "BigInt".intern(), bigint_constructor, 1);
+
+        def_native_property!(self, ctor, prototype, proto, NONE)?;
+        def_native_method!(self, ctor, asIntN, bigint_as_int_n, 2, W | C)?;
+        def_native_method!(self, ctor, asUintN, bigint_as_uint_n, 2, W | C)?;
+
+        def_native_property!(self, proto, constructor, ctor, W | C)?;
+        def_native_method!(self, proto, toString, bigint_to_string, 0, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toLocaleString,
+            bigint_to_locale_string,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, proto, valueOf, bigint_value_of, 0, W | C)?;
+        let tag = JsString::new(self, "BigInt");
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+
+        self.global_data.bigint_prototype = Some(proto);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_update_expressions() {
+        assert_eval_cases(&[
+            (
+                "var a = '5'; var b = a++; typeof b + ' ' + b + ' ' + a",
+                "number 5 6",
+            ),
+            (
+                "var a = '5'; var b = a--; typeof b + ' ' + b + ' ' + a",
+                "number 5 4",
+            ),
+            ("var a = '5'; var b = ++a; typeof b + ' ' + b", "number 6"),
+            ("var a = null; a++", "0"),
+            ("var a = {}; a++; a", "NaN"),
+            (
+                "var a = 10n; var b = a++; typeof b + ' ' + b + ' ' + a",
+                "bigint 10 11",
+            ),
+            ("var a = 0n; a--; a", "-1"),
+            (
+                "var o = { x: '1' }; var b = o.x++; typeof b + ' ' + o.x",
+                "number 2",
+            ),
+            ("var a = 2147483647; a++; a", "2147483648"),
+        ]);
+    }
+
+    #[test]
+    fn test_literals() {
+        assert_eval_cases(&[
+            (
+                "123456789012345678901234567890n",
+                "123456789012345678901234567890",
+            ),
+            ("0xffffffffffffffffffffn", "1208925819614629174706175"),
+            ("-0x1n", "-1"),
+            ("0n === -0n", "true"),
+            ("typeof 1n", "bigint"),
+        ]);
+    }
+}
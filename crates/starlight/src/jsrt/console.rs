@@ -0,0 +1,644 @@
+//! The `console` namespace object.
+//!
+//! `log`, `info`, `debug`, `dir`, `table`, `count` and `time*` write to stdout, `warn`, `error`,
+//! `trace` and `assert` write to stderr. The first argument may be a format string with
+//! `%s %d %i %f %j %o %O %c` substitutions, values are formatted by [inspect::inspect].
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    time::Instant,
+};
+
+use crate::{
+    prelude::*,
+    vm::{context::Context, error::stack_trace_limit, interpreter::SpreadValue, operations},
+};
+
+pub mod inspect;
+
+use inspect::{inspect, primitive, DEFAULT_DEPTH};
+
+/// Per context state of `console.count`, `console.time` and `console.group`.
+#[derive(Default)]
+pub struct ConsoleState {
+    counts: HashMap<String, u64>,
+    timers: HashMap<String, Instant>,
+    group_indent: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Writes `text` followed by a newline, every line indented by the current group.
+fn write(ctx: GcPointer<Context>, stream: Stream, text: &str) {
+    let indent = " ".repeat(ctx.console.group_indent);
+    let mut output = String::with_capacity(text.len() + indent.len() + 1);
+    for line in text.split('\n') {
+        output.push_str(&indent);
+        output.push_str(line);
+        output.push('\n');
+    }
+    let _ = match stream {
+        Stream::Stdout => io::stdout().lock().write_all(output.as_bytes()),
+        Stream::Stderr => io::stderr().lock().write_all(output.as_bytes()),
+    };
+}
+
+/// Formats a value that is not consumed by a substitution, strings are written as they are.
+fn format_value(ctx: GcPointer<Context>, value: JsValue) -> Result<String, JsValue> {
+    if value.is_string() {
+        return Ok(value.get_string().as_str().into_owned());
+    }
+    inspect(ctx, value, Some(DEFAULT_DEPTH))
+}
+
+fn format_number(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    integer: bool,
+) -> Result<String, JsValue> {
+    if value.is_bigint() {
+        return primitive(ctx, value);
+    }
+    if value.is_symbol() || value.is_jsobject() {
+        return Ok("NaN".to_owned());
+    }
+    let number = value.to_number(ctx)?;
+    let number = if integer { number.trunc() } else { number };
+    primitive(ctx, JsValue::new(number))
+}
+
+/// Formats `args` starting at `from` like Node's `util.format`.
+fn format_args(ctx: GcPointer<Context>, args: &Arguments, from: usize) -> Result<String, JsValue> {
+    let mut result = String::new();
+    let mut next = from;
+    let first = args.at(from);
+    if args.size() > from && first.is_string() {
+        next += 1;
+        let format = first.get_string().as_str().into_owned();
+        let mut chars = format.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                result.push(ch);
+                continue;
+            }
+            let spec = match chars.peek() {
+                Some(&spec) => spec,
+                None => {
+                    result.push('%');
+                    break;
+                }
+            };
+            if spec == '%' {
+                chars.next();
+                result.push('%');
+                continue;
+            }
+            if !"sdifjoOc".contains(spec) {
+                result.push('%');
+                continue;
+            }
+            chars.next();
+            if next >= args.size() {
+                result.push('%');
+                result.push(spec);
+                continue;
+            }
+            let value = args.at(next);
+            next += 1;
+            match spec {
+                's' if value.is_string() => result.push_str(&value.get_string().as_str()),
+                's' if value.is_jsobject() => result.push_str(&inspect(ctx, value, Some(0))?),
+                's' => result.push_str(&primitive(ctx, value)?),
+                'd' => result.push_str(&format_number(ctx, value, false)?),
+                'i' => result.push_str(&format_number(ctx, value, true)?),
+                'f' if value.is_symbol() => result.push_str("NaN"),
+                'f' => result.push_str(&primitive(ctx, JsValue::new(value.to_number(ctx)?))?),
+                'j' => result.push_str(&json(ctx, value)?),
+                'o' => result.push_str(&inspect(ctx, value, Some(4))?),
+                'O' => result.push_str(&inspect(ctx, value, Some(DEFAULT_DEPTH))?),
+                _ => (),
+            }
+        }
+    }
+    for index in next..args.size() {
+        if !result.is_empty() || index > from {
+            result.push(' ');
+        }
+        result.push_str(&format_value(ctx, args.at(index))?);
+    }
+    Ok(result)
+}
+
+/// Serializes `value` for `%j`. There is no `JSON` object, so this is a minimal `JSON.stringify`
+/// that honours `toJSON` and prints `[Circular]` for cycles.
+fn json(ctx: GcPointer<Context>, value: JsValue) -> Result<String, JsValue> {
+    fn serialize(
+        ctx: GcPointer<Context>,
+        value: JsValue,
+        seen: &mut Vec<GcPointer<JsObject>>,
+    ) -> Result<Option<String>, JsValue> {
+        let mut value = value;
+        if value.is_jsobject() {
+            let to_json = value.get_jsobject().get(ctx, "toJSON".intern())?;
+            if to_json.is_callable() {
+                value = operations::call(ctx, to_json, value, &mut [])?;
+            }
+        }
+        if value.is_undefined() || value.is_symbol() || value.is_callable() {
+            return Ok(None);
+        }
+        if value.is_null() || value.is_bool() {
+            return Ok(Some(primitive(ctx, value)?));
+        }
+        if value.is_number() {
+            let number = value.get_number();
+            if !number.is_finite() {
+                return Ok(Some("null".to_owned()));
+            }
+            return Ok(Some(JsValue::new(number).to_string(ctx)?));
+        }
+        if value.is_bigint() {
+            return Err(JsValue::new(
+                ctx.new_type_error("Do not know how to serialize a BigInt"),
+            ));
+        }
+        if value.is_string() {
+            return Ok(Some(json_quote(&value.get_string().as_str())));
+        }
+        let mut object = value.get_jsobject();
+        if seen.contains(&object) {
+            return Ok(Some("\"[Circular]\"".to_owned()));
+        }
+        seen.push(object);
+        let result = if operations::is_array(ctx, value)? {
+            let length = object.get(ctx, S_LENGTH.intern())?.to_number(ctx)? as u32;
+            let mut elements = Vec::with_capacity(length as usize);
+            for index in 0..length {
+                let element = object.get(ctx, Symbol::Index(index))?;
+                elements.push(serialize(ctx, element, seen)?.unwrap_or_else(|| "null".to_owned()));
+            }
+            format!("[{}]", elements.join(","))
+        } else {
+            let mut members = vec![];
+            for key in operations::own_property_keys(ctx, &mut object)? {
+                if let Symbol::Private(_) = key {
+                    continue;
+                }
+                match operations::get_own_property(ctx, &mut object, key)? {
+                    Some(desc) if desc.is_enumerable() => (),
+                    _ => continue,
+                }
+                let member = object.get(ctx, key)?;
+                if let Some(member) = serialize(ctx, member, seen)? {
+                    members.push(format!("{}:{}", json_quote(&ctx.description(key)), member));
+                }
+            }
+            format!("{{{}}}", members.join(","))
+        };
+        seen.pop();
+        Ok(Some(result))
+    }
+    Ok(serialize(ctx, value, &mut vec![])?.unwrap_or_else(|| "undefined".to_owned()))
+}
+
+fn json_quote(string: &str) -> String {
+    let mut result = String::with_capacity(string.len() + 2);
+    result.push('"');
+    for ch in string.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\x08' => result.push_str("\\b"),
+            '\x0c' => result.push_str("\\f"),
+            ch if (ch as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Label argument of `count` and `time`, `"default"` when it is missing.
+fn label(ctx: GcPointer<Context>, args: &Arguments) -> Result<String, JsValue> {
+    let label = args.at(0);
+    if label.is_undefined() {
+        Ok("default".to_owned())
+    } else {
+        label.to_string(ctx)
+    }
+}
+
+fn elapsed(start: Instant) -> String {
+    let ms = start.elapsed().as_secs_f64() * 1000.0;
+    if ms >= 1000.0 {
+        format!("{:.3}s", ms / 1000.0)
+    } else {
+        format!("{:.3}ms", ms)
+    }
+}
+
+/// console.log(...data)
+pub fn console_log(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    write(ctx, Stream::Stdout, &format_args(ctx, args, 0)?);
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.error(...data)
+pub fn console_error(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    write(ctx, Stream::Stderr, &format_args(ctx, args, 0)?);
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.trace(...data)
+pub fn console_trace(mut ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let message = format_args(ctx, args, 0)?;
+    let mut trace = if message.is_empty() {
+        "Trace".to_owned()
+    } else {
+        format!("Trace: {}", message)
+    };
+    let limit = stack_trace_limit(ctx).unwrap_or(10);
+    for site in ctx.call_sites(None, limit) {
+        trace.push_str(&format!("\n    at {}", site));
+    }
+    write(ctx, Stream::Stderr, &trace);
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.assert(condition, ...data)
+pub fn console_assert(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.at(0).to_boolean() {
+        return Ok(JsValue::encode_undefined_value());
+    }
+    let message = format_args(ctx, args, 1)?;
+    if message.is_empty() {
+        write(ctx, Stream::Stderr, "Assertion failed");
+    } else {
+        write(
+            ctx,
+            Stream::Stderr,
+            &format!("Assertion failed: {}", message),
+        );
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.count(label)
+pub fn console_count(mut ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let label = label(ctx, args)?;
+    let count = ctx.console.counts.entry(label.clone()).or_insert(0);
+    *count += 1;
+    let line = format!("{}: {}", label, count);
+    write(ctx, Stream::Stdout, &line);
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.countReset(label)
+pub fn console_count_reset(
+    mut ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let label = label(ctx, args)?;
+    match ctx.console.counts.get_mut(&label) {
+        Some(count) => *count = 0,
+        None => write(
+            ctx,
+            Stream::Stderr,
+            &format!("Warning: Count for '{}' does not exist", label),
+        ),
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.time(label)
+pub fn console_time(mut ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let label = label(ctx, args)?;
+    if ctx.console.timers.contains_key(&label) {
+        let warning = format!(
+            "Warning: Label '{}' already exists for console.time()",
+            label
+        );
+        write(ctx, Stream::Stderr, &warning);
+    } else {
+        ctx.console.timers.insert(label, Instant::now());
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.timeLog(label, ...data)
+pub fn console_time_log(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let label = label(ctx, args)?;
+    match ctx.console.timers.get(&label).copied() {
+        Some(start) => {
+            let mut line = format!("{}: {}", label, elapsed(start));
+            if args.size() > 1 {
+                line.push(' ');
+                line.push_str(&format_args(ctx, args, 1)?);
+            }
+            write(ctx, Stream::Stdout, &line);
+        }
+        None => {
+            let warning = format!("Warning: No such label '{}' for console.timeLog()", label);
+            write(ctx, Stream::Stderr, &warning);
+        }
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.timeEnd(label)
+pub fn console_time_end(mut ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let label = label(ctx, args)?;
+    match ctx.console.timers.remove(&label) {
+        Some(start) => {
+            let line = format!("{}: {}", label, elapsed(start));
+            write(ctx, Stream::Stdout, &line);
+        }
+        None => {
+            let warning = format!("Warning: No such label '{}' for console.timeEnd()", label);
+            write(ctx, Stream::Stderr, &warning);
+        }
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.group(...label)
+pub fn console_group(mut ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.size() > 0 {
+        write(ctx, Stream::Stdout, &format_args(ctx, args, 0)?);
+    }
+    ctx.console.group_indent += 2;
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.groupEnd()
+pub fn console_group_end(mut ctx: GcPointer<Context>, _: &Arguments) -> Result<JsValue, JsValue> {
+    ctx.console.group_indent = ctx.console.group_indent.saturating_sub(2);
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.dir(item, { depth })
+pub fn console_dir(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut depth = Some(DEFAULT_DEPTH);
+    let options = args.at(1);
+    if options.is_jsobject() {
+        let value = options.get_jsobject().get(ctx, "depth".intern())?;
+        if value.is_null() {
+            depth = None;
+        } else if !value.is_undefined() {
+            let value = value.to_number(ctx)?;
+            depth = if value.is_infinite() && value > 0.0 {
+                None
+            } else if value > 0.0 {
+                Some(value as usize)
+            } else {
+                Some(0)
+            };
+        }
+    }
+    write(ctx, Stream::Stdout, &inspect(ctx, args.at(0), depth)?);
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// console.table(tabularData, properties)
+pub fn console_table(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let data = args.at(0);
+    if !data.is_jsobject() {
+        return console_log(ctx, args);
+    }
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, data.get_jsobject());
+    let filter = if args.at(1).is_jsobject() {
+        letroot!(properties = stack, SpreadValue::new(ctx, args.at(1))?);
+        let mut filter = vec![];
+        for property in properties.array.iter() {
+            filter.push(property.to_string(ctx)?);
+        }
+        Some(filter)
+    } else {
+        None
+    };
+
+    let mut columns: Vec<String> = vec![];
+    let mut has_values = false;
+    let mut rows = vec![];
+    for key in operations::own_property_keys(ctx, &mut object)? {
+        if let Symbol::Private(_) = key {
+            continue;
+        }
+        match operations::get_own_property(ctx, &mut object, key)? {
+            Some(desc) if desc.is_enumerable() => (),
+            _ => continue,
+        }
+        let value = object.get(ctx, key)?;
+        let mut cells = HashMap::new();
+        let mut primitive_value = None;
+        if value.is_jsobject() && !value.is_callable() {
+            letroot!(row = stack, value.get_jsobject());
+            for column in operations::own_property_keys(ctx, &mut row)? {
+                if let Symbol::Private(_) = column {
+                    continue;
+                }
+                match operations::get_own_property(ctx, &mut row, column)? {
+                    Some(desc) if desc.is_enumerable() => (),
+                    _ => continue,
+                }
+                let name = ctx.description(column);
+                if let Some(filter) = &filter {
+                    if !filter.contains(&name) {
+                        continue;
+                    }
+                }
+                let cell = row.get(ctx, column)?;
+                if !columns.contains(&name) {
+                    columns.push(name.clone());
+                }
+                cells.insert(name, inspect(ctx, cell, Some(0))?);
+            }
+        } else {
+            has_values = true;
+            primitive_value = Some(inspect(ctx, value, Some(0))?);
+        }
+        rows.push((ctx.description(key), cells, primitive_value));
+    }
+    if let Some(filter) = filter {
+        columns = filter;
+    }
+
+    let mut header = vec!["(index)".to_owned()];
+    header.extend(columns.iter().cloned());
+    if has_values {
+        header.push("Values".to_owned());
+    }
+    let body = rows
+        .into_iter()
+        .map(|(index, mut cells, value)| {
+            let mut line = vec![index];
+            for column in columns.iter() {
+                line.push(cells.remove(column).unwrap_or_default());
+            }
+            if has_values {
+                line.push(value.unwrap_or_default());
+            }
+            line
+        })
+        .collect::<Vec<_>>();
+    write(ctx, Stream::Stdout, &render_table(&header, &body));
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// Draws `header` and `body` with box characters, every cell centered in its column.
+fn render_table(header: &[String], body: &[Vec<String>]) -> String {
+    let widths = (0..header.len())
+        .map(|column| {
+            body.iter()
+                .map(|row| row[column].chars().count())
+                .chain(std::iter::once(header[column].chars().count()))
+                .max()
+                .unwrap_or(0)
+                + 2
+        })
+        .collect::<Vec<_>>();
+    let divider = |left: char, middle: char, right: char| {
+        let segments = widths
+            .iter()
+            .map(|width| "─".repeat(*width))
+            .collect::<Vec<_>>();
+        format!("{}{}{}", left, segments.join(&middle.to_string()), right)
+    };
+    let row = |cells: &[String]| {
+        let cells = cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| {
+                let padding = width - cell.chars().count();
+                let left = padding / 2;
+                format!("{}{}{}", " ".repeat(left), cell, " ".repeat(padding - left))
+            })
+            .collect::<Vec<_>>();
+        format!("│{}│", cells.join("│"))
+    };
+    let mut lines = vec![divider('┌', '┬', '┐'), row(header), divider('├', '┼', '┤')];
+    for cells in body {
+        lines.push(row(cells));
+    }
+    lines.push(divider('└', '┴', '┘'));
+    lines.join("\n")
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_console_in_global_object(mut self) -> Result<(), JsValue> {
+        let mut console = JsObject::new_empty(self);
+
+        def_native_method!(self, console, log, console_log, 0, W | E | C)?;
+        def_native_method!(self, console, info, console_log, 0, W | E | C)?;
+        def_native_method!(self, console, debug, console_log, 0, W | E | C)?;
+        def_native_method!(self, console, warn, console_error, 0, W | E | C)?;
+        def_native_method!(self, console, error, console_error, 0, W | E | C)?;
+        def_native_method!(self, console, trace, console_trace, 0, W | E | C)?;
+        def_native_method!(self, console, assert, console_assert, 0, W | E | C)?;
+        def_native_method!(self, console, count, console_count, 0, W | E | C)?;
+        def_native_method!(self, console, countReset, console_count_reset, 0, W | E | C)?;
+        def_native_method!(self, console, time, console_time, 0, W | E | C)?;
+        def_native_method!(self, console, timeLog, console_time_log, 0, W | E | C)?;
+        def_native_method!(self, console, timeEnd, console_time_end, 0, W | E | C)?;
+        def_native_method!(self, console, group, console_group, 0, W | E | C)?;
+        def_native_method!(self, console, groupCollapsed, console_group, 0, W | E | C)?;
+        def_native_method!(self, console, groupEnd, console_group_end, 0, W | E | C)?;
+        def_native_method!(self, console, table, console_table, 1, W | E | C)?;
+        def_native_method!(self, console, dir, console_dir, 0, W | E | C)?;
+
+        let mut global_object = self.global_object();
+        def_native_property!(self, global_object, console, console, W | C)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{options::Options, Platform};
+
+    /// Evaluates `script` to an array and formats its elements like `console.log` does.
+    fn log(mut ctx: GcPointer<Context>, script: &str) -> String {
+        let stack = ctx.shadowstack();
+        letroot!(
+            array = stack,
+            ctx.eval(script)
+                .unwrap_or_else(|_| panic!("{} threw", script))
+                .get_jsobject()
+        );
+        let length = array.get(ctx, S_LENGTH.intern()).unwrap().get_number() as u32;
+        let mut values = (0..length)
+            .map(|index| array.get(ctx, Symbol::Index(index)).unwrap())
+            .collect::<Vec<_>>();
+        let args = Arguments::new(JsValue::encode_undefined_value(), &mut values);
+        format_args(ctx, &args, 0).unwrap_or_else(|_| panic!("formatting {} threw", script))
+    }
+
+    fn dir(mut ctx: GcPointer<Context>, script: &str) -> String {
+        let value = ctx
+            .eval(script)
+            .unwrap_or_else(|_| panic!("{} threw", script));
+        inspect(ctx, value, Some(DEFAULT_DEPTH))
+            .unwrap_or_else(|_| panic!("inspecting {} threw", script))
+    }
+
+    #[test]
+    fn test_format_args() {
+        Platform::initialize();
+        let mut rt = Platform::new_runtime(Options::default(), None);
+        let ctx = Context::new(&mut rt);
+        let cases = [
+            ("['%s=%d', 'a', 42]", "a=42"),
+            ("['%i|%f|%d|%d', 4.7, '1.5', {}, 10n]", "4|1.5|NaN|10n"),
+            ("['%s %s', 'a']", "a %s"),
+            ("['%%s %x 100%', 'a']", "%s %x 100% a"),
+            ("['%c%s', 'color: red', 'x']", "x"),
+            ("['%s', { a: { b: 1 } }]", "{ a: [Object] }"),
+            (
+                "['%j', { a: [1, 'b\"', undefined], f: function () {}, t: { toJSON: function () { return 2; } } }]",
+                "{\"a\":[1,\"b\\\"\",null],\"t\":2}",
+            ),
+            ("[1, 'a', { b: 'c' }, -0]", "1 a { b: 'c' } -0"),
+        ];
+        for (script, expected) in cases.iter() {
+            assert_eq!(log(ctx, script), *expected, "{}", script);
+        }
+    }
+
+    #[test]
+    fn test_inspect() {
+        Platform::initialize();
+        let mut rt = Platform::new_runtime(Options::default(), None);
+        let ctx = Context::new(&mut rt);
+        let cases = [
+            ("[1, , , 'a', -0]", "[ 1, <2 empty items>, 'a', -0 ]"),
+            ("var o = { x: 1 }; o.self = o; o", "{ x: 1, self: [Circular] }"),
+            (
+                "var p = { 'a-b': 1 }; Object.defineProperty(p, 'g', { get: function () { throw 1; }, enumerable: true }); p",
+                "{ 'a-b': 1, g: [Getter] }",
+            ),
+            ("Object.create(null)", "[Object: null prototype] {}"),
+            ("({ a: { b: { c: { d: 1 } } } })", "{ a: { b: { c: [Object] } } }"),
+            ("function Foo() { this.x = 1; } new Foo()", "Foo { x: 1 }"),
+            ("(function foo() {})", "[Function: foo]"),
+            ("new Uint8Array([1, 2])", "Uint8Array(2) [ 1, 2 ]"),
+            ("[/a/g, new RegExp('')]", "[ /a/g, /(?:)/ ]"),
+            ("[\"it's\", 'a\\nb']", "[ \"it's\", 'a\\nb' ]"),
+            (
+                "({ first: 'abcdefghijklmnopqrstuvwxyz', second: 'abcdefghijklmnopqrstuvwxyz', third: 1 })",
+                "{\n  first: 'abcdefghijklmnopqrstuvwxyz',\n  second: 'abcdefghijklmnopqrstuvwxyz',\n  third: 1\n}",
+            ),
+        ];
+        for (script, expected) in cases.iter() {
+            assert_eq!(dir(ctx, script), *expected, "{}", script);
+        }
+    }
+}
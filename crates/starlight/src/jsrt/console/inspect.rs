@@ -0,0 +1,433 @@
+//! Human readable formatting of values, modeled on Node's `util.inspect`.
+//!
+//! Only own enumerable properties are shown. Accessors print as `[Getter]` and `[Setter]` and
+//! are never called, so inspecting a value does not run script code.
+use crate::{
+    prelude::*,
+    vm::{
+        array_buffer::JsArrayBuffer, context::Context, data_view::JsDataView,
+        object::TypedJsObject, operations, proxy::JsProxy, typedarray::JsTypedArray,
+    },
+};
+
+use super::super::{date::Date, regexp::RegExp};
+
+/// Objects nested deeper than this are shortened to `[Object]` unless another depth is given.
+pub const DEFAULT_DEPTH: usize = 2;
+/// Entries printed on one line must fit in this many columns.
+const BREAK_LENGTH: usize = 80;
+/// Array and typed array elements shown before the rest is summarized.
+const MAX_ARRAY_LENGTH: usize = 100;
+/// `ArrayBuffer` bytes shown before the rest is summarized.
+const MAX_BUFFER_LENGTH: usize = 50;
+
+/// Formats `value` the way `console.dir` does. `depth` of `None` means no limit.
+pub fn inspect(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    depth: Option<usize>,
+) -> Result<String, JsValue> {
+    let mut inspector = Inspector {
+        ctx,
+        depth,
+        seen: vec![],
+    };
+    inspector.value(value, 0)
+}
+
+/// Quotes `string` with the first of `'`, `"` and `` ` `` it does not contain.
+pub fn quote(string: &str) -> String {
+    let quote = ['\'', '"', '`']
+        .iter()
+        .copied()
+        .find(|quote| !string.contains(*quote))
+        .unwrap_or('\'');
+    let mut result = String::with_capacity(string.len() + 2);
+    result.push(quote);
+    for ch in string.chars() {
+        match ch {
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            '\x08' => result.push_str("\\b"),
+            '\x0c' => result.push_str("\\f"),
+            '\x0b' => result.push_str("\\v"),
+            '\\' => result.push_str("\\\\"),
+            ch if ch == quote => {
+                result.push('\\');
+                result.push(ch);
+            }
+            ch if (ch as u32) < 0x20 || ch as u32 == 0x7f => {
+                result.push_str(&format!("\\x{:02X}", ch as u32))
+            }
+            ch => result.push(ch),
+        }
+    }
+    result.push(quote);
+    result
+}
+
+/// Formats a primitive that is not a string. `-0` keeps its sign.
+pub fn primitive(ctx: GcPointer<Context>, value: JsValue) -> Result<String, JsValue> {
+    if value.is_number() && value.get_number() == 0.0 && value.get_number().is_sign_negative() {
+        return Ok("-0".to_owned());
+    }
+    if value.is_bigint() {
+        return Ok(format!("{}n", value.get_bigint().value()));
+    }
+    if value.is_symbol() {
+        let symbol = value.get_object().downcast::<JsSymbol>().unwrap();
+        return Ok(format!("Symbol({})", ctx.description(symbol.symbol())));
+    }
+    value.to_string(ctx)
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(ch) if ch.is_alphabetic() || ch == '_' || ch == '$' => (),
+        _ => return false,
+    }
+    chars.all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$')
+}
+
+struct Inspector {
+    ctx: GcPointer<Context>,
+    depth: Option<usize>,
+    /// Objects on the path from the inspected value, used to detect cycles.
+    seen: Vec<GcPointer<JsObject>>,
+}
+
+impl Inspector {
+    fn value(&mut self, value: JsValue, level: usize) -> Result<String, JsValue> {
+        if value.is_string() {
+            return Ok(quote(&value.get_string().as_str()));
+        }
+        if !value.is_jsobject() {
+            return primitive(self.ctx, value);
+        }
+        let mut object = value.get_jsobject();
+        if JsProxy::is_proxy(&object) {
+            match JsProxy::target(&object) {
+                Some(target) => object = target,
+                None => return Ok("<Revoked Proxy>".to_owned()),
+            }
+        }
+        if self.seen.contains(&object) {
+            return Ok("[Circular]".to_owned());
+        }
+        self.seen.push(object);
+        let result = self.object(object, level);
+        self.seen.pop();
+        result
+    }
+
+    fn object(&mut self, mut object: GcPointer<JsObject>, level: usize) -> Result<String, JsValue> {
+        let ctx = self.ctx;
+        let too_deep = self.depth.map_or(false, |depth| level > depth);
+
+        if object.is_callable() {
+            let kind = if object.as_function().is_generator() {
+                "GeneratorFunction"
+            } else {
+                "Function"
+            };
+            let base = match self.own_string(object, S_NAME) {
+                Some(name) if !name.is_empty() => format!("[{}: {}]", kind, name),
+                _ => format!("[{} (anonymous)]", kind),
+            };
+            return self.with_properties(base, object, level, too_deep);
+        }
+        if object.is_class(Date::get_class()) {
+            let base = (**object.data::<Date>()).to_iso_string();
+            return self.with_properties(base, object, level, too_deep);
+        }
+        if object.is_class(RegExp::get_class()) {
+            let regexp = object.data::<RegExp>();
+            let source = if regexp.original_source.is_empty() {
+                "(?:)"
+            } else {
+                &regexp.original_source[..]
+            };
+            let base = format!("/{}/{}", source, regexp.original_flags);
+            return self.with_properties(base, object, level, too_deep);
+        }
+        if is_error(&object) {
+            // errors without a captured stack are bracketed like Node does
+            let base = match self.own_string(object, "stack") {
+                Some(stack) => stack,
+                None => {
+                    let name = object.get(ctx, S_NAME.intern())?.to_string(ctx)?;
+                    let message = object.get(ctx, S_MESSAGE.intern())?.to_string(ctx)?;
+                    if message.is_empty() {
+                        format!("[{}]", name)
+                    } else {
+                        format!("[{}: {}]", name, message)
+                    }
+                }
+            };
+            return self.with_properties(base, object, level, too_deep);
+        }
+
+        let constructor = self.constructor_name(object);
+        let name = constructor.clone().unwrap_or_default();
+        if too_deep {
+            return Ok(match constructor {
+                Some(name) if !name.is_empty() => format!("[{}]", name),
+                _ if object.is_class(JsArray::get_class()) => "[Array]".to_owned(),
+                _ => "[Object]".to_owned(),
+            });
+        }
+        if object.is_class(JsArray::get_class()) {
+            let length = match object.get_own_property(ctx, S_LENGTH.intern()) {
+                Some(desc) if desc.is_data() => desc.value().get_number() as u32,
+                _ => 0,
+            };
+            let open = if name == "Array" {
+                "[".to_owned()
+            } else {
+                format!("{}({}) [", name, length)
+            };
+            let mut entries = self.elements(object, length, level)?;
+            entries.extend(self.properties(object, level, true)?);
+            return Ok(reduce(&open, "]", entries, level));
+        }
+        if JsTypedArray::is_typed_array(&object) {
+            let array = object.data::<JsTypedArray>();
+            let length = array.length();
+            let mut entries = (0..length.min(MAX_ARRAY_LENGTH))
+                .map(|index| primitive(ctx, array.get(index).unwrap()))
+                .collect::<Result<Vec<_>, _>>()?;
+            if length > MAX_ARRAY_LENGTH {
+                entries.push(more_items(length - MAX_ARRAY_LENGTH));
+            }
+            let open = format!("{}({}) [", array.kind().name(), length);
+            return Ok(reduce(&open, "]", entries, level));
+        }
+        if object.is_class(JsArrayBuffer::get_class()) {
+            let buffer = TypedJsObject::<JsArrayBuffer>::new(object);
+            let contents = if !buffer.attached() {
+                "(detached)".to_owned()
+            } else {
+                let bytes = if buffer.size() == 0 {
+                    &[][..]
+                } else {
+                    buffer.data()
+                };
+                let mut hex = bytes
+                    .iter()
+                    .take(MAX_BUFFER_LENGTH)
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if bytes.len() > MAX_BUFFER_LENGTH {
+                    hex.push_str(&format!(
+                        " ... {} more bytes",
+                        bytes.len() - MAX_BUFFER_LENGTH
+                    ));
+                }
+                format!("[Uint8Contents]: <{}>", hex)
+            };
+            let entries = vec![contents, format!("byteLength: {}", buffer.size())];
+            return Ok(reduce("ArrayBuffer {", "}", entries, level));
+        }
+        if object.is_class(JsDataView::get_class()) {
+            let view = object.data::<JsDataView>();
+            let buffer = self.value(JsValue::new(view.get_buffer().object()), level + 1)?;
+            let entries = vec![
+                format!("byteLength: {}", view.byte_length()),
+                format!("byteOffset: {}", view.byte_offset()),
+                format!("buffer: {}", buffer),
+            ];
+            return Ok(reduce("DataView {", "}", entries, level));
+        }
+
+        let open = match constructor {
+            Some(name) if name == "Object" => "{".to_owned(),
+            Some(name) if name.is_empty() => "{".to_owned(),
+            Some(name) => format!("{} {{", name),
+            None => "[Object: null prototype] {".to_owned(),
+        };
+        let entries = self.properties(object, level, false)?;
+        Ok(reduce(&open, "}", entries, level))
+    }
+
+    /// Appends own enumerable properties of objects that have their own formatting, like
+    /// functions with static properties.
+    fn with_properties(
+        &mut self,
+        base: String,
+        object: GcPointer<JsObject>,
+        level: usize,
+        too_deep: bool,
+    ) -> Result<String, JsValue> {
+        if too_deep {
+            return Ok(base);
+        }
+        let entries = self.properties(object, level, false)?;
+        if entries.is_empty() {
+            return Ok(base);
+        }
+        Ok(format!("{} {}", base, reduce("{", "}", entries, level)))
+    }
+
+    /// Array elements with runs of holes collapsed into `<n empty items>`.
+    fn elements(
+        &mut self,
+        mut object: GcPointer<JsObject>,
+        length: u32,
+        level: usize,
+    ) -> Result<Vec<String>, JsValue> {
+        let ctx = self.ctx;
+        let mut entries = vec![];
+        let mut next = 0;
+        for key in operations::own_property_keys(ctx, &mut object)? {
+            let index = match key {
+                Symbol::Index(index) if index < length => index,
+                _ => continue,
+            };
+            if entries.len() >= MAX_ARRAY_LENGTH {
+                break;
+            }
+            if index > next {
+                entries.push(empty_items(index - next));
+            }
+            entries.push(self.property_value(object, key, level)?);
+            next = index + 1;
+        }
+        if next < length {
+            if entries.len() >= MAX_ARRAY_LENGTH {
+                entries.push(more_items((length - next) as usize));
+            } else {
+                entries.push(empty_items(length - next));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Own enumerable properties as `key: value` entries. Index keys are skipped for arrays,
+    /// which print them as elements.
+    fn properties(
+        &mut self,
+        mut object: GcPointer<JsObject>,
+        level: usize,
+        skip_indices: bool,
+    ) -> Result<Vec<String>, JsValue> {
+        let ctx = self.ctx;
+        let mut entries = vec![];
+        for key in operations::own_property_keys(ctx, &mut object)? {
+            if skip_indices && matches!(key, Symbol::Index(_)) {
+                continue;
+            }
+            match object.get_own_property(ctx, key) {
+                Some(desc) if desc.is_enumerable() => (),
+                _ => continue,
+            }
+            let name = match key {
+                Symbol::Index(index) => quote(&index.to_string()),
+                Symbol::Private(_) => format!("[Symbol({})]", ctx.description(key)),
+                Symbol::Key(_) => {
+                    let name = ctx.description(key);
+                    if is_identifier(&name) {
+                        name
+                    } else {
+                        quote(&name)
+                    }
+                }
+            };
+            let value = self.property_value(object, key, level)?;
+            entries.push(format!("{}: {}", name, value));
+        }
+        Ok(entries)
+    }
+
+    fn property_value(
+        &mut self,
+        mut object: GcPointer<JsObject>,
+        key: Symbol,
+        level: usize,
+    ) -> Result<String, JsValue> {
+        let desc = match object.get_own_property(self.ctx, key) {
+            Some(desc) => desc,
+            None => return Ok("undefined".to_owned()),
+        };
+        if desc.is_data() {
+            return self.value(desc.value(), level + 1);
+        }
+        Ok(
+            match (desc.getter().is_undefined(), desc.setter().is_undefined()) {
+                (false, false) => "[Getter/Setter]",
+                (false, true) => "[Getter]",
+                _ => "[Setter]",
+            }
+            .to_owned(),
+        )
+    }
+
+    /// Reads a string valued own data property without running accessors.
+    fn own_string(&self, mut object: GcPointer<JsObject>, name: &str) -> Option<String> {
+        let desc = object.get_own_property(self.ctx, name.intern())?;
+        if desc.is_data() && desc.value().is_string() {
+            Some(desc.value().get_string().as_str().into_owned())
+        } else {
+            None
+        }
+    }
+
+    /// Name of the closest `constructor` on the prototype chain, `None` for null prototypes.
+    fn constructor_name(&self, object: GcPointer<JsObject>) -> Option<String> {
+        let mut proto = Some(*object.prototype()?);
+        while let Some(mut current) = proto {
+            if let Some(desc) = current.get_own_property(self.ctx, S_CONSTURCTOR.intern()) {
+                if desc.is_data() && desc.value().is_callable() {
+                    let name = self.own_string(desc.value().get_jsobject(), S_NAME);
+                    return Some(name.unwrap_or_default());
+                }
+            }
+            proto = current.prototype().copied();
+        }
+        Some(String::new())
+    }
+}
+
+fn empty_items(count: u32) -> String {
+    if count == 1 {
+        "<1 empty item>".to_owned()
+    } else {
+        format!("<{} empty items>", count)
+    }
+}
+
+fn more_items(count: usize) -> String {
+    if count == 1 {
+        "... 1 more item".to_owned()
+    } else {
+        format!("... {} more items", count)
+    }
+}
+
+/// Joins `entries` on one line when they fit, otherwise puts each one on its own line.
+fn reduce(open: &str, close: &str, entries: Vec<String>, level: usize) -> String {
+    if entries.is_empty() {
+        return format!("{}{}", open, close);
+    }
+    let width = level * 2
+        + open.chars().count()
+        + close.chars().count()
+        + entries
+            .iter()
+            .map(|entry| entry.chars().count() + 2)
+            .sum::<usize>();
+    if width <= BREAK_LENGTH && !entries.iter().any(|entry| entry.contains('\n')) {
+        return format!("{} {} {}", open, entries.join(", "), close);
+    }
+    let indent = "  ".repeat(level + 1);
+    format!(
+        "{}\n{}{}\n{}{}",
+        open,
+        indent,
+        entries.join(&format!(",\n{}", indent)),
+        "  ".repeat(level),
+        close
+    )
+}
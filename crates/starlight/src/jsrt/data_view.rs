@@ -271,6 +271,14 @@ impl GcPointer<Context> {
             false,
         )?;
 
+        let tag = JsString::new(self, "DataView");
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+
         self.global_data.data_view_prototype = Some(proto);
         Ok(())
     }
@@ -1,10 +1,10 @@
-use chrono::{prelude::*, Duration, LocalResult};
+use chrono::{prelude::*, Duration};
 use std::{
-    fmt::Display,
     intrinsics::transmute,
     mem::{size_of, ManuallyDrop},
 };
 
+use super::intl::date_time_format::{DateTimeFormatter, Defaults, Required};
 use crate::{
     constant::S_CONSTURCTOR,
     define_jsclass,
@@ -13,6 +13,12 @@ use crate::{
     JsTryFrom,
 };
 
+mod parse;
+mod tz;
+
+pub(crate) use tz::{civil_from_days, days_from_civil, days_in_month, is_leap_year};
+pub use tz::{LocalTimeType, TimeZone};
+
 /// The number of nanoseconds in a millisecond.
 const NANOS_PER_MS: i64 = 1_000_000;
 /// The number of milliseconds in an hour.
@@ -21,6 +27,8 @@ const MILLIS_PER_HOUR: i64 = 3_600_000;
 const MILLIS_PER_MINUTE: i64 = 60_000;
 /// The number of milliseconds in a second.
 const MILLIS_PER_SECOND: i64 = 1000;
+/// The number of milliseconds in a day.
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
 
 #[inline]
 fn is_zero_or_normal_opt(value: Option<f64>) -> bool {
@@ -35,25 +43,54 @@ macro_rules! check_normal_opt {
     };
 }
 
-#[inline]
-fn ignore_ambiguity<T>(result: LocalResult<T>) -> Option<T> {
-    match result {
-        LocalResult::Ambiguous(v, _) => Some(v),
-        LocalResult::Single(v) => Some(v),
-        LocalResult::None => None,
+/// `MakeTime(hour, min, sec, ms)`, milliseconds since the start of a day.
+///
+/// [spec]: https://tc39.es/ecma262/#sec-maketime
+fn make_time(hour: f64, minute: f64, second: f64, millisecond: f64) -> f64 {
+    if !(hour.is_finite() && minute.is_finite() && second.is_finite() && millisecond.is_finite()) {
+        return f64::NAN;
     }
+    hour.trunc() * MILLIS_PER_HOUR as f64
+        + minute.trunc() * MILLIS_PER_MINUTE as f64
+        + second.trunc() * MILLIS_PER_SECOND as f64
+        + millisecond.trunc()
 }
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Date(Option<NaiveDateTime>);
 
-impl Display for Date {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.to_local() {
-            Some(v) => write!(f, "{}", v.format("%a %b %d %Y %H:%M:%S GMT%:z")),
-            _ => write!(f, "Invalid Date"),
-        }
+/// `MakeDay(year, month, date)`, days since the epoch. `month` is zero based and may be out of
+/// range, it then moves the year.
+///
+/// [spec]: https://tc39.es/ecma262/#sec-makeday
+fn make_day(year: f64, month: f64, date: f64) -> f64 {
+    if !(year.is_finite() && month.is_finite() && date.is_finite()) {
+        return f64::NAN;
+    }
+    let year = year.trunc() + (month.trunc() / 12.0).floor();
+    // far outside of the time value range, but keeps the day computation from overflowing
+    if year.abs() > 400_000.0 {
+        return f64::NAN;
+    }
+    let month = month.trunc().rem_euclid(12.0);
+    tz::days_from_civil(year as i64, month as i64 + 1, 1) as f64 + date.trunc() - 1.0
+}
+
+/// `MakeDate(day, time)`
+///
+/// [spec]: https://tc39.es/ecma262/#sec-makedate
+fn make_date(day: f64, time: f64) -> f64 {
+    day * MILLIS_PER_DAY + time
+}
+
+/// Years 0 to 99 passed to `Date` and `Date.UTC` mean 1900 to 1999.
+fn full_year(year: f64) -> f64 {
+    if year.is_finite() && (0.0..100.0).contains(&year.trunc()) {
+        1900.0 + year.trunc()
+    } else {
+        year
     }
 }
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(Option<NaiveDateTime>);
 impl Default for Date {
     fn default() -> Self {
         Self(Some(Utc::now().naive_utc()))
@@ -116,12 +153,53 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.todatestring
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/toDateString
-    pub fn to_date_string(self) -> String {
-        self.to_local()
+    pub fn to_date_string(self, tz: &TimeZone) -> String {
+        self.to_local(tz)
             .map(|date_time| date_time.format("%a %b %d %Y").to_string())
             .unwrap_or_else(|| "Invalid Date".to_string())
     }
 
+    /// `Date.prototype.toString()`
+    ///
+    /// The `toString()` method returns a string representing the specified Date object in local time, for example
+    /// `Sun Oct 18 2026 10:00:00 GMT+0200 (CEST)`.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///  - [MDN documentation][mdn]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.tostring
+    /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/toString
+    pub fn to_date_time_string(self, tz: &TimeZone) -> String {
+        match self.to_local(tz) {
+            Some(date_time) => format!(
+                "{} {}",
+                date_time.format("%a %b %d %Y %H:%M:%S"),
+                self.time_zone_string(tz)
+            ),
+            None => "Invalid Date".to_string(),
+        }
+    }
+
+    /// `TimeZoneString(tv)`, the offset and abbreviation of the local time zone like `GMT+0200 (CEST)`.
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-timezoneestring
+    fn time_zone_string(self, tz: &TimeZone) -> String {
+        let utc = match self.0 {
+            Some(utc) => utc,
+            None => return String::new(),
+        };
+        let local = tz.local_time_type(utc.timestamp());
+        let minutes = local.offset.abs() / 60;
+        format!(
+            "GMT{}{:02}{:02} ({})",
+            if local.offset < 0 { '-' } else { '+' },
+            minutes / 60,
+            minutes % 60,
+            local.abbreviation
+        )
+    }
+
     /// `Date.prototype.toGMTString()`
     ///
     /// The `toGMTString()` method converts a date to a string, using Internet Greenwich Mean Time (GMT) conventions.
@@ -180,10 +258,15 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.totimestring
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/toTimeString
-    pub fn to_time_string(self) -> String {
-        self.to_local()
-            .map(|date_time| date_time.format("%H:%M:%S GMT%:z").to_string())
-            .unwrap_or_else(|| "Invalid Date".to_string())
+    pub fn to_time_string(self, tz: &TimeZone) -> String {
+        match self.to_local(tz) {
+            Some(date_time) => format!(
+                "{} {}",
+                date_time.format("%H:%M:%S"),
+                self.time_zone_string(tz)
+            ),
+            None => "Invalid Date".to_string(),
+        }
     }
 
     /// `Date.prototype.toUTCString()`
@@ -226,8 +309,8 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.getdate
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/getDate
-    pub fn get_date(&self) -> f64 {
-        self.to_local().map_or(f64::NAN, |dt| dt.day() as f64)
+    pub fn get_date(&self, tz: &TimeZone) -> f64 {
+        self.to_local(tz).map_or(f64::NAN, |dt| dt.day() as f64)
     }
 
     /// `Date.prototype.getDay()`
@@ -241,8 +324,8 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.getday
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/getDay
-    pub fn get_day(&self) -> f64 {
-        self.to_local().map_or(f64::NAN, |dt| {
+    pub fn get_day(&self, tz: &TimeZone) -> f64 {
+        self.to_local(tz).map_or(f64::NAN, |dt| {
             let weekday = dt.weekday() as u32;
             let weekday = (weekday + 1) % 7; // 0 represents Monday in Chrono
             weekday as f64
@@ -259,8 +342,8 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.getfullyear
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/getFullYear
-    pub fn get_full_year(&self) -> f64 {
-        self.to_local().map_or(f64::NAN, |dt| dt.year() as f64)
+    pub fn get_full_year(&self, tz: &TimeZone) -> f64 {
+        self.to_local(tz).map_or(f64::NAN, |dt| dt.year() as f64)
     }
 
     /// `Date.prototype.getHours()`
@@ -273,8 +356,8 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.gethours
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/getHours
-    pub fn get_hours(&self) -> f64 {
-        self.to_local().map_or(f64::NAN, |dt| dt.hour() as f64)
+    pub fn get_hours(&self, tz: &TimeZone) -> f64 {
+        self.to_local(tz).map_or(f64::NAN, |dt| dt.hour() as f64)
     }
 
     /// `Date.prototype.getMilliseconds()`
@@ -287,8 +370,8 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.getmilliseconds
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/getMilliseconds
-    pub fn get_milliseconds(&self) -> f64 {
-        self.to_local()
+    pub fn get_milliseconds(&self, tz: &TimeZone) -> f64 {
+        self.to_local(tz)
             .map_or(f64::NAN, |dt| dt.nanosecond() as f64 / NANOS_PER_MS as f64)
     }
 
@@ -302,8 +385,8 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.getminutes
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/getMinutes
-    pub fn get_minutes(&self) -> f64 {
-        self.to_local().map_or(f64::NAN, |dt| dt.minute() as f64)
+    pub fn get_minutes(&self, tz: &TimeZone) -> f64 {
+        self.to_local(tz).map_or(f64::NAN, |dt| dt.minute() as f64)
     }
 
     /// `Date.prototype.getMonth()`
@@ -317,8 +400,8 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.getmonth
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/getMonth
-    pub fn get_month(&self) -> f64 {
-        self.to_local().map_or(f64::NAN, |dt| dt.month0() as f64)
+    pub fn get_month(&self, tz: &TimeZone) -> f64 {
+        self.to_local(tz).map_or(f64::NAN, |dt| dt.month0() as f64)
     }
 
     /// `Date.prototype.getSeconds()`
@@ -331,8 +414,8 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.getseconds
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/getSeconds
-    pub fn get_seconds(&self) -> f64 {
-        self.to_local().map_or(f64::NAN, |dt| dt.second() as f64)
+    pub fn get_seconds(&self, tz: &TimeZone) -> f64 {
+        self.to_local(tz).map_or(f64::NAN, |dt| dt.second() as f64)
     }
 
     /// `Date.prototype.getYear()`
@@ -346,8 +429,8 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.getyear
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/getYear
-    pub fn get_year(&self) -> f64 {
-        self.to_local()
+    pub fn get_year(&self, tz: &TimeZone) -> f64 {
+        self.to_local(tz)
             .map_or(f64::NAN, |dt| dt.year() as f64 - 1900f64)
     }
 
@@ -368,8 +451,8 @@ impl Date {
 
     /// `Date.prototype.getTimeZoneOffset()`
     ///
-    /// The getTimezoneOffset() method returns the time zone difference, in minutes, from current locale (host system
-    /// settings) to UTC.
+    /// The getTimezoneOffset() method returns the time zone difference, in minutes, from local time to UTC at the
+    /// specified date, so it changes across DST transitions.
     ///
     /// More information:
     ///  - [ECMAScript reference][spec]
@@ -377,10 +460,12 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.gettimezoneoffset
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/getTimezoneOffset
-    #[inline]
-    pub fn get_timezone_offset() -> f64 {
-        let offset_seconds = chrono::Local::now().offset().local_minus_utc() as f64;
-        offset_seconds / 60f64
+    pub fn get_timezone_offset(&self, tz: &TimeZone) -> f64 {
+        let time = self.get_time();
+        if time.is_nan() {
+            return f64::NAN;
+        }
+        -tz.offset_at(time) / MILLIS_PER_MINUTE as f64
     }
 
     /// `Date.prototype.getUTCDate()`
@@ -518,12 +603,26 @@ impl Date {
         }
     }
 
-    /// Converts the `Date` to a local `DateTime`.
+    /// Creates a `Date` from a time value, the `Date` is invalid when the value is NaN or out of range.
+    pub fn from_time_value(time: f64) -> Self {
+        let time = match Self::time_clip(time) {
+            Some(time) if time.is_finite() => time.trunc() as i64,
+            _ => return Self(None),
+        };
+        Self(NaiveDateTime::from_timestamp_opt(
+            time.div_euclid(MILLIS_PER_SECOND),
+            (time.rem_euclid(MILLIS_PER_SECOND) * NANOS_PER_MS) as u32,
+        ))
+    }
+
+    /// Converts the `Date` to the date and time in the local time zone `tz`.
     ///
     /// If the `Date` is invalid (i.e. NAN), this function will return `None`.
-    pub fn to_local(self) -> Option<DateTime<Local>> {
-        self.0
-            .map(|utc| Local::now().timezone().from_utc_datetime(&utc))
+    pub fn to_local(self, tz: &TimeZone) -> Option<NaiveDateTime> {
+        self.0.and_then(|utc| {
+            let offset = tz.local_time_type(utc.timestamp()).offset;
+            utc.checked_add_signed(Duration::seconds(offset as i64))
+        })
     }
 
     /// Converts the `Date` to a UTC `DateTime`.
@@ -537,11 +636,12 @@ impl Date {
     /// Optionally sets the individual components of the `Date`.
     ///
     /// Each component does not have to be within the range of valid values. For example, if `month` is too large
-    /// then `year` will be incremented by the required amount.
+    /// then `year` will be incremented by the required amount. The components are in the local time zone `tz`, or
+    /// in UTC when it is `None`.
     #[allow(clippy::too_many_arguments)]
     pub fn set_components(
         &mut self,
-        utc: bool,
+        tz: Option<&TimeZone>,
         year: Option<f64>,
         month: Option<f64>,
         day: Option<f64>,
@@ -617,10 +717,9 @@ impl Date {
             return;
         }
 
-        let naive = if utc {
-            self.to_utc().map(|dt| dt.naive_utc())
-        } else {
-            self.to_local().map(|dt| dt.naive_local())
+        let naive = match tz {
+            None => self.0,
+            Some(tz) => self.to_local(tz),
         };
 
         self.0 = naive.and_then(|naive| {
@@ -648,11 +747,11 @@ impl Date {
 
             NaiveDate::from_ymd_opt(year, month + 1, day + 1)
                 .and_then(|dt| dt.and_hms(0, 0, 0).checked_add_signed(duration))
-                .and_then(|dt| {
-                    if utc {
-                        Some(Utc.from_utc_datetime(&dt).naive_utc())
-                    } else {
-                        ignore_ambiguity(Local.from_local_datetime(&dt)).map(|dt| dt.naive_utc())
+                .and_then(|dt| match tz {
+                    None => Some(dt),
+                    Some(tz) => {
+                        let offset = tz.offset_from_local(dt.timestamp_millis() as f64);
+                        dt.checked_sub_signed(Duration::milliseconds(offset as i64))
                     }
                 })
                 .filter(|dt| Self::time_clip(dt.timestamp_millis() as f64).is_some())
@@ -660,7 +759,8 @@ impl Date {
     }
 
     fn make_date_string(ctx: GcPointer<Context>) -> JsValue {
-        JsValue::new(JsString::new(ctx, Local::now().to_rfc3339()))
+        let string = Date::default().to_date_time_string(&ctx.vm().time_zone);
+        JsValue::new(JsString::new(ctx, string))
     }
     /// `Date()`
     ///
@@ -692,25 +792,19 @@ impl Date {
         object: GcPointer<JsObject>,
         value: JsValue,
     ) -> Result<JsValue, JsValue> {
-        let tv = match this_time_value(value, ctx) {
-            Ok(dt) => dt.0,
+        let date = match this_time_value(value, ctx) {
+            Ok(date) => date,
             _ => {
                 let prim = value.to_primitive(ctx, JsHint::None)?;
                 if prim.is_jsstring() {
-                    match chrono::DateTime::parse_from_rfc3339(&prim.to_string(ctx)?) {
-                        Ok(dt) => Some(dt.naive_utc()),
-                        _ => None,
-                    }
+                    let string = prim.to_string(ctx)?;
+                    let time = parse::parse(&string, &ctx.vm().time_zone);
+                    Date::from_time_value(time.unwrap_or(f64::NAN))
                 } else {
-                    let tv = prim.to_number(ctx)?;
-                    let secs = (tv / 1_000f64) as i64;
-                    let nsecs = ((tv % 1_000f64) * 1_000_000f64) as u32;
-                    NaiveDateTime::from_timestamp_opt(secs, nsecs)
+                    Date::from_time_value(prim.to_number(ctx)?)
                 }
             }
         };
-        let tv = tv.filter(|time| Self::time_clip(time.timestamp_millis() as f64).is_some());
-        let date = Date(tv);
         *object.data::<Date>() = ManuallyDrop::new(date);
         Ok(JsValue::new(object))
     }
@@ -737,36 +831,13 @@ impl Date {
         let milli = args
             .try_at(6)
             .map_or(Ok(0f64), |value| value.to_number(ctx))?;
-        // If any of the args are infinity or NaN, return an invalid date.
-        if !check_normal_opt!(year, month, day, hour, min, sec, milli) {
-            let date = Date(None);
-            *object.data::<Self>() = ManuallyDrop::new(date);
-
-            return Ok(JsValue::new(object));
-        }
-
-        let year = year as i32;
-        let month = month as u32;
-        let day = day as u32;
-        let hour = hour as u32;
-        let min = min as u32;
-        let sec = sec as u32;
-        let milli = milli as u32;
+        let local = make_date(
+            make_day(full_year(year), month, day),
+            make_time(hour, min, sec, milli),
+        );
+        let time = local - ctx.vm().time_zone.offset_from_local(local);
 
-        let year = if (0..=99).contains(&year) {
-            1900 + year
-        } else {
-            year
-        };
-
-        let final_date = NaiveDate::from_ymd_opt(year, month + 1, day)
-            .and_then(|naive_date| naive_date.and_hms_milli_opt(hour, min, sec, milli))
-            .and_then(|local| ignore_ambiguity(Local.from_local_datetime(&local)))
-            .map(|local| local.naive_utc())
-            .filter(|time| Self::time_clip(time.timestamp_millis() as f64).is_some());
-
-        let date = Date(final_date);
-        *object.data::<Self>() = ManuallyDrop::new(date);
+        *object.data::<Self>() = ManuallyDrop::new(Date::from_time_value(time));
         Ok(JsValue::new(object))
     }
 
@@ -781,9 +852,9 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.setdate
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setDate
-    pub fn set_date(&mut self, day: Option<f64>) {
+    pub fn set_date(&mut self, tz: &TimeZone, day: Option<f64>) {
         if let Some(day) = day {
-            self.set_components(false, None, None, Some(day), None, None, None, None)
+            self.set_components(Some(tz), None, None, Some(day), None, None, None, None)
         } else {
             self.0 = None
         }
@@ -800,9 +871,15 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.setfullyear
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setFullYear
-    pub fn set_full_year(&mut self, year: Option<f64>, month: Option<f64>, day: Option<f64>) {
+    pub fn set_full_year(
+        &mut self,
+        tz: &TimeZone,
+        year: Option<f64>,
+        month: Option<f64>,
+        day: Option<f64>,
+    ) {
         if let Some(year) = year {
-            self.set_components(false, Some(year), month, day, None, None, None, None)
+            self.set_components(Some(tz), Some(year), month, day, None, None, None, None)
         } else {
             self.0 = None
         }
@@ -822,6 +899,7 @@ impl Date {
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setHours
     pub fn set_hours(
         &mut self,
+        tz: &TimeZone,
         hour: Option<f64>,
         minute: Option<f64>,
         second: Option<f64>,
@@ -829,7 +907,7 @@ impl Date {
     ) {
         if let Some(hour) = hour {
             self.set_components(
-                false,
+                Some(tz),
                 None,
                 None,
                 None,
@@ -853,9 +931,18 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.setmilliseconds
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setMilliseconds
-    pub fn set_milliseconds(&mut self, millisecond: Option<f64>) {
+    pub fn set_milliseconds(&mut self, tz: &TimeZone, millisecond: Option<f64>) {
         if let Some(millisecond) = millisecond {
-            self.set_components(false, None, None, None, None, None, None, Some(millisecond))
+            self.set_components(
+                Some(tz),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(millisecond),
+            )
         } else {
             self.0 = None
         }
@@ -873,13 +960,14 @@ impl Date {
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setMinutes
     pub fn set_minutes(
         &mut self,
+        tz: &TimeZone,
         minute: Option<f64>,
         second: Option<f64>,
         millisecond: Option<f64>,
     ) {
         if let Some(minute) = minute {
             self.set_components(
-                false,
+                Some(tz),
                 None,
                 None,
                 None,
@@ -903,9 +991,9 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.setmonth
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setMonth
-    pub fn set_month(&mut self, month: Option<f64>, day: Option<f64>) {
+    pub fn set_month(&mut self, tz: &TimeZone, month: Option<f64>, day: Option<f64>) {
         if let Some(month) = month {
-            self.set_components(false, None, Some(month), day, None, None, None, None)
+            self.set_components(Some(tz), None, Some(month), day, None, None, None, None)
         } else {
             self.0 = None
         }
@@ -921,10 +1009,10 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.setseconds
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setSeconds
-    pub fn set_seconds(&mut self, second: Option<f64>, millisecond: Option<f64>) {
+    pub fn set_seconds(&mut self, tz: &TimeZone, second: Option<f64>, millisecond: Option<f64>) {
         if let Some(second) = second {
             self.set_components(
-                false,
+                Some(tz),
                 None,
                 None,
                 None,
@@ -948,14 +1036,20 @@ impl Date {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-date.prototype.setyear
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setYear
-    pub fn set_year(&mut self, year: Option<f64>, month: Option<f64>, day: Option<f64>) {
+    pub fn set_year(
+        &mut self,
+        tz: &TimeZone,
+        year: Option<f64>,
+        month: Option<f64>,
+        day: Option<f64>,
+    ) {
         if let Some(mut year) = year {
             year += if (0f64..100f64).contains(&year) {
                 1900f64
             } else {
                 0f64
             };
-            self.set_components(false, Some(year), month, day, None, None, None, None)
+            self.set_components(Some(tz), Some(year), month, day, None, None, None, None)
         } else {
             self.0 = None
         }
@@ -974,9 +1068,7 @@ impl Date {
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setTime
     pub fn set_time(&mut self, time: Option<f64>) {
         if let Some(time) = time {
-            let secs = (time / 1_000f64) as i64;
-            let nsecs = ((time % 1_000f64) * 1_000_000f64) as u32;
-            self.0 = ignore_ambiguity(Local.timestamp_opt(secs, nsecs)).map(|dt| dt.naive_utc());
+            *self = Self::from_time_value(time);
         } else {
             self.0 = None
         }
@@ -994,7 +1086,7 @@ impl Date {
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setUTCDate
     pub fn set_utc_date(&mut self, day: Option<f64>) {
         if let Some(day) = day {
-            self.set_components(true, None, None, Some(day), None, None, None, None)
+            self.set_components(None, None, None, Some(day), None, None, None, None)
         } else {
             self.0 = None
         }
@@ -1013,7 +1105,7 @@ impl Date {
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setUTCFullYear
     pub fn set_utc_full_year(&mut self, year: Option<f64>, month: Option<f64>, day: Option<f64>) {
         if let Some(year) = year {
-            self.set_components(true, Some(year), month, day, None, None, None, None)
+            self.set_components(None, Some(year), month, day, None, None, None, None)
         } else {
             self.0 = None
         }
@@ -1040,7 +1132,7 @@ impl Date {
     ) {
         if let Some(hour) = hour {
             self.set_components(
-                true,
+                None,
                 None,
                 None,
                 None,
@@ -1066,7 +1158,7 @@ impl Date {
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setUTCMilliseconds
     pub fn set_utc_milliseconds(&mut self, millisecond: Option<f64>) {
         if let Some(millisecond) = millisecond {
-            self.set_components(true, None, None, None, None, None, None, Some(millisecond))
+            self.set_components(None, None, None, None, None, None, None, Some(millisecond))
         } else {
             self.0 = None
         }
@@ -1090,7 +1182,7 @@ impl Date {
     ) {
         if let Some(minute) = minute {
             self.set_components(
-                true,
+                None,
                 None,
                 None,
                 None,
@@ -1116,7 +1208,7 @@ impl Date {
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Date/setUTCMonth
     pub fn set_utc_month(&mut self, month: Option<f64>, day: Option<f64>) {
         if let Some(month) = month {
-            self.set_components(true, None, Some(month), day, None, None, None, None)
+            self.set_components(None, None, Some(month), day, None, None, None, None)
         } else {
             self.0 = None
         }
@@ -1135,7 +1227,7 @@ impl Date {
     pub fn set_utc_seconds(&mut self, second: Option<f64>, millisecond: Option<f64>) {
         if let Some(second) = second {
             self.set_components(
-                true,
+                None,
                 None,
                 None,
                 None,
@@ -1164,7 +1256,7 @@ pub fn date_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsV
         if args.size() == 0 {
             return Ok(Date::make_date_now(ctx, object));
         } else if args.size() == 1 {
-            return Date::make_date_single(ctx, object, args.at(1));
+            return Date::make_date_single(ctx, object, args.at(0));
         } else {
             return Date::make_date_multiple(ctx, object, args);
         }
@@ -1172,13 +1264,9 @@ pub fn date_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsV
 }
 
 pub fn date_parse(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    if args.size() == 0 {
-        return Ok(JsValue::encode_nan_value());
-    }
-    match DateTime::parse_from_rfc3339(&args.at(0).to_string(ctx)?) {
-        Ok(v) => Ok(JsValue::new(v.naive_utc().timestamp_millis() as f64)),
-        _ => Ok(JsValue::encode_nan_value()),
-    }
+    let string = args.at(0).to_string(ctx)?;
+    let time = parse::parse(&string, &ctx.vm().time_zone).unwrap_or(f64::NAN);
+    Ok(JsValue::new(Date::from_time_value(time).get_time()))
 }
 
 pub fn date_utc(context: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
@@ -1187,7 +1275,7 @@ pub fn date_utc(context: GcPointer<Context>, args: &Arguments) -> Result<JsValue
         .map_or(Ok(f64::NAN), |value| value.to_number(context))?;
     let month = args
         .try_at(1)
-        .map_or(Ok(1f64), |value| value.to_number(context))?;
+        .map_or(Ok(0f64), |value| value.to_number(context))?;
     let day = args
         .try_at(2)
         .map_or(Ok(1f64), |value| value.to_number(context))?;
@@ -1204,27 +1292,11 @@ pub fn date_utc(context: GcPointer<Context>, args: &Arguments) -> Result<JsValue
         .try_at(6)
         .map_or(Ok(0f64), |value| value.to_number(context))?;
 
-    if !check_normal_opt!(year, month, day, hour, min, sec, milli) {
-        return Ok(JsValue::encode_nan_value());
-    }
-
-    let year = year as i32;
-    let month = month as u32;
-    let day = day as u32;
-    let hour = hour as u32;
-    let min = min as u32;
-    let sec = sec as u32;
-    let milli = milli as u32;
-
-    let year = if (0..=99).contains(&year) {
-        1900 + year
-    } else {
-        year
-    };
-    NaiveDate::from_ymd_opt(year, month + 1, day)
-        .and_then(|f| f.and_hms_milli_opt(hour, min, sec, milli))
-        .and_then(|f| Date::time_clip(f.timestamp_millis() as f64))
-        .map_or(Ok(JsValue::new(f64::NAN)), |time| Ok(JsValue::new(time)))
+    let time = make_date(
+        make_day(full_year(year), month, day),
+        make_time(hour, min, sec, milli),
+    );
+    Ok(JsValue::new(Date::from_time_value(time).get_time()))
 }
 
 /// The abstract operation `thisTimeValue` takes argument value.
@@ -1252,64 +1324,74 @@ fn this_time_value(value: JsValue, ctx: GcPointer<Context>) -> Result<Date, JsVa
 }
 pub fn date_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     let date = TypedJsObject::<Date>::try_from(ctx, args.this)?;
-    Ok(JsValue::new(JsString::new(ctx, (*date).to_string())))
+    let string = date.to_date_time_string(&ctx.vm().time_zone);
+    Ok(JsValue::new(JsString::new(ctx, string)))
 }
 
 macro_rules! getter_method {
-    ($n: ident $name:ident) => {
+    ($n: ident local $name:ident) => {
         pub fn $n(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
             Ok(JsValue::js_from(
                 ctx,
-                this_time_value(args.this, ctx)?.$name(),
+                this_time_value(args.this, ctx)?.$name(&ctx.vm().time_zone),
             ))
         }
     };
-    ($n : ident Self::$name:ident) => {
-        pub fn $n(_ctx: GcPointer<Context>, _args: &Arguments) -> Result<JsValue, JsValue> {
-            Ok(JsValue::js_from(ctx, Date::$name()))
+    ($n: ident $name:ident) => {
+        pub fn $n(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+            Ok(JsValue::js_from(
+                ctx,
+                this_time_value(args.this, ctx)?.$name(),
+            ))
         }
     };
 }
 
+/// Argument `index` of a setter, `None` when it is missing or not a finite number.
+fn setter_argument(ctx: GcPointer<Context>, args: &Arguments, index: usize) -> Option<f64> {
+    args.try_at(index).and_then(|value| {
+        value.to_numeric_number(ctx).map_or_else(
+            |_| None,
+            |value| {
+                if value == 0f64 || value.is_normal() {
+                    Some(value)
+                } else {
+                    None
+                }
+            },
+        )
+    })
+}
+
 macro_rules! setter_method {
-    ($new_name : ident $name:ident($($e:expr),* $(,)?)) => {
+    ($new_name : ident local $name:ident($($e:expr),* $(,)?)) => {
         pub fn $new_name(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue,JsValue> {
             let mut result = this_time_value(args.this, ctx)?;
-            result.$name(
-                $(
-                    args
-                        .try_at($e)
-                        .and_then(|value| {
-                            value.to_numeric_number(ctx).map_or_else(
-                                |_| None,
-                                |value| {
-                                    if value == 0f64 || value.is_normal() {
-                                        Some(value)
-                                    } else {
-                                        None
-                                    }
-                                },
-                            )
-                        })
-                ),*
-            );
+            result.$name(&ctx.vm().time_zone, $(setter_argument(ctx, args, $e)),*);
 
             *TypedJsObject::<Date>::try_from(ctx,args.this)? = result;
-            //this.set_data(ObjectData::Date(result));
             Ok(JsValue::from(result.get_time()))
         }
+    };
+    ($new_name : ident $name:ident($($e:expr),* $(,)?)) => {
+        pub fn $new_name(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue,JsValue> {
+            let mut result = this_time_value(args.this, ctx)?;
+            result.$name($(setter_argument(ctx, args, $e)),*);
 
+            *TypedJsObject::<Date>::try_from(ctx,args.this)? = result;
+            Ok(JsValue::from(result.get_time()))
+        }
     };
 }
 
-setter_method!(date_set_date set_date(0));
-setter_method!(date_set_full_year set_full_year(0,1,2));
-setter_method!(date_set_hours set_hours(0,1,2,3));
-setter_method!(date_set_milliseconds set_milliseconds(0));
-setter_method!(date_set_minutes set_minutes(0,1,2));
-setter_method!(date_set_month set_month(0,1));
-setter_method!(date_set_seconds set_seconds(0,1));
-setter_method!(date_set_year set_year(0,1,2));
+setter_method!(date_set_date local set_date(0));
+setter_method!(date_set_full_year local set_full_year(0,1,2));
+setter_method!(date_set_hours local set_hours(0,1,2,3));
+setter_method!(date_set_milliseconds local set_milliseconds(0));
+setter_method!(date_set_minutes local set_minutes(0,1,2));
+setter_method!(date_set_month local set_month(0,1));
+setter_method!(date_set_seconds local set_seconds(0,1));
+setter_method!(date_set_year local set_year(0,1,2));
 setter_method!(date_set_time set_time(0));
 setter_method!(date_set_utc_date set_utc_date(0));
 setter_method!(date_set_utc_full_year set_utc_full_year(0,1,2));
@@ -1317,16 +1399,17 @@ setter_method!(date_set_utc_hours set_utc_hours(0,1,2,3));
 setter_method!(date_set_utc_minutes set_utc_minutes(0,1,2));
 setter_method!(date_set_utc_month set_utc_month(0,1));
 setter_method!(date_set_utc_seconds set_utc_seconds(0,1));
-getter_method!(date_get_date get_date);
-getter_method!(date_get_day get_day);
-getter_method!(date_get_full_year get_full_year);
-getter_method!(date_get_hours get_hours);
-getter_method!(date_get_milliseconds get_milliseconds);
-getter_method!(date_get_minutes get_minutes);
-getter_method!(date_get_month get_month);
-getter_method!(date_get_seconds get_seconds);
+getter_method!(date_get_date local get_date);
+getter_method!(date_get_day local get_day);
+getter_method!(date_get_full_year local get_full_year);
+getter_method!(date_get_hours local get_hours);
+getter_method!(date_get_milliseconds local get_milliseconds);
+getter_method!(date_get_minutes local get_minutes);
+getter_method!(date_get_month local get_month);
+getter_method!(date_get_seconds local get_seconds);
 getter_method!(date_get_time get_time);
-getter_method!(date_get_year get_year);
+getter_method!(date_get_timezone_offset local get_timezone_offset);
+getter_method!(date_get_year local get_year);
 getter_method!(date_get_utc_date get_utc_date);
 getter_method!(date_get_utc_day get_utc_day);
 getter_method!(date_get_utc_full_year get_utc_full_year);
@@ -1336,16 +1419,69 @@ getter_method!(date_get_utc_milliseconds get_utc_milliseconds);
 getter_method!(date_get_utc_month get_utc_month);
 getter_method!(date_get_utc_seconds get_utc_seconds);
 getter_method!(date_to_json to_json);
-getter_method!(date_to_time_string to_time_string);
+getter_method!(date_to_time_string local to_time_string);
 getter_method!(date_value_of value_of);
 getter_method!(date_to_gmt_string to_gmt_string);
 getter_method!(date_to_iso_string to_iso_string);
 getter_method!(date_to_utc_string to_utc_string);
-getter_method!(date_to_date_string to_date_string);
+getter_method!(date_to_date_string local to_date_string);
+
+macro_rules! locale_method {
+    ($n: ident $required:ident $defaults:ident) => {
+        pub fn $n(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+            let time = this_time_value(args.this, ctx)?.get_time();
+            if time.is_nan() {
+                return Ok(JsValue::new(JsString::new(ctx, "Invalid Date")));
+            }
+            let formatter = DateTimeFormatter::new(
+                ctx,
+                args.at(0),
+                args.at(1),
+                Required::$required,
+                Defaults::$defaults,
+            )?;
+            Ok(JsValue::new(JsString::new(ctx, formatter.format(time))))
+        }
+    };
+}
+
+locale_method!(date_to_locale_string Any All);
+locale_method!(date_to_locale_date_string Date Date);
+locale_method!(date_to_locale_time_string Time Time);
 
 pub fn date_now(_ctx: GcPointer<Context>, _args: &Arguments) -> Result<JsValue, JsValue> {
     Ok(JsValue::new(Utc::now().timestamp_millis() as f64))
 }
+
+/// `Date.prototype[Symbol.toPrimitive]`, dates convert to strings unless number is requested.
+pub fn date_to_primitive(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if !args.this.is_jsobject() {
+        return Err(JsValue::new(ctx.new_type_error(
+            "Date.prototype[Symbol.toPrimitive] called on non-object",
+        )));
+    }
+    let hint = args.at(0);
+    let hint = if hint.is_jsstring() {
+        match &*hint.get_jsstring().as_str() {
+            "string" | "default" => Some(JsHint::String),
+            "number" => Some(JsHint::Number),
+            _ => None,
+        }
+    } else {
+        None
+    };
+    let hint = match hint {
+        Some(hint) => hint,
+        None => {
+            return Err(JsValue::new(ctx.new_type_error(
+                "Date.prototype[Symbol.toPrimitive]: invalid hint",
+            )))
+        }
+    };
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, args.this.get_jsobject());
+    JsObject::DefaultValueMethod(&mut this, ctx, hint)
+}
 impl GcPointer<Context> {
     pub(crate) fn init_date_in_global_object(mut self) -> Result<(), JsValue> {
         let mut ctx = self;
@@ -1428,6 +1564,7 @@ impl GcPointer<Context> {
             def_native_method!(ctx, proto, getMonth, date_get_month, 0)?;
             def_native_method!(ctx, proto, getSeconds, date_get_seconds, 0)?;
             def_native_method!(ctx, proto, getTime, date_get_time, 0)?;
+            def_native_method!(ctx, proto, getTimezoneOffset, date_get_timezone_offset, 0)?;
             def_native_method!(ctx, proto, getYear, date_get_year, 0)?;
             def_native_method!(ctx, proto, getUTCDate, date_get_utc_date, 0)?;
             def_native_method!(ctx, proto, getUTCDay, date_get_utc_day, 0)?;
@@ -1443,6 +1580,29 @@ impl GcPointer<Context> {
             def_native_method!(ctx, proto, toISOString, date_to_iso_string, 0)?;
             def_native_method!(ctx, proto, toUTCString, date_to_utc_string, 0)?;
             def_native_method!(ctx, proto, toDateString, date_to_date_string, 0)?;
+            def_native_method!(ctx, proto, toLocaleString, date_to_locale_string, 0)?;
+            def_native_method!(
+                ctx,
+                proto,
+                toLocaleDateString,
+                date_to_locale_date_string,
+                0
+            )?;
+            def_native_method!(
+                ctx,
+                proto,
+                toLocaleTimeString,
+                date_to_locale_time_string,
+                0
+            )?;
+            let to_primitive =
+                JsNativeFunction::new(ctx, "[Symbol.toPrimitive]".intern(), date_to_primitive, 1);
+            proto.define_own_property(
+                ctx,
+                "Symbol.toPrimitive".intern().private(),
+                &*DataDescriptor::new(JsValue::new(to_primitive), C),
+                false,
+            )?;
             self.global_data.date_prototype = Some(proto);
 
             Ok(())
@@ -0,0 +1,483 @@
+//! `Date.parse` for the date time string format of ECMAScript and the formats other engines accept:
+//! RFC 2822, the output of `toString` and `toUTCString`, and forms like `10/18/2026` or
+//! `October 18, 2026 10:00 PM`.
+
+use super::tz::{days_from_civil, days_in_month, TimeZone};
+
+/// Parses `text` to a time value in the local time zone `tz`, `None` when no format matches.
+pub(super) fn parse(text: &str, tz: &TimeZone) -> Option<f64> {
+    let text = text.trim();
+    // expanded years only exist in the ISO format, `-000000` must not reach the legacy parser
+    let signed = matches!(text.bytes().next(), Some(b'+') | Some(b'-'));
+    parse_iso(text)
+        .or_else(|| if signed { None } else { parse_legacy(text) })
+        .map(|fields| fields.time_value(tz))
+}
+
+struct Fields {
+    year: i64,
+    /// 1 based.
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    millisecond: i64,
+    /// Minutes east of UTC, local time when `None`.
+    offset: Option<i64>,
+}
+
+impl Fields {
+    fn new(year: i64) -> Self {
+        Self {
+            year,
+            month: 1,
+            day: 1,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            millisecond: 0,
+            offset: None,
+        }
+    }
+
+    fn validate(self) -> Option<Self> {
+        let end_of_day =
+            self.hour == 24 && self.minute == 0 && self.second == 0 && self.millisecond == 0;
+        let valid = (1..=12).contains(&self.month)
+            && self.day >= 1
+            && self.day <= days_in_month(self.year, self.month)
+            && (self.hour < 24 || end_of_day)
+            && self.minute < 60
+            && self.second < 60;
+        if valid {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn time_value(&self, tz: &TimeZone) -> f64 {
+        let days = days_from_civil(self.year, self.month, self.day) as f64;
+        let time = ((self.hour * 60 + self.minute) * 60 + self.second) * 1000 + self.millisecond;
+        let time = days * 86_400_000.0 + time as f64;
+        match self.offset {
+            Some(offset) => time - offset as f64 * 60_000.0,
+            None => time - tz.offset_from_local(time),
+        }
+    }
+}
+
+/// Date Time String Format `YYYY-MM-DDTHH:mm:ss.sssZ` and its shorter forms. Date only forms are
+/// UTC, date time forms without an offset are local time. A space may separate the date and the
+/// time.
+///
+/// [spec]: https://tc39.es/ecma262/#sec-date-time-string-format
+fn parse_iso(text: &str) -> Option<Fields> {
+    let mut scanner = Scanner::new(text);
+    let year = match scanner.peek()? {
+        sign @ b'+' | sign @ b'-' => {
+            scanner.bump();
+            let year = scanner.digits_exact(6)?;
+            // -000000 is not a valid year
+            if sign == b'-' && year == 0 {
+                return None;
+            }
+            if sign == b'-' {
+                -year
+            } else {
+                year
+            }
+        }
+        _ => scanner.digits_exact(4)?,
+    };
+    let mut fields = Fields::new(year);
+    if scanner.eat(b'-') {
+        fields.month = scanner.digits_exact(2)?;
+        if scanner.eat(b'-') {
+            fields.day = scanner.digits_exact(2)?;
+        }
+    }
+    if scanner.eat(b'T') || scanner.eat(b't') || scanner.eat(b' ') {
+        fields.hour = scanner.digits_exact(2)?;
+        scanner.expect(b':')?;
+        fields.minute = scanner.digits_exact(2)?;
+        if scanner.eat(b':') {
+            fields.second = scanner.digits_exact(2)?;
+            if scanner.eat(b'.') || scanner.eat(b',') {
+                fields.millisecond = scanner.fraction()?;
+            }
+        }
+        if scanner.eat(b'Z') || scanner.eat(b'z') {
+            fields.offset = Some(0);
+        } else if let Some(sign @ b'+') | Some(sign @ b'-') = scanner.peek() {
+            scanner.bump();
+            let hours = scanner.digits_exact(2)?;
+            scanner.eat(b':');
+            let minutes = scanner.digits_exact(2)?;
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = hours * 60 + minutes;
+            fields.offset = Some(if sign == b'-' { -offset } else { offset });
+        }
+    } else {
+        fields.offset = Some(0);
+    }
+    if !scanner.is_empty() {
+        return None;
+    }
+    fields.validate()
+}
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// Offsets in minutes of the zone names RFC 2822 allows.
+fn zone_offset(name: &str) -> Option<i64> {
+    Some(match name {
+        "z" | "ut" | "utc" | "gmt" => 0,
+        "edt" => -4 * 60,
+        "est" | "cdt" => -5 * 60,
+        "cst" | "mdt" => -6 * 60,
+        "mst" | "pdt" => -7 * 60,
+        "pst" => -8 * 60,
+        _ => return None,
+    })
+}
+
+/// Two digit years are read as 1950 to 2049, like RFC 2822 obsolete years.
+fn expand_year(year: i64, digits: usize) -> i64 {
+    match digits {
+        1 | 2 if year < 50 => 2000 + year,
+        1 | 2 => 1900 + year,
+        _ => year,
+    }
+}
+
+/// Loosely structured dates such as `Tue, 18 Oct 2026 08:00:00 GMT`,
+/// `Sun Oct 18 2026 10:00:00 GMT+0200 (CEST)`, `10/18/2026 10:00 PM` or `18 October 2026`.
+/// Parenthesized comments and weekday names are ignored, dates without a zone are local time.
+fn parse_legacy(text: &str) -> Option<Fields> {
+    let mut scanner = Scanner::new(text);
+    let mut year = None;
+    let mut month = None;
+    let mut day = None;
+    let mut time = None;
+    let mut pm = None;
+    let mut offset = None;
+    // numbers that are neither part of a time nor of a numeric date, with their digit count
+    let mut numbers = vec![];
+
+    while let Some(c) = scanner.peek() {
+        match c {
+            b'(' => scanner.skip_comment(),
+            b' ' | b'\t' | b'\n' | b'\r' | b',' | b'.' => scanner.bump(),
+            b'0'..=b'9' => {
+                let (value, digits) = scanner.number()?;
+                match scanner.peek() {
+                    Some(b':') => {
+                        if time.is_some() {
+                            return None;
+                        }
+                        scanner.bump();
+                        let (minute, _) = scanner.number()?;
+                        let (mut second, mut millisecond) = (0, 0);
+                        if scanner.eat(b':') {
+                            second = scanner.number()?.0;
+                            if scanner.eat(b'.') {
+                                millisecond = scanner.fraction()?;
+                            }
+                        }
+                        time = Some((value, minute, second, millisecond));
+                    }
+                    Some(separator @ b'/') | Some(separator @ b'-')
+                        if month.is_none()
+                            && time.is_none()
+                            && matches!(scanner.peek_at(1), Some(b'0'..=b'9')) =>
+                    {
+                        scanner.bump();
+                        let (second, _) = scanner.number()?;
+                        scanner.expect(separator)?;
+                        let (third, third_digits) = scanner.number()?;
+                        if digits >= 3 {
+                            year = Some(value);
+                            month = Some(second);
+                            day = Some(third);
+                        } else {
+                            month = Some(value);
+                            day = Some(second);
+                            year = Some(expand_year(third, third_digits));
+                        }
+                    }
+                    _ => numbers.push((value, digits)),
+                }
+            }
+            b'+' | b'-' if time.is_some() || offset.is_some() => {
+                scanner.bump();
+                let (value, digits) = scanner.number()?;
+                let minutes = if scanner.eat(b':') {
+                    value * 60 + scanner.number()?.0
+                } else if digits <= 2 {
+                    value * 60
+                } else {
+                    value / 100 * 60 + value % 100
+                };
+                offset = Some(if c == b'-' { -minutes } else { minutes });
+            }
+            b'-' | b'/' => scanner.bump(),
+            c if c.is_ascii_alphabetic() => {
+                let word = scanner.word().to_ascii_lowercase();
+                if word == "am" || word == "pm" {
+                    pm = Some(word == "pm");
+                } else if let Some(zone) = zone_offset(&word) {
+                    offset = Some(zone);
+                } else if let Some(index) = MONTHS
+                    .iter()
+                    .position(|month| word.len() >= 3 && word.starts_with(month))
+                {
+                    if month.is_some() {
+                        return None;
+                    }
+                    month = Some(index as i64 + 1);
+                } else if word != "t"
+                    && !(word.len() >= 3 && WEEKDAYS.iter().any(|day| word.starts_with(day)))
+                {
+                    // `T` separates the date and the time, weekday names are ignored
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    for (value, digits) in numbers {
+        if digits >= 3 || value > 31 || day.is_some() {
+            if year.is_some() {
+                return None;
+            }
+            year = Some(expand_year(value, digits));
+        } else {
+            day = Some(value);
+        }
+    }
+
+    let mut fields = Fields::new(year?);
+    fields.month = month?;
+    fields.day = day.unwrap_or(1);
+    if let Some((hour, minute, second, millisecond)) = time {
+        fields.hour = match pm {
+            Some(_) if hour == 0 || hour > 12 => return None,
+            Some(pm) => hour % 12 + if pm { 12 } else { 0 },
+            None => hour,
+        };
+        fields.minute = minute;
+        fields.second = second;
+        fields.millisecond = millisecond;
+    } else if pm.is_some() {
+        return None;
+    }
+    fields.offset = offset;
+    fields.validate()
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            bytes: text.as_bytes(),
+            position: 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.bytes.get(self.position + offset).copied()
+    }
+
+    fn bump(&mut self) {
+        self.position += 1;
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: u8) -> Option<()> {
+        if self.eat(c) {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a [u8] {
+        let start = self.position;
+        while self.peek().map_or(false, &f) {
+            self.bump();
+        }
+        &self.bytes[start..self.position]
+    }
+
+    /// Decimal number of at most 9 digits and its digit count.
+    fn number(&mut self) -> Option<(i64, usize)> {
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() || digits.len() > 9 {
+            return None;
+        }
+        let value = digits
+            .iter()
+            .fold(0, |value, &digit| value * 10 + (digit - b'0') as i64);
+        Some((value, digits.len()))
+    }
+
+    fn digits_exact(&mut self, count: usize) -> Option<i64> {
+        match self.number()? {
+            (value, digits) if digits == count => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Fraction of a second in milliseconds, digits past the third are ignored.
+    fn fraction(&mut self) -> Option<i64> {
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() {
+            return None;
+        }
+        Some(
+            digits
+                .iter()
+                .chain([b'0'; 3].iter())
+                .take(3)
+                .fold(0, |value, &digit| value * 10 + (digit - b'0') as i64),
+        )
+    }
+
+    fn word(&mut self) -> String {
+        String::from_utf8_lossy(self.take_while(|c| c.is_ascii_alphabetic())).into_owned()
+    }
+
+    /// Skips a parenthesized comment, comments may nest.
+    fn skip_comment(&mut self) {
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            self.bump();
+            match c {
+                b'(' => depth += 1,
+                b')' if depth == 1 => return,
+                b')' => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two hours ahead of UTC, so local and UTC forms give different time values.
+    fn zone() -> TimeZone {
+        TimeZone::fixed("UTC+2", 7200)
+    }
+
+    #[test]
+    fn test_parse_iso() {
+        let cases = [
+            // date only forms are UTC
+            ("2026-10-18", Some(1_792_281_600_000.0)),
+            ("2026-10", Some(1_790_812_800_000.0)),
+            ("2026", Some(1_767_225_600_000.0)),
+            ("2024-02-29", Some(1_709_164_800_000.0)),
+            // date time forms without an offset are local time
+            ("2026-10-18T10:00", Some(1_792_310_400_000.0)),
+            ("2026-10-18 10:00", Some(1_792_310_400_000.0)),
+            ("2026-10-18T10:00:00.123Z", Some(1_792_317_600_123.0)),
+            ("2026-10-18T10:00:00,5+05:30", Some(1_792_297_800_500.0)),
+            ("2026-10-18T10:00:00+0530", Some(1_792_297_800_000.0)),
+            ("2026-10-18T24:00", Some(1_792_360_800_000.0)),
+            ("+275760-09-13T00:00:00Z", Some(8.64e15)),
+            ("-000001-01-01T00:00:00Z", Some(-62_198_755_200_000.0)),
+            ("2026-02-29", None),
+            ("2026-13-01", None),
+            ("2026-10-18T24:01", None),
+            ("2026-10-18T10:60", None),
+            ("2026-10-18T10", None),
+            ("2026-10-18T10:00Zjunk", None),
+        ];
+        let zone = zone();
+        for (text, expected) in cases.iter() {
+            assert_eq!(parse(text, &zone), *expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_signed_years() {
+        let zone = TimeZone::fixed("UTC", 0);
+        let cases = [
+            ("+002026-10-18", Some(1_792_281_600_000.0)),
+            ("-000001-01-01T00:00:00Z", Some(-62_198_755_200_000.0)),
+            // `-0` is not a valid expanded year, and no legacy format starts with a sign
+            ("-000000-01-01T00:00:00Z", None),
+            ("-000000-01-01", None),
+            ("+2026-10-18", None),
+            ("-10/18/2026", None),
+        ];
+        for (text, expected) in cases.iter() {
+            assert_eq!(parse(text, &zone), *expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_legacy() {
+        let cases = [
+            ("Tue, 18 Oct 2026 08:00:00 GMT", Some(1_792_310_400_000.0)),
+            (
+                "Sun Oct 18 2026 10:00:00 GMT+0200 (CEST)",
+                Some(1_792_310_400_000.0),
+            ),
+            ("Oct 18 2026 10:00 EST", Some(1_792_335_600_000.0)),
+            ("18 Oct 2026 10:00 -0500", Some(1_792_335_600_000.0)),
+            // dates without a zone are local time
+            ("10/18/2026", Some(1_792_274_400_000.0)),
+            ("2026/10/18", Some(1_792_274_400_000.0)),
+            ("18 October 2026", Some(1_792_274_400_000.0)),
+            ("10/18/2026 10:00 PM", Some(1_792_353_600_000.0)),
+            ("October 18, 2026 10:00 PM", Some(1_792_353_600_000.0)),
+            (
+                "October 18, 2026 (a (nested) comment) 10:00 PM",
+                Some(1_792_353_600_000.0),
+            ),
+            ("1/2/99", Some(915_228_000_000.0)),
+            ("13:00 PM Oct 18 2026", None),
+            ("10 PM Oct 18 2026", None),
+            ("Oct 32 2026", None),
+            ("Oct Nov 18 2026", None),
+            ("Foo 18 2026", None),
+            ("18 2026", None),
+            ("", None),
+        ];
+        let zone = zone();
+        for (text, expected) in cases.iter() {
+            assert_eq!(parse(text, &zone), *expected, "{}", text);
+        }
+    }
+}
@@ -0,0 +1,779 @@
+//! Local time zone rules read from compiled tzdata (TZif files, RFC 8536). Instants past the last
+//! transition of a file, and zones given as POSIX TZ strings, follow the POSIX TZ rule.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+const ZONEINFO_DIR: &str = "/usr/share/zoneinfo";
+const LOCALTIME: &str = "/etc/localtime";
+const SECONDS_PER_DAY: i64 = 86_400;
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+/// Days since 1970-01-01 of the proleptic Gregorian date, `month` is 1 based.
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Proleptic Gregorian year, month (1 based) and day of the day `days` since 1970-01-01.
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // the computation above counts months from March
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let (year, month) = if month < 10 {
+        (year_of_era + era * 400, month + 3)
+    } else {
+        (year_of_era + era * 400 + 1, month - 9)
+    };
+    (year, month, day)
+}
+
+pub(crate) fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub(crate) fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Offset from UTC in effect for a period of time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalTimeType {
+    /// Seconds added to UTC to get local time.
+    pub offset: i32,
+    pub is_dst: bool,
+    pub abbreviation: Box<str>,
+}
+
+impl LocalTimeType {
+    fn utc() -> Self {
+        Self {
+            offset: 0,
+            is_dst: false,
+            abbreviation: "UTC".into(),
+        }
+    }
+}
+
+/// Day of the year a POSIX TZ rule switches on.
+#[derive(Debug, Clone, Copy)]
+enum RuleDay {
+    /// `Jn`, 1 based day of the year that never counts February 29.
+    Julian1(i64),
+    /// `n`, 0 based day of the year.
+    Julian0(i64),
+    /// `Mm.w.d`, day `d` (0 is Sunday) of week `w` of month `m`, week 5 is the last one.
+    MonthWeekDay { month: i64, week: i64, day: i64 },
+}
+
+impl RuleDay {
+    /// Days since 1970-01-01 of this day in `year`.
+    fn days(self, year: i64) -> i64 {
+        let january_first = days_from_civil(year, 1, 1);
+        match self {
+            RuleDay::Julian1(day) if is_leap_year(year) && day >= 60 => january_first + day,
+            RuleDay::Julian1(day) => january_first + day - 1,
+            RuleDay::Julian0(day) => january_first + day,
+            RuleDay::MonthWeekDay { month, week, day } => {
+                let first = days_from_civil(year, month, 1);
+                // 1970-01-01 was a Thursday
+                let first_weekday = (first + 4).rem_euclid(7);
+                let mut day_of_month = (day - first_weekday).rem_euclid(7) + (week - 1) * 7;
+                while day_of_month >= days_in_month(year, month) {
+                    day_of_month -= 7;
+                }
+                first + day_of_month
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RuleTransition {
+    day: RuleDay,
+    /// Seconds after local midnight, may be negative or past the end of the day.
+    time: i64,
+}
+
+impl RuleTransition {
+    /// Seconds since the epoch of this transition in `year` when `offset` is in effect before it.
+    fn utc_time(self, year: i64, offset: i32) -> i64 {
+        self.day.days(year) * SECONDS_PER_DAY + self.time - offset as i64
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Rule {
+    Fixed(LocalTimeType),
+    Alternate {
+        std: LocalTimeType,
+        dst: LocalTimeType,
+        start: RuleTransition,
+        end: RuleTransition,
+    },
+}
+
+impl Rule {
+    fn local_time_type(&self, time: i64) -> &LocalTimeType {
+        match self {
+            Rule::Fixed(local) => local,
+            Rule::Alternate {
+                std,
+                dst,
+                start,
+                end,
+            } => {
+                let (year, _, _) =
+                    civil_from_days((time + std.offset as i64).div_euclid(SECONDS_PER_DAY));
+                let start = start.utc_time(year, std.offset);
+                let end = end.utc_time(year, dst.offset);
+                // southern hemisphere zones end DST before starting it again in the same year
+                let is_dst = if start <= end {
+                    start <= time && time < end
+                } else {
+                    !(end <= time && time < start)
+                };
+                if is_dst {
+                    dst
+                } else {
+                    std
+                }
+            }
+        }
+    }
+}
+
+/// Local time zone of a runtime, see [TimeZone::load].
+#[derive(Debug, Clone)]
+pub struct TimeZone {
+    name: Box<str>,
+    /// Seconds since the epoch at which `types[transition_types[i]]` starts.
+    transitions: Vec<i64>,
+    transition_types: Vec<usize>,
+    types: Vec<LocalTimeType>,
+    rule: Option<Rule>,
+}
+
+impl TimeZone {
+    pub fn utc() -> Self {
+        Self {
+            name: "UTC".into(),
+            transitions: vec![],
+            transition_types: vec![],
+            types: vec![LocalTimeType::utc()],
+            rule: None,
+        }
+    }
+
+    /// Loads the zone named by `name` or the `TZ` environment variable, `/etc/localtime` is used
+    /// when neither is set. Names are looked up in `TZDIR` or `/usr/share/zoneinfo`, names which
+    /// are not found there are parsed as POSIX TZ strings. Unknown zones fall back to UTC.
+    pub fn load(name: Option<&str>) -> Self {
+        let name = name
+            .map(str::to_owned)
+            .or_else(|| env::var("TZ").ok())
+            .filter(|name| !name.is_empty());
+        let zone = match name {
+            Some(name) => Self::named(&name),
+            None => {
+                let name = fs::read_link(LOCALTIME)
+                    .ok()
+                    .and_then(|path| {
+                        let path = path.to_str()?;
+                        Some(path.split("zoneinfo/").nth(1)?.to_owned())
+                    })
+                    .unwrap_or_else(|| "localtime".to_owned());
+                fs::read(LOCALTIME)
+                    .ok()
+                    .and_then(|data| Self::from_tzif(&name, &data))
+            }
+        };
+        zone.unwrap_or_else(Self::utc)
+    }
+
+    fn named(name: &str) -> Option<Self> {
+        let name = name.strip_prefix(':').unwrap_or(name);
+        let zone = if Path::new(name).is_absolute() {
+            fs::read(name)
+                .ok()
+                .and_then(|data| Self::from_tzif(name, &data))
+        } else {
+            Self::find(name)
+        };
+        zone.or_else(|| {
+            Some(Self {
+                name: name.into(),
+                transitions: vec![],
+                transition_types: vec![],
+                types: vec![],
+                rule: Some(parse_rule(name)?),
+            })
+        })
+    }
+
+    /// Zone with the IANA name `name` from `TZDIR` or `/usr/share/zoneinfo`.
+    pub fn find(name: &str) -> Option<Self> {
+        let valid = !name.is_empty()
+            && !name.starts_with('/')
+            && !name.split('/').any(|part| part == ".." || part == ".");
+        if !valid {
+            return None;
+        }
+        let dir = env::var("TZDIR").unwrap_or_else(|_| ZONEINFO_DIR.to_owned());
+        let path: PathBuf = Path::new(&dir).join(name);
+        let data = fs::read(path).ok()?;
+        Self::from_tzif(name, &data)
+    }
+
+    /// Zone that is always `offset` seconds ahead of UTC.
+    pub fn fixed(name: &str, offset: i32) -> Self {
+        Self {
+            name: name.into(),
+            transitions: vec![],
+            transition_types: vec![],
+            types: vec![LocalTimeType {
+                offset,
+                is_dst: false,
+                abbreviation: name.into(),
+            }],
+            rule: None,
+        }
+    }
+
+    fn from_tzif(name: &str, data: &[u8]) -> Option<Self> {
+        let mut reader = Reader { data, position: 0 };
+        let mut header = reader.header()?;
+        let mut time_size = 4;
+        if header.version >= 2 {
+            // version 1 data block is followed by a second header and 64-bit data
+            reader.skip(header.data_len(4))?;
+            header = reader.header()?;
+            time_size = 8;
+        }
+
+        let mut transitions = Vec::with_capacity(header.time_count);
+        for _ in 0..header.time_count {
+            transitions.push(reader.int(time_size)?);
+        }
+        let mut transition_types = Vec::with_capacity(header.time_count);
+        for _ in 0..header.time_count {
+            transition_types.push(reader.byte()? as usize);
+        }
+        let mut raw_types = Vec::with_capacity(header.type_count);
+        for _ in 0..header.type_count {
+            let offset = reader.int(4)? as i32;
+            let is_dst = reader.byte()? != 0;
+            let index = reader.byte()? as usize;
+            raw_types.push((offset, is_dst, index));
+        }
+        let chars = reader.bytes(header.char_count)?;
+        let types = raw_types
+            .into_iter()
+            .map(|(offset, is_dst, index)| {
+                let abbreviation = chars.get(index..).unwrap_or(&[]);
+                let end = abbreviation
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(abbreviation.len());
+                LocalTimeType {
+                    offset,
+                    is_dst,
+                    abbreviation: String::from_utf8_lossy(&abbreviation[..end]).into(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if types.is_empty() || transition_types.iter().any(|&index| index >= types.len()) {
+            return None;
+        }
+        reader.skip(
+            header.leap_count * (time_size + 4) + header.std_wall_count + header.ut_local_count,
+        )?;
+        let rule = if header.version >= 2 {
+            reader.footer().and_then(parse_rule)
+        } else {
+            None
+        };
+        Some(Self {
+            name: name.into(),
+            transitions,
+            transition_types,
+            types,
+            rule,
+        })
+    }
+
+    /// IANA name of the zone, or the TZ string it was created from.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Local time type in effect at `time` seconds since the epoch.
+    pub fn local_time_type(&self, time: i64) -> &LocalTimeType {
+        let index = self
+            .transitions
+            .partition_point(|&transition| transition <= time);
+        if index == self.transitions.len() {
+            if let Some(rule) = &self.rule {
+                return rule.local_time_type(time);
+            }
+        }
+        if index == 0 {
+            return &self.types[0];
+        }
+        &self.types[self.transition_types[index - 1]]
+    }
+
+    /// LocalTZA(t, true), milliseconds added to the UTC time value `time` to get local time.
+    pub fn offset_at(&self, time: f64) -> f64 {
+        let seconds = (time / 1000.0).floor() as i64;
+        self.local_time_type(seconds).offset as f64 * 1000.0
+    }
+
+    /// LocalTZA(t, false), milliseconds subtracted from the local time value `time` to get UTC.
+    /// Local times repeated when clocks go back resolve to the earlier instant, local times skipped
+    /// when clocks go forward are interpreted with the offset before the transition.
+    pub fn offset_from_local(&self, time: f64) -> f64 {
+        let before = self.offset_at(time - MILLIS_PER_DAY);
+        let after = self.offset_at(time + MILLIS_PER_DAY);
+        [before, after]
+            .iter()
+            .copied()
+            .filter(|&offset| self.offset_at(time - offset) == offset)
+            .fold(None, |earliest: Option<f64>, offset| {
+                Some(earliest.map_or(offset, |earliest| earliest.max(offset)))
+            })
+            .unwrap_or(before)
+    }
+}
+
+struct Header {
+    version: u8,
+    ut_local_count: usize,
+    std_wall_count: usize,
+    leap_count: usize,
+    time_count: usize,
+    type_count: usize,
+    char_count: usize,
+}
+
+impl Header {
+    /// Length of the data block that follows the header.
+    fn data_len(&self, time_size: usize) -> usize {
+        self.time_count * (time_size + 1)
+            + self.type_count * 6
+            + self.char_count
+            + self.leap_count * (time_size + 4)
+            + self.std_wall_count
+            + self.ut_local_count
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.position..self.position.checked_add(len)?)?;
+        self.position += len;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        self.bytes(1).map(|bytes| bytes[0])
+    }
+
+    /// Big endian two's complement integer of `size` bytes.
+    fn int(&mut self, size: usize) -> Option<i64> {
+        let bytes = self.bytes(size)?;
+        let value = bytes
+            .iter()
+            .fold(0u64, |value, &byte| value << 8 | byte as u64);
+        Some(if size == 4 {
+            value as u32 as i32 as i64
+        } else {
+            value as i64
+        })
+    }
+
+    fn count(&mut self) -> Option<usize> {
+        Some(self.int(4)? as u32 as usize)
+    }
+
+    fn header(&mut self) -> Option<Header> {
+        if self.bytes(4)? != b"TZif" {
+            return None;
+        }
+        let version = match self.byte()? {
+            0 => 1,
+            version @ b'2'..=b'9' => version - b'0',
+            _ => return None,
+        };
+        self.skip(15)?;
+        Some(Header {
+            version,
+            ut_local_count: self.count()?,
+            std_wall_count: self.count()?,
+            leap_count: self.count()?,
+            time_count: self.count()?,
+            type_count: self.count()?,
+            char_count: self.count()?,
+        })
+    }
+
+    /// POSIX TZ string enclosed in the newlines that end files of version 2 and later.
+    fn footer(&mut self) -> Option<&'a str> {
+        let rest = self.data.get(self.position..)?.strip_prefix(b"\n")?;
+        let end = rest.iter().position(|&c| c == b'\n')?;
+        std::str::from_utf8(&rest[..end]).ok()
+    }
+}
+
+/// Parses a POSIX TZ string such as `CET-1CEST,M3.5.0,M10.5.0/3`.
+fn parse_rule(rule: &str) -> Option<Rule> {
+    let mut cursor = Cursor {
+        rest: rule.as_bytes(),
+    };
+    // POSIX offsets are positive west of Greenwich
+    let std = LocalTimeType {
+        abbreviation: cursor.name()?,
+        offset: -cursor.offset()?,
+        is_dst: false,
+    };
+    if cursor.rest.is_empty() {
+        return Some(Rule::Fixed(std));
+    }
+    let abbreviation = cursor.name()?;
+    let offset = match cursor.peek() {
+        Some(b'+') | Some(b'-') | Some(b'0'..=b'9') => -cursor.offset()?,
+        _ => std.offset + 3600,
+    };
+    let dst = LocalTimeType {
+        abbreviation,
+        offset,
+        is_dst: true,
+    };
+    let (start, end) = if cursor.rest.is_empty() {
+        // POSIX leaves the default rule to the implementation, use the current US one
+        (
+            RuleTransition {
+                day: RuleDay::MonthWeekDay {
+                    month: 3,
+                    week: 2,
+                    day: 0,
+                },
+                time: 7200,
+            },
+            RuleTransition {
+                day: RuleDay::MonthWeekDay {
+                    month: 11,
+                    week: 1,
+                    day: 0,
+                },
+                time: 7200,
+            },
+        )
+    } else {
+        cursor.expect(b',')?;
+        let start = cursor.transition()?;
+        cursor.expect(b',')?;
+        let end = cursor.transition()?;
+        (start, end)
+    };
+    if !cursor.rest.is_empty() {
+        return None;
+    }
+    Some(Rule::Alternate {
+        std,
+        dst,
+        start,
+        end,
+    })
+}
+
+struct Cursor<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.rest.first().copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Option<()> {
+        if self.peek()? != c {
+            return None;
+        }
+        self.rest = &self.rest[1..];
+        Some(())
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a [u8] {
+        let len = self
+            .rest
+            .iter()
+            .position(|&c| !f(c))
+            .unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        taken
+    }
+
+    /// Zone abbreviation, alphabetic or quoted in angle brackets like `<+03>`.
+    fn name(&mut self) -> Option<Box<str>> {
+        let name = if self.peek()? == b'<' {
+            self.expect(b'<')?;
+            let name = self.take_while(|c| c != b'>');
+            self.expect(b'>')?;
+            name
+        } else {
+            self.take_while(|c| c.is_ascii_alphabetic())
+        };
+        if name.len() < 3 {
+            return None;
+        }
+        Some(String::from_utf8_lossy(name).into())
+    }
+
+    fn number(&mut self) -> Option<i64> {
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() || digits.len() > 9 {
+            return None;
+        }
+        std::str::from_utf8(digits).ok()?.parse().ok()
+    }
+
+    /// `[+-]hh[:mm[:ss]]` in seconds.
+    fn offset(&mut self) -> Option<i32> {
+        let sign = match self.peek()? {
+            b'-' => {
+                self.expect(b'-')?;
+                -1
+            }
+            b'+' => {
+                self.expect(b'+')?;
+                1
+            }
+            _ => 1,
+        };
+        let mut seconds = self.number()? * 3600;
+        if self.peek() == Some(b':') {
+            self.expect(b':')?;
+            seconds += self.number()? * 60;
+            if self.peek() == Some(b':') {
+                self.expect(b':')?;
+                seconds += self.number()?;
+            }
+        }
+        Some(sign * seconds as i32)
+    }
+
+    /// `date[/time]` of the start or the end of DST.
+    fn transition(&mut self) -> Option<RuleTransition> {
+        let day = match self.peek()? {
+            b'J' => {
+                self.expect(b'J')?;
+                let day = self.number()?;
+                if !(1..=365).contains(&day) {
+                    return None;
+                }
+                RuleDay::Julian1(day)
+            }
+            b'M' => {
+                self.expect(b'M')?;
+                let month = self.number()?;
+                self.expect(b'.')?;
+                let week = self.number()?;
+                self.expect(b'.')?;
+                let day = self.number()?;
+                if !(1..=12).contains(&month) || !(1..=5).contains(&week) || day > 6 {
+                    return None;
+                }
+                RuleDay::MonthWeekDay { month, week, day }
+            }
+            _ => {
+                let day = self.number()?;
+                if day > 365 {
+                    return None;
+                }
+                RuleDay::Julian0(day)
+            }
+        };
+        let time = if self.peek() == Some(b'/') {
+            self.expect(b'/')?;
+            self.offset()? as i64
+        } else {
+            7200
+        };
+        Some(RuleTransition { day, time })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BERLIN: &str = "CET-1CEST,M3.5.0,M10.5.0/3";
+    const SYDNEY: &str = "AEST-10AEDT,M10.1.0,M4.1.0/3";
+
+    /// TZif version 1 data with the given transitions and local time types.
+    fn tzif(transitions: &[(i64, u8)], types: &[(i32, bool, &str)]) -> Vec<u8> {
+        let mut chars = vec![];
+        let mut indices = vec![];
+        for (_, _, abbreviation) in types {
+            indices.push(chars.len() as u8);
+            chars.extend_from_slice(abbreviation.as_bytes());
+            chars.push(0);
+        }
+        let mut data = b"TZif".to_vec();
+        data.extend_from_slice(&[0; 16]);
+        let counts = [0, 0, 0, transitions.len(), types.len(), chars.len()];
+        for count in counts.iter() {
+            data.extend_from_slice(&(*count as u32).to_be_bytes());
+        }
+        for (time, _) in transitions {
+            data.extend_from_slice(&(*time as i32).to_be_bytes());
+        }
+        data.extend(transitions.iter().map(|(_, index)| index));
+        for ((offset, is_dst, _), index) in types.iter().zip(indices) {
+            data.extend_from_slice(&offset.to_be_bytes());
+            data.push(*is_dst as u8);
+            data.push(index);
+        }
+        data.extend_from_slice(&chars);
+        data
+    }
+
+    #[test]
+    fn test_offset_at_dst_transitions() {
+        let cases = [
+            // 2021-03-28T01:00:00Z, clocks go forward in Berlin
+            (BERLIN, 1_616_893_199, 3600),
+            (BERLIN, 1_616_893_200, 7200),
+            // 2021-10-31T01:00:00Z, clocks go back in Berlin
+            (BERLIN, 1_635_641_999, 7200),
+            (BERLIN, 1_635_642_000, 3600),
+            (BERLIN, 1_610_712_000, 3600),
+            (BERLIN, 1_626_350_400, 7200),
+            // 2021-04-03T16:00:00Z, DST ends in Sydney
+            (SYDNEY, 1_617_465_599, 39600),
+            (SYDNEY, 1_617_465_600, 36000),
+            // 2021-10-02T16:00:00Z, DST starts in Sydney
+            (SYDNEY, 1_633_190_399, 36000),
+            (SYDNEY, 1_633_190_400, 39600),
+            (SYDNEY, 1_610_712_000, 39600),
+            (SYDNEY, 1_626_350_400, 36000),
+            ("EST5", 1_626_350_400, -18000),
+            ("<+0530>-5:30", 1_626_350_400, 19800),
+        ];
+        for (rule, time, expected) in cases.iter() {
+            let zone = TimeZone::named(rule).unwrap();
+            assert_eq!(
+                zone.offset_at(*time as f64 * 1000.0),
+                *expected as f64 * 1000.0,
+                "{} at {}",
+                rule,
+                time
+            );
+        }
+    }
+
+    #[test]
+    fn test_offset_from_local_gap_and_overlap() {
+        // Local times written as if they were UTC.
+        let cases = [
+            // 2021-03-28T02:30 does not exist in Berlin, the offset before the gap applies
+            (BERLIN, 1_616_898_600, 3600),
+            // 2021-10-31T02:30 happens twice in Berlin, the earlier instant wins
+            (BERLIN, 1_635_647_400, 7200),
+            (BERLIN, 1_610_712_000, 3600),
+            (BERLIN, 1_626_350_400, 7200),
+            // 2021-10-03T02:30 does not exist in Sydney
+            (SYDNEY, 1_633_228_200, 36000),
+            // 2021-04-04T02:30 happens twice in Sydney
+            (SYDNEY, 1_617_503_400, 39600),
+            ("EST5", 1_626_350_400, -18000),
+        ];
+        for (rule, time, expected) in cases.iter() {
+            let zone = TimeZone::named(rule).unwrap();
+            assert_eq!(
+                zone.offset_from_local(*time as f64 * 1000.0),
+                *expected as f64 * 1000.0,
+                "{} at local {}",
+                rule,
+                time
+            );
+        }
+    }
+
+    #[test]
+    fn test_tzif_transitions() {
+        let data = tzif(
+            &[(1_616_893_200, 1), (1_635_642_000, 0)],
+            &[(3600, false, "CET"), (7200, true, "CEST")],
+        );
+        let zone = TimeZone::from_tzif("Europe/Berlin", &data).unwrap();
+        let cases = [
+            (0, 3600, false, "CET"),
+            (1_616_893_199, 3600, false, "CET"),
+            (1_616_893_200, 7200, true, "CEST"),
+            (1_635_641_999, 7200, true, "CEST"),
+            (1_635_642_000, 3600, false, "CET"),
+            (2_000_000_000, 3600, false, "CET"),
+        ];
+        for (time, offset, is_dst, abbreviation) in cases.iter() {
+            let local = zone.local_time_type(*time);
+            assert_eq!(local.offset, *offset, "offset at {}", time);
+            assert_eq!(local.is_dst, *is_dst, "is_dst at {}", time);
+            assert_eq!(
+                &*local.abbreviation, *abbreviation,
+                "abbreviation at {}",
+                time
+            );
+        }
+        assert!(TimeZone::from_tzif("broken", &data[..data.len() - 1]).is_none());
+        assert!(TimeZone::from_tzif("broken", b"TZif").is_none());
+    }
+
+    #[test]
+    fn test_parse_rule_rejects_malformed() {
+        let cases = [
+            "",
+            "E5",
+            "EST",
+            "EST5EDT,M3.2.0",
+            "EST5EDT,M13.2.0,M11.1.0",
+            "EST5EDT,M3.6.0,M11.1.0",
+            "EST5EDT,J0,J100",
+            "EST5EDT,M3.2.0,M11.1.0 ",
+            "<+03",
+        ];
+        for rule in cases.iter() {
+            assert!(parse_rule(rule).is_none(), "{:?}", rule);
+        }
+    }
+}
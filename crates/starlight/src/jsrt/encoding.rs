@@ -0,0 +1,642 @@
+//! `TextEncoder` and `TextDecoder` of the WHATWG Encoding Standard.
+//!
+//! Only UTF-8, UTF-16LE and windows-1252 are supported. The standard maps `latin1` and
+//! `iso-8859-1` to windows-1252, so those labels decode the same way browsers do.
+use std::{
+    intrinsics::unlikely,
+    mem::{size_of, ManuallyDrop},
+};
+
+use crate::{
+    constant::S_CONSTURCTOR,
+    define_jsclass,
+    prelude::*,
+    vm::{
+        array_buffer::JsArrayBuffer,
+        class::JsClass,
+        context::Context,
+        data_view::JsDataView,
+        object::TypedJsObject,
+        typedarray::{JsTypedArray, TypedArrayKind},
+    },
+    JsTryFrom,
+};
+
+/// Code points of the bytes 0x80 to 0x9F in windows-1252, other bytes are their own code point.
+const WINDOWS_1252: [u16; 32] = [
+    0x20ac, 0x81, 0x201a, 0x192, 0x201e, 0x2026, 0x2020, 0x2021, 0x2c6, 0x2030, 0x160, 0x2039,
+    0x152, 0x8d, 0x17d, 0x8f, 0x90, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014, 0x2dc,
+    0x2122, 0x161, 0x203a, 0x153, 0x9d, 0x17e, 0x178,
+];
+
+const REPLACEMENT_CHARACTER: u16 = 0xfffd;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    Utf16Le,
+    Windows1252,
+}
+
+impl Encoding {
+    /// The get an encoding algorithm, labels are matched case-insensitively after trimming ASCII whitespace.
+    fn from_label(label: &str) -> Option<Self> {
+        let label = label
+            .trim_matches(|c: char| matches!(c, '\t' | '\n' | '\x0c' | '\r' | ' '))
+            .to_ascii_lowercase();
+        Some(match label.as_str() {
+            "unicode-1-1-utf-8" | "unicode11utf8" | "unicode20utf8" | "utf-8" | "utf8"
+            | "x-unicode20utf8" => Encoding::Utf8,
+            "csunicode" | "iso-10646-ucs-2" | "ucs-2" | "unicode" | "unicodefeff" | "utf-16"
+            | "utf-16le" => Encoding::Utf16Le,
+            "ansi_x3.4-1968" | "ascii" | "cp1252" | "cp819" | "csisolatin1" | "ibm819"
+            | "iso-8859-1" | "iso-ir-100" | "iso8859-1" | "iso88591" | "iso_8859-1"
+            | "iso_8859-1:1987" | "l1" | "latin1" | "us-ascii" | "windows-1252" | "x-cp1252" => {
+                Encoding::Windows1252
+            }
+            _ => return None,
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Windows1252 => "windows-1252",
+        }
+    }
+}
+
+/// Error of a fatal decoder.
+struct DecodeError;
+
+/// Decoder state that is kept between streaming `decode` calls.
+struct Decoder {
+    encoding: Encoding,
+    fatal: bool,
+    ignore_bom: bool,
+    bom_seen: bool,
+    /// Bytes of an incomplete sequence at the end of the last chunk.
+    pending: Vec<u8>,
+}
+
+impl Decoder {
+    /// Decodes `input` after the pending bytes. Unless `flush` is set, an incomplete sequence at
+    /// the end is kept for the next call.
+    fn decode(&mut self, input: &[u8], flush: bool) -> Result<Vec<u16>, DecodeError> {
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(input);
+        let mut output = Vec::with_capacity(bytes.len());
+        match self.encoding {
+            Encoding::Utf8 => self.decode_utf8(&bytes, flush, &mut output),
+            Encoding::Utf16Le => self.decode_utf16le(&bytes, flush, &mut output),
+            Encoding::Windows1252 => {
+                output.extend(bytes.iter().map(|&byte| match byte {
+                    0x80..=0x9f => WINDOWS_1252[byte as usize - 0x80],
+                    _ => byte as u16,
+                }));
+                Ok(())
+            }
+        }?;
+        if self.encoding != Encoding::Windows1252 && !self.ignore_bom && !self.bom_seen {
+            if output.first() == Some(&0xfeff) {
+                output.remove(0);
+                self.bom_seen = true;
+            } else if !output.is_empty() {
+                self.bom_seen = true;
+            }
+        }
+        Ok(output)
+    }
+
+    fn error(&self, output: &mut Vec<u16>) -> Result<(), DecodeError> {
+        if self.fatal {
+            return Err(DecodeError);
+        }
+        output.push(REPLACEMENT_CHARACTER);
+        Ok(())
+    }
+
+    fn decode_utf8(
+        &mut self,
+        mut bytes: &[u8],
+        flush: bool,
+        output: &mut Vec<u16>,
+    ) -> Result<(), DecodeError> {
+        // `from_utf8` reports maximal subparts of ill-formed sequences, the errors the standard
+        // replaces one by one.
+        loop {
+            match std::str::from_utf8(bytes) {
+                Ok(valid) => {
+                    output.extend(valid.encode_utf16());
+                    return Ok(());
+                }
+                Err(error) => {
+                    let (valid, rest) = bytes.split_at(error.valid_up_to());
+                    let valid = unsafe { std::str::from_utf8_unchecked(valid) };
+                    output.extend(valid.encode_utf16());
+                    match error.error_len() {
+                        Some(len) => {
+                            self.error(output)?;
+                            bytes = &rest[len..];
+                        }
+                        None if flush => return self.error(output),
+                        None => {
+                            self.pending = rest.to_vec();
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn decode_utf16le(
+        &mut self,
+        bytes: &[u8],
+        flush: bool,
+        output: &mut Vec<u16>,
+    ) -> Result<(), DecodeError> {
+        let mut units = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect::<Vec<_>>();
+        let odd_byte = bytes.len() % 2 == 1;
+        if !flush {
+            // An odd byte or a lead surrogate at the end may be completed by the next chunk.
+            let mut keep_from = bytes.len() - odd_byte as usize;
+            if matches!(units.last(), Some(0xd800..=0xdbff)) {
+                units.pop();
+                keep_from -= 2;
+            }
+            self.pending = bytes[keep_from..].to_vec();
+        }
+        for unit in std::char::decode_utf16(units.iter().copied()) {
+            match unit {
+                Ok(c) => {
+                    let mut buffer = [0; 2];
+                    output.extend_from_slice(c.encode_utf16(&mut buffer));
+                }
+                Err(_) => self.error(output)?,
+            }
+        }
+        if flush && odd_byte {
+            self.error(output)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct TextEncoder;
+
+extern "C" fn encoder_fsz() -> usize {
+    size_of::<TextEncoder>()
+}
+
+extern "C" fn encoder_ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize a TextEncoder");
+}
+
+extern "C" fn encoder_deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize a TextEncoder");
+}
+
+define_jsclass!(
+    TextEncoder,
+    TextEncoder,
+    Object,
+    None,
+    None,
+    Some(encoder_deser),
+    Some(encoder_ser),
+    Some(encoder_fsz)
+);
+
+impl JsClass for TextEncoder {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+pub struct TextDecoder {
+    decoder: Decoder,
+    /// Whether the last `decode` call was streaming, the state is reset after a non-streaming one.
+    do_not_flush: bool,
+}
+
+extern "C" fn decoder_fsz() -> usize {
+    size_of::<TextDecoder>()
+}
+
+extern "C" fn decoder_ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize a TextDecoder");
+}
+
+extern "C" fn decoder_deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize a TextDecoder");
+}
+
+extern "C" fn drop_decoder(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<TextDecoder>()) }
+}
+
+define_jsclass!(
+    TextDecoder,
+    TextDecoder,
+    Object,
+    Some(drop_decoder),
+    None,
+    Some(decoder_deser),
+    Some(decoder_ser),
+    Some(decoder_fsz)
+);
+
+impl JsClass for TextDecoder {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+fn type_error(ctx: GcPointer<Context>, message: impl AsRef<str>) -> JsValue {
+    JsValue::new(ctx.new_type_error(message))
+}
+
+/// Allocates an object of `T` that inherits from new.target's prototype.
+fn new_object<T: JsClass>(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    name: &str,
+    data: T,
+) -> Result<JsValue, JsValue> {
+    if unlikely(!args.ctor_call) {
+        return Err(type_error(
+            ctx,
+            format!("{} constructor requires 'new'", name),
+        ));
+    }
+    let proto = if args.this.is_jsobject() {
+        args.this.get_jsobject().prototype().copied()
+    } else {
+        None
+    };
+    let stack = ctx.shadowstack();
+    letroot!(structure = stack, Structure::new_indexed(ctx, proto, false));
+    let object = JsObject::new(ctx, &structure, T::class(), ObjectTag::Ordinary);
+    *object.data::<T>() = ManuallyDrop::new(data);
+    Ok(JsValue::new(object))
+}
+
+/// Copy of the bytes viewed by an `ArrayBuffer`, a typed array or a `DataView`.
+fn buffer_source_bytes(ctx: GcPointer<Context>, value: JsValue) -> Result<Vec<u8>, JsValue> {
+    let error = || {
+        type_error(
+            ctx,
+            "The provided value is not of type '(ArrayBuffer or ArrayBufferView)'",
+        )
+    };
+    if !value.is_jsobject() {
+        return Err(error());
+    }
+    let object = value.get_jsobject();
+    let (buffer, offset, length) = if object.is_class(JsArrayBuffer::get_class()) {
+        let buffer = TypedJsObject::<JsArrayBuffer>::new(object);
+        (buffer, 0, buffer.byte_length())
+    } else if JsTypedArray::is_typed_array(&object) {
+        let array = object.data::<JsTypedArray>();
+        (array.buffer(), array.byte_offset(), array.byte_length())
+    } else if object.is_class(JsDataView::get_class()) {
+        let view = object.data::<JsDataView>();
+        (view.get_buffer(), view.byte_offset(), view.byte_length())
+    } else {
+        return Err(error());
+    };
+    if !buffer.attached() || length == 0 {
+        return Ok(vec![]);
+    }
+    Ok(buffer.data()[offset..offset + length].to_vec())
+}
+
+/// The `Uint8Array` that `encodeInto` writes to.
+fn uint8_array_destination(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    if value.is_jsobject() {
+        let object = value.get_jsobject();
+        if TypedArrayKind::from_tag(object.tag) == Some(TypedArrayKind::Uint8) {
+            return Ok(object);
+        }
+    }
+    Err(type_error(
+        ctx,
+        "TextEncoder.prototype.encodeInto: destination is not a Uint8Array",
+    ))
+}
+
+pub fn text_encoder_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    new_object(ctx, args, "TextEncoder", TextEncoder)
+}
+
+pub fn text_encoder_encoding(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    TypedJsObject::<TextEncoder>::try_from(ctx, args.this)?;
+    Ok(JsValue::new(JsString::new(ctx, "utf-8")))
+}
+
+pub fn text_encoder_encode(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    TypedJsObject::<TextEncoder>::try_from(ctx, args.this)?;
+    let input = if args.at(0).is_undefined() {
+        String::new()
+    } else {
+        args.at(0).to_string(ctx)?
+    };
+    let array = JsTypedArray::with_length(ctx, TypedArrayKind::Uint8, input.len())?;
+    if !input.is_empty() {
+        array
+            .data::<JsTypedArray>()
+            .buffer()
+            .data_mut()
+            .copy_from_slice(input.as_bytes());
+    }
+    Ok(JsValue::new(array))
+}
+
+pub fn text_encoder_encode_into(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    TypedJsObject::<TextEncoder>::try_from(ctx, args.this)?;
+    let source = args.at(0).to_jsstring(ctx)?;
+    let destination = uint8_array_destination(ctx, args.at(1))?;
+    let destination = destination.data::<JsTypedArray>();
+    let available = destination.byte_length();
+    let mut read = 0;
+    let mut written = 0;
+    if available != 0 {
+        let offset = destination.byte_offset();
+        let mut buffer = destination.buffer();
+        let bytes = &mut buffer.data_mut()[offset..offset + available];
+        for c in std::char::decode_utf16(source.to_utf16().iter().copied()) {
+            let (c, units) = match c {
+                Ok(c) => (c, c.len_utf16()),
+                Err(_) => (std::char::REPLACEMENT_CHARACTER, 1),
+            };
+            if written + c.len_utf8() > available {
+                break;
+            }
+            c.encode_utf8(&mut bytes[written..]);
+            written += c.len_utf8();
+            read += units;
+        }
+    }
+    let stack = ctx.shadowstack();
+    letroot!(result = stack, JsObject::new_empty(ctx));
+    result.put(ctx, "read".intern(), JsValue::new(read as u32), false)?;
+    result.put(ctx, "written".intern(), JsValue::new(written as u32), false)?;
+    Ok(JsValue::new(*result))
+}
+
+pub fn text_decoder_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let label = if args.at(0).is_undefined() {
+        "utf-8".to_owned()
+    } else {
+        args.at(0).to_string(ctx)?
+    };
+    let mut fatal = false;
+    let mut ignore_bom = false;
+    let options = args.at(1);
+    if options.is_jsobject() {
+        let mut options = options.get_jsobject();
+        fatal = options.get(ctx, "fatal".intern())?.to_boolean();
+        ignore_bom = options.get(ctx, "ignoreBOM".intern())?.to_boolean();
+    } else if unlikely(!options.is_undefined() && !options.is_null()) {
+        return Err(type_error(ctx, "TextDecoder: options must be an object"));
+    }
+    let encoding = match Encoding::from_label(&label) {
+        Some(encoding) => encoding,
+        None => {
+            return Err(JsValue::new(ctx.new_range_error(format!(
+                "TextDecoder: the encoding label '{}' is not supported",
+                label
+            ))))
+        }
+    };
+    let decoder = Decoder {
+        encoding,
+        fatal,
+        ignore_bom,
+        bom_seen: false,
+        pending: vec![],
+    };
+    new_object(
+        ctx,
+        args,
+        "TextDecoder",
+        TextDecoder {
+            decoder,
+            do_not_flush: false,
+        },
+    )
+}
+
+pub fn text_decoder_encoding(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let decoder = TypedJsObject::<TextDecoder>::try_from(ctx, args.this)?;
+    let name = decoder.decoder.encoding.name();
+    Ok(JsValue::new(JsString::new(ctx, name)))
+}
+
+pub fn text_decoder_fatal(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let decoder = TypedJsObject::<TextDecoder>::try_from(ctx, args.this)?;
+    Ok(JsValue::new(decoder.decoder.fatal))
+}
+
+pub fn text_decoder_ignore_bom(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let decoder = TypedJsObject::<TextDecoder>::try_from(ctx, args.this)?;
+    Ok(JsValue::new(decoder.decoder.ignore_bom))
+}
+
+pub fn text_decoder_decode(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut decoder = TypedJsObject::<TextDecoder>::try_from(ctx, args.this)?;
+    let input = if args.at(0).is_undefined() {
+        vec![]
+    } else {
+        buffer_source_bytes(ctx, args.at(0))?
+    };
+    let options = args.at(1);
+    let stream = if options.is_jsobject() {
+        options
+            .get_jsobject()
+            .get(ctx, "stream".intern())?
+            .to_boolean()
+    } else {
+        false
+    };
+    // A non-streaming call ends the stream, the next call starts a new one.
+    if !decoder.do_not_flush {
+        decoder.decoder.pending.clear();
+        decoder.decoder.bom_seen = false;
+    }
+    decoder.do_not_flush = stream;
+    match decoder.decoder.decode(&input, !stream) {
+        Ok(units) => Ok(JsValue::new(JsString::from_utf16(ctx, &units))),
+        Err(DecodeError) => {
+            decoder.do_not_flush = false;
+            Err(type_error(
+                ctx,
+                format!(
+                    "TextDecoder.prototype.decode: the encoded data was not valid {}",
+                    decoder.decoder.encoding.name()
+                ),
+            ))
+        }
+    }
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_encoding_in_global_object(mut self) -> Result<(), JsValue> {
+        let mut global_object = self.global_object();
+        let ctor = self
+            .global_data
+            .text_encoder_prototype
+            .unwrap()
+            .get(self, S_CONSTURCTOR.intern())?;
+        def_native_property!(self, global_object, TextEncoder, ctor, W | C)?;
+        let ctor = self
+            .global_data
+            .text_decoder_prototype
+            .unwrap()
+            .get(self, S_CONSTURCTOR.intern())?;
+        def_native_property!(self, global_object, TextDecoder, ctor, W | C)?;
+        Ok(())
+    }
+
+    pub(crate) fn init_encoding_in_global_data(mut self) -> Result<(), JsValue> {
+        let mut proto = self.new_encoding_class("TextEncoder", text_encoder_constructor)?;
+        def_native_accessor!(self, proto, encoding, text_encoder_encoding, E | C)?;
+        def_native_method!(self, proto, encode, text_encoder_encode, 0, W | E | C)?;
+        def_native_method!(
+            self,
+            proto,
+            encodeInto,
+            text_encoder_encode_into,
+            2,
+            W | E | C
+        )?;
+        self.global_data.text_encoder_prototype = Some(proto);
+
+        let mut proto = self.new_encoding_class("TextDecoder", text_decoder_constructor)?;
+        def_native_accessor!(self, proto, encoding, text_decoder_encoding, E | C)?;
+        def_native_accessor!(self, proto, fatal, text_decoder_fatal, E | C)?;
+        def_native_accessor!(self, proto, ignoreBOM, text_decoder_ignore_bom, E | C)?;
+        def_native_method!(self, proto, decode, text_decoder_decode, 0, W | E | C)?;
+        self.global_data.text_decoder_prototype = Some(proto);
+        Ok(())
+    }
+
+    /// Prototype object of `TextEncoder` or `TextDecoder`, with the constructor.
+    fn new_encoding_class(
+        self,
+        name: &str,
+        constructor: JsAPI,
+    ) -> Result<GcPointer<JsObject>, JsValue> {
+        let obj_proto = self.global_data().get_object_prototype();
+        let structure = Structure::new_unique_indexed(self, Some(obj_proto), false);
+        let mut proto = JsObject::new(self, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+        let mut ctor = JsNativeFunction::new(self, name.intern(), constructor, 0);
+        def_native_property!(self, ctor, prototype, proto, NONE)?;
+        def_native_property!(self, proto, constructor, ctor, W | C)?;
+        let tag = JsString::new(self, name);
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+        Ok(proto)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_text_encoder() {
+        assert_eval_cases(&[
+            (
+                "var encoder = new TextEncoder(); \
+                 function bytes(a) { var s = []; for (var i = 0; i < a.length; i++) s.push(a[i]); return s.join(); } \
+                 bytes(encoder.encode('a\\u00e9\\u20ac\\ud83d\\ude00'))",
+                "97,195,169,226,130,172,240,159,152,128",
+            ),
+            ("bytes(encoder.encode('\\ud800x\\udc00'))", "239,191,189,120,239,191,189"),
+            ("encoder.encode().length", "0"),
+            (
+                "var dest = new Uint8Array(5); var r = encoder.encodeInto('a\\ud83d\\ude00b', dest); \
+                 r.read + ':' + r.written + ':' + bytes(dest)",
+                "3:5:97,240,159,152,128",
+            ),
+            (
+                "r = encoder.encodeInto('\\u00e9\\u00e9', new Uint8Array(3)); r.read + ':' + r.written",
+                "1:2",
+            ),
+            (
+                "try { encoder.encodeInto('a', new Uint16Array(1)) } catch (e) { e.name }",
+                "TypeError",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_text_decoder() {
+        assert_eval_cases(&[
+            (
+                "var d = new TextDecoder(); d.decode(new Uint8Array([0xef, 0xbb, 0xbf, 0x68, 0x69]))",
+                "hi",
+            ),
+            (
+                "new TextDecoder('utf-8', { ignoreBOM: true }).decode(new Uint8Array([0xef, 0xbb, 0xbf])).charCodeAt(0)",
+                "65279",
+            ),
+            (
+                "d.decode(new Uint8Array([0x61, 0xff, 0xe2, 0x82])).split('').map(function (c) { return c.charCodeAt(0); }).join()",
+                "97,65533,65533",
+            ),
+            (
+                "try { new TextDecoder('utf-8', { fatal: true }).decode(new Uint8Array([0xc3])) } catch (e) { e.name }",
+                "TypeError",
+            ),
+            (
+                "d.decode(new Uint8Array([0xe2, 0x82]), { stream: true }) + '|' + d.decode(new Uint8Array([0xac]))",
+                "|\u{20ac}",
+            ),
+            (
+                "var u = new TextDecoder('utf-16le'); \
+                 u.decode(new Uint8Array([0x3d, 0xd8]), { stream: true }) + u.decode(new Uint8Array([0x00, 0xde])) === '\\ud83d\\ude00'",
+                "true",
+            ),
+            (
+                "new TextDecoder(' Latin1 ').encoding + ',' + new TextDecoder('latin1').decode(new Uint8Array([0x80, 0xe9]))",
+                "windows-1252,\u{20ac}\u{e9}",
+            ),
+            (
+                "try { new TextDecoder('utf-7') } catch (e) { e.name }",
+                "RangeError",
+            ),
+        ]);
+    }
+}
@@ -4,81 +4,114 @@
 use crate::{
     gc::cell::GcPointer,
     vm::{
-        arguments::Arguments, context::Context, error::JsTypeError, error::*, object::JsObject,
-        slot::*, string::JsString, symbol_table::*, value::JsValue,
+        arguments::Arguments, array::JsArray, attributes::*, context::Context, error::JsTypeError,
+        error::*, interpreter::SpreadValue, object::JsObject, property_descriptor::DataDescriptor,
+        slot::*, string::JsString, structure::Structure, symbol_table::*, value::JsValue,
     },
 };
 
-pub fn error_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let message = args.at(0).to_string(ctx)?;
+type ErrorAllocator = fn(
+    GcPointer<Context>,
+    GcPointer<JsString>,
+    Option<GcPointer<Structure>>,
+) -> GcPointer<JsObject>;
+
+/// Creates error object with `new` and installs `message` and `options.cause` on it.
+fn construct_error(
+    ctx: GcPointer<Context>,
+    message: JsValue,
+    options: JsValue,
+    new: ErrorAllocator,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    let message = if message.is_undefined() {
+        String::new()
+    } else {
+        message.to_string(ctx)?
+    };
     let msg = JsString::new(ctx, message);
-    Ok(JsValue::encode_object_value(JsError::new(ctx, msg, None)))
+    let stack = ctx.shadowstack();
+    letroot!(error = stack, new(ctx, msg, None));
+    // InstallErrorCause
+    if options.is_jsobject() {
+        letroot!(options = stack, options.get_jsobject());
+        if options.has_property(ctx, "cause".intern()) {
+            let cause = options.get(ctx, "cause".intern())?;
+            error.define_own_property(
+                ctx,
+                "cause".intern(),
+                &*DataDescriptor::new(cause, W | C),
+                false,
+            )?;
+        }
+    }
+    Ok(*error)
+}
+
+pub fn error_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    construct_error(ctx, args.at(0), args.at(1), JsError::new).map(JsValue::new)
 }
 
 pub fn eval_error_constructor(
     ctx: GcPointer<Context>,
     args: &Arguments,
 ) -> Result<JsValue, JsValue> {
-    let message = args.at(0).to_string(ctx)?;
-    let msg = JsString::new(ctx, message);
-    Ok(JsValue::encode_object_value(JsEvalError::new(
-        ctx, msg, None,
-    )))
+    construct_error(ctx, args.at(0), args.at(1), JsEvalError::new).map(JsValue::new)
 }
 
 pub fn reference_error_constructor(
     ctx: GcPointer<Context>,
     args: &Arguments,
 ) -> Result<JsValue, JsValue> {
-    let message = args.at(0).to_string(ctx)?;
-    let msg = JsString::new(ctx, message);
-    Ok(JsValue::encode_object_value(JsReferenceError::new(
-        ctx, msg, None,
-    )))
+    construct_error(ctx, args.at(0), args.at(1), JsReferenceError::new).map(JsValue::new)
 }
 
 pub fn type_error_constructor(
     ctx: GcPointer<Context>,
     args: &Arguments,
 ) -> Result<JsValue, JsValue> {
-    let message = args.at(0).to_string(ctx)?;
-    let msg = JsString::new(ctx, message);
-    Ok(JsValue::encode_object_value(JsTypeError::new(
-        ctx, msg, None,
-    )))
+    construct_error(ctx, args.at(0), args.at(1), JsTypeError::new).map(JsValue::new)
 }
 
 pub fn syntax_error_constructor(
     ctx: GcPointer<Context>,
     args: &Arguments,
 ) -> Result<JsValue, JsValue> {
-    let message = args.at(0).to_string(ctx)?;
-    let msg = JsString::new(ctx, message);
-    Ok(JsValue::encode_object_value(JsEvalError::new(
-        ctx, msg, None,
-    )))
+    construct_error(ctx, args.at(0), args.at(1), JsSyntaxError::new).map(JsValue::new)
 }
 
 pub fn range_error_constructor(
     ctx: GcPointer<Context>,
     args: &Arguments,
 ) -> Result<JsValue, JsValue> {
-    let message = args.at(0).to_string(ctx)?;
-    let msg = JsString::new(ctx, message);
-    Ok(JsValue::encode_object_value(JsRangeError::new(
-        ctx, msg, None,
-    )))
+    construct_error(ctx, args.at(0), args.at(1), JsRangeError::new).map(JsValue::new)
 }
 
 pub fn uri_error_constructor(
     ctx: GcPointer<Context>,
     args: &Arguments,
 ) -> Result<JsValue, JsValue> {
-    let message = args.at(0).to_string(ctx)?;
-    let msg = JsString::new(ctx, message);
-    Ok(JsValue::encode_object_value(JsURIError::new(
-        ctx, msg, None,
-    )))
+    construct_error(ctx, args.at(0), args.at(1), JsURIError::new).map(JsValue::new)
+}
+
+/// AggregateError ( errors, message [ , options ] )
+pub fn aggregate_error_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        error = stack,
+        construct_error(ctx, args.at(1), args.at(2), JsAggregateError::new)?
+    );
+    letroot!(errors = stack, SpreadValue::new(ctx, args.at(0))?);
+    let errors = JsArray::from_slice(ctx, &errors.array);
+    error.define_own_property(
+        ctx,
+        "errors".intern(),
+        &*DataDescriptor::new(JsValue::new(errors), W | C),
+        false,
+    )?;
+    Ok(JsValue::new(*error))
 }
 
 /// section 15.11.4.4 Error.prototype.toString()
@@ -127,3 +160,240 @@ pub fn error_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsVa
         )));
     }
 }
+
+/// Error.captureStackTrace(targetObject[, constructorOpt])
+pub fn error_capture_stack_trace(
+    mut ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    if !args.at(0).is_jsobject() {
+        return Err(JsValue::new(ctx.new_type_error(
+            "Error.captureStackTrace requires object argument",
+        )));
+    }
+    let limit = match stack_trace_limit(ctx) {
+        Some(limit) => limit,
+        None => return Ok(JsValue::encode_undefined_value()),
+    };
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.at(0).get_jsobject());
+    let until = Some(args.at(1)).filter(|until| until.is_callable());
+    let sites = ctx.call_sites(until, limit);
+    let mut buf = [];
+    letroot!(
+        args = stack,
+        Arguments::new(JsValue::new(*object), &mut buf)
+    );
+    let header = error_to_string(ctx, &args)?.to_string(ctx)?;
+    let trace = format_stack_trace(ctx, *object, &header, sites)?;
+    object.define_own_property(
+        ctx,
+        "stack".intern(),
+        &*DataDescriptor::new(trace, W | C),
+        false,
+    )?;
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// Returns `this` of CallSite methods, it must be an object created by [CallSite::into_object].
+fn this_call_site(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    let this = args.this;
+    if this.is_jsobject() && this.get_jsobject().is_class(CallSite::get_class()) {
+        return Ok(this.get_jsobject());
+    }
+    Err(JsValue::new(ctx.new_type_error(
+        "CallSite method called on incompatible receiver",
+    )))
+}
+
+fn optional_string(ctx: GcPointer<Context>, string: &Option<String>) -> JsValue {
+    match string {
+        Some(string) => JsValue::new(JsString::new(ctx, string)),
+        None => JsValue::encode_null_value(),
+    }
+}
+
+pub fn call_site_get_this(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let object = this_call_site(ctx, args)?;
+    let site = object.data::<CallSite>();
+    if site.this.is_empty() {
+        return Ok(JsValue::encode_undefined_value());
+    }
+    Ok(site.this)
+}
+
+pub fn call_site_get_type_name(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let object = this_call_site(ctx, args)?;
+    let site = object.data::<CallSite>();
+    if !site.this.is_jsobject() {
+        return Ok(JsValue::encode_null_value());
+    }
+    let name = site.this.get_jsobject().class().name;
+    Ok(JsValue::new(JsString::new(ctx, name)))
+}
+
+pub fn call_site_get_function(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let object = this_call_site(ctx, args)?;
+    Ok(object.data::<CallSite>().function)
+}
+
+pub fn call_site_get_function_name(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let object = this_call_site(ctx, args)?;
+    let site = object.data::<CallSite>();
+    Ok(optional_string(ctx, &site.function_name))
+}
+
+pub fn call_site_get_file_name(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let object = this_call_site(ctx, args)?;
+    let site = object.data::<CallSite>();
+    if site.file_name.is_none() && !site.native {
+        return Ok(JsValue::encode_undefined_value());
+    }
+    Ok(optional_string(ctx, &site.file_name))
+}
+
+pub fn call_site_get_line_number(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let object = this_call_site(ctx, args)?;
+    let site = object.data::<CallSite>();
+    Ok(match site.location {
+        Some((line, _)) => JsValue::new(line),
+        None => JsValue::encode_null_value(),
+    })
+}
+
+pub fn call_site_get_column_number(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let object = this_call_site(ctx, args)?;
+    let site = object.data::<CallSite>();
+    Ok(match site.location {
+        Some((_, column)) => JsValue::new(column),
+        None => JsValue::encode_null_value(),
+    })
+}
+
+pub fn call_site_is_toplevel(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let object = this_call_site(ctx, args)?;
+    let site = object.data::<CallSite>();
+    Ok(JsValue::new(site.top_level))
+}
+
+pub fn call_site_is_eval(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    this_call_site(ctx, args)?;
+    Ok(JsValue::new(false))
+}
+
+pub fn call_site_is_native(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let object = this_call_site(ctx, args)?;
+    let site = object.data::<CallSite>();
+    Ok(JsValue::new(site.native))
+}
+
+pub fn call_site_is_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let object = this_call_site(ctx, args)?;
+    let site = object.data::<CallSite>();
+    Ok(JsValue::new(site.constructor))
+}
+
+pub fn call_site_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let object = this_call_site(ctx, args)?;
+    let site = object.data::<CallSite>();
+    Ok(JsValue::new(JsString::new(ctx, site.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_stack_location_of_failed_instruction() {
+        let frame = |body: &str| {
+            format!(
+                "function g() {{}}\nfunction f() {{\n  g();\n  {}\n}}\n\
+                 try {{ f(); }} catch (e) {{ /^    at f \\(<anonymous>:4:\\d+\\)$/.test(e.stack.split('\\n')[1]); }}",
+                body
+            )
+        };
+        let scripts = [
+            frame("return null.x;"),
+            frame("var o = null; return o[0];"),
+            frame("return 1n + 1;"),
+            frame("return 1n * 2;"),
+            frame("return -Symbol();"),
+            frame("return 1 in 2;"),
+            frame("return null();"),
+            frame("throw new Error('thrown');"),
+        ];
+        let cases = scripts
+            .iter()
+            .map(|script| (script.as_str(), "true"))
+            .collect::<Vec<_>>();
+        assert_eval_cases(&cases);
+    }
+
+    #[test]
+    fn test_capture_and_prepare_stack_trace() {
+        assert_eval_cases(&[
+            (
+                "var o = {}; Error.captureStackTrace(o); typeof o.stack + ',' + o.stack.split('\\n')[0]",
+                "string,Error",
+            ),
+            (
+                "try { Error.captureStackTrace(1) } catch (e) { e.name }",
+                "TypeError",
+            ),
+            (
+                "function outer() { inner(); }\nfunction inner() { Error.captureStackTrace(skipped, inner); }\n\
+                 var skipped = {}; outer(); skipped.stack.split('\\n')[1].indexOf('    at outer (') === 0",
+                "true",
+            ),
+            (
+                "Error.stackTraceLimit = 0; var e = new Error('m'); Error.stackTraceLimit = 10; e.stack",
+                "Error: m",
+            ),
+            (
+                "function f() { return new Error('p').stack; }\n\
+                 Error.prepareStackTrace = function (err, sites) { \
+                 return err.message + ':' + Array.isArray(sites) + ':' + sites[0].getFunctionName(); }; \
+                 var s = f(); Error.prepareStackTrace = undefined; s",
+                "p:true:f",
+            ),
+            (
+                "Error.prepareStackTrace = function (err, sites) { return sites; };\n\
+                 function h() {\n  return new Error().stack;\n}\n\
+                 var sites = h(); Error.prepareStackTrace = undefined; \
+                 [sites[0].getLineNumber(), sites[0].getFunctionName(), sites[1].isToplevel()].join()",
+                "3,h,true",
+            ),
+            (
+                "try { sites[0].getLineNumber.call({}) } catch (e) { e.name }",
+                "TypeError",
+            ),
+        ]);
+    }
+}
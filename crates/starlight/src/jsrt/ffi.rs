@@ -381,8 +381,8 @@ impl Argument {
                         return Ok(Argument::F64(n));
                     }
 
-                } else if val.is_jsstring() {
-                     return Ok(Argument::Pointer(val.get_jsstring().as_str().as_ptr() as *mut _));
+                } else if val.is_jsstring() && val.get_jsstring().is_latin1() {
+                     return Ok(Argument::Pointer(val.get_jsstring().as_latin1().unwrap().as_ptr() as *mut _));
                 } else {
                     let val_str = val.to_string(rt);
                     let val_str = if let Ok(val_str) = val_str {
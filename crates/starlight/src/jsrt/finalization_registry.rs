@@ -0,0 +1,318 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+use std::intrinsics::unlikely;
+use std::mem::ManuallyDrop;
+
+use crate::define_jsclass;
+use crate::js_method_table;
+use crate::prelude::*;
+use crate::vm::class::JsClass;
+use crate::vm::context::Context;
+use crate::vm::object::TypedJsObject;
+use crate::JsTryFrom;
+
+/// Single `register` call: weakly held target, strongly held value that is passed to the cleanup
+/// callback and optional weakly held unregister token.
+pub struct FinalizationCell {
+    target: WeakRef<JsObject>,
+    held_value: JsValue,
+    unregister_token: Option<WeakRef<JsObject>>,
+}
+
+pub struct JsFinalizationRegistry {
+    realm: GcPointer<Context>,
+    cleanup: JsValue,
+    cells: Vec<FinalizationCell>,
+    /// Held values of cells whose targets were collected. They stay here until cleanup job runs.
+    cleared: Vec<JsValue>,
+    cleanup_scheduled: bool,
+}
+
+extern "C" fn fsz() -> usize {
+    std::mem::size_of::<JsFinalizationRegistry>()
+}
+
+extern "C" fn ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize a FinalizationRegistry");
+}
+
+extern "C" fn deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize a FinalizationRegistry");
+}
+
+extern "C" fn drop_registry(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<JsFinalizationRegistry>()) }
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<JsFinalizationRegistry>().trace(tracer);
+}
+
+unsafe impl Trace for JsFinalizationRegistry {
+    fn trace(&mut self, visitor: &mut dyn Tracer) {
+        self.realm.trace(visitor);
+        self.cleanup.trace(visitor);
+        self.cells.iter_mut().for_each(|cell| {
+            cell.target.trace(visitor);
+            cell.held_value.trace(visitor);
+            cell.unregister_token.trace(visitor);
+        });
+        self.cleared.trace(visitor);
+    }
+}
+
+define_jsclass!(
+    JsFinalizationRegistry,
+    FinalizationRegistry,
+    Object,
+    Some(drop_registry),
+    Some(trace),
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl JsFinalizationRegistry {
+    /// Move held values of cells with dead targets to the cleared list.
+    ///
+    /// Invoked by GC after weak references are updated and before sweeping. This function must not
+    /// allocate nor run any JS code.
+    pub(crate) fn collect_dead_cells(registry: &mut GcPointer<JsObject>) {
+        let data: &mut Self = &mut **registry.data::<Self>();
+        let cleared = &mut data.cleared;
+        data.cells.retain(|cell| {
+            if cell.target.upgrade().is_some() {
+                return true;
+            }
+            cleared.push(cell.held_value);
+            false
+        });
+    }
+
+    /// Queue cleanup job for `registry` if it has cleared cells. Invoked by GC once sweeping is done.
+    pub(crate) fn schedule_cleanup(registry: GcPointer<JsObject>) {
+        let data = registry.data::<Self>();
+        if data.cleared.is_empty() || data.cleanup_scheduled {
+            return;
+        }
+        let mut ctx = data.realm;
+        let root = ctx.vm.add_persistent_root(JsValue::new(registry));
+        // The job owns `root`, if scheduling fails dropping the job releases the registry.
+        let scheduled = ctx.schedule_async(move |ctx| {
+            let registry = root.get_value().get_jsobject();
+            let callback = registry.data::<Self>().cleanup;
+            if let Err(error) = Self::cleanup(ctx, registry, callback) {
+                ctx.report_uncaught_exception(error);
+            }
+        });
+        if scheduled.is_ok() {
+            data.cleanup_scheduled = true;
+        }
+    }
+
+    /// Invoke `callback` with every cleared held value of `registry`.
+    pub fn cleanup(
+        ctx: GcPointer<Context>,
+        registry: GcPointer<JsObject>,
+        callback: JsValue,
+    ) -> Result<(), JsValue> {
+        let stack = ctx.shadowstack();
+        letroot!(registry = stack, registry);
+        letroot!(callback = stack, callback);
+        registry.data::<Self>().cleanup_scheduled = false;
+        // Cells cleared by a GC inside the callback are picked up by the next round.
+        loop {
+            letroot!(
+                cleared = stack,
+                std::mem::take(&mut registry.data::<Self>().cleared)
+            );
+            if cleared.is_empty() {
+                break;
+            }
+            for i in 0..cleared.len() {
+                let mut tmp = [cleared[i]];
+                letroot!(
+                    args = stack,
+                    Arguments::new(JsValue::encode_undefined_value(), &mut tmp)
+                );
+                let result = callback
+                    .get_jsobject()
+                    .as_function_mut()
+                    .call(ctx, &mut args, *callback);
+                if let Err(error) = result {
+                    // Held values the callback has not seen yet stay for the next cleanup.
+                    let rest = cleared[i + 1..].to_vec();
+                    registry.data::<Self>().cleared.splice(0..0, rest);
+                    return Err(error);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn finalization_registry_constructor(
+    mut ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    if unlikely(!args.ctor_call) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "FinalizationRegistry() called in function context instead of constructor",
+        )));
+    }
+    let cleanup = args.at(0);
+    if unlikely(!cleanup.is_callable()) {
+        return Err(JsValue::new(
+            ctx.new_type_error("FinalizationRegistry: cleanup must be callable"),
+        ));
+    }
+    let map = ctx.global_data().finalization_registry_structure.unwrap();
+    let mut registry = JsObject::new(
+        ctx,
+        &map,
+        JsFinalizationRegistry::get_class(),
+        ObjectTag::Ordinary,
+    );
+    *registry.data::<JsFinalizationRegistry>() = ManuallyDrop::new(JsFinalizationRegistry {
+        realm: ctx,
+        cleanup,
+        cells: vec![],
+        cleared: vec![],
+        cleanup_scheduled: false,
+    });
+    ctx.heap().add_finalization_registry(registry);
+    Ok(JsValue::new(registry))
+}
+
+pub fn finalization_registry_prototype_register(
+    mut ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut registry = TypedJsObject::<JsFinalizationRegistry>::try_from(ctx, args.this)?;
+    let target = args.at(0);
+    let held_value = args.at(1);
+    let token = args.at(2);
+    if unlikely(!target.is_jsobject()) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "FinalizationRegistry.prototype.register: target must be an object",
+        )));
+    }
+    if unlikely(JsValue::same_value(target, held_value)) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "FinalizationRegistry.prototype.register: target and holdings must not be same",
+        )));
+    }
+    if unlikely(!token.is_jsobject() && !token.is_undefined()) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "FinalizationRegistry.prototype.register: unregister token must be an object",
+        )));
+    }
+    let target = ctx.heap().make_weak(target.get_jsobject());
+    let unregister_token = if token.is_undefined() {
+        None
+    } else {
+        Some(ctx.heap().make_weak(token.get_jsobject()))
+    };
+    registry.cells.push(FinalizationCell {
+        target,
+        held_value,
+        unregister_token,
+    });
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn finalization_registry_prototype_unregister(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut registry = TypedJsObject::<JsFinalizationRegistry>::try_from(ctx, args.this)?;
+    let token = args.at(0);
+    if unlikely(!token.is_jsobject()) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "FinalizationRegistry.prototype.unregister: unregister token must be an object",
+        )));
+    }
+    let token = token.get_jsobject();
+    let before = registry.cells.len();
+    registry.cells.retain(|cell| match cell.unregister_token {
+        Some(ref weak) => match weak.upgrade() {
+            Some(value) => !GcPointer::ptr_eq(&value, &token),
+            None => true,
+        },
+        None => true,
+    });
+    Ok(JsValue::new(registry.cells.len() != before))
+}
+
+impl JsClass for JsFinalizationRegistry {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::options::Options;
+    use crate::vm::context::Context;
+    use crate::Platform;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_cleanup_after_gc() {
+        Platform::initialize();
+        let todos = Rc::new(RefCell::new(vec![]));
+        let todos2 = todos.clone();
+        let mut rt = Platform::new_runtime(Options::default(), None).with_async_scheduler(
+            Box::new(move |job| {
+                todos2.borrow_mut().push(job);
+            }),
+        );
+        let mut ctx = Context::new(&mut rt);
+        ctx.eval(
+            "var held = []; var registry = new FinalizationRegistry(function (value) { held.push(value); }); \
+             (function () { for (var i = 0; i < 100; i++) registry.register({}, i); })(); gc();",
+        )
+        .unwrap_or_else(|_| panic!("registering threw"));
+        assert!(!todos.borrow().is_empty(), "gc() scheduled no cleanup");
+
+        loop {
+            let job;
+            {
+                let todos_vec = &mut *todos.borrow_mut();
+                if todos_vec.is_empty() {
+                    break;
+                }
+                job = todos_vec.remove(0);
+            }
+            job(ctx);
+        }
+        let result = ctx
+            .eval("held.length > 0 && held.every(function (value) { return value >= 0 && value < 100; })")
+            .and_then(|value| value.to_string(ctx))
+            .unwrap_or_else(|_| panic!("reading held values threw"));
+        assert_eq!(result, "true");
+    }
+
+    #[test]
+    fn test_cleanup_exception_is_reported() {
+        Platform::initialize();
+        let errors = Rc::new(RefCell::new(vec![]));
+        let errors2 = errors.clone();
+        let mut rt = Platform::new_runtime(Options::default(), None)
+            .with_uncaught_exception_handler(Box::new(move |ctx, error| {
+                let message = error.to_string(ctx).unwrap_or_default();
+                errors2.borrow_mut().push(message);
+            }));
+        let ctx = Context::new(&mut rt);
+        ctx.eval(
+            "var registry = new FinalizationRegistry(function () { throw new Error('cleanup'); }); \
+             (function () { for (var i = 0; i < 100; i++) registry.register({}, i); })(); gc();",
+        )
+        .unwrap_or_else(|_| panic!("registering threw"));
+        // the job stops at the first exception, remaining held values wait for the next cleanup
+        assert_eq!(*errors.borrow(), vec!["Error: cleanup".to_owned()]);
+    }
+}
@@ -173,3 +173,19 @@ pub fn function_call(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValu
         ctx, msg, None,
     )))
 }
+
+/// `Function.prototype[Symbol.hasInstance]`, default behavior of `instanceof`.
+pub fn function_has_instance(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    if !args.this.is_callable() {
+        return Ok(JsValue::encode_bool_value(false));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(func = stack, args.this.get_jsobject());
+    letroot!(this = stack, *func);
+    func.as_function()
+        .has_instance(&mut this, ctx, args.at(0))
+        .map(JsValue::encode_bool_value)
+}
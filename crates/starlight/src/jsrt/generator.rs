@@ -34,6 +34,13 @@ impl GcPointer<Context> {
             JsValue::new(iter),
             false,
         )?;
+        let tag = JsString::new(self, "Generator");
+        generator.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
         self.global_data.generator_prototype = Some(generator);
         self.global_data.generator_structure =
             Some(Structure::new_indexed(self, Some(generator), false));
@@ -4,20 +4,58 @@
 use crate::{
     gc::cell::GcPointer,
     prelude::JsString,
-    vm::{arguments::Arguments, context::Context, value::*},
+    vm::{arguments::Arguments, context::Context, operations, string::JsStringBuilder, value::*},
 };
 use num::traits::*;
 use std::io::Write;
 pub fn parse_float(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    if !args.size() != 0 {
-        let str = args.at(0).to_string(ctx)?;
+    let str = args.at(0).to_string(ctx)?;
+    let str = str.trim_start_matches(is_trimmable_whitespace);
+    Ok(JsValue::encode_untrusted_f64_value(
+        str_decimal_prefix(str)
+            .and_then(|prefix| prefix.parse::<f64>().ok())
+            .unwrap_or(std::f64::NAN),
+    ))
+}
 
-        Ok(JsValue::encode_untrusted_f64_value(
-            str.parse::<f64>().unwrap_or(std::f64::NAN),
-        ))
-    } else {
-        Ok(JsValue::encode_nan_value())
+/// Longest prefix of `str` that satisfies the syntax of StrDecimalLiteral, if any.
+fn str_decimal_prefix(str: &str) -> Option<&str> {
+    let bytes = str.as_bytes();
+    let mut end = 0;
+    if matches!(bytes.first(), Some(b'+') | Some(b'-')) {
+        end += 1;
+    }
+    if str[end..].starts_with("Infinity") {
+        return Some(&str[..end + "Infinity".len()]);
+    }
+    let digits = |mut at: usize| {
+        while at < bytes.len() && bytes[at].is_ascii_digit() {
+            at += 1;
+        }
+        at
+    };
+    let integral = digits(end);
+    let mut mantissa_digits = integral - end;
+    end = integral;
+    if bytes.get(end) == Some(&b'.') {
+        let fraction = digits(end + 1);
+        mantissa_digits += fraction - end - 1;
+        end = fraction;
+    }
+    if mantissa_digits == 0 {
+        return None;
     }
+    if matches!(bytes.get(end), Some(b'e') | Some(b'E')) {
+        let mut exponent = end + 1;
+        if matches!(bytes.get(exponent), Some(b'+') | Some(b'-')) {
+            exponent += 1;
+        }
+        let exponent_end = digits(exponent);
+        if exponent_end > exponent {
+            end = exponent_end;
+        }
+    }
+    Some(&str[..end])
 }
 /// This function is taken from Boa.
 /// Helper function to check if a `char` is trimmable.
@@ -134,6 +172,180 @@ pub fn parse_int(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, J
     }
 }
 
+/// uriReserved of the URI grammar, plus `#` which encodeURI and decodeURI also leave alone.
+const URI_RESERVED: &str = ";/?:@&=+$,#";
+
+/// uriUnescaped: ASCII letters, digits and uriMark.
+fn is_uri_unescaped(unit: u16) -> bool {
+    unit < 0x80 && {
+        let c = unit as u8;
+        c.is_ascii_alphanumeric() || b"-_.!~*'()".contains(&c)
+    }
+}
+
+fn uri_error(ctx: GcPointer<Context>, msg: &str) -> JsValue {
+    JsValue::new(ctx.new_uri_error(msg))
+}
+
+fn hex_digit(unit: u16) -> Option<u8> {
+    std::char::from_u32(unit as u32)
+        .and_then(|c| c.to_digit(16))
+        .map(|digit| digit as u8)
+}
+
+/// Encode(string, extraUnescaped): UTF-8 bytes of everything else become `%XX` escapes.
+fn encode(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    extra_unescaped: &str,
+) -> Result<JsValue, JsValue> {
+    let string = value.to_jsstring(ctx)?;
+    let units = string.to_utf16();
+    let mut builder = JsStringBuilder::new();
+    let mut k = 0;
+    while k < units.len() {
+        let unit = units[k];
+        if is_uri_unescaped(unit) || (unit < 0x80 && extra_unescaped.contains(unit as u8 as char)) {
+            builder.push(unit);
+            k += 1;
+            continue;
+        }
+        let code_point = match std::char::decode_utf16(units[k..].iter().copied()).next() {
+            Some(Ok(c)) => c,
+            _ => return Err(uri_error(ctx, "URI malformed")),
+        };
+        k += code_point.len_utf16();
+        let mut bytes = [0; 4];
+        for byte in code_point.encode_utf8(&mut bytes).bytes() {
+            builder.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    Ok(JsValue::new(builder.build(ctx)))
+}
+
+/// Decode(string, preserveEscapeSet): `%XX` escapes of UTF-8 sequences are decoded, except for
+/// escapes of characters in `preserve_escape_set`.
+fn decode(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    preserve_escape_set: &str,
+) -> Result<JsValue, JsValue> {
+    let string = value.to_jsstring(ctx)?;
+    let units = string.to_utf16();
+    let escaped_byte = |at: usize| -> Option<u8> {
+        if units.get(at) != Some(&(b'%' as u16)) {
+            return None;
+        }
+        let high = hex_digit(*units.get(at + 1)?)?;
+        let low = hex_digit(*units.get(at + 2)?)?;
+        Some(high << 4 | low)
+    };
+    let mut builder = JsStringBuilder::new();
+    let mut k = 0;
+    while k < units.len() {
+        if units[k] != b'%' as u16 {
+            builder.push(units[k]);
+            k += 1;
+            continue;
+        }
+        let start = k;
+        let byte = escaped_byte(k).ok_or_else(|| uri_error(ctx, "URI malformed"))?;
+        k += 3;
+        if byte < 0x80 {
+            if preserve_escape_set.contains(byte as char) {
+                builder.push_units(&string, start as u32, k as u32);
+            } else {
+                builder.push(byte as u16);
+            }
+            continue;
+        }
+        let n = byte.leading_ones() as usize;
+        if n == 1 || n > 4 {
+            return Err(uri_error(ctx, "URI malformed"));
+        }
+        let mut octets = vec![byte];
+        for _ in 1..n {
+            let byte = escaped_byte(k).ok_or_else(|| uri_error(ctx, "URI malformed"))?;
+            octets.push(byte);
+            k += 3;
+        }
+        // from_utf8 rejects overlong forms and surrogates.
+        match std::str::from_utf8(&octets) {
+            Ok(str) => builder.push_str(str),
+            Err(_) => return Err(uri_error(ctx, "URI malformed")),
+        }
+    }
+    Ok(JsValue::new(builder.build(ctx)))
+}
+
+pub fn encode_uri(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    encode(ctx, args.at(0), URI_RESERVED)
+}
+
+pub fn encode_uri_component(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    encode(ctx, args.at(0), "")
+}
+
+pub fn decode_uri(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    decode(ctx, args.at(0), URI_RESERVED)
+}
+
+pub fn decode_uri_component(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    decode(ctx, args.at(0), "")
+}
+
+/// Annex B `escape`: code units outside of the unescaped set become `%XX` or `%uXXXX`.
+pub fn escape(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = args.at(0).to_jsstring(ctx)?;
+    let mut builder = JsStringBuilder::new();
+    for unit in string.code_units() {
+        let unescaped = unit < 0x80 && {
+            let c = unit as u8;
+            c.is_ascii_alphanumeric() || b"@*_+-./".contains(&c)
+        };
+        if unescaped {
+            builder.push(unit);
+        } else if unit < 0x100 {
+            builder.push_str(&format!("%{:02X}", unit));
+        } else {
+            builder.push_str(&format!("%u{:04X}", unit));
+        }
+    }
+    Ok(JsValue::new(builder.build(ctx)))
+}
+
+/// Annex B `unescape`, malformed escapes are kept as they are.
+pub fn unescape(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = args.at(0).to_jsstring(ctx)?;
+    let units = string.to_utf16();
+    let hex = |digits: &[u16]| {
+        digits.iter().try_fold(0u16, |value, &unit| {
+            Some(value << 4 | hex_digit(unit)? as u16)
+        })
+    };
+    let mut builder = JsStringBuilder::new();
+    let mut k = 0;
+    while k < units.len() {
+        let mut unit = units[k];
+        if unit == b'%' as u16 {
+            if units.get(k + 1) == Some(&(b'u' as u16)) && k + 6 <= units.len() {
+                if let Some(value) = hex(&units[k + 2..k + 6]) {
+                    unit = value;
+                    k += 5;
+                }
+            } else if k + 3 <= units.len() {
+                if let Some(value) = hex(&units[k + 1..k + 3]) {
+                    unit = value;
+                    k += 2;
+                }
+            }
+        }
+        builder.push(unit);
+        k += 1;
+    }
+    Ok(JsValue::new(builder.build(ctx)))
+}
+
 pub fn is_nan(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     if args.size() != 0 {
         let val = args.at(0);
@@ -158,6 +370,24 @@ pub fn gc(mut ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsVa
     Ok(JsValue::encode_undefined_value())
 }
 
+/// queueMicrotask(callback)
+pub fn queue_microtask(mut ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let callback = args.at(0);
+    if !callback.is_callable() {
+        return Err(JsValue::new(
+            ctx.new_type_error("queueMicrotask: callback is not a function"),
+        ));
+    }
+    let root = ctx.vm.add_persistent_root(callback);
+    ctx.schedule_async(move |ctx| {
+        let this = JsValue::encode_undefined_value();
+        if let Err(error) = operations::call(ctx, root.get_value(), this, &mut []) {
+            ctx.report_uncaught_exception(error);
+        }
+    })?;
+    Ok(JsValue::encode_undefined_value())
+}
+
 pub fn ___trunc(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     let n = args.at(0).to_number(ctx)?.trunc();
     Ok(JsValue::new(n))
@@ -185,10 +415,7 @@ pub fn ___is_constructor(_ctx: GcPointer<Context>, args: &Arguments) -> Result<J
     let arg = args.at(0);
     if arg.is_callable() {
         let fun = arg.get_jsobject();
-        return Ok(JsValue::new(
-            fun.as_function().is_native()
-                || (fun.as_function().is_vm() && fun.as_function().as_vm().code.is_constructor),
-        ));
+        return Ok(JsValue::new(fun.as_function().is_constructor()));
     }
 
     Ok(JsValue::new(false))
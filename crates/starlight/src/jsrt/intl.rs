@@ -0,0 +1,495 @@
+//! `Intl`: number and date-time formatting, collation and plural rules for a few bundled locales.
+//!
+//! There is no ICU, the locale data for `en`, `de`, `fr`, `ja` and `ru` lives in [locale]. Other
+//! locales fall back to the closest bundled one, or to `en-US` when there is none.
+use std::intrinsics::unlikely;
+
+use crate::{
+    constant::S_CONSTURCTOR,
+    prelude::*,
+    vm::{array_storage::ArrayStorage, class::JsClass, context::Context},
+};
+
+use self::locale::Language;
+
+mod locale;
+
+pub mod collator;
+pub mod date_time_format;
+pub mod number_format;
+pub mod plural_rules;
+
+/// Locales with bundled data, see [Locale::lookup].
+const AVAILABLE_LOCALES: [&str; 10] = [
+    "de", "de-DE", "en", "en-US", "fr", "fr-FR", "ja", "ja-JP", "ru", "ru-RU",
+];
+
+const DEFAULT_LOCALE: &str = "en-US";
+
+/// Locale a service object was resolved to.
+#[derive(Clone)]
+pub(crate) struct Locale {
+    tag: Box<str>,
+    language: Language,
+}
+
+impl Locale {
+    fn new(tag: &str) -> Self {
+        let language = Language::from_tag(tag).expect("locale without bundled data");
+        Self {
+            tag: tag.into(),
+            language,
+        }
+    }
+
+    /// BCP 47 Lookup of a canonical language tag: subtags are removed from the end until what is
+    /// left is an available locale. Extensions are not supported and are always removed.
+    fn lookup(tag: &str) -> Option<Self> {
+        let mut candidate = strip_extensions(tag);
+        loop {
+            if AVAILABLE_LOCALES.contains(&candidate) {
+                return Some(Self::new(candidate));
+            }
+            let end = candidate.rfind('-')?;
+            candidate = &candidate[..end];
+        }
+    }
+
+    /// ResolveLocale, the first of `locales` that has bundled data.
+    fn resolve(ctx: GcPointer<Context>, locales: JsValue) -> Result<Self, JsValue> {
+        let requested = canonicalize_locale_list(ctx, locales)?;
+        Ok(requested
+            .iter()
+            .find_map(|tag| Self::lookup(tag))
+            .unwrap_or_else(|| Self::new(DEFAULT_LOCALE)))
+    }
+
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn value(&self, ctx: GcPointer<Context>) -> JsValue {
+        string_value(ctx, &*self.tag)
+    }
+}
+
+/// A language tag without its extension and private use subtags.
+fn strip_extensions(tag: &str) -> &str {
+    let mut end = tag.len();
+    let mut offset = 0;
+    for subtag in tag.split('-') {
+        if offset != 0 && subtag.len() == 1 {
+            end = offset - 1;
+            break;
+        }
+        offset += subtag.len() + 1;
+    }
+    &tag[..end]
+}
+
+/// Canonical case of `tag` if it is a structurally valid BCP 47 language tag, aliases are not
+/// replaced.
+fn canonicalize_tag(tag: &str) -> Option<String> {
+    fn is_alpha(subtag: &str) -> bool {
+        subtag.bytes().all(|c| c.is_ascii_alphabetic())
+    }
+    fn is_alphanumeric(subtag: &str) -> bool {
+        subtag.bytes().all(|c| c.is_ascii_alphanumeric())
+    }
+
+    let lower = tag.to_ascii_lowercase();
+    let mut subtags = lower.split('-').peekable();
+    let language = subtags.next()?;
+    if !matches!(language.len(), 2..=3 | 5..=8) || !is_alpha(language) {
+        return None;
+    }
+    let mut result = vec![language.to_owned()];
+    if let Some(script) = subtags.next_if(|subtag| subtag.len() == 4 && is_alpha(subtag)) {
+        result.push(script[..1].to_ascii_uppercase() + &script[1..]);
+    }
+    let is_region = |subtag: &&str| {
+        (subtag.len() == 2 && is_alpha(subtag))
+            || (subtag.len() == 3 && subtag.bytes().all(|c| c.is_ascii_digit()))
+    };
+    if let Some(region) = subtags.next_if(is_region) {
+        result.push(region.to_ascii_uppercase());
+    }
+    let is_variant = |subtag: &&str| {
+        is_alphanumeric(subtag)
+            && (matches!(subtag.len(), 5..=8)
+                || (subtag.len() == 4 && subtag.as_bytes()[0].is_ascii_digit()))
+    };
+    let variants_start = result.len();
+    while let Some(variant) = subtags.next_if(is_variant) {
+        if result[variants_start..].iter().any(|seen| seen == variant) {
+            return None;
+        }
+        result.push(variant.to_owned());
+    }
+    let mut singletons = vec![];
+    while let Some(singleton) = subtags.next() {
+        if singleton.len() != 1 || !is_alphanumeric(singleton) || singletons.contains(&singleton) {
+            return None;
+        }
+        singletons.push(singleton);
+        result.push(singleton.to_owned());
+        let (min, max) = if singleton == "x" { (1, 8) } else { (2, 8) };
+        let mut count = 0;
+        while let Some(subtag) =
+            subtags.next_if(|subtag| (min..=max).contains(&subtag.len()) && is_alphanumeric(subtag))
+        {
+            result.push(subtag.to_owned());
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+        if singleton == "x" && subtags.peek().is_some() {
+            return None;
+        }
+    }
+    Some(result.join("-"))
+}
+
+/// CanonicalizeLocaleList: `undefined`, a language tag or an array-like of language tags.
+fn canonicalize_locale_list(
+    ctx: GcPointer<Context>,
+    locales: JsValue,
+) -> Result<Vec<String>, JsValue> {
+    if locales.is_undefined() {
+        return Ok(vec![]);
+    }
+    let stack = ctx.shadowstack();
+    let mut seen = vec![];
+    let mut add = |value: JsValue| -> Result<(), JsValue> {
+        if unlikely(!value.is_jsstring() && !value.is_jsobject()) {
+            return Err(type_error(ctx, "Intl: language tag must be a string"));
+        }
+        let tag = value.to_string(ctx)?;
+        match canonicalize_tag(&tag) {
+            Some(tag) => {
+                if !seen.contains(&tag) {
+                    seen.push(tag);
+                }
+                Ok(())
+            }
+            None => Err(range_error(
+                ctx,
+                format!("Intl: incorrect locale information provided: {}", tag),
+            )),
+        }
+    };
+    if locales.is_jsstring() {
+        add(locales)?;
+    } else {
+        letroot!(list = stack, locales.to_object(ctx)?);
+        let length = super::get_length(ctx, &mut list)?;
+        for index in 0..length {
+            let key = Symbol::Index(index);
+            if list.has_property(ctx, key) {
+                add(list.get(ctx, key)?)?;
+            }
+        }
+    }
+    Ok(seen)
+}
+
+fn range_error(ctx: GcPointer<Context>, message: impl AsRef<str>) -> JsValue {
+    JsValue::new(ctx.new_range_error(message))
+}
+
+fn type_error(ctx: GcPointer<Context>, message: impl AsRef<str>) -> JsValue {
+    JsValue::new(ctx.new_type_error(message))
+}
+
+fn string_value(ctx: GcPointer<Context>, string: impl AsRef<str>) -> JsValue {
+    JsValue::new(JsString::new(ctx, string))
+}
+
+/// CoerceOptionsToObject, `undefined` means no options.
+fn coerce_options(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+) -> Result<Option<GcPointer<JsObject>>, JsValue> {
+    if value.is_undefined() {
+        Ok(None)
+    } else {
+        value.to_object(ctx).map(Some)
+    }
+}
+
+fn get_option(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+    name: &str,
+) -> Result<JsValue, JsValue> {
+    match options {
+        Some(mut options) => options.get(ctx, name.intern()),
+        None => Ok(JsValue::encode_undefined_value()),
+    }
+}
+
+/// String option that has to be one of `allowed`, `None` when it is not set.
+fn get_string_option(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+    name: &str,
+    allowed: &[&'static str],
+) -> Result<Option<&'static str>, JsValue> {
+    let value = get_option(ctx, options, name)?;
+    if value.is_undefined() {
+        return Ok(None);
+    }
+    let value = value.to_string(ctx)?;
+    match allowed.iter().find(|&&allowed| allowed == value) {
+        Some(&value) => Ok(Some(value)),
+        None => Err(range_error(
+            ctx,
+            format!("Intl: {} is not a valid value for option {}", value, name),
+        )),
+    }
+}
+
+fn get_boolean_option(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+    name: &str,
+) -> Result<Option<bool>, JsValue> {
+    let value = get_option(ctx, options, name)?;
+    if value.is_undefined() {
+        Ok(None)
+    } else {
+        Ok(Some(value.to_boolean()))
+    }
+}
+
+/// DefaultNumberOption, an integer in `minimum..=maximum` or `None` when `value` is `undefined`.
+fn default_number_option(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    name: &str,
+    minimum: u32,
+    maximum: u32,
+) -> Result<Option<u32>, JsValue> {
+    if value.is_undefined() {
+        return Ok(None);
+    }
+    let number = value.to_number(ctx)?;
+    if unlikely(number.is_nan() || number < minimum as f64 || number > maximum as f64) {
+        return Err(range_error(
+            ctx,
+            format!("Intl: {} value is out of range", name),
+        ));
+    }
+    Ok(Some(number.floor() as u32))
+}
+
+/// Resolves the locale and reads `localeMatcher`, which only has one implementation here.
+fn resolve_locale(
+    ctx: GcPointer<Context>,
+    locales: JsValue,
+    options: Option<GcPointer<JsObject>>,
+) -> Result<Locale, JsValue> {
+    let locale = Locale::resolve(ctx, locales)?;
+    get_string_option(ctx, options, "localeMatcher", &["lookup", "best fit"])?;
+    Ok(locale)
+}
+
+/// Reads `numberingSystem`, only Latin digits are supported.
+fn check_numbering_system(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+) -> Result<(), JsValue> {
+    let value = get_option(ctx, options, "numberingSystem")?;
+    if !value.is_undefined() {
+        let numbering_system = value.to_string(ctx)?;
+        if numbering_system != "latn" {
+            return Err(range_error(
+                ctx,
+                format!("Intl: unsupported numbering system {}", numbering_system),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Allocates an object of the Intl class `T` that inherits from the prototype of new.target, or
+/// from `default_proto` when the constructor is called as a function.
+fn new_object<T: JsClass>(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    default_proto: Option<GcPointer<JsObject>>,
+    data: T,
+) -> JsValue {
+    let proto = if args.ctor_call && args.this.is_jsobject() {
+        args.this.get_jsobject().prototype().copied()
+    } else {
+        default_proto
+    };
+    let stack = ctx.shadowstack();
+    letroot!(structure = stack, Structure::new_indexed(ctx, proto, false));
+    let object = JsObject::new(ctx, &structure, T::class(), ObjectTag::Ordinary);
+    *object.data::<T>() = std::mem::ManuallyDrop::new(data);
+    JsValue::new(object)
+}
+
+/// The native `method` bound to `this`, what the `format` and `compare` getters return.
+fn bound_method(
+    ctx: GcPointer<Context>,
+    this: GcPointer<JsObject>,
+    name: &str,
+    method: JsAPI,
+    length: u32,
+) -> GcPointer<JsObject> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this);
+    letroot!(
+        target = stack,
+        JsNativeFunction::new(ctx, name.intern(), method, length)
+    );
+    letroot!(args = stack, ArrayStorage::with_size(ctx, 0, 0));
+    JsFunction::new(
+        ctx,
+        FuncType::Bound(JsBoundFunction {
+            args: *args,
+            this: JsValue::new(*this),
+            target: *target,
+        }),
+        false,
+    )
+}
+
+/// Object with the given data properties in order, what `resolvedOptions` returns.
+fn new_options_object(
+    ctx: GcPointer<Context>,
+    properties: &[(&str, JsValue)],
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, JsObject::new_empty(ctx));
+    for (name, value) in properties {
+        object.put(ctx, name.intern(), *value, false)?;
+    }
+    Ok(JsValue::new(*object))
+}
+
+/// Array of `{ type, value }` objects, what `formatToParts` returns.
+fn new_parts_array(
+    ctx: GcPointer<Context>,
+    parts: &[(&'static str, String)],
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(array = stack, JsArray::new(ctx, parts.len() as u32));
+    for (index, (kind, value)) in parts.iter().enumerate() {
+        let kind = string_value(ctx, kind);
+        let value = string_value(ctx, value);
+        let part = new_options_object(ctx, &[("type", kind), ("value", value)])?;
+        array.put(ctx, Symbol::Index(index as u32), part, false)?;
+    }
+    Ok(JsValue::new(*array))
+}
+
+fn new_string_array(ctx: GcPointer<Context>, strings: &[impl AsRef<str>]) -> JsValue {
+    let values = strings
+        .iter()
+        .map(|string| string_value(ctx, string))
+        .collect::<Vec<_>>();
+    JsValue::new(JsArray::from_slice(ctx, &values))
+}
+
+pub fn intl_get_canonical_locales(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let locales = canonicalize_locale_list(ctx, args.at(0))?;
+    Ok(new_string_array(ctx, &locales))
+}
+
+/// `supportedLocalesOf` of every Intl constructor, they all share the same locale data.
+pub fn intl_supported_locales_of(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let locales = canonicalize_locale_list(ctx, args.at(0))?;
+    let options = coerce_options(ctx, args.at(1))?;
+    get_string_option(ctx, options, "localeMatcher", &["lookup", "best fit"])?;
+    let supported = locales
+        .into_iter()
+        .filter(|tag| Locale::lookup(tag).is_some())
+        .collect::<Vec<_>>();
+    Ok(new_string_array(ctx, &supported))
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_intl_in_global_object(mut self) -> Result<(), JsValue> {
+        let mut intl = JsObject::new_empty(self);
+        let constructor = |ctx: GcPointer<Context>, proto: Option<GcPointer<JsObject>>| {
+            proto.unwrap().get(ctx, S_CONSTURCTOR.intern())
+        };
+        let ctor = constructor(self, self.global_data.intl_collator_prototype)?;
+        def_native_property!(self, intl, Collator, ctor, W | C)?;
+        let ctor = constructor(self, self.global_data.intl_date_time_format_prototype)?;
+        def_native_property!(self, intl, DateTimeFormat, ctor, W | C)?;
+        let ctor = constructor(self, self.global_data.intl_number_format_prototype)?;
+        def_native_property!(self, intl, NumberFormat, ctor, W | C)?;
+        let ctor = constructor(self, self.global_data.intl_plural_rules_prototype)?;
+        def_native_property!(self, intl, PluralRules, ctor, W | C)?;
+        def_native_method!(
+            self,
+            intl,
+            getCanonicalLocales,
+            intl_get_canonical_locales,
+            1,
+            W | C
+        )?;
+        let tag = JsString::new(self, "Intl");
+        intl.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+
+        let mut global_object = self.global_object();
+        def_native_property!(self, global_object, Intl, intl, W | C)?;
+        Ok(())
+    }
+
+    pub(crate) fn init_intl_in_global_data(self) -> Result<(), JsValue> {
+        self.init_intl_collator_in_global_data()?;
+        self.init_intl_date_time_format_in_global_data()?;
+        self.init_intl_number_format_in_global_data()?;
+        self.init_intl_plural_rules_in_global_data()?;
+        Ok(())
+    }
+
+    /// Prototype object and constructor of an Intl service, `Intl.{name}`.
+    fn new_intl_class(
+        self,
+        name: &str,
+        constructor: JsAPI,
+        length: u32,
+    ) -> Result<(GcPointer<JsObject>, GcPointer<JsObject>), JsValue> {
+        let obj_proto = self.global_data().get_object_prototype();
+        let structure = Structure::new_unique_indexed(self, Some(obj_proto), false);
+        let mut proto = JsObject::new(self, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+        let mut ctor = JsNativeFunction::new(self, name.intern(), constructor, length);
+        def_native_property!(self, ctor, prototype, proto, NONE)?;
+        def_native_property!(self, proto, constructor, ctor, W | C)?;
+        def_native_method!(
+            self,
+            ctor,
+            supportedLocalesOf,
+            intl_supported_locales_of,
+            1,
+            W | C
+        )?;
+        let tag = JsString::new(self, format!("Intl.{}", name));
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+        Ok((proto, ctor))
+    }
+}
@@ -0,0 +1,378 @@
+//! `Intl.Collator`, a simplified Unicode collation with three levels.
+//!
+//! Strings are compared in canonical decomposition. Base letters are the primary level, accents
+//! the secondary level and case, kana and width the tertiary level. Whitespace and punctuation
+//! sort before symbols, digits and letters, and letters of different scripts in code point order,
+//! except that Russian puts Cyrillic first.
+use std::{cmp::Ordering, mem::ManuallyDrop};
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use super::{
+    bound_method, coerce_options, get_boolean_option, get_string_option, locale::Language,
+    new_object, new_options_object, resolve_locale, string_value, Locale,
+};
+use crate::{
+    define_jsclass,
+    prelude::*,
+    vm::{class::JsClass, context::Context, object::TypedJsObject},
+    JsTryFrom,
+};
+
+const SPACE: u64 = 1;
+const PUNCTUATION: u64 = 2;
+const SYMBOL: u64 = 3;
+const DIGIT: u64 = 4;
+const LETTER: u64 = 5;
+
+const UPPER: u8 = 1;
+const KATAKANA: u8 = 2;
+const FULL_WIDTH: u8 = 4;
+
+/// Secondary weights of the common accents, the order of the CLDR root collation.
+const ACCENTS: [char; 14] = [
+    '\u{301}', '\u{300}', '\u{306}', '\u{302}', '\u{30c}', '\u{30a}', '\u{308}', '\u{30b}',
+    '\u{303}', '\u{307}', '\u{328}', '\u{327}', '\u{304}', '\u{338}',
+];
+
+#[derive(Clone, Copy)]
+struct Element {
+    primary: u64,
+    secondary: u32,
+    tertiary: u8,
+}
+
+impl Element {
+    fn new(group: u64, weight: u32, tertiary: u8) -> Self {
+        Self {
+            primary: (group << 40) | weight as u64,
+            secondary: 0,
+            tertiary,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Sensitivity {
+    Base,
+    Accent,
+    Case,
+    Variant,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CaseFirst {
+    Upper,
+    Lower,
+    False,
+}
+
+/// Resolved options of a `Collator`, also used by `String.prototype.localeCompare`.
+#[derive(Clone)]
+pub(crate) struct StringCollator {
+    locale: Locale,
+    search: bool,
+    sensitivity: Sensitivity,
+    ignore_punctuation: bool,
+    numeric: bool,
+    case_first: CaseFirst,
+}
+
+impl StringCollator {
+    /// InitializeCollator
+    pub(crate) fn new(
+        ctx: GcPointer<Context>,
+        locales: JsValue,
+        options: JsValue,
+    ) -> Result<Self, JsValue> {
+        let options = coerce_options(ctx, options)?;
+        let search =
+            get_string_option(ctx, options, "usage", &["sort", "search"])? == Some("search");
+        let locale = resolve_locale(ctx, locales, options)?;
+        let numeric = get_boolean_option(ctx, options, "numeric")?.unwrap_or(false);
+        let case_first =
+            match get_string_option(ctx, options, "caseFirst", &["upper", "lower", "false"])? {
+                Some("upper") => CaseFirst::Upper,
+                Some("lower") => CaseFirst::Lower,
+                _ => CaseFirst::False,
+            };
+        let sensitivity = match get_string_option(
+            ctx,
+            options,
+            "sensitivity",
+            &["base", "accent", "case", "variant"],
+        )? {
+            Some("base") => Sensitivity::Base,
+            Some("accent") => Sensitivity::Accent,
+            Some("case") => Sensitivity::Case,
+            _ => Sensitivity::Variant,
+        };
+        let ignore_punctuation =
+            get_boolean_option(ctx, options, "ignorePunctuation")?.unwrap_or(false);
+        Ok(Self {
+            locale,
+            search,
+            sensitivity,
+            ignore_punctuation,
+            numeric,
+            case_first,
+        })
+    }
+
+    /// CompareStrings
+    pub(crate) fn compare(&self, x: &str, y: &str) -> Ordering {
+        let (x, y) = (self.elements(x), self.elements(y));
+        let primary = |element: &Element| element.primary;
+        let ordering = x.iter().map(primary).cmp(y.iter().map(primary));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        if matches!(self.sensitivity, Sensitivity::Accent | Sensitivity::Variant) {
+            let secondary = |element: &Element| element.secondary;
+            let ordering = x.iter().map(secondary).cmp(y.iter().map(secondary));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        if matches!(self.sensitivity, Sensitivity::Case | Sensitivity::Variant) {
+            let mask = if self.sensitivity == Sensitivity::Case {
+                UPPER
+            } else {
+                UPPER | KATAKANA | FULL_WIDTH
+            };
+            let upper_first = self.case_first == CaseFirst::Upper;
+            let tertiary = |element: &Element| {
+                let tertiary = element.tertiary & mask;
+                if upper_first {
+                    tertiary ^ UPPER
+                } else {
+                    tertiary
+                }
+            };
+            return x.iter().map(tertiary).cmp(y.iter().map(tertiary));
+        }
+        Ordering::Equal
+    }
+
+    fn elements(&self, text: &str) -> Vec<Element> {
+        let cyrillic_first = self.locale.language() == Language::Ru;
+        let chars = text.nfd().collect::<Vec<_>>();
+        let mut elements: Vec<Element> = Vec::with_capacity(chars.len());
+        let mut index = 0;
+        while index < chars.len() {
+            let c = chars[index];
+            index += 1;
+            if is_combining_mark(c) {
+                if let Some(last) = elements.last_mut() {
+                    let weight = match ACCENTS.iter().position(|&accent| accent == c) {
+                        Some(position) => position as u32 + 1,
+                        None => 32 + (c as u32 & 0xff),
+                    };
+                    last.secondary = last.secondary.saturating_mul(512).saturating_add(weight);
+                }
+                continue;
+            }
+            if let Some(digit) = c.to_digit(10) {
+                if !self.numeric {
+                    elements.push(Element::new(DIGIT, digit, 0));
+                    continue;
+                }
+                // digit runs compare by their value, first by the number of significant digits
+                let mut digits = vec![digit];
+                while let Some(digit) = chars.get(index).and_then(|c| c.to_digit(10)) {
+                    digits.push(digit);
+                    index += 1;
+                }
+                let leading = digits.iter().take_while(|&&digit| digit == 0).count();
+                let digits = &digits[leading..];
+                elements.push(Element::new(DIGIT, digits.len() as u32, 0));
+                elements.extend(digits.iter().map(|&digit| Element::new(DIGIT, digit, 0)));
+                continue;
+            }
+            if !c.is_alphanumeric() {
+                let group = if c.is_whitespace() {
+                    SPACE
+                } else if "$+<=>^`|~".contains(c) || (!c.is_ascii() && !is_punctuation(c)) {
+                    SYMBOL
+                } else {
+                    PUNCTUATION
+                };
+                if group != SYMBOL && self.ignore_punctuation {
+                    continue;
+                }
+                elements.push(Element::new(group, c as u32, 0));
+                continue;
+            }
+            let (c, mut tertiary) = match c as u32 {
+                0xff21..=0xff5a => (char::from_u32(c as u32 - 0xfee0).unwrap(), FULL_WIDTH),
+                0x30a1..=0x30f6 => (char::from_u32(c as u32 - 0x60).unwrap(), KATAKANA),
+                _ => (c, 0),
+            };
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            if lower != c {
+                tertiary |= UPPER;
+            }
+            let (base, second, secondary) = match lower {
+                'ß' => ('s', Some('s'), 0),
+                'æ' => ('a', Some('e'), 0),
+                'œ' => ('o', Some('e'), 0),
+                'ø' => ('o', None, 16),
+                'đ' => ('d', None, 16),
+                'ł' => ('l', None, 16),
+                'ı' => ('i', None, 16),
+                _ => (lower, None, 0),
+            };
+            for base in std::iter::once(base).chain(second) {
+                let rank = if cyrillic_first && ('\u{400}'..'\u{530}').contains(&base) {
+                    0
+                } else {
+                    1
+                };
+                let mut element = Element::new(LETTER, base as u32 | rank << 31, tertiary);
+                element.secondary = secondary;
+                elements.push(element);
+            }
+        }
+        elements
+    }
+
+    fn resolved_options(&self, ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
+        let usage = if self.search { "search" } else { "sort" };
+        let sensitivity = match self.sensitivity {
+            Sensitivity::Base => "base",
+            Sensitivity::Accent => "accent",
+            Sensitivity::Case => "case",
+            Sensitivity::Variant => "variant",
+        };
+        let case_first = match self.case_first {
+            CaseFirst::Upper => "upper",
+            CaseFirst::Lower => "lower",
+            CaseFirst::False => "false",
+        };
+        new_options_object(
+            ctx,
+            &[
+                ("locale", self.locale.value(ctx)),
+                ("usage", string_value(ctx, usage)),
+                ("sensitivity", string_value(ctx, sensitivity)),
+                ("ignorePunctuation", JsValue::new(self.ignore_punctuation)),
+                ("collation", string_value(ctx, "default")),
+                ("numeric", JsValue::new(self.numeric)),
+                ("caseFirst", string_value(ctx, case_first)),
+            ],
+        )
+    }
+}
+
+/// Punctuation outside ASCII, the general punctuation block and the CJK and full width forms of
+/// ASCII punctuation.
+fn is_punctuation(c: char) -> bool {
+    matches!(c,
+        '\u{a1}' | '\u{a7}' | '\u{ab}' | '\u{b6}' | '\u{b7}' | '\u{bb}' | '\u{bf}'
+        | '\u{2010}'..='\u{2027}' | '\u{2030}'..='\u{205e}' | '\u{3001}'..='\u{3003}'
+        | '\u{3008}'..='\u{3011}' | '\u{ff01}'..='\u{ff0f}' | '\u{ff1a}'..='\u{ff20}')
+}
+
+pub struct Collator {
+    collator: StringCollator,
+    bound_compare: Option<GcPointer<JsObject>>,
+}
+
+extern "C" fn fsz() -> usize {
+    std::mem::size_of::<Collator>()
+}
+
+extern "C" fn ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize an Intl.Collator");
+}
+
+extern "C" fn deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize an Intl.Collator");
+}
+
+extern "C" fn drop_collator(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<Collator>()) }
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<Collator>().bound_compare.trace(tracer);
+}
+
+define_jsclass!(
+    Collator,
+    Collator,
+    Object,
+    Some(drop_collator),
+    Some(trace),
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl JsClass for Collator {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+pub fn collator_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let collator = StringCollator::new(ctx, args.at(0), args.at(1))?;
+    let proto = ctx.global_data.intl_collator_prototype;
+    Ok(new_object(
+        ctx,
+        args,
+        proto,
+        Collator {
+            collator,
+            bound_compare: None,
+        },
+    ))
+}
+
+/// Getter of `compare`, the bound compare function is created once per `Collator`.
+pub fn collator_compare(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut collator = TypedJsObject::<Collator>::try_from(ctx, args.this)?;
+    if let Some(bound) = collator.bound_compare {
+        return Ok(JsValue::new(bound));
+    }
+    let bound = bound_method(ctx, args.this.get_jsobject(), "", collator_bound_compare, 2);
+    collator.bound_compare = Some(bound);
+    Ok(JsValue::new(bound))
+}
+
+/// Function returned by the `compare` getter, `this` is bound to the `Collator`.
+pub fn collator_bound_compare(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let collator = TypedJsObject::<Collator>::try_from(ctx, args.this)?;
+    let x = args.at(0).to_string(ctx)?;
+    let y = args.at(1).to_string(ctx)?;
+    Ok(JsValue::new(collator.collator.compare(&x, &y) as i32))
+}
+
+pub fn collator_resolved_options(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let collator = TypedJsObject::<Collator>::try_from(ctx, args.this)?;
+    collator.collator.resolved_options(ctx)
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_intl_collator_in_global_data(mut self) -> Result<(), JsValue> {
+        let (mut proto, _) = self.new_intl_class("Collator", collator_constructor, 0)?;
+        def_native_accessor!(self, proto, compare, collator_compare, C)?;
+        def_native_method!(
+            self,
+            proto,
+            resolvedOptions,
+            collator_resolved_options,
+            0,
+            W | C
+        )?;
+        self.global_data.intl_collator_prototype = Some(proto);
+        Ok(())
+    }
+}
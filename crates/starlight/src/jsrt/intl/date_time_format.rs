@@ -0,0 +1,932 @@
+//! `Intl.DateTimeFormat`, the Gregorian calendar fields of time values in a time zone.
+use std::{intrinsics::unlikely, mem::ManuallyDrop, rc::Rc};
+
+use chrono::Utc;
+
+use super::{
+    bound_method, check_numbering_system, coerce_options, default_number_option,
+    get_boolean_option, get_option, get_string_option, locale::Language, new_object,
+    new_options_object, new_parts_array, range_error, resolve_locale, string_value, type_error,
+    Locale,
+};
+use crate::{
+    define_jsclass,
+    jsrt::{
+        date::{civil_from_days, Date, LocalTimeType, TimeZone},
+        temporal::parse_time_zone,
+    },
+    prelude::*,
+    vm::{class::JsClass, context::Context, object::TypedJsObject},
+    JsTryFrom,
+};
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// Width of month and weekday names, in the order of [super::locale::Names].
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Width {
+    Long,
+    Short,
+    Narrow,
+}
+
+impl Width {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Long => "long",
+            Self::Short => "short",
+            Self::Narrow => "narrow",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Digits {
+    Numeric,
+    TwoDigit,
+}
+
+impl Digits {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Numeric => "numeric",
+            Self::TwoDigit => "2-digit",
+        }
+    }
+
+    fn format(self, value: i64) -> String {
+        match self {
+            Self::Numeric => value.to_string(),
+            Self::TwoDigit => format!("{:02}", value),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Month {
+    Digits(Digits),
+    Text(Width),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Full,
+    Long,
+    Medium,
+    Short,
+}
+
+impl Style {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Long => "long",
+            Self::Medium => "medium",
+            Self::Short => "short",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HourCycle {
+    H11,
+    H12,
+    H23,
+    H24,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ZoneName {
+    Short,
+    Long,
+    ShortOffset,
+    LongOffset,
+    ShortGeneric,
+    LongGeneric,
+}
+
+const ZONE_NAMES: [(&str, ZoneName); 6] = [
+    ("short", ZoneName::Short),
+    ("long", ZoneName::Long),
+    ("shortOffset", ZoneName::ShortOffset),
+    ("longOffset", ZoneName::LongOffset),
+    ("shortGeneric", ZoneName::ShortGeneric),
+    ("longGeneric", ZoneName::LongGeneric),
+];
+
+/// Fields a `DateTimeFormat` writes.
+#[derive(Clone, Copy, Default)]
+struct Components {
+    weekday: Option<Width>,
+    year: Option<Digits>,
+    month: Option<Month>,
+    day: Option<Digits>,
+    hour: Option<Digits>,
+    minute: Option<Digits>,
+    second: Option<Digits>,
+    fractional_second_digits: Option<u32>,
+    time_zone_name: Option<ZoneName>,
+}
+
+impl Components {
+    fn has_date(&self) -> bool {
+        self.weekday.is_some() || self.year.is_some() || self.month.is_some() || self.day.is_some()
+    }
+
+    fn has_time(&self) -> bool {
+        self.hour.is_some()
+            || self.minute.is_some()
+            || self.second.is_some()
+            || self.fractional_second_digits.is_some()
+    }
+
+    /// Fields of the date and time styles, the patterns of the locale's CLDR styles.
+    fn from_styles(
+        language: Language,
+        date_style: Option<Style>,
+        time_style: Option<Style>,
+    ) -> Self {
+        use Digits::*;
+        let mut components = Self::default();
+        match date_style {
+            Some(Style::Full) | Some(Style::Long) => {
+                if date_style == Some(Style::Full) {
+                    components.weekday = Some(Width::Long);
+                }
+                components.year = Some(Numeric);
+                components.month = Some(Month::Text(Width::Long));
+                components.day = Some(Numeric);
+            }
+            Some(Style::Medium) => match language {
+                Language::De | Language::Ja => {
+                    components.year = Some(Numeric);
+                    components.month = Some(Month::Digits(TwoDigit));
+                    components.day = Some(TwoDigit);
+                }
+                _ => {
+                    components.year = Some(Numeric);
+                    components.month = Some(Month::Text(Width::Short));
+                    components.day = Some(Numeric);
+                }
+            },
+            Some(Style::Short) => {
+                let (year, month, day) = match language {
+                    Language::En => (TwoDigit, Numeric, Numeric),
+                    Language::De => (TwoDigit, TwoDigit, TwoDigit),
+                    _ => (Numeric, TwoDigit, TwoDigit),
+                };
+                components.year = Some(year);
+                components.month = Some(Month::Digits(month));
+                components.day = Some(day);
+            }
+            None => {}
+        }
+        if let Some(style) = time_style {
+            components.hour = Some(Numeric);
+            components.minute = Some(TwoDigit);
+            if style != Style::Short {
+                components.second = Some(TwoDigit);
+            }
+            components.time_zone_name = match style {
+                Style::Full => Some(ZoneName::Long),
+                Style::Long => Some(ZoneName::Short),
+                _ => None,
+            };
+        }
+        components
+    }
+}
+
+/// Date and time fields of a time value in a time zone.
+struct Fields {
+    year: i64,
+    month: i64,
+    day: i64,
+    /// Days since Sunday.
+    weekday: usize,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    millisecond: i64,
+}
+
+impl Fields {
+    fn new(local: i64) -> Self {
+        let days = local.div_euclid(MILLIS_PER_DAY);
+        let time = local.rem_euclid(MILLIS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        Self {
+            year,
+            month,
+            day,
+            weekday: (days + 4).rem_euclid(7) as usize,
+            hour: time / 3_600_000,
+            minute: time / 60_000 % 60,
+            second: time / 1000 % 60,
+            millisecond: time % 1000,
+        }
+    }
+}
+
+/// Which fields `toLocaleString` and friends require and which they add when none are given,
+/// ToDateTimeOptions.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Required {
+    Date,
+    Time,
+    Any,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Defaults {
+    Date,
+    Time,
+    All,
+}
+
+type Parts = Vec<(&'static str, String)>;
+
+/// Resolved options of a `DateTimeFormat`, also used by `toLocaleString` of dates.
+#[derive(Clone)]
+pub(crate) struct DateTimeFormatter {
+    locale: Locale,
+    time_zone: Rc<TimeZone>,
+    hour_cycle: Option<HourCycle>,
+    date_style: Option<Style>,
+    time_style: Option<Style>,
+    components: Components,
+}
+
+impl DateTimeFormatter {
+    /// CreateDateTimeFormat
+    pub(crate) fn new(
+        ctx: GcPointer<Context>,
+        locales: JsValue,
+        options: JsValue,
+        required: Required,
+        defaults: Defaults,
+    ) -> Result<Self, JsValue> {
+        let options = coerce_options(ctx, options)?;
+        let locale = resolve_locale(ctx, locales, options)?;
+        let calendar = get_option(ctx, options, "calendar")?;
+        if !calendar.is_undefined() {
+            let calendar = calendar.to_string(ctx)?;
+            if calendar != "gregory" {
+                return Err(range_error(
+                    ctx,
+                    format!("Intl.DateTimeFormat: unsupported calendar {}", calendar),
+                ));
+            }
+        }
+        check_numbering_system(ctx, options)?;
+        let hour12 = get_boolean_option(ctx, options, "hour12")?;
+        let hour_cycle =
+            get_string_option(ctx, options, "hourCycle", &["h11", "h12", "h23", "h24"])?;
+        let time_zone = get_option(ctx, options, "timeZone")?;
+        let time_zone = if time_zone.is_undefined() {
+            Rc::new(ctx.vm().time_zone.clone())
+        } else {
+            let name = time_zone.to_string(ctx)?;
+            match parse_time_zone(&name) {
+                Some(zone) => Rc::new(zone),
+                None => {
+                    return Err(range_error(
+                        ctx,
+                        format!("Intl.DateTimeFormat: invalid time zone {}", name),
+                    ))
+                }
+            }
+        };
+
+        let width = |name| -> Result<Option<Width>, JsValue> {
+            Ok(
+                match get_string_option(ctx, options, name, &["narrow", "short", "long"])? {
+                    Some("narrow") => Some(Width::Narrow),
+                    Some("short") => Some(Width::Short),
+                    Some(_) => Some(Width::Long),
+                    None => None,
+                },
+            )
+        };
+        let digits = |name| -> Result<Option<Digits>, JsValue> {
+            Ok(
+                match get_string_option(ctx, options, name, &["2-digit", "numeric"])? {
+                    Some("2-digit") => Some(Digits::TwoDigit),
+                    Some(_) => Some(Digits::Numeric),
+                    None => None,
+                },
+            )
+        };
+        let weekday = width("weekday")?;
+        let year = digits("year")?;
+        let month = match get_string_option(
+            ctx,
+            options,
+            "month",
+            &["2-digit", "numeric", "narrow", "short", "long"],
+        )? {
+            Some("2-digit") => Some(Month::Digits(Digits::TwoDigit)),
+            Some("numeric") => Some(Month::Digits(Digits::Numeric)),
+            Some("narrow") => Some(Month::Text(Width::Narrow)),
+            Some("short") => Some(Month::Text(Width::Short)),
+            Some(_) => Some(Month::Text(Width::Long)),
+            None => None,
+        };
+        let day = digits("day")?;
+        let hour = digits("hour")?;
+        let minute = digits("minute")?;
+        let second = digits("second")?;
+        let fractional_second_digits = get_option(ctx, options, "fractionalSecondDigits")?;
+        let fractional_second_digits = default_number_option(
+            ctx,
+            fractional_second_digits,
+            "fractionalSecondDigits",
+            1,
+            3,
+        )?;
+        let names = ZONE_NAMES.map(|(name, _)| name);
+        let time_zone_name = get_string_option(ctx, options, "timeZoneName", &names)?
+            .and_then(|name| ZONE_NAMES.iter().find(|(zone_name, _)| *zone_name == name))
+            .map(|&(_, zone_name)| zone_name);
+        let mut components = Components {
+            weekday,
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            fractional_second_digits,
+            time_zone_name,
+        };
+        get_string_option(ctx, options, "formatMatcher", &["basic", "best fit"])?;
+
+        let style = |name| -> Result<Option<Style>, JsValue> {
+            Ok(
+                match get_string_option(ctx, options, name, &["full", "long", "medium", "short"])? {
+                    Some("full") => Some(Style::Full),
+                    Some("long") => Some(Style::Long),
+                    Some("medium") => Some(Style::Medium),
+                    Some(_) => Some(Style::Short),
+                    None => None,
+                },
+            )
+        };
+        let date_style = style("dateStyle")?;
+        let time_style = style("timeStyle")?;
+        if date_style.is_some() || time_style.is_some() {
+            if unlikely(components.has_date() || components.has_time() || time_zone_name.is_some())
+            {
+                return Err(type_error(
+                    ctx,
+                    "Intl.DateTimeFormat: dateStyle and timeStyle cannot be combined with date or time fields",
+                ));
+            }
+            if unlikely(required == Required::Date && time_style.is_some()) {
+                return Err(type_error(
+                    ctx,
+                    "Intl.DateTimeFormat: timeStyle cannot be used to format dates",
+                ));
+            }
+            if unlikely(required == Required::Time && date_style.is_some()) {
+                return Err(type_error(
+                    ctx,
+                    "Intl.DateTimeFormat: dateStyle cannot be used to format times",
+                ));
+            }
+            components = Components::from_styles(locale.language(), date_style, time_style);
+        } else {
+            let need_defaults = match required {
+                Required::Date => !components.has_date(),
+                Required::Time => !components.has_time(),
+                Required::Any => !components.has_date() && !components.has_time(),
+            };
+            if need_defaults && defaults != Defaults::Time {
+                components.year = Some(Digits::Numeric);
+                components.month = Some(Month::Digits(Digits::Numeric));
+                components.day = Some(Digits::Numeric);
+            }
+            if need_defaults && defaults != Defaults::Date {
+                components.hour = Some(Digits::Numeric);
+                components.minute = Some(Digits::Numeric);
+                components.second = Some(Digits::Numeric);
+            }
+        }
+
+        let hour_cycle = components.hour.map(|_| match (hour12, hour_cycle) {
+            (Some(true), _) if locale.language() == Language::Ja => HourCycle::H11,
+            (Some(true), _) => HourCycle::H12,
+            (Some(false), _) => HourCycle::H23,
+            (None, Some("h11")) => HourCycle::H11,
+            (None, Some("h12")) => HourCycle::H12,
+            (None, Some("h23")) => HourCycle::H23,
+            (None, Some(_)) => HourCycle::H24,
+            (None, None) if locale.language().data().hour12 => HourCycle::H12,
+            (None, None) => HourCycle::H23,
+        });
+        Ok(Self {
+            locale,
+            time_zone,
+            hour_cycle,
+            date_style,
+            time_style,
+            components,
+        })
+    }
+
+    /// FormatDateTimePattern of a time value that TimeClip accepted.
+    pub(crate) fn format_to_parts(&self, time: f64) -> Parts {
+        let data = self.locale.language().data();
+        let time = time as i64;
+        let local_time_type = self
+            .time_zone
+            .local_time_type(time.div_euclid(1000))
+            .clone();
+        let fields = Fields::new(time + local_time_type.offset as i64 * 1000);
+        let mut date = vec![];
+        self.push_date(&mut date, &fields);
+        let mut time = vec![];
+        self.push_time(&mut time, &fields);
+        if let Some(style) = self.components.time_zone_name {
+            let name = ("timeZoneName", self.zone_name(style, &local_time_type));
+            if !time.is_empty() {
+                time.push(("literal", " ".to_owned()));
+                time.push(name);
+            } else if !date.is_empty() {
+                date.push(("literal", data.date_time_separator.to_owned()));
+                date.push(name);
+            } else {
+                date.push(name);
+            }
+        }
+        if date.is_empty() || time.is_empty() {
+            date.append(&mut time);
+            return date;
+        }
+        let separator = match self.date_style {
+            Some(Style::Full) | Some(Style::Long) => data.long_date_time_separator,
+            _ => data.date_time_separator,
+        };
+        date.push(("literal", separator.to_owned()));
+        date.append(&mut time);
+        date
+    }
+
+    pub(crate) fn format(&self, time: f64) -> String {
+        self.format_to_parts(time)
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    fn push_date(&self, parts: &mut Parts, fields: &Fields) {
+        let language = self.locale.language();
+        let data = language.data();
+        let components = &self.components;
+        let year = components.year.map(|digits| match digits {
+            Digits::TwoDigit => digits.format(fields.year.rem_euclid(100)),
+            Digits::Numeric => digits.format(fields.year),
+        });
+        let weekday = components
+            .weekday
+            .map(|width| (width, data.weekdays[width as usize][fields.weekday]));
+        let mut push = |kind: &'static str, value: &str| parts.push((kind, value.to_owned()));
+        let month = match components.month {
+            Some(Month::Text(width)) => {
+                let names = if components.day.is_some() {
+                    &data.months
+                } else {
+                    &data.stand_alone_months
+                };
+                names[width as usize][fields.month as usize - 1]
+            }
+            month => {
+                // numeric dates, fr and ru always write two digits for days and months
+                let count = [year.is_some(), month.is_some(), components.day.is_some()]
+                    .iter()
+                    .filter(|&&present| present)
+                    .count();
+                let pad = count > 1 && matches!(language, Language::Fr | Language::Ru);
+                let pad = |digits: Digits| if pad { Digits::TwoDigit } else { digits };
+                let month = month.map(|month| match month {
+                    Month::Digits(digits) => pad(digits).format(fields.month),
+                    Month::Text(_) => unreachable!(),
+                });
+                let day = components.day.map(|digits| pad(digits).format(fields.day));
+                let (order, separator) = match language {
+                    Language::En => ([(1, "month"), (2, "day"), (0, "year")], "/"),
+                    Language::Ja => ([(0, "year"), (1, "month"), (2, "day")], "/"),
+                    Language::De | Language::Ru => ([(2, "day"), (1, "month"), (0, "year")], "."),
+                    Language::Fr => ([(2, "day"), (1, "month"), (0, "year")], "/"),
+                };
+                let values = [year, month, day];
+                let date = order
+                    .iter()
+                    .filter_map(|&(index, kind)| Some((kind, values[index].clone()?)))
+                    .collect::<Vec<_>>();
+                if let Some((_, weekday)) = weekday.filter(|_| language != Language::Ja) {
+                    push("weekday", weekday);
+                    if !date.is_empty() {
+                        push("literal", if language == Language::Fr { " " } else { ", " });
+                    }
+                }
+                for (index, (kind, value)) in date.iter().enumerate() {
+                    if index != 0 {
+                        push("literal", separator);
+                    }
+                    push(kind, value);
+                }
+                if language == Language::De && values[2].is_some() && values[0].is_none() {
+                    push("literal", ".");
+                }
+                if let Some((width, weekday)) = weekday.filter(|_| language == Language::Ja) {
+                    push_ja_weekday(&mut push, width, weekday, date.is_empty());
+                }
+                return;
+            }
+        };
+        let day = components.day.map(|digits| digits.format(fields.day));
+        match language {
+            Language::En => {
+                if let Some((_, weekday)) = weekday {
+                    push("weekday", weekday);
+                    push("literal", ", ");
+                }
+                push("month", month);
+                if let Some(day) = &day {
+                    push("literal", " ");
+                    push("day", day);
+                }
+                if let Some(year) = &year {
+                    push("literal", if day.is_some() { ", " } else { " " });
+                    push("year", year);
+                }
+            }
+            Language::Ja => {
+                if let Some(year) = &year {
+                    push("year", year);
+                    push("literal", "年");
+                }
+                push("month", month);
+                if let Some(day) = &day {
+                    push("day", day);
+                    push("literal", "日");
+                }
+                if let Some((width, weekday)) = weekday {
+                    push_ja_weekday(&mut push, width, weekday, false);
+                }
+            }
+            Language::De | Language::Fr | Language::Ru => {
+                if let Some((_, weekday)) = weekday {
+                    push("weekday", weekday);
+                    push("literal", if language == Language::Fr { " " } else { ", " });
+                }
+                if let Some(day) = &day {
+                    push("day", day);
+                    push("literal", if language == Language::De { ". " } else { " " });
+                }
+                push("month", month);
+                if let Some(year) = &year {
+                    push("literal", " ");
+                    push("year", year);
+                    if language == Language::Ru {
+                        push("literal", " г.");
+                    }
+                }
+            }
+        }
+    }
+
+    fn push_time(&self, parts: &mut Parts, fields: &Fields) {
+        let language = self.locale.language();
+        let data = language.data();
+        let components = &self.components;
+        if !components.has_time() {
+            return;
+        }
+        let twelve_hours = matches!(self.hour_cycle, Some(HourCycle::H11 | HourCycle::H12));
+        let hour = components.hour.map(|digits| {
+            let hour = match self.hour_cycle {
+                Some(HourCycle::H11) => fields.hour % 12,
+                Some(HourCycle::H12) if fields.hour % 12 == 0 => 12,
+                Some(HourCycle::H12) => fields.hour % 12,
+                Some(HourCycle::H24) if fields.hour == 0 => 24,
+                _ => fields.hour,
+            };
+            if !twelve_hours && language != Language::Ja {
+                Digits::TwoDigit.format(hour)
+            } else {
+                digits.format(hour)
+            }
+        });
+        let minute = components.minute.map(|digits| {
+            if hour.is_some() {
+                Digits::TwoDigit.format(fields.minute)
+            } else {
+                digits.format(fields.minute)
+            }
+        });
+        let second = components.second.map(|digits| {
+            if hour.is_some() || minute.is_some() {
+                Digits::TwoDigit.format(fields.second)
+            } else {
+                digits.format(fields.second)
+            }
+        });
+        let day_period = data.day_periods[(fields.hour >= 12) as usize];
+        if twelve_hours && language == Language::Ja {
+            parts.push(("dayPeriod", day_period.to_owned()));
+        }
+        let clock = [("hour", &hour), ("minute", &minute), ("second", &second)];
+        for (kind, value) in clock.iter() {
+            if let Some(value) = value {
+                if !parts.is_empty() && parts.last().unwrap().0 != "dayPeriod" {
+                    parts.push(("literal", ":".to_owned()));
+                }
+                parts.push((kind, value.clone()));
+            }
+        }
+        if let Some(digits) = components.fractional_second_digits {
+            let fraction = format!("{:03}", fields.millisecond);
+            if !parts.is_empty() {
+                parts.push(("literal", data.decimal.to_owned()));
+            }
+            parts.push(("fractionalSecond", fraction[..digits as usize].to_owned()));
+        }
+        if hour.is_some() && minute.is_none() && second.is_none() {
+            let suffix = match language {
+                Language::De if !twelve_hours => " Uhr",
+                Language::Fr if !twelve_hours => " h",
+                Language::Ja => "時",
+                _ => "",
+            };
+            if !suffix.is_empty() {
+                parts.push(("literal", suffix.to_owned()));
+            }
+        }
+        if twelve_hours && language != Language::Ja {
+            parts.push(("literal", data.day_period_separator.to_owned()));
+            parts.push(("dayPeriod", day_period.to_owned()));
+        }
+    }
+
+    fn zone_name(&self, style: ZoneName, local_time_type: &LocalTimeType) -> String {
+        let language = self.locale.language();
+        let data = language.data();
+        let is_utc = matches!(self.time_zone.name(), "UTC" | "Etc/UTC" | "Etc/GMT");
+        let abbreviation = &local_time_type.abbreviation;
+        let long = matches!(
+            style,
+            ZoneName::Long | ZoneName::LongOffset | ZoneName::LongGeneric
+        );
+        match style {
+            ZoneName::Short | ZoneName::ShortGeneric if is_utc => return "UTC".to_owned(),
+            ZoneName::Long | ZoneName::LongGeneric if is_utc => return data.utc_name.to_owned(),
+            ZoneName::Short | ZoneName::ShortGeneric
+                if language == Language::En
+                    && abbreviation.len() > 1
+                    && abbreviation.bytes().all(|c| c.is_ascii_alphabetic()) =>
+            {
+                return abbreviation.to_string()
+            }
+            _ => {}
+        }
+        let offset = local_time_type.offset / 60;
+        if offset == 0 {
+            return data.gmt.to_owned();
+        }
+        let sign = if offset < 0 { '-' } else { '+' };
+        let (hours, minutes) = (offset.abs() / 60, offset.abs() % 60);
+        if long {
+            format!("{}{}{:02}:{:02}", data.gmt, sign, hours, minutes)
+        } else if minutes == 0 {
+            format!("{}{}{}", data.gmt, sign, hours)
+        } else {
+            format!("{}{}{}:{:02}", data.gmt, sign, hours, minutes)
+        }
+    }
+
+    fn resolved_options(&self, ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
+        let mut properties = vec![
+            ("locale", self.locale.value(ctx)),
+            ("calendar", string_value(ctx, "gregory")),
+            ("numberingSystem", string_value(ctx, "latn")),
+            ("timeZone", string_value(ctx, self.time_zone.name())),
+        ];
+        if let Some(hour_cycle) = self.hour_cycle {
+            let name = match hour_cycle {
+                HourCycle::H11 => "h11",
+                HourCycle::H12 => "h12",
+                HourCycle::H23 => "h23",
+                HourCycle::H24 => "h24",
+            };
+            properties.push(("hourCycle", string_value(ctx, name)));
+            let hour12 = matches!(hour_cycle, HourCycle::H11 | HourCycle::H12);
+            properties.push(("hour12", JsValue::new(hour12)));
+        }
+        if self.date_style.is_none() && self.time_style.is_none() {
+            let components = &self.components;
+            if let Some(width) = components.weekday {
+                properties.push(("weekday", string_value(ctx, width.name())));
+            }
+            if let Some(digits) = components.year {
+                properties.push(("year", string_value(ctx, digits.name())));
+            }
+            if let Some(month) = components.month {
+                let name = match month {
+                    Month::Digits(digits) => digits.name(),
+                    Month::Text(width) => width.name(),
+                };
+                properties.push(("month", string_value(ctx, name)));
+            }
+            let clock = [
+                ("day", components.day),
+                ("hour", components.hour),
+                ("minute", components.minute),
+                ("second", components.second),
+            ];
+            for (name, digits) in clock.iter() {
+                if let Some(digits) = digits {
+                    properties.push((name, string_value(ctx, digits.name())));
+                }
+            }
+            if let Some(digits) = components.fractional_second_digits {
+                properties.push(("fractionalSecondDigits", JsValue::new(digits as i32)));
+            }
+            if let Some(style) = components.time_zone_name {
+                let (name, _) = ZONE_NAMES.iter().find(|(_, name)| *name == style).unwrap();
+                properties.push(("timeZoneName", string_value(ctx, name)));
+            }
+        }
+        if let Some(style) = self.date_style {
+            properties.push(("dateStyle", string_value(ctx, style.name())));
+        }
+        if let Some(style) = self.time_style {
+            properties.push(("timeStyle", string_value(ctx, style.name())));
+        }
+        new_options_object(ctx, &properties)
+    }
+}
+
+/// Weekday after a Japanese date, in parentheses unless it is the long name.
+fn push_ja_weekday(
+    push: &mut impl FnMut(&'static str, &str),
+    width: Width,
+    weekday: &str,
+    alone: bool,
+) {
+    if alone || width == Width::Long {
+        push("weekday", weekday);
+    } else {
+        push("literal", "(");
+        push("weekday", weekday);
+        push("literal", ")");
+    }
+}
+
+/// The time value to format, the current time when `value` is undefined.
+fn to_time_value(ctx: GcPointer<Context>, value: JsValue) -> Result<f64, JsValue> {
+    let time = if value.is_undefined() {
+        Utc::now().timestamp_millis() as f64
+    } else {
+        value.to_number(ctx)?
+    };
+    match Date::time_clip(time) {
+        Some(time) if time.is_finite() => Ok(time.trunc()),
+        _ => Err(range_error(ctx, "Intl.DateTimeFormat: invalid time value")),
+    }
+}
+
+pub struct DateTimeFormat {
+    formatter: DateTimeFormatter,
+    bound_format: Option<GcPointer<JsObject>>,
+}
+
+extern "C" fn fsz() -> usize {
+    std::mem::size_of::<DateTimeFormat>()
+}
+
+extern "C" fn ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize an Intl.DateTimeFormat");
+}
+
+extern "C" fn deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize an Intl.DateTimeFormat");
+}
+
+extern "C" fn drop_date_time_format(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<DateTimeFormat>()) }
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<DateTimeFormat>().bound_format.trace(tracer);
+}
+
+define_jsclass!(
+    DateTimeFormat,
+    DateTimeFormat,
+    Object,
+    Some(drop_date_time_format),
+    Some(trace),
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl JsClass for DateTimeFormat {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+pub fn date_time_format_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let formatter =
+        DateTimeFormatter::new(ctx, args.at(0), args.at(1), Required::Any, Defaults::Date)?;
+    let proto = ctx.global_data.intl_date_time_format_prototype;
+    Ok(new_object(
+        ctx,
+        args,
+        proto,
+        DateTimeFormat {
+            formatter,
+            bound_format: None,
+        },
+    ))
+}
+
+/// Getter of `format`, the bound format function is created once per `DateTimeFormat`.
+pub fn date_time_format_format(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut format = TypedJsObject::<DateTimeFormat>::try_from(ctx, args.this)?;
+    if let Some(bound) = format.bound_format {
+        return Ok(JsValue::new(bound));
+    }
+    let bound = bound_method(
+        ctx,
+        args.this.get_jsobject(),
+        "",
+        date_time_format_bound_format,
+        1,
+    );
+    format.bound_format = Some(bound);
+    Ok(JsValue::new(bound))
+}
+
+/// Function returned by the `format` getter, `this` is bound to the `DateTimeFormat`.
+pub fn date_time_format_bound_format(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let format = TypedJsObject::<DateTimeFormat>::try_from(ctx, args.this)?;
+    let time = to_time_value(ctx, args.at(0))?;
+    Ok(string_value(ctx, format.formatter.format(time)))
+}
+
+pub fn date_time_format_format_to_parts(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let format = TypedJsObject::<DateTimeFormat>::try_from(ctx, args.this)?;
+    let time = to_time_value(ctx, args.at(0))?;
+    new_parts_array(ctx, &format.formatter.format_to_parts(time))
+}
+
+pub fn date_time_format_resolved_options(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let format = TypedJsObject::<DateTimeFormat>::try_from(ctx, args.this)?;
+    format.formatter.resolved_options(ctx)
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_intl_date_time_format_in_global_data(mut self) -> Result<(), JsValue> {
+        let (mut proto, _) =
+            self.new_intl_class("DateTimeFormat", date_time_format_constructor, 0)?;
+        def_native_accessor!(self, proto, format, date_time_format_format, C)?;
+        def_native_method!(
+            self,
+            proto,
+            formatToParts,
+            date_time_format_format_to_parts,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            resolvedOptions,
+            date_time_format_resolved_options,
+            0,
+            W | C
+        )?;
+        self.global_data.intl_date_time_format_prototype = Some(proto);
+        Ok(())
+    }
+}
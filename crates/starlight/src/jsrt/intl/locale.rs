@@ -0,0 +1,436 @@
+//! Bundled locale data, a small excerpt of CLDR.
+
+/// Language of a bundled locale, regional variants share the data of their language.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Language {
+    De,
+    En,
+    Fr,
+    Ja,
+    Ru,
+}
+
+impl Language {
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        match tag.split('-').next()? {
+            "de" => Some(Self::De),
+            "en" => Some(Self::En),
+            "fr" => Some(Self::Fr),
+            "ja" => Some(Self::Ja),
+            "ru" => Some(Self::Ru),
+            _ => None,
+        }
+    }
+
+    pub fn data(self) -> &'static LocaleData {
+        match self {
+            Self::De => &DE,
+            Self::En => &EN,
+            Self::Fr => &FR,
+            Self::Ja => &JA,
+            Self::Ru => &RU,
+        }
+    }
+}
+
+/// Names in long, short and narrow width.
+pub type Names<const N: usize> = [[&'static str; N]; 3];
+
+pub struct LocaleData {
+    pub decimal: &'static str,
+    pub group: &'static str,
+    pub nan: &'static str,
+    /// Between the number and the percent sign.
+    pub percent_separator: &'static str,
+    /// Whether the currency symbol comes before the number, otherwise it follows it after a
+    /// no-break space.
+    pub currency_first: bool,
+    /// Whether negative amounts in the accounting currency sign are written in parentheses.
+    pub accounting_parentheses: bool,
+    /// Months in the format context, next to a day.
+    pub months: Names<12>,
+    /// Months on their own.
+    pub stand_alone_months: Names<12>,
+    /// Weekdays from Sunday.
+    pub weekdays: Names<7>,
+    pub day_periods: [&'static str; 2],
+    /// Whether 12-hour clocks are the default.
+    pub hour12: bool,
+    /// Between the time and the day period.
+    pub day_period_separator: &'static str,
+    /// Prefix of time zone offsets, `GMT` in `GMT+1`.
+    pub gmt: &'static str,
+    pub utc_name: &'static str,
+    /// Between a date and a time.
+    pub date_time_separator: &'static str,
+    /// Between the long or full date style and a time.
+    pub long_date_time_separator: &'static str,
+}
+
+const LATIN_NARROW_MONTHS: [&str; 12] =
+    ["J", "F", "M", "A", "M", "J", "J", "A", "S", "O", "N", "D"];
+
+static DE: LocaleData = LocaleData {
+    decimal: ",",
+    group: ".",
+    nan: "NaN",
+    percent_separator: "\u{a0}",
+    currency_first: false,
+    accounting_parentheses: false,
+    months: [
+        [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        [
+            "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.",
+            "Dez.",
+        ],
+        LATIN_NARROW_MONTHS,
+    ],
+    stand_alone_months: [
+        [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        [
+            "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+        ],
+        LATIN_NARROW_MONTHS,
+    ],
+    weekdays: [
+        [
+            "Sonntag",
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+        ],
+        ["So.", "Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa."],
+        ["S", "M", "D", "M", "D", "F", "S"],
+    ],
+    day_periods: ["AM", "PM"],
+    hour12: false,
+    day_period_separator: " ",
+    gmt: "GMT",
+    utc_name: "Koordinierte Weltzeit",
+    date_time_separator: ", ",
+    long_date_time_separator: " um ",
+};
+
+static EN: LocaleData = LocaleData {
+    decimal: ".",
+    group: ",",
+    nan: "NaN",
+    percent_separator: "",
+    currency_first: true,
+    accounting_parentheses: true,
+    months: [
+        [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+        [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ],
+        LATIN_NARROW_MONTHS,
+    ],
+    stand_alone_months: [
+        [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+        [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ],
+        LATIN_NARROW_MONTHS,
+    ],
+    weekdays: [
+        [
+            "Sunday",
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+        ],
+        ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"],
+        ["S", "M", "T", "W", "T", "F", "S"],
+    ],
+    day_periods: ["AM", "PM"],
+    hour12: true,
+    day_period_separator: "\u{202f}",
+    gmt: "GMT",
+    utc_name: "Coordinated Universal Time",
+    date_time_separator: ", ",
+    long_date_time_separator: " at ",
+};
+
+static FR: LocaleData = LocaleData {
+    decimal: ",",
+    group: "\u{202f}",
+    nan: "NaN",
+    percent_separator: "\u{202f}",
+    currency_first: false,
+    accounting_parentheses: false,
+    months: [
+        [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+            "nov.", "déc.",
+        ],
+        LATIN_NARROW_MONTHS,
+    ],
+    stand_alone_months: [
+        [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+            "nov.", "déc.",
+        ],
+        LATIN_NARROW_MONTHS,
+    ],
+    weekdays: [
+        [
+            "dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi",
+        ],
+        ["dim.", "lun.", "mar.", "mer.", "jeu.", "ven.", "sam."],
+        ["D", "L", "M", "M", "J", "V", "S"],
+    ],
+    day_periods: ["AM", "PM"],
+    hour12: false,
+    day_period_separator: " ",
+    gmt: "UTC",
+    utc_name: "temps universel coordonné",
+    date_time_separator: " ",
+    long_date_time_separator: " à ",
+};
+
+const JA_MONTHS: [&str; 12] = [
+    "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+];
+
+static JA: LocaleData = LocaleData {
+    decimal: ".",
+    group: ",",
+    nan: "NaN",
+    percent_separator: "",
+    currency_first: true,
+    accounting_parentheses: true,
+    months: [JA_MONTHS, JA_MONTHS, JA_MONTHS],
+    stand_alone_months: [JA_MONTHS, JA_MONTHS, JA_MONTHS],
+    weekdays: [
+        [
+            "日曜日",
+            "月曜日",
+            "火曜日",
+            "水曜日",
+            "木曜日",
+            "金曜日",
+            "土曜日",
+        ],
+        ["日", "月", "火", "水", "木", "金", "土"],
+        ["日", "月", "火", "水", "木", "金", "土"],
+    ],
+    day_periods: ["午前", "午後"],
+    hour12: false,
+    day_period_separator: "",
+    gmt: "GMT",
+    utc_name: "協定世界時",
+    date_time_separator: " ",
+    long_date_time_separator: " ",
+};
+
+static RU: LocaleData = LocaleData {
+    decimal: ",",
+    group: "\u{a0}",
+    nan: "не\u{a0}число",
+    percent_separator: "\u{a0}",
+    currency_first: false,
+    accounting_parentheses: false,
+    months: [
+        [
+            "января",
+            "февраля",
+            "марта",
+            "апреля",
+            "мая",
+            "июня",
+            "июля",
+            "августа",
+            "сентября",
+            "октября",
+            "ноября",
+            "декабря",
+        ],
+        [
+            "янв.",
+            "февр.",
+            "мар.",
+            "апр.",
+            "мая",
+            "июн.",
+            "июл.",
+            "авг.",
+            "сент.",
+            "окт.",
+            "нояб.",
+            "дек.",
+        ],
+        ["Я", "Ф", "М", "А", "М", "И", "И", "А", "С", "О", "Н", "Д"],
+    ],
+    stand_alone_months: [
+        [
+            "январь",
+            "февраль",
+            "март",
+            "апрель",
+            "май",
+            "июнь",
+            "июль",
+            "август",
+            "сентябрь",
+            "октябрь",
+            "ноябрь",
+            "декабрь",
+        ],
+        [
+            "янв.",
+            "февр.",
+            "март",
+            "апр.",
+            "май",
+            "июнь",
+            "июль",
+            "авг.",
+            "сент.",
+            "окт.",
+            "нояб.",
+            "дек.",
+        ],
+        ["Я", "Ф", "М", "А", "М", "И", "И", "А", "С", "О", "Н", "Д"],
+    ],
+    weekdays: [
+        [
+            "воскресенье",
+            "понедельник",
+            "вторник",
+            "среда",
+            "четверг",
+            "пятница",
+            "суббота",
+        ],
+        ["вс", "пн", "вт", "ср", "чт", "пт", "сб"],
+        ["В", "П", "В", "С", "Ч", "П", "С"],
+    ],
+    day_periods: ["AM", "PM"],
+    hour12: false,
+    day_period_separator: " ",
+    gmt: "GMT",
+    utc_name: "Всемирное координированное время",
+    date_time_separator: ", ",
+    long_date_time_separator: ", ",
+};
+
+/// Symbols of a few common currencies in `de`, `en`, `fr`, `ja` and `ru`, followed by the narrow
+/// symbol. Other currencies are written with their code.
+const CURRENCY_SYMBOLS: [(&str, [&str; 6]); 11] = [
+    ("AUD", ["AU$", "A$", "$AU", "A$", "A$", "$"]),
+    ("CAD", ["CA$", "CA$", "$CA", "CA$", "CA$", "$"]),
+    ("CHF", ["CHF", "CHF", "CHF", "CHF", "CHF", "CHF"]),
+    ("CNY", ["CN¥", "CN¥", "CNY", "元", "CN¥", "¥"]),
+    ("EUR", ["€", "€", "€", "€", "€", "€"]),
+    ("GBP", ["£", "£", "£GB", "£", "£", "£"]),
+    ("INR", ["₹", "₹", "₹", "₹", "₹", "₹"]),
+    ("JPY", ["¥", "¥", "JPY", "￥", "¥", "¥"]),
+    ("KRW", ["₩", "₩", "₩", "₩", "₩", "₩"]),
+    ("RUB", ["RUB", "RUB", "RUB", "RUB", "₽", "₽"]),
+    ("USD", ["$", "$", "$US", "$", "$", "$"]),
+];
+
+/// Symbol of the currency `code` in `language`, or its narrow symbol.
+pub fn currency_symbol(code: &str, language: Language, narrow: bool) -> &str {
+    let index = if narrow { 5 } else { language as usize };
+    CURRENCY_SYMBOLS
+        .iter()
+        .find(|(currency, _)| *currency == code)
+        .map_or(code, |(_, symbols)| symbols[index])
+}
+
+/// Number of minor unit digits of the currency `code`, ISO 4217.
+pub fn currency_digits(code: &str) -> u32 {
+    match code {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
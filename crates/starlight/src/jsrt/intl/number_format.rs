@@ -0,0 +1,824 @@
+//! `Intl.NumberFormat`, decimal, percent and currency styles.
+use std::mem::ManuallyDrop;
+
+use num::BigInt;
+
+use super::{
+    bound_method, check_numbering_system, coerce_options, default_number_option, get_option,
+    get_string_option, locale, new_object, new_options_object, new_parts_array, range_error,
+    resolve_locale, string_value, type_error, Locale,
+};
+use crate::{
+    define_jsclass,
+    prelude::*,
+    vm::{class::JsClass, context::Context, object::TypedJsObject},
+    JsTryFrom,
+};
+
+/// Non-negative decimal `0.digits × 10^exponent`, without leading or trailing zero digits.
+#[derive(Clone, Debug)]
+pub(crate) struct Decimal {
+    digits: Vec<u8>,
+    exponent: i32,
+}
+
+impl Decimal {
+    fn new(mut digits: Vec<u8>, mut exponent: i32) -> Self {
+        let leading = digits.iter().take_while(|&&digit| digit == 0).count();
+        digits.drain(..leading);
+        exponent -= leading as i32;
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        if digits.is_empty() {
+            exponent = 0;
+        }
+        Self { digits, exponent }
+    }
+
+    /// Shortest digits that round-trip to `value`, which has to be finite. Rounding these instead
+    /// of the exact binary value formats `1.005` with two digits as `1.01`.
+    fn from_f64(value: f64) -> Self {
+        let text = format!("{:e}", value.abs());
+        let (mantissa, exponent) = text.split_once('e').unwrap();
+        let digits = mantissa
+            .bytes()
+            .filter(u8::is_ascii_digit)
+            .map(|digit| digit - b'0')
+            .collect();
+        Self::new(digits, exponent.parse::<i32>().unwrap() + 1)
+    }
+
+    fn from_integer(digits: &str) -> Self {
+        let digits = digits.bytes().map(|digit| digit - b'0').collect::<Vec<_>>();
+        let exponent = digits.len() as i32;
+        Self::new(digits, exponent)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Rounds half away from zero to the first `count` digits.
+    fn round_to_digits(&mut self, count: i32) {
+        if count < 0 {
+            self.digits.clear();
+        } else if (count as usize) < self.digits.len() {
+            let count = count as usize;
+            let round_up = self.digits[count] >= 5;
+            self.digits.truncate(count);
+            if round_up {
+                match self.digits.iter().rposition(|&digit| digit != 9) {
+                    Some(index) => {
+                        self.digits.truncate(index + 1);
+                        self.digits[index] += 1;
+                    }
+                    None => {
+                        self.digits = vec![1];
+                        self.exponent += 1;
+                    }
+                }
+            }
+        }
+        *self = Self::new(std::mem::take(&mut self.digits), self.exponent);
+    }
+
+    /// Digits before and after the decimal point, the integer part is empty below one.
+    fn split(&self) -> (String, String) {
+        let digit = |digit: &u8| (b'0' + digit) as char;
+        let length = self.digits.len() as i32;
+        let mut integer = String::new();
+        let mut fraction = String::new();
+        if self.exponent > 0 {
+            let end = self.exponent.min(length) as usize;
+            integer.extend(self.digits[..end].iter().map(digit));
+            integer.extend((length..self.exponent).map(|_| '0'));
+        }
+        if self.exponent < length {
+            fraction.extend((self.exponent..0).map(|_| '0'));
+            let start = self.exponent.max(0) as usize;
+            fraction.extend(self.digits[start..].iter().map(digit));
+        }
+        (integer, fraction)
+    }
+}
+
+/// Rounding of formatted numbers, [DigitOptions::new].
+#[derive(Clone, Copy)]
+pub(crate) enum Rounding {
+    Fraction { minimum: u32, maximum: u32 },
+    Significant { minimum: u32, maximum: u32 },
+}
+
+/// Digit options shared by `NumberFormat` and `PluralRules`.
+#[derive(Clone, Copy)]
+pub(crate) struct DigitOptions {
+    minimum_integer_digits: u32,
+    rounding: Rounding,
+}
+
+impl DigitOptions {
+    /// SetNumberFormatDigitOptions with the default fraction digits of the style.
+    pub(crate) fn new(
+        ctx: GcPointer<Context>,
+        options: Option<GcPointer<JsObject>>,
+        default_minimum: u32,
+        default_maximum: u32,
+    ) -> Result<Self, JsValue> {
+        let minimum_integer_digits = get_option(ctx, options, "minimumIntegerDigits")?;
+        let minimum_integer_digits =
+            default_number_option(ctx, minimum_integer_digits, "minimumIntegerDigits", 1, 21)?
+                .unwrap_or(1);
+        let minimum_fraction = get_option(ctx, options, "minimumFractionDigits")?;
+        let maximum_fraction = get_option(ctx, options, "maximumFractionDigits")?;
+        let minimum_significant = get_option(ctx, options, "minimumSignificantDigits")?;
+        let maximum_significant = get_option(ctx, options, "maximumSignificantDigits")?;
+        let rounding = if !minimum_significant.is_undefined() || !maximum_significant.is_undefined()
+        {
+            let minimum =
+                default_number_option(ctx, minimum_significant, "minimumSignificantDigits", 1, 21)?
+                    .unwrap_or(1);
+            let maximum = default_number_option(
+                ctx,
+                maximum_significant,
+                "maximumSignificantDigits",
+                minimum,
+                21,
+            )?
+            .unwrap_or(21);
+            Rounding::Significant { minimum, maximum }
+        } else {
+            let minimum =
+                default_number_option(ctx, minimum_fraction, "minimumFractionDigits", 0, 100)?;
+            let maximum =
+                default_number_option(ctx, maximum_fraction, "maximumFractionDigits", 0, 100)?;
+            let (minimum, maximum) = match (minimum, maximum) {
+                (None, None) => (default_minimum, default_maximum),
+                (Some(minimum), None) => (minimum, minimum.max(default_maximum)),
+                (None, Some(maximum)) => (default_minimum.min(maximum), maximum),
+                (Some(minimum), Some(maximum)) if minimum > maximum => {
+                    return Err(range_error(
+                        ctx,
+                        "Intl: maximumFractionDigits is less than minimumFractionDigits",
+                    ))
+                }
+                (Some(minimum), Some(maximum)) => (minimum, maximum),
+            };
+            Rounding::Fraction { minimum, maximum }
+        };
+        Ok(Self {
+            minimum_integer_digits,
+            rounding,
+        })
+    }
+
+    /// Rounds `decimal` and returns its integer and fraction digits padded to the minimums.
+    pub(crate) fn apply(&self, decimal: &mut Decimal) -> (String, String) {
+        match self.rounding {
+            Rounding::Fraction { maximum, .. } => {
+                decimal.round_to_digits(decimal.exponent + maximum as i32)
+            }
+            Rounding::Significant { maximum, .. } => decimal.round_to_digits(maximum as i32),
+        }
+        let (mut integer, mut fraction) = decimal.split();
+        let padding = match self.rounding {
+            Rounding::Fraction { minimum, .. } => minimum as usize,
+            Rounding::Significant { minimum, .. } => {
+                let shown = if decimal.is_zero() {
+                    1
+                } else {
+                    decimal.digits.len().max(decimal.exponent.max(0) as usize)
+                };
+                fraction.len() + (minimum as usize).saturating_sub(shown)
+            }
+        };
+        while fraction.len() < padding {
+            fraction.push('0');
+        }
+        while integer.len() < self.minimum_integer_digits as usize {
+            integer.insert(0, '0');
+        }
+        (integer, fraction)
+    }
+
+    pub(crate) fn resolved_options(&self, properties: &mut Vec<(&'static str, JsValue)>) {
+        properties.push((
+            "minimumIntegerDigits",
+            JsValue::new(self.minimum_integer_digits as i32),
+        ));
+        match self.rounding {
+            Rounding::Fraction { minimum, maximum } => {
+                properties.push(("minimumFractionDigits", JsValue::new(minimum as i32)));
+                properties.push(("maximumFractionDigits", JsValue::new(maximum as i32)));
+            }
+            Rounding::Significant { minimum, maximum } => {
+                properties.push(("minimumSignificantDigits", JsValue::new(minimum as i32)));
+                properties.push(("maximumSignificantDigits", JsValue::new(maximum as i32)));
+            }
+        }
+    }
+}
+
+/// Mathematical value to format.
+pub(crate) enum Number {
+    NaN,
+    Infinity { negative: bool },
+    Finite { negative: bool, decimal: Decimal },
+}
+
+impl Number {
+    pub(crate) fn from_f64(value: f64) -> Self {
+        if value.is_nan() {
+            Self::NaN
+        } else if value.is_infinite() {
+            Self::Infinity {
+                negative: value < 0.0,
+            }
+        } else {
+            Self::Finite {
+                negative: value.is_sign_negative(),
+                decimal: Decimal::from_f64(value),
+            }
+        }
+    }
+
+    pub(crate) fn from_bigint(value: &BigInt) -> Self {
+        let digits = value.to_string();
+        Self::Finite {
+            negative: digits.starts_with('-'),
+            decimal: Decimal::from_integer(digits.trim_start_matches('-')),
+        }
+    }
+
+    /// ToIntlMathematicalValue, BigInts keep all their digits.
+    fn from_value(ctx: GcPointer<Context>, value: JsValue) -> Result<Self, JsValue> {
+        let value = value.to_primitive(ctx, JsHint::Number)?;
+        if value.is_bigint() {
+            Ok(Self::from_bigint(value.get_bigint().value()))
+        } else {
+            value.to_number(ctx).map(Self::from_f64)
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Decimal,
+    Percent,
+    Currency,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CurrencyDisplay {
+    Code,
+    Symbol,
+    NarrowSymbol,
+    /// There are no display names, the code is written after the number.
+    Name,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Grouping {
+    Always,
+    Auto,
+    Min2,
+    Off,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SignDisplay {
+    Auto,
+    Never,
+    Always,
+    ExceptZero,
+    Negative,
+}
+
+/// Resolved options of a `NumberFormat`, also used by `toLocaleString` of numbers and BigInts.
+#[derive(Clone)]
+pub(crate) struct NumberFormatter {
+    locale: Locale,
+    style: Style,
+    currency: Option<Box<str>>,
+    currency_display: CurrencyDisplay,
+    accounting: bool,
+    digits: DigitOptions,
+    grouping: Grouping,
+    sign_display: SignDisplay,
+}
+
+impl NumberFormatter {
+    /// InitializeNumberFormat
+    pub(crate) fn new(
+        ctx: GcPointer<Context>,
+        locales: JsValue,
+        options: JsValue,
+    ) -> Result<Self, JsValue> {
+        let options = coerce_options(ctx, options)?;
+        let locale = resolve_locale(ctx, locales, options)?;
+        check_numbering_system(ctx, options)?;
+
+        let style = match get_string_option(
+            ctx,
+            options,
+            "style",
+            &["decimal", "percent", "currency", "unit"],
+        )? {
+            None | Some("decimal") => Style::Decimal,
+            Some("percent") => Style::Percent,
+            Some("currency") => Style::Currency,
+            Some(style) => {
+                return Err(range_error(
+                    ctx,
+                    format!("Intl.NumberFormat: style {} is not supported", style),
+                ))
+            }
+        };
+        let currency = get_option(ctx, options, "currency")?;
+        let currency = if currency.is_undefined() {
+            None
+        } else {
+            let code = currency.to_string(ctx)?;
+            if code.len() != 3 || !code.bytes().all(|c| c.is_ascii_alphabetic()) {
+                return Err(range_error(
+                    ctx,
+                    format!("Intl.NumberFormat: invalid currency code {}", code),
+                ));
+            }
+            Some(code.to_ascii_uppercase().into_boxed_str())
+        };
+        let currency_display = match get_string_option(
+            ctx,
+            options,
+            "currencyDisplay",
+            &["code", "symbol", "narrowSymbol", "name"],
+        )? {
+            Some("code") => CurrencyDisplay::Code,
+            Some("narrowSymbol") => CurrencyDisplay::NarrowSymbol,
+            Some("name") => CurrencyDisplay::Name,
+            _ => CurrencyDisplay::Symbol,
+        };
+        let accounting =
+            get_string_option(ctx, options, "currencySign", &["standard", "accounting"])?
+                == Some("accounting");
+        if style == Style::Currency && currency.is_none() {
+            return Err(type_error(
+                ctx,
+                "Intl.NumberFormat: currency style requires a currency",
+            ));
+        }
+
+        if let Some(notation) = get_string_option(
+            ctx,
+            options,
+            "notation",
+            &["standard", "scientific", "engineering", "compact"],
+        )? {
+            if notation != "standard" {
+                return Err(range_error(
+                    ctx,
+                    format!("Intl.NumberFormat: notation {} is not supported", notation),
+                ));
+            }
+        }
+        let (default_minimum, default_maximum) = match (style, &currency) {
+            (Style::Currency, Some(code)) => {
+                let digits = locale::currency_digits(code);
+                (digits, digits)
+            }
+            (Style::Percent, _) => (0, 0),
+            _ => (0, 3),
+        };
+        let digits = DigitOptions::new(ctx, options, default_minimum, default_maximum)?;
+
+        let grouping = get_option(ctx, options, "useGrouping")?;
+        let grouping = if grouping.is_undefined() {
+            Grouping::Auto
+        } else if grouping.is_bool() {
+            if grouping.get_bool() {
+                Grouping::Always
+            } else {
+                Grouping::Off
+            }
+        } else {
+            match grouping.to_string(ctx)?.as_str() {
+                "always" => Grouping::Always,
+                "min2" => Grouping::Min2,
+                "auto" | "true" | "false" => Grouping::Auto,
+                _ if !grouping.to_boolean() => Grouping::Off,
+                value => {
+                    return Err(range_error(
+                        ctx,
+                        format!(
+                            "Intl: {} is not a valid value for option useGrouping",
+                            value
+                        ),
+                    ))
+                }
+            }
+        };
+        let sign_display = match get_string_option(
+            ctx,
+            options,
+            "signDisplay",
+            &["auto", "never", "always", "exceptZero", "negative"],
+        )? {
+            Some("never") => SignDisplay::Never,
+            Some("always") => SignDisplay::Always,
+            Some("exceptZero") => SignDisplay::ExceptZero,
+            Some("negative") => SignDisplay::Negative,
+            _ => SignDisplay::Auto,
+        };
+        Ok(Self {
+            locale,
+            style,
+            currency: if style == Style::Currency {
+                currency
+            } else {
+                None
+            },
+            currency_display,
+            accounting,
+            digits,
+            grouping,
+            sign_display,
+        })
+    }
+
+    /// PartitionNumberPattern
+    pub(crate) fn format_to_parts(&self, number: Number) -> Vec<(&'static str, String)> {
+        let data = self.locale.language().data();
+        let mut body = vec![];
+        let (negative, zero) = match number {
+            Number::NaN => {
+                body.push(("nan", data.nan.to_owned()));
+                (false, true)
+            }
+            Number::Infinity { negative } => {
+                body.push(("infinity", "∞".to_owned()));
+                (negative, false)
+            }
+            Number::Finite {
+                negative,
+                mut decimal,
+            } => {
+                if self.style == Style::Percent && !decimal.is_zero() {
+                    decimal.exponent += 2;
+                }
+                let (integer, fraction) = self.digits.apply(&mut decimal);
+                self.push_integer(&mut body, &integer, data.group);
+                if !fraction.is_empty() {
+                    body.push(("decimal", data.decimal.to_owned()));
+                    body.push(("fraction", fraction));
+                }
+                (negative, decimal.is_zero())
+            }
+        };
+        let sign = match self.sign_display {
+            SignDisplay::Auto if negative => Some("-"),
+            SignDisplay::Always => Some(if negative { "-" } else { "+" }),
+            SignDisplay::ExceptZero if !zero => Some(if negative { "-" } else { "+" }),
+            SignDisplay::Negative if negative && !zero => Some("-"),
+            _ => None,
+        };
+        let parentheses = sign == Some("-")
+            && self.accounting
+            && data.accounting_parentheses
+            && self.currency.is_some();
+
+        let mut parts = vec![];
+        if parentheses {
+            parts.push(("literal", "(".to_owned()));
+        } else {
+            match sign {
+                Some("-") => parts.push(("minusSign", "-".to_owned())),
+                Some(sign) => parts.push(("plusSign", sign.to_owned())),
+                None => {}
+            }
+        }
+        match (self.style, &self.currency) {
+            (Style::Currency, Some(code)) => {
+                let symbol = match self.currency_display {
+                    CurrencyDisplay::Code | CurrencyDisplay::Name => code,
+                    CurrencyDisplay::Symbol => {
+                        locale::currency_symbol(code, self.locale.language(), false)
+                    }
+                    CurrencyDisplay::NarrowSymbol => {
+                        locale::currency_symbol(code, self.locale.language(), true)
+                    }
+                };
+                if data.currency_first && self.currency_display != CurrencyDisplay::Name {
+                    parts.push(("currency", symbol.to_owned()));
+                    if symbol.chars().last().map_or(false, char::is_alphabetic) {
+                        parts.push(("literal", "\u{a0}".to_owned()));
+                    }
+                    parts.extend(body);
+                } else {
+                    parts.extend(body);
+                    parts.push(("literal", "\u{a0}".to_owned()));
+                    parts.push(("currency", symbol.to_owned()));
+                }
+            }
+            (Style::Percent, _) => {
+                parts.extend(body);
+                if !data.percent_separator.is_empty() {
+                    parts.push(("literal", data.percent_separator.to_owned()));
+                }
+                parts.push(("percentSign", "%".to_owned()));
+            }
+            _ => parts.extend(body),
+        }
+        if parentheses {
+            parts.push(("literal", ")".to_owned()));
+        }
+        parts
+    }
+
+    pub(crate) fn format(&self, number: Number) -> String {
+        self.format_to_parts(number)
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    /// Integer digits in groups of three.
+    fn push_integer(&self, parts: &mut Vec<(&'static str, String)>, integer: &str, group: &str) {
+        let grouped = match self.grouping {
+            Grouping::Off => false,
+            Grouping::Min2 => integer.len() >= 5,
+            Grouping::Always | Grouping::Auto => integer.len() >= 4,
+        };
+        if !grouped {
+            parts.push(("integer", integer.to_owned()));
+            return;
+        }
+        let first = match integer.len() % 3 {
+            0 => 3,
+            first => first,
+        };
+        parts.push(("integer", integer[..first].to_owned()));
+        for start in (first..integer.len()).step_by(3) {
+            parts.push(("group", group.to_owned()));
+            parts.push(("integer", integer[start..start + 3].to_owned()));
+        }
+    }
+
+    fn resolved_options(&self, ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
+        let mut properties = vec![
+            ("locale", self.locale.value(ctx)),
+            ("numberingSystem", string_value(ctx, "latn")),
+        ];
+        let style = match self.style {
+            Style::Decimal => "decimal",
+            Style::Percent => "percent",
+            Style::Currency => "currency",
+        };
+        properties.push(("style", string_value(ctx, style)));
+        if let Some(code) = &self.currency {
+            properties.push(("currency", string_value(ctx, code)));
+            let display = match self.currency_display {
+                CurrencyDisplay::Code => "code",
+                CurrencyDisplay::Symbol => "symbol",
+                CurrencyDisplay::NarrowSymbol => "narrowSymbol",
+                CurrencyDisplay::Name => "name",
+            };
+            properties.push(("currencyDisplay", string_value(ctx, display)));
+            let sign = if self.accounting {
+                "accounting"
+            } else {
+                "standard"
+            };
+            properties.push(("currencySign", string_value(ctx, sign)));
+        }
+        self.digits.resolved_options(&mut properties);
+        let grouping = match self.grouping {
+            Grouping::Always => string_value(ctx, "always"),
+            Grouping::Auto => string_value(ctx, "auto"),
+            Grouping::Min2 => string_value(ctx, "min2"),
+            Grouping::Off => JsValue::new(false),
+        };
+        properties.push(("useGrouping", grouping));
+        properties.push(("notation", string_value(ctx, "standard")));
+        let sign_display = match self.sign_display {
+            SignDisplay::Auto => "auto",
+            SignDisplay::Never => "never",
+            SignDisplay::Always => "always",
+            SignDisplay::ExceptZero => "exceptZero",
+            SignDisplay::Negative => "negative",
+        };
+        properties.push(("signDisplay", string_value(ctx, sign_display)));
+        properties.push(("roundingMode", string_value(ctx, "halfExpand")));
+        new_options_object(ctx, &properties)
+    }
+}
+
+pub struct NumberFormat {
+    formatter: NumberFormatter,
+    bound_format: Option<GcPointer<JsObject>>,
+}
+
+extern "C" fn fsz() -> usize {
+    std::mem::size_of::<NumberFormat>()
+}
+
+extern "C" fn ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize an Intl.NumberFormat");
+}
+
+extern "C" fn deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize an Intl.NumberFormat");
+}
+
+extern "C" fn drop_number_format(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<NumberFormat>()) }
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<NumberFormat>().bound_format.trace(tracer);
+}
+
+define_jsclass!(
+    NumberFormat,
+    NumberFormat,
+    Object,
+    Some(drop_number_format),
+    Some(trace),
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl JsClass for NumberFormat {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+pub fn number_format_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let formatter = NumberFormatter::new(ctx, args.at(0), args.at(1))?;
+    let proto = ctx.global_data.intl_number_format_prototype;
+    Ok(new_object(
+        ctx,
+        args,
+        proto,
+        NumberFormat {
+            formatter,
+            bound_format: None,
+        },
+    ))
+}
+
+/// Getter of `format`, the bound format function is created once per `NumberFormat`.
+pub fn number_format_format(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut format = TypedJsObject::<NumberFormat>::try_from(ctx, args.this)?;
+    if let Some(bound) = format.bound_format {
+        return Ok(JsValue::new(bound));
+    }
+    let bound = bound_method(
+        ctx,
+        args.this.get_jsobject(),
+        "",
+        number_format_bound_format,
+        1,
+    );
+    format.bound_format = Some(bound);
+    Ok(JsValue::new(bound))
+}
+
+/// Function returned by the `format` getter, `this` is bound to the `NumberFormat`.
+pub fn number_format_bound_format(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let format = TypedJsObject::<NumberFormat>::try_from(ctx, args.this)?;
+    let number = Number::from_value(ctx, args.at(0))?;
+    Ok(string_value(ctx, format.formatter.format(number)))
+}
+
+pub fn number_format_format_to_parts(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let format = TypedJsObject::<NumberFormat>::try_from(ctx, args.this)?;
+    let number = Number::from_value(ctx, args.at(0))?;
+    new_parts_array(ctx, &format.formatter.format_to_parts(number))
+}
+
+pub fn number_format_resolved_options(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let format = TypedJsObject::<NumberFormat>::try_from(ctx, args.this)?;
+    format.formatter.resolved_options(ctx)
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_intl_number_format_in_global_data(mut self) -> Result<(), JsValue> {
+        let (mut proto, _) = self.new_intl_class("NumberFormat", number_format_constructor, 0)?;
+        def_native_accessor!(self, proto, format, number_format_format, C)?;
+        def_native_method!(
+            self,
+            proto,
+            formatToParts,
+            number_format_format_to_parts,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            resolvedOptions,
+            number_format_resolved_options,
+            0,
+            W | C
+        )?;
+        self.global_data.intl_number_format_prototype = Some(proto);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_digit_options_rounding() {
+        let fraction = |minimum, maximum| Rounding::Fraction { minimum, maximum };
+        let significant = |minimum, maximum| Rounding::Significant { minimum, maximum };
+        let cases = [
+            (1.005, fraction(0, 2), ("1", "01")),
+            (1.0, fraction(2, 2), ("1", "00")),
+            (2.5, fraction(0, 0), ("3", "")),
+            (0.4, fraction(0, 0), ("0", "")),
+            (0.0005, fraction(0, 3), ("0", "001")),
+            (999.9995, fraction(0, 3), ("1000", "")),
+            (1.5e-7, fraction(0, 8), ("0", "00000015")),
+            (1e21, fraction(0, 0), ("1000000000000000000000", "")),
+            (123456.0, significant(1, 3), ("123000", "")),
+            (0.00123, significant(1, 2), ("0", "0012")),
+            (1.5, significant(5, 21), ("1", "5000")),
+            (0.0, significant(3, 21), ("0", "00")),
+        ];
+        for (value, rounding, (integer, fraction)) in cases.iter() {
+            let options = DigitOptions {
+                minimum_integer_digits: 1,
+                rounding: *rounding,
+            };
+            let mut decimal = Decimal::from_f64(*value);
+            assert_eq!(
+                options.apply(&mut decimal),
+                (integer.to_string(), fraction.to_string()),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_per_locale() {
+        assert_eval_cases(&[
+            // grouping separators
+            ("new Intl.NumberFormat('en').format(1234567.891)", "1,234,567.891"),
+            ("new Intl.NumberFormat('de-DE').format(1234567.891)", "1.234.567,891"),
+            ("new Intl.NumberFormat('fr').format(1234567.891)", "1\u{202f}234\u{202f}567,891"),
+            ("new Intl.NumberFormat('ru').format(1234567.891)", "1\u{a0}234\u{a0}567,891"),
+            ("new Intl.NumberFormat('ja').format(1234567.891)", "1,234,567.891"),
+            ("new Intl.NumberFormat('en').format(123)", "123"),
+            ("new Intl.NumberFormat('en').format(1234)", "1,234"),
+            ("new Intl.NumberFormat('en', { useGrouping: 'min2' }).format(1234)", "1234"),
+            ("new Intl.NumberFormat('en', { useGrouping: 'min2' }).format(12345)", "12,345"),
+            ("new Intl.NumberFormat('de', { useGrouping: false }).format(1234567)", "1234567"),
+            ("new Intl.NumberFormat('en').format(BigInt('12345678901234567890'))", "12,345,678,901,234,567,890"),
+            // rounding
+            ("new Intl.NumberFormat('en', { maximumFractionDigits: 2 }).format(1.005)", "1.01"),
+            ("new Intl.NumberFormat('de', { maximumFractionDigits: 0 }).format(-2.5)", "-3"),
+            ("new Intl.NumberFormat('en', { maximumFractionDigits: 0 }).format(-0.4)", "-0"),
+            ("new Intl.NumberFormat('en').format(999.9995)", "1,000"),
+            ("new Intl.NumberFormat('fr', { minimumFractionDigits: 2 }).format(1)", "1,00"),
+            ("new Intl.NumberFormat('en', { maximumSignificantDigits: 3 }).format(123456)", "123,000"),
+            ("new Intl.NumberFormat('ru', { minimumSignificantDigits: 5 }).format(1.5)", "1,5000"),
+            ("new Intl.NumberFormat('en', { minimumIntegerDigits: 3 }).format(7)", "007"),
+            // percent and currency
+            ("new Intl.NumberFormat('en', { style: 'percent' }).format(0.256)", "26%"),
+            ("new Intl.NumberFormat('de', { style: 'percent' }).format(0.256)", "26\u{a0}%"),
+            ("new Intl.NumberFormat('fr', { style: 'percent' }).format(0.256)", "26\u{202f}%"),
+            ("new Intl.NumberFormat('en', { style: 'currency', currency: 'USD' }).format(1234.5)", "$1,234.50"),
+            ("new Intl.NumberFormat('de', { style: 'currency', currency: 'EUR' }).format(1234.5)", "1.234,50\u{a0}€"),
+            ("new Intl.NumberFormat('fr', { style: 'currency', currency: 'USD' }).format(1234.5)", "1\u{202f}234,50\u{a0}$US"),
+            ("new Intl.NumberFormat('ja', { style: 'currency', currency: 'JPY' }).format(1234.5)", "￥1,235"),
+            ("new Intl.NumberFormat('ru', { style: 'currency', currency: 'RUB' }).format(5)", "5,00\u{a0}₽"),
+            ("new Intl.NumberFormat('en', { style: 'currency', currency: 'CHF' }).format(5)", "CHF\u{a0}5.00"),
+            ("new Intl.NumberFormat('en', { style: 'currency', currency: 'USD', currencySign: 'accounting' }).format(-5)", "($5.00)"),
+            ("new Intl.NumberFormat('de', { style: 'currency', currency: 'EUR', currencySign: 'accounting' }).format(-5)", "-5,00\u{a0}€"),
+            // sign display
+            ("new Intl.NumberFormat('en', { signDisplay: 'always' }).format(1)", "+1"),
+            ("new Intl.NumberFormat('en', { signDisplay: 'exceptZero' }).format(-0)", "0"),
+            ("new Intl.NumberFormat('en', { signDisplay: 'negative' }).format(-0)", "0"),
+        ]);
+    }
+}
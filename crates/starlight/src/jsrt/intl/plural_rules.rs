@@ -0,0 +1,196 @@
+//! `Intl.PluralRules`, the CLDR cardinal and ordinal plural rules of the bundled languages.
+use std::{intrinsics::unlikely, mem::ManuallyDrop};
+
+use super::{
+    coerce_options, get_string_option,
+    locale::Language,
+    new_object, new_options_object, new_string_array,
+    number_format::{DigitOptions, Number},
+    resolve_locale, string_value, type_error, Locale,
+};
+use crate::{
+    define_jsclass,
+    prelude::*,
+    vm::{class::JsClass, context::Context, object::TypedJsObject},
+    JsTryFrom,
+};
+
+/// Plural operands of a formatted number, `i` modulo 10^18 and the count of fraction digits `v`.
+struct Operands {
+    integer: u64,
+    /// Whether `i` has more than 18 digits.
+    large: bool,
+    fraction_digits: usize,
+}
+
+impl Operands {
+    fn new(integer: &str, fraction: &str) -> Self {
+        let integer = integer.trim_start_matches('0');
+        let start = integer.len().saturating_sub(18);
+        Self {
+            integer: integer[start..].parse().unwrap_or(0),
+            large: start != 0,
+            fraction_digits: fraction.len(),
+        }
+    }
+
+    fn is_integer(&self, value: u64) -> bool {
+        self.fraction_digits == 0 && !self.large && self.integer == value
+    }
+}
+
+pub struct PluralRules {
+    locale: Locale,
+    ordinal: bool,
+    digits: DigitOptions,
+}
+
+extern "C" fn fsz() -> usize {
+    std::mem::size_of::<PluralRules>()
+}
+
+extern "C" fn ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize an Intl.PluralRules");
+}
+
+extern "C" fn deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize an Intl.PluralRules");
+}
+
+extern "C" fn drop_plural_rules(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<PluralRules>()) }
+}
+
+define_jsclass!(
+    PluralRules,
+    PluralRules,
+    Object,
+    Some(drop_plural_rules),
+    None,
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl JsClass for PluralRules {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+impl PluralRules {
+    /// Categories in the CLDR order `zero`, `one`, `two`, `few`, `many`, `other`.
+    fn categories(&self) -> &'static [&'static str] {
+        match (self.locale.language(), self.ordinal) {
+            (Language::En, false) | (Language::De, false) => &["one", "other"],
+            (Language::En, true) => &["one", "two", "few", "other"],
+            (Language::Fr, false) => &["one", "many", "other"],
+            (Language::Fr, true) => &["one", "other"],
+            (Language::Ru, false) => &["one", "few", "many", "other"],
+            _ => &["other"],
+        }
+    }
+
+    /// ResolvePlural of a finite number.
+    fn select(&self, value: f64) -> &'static str {
+        let mut decimal = match Number::from_f64(value) {
+            Number::Finite { decimal, .. } => decimal,
+            _ => return "other",
+        };
+        let (integer, fraction) = self.digits.apply(&mut decimal);
+        let operands = Operands::new(&integer, &fraction);
+        let i = operands.integer;
+        let visible_integer = operands.fraction_digits == 0;
+        match (self.locale.language(), self.ordinal) {
+            (Language::En, false) | (Language::De, false) if operands.is_integer(1) => "one",
+            (Language::En, true) if visible_integer => match (i % 10, i % 100) {
+                (1, hundreds) if hundreds != 11 => "one",
+                (2, hundreds) if hundreds != 12 => "two",
+                (3, hundreds) if hundreds != 13 => "few",
+                _ => "other",
+            },
+            (Language::Fr, false) if i <= 1 && !operands.large => "one",
+            (Language::Fr, false)
+                if visible_integer && i % 1_000_000 == 0 && (i != 0 || operands.large) =>
+            {
+                "many"
+            }
+            (Language::Fr, true) if operands.is_integer(1) => "one",
+            (Language::Ru, false) if visible_integer => match (i % 10, i % 100) {
+                (1, hundreds) if hundreds != 11 => "one",
+                (2..=4, hundreds) if !(12..=14).contains(&hundreds) => "few",
+                _ => "many",
+            },
+            _ => "other",
+        }
+    }
+}
+
+pub fn plural_rules_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    if unlikely(!args.ctor_call) {
+        return Err(type_error(
+            ctx,
+            "Intl.PluralRules constructor requires 'new'",
+        ));
+    }
+    let options = coerce_options(ctx, args.at(1))?;
+    let locale = resolve_locale(ctx, args.at(0), options)?;
+    let ordinal =
+        get_string_option(ctx, options, "type", &["cardinal", "ordinal"])? == Some("ordinal");
+    let digits = DigitOptions::new(ctx, options, 0, 3)?;
+    let proto = ctx.global_data.intl_plural_rules_prototype;
+    Ok(new_object(
+        ctx,
+        args,
+        proto,
+        PluralRules {
+            locale,
+            ordinal,
+            digits,
+        },
+    ))
+}
+
+pub fn plural_rules_select(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let rules = TypedJsObject::<PluralRules>::try_from(ctx, args.this)?;
+    let value = args.at(0).to_number(ctx)?;
+    Ok(string_value(ctx, rules.select(value)))
+}
+
+pub fn plural_rules_resolved_options(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let rules = TypedJsObject::<PluralRules>::try_from(ctx, args.this)?;
+    let kind = if rules.ordinal { "ordinal" } else { "cardinal" };
+    let mut properties = vec![
+        ("locale", rules.locale.value(ctx)),
+        ("type", string_value(ctx, kind)),
+    ];
+    rules.digits.resolved_options(&mut properties);
+    properties.push((
+        "pluralCategories",
+        new_string_array(ctx, rules.categories()),
+    ));
+    new_options_object(ctx, &properties)
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_intl_plural_rules_in_global_data(mut self) -> Result<(), JsValue> {
+        let (mut proto, _) = self.new_intl_class("PluralRules", plural_rules_constructor, 0)?;
+        def_native_method!(self, proto, select, plural_rules_select, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            resolvedOptions,
+            plural_rules_resolved_options,
+            0,
+            W | C
+        )?;
+        self.global_data.intl_plural_rules_prototype = Some(proto);
+        Ok(())
+    }
+}
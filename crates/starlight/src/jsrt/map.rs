@@ -0,0 +1,576 @@
+//! `Map` and `Set`, both keep their entries in a [`crate::vm::map::MapStorage`] in insertion
+//! order.
+use std::{
+    intrinsics::unlikely,
+    mem::{size_of, ManuallyDrop},
+};
+
+use crate::{
+    constant::S_CONSTURCTOR,
+    define_jsclass,
+    prelude::*,
+    vm::{
+        class::JsClass,
+        context::Context,
+        interpreter::SpreadValue,
+        map::{storage_of, JsMap, JsSet},
+        object::TypedJsObject,
+        operations,
+    },
+    JsTryFrom,
+};
+
+#[derive(Clone, Copy)]
+enum IterationKind {
+    Key,
+    Value,
+    KeyValue,
+}
+
+/// `Map` and `Set` iterators, they see entries added during iteration.
+pub struct JsMapIterator {
+    /// `None` once the iterator is exhausted.
+    collection: Option<GcPointer<JsObject>>,
+    index: usize,
+    kind: IterationKind,
+}
+
+extern "C" fn iterator_fsz() -> usize {
+    size_of::<JsMapIterator>()
+}
+
+extern "C" fn iterator_ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize a Map Iterator");
+}
+
+extern "C" fn iterator_deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize a Map Iterator");
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn iterator_trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<JsMapIterator>().collection.trace(tracer);
+}
+
+define_jsclass!(
+    JsMapIterator,
+    MapIterator,
+    Object,
+    None,
+    Some(iterator_trace),
+    Some(iterator_deser),
+    Some(iterator_ser),
+    Some(iterator_fsz)
+);
+
+impl JsClass for JsMapIterator {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+fn type_error(ctx: GcPointer<Context>, message: impl AsRef<str>) -> JsValue {
+    JsValue::new(ctx.new_type_error(message))
+}
+
+/// Structure of the object a constructor creates, from new.target so subclasses keep theirs.
+fn constructor_structure(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    name: &str,
+    structure: GcPointer<Structure>,
+) -> Result<GcPointer<Structure>, JsValue> {
+    if unlikely(!args.ctor_call) {
+        return Err(type_error(
+            ctx,
+            format!("{} constructor requires 'new'", name),
+        ));
+    }
+    let proto = if args.this.is_jsobject() {
+        args.this.get_jsobject().prototype().copied()
+    } else {
+        None
+    };
+    match proto {
+        Some(proto) if structure.prototype() != Some(&proto) => {
+            Ok(Structure::new_indexed(ctx, Some(proto), false))
+        }
+        _ => Ok(structure),
+    }
+}
+
+/// Calls `adder` of `collection` with the entries of `iterable`, `pairs` splits them into key
+/// and value first.
+fn add_entries_from_iterable(
+    ctx: GcPointer<Context>,
+    collection: GcPointer<JsObject>,
+    iterable: JsValue,
+    adder: &str,
+    pairs: bool,
+) -> Result<(), JsValue> {
+    if iterable.is_undefined() || iterable.is_null() {
+        return Ok(());
+    }
+    let stack = ctx.shadowstack();
+    letroot!(collection = stack, collection);
+    let adder = collection.get(ctx, adder.intern())?;
+    if unlikely(!adder.is_callable()) {
+        return Err(type_error(ctx, "adder is not a function"));
+    }
+    letroot!(entries = stack, SpreadValue::new(ctx, iterable)?);
+    for i in 0..entries.array.len() {
+        let entry = entries.array[i];
+        let mut arguments = if pairs {
+            if unlikely(!entry.is_jsobject()) {
+                return Err(type_error(
+                    ctx,
+                    format!(
+                        "Iterator value {} is not an entry object",
+                        entry.to_string(ctx)?
+                    ),
+                ));
+            }
+            letroot!(entry = stack, entry.get_jsobject());
+            let key = entry.get(ctx, Symbol::Index(0))?;
+            let value = entry.get(ctx, Symbol::Index(1))?;
+            vec![key, value]
+        } else {
+            vec![entry]
+        };
+        operations::call(ctx, adder, JsValue::new(*collection), &mut arguments)?;
+    }
+    Ok(())
+}
+
+/// Calls `callback` for each entry, entries added by `callback` are visited as well.
+fn for_each_entry(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    mut collection: GcPointer<JsObject>,
+    name: &str,
+) -> Result<JsValue, JsValue> {
+    let callback = args.at(0);
+    if unlikely(!callback.is_callable()) {
+        return Err(type_error(
+            ctx,
+            format!("{}.prototype.forEach: callback is not a function", name),
+        ));
+    }
+    let mut index = 0;
+    while let Some((position, key, value)) = storage_of(&mut collection).unwrap().entry_from(index)
+    {
+        let mut arguments = [value, key, args.this];
+        operations::call(ctx, callback, args.at(1), &mut arguments)?;
+        index = position + 1;
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+fn new_iterator(
+    ctx: GcPointer<Context>,
+    collection: GcPointer<JsObject>,
+    kind: IterationKind,
+    structure: GcPointer<Structure>,
+) -> JsValue {
+    let object = JsObject::new(
+        ctx,
+        &structure,
+        JsMapIterator::get_class(),
+        ObjectTag::Ordinary,
+    );
+    *object.data::<JsMapIterator>() = ManuallyDrop::new(JsMapIterator {
+        collection: Some(collection),
+        index: 0,
+        kind,
+    });
+    JsValue::new(object)
+}
+
+fn this_map(ctx: GcPointer<Context>, args: &Arguments) -> Result<TypedJsObject<JsMap>, JsValue> {
+    TypedJsObject::<JsMap>::try_from(ctx, args.this)
+}
+
+fn this_set(ctx: GcPointer<Context>, args: &Arguments) -> Result<TypedJsObject<JsSet>, JsValue> {
+    TypedJsObject::<JsSet>::try_from(ctx, args.this)
+}
+
+pub fn map_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let structure = ctx.global_data().map_structure.unwrap();
+    let structure = constructor_structure(ctx, args, "Map", structure)?;
+    let stack = ctx.shadowstack();
+    letroot!(map = stack, JsMap::new(ctx, &structure));
+    add_entries_from_iterable(ctx, *map, args.at(0), "set", true)?;
+    Ok(JsValue::new(*map))
+}
+
+pub fn map_prototype_get(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let map = this_map(ctx, args)?;
+    Ok(map.storage.get(args.at(0)))
+}
+
+pub fn map_prototype_set(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut map = this_map(ctx, args)?;
+    map.storage.set(args.at(0), args.at(1));
+    Ok(args.this)
+}
+
+pub fn map_prototype_has(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let map = this_map(ctx, args)?;
+    Ok(JsValue::new(map.storage.has(args.at(0))))
+}
+
+pub fn map_prototype_delete(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut map = this_map(ctx, args)?;
+    Ok(JsValue::new(map.storage.delete(args.at(0)).is_some()))
+}
+
+pub fn map_prototype_clear(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut map = this_map(ctx, args)?;
+    map.storage.clear();
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn map_prototype_size(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let map = this_map(ctx, args)?;
+    Ok(JsValue::new(map.storage.size() as u32))
+}
+
+pub fn map_prototype_for_each(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let map = this_map(ctx, args)?;
+    for_each_entry(ctx, args, map.object(), "Map")
+}
+
+fn new_map_iterator(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    kind: IterationKind,
+) -> Result<JsValue, JsValue> {
+    let map = this_map(ctx, args)?;
+    let structure = ctx.global_data().map_iterator_structure.unwrap();
+    Ok(new_iterator(ctx, map.object(), kind, structure))
+}
+
+pub fn map_prototype_keys(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    new_map_iterator(ctx, args, IterationKind::Key)
+}
+
+pub fn map_prototype_values(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    new_map_iterator(ctx, args, IterationKind::Value)
+}
+
+pub fn map_prototype_entries(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    new_map_iterator(ctx, args, IterationKind::KeyValue)
+}
+
+pub fn map_group_by(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let items = args.at(0);
+    if unlikely(items.is_undefined() || items.is_null()) {
+        return Err(type_error(ctx, "Map.groupBy called on null or undefined"));
+    }
+    let callback = args.at(1);
+    if unlikely(!callback.is_callable()) {
+        return Err(type_error(ctx, "Map.groupBy callback must be a function"));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(items = stack, SpreadValue::new(ctx, items)?);
+    let structure = ctx.global_data().map_structure.unwrap();
+    letroot!(map = stack, JsMap::new(ctx, &structure));
+    // keys are compared with SameValueZero, the map holds the index of each group until the
+    // groups become arrays
+    let mut groups: Vec<Vec<JsValue>> = vec![];
+    for k in 0..items.array.len() {
+        let value = items.array[k];
+        let mut arguments = [value, JsValue::new(k as u32)];
+        let key = operations::call(
+            ctx,
+            callback,
+            JsValue::encode_undefined_value(),
+            &mut arguments,
+        )?;
+        let storage = &mut map.data::<JsMap>().storage;
+        if storage.has(key) {
+            groups[storage.get(key).get_number() as usize].push(value);
+        } else {
+            storage.set(key, JsValue::new(groups.len() as u32));
+            groups.push(vec![value]);
+        }
+    }
+    let keys = map
+        .data::<JsMap>()
+        .storage
+        .iter()
+        .map(|(key, _)| key)
+        .collect::<Vec<_>>();
+    for (key, elements) in keys.into_iter().zip(groups.iter()) {
+        let elements = JsArray::from_slice(ctx, elements);
+        map.data::<JsMap>().storage.set(key, JsValue::new(elements));
+    }
+    Ok(JsValue::new(*map))
+}
+
+pub fn set_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let structure = ctx.global_data().set_structure.unwrap();
+    let structure = constructor_structure(ctx, args, "Set", structure)?;
+    let stack = ctx.shadowstack();
+    letroot!(set = stack, JsSet::new(ctx, &structure));
+    add_entries_from_iterable(ctx, *set, args.at(0), "add", false)?;
+    Ok(JsValue::new(*set))
+}
+
+pub fn set_prototype_add(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut set = this_set(ctx, args)?;
+    set.add(args.at(0));
+    Ok(args.this)
+}
+
+pub fn set_prototype_has(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let set = this_set(ctx, args)?;
+    Ok(JsValue::new(set.storage.has(args.at(0))))
+}
+
+pub fn set_prototype_delete(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut set = this_set(ctx, args)?;
+    Ok(JsValue::new(set.storage.delete(args.at(0)).is_some()))
+}
+
+pub fn set_prototype_clear(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut set = this_set(ctx, args)?;
+    set.storage.clear();
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn set_prototype_size(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let set = this_set(ctx, args)?;
+    Ok(JsValue::new(set.storage.size() as u32))
+}
+
+pub fn set_prototype_for_each(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let set = this_set(ctx, args)?;
+    for_each_entry(ctx, args, set.object(), "Set")
+}
+
+fn new_set_iterator(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    kind: IterationKind,
+) -> Result<JsValue, JsValue> {
+    let set = this_set(ctx, args)?;
+    let structure = ctx.global_data().set_iterator_structure.unwrap();
+    Ok(new_iterator(ctx, set.object(), kind, structure))
+}
+
+pub fn set_prototype_values(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    new_set_iterator(ctx, args, IterationKind::Value)
+}
+
+pub fn set_prototype_entries(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    new_set_iterator(ctx, args, IterationKind::KeyValue)
+}
+
+pub fn map_iterator_next(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let this = args.this;
+    if unlikely(!this.is_jsobject() || !this.get_jsobject().is_class(JsMapIterator::get_class())) {
+        return Err(type_error(
+            ctx,
+            "Map Iterator.prototype.next requires that 'this' be a Map or Set Iterator",
+        ));
+    }
+    let iterator = this.get_jsobject();
+    let iterator = iterator.data::<JsMapIterator>();
+    let entry = match iterator.collection {
+        Some(mut collection) => storage_of(&mut collection)
+            .unwrap()
+            .entry_from(iterator.index),
+        None => None,
+    };
+    let stack = ctx.shadowstack();
+    letroot!(result = stack, JsObject::new_empty(ctx));
+    let (value, done) = match entry {
+        Some((position, key, value)) => {
+            iterator.index = position + 1;
+            let value = match iterator.kind {
+                IterationKind::Key => key,
+                IterationKind::Value => value,
+                IterationKind::KeyValue => JsValue::new(JsArray::from_slice(ctx, &[key, value])),
+            };
+            (value, false)
+        }
+        None => {
+            iterator.collection = None;
+            (JsValue::encode_undefined_value(), true)
+        }
+    };
+    result.put(ctx, "value".intern(), value, false)?;
+    result.put(ctx, "done".intern(), JsValue::new(done), false)?;
+    Ok(JsValue::new(*result))
+}
+
+pub fn map_iterator_iterator(
+    _ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Ok(args.this)
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_map_in_global_object(mut self) -> Result<(), JsValue> {
+        let mut global_object = self.global_object();
+        let ctor = self
+            .global_data
+            .map_prototype
+            .unwrap()
+            .get(self, S_CONSTURCTOR.intern())?;
+        def_native_property!(self, global_object, Map, ctor, W | C)?;
+        let ctor = self
+            .global_data
+            .set_prototype
+            .unwrap()
+            .get(self, S_CONSTURCTOR.intern())?;
+        def_native_property!(self, global_object, Set, ctor, W | C)?;
+        Ok(())
+    }
+
+    pub(crate) fn init_map_in_global_data(mut self) -> Result<(), JsValue> {
+        let iterator = "Symbol.iterator".intern().private();
+
+        let mut proto = self.new_collection_class("Map", map_constructor)?;
+        let mut ctor = proto.get(self, S_CONSTURCTOR.intern())?.get_jsobject();
+        def_native_method!(self, ctor, groupBy, map_group_by, 2, W | C)?;
+        def_native_method!(self, proto, get, map_prototype_get, 1, W | C)?;
+        def_native_method!(self, proto, set, map_prototype_set, 2, W | C)?;
+        def_native_method!(self, proto, has, map_prototype_has, 1, W | C)?;
+        def_native_method!(self, proto, delete, map_prototype_delete, 1, W | C)?;
+        def_native_method!(self, proto, clear, map_prototype_clear, 0, W | C)?;
+        def_native_method!(self, proto, forEach, map_prototype_for_each, 1, W | C)?;
+        def_native_accessor!(self, proto, size, map_prototype_size, C)?;
+        def_native_method!(self, proto, keys, map_prototype_keys, 0, W | C)?;
+        def_native_method!(self, proto, values, map_prototype_values, 0, W | C)?;
+        let entries = JsNativeFunction::new(self, "entries".intern(), map_prototype_entries, 0);
+        def_native_property!(self, proto, entries, entries, W | C)?;
+        proto.define_own_property(
+            self,
+            iterator,
+            &*DataDescriptor::new(JsValue::new(entries), W | C),
+            false,
+        )?;
+        self.global_data.map_structure = Some(Structure::new_indexed(self, Some(proto), false));
+        self.global_data.map_prototype = Some(proto);
+        self.global_data.map_iterator_structure =
+            Some(self.new_iterator_structure("Map Iterator")?);
+
+        let mut proto = self.new_collection_class("Set", set_constructor)?;
+        def_native_method!(self, proto, add, set_prototype_add, 1, W | C)?;
+        def_native_method!(self, proto, has, set_prototype_has, 1, W | C)?;
+        def_native_method!(self, proto, delete, set_prototype_delete, 1, W | C)?;
+        def_native_method!(self, proto, clear, set_prototype_clear, 0, W | C)?;
+        def_native_method!(self, proto, forEach, set_prototype_for_each, 1, W | C)?;
+        def_native_accessor!(self, proto, size, set_prototype_size, C)?;
+        def_native_method!(self, proto, entries, set_prototype_entries, 0, W | C)?;
+        let values = JsNativeFunction::new(self, "values".intern(), set_prototype_values, 0);
+        def_native_property!(self, proto, values, values, W | C)?;
+        def_native_property!(self, proto, keys, values, W | C)?;
+        proto.define_own_property(
+            self,
+            iterator,
+            &*DataDescriptor::new(JsValue::new(values), W | C),
+            false,
+        )?;
+        self.global_data.set_structure = Some(Structure::new_indexed(self, Some(proto), false));
+        self.global_data.set_prototype = Some(proto);
+        self.global_data.set_iterator_structure =
+            Some(self.new_iterator_structure("Set Iterator")?);
+        Ok(())
+    }
+
+    /// Prototype object of `Map` or `Set` linked with its constructor.
+    fn new_collection_class(
+        self,
+        name: &str,
+        constructor: JsAPI,
+    ) -> Result<GcPointer<JsObject>, JsValue> {
+        let obj_proto = self.global_data().get_object_prototype();
+        let structure = Structure::new_unique_indexed(self, Some(obj_proto), false);
+        let mut proto = JsObject::new(self, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+        let mut ctor = JsNativeFunction::new(self, name.intern(), constructor, 0);
+        super::define_species(self, &mut ctor)?;
+        def_native_property!(self, ctor, prototype, proto, NONE)?;
+        def_native_property!(self, proto, constructor, ctor, W | C)?;
+        let tag = JsString::new(self, name);
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+        Ok(proto)
+    }
+
+    /// Structure of `Map` or `Set` iterators, their prototype is shared by all of them.
+    fn new_iterator_structure(self, tag: &str) -> Result<GcPointer<Structure>, JsValue> {
+        let iterator = "Symbol.iterator".intern().private();
+        let obj_proto = self.global_data().get_object_prototype();
+        let structure = Structure::new_unique_indexed(self, Some(obj_proto), false);
+        let mut proto = JsObject::new(self, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+        def_native_method!(self, proto, next, map_iterator_next, 0, W | C)?;
+        let func = JsNativeFunction::new(self, iterator, map_iterator_iterator, 0);
+        proto.define_own_property(
+            self,
+            iterator,
+            &*DataDescriptor::new(JsValue::new(func), W | C),
+            false,
+        )?;
+        let tag = JsString::new(self, tag);
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+        Ok(Structure::new_indexed(self, Some(proto), false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_map_and_set() {
+        assert_eval_cases(&[
+            ("new Map([[1, 'a'], [2, 'b']]).get(2)", "b"),
+            ("new Map([[1.0, 'a']]).has(1)", "true"),
+            ("var m = new Map(); m.set(-0, 'z'); m.get(0) + Object.is([...m.keys()][0], 0)", "ztrue"),
+            ("var m = new Map(); m.set(NaN, 1); m.has(NaN)", "true"),
+            ("[...new Map([['b', 1], ['a', 2]]).keys()].join()", "b,a"),
+            ("var m = new Map([[1, 1], [2, 2]]); m.delete(1); m.set(1, 1); [...m.keys()].join()", "2,1"),
+            ("var m = new Map([[1, 1]]); var s = []; m.forEach(function (v, k) { s.push(k); if (k < 3) m.set(k + 1, 0); }); s.join()", "1,2,3"),
+            ("var s = new Set([1, 2, 2, 3]); s.size", "3"),
+            ("var s = new Set(['x']); var it = s.values(); s.clear(); s.add('y'); it.next().value", "y"),
+            ("[...new Set([3, 1, 3]).entries()].join(';')", "3,3;1,1"),
+            ("Object.prototype.toString.call(new Set())", "[object Set]"),
+            ("Object.prototype.toString.call(new Map().entries())", "[object Map Iterator]"),
+            ("function F() {} F.prototype = Object.create(Map.prototype); var f = Reflect.construct(Map, [[[1, 2]]], F); (f instanceof F) + ',' + f.get(1)", "true,2"),
+            ("try { Map(); } catch (e) { e instanceof TypeError }", "true"),
+            ("Map[Symbol.species] === Map && Set[Symbol.species] === Set", "true"),
+            ("var g = Map.groupBy([1, -0, 2, 0, NaN, NaN], function (v) { return v; }); g.size + ':' + g.get(0).length + ':' + g.get(NaN).length", "4:2:2"),
+            ("var g = Map.groupBy('abca', function (c) { return c; }); [...g.keys()].join() + ':' + g.get('a').join('')", "a,b,c:aa"),
+            ("try { Map.groupBy(null, function () {}); } catch (e) { e instanceof TypeError }", "true"),
+            ("var m = new Map([[1, 1], [2, 2], [3, 3]]); var s = []; m.forEach(function (v, k) { s.push(k); if (k === 1) m.delete(2); }); s.join()", "1,3"),
+            ("new Set().add(1).add(1).size", "1"),
+            ("try { new Map([1]); } catch (e) { e instanceof TypeError }", "true"),
+            ("try { Map.prototype.get.call(new Set(), 1); } catch (e) { e instanceof TypeError }", "true"),
+        ]);
+    }
+}
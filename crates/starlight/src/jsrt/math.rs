@@ -38,13 +38,9 @@ pub fn math_atan(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, J
     }
 }
 pub fn math_atan2(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    if args.size() < 1 {
-        let num = args.at(0).to_number(ctx)?;
-        let x = args.at(1).to_number(ctx);
-        Ok(JsValue::new(num.atan2(x?)))
-    } else {
-        Ok(JsValue::encode_nan_value())
-    }
+    let y = args.at(0).to_number(ctx)?;
+    let x = args.at(1).to_number(ctx)?;
+    Ok(JsValue::new(y.atan2(x)))
 }
 
 pub fn math_ceil(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
@@ -103,8 +99,7 @@ pub fn math_trunc(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue,
 pub fn math_log(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     if args.size() != 0 {
         let num = args.at(0).to_number(ctx)?;
-        let y = args.at(1).to_number(ctx)?;
-        Ok(JsValue::new(num.log(y)))
+        Ok(JsValue::new(num.ln()))
     } else {
         Ok(JsValue::encode_nan_value())
     }
@@ -116,30 +111,261 @@ pub fn math_random(_ctx: GcPointer<Context>, _args: &Arguments) -> Result<JsValu
 pub fn math_sqrt(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     Ok(JsValue::new(args.at(0).to_number(ctx)?.sqrt()))
 }
+
+/// Defines `Math` functions that apply `f64 -> f64` function to ToNumber(x).
+macro_rules! math_unary {
+    ($($name: ident => $f: expr),*) => {
+        $(
+            pub fn $name(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+                let f: fn(f64) -> f64 = $f;
+                Ok(JsValue::new(f(args.at(0).to_number(ctx)?)))
+            }
+        )*
+    };
+}
+
+math_unary! {
+    math_tan => f64::tan,
+    math_sinh => f64::sinh,
+    math_cosh => f64::cosh,
+    math_tanh => f64::tanh,
+    math_asinh => f64::asinh,
+    math_acosh => f64::acosh,
+    math_atanh => f64::atanh,
+    math_log2 => f64::log2,
+    math_log10 => f64::log10,
+    math_log1p => f64::ln_1p,
+    math_expm1 => f64::exp_m1,
+    math_cbrt => f64::cbrt,
+    math_fround => |x| x as f32 as f64,
+    math_sign => |x| {
+        if x.is_nan() || x == 0.0 {
+            x
+        } else {
+            x.signum()
+        }
+    },
+    math_round => |x| {
+        if !x.is_finite() || x == 0.0 {
+            return x;
+        }
+        // keep sign of zero for x in [-0.5, 0) and (0, 0.5).
+        if x >= -0.5 && x < 0.5 {
+            return 0.0f64.copysign(x);
+        }
+        let floor = x.floor();
+        // `x + 0.5` is inexact for large x and x = 0.5 - ulp.
+        if x - floor >= 0.5 {
+            floor + 1.0
+        } else {
+            floor
+        }
+    }
+}
+
+pub fn math_pow(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let base = args.at(0).to_number(ctx)?;
+    let exponent = args.at(1).to_number(ctx)?;
+    // powf(1, NaN) and powf(-1, +-Infinity) are 1 in Rust but NaN in JS.
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        return Ok(JsValue::encode_nan_value());
+    }
+    Ok(JsValue::new(base.powf(exponent)))
+}
+
+/// ToUint32(value). Unlike [JsValue::to_uint32] this wraps values modulo 2^32.
+fn to_uint32(ctx: GcPointer<Context>, value: JsValue) -> Result<u32, JsValue> {
+    if value.is_int32() {
+        return Ok(value.get_int32() as u32);
+    }
+    let number = value.to_number(ctx)?;
+    if !number.is_finite() {
+        return Ok(0);
+    }
+    let number = number.trunc() % 4294967296.0;
+    Ok(if number < 0.0 {
+        (number + 4294967296.0) as u32
+    } else {
+        number as u32
+    })
+}
+
+pub fn math_imul(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let a = to_uint32(ctx, args.at(0))?;
+    let b = to_uint32(ctx, args.at(1))?;
+    Ok(JsValue::new(a.wrapping_mul(b) as i32))
+}
+
+pub fn math_clz32(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(
+        to_uint32(ctx, args.at(0))?.leading_zeros() as i32
+    ))
+}
+
+pub fn math_hypot(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut numbers = Vec::with_capacity(args.size());
+    for i in 0..args.size() {
+        numbers.push(args.at(i).to_number(ctx)?);
+    }
+    if numbers.iter().any(|x| x.is_infinite()) {
+        return Ok(JsValue::new(f64::INFINITY));
+    }
+    if numbers.iter().any(|x| x.is_nan()) {
+        return Ok(JsValue::encode_nan_value());
+    }
+    let max = numbers.iter().fold(0.0f64, |max, x| max.max(x.abs()));
+    if max == 0.0 {
+        return Ok(JsValue::new(0));
+    }
+    // scale arguments by the largest one so squares do not overflow.
+    let sum = numbers.iter().map(|x| (x / max) * (x / max)).sum::<f64>();
+    Ok(JsValue::new(sum.sqrt() * max))
+}
+
+pub fn math_max(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.size() == 2 && args.at(0).is_int32() && args.at(1).is_int32() {
+        return Ok(JsValue::new(
+            args.at(0).get_int32().max(args.at(1).get_int32()),
+        ));
+    }
+    let mut result = f64::NEG_INFINITY;
+    for i in 0..args.size() {
+        // all arguments are converted even if NaN is already seen.
+        let number = args.at(i).to_number(ctx)?;
+        if number.is_nan() || result.is_nan() {
+            result = f64::NAN;
+        } else if number > result || (number == 0.0 && result == 0.0 && number.is_sign_positive()) {
+            result = number;
+        }
+    }
+    Ok(JsValue::new(result))
+}
+
+pub fn math_min(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if args.size() == 2 && args.at(0).is_int32() && args.at(1).is_int32() {
+        return Ok(JsValue::new(
+            args.at(0).get_int32().min(args.at(1).get_int32()),
+        ));
+    }
+    let mut result = f64::INFINITY;
+    for i in 0..args.size() {
+        let number = args.at(i).to_number(ctx)?;
+        if number.is_nan() || result.is_nan() {
+            result = f64::NAN;
+        } else if number < result || (number == 0.0 && result == 0.0 && number.is_sign_negative()) {
+            result = number;
+        }
+    }
+    Ok(JsValue::new(result))
+}
 impl GcPointer<Context> {
     pub(crate) fn init_math_in_global_object(mut self) -> Result<(), JsValue> {
         let mut math = JsObject::new_empty(self);
 
-        def_native_method!(self, math, trunc, math_trunc, 1)?;
-        def_native_method!(self, math, floor, math_floor, 1)?;
-        def_native_method!(self, math, log, math_log, 2)?;
-        def_native_method!(self, math, sin, math_sin, 1)?;
-        def_native_method!(self, math, cos, math_cos, 1)?;
-        def_native_method!(self, math, ceil, math_ceil, 1)?;
-        def_native_method!(self, math, exp, math_exp, 1)?;
-        def_native_method!(self, math, abs, math_abs, 1)?;
-        def_native_method!(self, math, random, math_random, 0)?;
-        def_native_method!(self, math, sqrt, math_sqrt, 1)?;
+        def_native_method!(self, math, abs, math_abs, 1, W | C)?;
+        def_native_method!(self, math, acos, math_acos, 1, W | C)?;
+        def_native_method!(self, math, acosh, math_acosh, 1, W | C)?;
+        def_native_method!(self, math, asin, math_asin, 1, W | C)?;
+        def_native_method!(self, math, asinh, math_asinh, 1, W | C)?;
+        def_native_method!(self, math, atan, math_atan, 1, W | C)?;
+        def_native_method!(self, math, atanh, math_atanh, 1, W | C)?;
+        def_native_method!(self, math, atan2, math_atan2, 2, W | C)?;
+        def_native_method!(self, math, cbrt, math_cbrt, 1, W | C)?;
+        def_native_method!(self, math, ceil, math_ceil, 1, W | C)?;
+        def_native_method!(self, math, clz32, math_clz32, 1, W | C)?;
+        def_native_method!(self, math, cos, math_cos, 1, W | C)?;
+        def_native_method!(self, math, cosh, math_cosh, 1, W | C)?;
+        def_native_method!(self, math, exp, math_exp, 1, W | C)?;
+        def_native_method!(self, math, expm1, math_expm1, 1, W | C)?;
+        def_native_method!(self, math, floor, math_floor, 1, W | C)?;
+        def_native_method!(self, math, fround, math_fround, 1, W | C)?;
+        def_native_method!(self, math, hypot, math_hypot, 2, W | C)?;
+        def_native_method!(self, math, imul, math_imul, 2, W | C)?;
+        def_native_method!(self, math, log, math_log, 1, W | C)?;
+        def_native_method!(self, math, log1p, math_log1p, 1, W | C)?;
+        def_native_method!(self, math, log10, math_log10, 1, W | C)?;
+        def_native_method!(self, math, log2, math_log2, 1, W | C)?;
+        def_native_method!(self, math, max, math_max, 2, W | C)?;
+        def_native_method!(self, math, min, math_min, 2, W | C)?;
+        def_native_method!(self, math, pow, math_pow, 2, W | C)?;
+        def_native_method!(self, math, random, math_random, 0, W | C)?;
+        def_native_method!(self, math, round, math_round, 1, W | C)?;
+        def_native_method!(self, math, sign, math_sign, 1, W | C)?;
+        def_native_method!(self, math, sin, math_sin, 1, W | C)?;
+        def_native_method!(self, math, sinh, math_sinh, 1, W | C)?;
+        def_native_method!(self, math, sqrt, math_sqrt, 1, W | C)?;
+        def_native_method!(self, math, tan, math_tan, 1, W | C)?;
+        def_native_method!(self, math, tanh, math_tanh, 1, W | C)?;
+        def_native_method!(self, math, trunc, math_trunc, 1, W | C)?;
 
-        def_native_property!(self, math, PI, std::f64::consts::PI)?;
+        def_native_property!(self, math, E, std::f64::consts::E, NONE)?;
+        def_native_property!(self, math, LN10, std::f64::consts::LN_10, NONE)?;
+        def_native_property!(self, math, LN2, std::f64::consts::LN_2, NONE)?;
+        def_native_property!(self, math, LOG10E, std::f64::consts::LOG10_E, NONE)?;
+        def_native_property!(self, math, LOG2E, std::f64::consts::LOG2_E, NONE)?;
+        def_native_property!(self, math, PI, std::f64::consts::PI, NONE)?;
+        def_native_property!(self, math, SQRT1_2, std::f64::consts::FRAC_1_SQRT_2, NONE)?;
+        def_native_property!(self, math, SQRT2, std::f64::consts::SQRT_2, NONE)?;
+        let tag = JsString::new(self, "Math");
+        math.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
 
         let mut global_object = self.global_object();
 
-        def_native_property!(self, global_object, Math, math)?;
-
-        let source = include_str!("../builtins/Math.js");
-        self.eval_internal(Some("../builtins/Math.js"), false, source, true)?;
+        def_native_property!(self, global_object, Math, math, W | C)?;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_round() {
+        assert_eval_cases(&[
+            ("Object.is(Math.round(-0.5), -0)", "true"),
+            ("Object.is(Math.round(-0.2), -0)", "true"),
+            ("Object.is(Math.round(0.2), 0)", "true"),
+            ("Math.round(-0.500001)", "-1"),
+            ("Math.round(-1.5)", "-1"),
+            ("Math.round(2.5)", "3"),
+            ("Math.round(0.49999999999999994)", "0"),
+        ]);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eval_cases(&[
+            ("Math.pow(1, NaN)", "NaN"),
+            ("Math.pow(-1, Infinity)", "NaN"),
+            ("Math.pow(NaN, 0)", "1"),
+            ("Math.imul(0xffffffff, 5)", "-5"),
+            ("Math.imul(4294967299, 2)", "6"),
+            ("Math.clz32(0)", "32"),
+            ("Math.clz32(-1)", "0"),
+            ("Math.clz32(4294967297)", "31"),
+            ("Math.hypot()", "0"),
+            ("Math.hypot(NaN, -Infinity)", "Infinity"),
+            ("Math.hypot(3, 4)", "5"),
+            ("isFinite(Math.hypot(1e200, 1e200))", "true"),
+            ("Math.max()", "-Infinity"),
+            ("Object.is(Math.max(-0, 0), 0)", "true"),
+            ("Object.is(Math.min(0, -0), -0)", "true"),
+            (
+                "var calls = 0; Math.max(NaN, { valueOf() { calls++; return 1 } }) + ',' + calls",
+                "NaN,1",
+            ),
+            ("Object.is(Math.sign(-0), -0)", "true"),
+            ("Object.is(Math.trunc(-0.9), -0)", "true"),
+            ("Math.cbrt(-8)", "-2"),
+            ("Math.fround(5.05)", "5.050000190734863"),
+            ("Math.atan2(0, -0)", "3.141592653589793"),
+        ]);
+    }
+}
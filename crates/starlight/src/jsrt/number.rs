@@ -1,9 +1,13 @@
-use num::traits::float::FloatCore;
+use num::{traits::float::FloatCore, BigInt, Integer, One};
 
+use super::intl::number_format::{Number, NumberFormatter};
 use crate::{
-    constant::S_CONSTURCTOR,
+    constant::*,
     prelude::*,
-    vm::{context::Context, number::NumberObject},
+    vm::{
+        context::Context,
+        number::{f64_to_string, NumberObject},
+    },
 };
 pub fn number_value_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     let obj = args.this;
@@ -41,13 +45,13 @@ pub fn number_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<J
     if args.ctor_call {
         let mut res = 0.0;
         if args.size() != 0 {
-            res = args.at(0).to_number(ctx)?;
+            res = args.at(0).to_numeric_number(ctx)?;
         }
         Ok(JsValue::new(NumberObject::new(ctx, res)))
     } else if args.size() == 0 {
         return Ok(JsValue::new(0i32));
     } else {
-        return args.at(0).to_number(ctx).map(JsValue::new);
+        return args.at(0).to_numeric_number(ctx).map(JsValue::new);
     }
 }
 pub fn number_is_nan(_ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
@@ -88,8 +92,21 @@ pub fn number_is_integer(_ctx: GcPointer<Context>, args: &Arguments) -> Result<J
     if !num.is_number() {
         return Ok(JsValue::new(false));
     }
+    let num = num.get_number();
+    Ok(JsValue::new(num.is_finite() && num.trunc() == num))
+}
+
+pub fn number_is_safe_integer(
+    _ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let num = args.at(0);
+    if !num.is_number() {
+        return Ok(JsValue::new(false));
+    }
+    let num = num.get_number();
     Ok(JsValue::new(
-        num.get_number() as i32 as f64 == num.get_number(),
+        num.is_finite() && num.trunc() == num && num.abs() <= MAX_SAFE_INTEGER,
     ))
 }
 
@@ -97,120 +114,203 @@ pub fn number_to_int(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValu
     let num = args.at(0);
     num.to_int32(ctx).map(JsValue::new)
 }
-pub fn number_to_precisiion(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let precision_var = args.at(0);
-    let mut this_num = this_number_val(ctx, args.this)?;
-    if precision_var.is_undefined() || !this_num.is_finite() {
-        return number_to_string(ctx, &Arguments::new(args.this, &mut []));
-    }
-
-    let precision = match precision_var.to_int32(ctx)? {
-        x if (1..=100).contains(&x) => x as usize,
-        _ => {
-            let msg = JsString::new(
-                ctx,
-                "precision must be an integer in range between 1 and 100",
-            );
-            return Err(JsValue::new(JsRangeError::new(ctx, msg, None)));
+
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+/// ToIntegerOrInfinity(value)
+pub(crate) fn to_integer_or_infinity(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+) -> Result<f64, JsValue> {
+    let num = value.to_number(ctx)?;
+    if num.is_nan() {
+        return Ok(0.0);
+    }
+    Ok(num.trunc())
+}
+
+/// Finite non-negative `value` multiplied by 10^`pow10` and rounded to the nearest integer,
+/// ties are rounded up. Computed exactly from the binary representation of `value`.
+fn scaled_round(value: f64, pow10: i32) -> BigInt {
+    let (mantissa, exponent, _) = FloatCore::integer_decode(value);
+    let mut numerator = BigInt::from(mantissa);
+    let mut denominator = BigInt::one();
+    if exponent >= 0 {
+        numerator <<= exponent as usize;
+    } else {
+        denominator <<= -exponent as usize;
+    }
+    let ten = BigInt::from(10u8);
+    if pow10 >= 0 {
+        numerator *= ten.pow(pow10 as u32);
+    } else {
+        denominator *= ten.pow(-pow10 as u32);
+    }
+    let (quotient, remainder) = numerator.div_rem(&denominator);
+    if remainder * 2u8 >= denominator {
+        quotient + 1u8
+    } else {
+        quotient
+    }
+}
+
+/// `precision` significant digits of finite positive `value` and decimal exponent of the first
+/// digit. `digits × 10^(exponent - precision + 1)` is the closest such number to `value`.
+fn exact_digits(value: f64, precision: u32) -> (String, i32) {
+    let lower = BigInt::from(10u8).pow(precision - 1);
+    let upper = &lower * 10u8;
+    // log10 may be off by one near powers of ten, the loop fixes the estimate.
+    let mut exponent = value.log10().floor() as i32;
+    loop {
+        let digits = scaled_round(value, precision as i32 - 1 - exponent);
+        if digits >= upper {
+            exponent += 1;
+        } else if digits < lower {
+            exponent -= 1;
+        } else {
+            return (digits.to_string(), exponent);
         }
-    };
+    }
+}
 
-    let precision_i32 = precision as i32;
+/// Shortest digits that round-trip to finite positive `value` and decimal exponent of the first
+/// digit.
+fn shortest_digits(value: f64) -> (String, i32) {
+    // `{:e}` prints shortest round-trip representation e.g. `1.2345e-7`.
+    let string = format!("{:e}", value);
+    let (mantissa, exponent) = string.split_at(string.find('e').unwrap());
+    (mantissa.replace('.', ""), exponent[1..].parse().unwrap())
+}
 
-    // 7
-    let mut prefix = String::new(); // spec: 's'
-    let mut suffix: String; // spec: 'm'
-    let exponent: i32; // spec: 'e'
+fn exponent_suffix(exponent: i32) -> String {
+    format!(
+        "e{}{}",
+        if exponent < 0 { '-' } else { '+' },
+        exponent.abs()
+    )
+}
+
+pub fn number_to_precision(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let precision_var = args.at(0);
+    let mut this_num = this_number_val(ctx, args.this)?;
+    if precision_var.is_undefined() {
+        return Ok(JsValue::new(JsString::new(ctx, f64_to_string(this_num))));
+    }
+    let precision = to_integer_or_infinity(ctx, precision_var)?;
+    if !this_num.is_finite() {
+        return Ok(JsValue::new(JsString::new(ctx, f64_to_string(this_num))));
+    }
+    if !(1.0..=100.0).contains(&precision) {
+        return Err(JsValue::new(ctx.new_range_error(
+            "toPrecision() argument must be between 1 and 100",
+        )));
+    }
+    let precision = precision as i32;
 
-    // 8
+    let mut prefix = String::new();
     if this_num < 0.0 {
         prefix.push('-');
         this_num = -this_num;
     }
-
-    // 9
-    if this_num == 0.0 {
-        suffix = "0".repeat(precision);
-        exponent = 0;
-    // 10
+    let (mut digits, exponent) = if this_num == 0.0 {
+        ("0".repeat(precision as usize), 0)
     } else {
-        // Due to f64 limitations, this pactx differs a bit from the spec,
-        // but has the same effect. It manipulates the string constructed
-        // by ryu-js: digits with an optional dot between two of them.
-
-        let mut buffer = ryu_js::Buffer::new();
-        suffix = buffer.format(this_num).to_string();
-
-        // a: getting an exponent
-        exponent = flt_str_to_exp(&suffix);
-        // b: getting relevant digits only
-        if exponent < 0 {
-            suffix = suffix.split_off((1 - exponent) as usize);
-        } else if let Some(n) = suffix.find('.') {
-            suffix.remove(n);
-        }
-        // impl: having exactly `precision` digits in `suffix`
-        suffix = round_to_precision(&mut suffix, precision);
-
-        // c: switching to scientific notation
-        let great_exp = exponent >= precision_i32;
-        if exponent < -6 || great_exp {
-            // ii
-            if precision > 1 {
-                suffix.insert(1, '.');
-            }
-            // vi
-            suffix.push('e');
-            // iii
-            if great_exp {
-                suffix.push('+');
-            }
-            // iv, v
-            suffix.push_str(&exponent.to_string());
+        exact_digits(this_num, precision as u32)
+    };
 
-            return Ok(JsValue::new(JsString::new(ctx, prefix + &suffix)));
+    if exponent < -6 || exponent >= precision {
+        if precision > 1 {
+            digits.insert(1, '.');
+        }
+        digits.push_str(&exponent_suffix(exponent));
+    } else if exponent >= 0 {
+        if exponent + 1 < precision {
+            digits.insert(exponent as usize + 1, '.');
         }
-    } // 11
-    let e_inc = exponent + 1;
-    if e_inc == precision_i32 {
-        return Ok(JsValue::from(JsString::new(ctx, prefix + &suffix)));
+    } else {
+        digits = format!("0.{}{}", "0".repeat((-exponent - 1) as usize), digits);
     }
+    Ok(JsValue::new(JsString::new(ctx, prefix + &digits)))
+}
 
-    // 12
-    if exponent >= 0 {
-        suffix.insert(e_inc as usize, '.');
-    // 13
-    } else {
-        prefix.push('0');
-        prefix.push('.');
-        prefix.push_str(&"0".repeat(-e_inc as usize));
+pub fn number_to_exponential(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let fraction_var = args.at(0);
+    let mut this_num = this_number_val(ctx, args.this)?;
+    let fraction = to_integer_or_infinity(ctx, fraction_var)?;
+    if !this_num.is_finite() {
+        return Ok(JsValue::new(JsString::new(ctx, f64_to_string(this_num))));
+    }
+    if !(0.0..=100.0).contains(&fraction) {
+        return Err(JsValue::new(ctx.new_range_error(
+            "toExponential() argument must be between 0 and 100",
+        )));
     }
 
-    // 14
-    Ok(JsValue::new(JsString::new(ctx, prefix + &suffix)))
+    let mut prefix = String::new();
+    if this_num < 0.0 {
+        prefix.push('-');
+        this_num = -this_num;
+    }
+    let (mut digits, exponent) = if this_num == 0.0 {
+        let count = if fraction_var.is_undefined() {
+            1
+        } else {
+            fraction as usize + 1
+        };
+        ("0".repeat(count), 0)
+    } else if fraction_var.is_undefined() {
+        shortest_digits(this_num)
+    } else {
+        exact_digits(this_num, fraction as u32 + 1)
+    };
+    if digits.len() > 1 {
+        digits.insert(1, '.');
+    }
+    digits.push_str(&exponent_suffix(exponent));
+    Ok(JsValue::new(JsString::new(ctx, prefix + &digits)))
 }
 
 pub fn number_to_fixed(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let fixed_var = args.at(0);
     let mut this_num = this_number_val(ctx, args.this)?;
-
-    let mut fixed = 0;
-    if !fixed_var.is_undefined() {
-        fixed = fixed_var.to_int32(ctx)?;
+    let fixed = to_integer_or_infinity(ctx, args.at(0))?;
+    if !(0.0..=100.0).contains(&fixed) {
+        return Err(JsValue::new(ctx.new_range_error(
+            "toFixed() digits argument must be between 0 and 100",
+        )));
     }
-
-    if !(0..=20).contains(&fixed) {
-        let msg = JsString::new(ctx, "toFixed() digits argument must be between 0 and 20");
-        return Err(JsValue::new(JsRangeError::new(ctx, msg, None)));
+    if !this_num.is_finite() || this_num.abs() >= 1e21 {
+        return Ok(JsValue::new(JsString::new(ctx, f64_to_string(this_num))));
     }
-
     let fixed = fixed as usize;
 
-    let mut buffer = ryu_js::Buffer::new();
-    let mut string = buffer.format(this_num).to_string();
-    // after .
-    string = round_to_fixed(&mut string, fixed);
-    Ok(JsValue::new(JsString::new(ctx, string)))
+    let mut prefix = String::new();
+    if this_num < 0.0 {
+        prefix.push('-');
+        this_num = -this_num;
+    }
+    let mut digits = scaled_round(this_num, fixed as i32).to_string();
+    if fixed != 0 {
+        if digits.len() <= fixed {
+            digits = "0".repeat(fixed + 1 - digits.len()) + &digits;
+        }
+        digits.insert(digits.len() - fixed, '.');
+    }
+    Ok(JsValue::new(JsString::new(ctx, prefix + &digits)))
+}
+
+pub fn number_to_locale_string(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let num = this_number_val(ctx, args.this)?;
+    let formatter = NumberFormatter::new(ctx, args.at(0), args.at(1))?;
+    Ok(JsValue::new(JsString::new(
+        ctx,
+        formatter.format(Number::from_f64(num)),
+    )))
 }
 
 pub fn number_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
@@ -228,40 +328,23 @@ pub fn number_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsV
         num = obj.get_number();
     }
 
-    if args.size() != 0 {
-        let first = args.at(0);
-        let radix;
-        if first.is_undefined() {
-            radix = 10;
-        } else {
-            radix = first.to_int32(ctx)?;
-        }
-        if radix == 10 {
-            return Ok(JsValue::new(JsString::new(ctx, num.to_string())));
-        }
-        if (2..=36).contains(&radix) {
-            if radix != 10 {
-                if num.is_nan() {
-                    return Ok(JsValue::new(JsString::new(ctx, "NaN")));
-                } else if num.is_infinite() {
-                    if num.is_sign_positive() {
-                        return Ok(JsValue::new(JsString::new(ctx, "Infinity")));
-                    } else {
-                        return Ok(JsValue::new(JsString::new(ctx, "-Infinity")));
-                    }
-                }
-            }
-            Ok(JsValue::new(JsString::new(
-                ctx,
-                to_native_string_radix(num, radix as _),
-            )))
-        } else {
-            let msg = JsString::new(ctx, "Illegal radix");
-            return Err(JsValue::new(JsRangeError::new(ctx, msg, None)));
-        }
+    let radix = if args.at(0).is_undefined() {
+        10.0
     } else {
-        return Ok(JsValue::new(JsString::new(ctx, num.to_string())));
+        to_integer_or_infinity(ctx, args.at(0))?
+    };
+    if !(2.0..=36.0).contains(&radix) {
+        return Err(JsValue::new(
+            ctx.new_range_error("toString() radix must be between 2 and 36"),
+        ));
     }
+    if radix == 10.0 || !num.is_finite() {
+        return Ok(JsValue::new(JsString::new(ctx, f64_to_string(num))));
+    }
+    Ok(JsValue::new(JsString::new(
+        ctx,
+        to_native_string_radix(num, radix as _),
+    )))
 }
 
 // https://chromium.googlesource.com/v8/v8/+/refs/heads/master/src/numbers/conversions.cc#1230
@@ -281,7 +364,7 @@ pub(crate) fn to_native_string_radix(mut value: f64, radix: u8) -> String {
     let mut buffer: [u8; BUF_SIZE] = [0; BUF_SIZE];
     let (int_buf, frac_buf) = buffer.split_at_mut(BUF_SIZE / 2);
     let mut fraction_cursor = 0;
-    let negative = value.is_sign_negative();
+    let negative = value < 0.0;
     if negative {
         value = -value
     }
@@ -310,9 +393,7 @@ pub(crate) fn to_native_string_radix(mut value: f64, radix: u8) -> String {
             // Calculate remainder.
             fraction -= digit as f64;
             // Round to even.
-            if fraction + delta > 1.0
-                && (fraction > 0.5 || (fraction - 0.5).abs() < f64::EPSILON && digit & 1 != 0)
-            {
+            if fraction + delta > 1.0 && (fraction > 0.5 || fraction == 0.5 && digit & 1 != 0) {
                 loop {
                     // We need to back trace already written digits in case of carry-over.
                     fraction_cursor -= 1;
@@ -324,7 +405,7 @@ pub(crate) fn to_native_string_radix(mut value: f64, radix: u8) -> String {
                     } else {
                         let c: u8 = frac_buf[fraction_cursor];
                         // Reconstruct digit.
-                        let digit_0 = (c as char).to_digit(10).unwrap();
+                        let digit_0 = (c as char).to_digit(radix as u32).unwrap();
                         if digit_0 + 1 >= radix as u32 {
                             continue;
                         }
@@ -385,135 +466,6 @@ fn next_after(x: f64, y: f64) -> f64 {
         f64::from_bits(x.to_bits() - 1)
     }
 }
-/// flt_str_to_exp - used in to_precision
-///
-/// This function traverses a string representing a number,
-/// returning the floored log10 of this number.
-///
-fn flt_str_to_exp(flt: &str) -> i32 {
-    let mut non_zero_encountered = false;
-    let mut dot_encountered = false;
-    for (i, c) in flt.chars().enumerate() {
-        if c == '.' {
-            if non_zero_encountered {
-                return (i as i32) - 1;
-            }
-            dot_encountered = true;
-        } else if c != '0' {
-            if dot_encountered {
-                return 1 - (i as i32);
-            }
-            non_zero_encountered = true;
-        }
-    }
-    (flt.len() as i32) - 1
-}
-
-/// round_to_precision - used in to_precision
-///
-/// This procedure has two roles:
-/// - If there are enough or more than enough digits in the
-///   string to show the required precision, the number
-///   represented by these digits is rounded using string
-///   manipulation.
-/// - Else, zeroes are appended to the string.
-///
-/// When this procedure returns, `digits` is exactly `precision` long.
-///
-pub fn round_to_precision(digits: &mut String, precision: usize) -> String {
-    if digits.len() > precision {
-        let to_round = digits.split_off(precision);
-        let mut digit_bytes = digits.clone().into_bytes();
-        let mut stop_index = (digit_bytes.len() - 1) as i32;
-        let mut digit = *digit_bytes.last().unwrap();
-
-        for c in to_round.chars() {
-            match c {
-                c if c < '4' => break,
-                c if c > '4' => {
-                    while digit == b'9' {
-                        digit_bytes[stop_index as usize] = b'0';
-                        stop_index -= 1;
-                        if stop_index == -1 {
-                            break;
-                        }
-                        digit = digit_bytes[stop_index as usize];
-                    }
-                    break;
-                }
-                _ => {}
-            }
-        }
-        if stop_index == -1 {
-            digit_bytes.insert(0, b'1');
-            digit_bytes.pop();
-        } else {
-            digit_bytes[stop_index as usize] += 1;
-        }
-        return String::from_utf8(digit_bytes).unwrap();
-    } else {
-        digits.push_str(&"0".repeat(precision - digits.len()));
-        return digits.to_string();
-    }
-}
-
-pub fn round_to_fixed(string: &mut String, fixed: usize) -> String {
-    if let Some(n) = string.find('.') {
-        if (string.len() - 1 - n) > fixed {
-            let to_round = string.split_off(n + fixed + 1);
-            let mut digit_bytes = string.clone().into_bytes();
-            let mut stop_index = (digit_bytes.len() - 1) as i32;
-            let mut digit = *digit_bytes.last().unwrap();
-
-            for c in to_round.chars() {
-                match c {
-                    c if c < '4' => break,
-                    c if c > '4' => {
-                        loop {
-                            if digit == b'9' {
-                                digit_bytes[stop_index as usize] = b'0';
-                                stop_index -= 1;
-                                if stop_index == -1 {
-                                    break;
-                                }
-                                digit = digit_bytes[stop_index as usize];
-                            } else if digit == b'.' {
-                                stop_index -= 1;
-                                digit = digit_bytes[stop_index as usize];
-                            } else {
-                                break;
-                            }
-                        }
-                        break;
-                    }
-                    _ => {}
-                }
-            }
-            if stop_index == -1 {
-                digit_bytes.insert(0, b'1');
-            } else {
-                digit_bytes[stop_index as usize] += 1;
-            }
-            if fixed == 0 {
-                digit_bytes.pop();
-            }
-            return String::from_utf8(digit_bytes).unwrap();
-        } else {
-            let mut digits = string.split_off(n + 1);
-            digits = round_to_precision(&mut digits, fixed);
-            string.push_str(&digits);
-            return string.to_string();
-        }
-    } else {
-        let mut digits = String::from("");
-        digits = round_to_precision(&mut digits, fixed);
-        if !digits.is_empty() {
-            string.push('.');
-            string.push_str(&digits);
-        }
-        return string.to_string();
-    }
-}
 
 impl GcPointer<Context> {
     pub(crate) fn init_number_in_global_object(mut self) -> Result<(), JsValue> {
@@ -542,21 +494,56 @@ impl GcPointer<Context> {
         let mut constructor = JsNativeFunction::new(self, "Number".intern(), number_constructor, 1);
 
         def_native_property!(self, constructor, prototype, proto, NONE)?;
-        def_native_property!(self, constructor, MAX_VALUE, f64::MAX)?;
-        def_native_property!(self, constructor, MIN_VALUE, f64::MIN)?;
-        def_native_property!(self, constructor, NaN, f64::NAN)?;
-        def_native_property!(self, constructor, NEGATIVE_INFINITY, f64::NEG_INFINITY)?;
-        def_native_property!(self, constructor, POSITIVE_INFINITY, f64::INFINITY)?;
-        def_native_property!(self, constructor, EPSILON, f64::EPSILON)?;
-        def_native_property!(self, constructor, MAX_SAFE_INTEGER, 9007199254740991.0)?;
-        def_native_method!(self, constructor, isNaN, number_is_nan, 0)?;
-        def_native_method!(self, constructor, isFinite, number_is_finite, 0)?;
-        def_native_method!(self, constructor, isInteger, number_is_integer, 0)?;
+        def_native_property!(self, constructor, MAX_VALUE, f64::MAX, NONE)?;
+        // smallest positive subnormal, 5e-324.
+        def_native_property!(self, constructor, MIN_VALUE, f64::from_bits(1), NONE)?;
+        def_native_property!(self, constructor, NaN, f64::NAN, NONE)?;
+        def_native_property!(
+            self,
+            constructor,
+            NEGATIVE_INFINITY,
+            f64::NEG_INFINITY,
+            NONE
+        )?;
+        def_native_property!(self, constructor, POSITIVE_INFINITY, f64::INFINITY, NONE)?;
+        def_native_property!(self, constructor, EPSILON, f64::EPSILON, NONE)?;
+        def_native_property!(self, constructor, MAX_SAFE_INTEGER, MAX_SAFE_INTEGER, NONE)?;
+        def_native_property!(self, constructor, MIN_SAFE_INTEGER, -MAX_SAFE_INTEGER, NONE)?;
+        def_native_method!(self, constructor, isNaN, number_is_nan, 1, W | C)?;
+        def_native_method!(self, constructor, isFinite, number_is_finite, 1, W | C)?;
+        def_native_method!(self, constructor, isInteger, number_is_integer, 1, W | C)?;
+        def_native_method!(
+            self,
+            constructor,
+            isSafeInteger,
+            number_is_safe_integer,
+            1,
+            W | C
+        )?;
+        // global `parseInt` and `parseFloat` are the same function objects.
+        def_native_method!(
+            self,
+            constructor,
+            parseFloat,
+            super::global::parse_float,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            constructor,
+            parseInt,
+            super::global::parse_int,
+            2,
+            W | C
+        )?;
 
         def_native_property!(self, proto, constructor, constructor)?;
         def_native_method!(self, proto, toString, number_to_string, 1)?;
+        def_native_method!(self, proto, toLocaleString, number_to_locale_string, 0)?;
         def_native_method!(self, proto, valueOf, number_value_of, 0)?;
-        def_native_method!(self, proto, toPrecision, number_to_precisiion, 1)?;
+        def_native_method!(self, proto, toPrecision, number_to_precision, 1)?;
+        def_native_method!(self, proto, toExponential, number_to_exponential, 1)?;
         def_native_method!(self, proto, toFixed, number_to_fixed, 1)?;
         def_native_method!(self, proto, clz, number_clz, 1)?;
 
@@ -565,3 +552,23 @@ impl GcPointer<Context> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_number_formatting() {
+        assert_eval_cases(&[
+            ("(0.5).toString(2)", "0.1"),
+            ("(255.5).toString(16)", "ff.8"),
+            ("(-255.5).toString(16)", "-ff.8"),
+            ("(0.25).toString(2)", "0.01"),
+            ("(1e21).toFixed(2)", "1e+21"),
+            ("(123.456).toExponential()", "1.23456e+2"),
+            ("(123.456).toExponential(2)", "1.23e+2"),
+            ("(0.000001).toPrecision(2)", "0.0000010"),
+            ("(1e21).toPrecision(3)", "1.00e+21"),
+        ]);
+    }
+}
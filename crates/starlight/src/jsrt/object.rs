@@ -8,8 +8,13 @@ use crate::{
         attributes::*,
         context::Context,
         error::JsTypeError,
+        function::JsNativeFunction,
+        interpreter::SpreadValue,
+        number::NumberObject,
         object::{JsObject, ObjectTag, *},
-        property_descriptor::DataDescriptor,
+        operations,
+        property_descriptor::{AccessorDescriptor, DataDescriptor, PropertyDescriptor},
+        slot::Slot,
         string::JsString,
         structure::Structure,
         symbol_table::*,
@@ -17,6 +22,66 @@ use crate::{
     },
 };
 
+/// Converts property key to the string or symbol value exposed to JS.
+pub(crate) fn property_key_to_value(ctx: GcPointer<Context>, key: Symbol) -> JsValue {
+    match key {
+        Symbol::Private(_) => JsValue::new(JsSymbol::new(ctx, key)),
+        _ => JsValue::new(ctx.key_to_jsstring(key)),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PropertyKind {
+    Key,
+    Value,
+    KeyValue,
+}
+
+/// EnumerableOwnPropertyNames(O, kind)
+fn enumerable_own_properties(
+    ctx: GcPointer<Context>,
+    object: &mut GcPointer<JsObject>,
+    kind: PropertyKind,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(properties = stack, Vec::<JsValue>::new());
+    for key in operations::own_property_keys(ctx, object)? {
+        if let Symbol::Private(_) = key {
+            continue;
+        }
+        match operations::get_own_property(ctx, object, key)? {
+            Some(desc) if desc.is_enumerable() => (),
+            _ => continue,
+        }
+        if kind == PropertyKind::Key {
+            properties.push(property_key_to_value(ctx, key));
+            continue;
+        }
+        let receiver = JsValue::new(*object);
+        let value = operations::get_with_receiver(ctx, object, key, receiver)?;
+        if kind == PropertyKind::Value {
+            properties.push(value);
+        } else {
+            let entry = [property_key_to_value(ctx, key), value];
+            properties.push(JsValue::new(JsArray::from_slice(ctx, &entry)));
+        }
+    }
+    Ok(JsValue::new(JsArray::from_slice(ctx, &properties)))
+}
+
+fn require_object_coercible(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    name: &str,
+) -> Result<(), JsValue> {
+    if unlikely(value.is_undefined() || value.is_null()) {
+        return Err(JsValue::new(
+            ctx.new_type_error(format!("{} called on null or undefined", name)),
+        ));
+    }
+    Ok(())
+}
+
 pub fn object_get_prototype_of(
     ctx: GcPointer<Context>,
     args: &Arguments,
@@ -28,9 +93,10 @@ pub fn object_get_prototype_of(
         ));
     }
 
-    let object = this.to_object(ctx)?;
-    Ok(match object.prototype() {
-        Some(proto) => JsValue::new(*proto),
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, this.to_object(ctx)?);
+    Ok(match operations::get_prototype_of(ctx, &mut object)? {
+        Some(proto) => JsValue::new(proto),
         None => JsValue::encode_null_value(),
     })
 }
@@ -49,9 +115,26 @@ pub fn object_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsV
             "[object Null]",
         )));
     }
-    let obj = this_binding.to_object(ctx)?;
-
-    let s = format!("[object {}]", obj.class().name);
+    let stack = ctx.shadowstack();
+    letroot!(obj = stack, this_binding.to_object(ctx)?);
+    let builtin_tag = if operations::is_array(ctx, JsValue::new(*obj))? {
+        "Array"
+    } else if obj.is_callable() {
+        "Function"
+    } else if obj.is_class(NumberObject::get_class()) {
+        "Number"
+    } else {
+        match obj.class().name {
+            name @ ("Arguments" | "Error" | "Boolean" | "String" | "Date" | "RegExp") => name,
+            _ => "Object",
+        }
+    };
+    let tag = obj.get(ctx, "Symbol.toStringTag".intern().private())?;
+    let s = if tag.is_jsstring() {
+        format!("[object {}]", tag.get_jsstring().as_str())
+    } else {
+        format!("[object {}]", builtin_tag)
+    };
     Ok(JsValue::encode_object_value(JsString::new(ctx, s)))
 }
 
@@ -163,59 +246,8 @@ pub fn object_get_own_property_descriptor(
         letroot!(obj = stack, first.get_jsobject());
         let name = prop.to_symbol(ctx)?;
 
-        match obj.get_own_property(ctx, name) {
-            Some(property_descriptor) => {
-                letroot!(res = stack, JsObject::new_empty(ctx));
-                res.define_own_property(
-                    ctx,
-                    "configurable".intern(),
-                    &*DataDescriptor::new(
-                        JsValue::new(property_descriptor.is_configurable()),
-                        W | C,
-                    ),
-                    false,
-                )?;
-                res.define_own_property(
-                    ctx,
-                    "enumerable".intern(),
-                    &*DataDescriptor::new(JsValue::new(property_descriptor.is_enumerable()), W | C),
-                    false,
-                )?;
-                if property_descriptor.is_data() {
-                    res.define_own_property(
-                        ctx,
-                        "value".intern(),
-                        &*DataDescriptor::new(JsValue::new(property_descriptor.value()), W | C),
-                        false,
-                    )?;
-                    res.define_own_property(
-                        ctx,
-                        "writable".intern(),
-                        &*DataDescriptor::new(
-                            JsValue::new(property_descriptor.is_writable()),
-                            W | C,
-                        ),
-                        false,
-                    )?;
-                } else {
-                    let getter = property_descriptor.getter();
-                    let setter = property_descriptor.setter();
-
-                    res.define_own_property(
-                        ctx,
-                        "get".intern(),
-                        &*DataDescriptor::new(getter, W | C),
-                        false,
-                    )?;
-                    res.define_own_property(
-                        ctx,
-                        "set".intern(),
-                        &*DataDescriptor::new(setter, W | C),
-                        false,
-                    )?;
-                }
-                Ok(JsValue::encode_object_value(*res))
-            }
+        match operations::get_own_property(ctx, &mut obj, name)? {
+            Some(property_descriptor) => super::from_property_descriptor(ctx, &property_descriptor),
             None => Ok(JsValue::new(Undefined)),
         }
     } else {
@@ -245,30 +277,20 @@ pub fn object_property_is_enumerable(
 
 pub fn object_keys(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     let stack = ctx.shadowstack();
-    if args.size() != 0 {
-        let first = args.at(0);
-        if first.is_jsobject() {
-            letroot!(obj = stack, first.get_jsobject());
-            let mut names = vec![];
-            obj.get_own_property_names(
-                ctx,
-                &mut |name, _| names.push(name),
-                EnumerationMode::Default,
-            );
-            letroot!(arr = stack, JsArray::new(ctx, names.len() as _));
+    letroot!(object = stack, args.at(0).to_object(ctx)?);
+    enumerable_own_properties(ctx, &mut object, PropertyKind::Key)
+}
 
-            for (i, name) in names.iter().enumerate() {
-                let desc = ctx.description(*name);
-                let name = JsString::new(ctx, desc);
-                arr.put(ctx, Symbol::Index(i as _), JsValue::new(name), false)?;
-            }
-            return Ok(JsValue::new(*arr));
-        }
-    }
+pub fn object_values(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.at(0).to_object(ctx)?);
+    enumerable_own_properties(ctx, &mut object, PropertyKind::Value)
+}
 
-    Err(JsValue::new(
-        ctx.new_type_error("Object.keys requires object argument"),
-    ))
+pub fn object_entries(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.at(0).to_object(ctx)?);
+    enumerable_own_properties(ctx, &mut object, PropertyKind::KeyValue)
 }
 
 pub fn object_freeze(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
@@ -309,7 +331,11 @@ pub fn object_prevent_extensions(
         let stack = ctx.shadowstack();
         if first.is_jsobject() {
             letroot!(obj = stack, first.get_jsobject());
-            obj.change_extensible(ctx, false);
+            if !operations::prevent_extensions(ctx, &mut obj)? {
+                return Err(JsValue::new(ctx.new_type_error(
+                    "Object.preventExtensions: cannot prevent extensions",
+                )));
+            }
             return Ok(JsValue::new(*obj));
         }
     }
@@ -380,10 +406,361 @@ pub fn object_is_extensible(ctx: GcPointer<Context>, args: &Arguments) -> Result
         if first.is_jsobject() {
             letroot!(obj = stack, first.get_jsobject());
 
-            return Ok(JsValue::new(obj.is_extensible()));
+            return operations::is_extensible(ctx, &mut obj).map(JsValue::new);
         }
     }
     Err(JsValue::new(ctx.new_type_error(
         "Object.isExtensible requires object argument",
     )))
 }
+
+pub fn object_assign(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(to = stack, args.at(0).to_object(ctx)?);
+    for i in 1..args.size() {
+        let source = args.at(i);
+        if source.is_undefined() || source.is_null() {
+            continue;
+        }
+        letroot!(from = stack, source.to_object(ctx)?);
+        for key in operations::own_property_keys(ctx, &mut from)? {
+            match operations::get_own_property(ctx, &mut from, key)? {
+                Some(desc) if desc.is_enumerable() => (),
+                _ => continue,
+            }
+            let receiver = JsValue::new(*from);
+            let value = operations::get_with_receiver(ctx, &mut from, key, receiver)?;
+            let receiver = JsValue::new(*to);
+            if unlikely(!operations::set_with_receiver(
+                ctx, &mut to, key, value, receiver,
+            )?) {
+                return Err(JsValue::new(ctx.new_type_error(format!(
+                    "Cannot assign to read only property '{}'",
+                    ctx.description(key)
+                ))));
+            }
+        }
+    }
+    Ok(JsValue::new(*to))
+}
+
+pub fn object_from_entries(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let iterable = args.at(0);
+    require_object_coercible(ctx, iterable, "Object.fromEntries")?;
+    let stack = ctx.shadowstack();
+    letroot!(entries = stack, SpreadValue::new(ctx, iterable)?);
+    letroot!(object = stack, JsObject::new_empty(ctx));
+    for i in 0..entries.array.len() {
+        let entry = entries.array[i];
+        if unlikely(!entry.is_jsobject()) {
+            return Err(JsValue::new(ctx.new_type_error(format!(
+                "Iterator value {} is not an entry object",
+                entry.to_string(ctx)?
+            ))));
+        }
+        let mut entry = entry.get_jsobject();
+        let key = entry.get(ctx, Symbol::Index(0))?;
+        let value = entry.get(ctx, Symbol::Index(1))?;
+        let key = key.to_symbol(ctx)?;
+        object.define_own_property(ctx, key, &*DataDescriptor::new(value, W | C | E), true)?;
+    }
+    Ok(JsValue::new(*object))
+}
+
+/// GetOwnPropertyKeys(O, type)
+fn own_property_keys_of_type(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    symbols: bool,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, value.to_object(ctx)?);
+    letroot!(keys = stack, Vec::<JsValue>::new());
+    for key in operations::own_property_keys(ctx, &mut object)? {
+        if matches!(key, Symbol::Private(_)) == symbols {
+            keys.push(property_key_to_value(ctx, key));
+        }
+    }
+    Ok(JsValue::new(JsArray::from_slice(ctx, &keys)))
+}
+
+pub fn object_get_own_property_names(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    own_property_keys_of_type(ctx, args.at(0), false)
+}
+
+pub fn object_get_own_property_symbols(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    own_property_keys_of_type(ctx, args.at(0), true)
+}
+
+pub fn object_get_own_property_descriptors(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.at(0).to_object(ctx)?);
+    letroot!(descriptors = stack, JsObject::new_empty(ctx));
+    for key in operations::own_property_keys(ctx, &mut object)? {
+        if let Some(desc) = operations::get_own_property(ctx, &mut object, key)? {
+            let desc = super::from_property_descriptor(ctx, &desc)?;
+            descriptors.define_own_property(
+                ctx,
+                key,
+                &*DataDescriptor::new(desc, W | C | E),
+                true,
+            )?;
+        }
+    }
+    Ok(JsValue::new(*descriptors))
+}
+
+pub fn object_set_prototype_of(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let object = args.at(0);
+    require_object_coercible(ctx, object, "Object.setPrototypeOf")?;
+    let proto = args.at(1);
+    let proto = if proto.is_jsobject() {
+        Some(proto.get_jsobject())
+    } else if proto.is_null() {
+        None
+    } else {
+        return Err(JsValue::new(
+            ctx.new_type_error("Object prototype may only be an Object or null"),
+        ));
+    };
+    if !object.is_jsobject() {
+        return Ok(object);
+    }
+    let stack = ctx.shadowstack();
+    letroot!(target = stack, object.get_jsobject());
+    if unlikely(!operations::set_prototype_of(ctx, &mut target, proto)?) {
+        return Err(JsValue::new(
+            ctx.new_type_error("Object.setPrototypeOf: cannot set prototype"),
+        ));
+    }
+    Ok(object)
+}
+
+pub fn object_has_own(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.at(0).to_object(ctx)?);
+    let key = args.at(1).to_symbol(ctx)?;
+    Ok(JsValue::new(
+        operations::get_own_property(ctx, &mut object, key)?.is_some(),
+    ))
+}
+
+pub fn object_group_by(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let items = args.at(0);
+    require_object_coercible(ctx, items, "Object.groupBy")?;
+    let callback = args.at(1);
+    if unlikely(!callback.is_callable()) {
+        return Err(JsValue::new(
+            ctx.new_type_error("Object.groupBy callback must be a function"),
+        ));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(items = stack, SpreadValue::new(ctx, items)?);
+    letroot!(callback = stack, callback.get_jsobject());
+    // groups keep order of the first element of each group.
+    let mut groups: Vec<(Symbol, Vec<JsValue>)> = vec![];
+    for k in 0..items.array.len() {
+        let value = items.array[k];
+        let mut buf = [value, JsValue::new(k as u32)];
+        letroot!(
+            args = stack,
+            Arguments::new(JsValue::encode_undefined_value(), &mut buf)
+        );
+        let func = JsValue::new(*callback);
+        let key = callback
+            .as_function_mut()
+            .call(ctx, &mut args, func)?
+            .to_symbol(ctx)?;
+        match groups.iter_mut().find(|(group, _)| *group == key) {
+            Some((_, elements)) => elements.push(value),
+            None => groups.push((key, vec![value])),
+        }
+    }
+    letroot!(
+        structure = stack,
+        Structure::new_unique_indexed(ctx, None, false)
+    );
+    letroot!(
+        object = stack,
+        JsObject::new(ctx, &structure, JsObject::get_class(), ObjectTag::Ordinary)
+    );
+    for (key, elements) in groups.iter() {
+        let elements = JsArray::from_slice(ctx, elements);
+        object.define_own_property(
+            ctx,
+            *key,
+            &*DataDescriptor::new(JsValue::new(elements), W | C | E),
+            true,
+        )?;
+    }
+    Ok(JsValue::new(*object))
+}
+
+pub fn object_is_prototype_of(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let value = args.at(0);
+    if !value.is_jsobject() {
+        return Ok(JsValue::new(false));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    letroot!(current = stack, value.get_jsobject());
+    while let Some(proto) = operations::get_prototype_of(ctx, &mut current)? {
+        if GcPointer::ptr_eq(&proto, &object) {
+            return Ok(JsValue::new(true));
+        }
+        *current = proto;
+    }
+    Ok(JsValue::new(false))
+}
+
+pub fn object_value_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    args.this.to_object(ctx).map(JsValue::new)
+}
+
+pub fn object_to_locale_string(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let this = args.this;
+    let func = this.get_slot(ctx, "toString".intern(), &mut Slot::new())?;
+    if unlikely(!func.is_callable()) {
+        return Err(JsValue::new(
+            ctx.new_type_error("toString is not a function"),
+        ));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(function = stack, func.get_jsobject());
+    letroot!(args = stack, Arguments::new(this, &mut []));
+    function.as_function_mut().call(ctx, &mut args, func)
+}
+
+pub fn object_proto_getter(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    Ok(match operations::get_prototype_of(ctx, &mut object)? {
+        Some(proto) => JsValue::new(proto),
+        None => JsValue::encode_null_value(),
+    })
+}
+
+pub fn object_proto_setter(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let this = args.this;
+    require_object_coercible(ctx, this, "Object.prototype.__proto__")?;
+    let proto = args.at(0);
+    let proto = if proto.is_jsobject() {
+        Some(proto.get_jsobject())
+    } else if proto.is_null() {
+        None
+    } else {
+        return Ok(JsValue::encode_undefined_value());
+    };
+    if !this.is_jsobject() {
+        return Ok(JsValue::encode_undefined_value());
+    }
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, this.get_jsobject());
+    if unlikely(!operations::set_prototype_of(ctx, &mut object, proto)?) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "Object.prototype.__proto__: cannot set prototype",
+        )));
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+fn define_accessor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    getter: bool,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let func = args.at(1);
+    if unlikely(!func.is_callable()) {
+        let name = if getter {
+            "__defineGetter__"
+        } else {
+            "__defineSetter__"
+        };
+        return Err(JsValue::new(ctx.new_type_error(format!(
+            "Object.prototype.{}: Expecting function",
+            name
+        ))));
+    }
+    let key = args.at(0).to_symbol(ctx)?;
+    let desc = if getter {
+        PropertyDescriptor::accessor_getter(func, E | C)
+    } else {
+        PropertyDescriptor::accessor_setter(func, E | C)
+    };
+    object.define_own_property(ctx, key, &desc, true)?;
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn object_define_getter(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    define_accessor(ctx, args, true)
+}
+
+pub fn object_define_setter(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    define_accessor(ctx, args, false)
+}
+
+fn lookup_accessor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    getter: bool,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, args.this.to_object(ctx)?);
+    let key = args.at(0).to_symbol(ctx)?;
+    loop {
+        if let Some(desc) = operations::get_own_property(ctx, &mut object, key)? {
+            if !desc.is_accessor() {
+                return Ok(JsValue::encode_undefined_value());
+            }
+            return Ok(if getter { desc.getter() } else { desc.setter() });
+        }
+        match operations::get_prototype_of(ctx, &mut object)? {
+            Some(proto) => *object = proto,
+            None => return Ok(JsValue::encode_undefined_value()),
+        }
+    }
+}
+
+pub fn object_lookup_getter(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    lookup_accessor(ctx, args, true)
+}
+
+pub fn object_lookup_setter(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    lookup_accessor(ctx, args, false)
+}
+
+/// Defines `Object.prototype.__proto__` accessor.
+pub(crate) fn define_proto_accessor(
+    ctx: GcPointer<Context>,
+    proto: &mut GcPointer<JsObject>,
+) -> Result<bool, JsValue> {
+    let name = "__proto__".intern();
+    let getter = JsNativeFunction::new(ctx, name, object_proto_getter, 0);
+    let setter = JsNativeFunction::new(ctx, name, object_proto_setter, 1);
+    proto.define_own_property(
+        ctx,
+        name,
+        &*AccessorDescriptor::new(JsValue::new(getter), JsValue::new(setter), C),
+        false,
+    )
+}
@@ -0,0 +1,76 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+use std::{cell::RefCell, intrinsics::unlikely};
+
+use crate::{
+    prelude::*,
+    vm::{context::Context, proxy::JsProxy},
+};
+
+/// ProxyCreate(target, handler)
+fn proxy_create(
+    ctx: GcPointer<Context>,
+    target: JsValue,
+    handler: JsValue,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    if unlikely(!target.is_jsobject() || !handler.is_jsobject()) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "Cannot create proxy with a non-object as target or handler",
+        )));
+    }
+    Ok(JsProxy::new(
+        ctx,
+        target.get_jsobject(),
+        handler.get_jsobject(),
+    ))
+}
+
+pub fn proxy_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if unlikely(!args.ctor_call) {
+        return Err(JsValue::new(
+            ctx.new_type_error("Constructor Proxy requires 'new'"),
+        ));
+    }
+    proxy_create(ctx, args.at(0), args.at(1)).map(JsValue::new)
+}
+
+pub fn proxy_revocable(mut ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(proxy = stack, proxy_create(ctx, args.at(0), args.at(1))?);
+    let root = RefCell::new(Some(ctx.vm.add_persistent_root(JsValue::new(*proxy))));
+    let revoke = JsClosureFunction::new(
+        ctx,
+        "".intern(),
+        move |_ctx, _args| {
+            // take the root so revoked proxy can be collected.
+            if let Some(root) = root.borrow_mut().take() {
+                JsProxy::revoke(&mut root.get_value().get_jsobject());
+            }
+            Ok(JsValue::encode_undefined_value())
+        },
+        0,
+    );
+    letroot!(revoke = stack, revoke);
+    letroot!(result = stack, JsObject::new_empty(ctx));
+    result.put(ctx, "proxy".intern(), JsValue::new(*proxy), false)?;
+    result.put(ctx, "revoke".intern(), JsValue::new(*revoke), false)?;
+    Ok(JsValue::new(*result))
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_proxy_in_global_data(mut self) -> Result<(), JsValue> {
+        self.global_data.proxy_structure = Some(Structure::new_indexed(self, None, false));
+        Ok(())
+    }
+
+    pub(crate) fn init_proxy_in_global_object(mut self) -> Result<(), JsValue> {
+        // Proxy has no `prototype` property.
+        let mut ctor = JsNativeFunction::new(self, "Proxy".intern(), proxy_constructor, 2);
+        def_native_method!(self, ctor, revocable, proxy_revocable, 2, W | C)?;
+
+        let mut global_object = self.global_object();
+        def_native_property!(self, global_object, Proxy, ctor, W | C)?;
+        Ok(())
+    }
+}
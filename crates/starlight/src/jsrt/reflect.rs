@@ -0,0 +1,390 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+use std::intrinsics::unlikely;
+
+use crate::{
+    prelude::*,
+    vm::{context::Context, operations},
+};
+
+fn target_object(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    name: &str,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    if unlikely(!value.is_jsobject()) {
+        return Err(JsValue::new(
+            ctx.new_type_error(format!("Reflect.{} called on non-object", name)),
+        ));
+    }
+    Ok(value.get_jsobject())
+}
+
+/// CreateListFromArrayLike
+fn list_from_array_like(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    name: &str,
+) -> Result<Vec<JsValue>, JsValue> {
+    if unlikely(!value.is_jsobject()) {
+        return Err(JsValue::new(ctx.new_type_error(format!(
+            "Reflect.{}: arguments list must be an object",
+            name
+        ))));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, value.get_jsobject());
+    let len = super::get_length(ctx, &mut object)?;
+    let mut list = Vec::with_capacity(len as usize);
+    for i in 0..len {
+        list.push(object.get(ctx, Symbol::Index(i))?);
+    }
+    Ok(list)
+}
+
+pub fn reflect_apply(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let target = args.at(0);
+    if unlikely(!target.is_callable()) {
+        return Err(JsValue::new(
+            ctx.new_type_error("Reflect.apply: target is not callable"),
+        ));
+    }
+    let stack = ctx.shadowstack();
+    let mut list = list_from_array_like(ctx, args.at(2), "apply")?;
+    letroot!(func = stack, target.get_jsobject());
+    letroot!(args_ = stack, Arguments::new(args.at(1), &mut list));
+    func.as_function_mut().call(ctx, &mut args_, target)
+}
+
+pub fn reflect_construct(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let target = args.at(0);
+    if unlikely(!operations::is_constructor(target)) {
+        return Err(JsValue::new(
+            ctx.new_type_error("Reflect.construct: target is not a constructor"),
+        ));
+    }
+    let new_target = if args.size() > 2 { args.at(2) } else { target };
+    if unlikely(!operations::is_constructor(new_target)) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "Reflect.construct: newTarget is not a constructor",
+        )));
+    }
+    let mut list = list_from_array_like(ctx, args.at(1), "construct")?;
+    operations::construct(ctx, target, &mut list, Some(new_target))
+}
+
+pub fn reflect_define_property(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        target = stack,
+        target_object(ctx, args.at(0), "defineProperty")?
+    );
+    let key = args.at(1).to_symbol(ctx)?;
+    let desc = super::to_property_descriptor(ctx, args.at(2))?;
+    target
+        .define_own_property(ctx, key, &desc, false)
+        .map(JsValue::new)
+}
+
+pub fn reflect_delete_property(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        target = stack,
+        target_object(ctx, args.at(0), "deleteProperty")?
+    );
+    let key = args.at(1).to_symbol(ctx)?;
+    target.delete(ctx, key, false).map(JsValue::new)
+}
+
+pub fn reflect_get(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(target = stack, target_object(ctx, args.at(0), "get")?);
+    let key = args.at(1).to_symbol(ctx)?;
+    let receiver = if args.size() > 2 {
+        args.at(2)
+    } else {
+        JsValue::new(*target)
+    };
+    operations::get_with_receiver(ctx, &mut target, key, receiver)
+}
+
+pub fn reflect_set(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(target = stack, target_object(ctx, args.at(0), "set")?);
+    let key = args.at(1).to_symbol(ctx)?;
+    let receiver = if args.size() > 3 {
+        args.at(3)
+    } else {
+        JsValue::new(*target)
+    };
+    operations::set_with_receiver(ctx, &mut target, key, args.at(2), receiver).map(JsValue::new)
+}
+
+pub fn reflect_get_own_property_descriptor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        target = stack,
+        target_object(ctx, args.at(0), "getOwnPropertyDescriptor")?
+    );
+    let key = args.at(1).to_symbol(ctx)?;
+    match operations::get_own_property(ctx, &mut target, key)? {
+        Some(desc) => super::from_property_descriptor(ctx, &desc),
+        None => Ok(JsValue::encode_undefined_value()),
+    }
+}
+
+pub fn reflect_get_prototype_of(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        target = stack,
+        target_object(ctx, args.at(0), "getPrototypeOf")?
+    );
+    Ok(match operations::get_prototype_of(ctx, &mut target)? {
+        Some(proto) => JsValue::new(proto),
+        None => JsValue::encode_null_value(),
+    })
+}
+
+pub fn reflect_set_prototype_of(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        target = stack,
+        target_object(ctx, args.at(0), "setPrototypeOf")?
+    );
+    let proto = args.at(1);
+    let proto = if proto.is_jsobject() {
+        Some(proto.get_jsobject())
+    } else if proto.is_null() {
+        None
+    } else {
+        return Err(JsValue::new(ctx.new_type_error(
+            "Reflect.setPrototypeOf: prototype must be an object or null",
+        )));
+    };
+    operations::set_prototype_of(ctx, &mut target, proto).map(JsValue::new)
+}
+
+pub fn reflect_has(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(target = stack, target_object(ctx, args.at(0), "has")?);
+    let key = args.at(1).to_symbol(ctx)?;
+    operations::has_property(ctx, &mut target, key).map(JsValue::new)
+}
+
+pub fn reflect_is_extensible(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        target = stack,
+        target_object(ctx, args.at(0), "isExtensible")?
+    );
+    operations::is_extensible(ctx, &mut target).map(JsValue::new)
+}
+
+pub fn reflect_own_keys(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(target = stack, target_object(ctx, args.at(0), "ownKeys")?);
+    letroot!(keys = stack, Vec::<JsValue>::new());
+    for key in operations::own_property_keys(ctx, &mut target)? {
+        keys.push(match key {
+            Symbol::Private(_) => JsValue::new(JsSymbol::new(ctx, key)),
+            _ => JsValue::new(ctx.key_to_jsstring(key)),
+        });
+    }
+    Ok(JsValue::new(JsArray::from_slice(ctx, &keys)))
+}
+
+pub fn reflect_prevent_extensions(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        target = stack,
+        target_object(ctx, args.at(0), "preventExtensions")?
+    );
+    operations::prevent_extensions(ctx, &mut target).map(JsValue::new)
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_reflect_in_global_object(mut self) -> Result<(), JsValue> {
+        let mut reflect = JsObject::new_empty(self);
+
+        def_native_method!(self, reflect, apply, reflect_apply, 3, W | C)?;
+        def_native_method!(self, reflect, construct, reflect_construct, 2, W | C)?;
+        def_native_method!(
+            self,
+            reflect,
+            defineProperty,
+            reflect_define_property,
+            3,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            reflect,
+            deleteProperty,
+            reflect_delete_property,
+            2,
+            W | C
+        )?;
+        def_native_method!(self, reflect, get, reflect_get, 2, W | C)?;
+        def_native_method!(
+            self,
+            reflect,
+            getOwnPropertyDescriptor,
+            reflect_get_own_property_descriptor,
+            2,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            reflect,
+            getPrototypeOf,
+            reflect_get_prototype_of,
+            1,
+            W | C
+        )?;
+        def_native_method!(self, reflect, has, reflect_has, 2, W | C)?;
+        def_native_method!(self, reflect, isExtensible, reflect_is_extensible, 1, W | C)?;
+        def_native_method!(self, reflect, ownKeys, reflect_own_keys, 1, W | C)?;
+        def_native_method!(
+            self,
+            reflect,
+            preventExtensions,
+            reflect_prevent_extensions,
+            1,
+            W | C
+        )?;
+        def_native_method!(self, reflect, set, reflect_set, 3, W | C)?;
+        def_native_method!(
+            self,
+            reflect,
+            setPrototypeOf,
+            reflect_set_prototype_of,
+            2,
+            W | C
+        )?;
+        let tag = JsString::new(self, "Reflect");
+        reflect.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+
+        let mut global_object = self.global_object();
+        def_native_property!(self, global_object, Reflect, reflect, W | C)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_construct_with_new_target() {
+        assert_eval_cases(&[
+            (
+                "function Sub() {} Sub.prototype = Object.create(Object.prototype); typeof Sub",
+                "function",
+            ),
+            (
+                "var d = Reflect.construct(Date, [0], Sub); (Object.getPrototypeOf(d) === Sub.prototype) + ',' + Date.prototype.getTime.call(d)",
+                "true,0",
+            ),
+            (
+                "var a = Reflect.construct(Array, [3], Sub); (Object.getPrototypeOf(a) === Sub.prototype) + ',' + Array.isArray(a) + ',' + a.length",
+                "true,true,3",
+            ),
+            (
+                "var e = Reflect.construct(TypeError, ['x'], Sub); (Object.getPrototypeOf(e) === Sub.prototype) + ',' + e.message",
+                "true,x",
+            ),
+            (
+                "Object.getPrototypeOf(Reflect.construct(Date, [])) === Date.prototype",
+                "true",
+            ),
+            (
+                "function NoProto() {} NoProto.prototype = 1; Object.getPrototypeOf(Reflect.construct(Date, [], NoProto)) === Date.prototype",
+                "true",
+            ),
+            (
+                "try { Reflect.construct(Date, [], undefined); 'no error' } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "try { Reflect.construct(Date, [], {}); 'no error' } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "try { Reflect.construct({}, []); 'no error' } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "Reflect.construct(Array, { length: 2, 0: 'a', 1: 'b' }).join('-')",
+                "a-b",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eval_cases(&[
+            (
+                "try { Reflect.apply(Math.max, null, 1); 'no error' } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            ("Reflect.apply(Math.max, null, { length: 2, 0: 1, 1: 5 })", "5"),
+            (
+                "try { Reflect.getPrototypeOf(1); 'no error' } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "Reflect.get({ get x() { return this.y; } }, 'x', { y: 7 })",
+                "7",
+            ),
+            (
+                "var receiver = {}; var target = {}; Reflect.set(target, 'a', 1, receiver) + ',' + receiver.a + ',' + target.a",
+                "true,1,undefined",
+            ),
+            (
+                "Reflect.defineProperty(Object.preventExtensions({}), 'a', { value: 1 })",
+                "false",
+            ),
+            (
+                "Reflect.setPrototypeOf(Object.preventExtensions({}), null)",
+                "false",
+            ),
+            (
+                "try { Reflect.setPrototypeOf({}, 1); 'no error' } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "var frozen = Object.freeze({ a: 1 }); Reflect.set(frozen, 'a', 2) + ',' + Reflect.deleteProperty(frozen, 'a')",
+                "false,false",
+            ),
+            ("Reflect.has(Object.create({ a: 1 }), 'a')", "true"),
+            ("Reflect.getOwnPropertyDescriptor({}, 'a')", "undefined"),
+        ]);
+    }
+}
@@ -1,10 +1,13 @@
+use super::{number::to_integer_or_infinity, string::is_regexp};
 use crate::constant::S_CONSTURCTOR;
 use crate::js_method_table;
+use crate::vm::operations::call;
 use crate::{define_jsclass, prelude::*, vm::context::Context};
-use regress::Regex;
+use regress::{Match, Regex};
 use std::{
     intrinsics::unlikely,
     mem::{size_of, ManuallyDrop},
+    ops::Range,
 };
 
 /// The internal representation on a `RegExp` object.
@@ -16,8 +19,8 @@ pub struct RegExp {
     /// Update last_index, set if global or sticky flags are set.
     use_last_index: bool,
 
-    /// String of parsed flags.
-    pub(crate) flags: Box<str>,
+    /// Flag 'd' - match results contain `indices`.
+    has_indices: bool,
 
     /// Flag 's' - dot matches newline characters.
     dot_all: bool,
@@ -40,68 +43,83 @@ pub struct RegExp {
     pub(crate) original_source: Box<str>,
     pub(crate) original_flags: Box<str>,
 }
-extern "C" fn drop_regexp_fn(obj: GcPointer<JsObject>) {
-    unsafe { ManuallyDrop::drop(obj.data::<RegExp>()) }
-}
-
-extern "C" fn deser(obj: &mut JsObject, deser: &mut Deserializer) {
-    unsafe {
-        let use_last_index = bool::deserialize_inplace(deser);
-        let flags = String::deserialize_inplace(deser);
-        let dot_all = bool::deserialize_inplace(deser);
-        let global = bool::deserialize_inplace(deser);
-        let ignore_case = bool::deserialize_inplace(deser);
-        let multiline = bool::deserialize_inplace(deser);
-        let sticky = bool::deserialize_inplace(deser);
-        let unicode = bool::deserialize_inplace(deser);
-        let original_source = String::deserialize_inplace(deser);
-        let original_flags = String::deserialize_inplace(deser);
 
-        let mut soctxed_flags = String::new();
-        if original_flags.contains('g') {
-            soctxed_flags.push('g');
-        }
-        if original_flags.contains('i') {
-            soctxed_flags.push('i');
-        }
-        if original_flags.contains('m') {
-            soctxed_flags.push('m');
-        }
-        if original_flags.contains('s') {
-            soctxed_flags.push('s');
-        }
-        if original_flags.contains('u') {
-            soctxed_flags.push('u');
+impl RegExp {
+    /// Parses `flags` and compiles `source`. Errors are messages of the SyntaxError to throw.
+    fn new(source: Box<str>, flags: Box<str>) -> Result<Self, String> {
+        let mut has_indices = false;
+        let mut dot_all = false;
+        let mut global = false;
+        let mut ignore_case = false;
+        let mut multiline = false;
+        let mut sticky = false;
+        let mut unicode = false;
+        for flag in flags.chars() {
+            let seen = match flag {
+                'd' => &mut has_indices,
+                'g' => &mut global,
+                'i' => &mut ignore_case,
+                'm' => &mut multiline,
+                's' => &mut dot_all,
+                'u' => &mut unicode,
+                'y' => &mut sticky,
+                _ => return Err(format!("Invalid regular expression flags '{}'", flags)),
+            };
+            if *seen {
+                return Err(format!("Invalid regular expression flags '{}'", flags));
+            }
+            *seen = true;
         }
-        if original_flags.contains('y') {
-            soctxed_flags.push('y');
+
+        let mut matcher_flags = String::new();
+        for &(flag, set) in [
+            ('i', ignore_case),
+            ('m', multiline),
+            ('s', dot_all),
+            ('u', unicode),
+        ]
+        .iter()
+        {
+            if set {
+                matcher_flags.push(flag);
+            }
         }
-        let matcher = Regex::with_flags(&original_source, soctxed_flags.as_str()).unwrap();
-        *obj.data::<RegExp>() = ManuallyDrop::new(RegExp {
-            use_last_index,
-            flags: flags.into_boxed_str(),
+        let matcher = Regex::with_flags(&source, matcher_flags.as_str())
+            .map_err(|error| format!("Invalid regular expression: /{}/: {}", source, error.text))?;
+        Ok(Self {
+            matcher,
+            use_last_index: global || sticky,
+            has_indices,
             dot_all,
             global,
             ignore_case,
             multiline,
             sticky,
             unicode,
-            original_source: original_source.into_boxed_str(),
-            original_flags: original_flags.into_boxed_str(),
-            matcher,
-        });
+            original_source: source,
+            original_flags: flags,
+        })
+    }
+}
+
+extern "C" fn drop_regexp_fn(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<RegExp>()) }
+}
+
+extern "C" fn deser(obj: &mut JsObject, deser: &mut Deserializer) {
+    unsafe {
+        let original_source = String::deserialize_inplace(deser);
+        let original_flags = String::deserialize_inplace(deser);
+        let regexp = RegExp::new(
+            original_source.into_boxed_str(),
+            original_flags.into_boxed_str(),
+        )
+        .unwrap();
+        *obj.data::<RegExp>() = ManuallyDrop::new(regexp);
     }
 }
 extern "C" fn ser(obj: &JsObject, serializer: &mut SnapshotSerializer) {
     let data = obj.data::<RegExp>();
-    data.use_last_index.serialize(serializer);
-    data.flags.to_string().serialize(serializer);
-    data.dot_all.serialize(serializer);
-    data.global.serialize(serializer);
-    data.ignore_case.serialize(serializer);
-    data.multiline.serialize(serializer);
-    data.sticky.serialize(serializer);
-    data.unicode.serialize(serializer);
     data.original_source.to_string().serialize(serializer);
     data.original_flags.to_string().serialize(serializer);
 }
@@ -122,20 +140,9 @@ define_jsclass!(
 
 impl GcPointer<Context> {
     pub(crate) fn init_regexp_in_global_object(mut self) -> Result<(), JsValue> {
-        let mut proto = self.global_data.regexp_prototype.unwrap();
-        let constructor = proto
-            .get_own_property(self, S_CONSTURCTOR.intern())
-            .unwrap()
-            .value();
+        let constructor = self.global_data.regexp_constructor.unwrap();
         self.global_object()
             .put(self, "RegExp".intern(), JsValue::new(constructor), false)?;
-        let mut sym = self
-            .global_object()
-            .get(self, "Symbol".intern())?
-            .get_jsobject();
-        let sym_match = sym.get(self, "match".intern())?.to_symbol(self)?;
-        let f = JsNativeFunction::new(self, sym_match, regexp_match, 1);
-        proto.put(self, sym_match, JsValue::new(f), false)?;
         Ok(())
     }
 
@@ -163,11 +170,40 @@ impl GcPointer<Context> {
             false,
         )?;
         def_native_method!(self, constructor, ___splitFast, regexp_split_fast, 3)?;
+        super::define_species(self, &mut constructor)?;
         def_native_method!(self, proto, exec, regexp_exec, 1)?;
         def_native_method!(self, proto, test, regexp_test, 1)?;
         def_native_method!(self, proto, toString, regexp_to_string, 0)?;
+        def_native_method!(self, proto, compile, regexp_compile, 2, W | C)?;
+        def_native_accessor!(self, proto, dotAll, regexp_dot_all, C)?;
+        def_native_accessor!(self, proto, flags, regexp_flags, C)?;
+        def_native_accessor!(self, proto, global, regexp_global, C)?;
+        def_native_accessor!(self, proto, hasIndices, regexp_has_indices, C)?;
+        def_native_accessor!(self, proto, ignoreCase, regexp_ignore_case, C)?;
+        def_native_accessor!(self, proto, multiline, regexp_multiline, C)?;
+        def_native_accessor!(self, proto, source, regexp_source, C)?;
+        def_native_accessor!(self, proto, sticky, regexp_sticky, C)?;
+        def_native_accessor!(self, proto, unicode, regexp_unicode, C)?;
+
+        let sym_match = "Symbol.match".intern().private();
+        let f = JsNativeFunction::new(self, sym_match, regexp_match, 1);
+        proto.define_own_property(
+            self,
+            sym_match,
+            &*DataDescriptor::new(JsValue::new(f), W | C),
+            false,
+        )?;
+        let sym_search = "Symbol.search".intern().private();
+        let f = JsNativeFunction::new(self, sym_search, regexp_search, 1);
+        proto.define_own_property(
+            self,
+            sym_search,
+            &*DataDescriptor::new(JsValue::new(f), W | C),
+            false,
+        )?;
 
         self.global_data.regexp_prototype = Some(proto);
+        self.global_data.regexp_constructor = Some(constructor);
         Ok(())
     }
 }
@@ -219,211 +255,362 @@ pub fn regexp_split_fast(ctx: GcPointer<Context>, args: &Arguments) -> Result<Js
     }
     Ok(JsValue::new(result))
 }
+
+/// Converts `pattern` argument to the source text of a regular expression, `undefined` is empty.
+fn to_source(ctx: GcPointer<Context>, pattern: JsValue) -> Result<Box<str>, JsValue> {
+    if pattern.is_undefined() {
+        return Ok(String::new().into_boxed_str());
+    }
+    Ok(pattern.to_string(ctx)?.into_boxed_str())
+}
+
+/// Compiles a regular expression, throwing SyntaxError when `source` or `flags` are invalid.
+fn new_regexp(
+    ctx: GcPointer<Context>,
+    source: Box<str>,
+    flags: Box<str>,
+) -> Result<RegExp, JsValue> {
+    RegExp::new(source, flags).map_err(|msg| JsValue::new(ctx.new_syntax_error(msg)))
+}
+
 pub fn regexp_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let proto = ctx.global_data.regexp_prototype.unwrap();
-    let structure = Structure::new_indexed(ctx, Some(proto), false);
-
-    let arg = args.at(0);
-
-    let (regex_body, mut regex_flags) = match arg {
-        arg if arg.is_jsstring() => (
-            arg.to_string(ctx)?.into_boxed_str(),
-            String::new().into_boxed_str(),
-        ),
-        arg if arg.is_jsobject() => {
-            let obj = arg.get_jsobject();
-            if obj.is_class(RegExp::get_class()) {
-                (
-                    obj.data::<RegExp>().original_source.clone(),
-                    obj.data::<RegExp>().original_flags.clone(),
-                )
-            } else {
-                (
-                    String::new().into_boxed_str(),
-                    String::new().into_boxed_str(),
-                )
+    let stack = ctx.shadowstack();
+    letroot!(pattern = stack, args.at(0));
+    letroot!(flags = stack, args.at(1));
+    let pattern_is_regexp = is_regexp(ctx, *pattern)?;
+    if !args.ctor_call && pattern_is_regexp && flags.is_undefined() {
+        let constructor = pattern.get_jsobject().get(ctx, S_CONSTURCTOR.intern())?;
+        if let Some(regexp_constructor) = ctx.global_data.regexp_constructor {
+            if JsValue::same_value(constructor, JsValue::new(regexp_constructor)) {
+                return Ok(*pattern);
             }
         }
-        _ => return Err(JsValue::encode_undefined_value()),
-    };
+    }
 
-    if args.at(1).is_jsstring() {
-        regex_flags = args
-            .at(1)
-            .get_jsstring()
-            .as_str()
-            .to_owned()
-            .into_boxed_str();
-    }
-
-    let mut soctxed_flags = String::new();
-    let mut dot_all = false;
-    let mut global = false;
-    let mut ignore_case = false;
-    let mut multiline = false;
-    let mut sticky = false;
-    let mut unicode = false;
-    if regex_flags.contains('g') {
-        global = true;
-        soctxed_flags.push('g');
-    }
-    if regex_flags.contains('i') {
-        ignore_case = true;
-        soctxed_flags.push('i');
-    }
-    if regex_flags.contains('m') {
-        multiline = true;
-        soctxed_flags.push('m');
-    }
-    if regex_flags.contains('s') {
-        dot_all = true;
-        soctxed_flags.push('s');
-    }
-    if regex_flags.contains('u') {
-        unicode = true;
-        soctxed_flags.push('u');
-    }
-    if regex_flags.contains('y') {
-        sticky = true;
-        soctxed_flags.push('y');
-    }
-
-    let matcher = match Regex::with_flags(&regex_body, soctxed_flags.as_str()) {
-        Err(error) => {
-            let msg = JsString::new(
-                ctx,
-                format!("failed to create matcher: {} in {}", error.text, regex_body),
-            );
-            return Err(JsValue::new(JsSyntaxError::new(ctx, msg, None)));
+    let (source, flags) = if let Some(regexp) = to_regexp(*pattern) {
+        let source = regexp.data::<RegExp>().original_source.clone();
+        let flags = if flags.is_undefined() {
+            regexp.data::<RegExp>().original_flags.clone()
+        } else {
+            to_source(ctx, *flags)?
+        };
+        (source, flags)
+    } else if pattern_is_regexp {
+        let mut object = pattern.get_jsobject();
+        let source = object.get(ctx, "source".intern())?;
+        let source = to_source(ctx, source)?;
+        if flags.is_undefined() {
+            *flags = object.get(ctx, "flags".intern())?;
         }
-        Ok(val) => val,
+        (source, to_source(ctx, *flags)?)
+    } else {
+        (to_source(ctx, *pattern)?, to_source(ctx, *flags)?)
     };
+    let regexp = new_regexp(ctx, source, flags)?;
 
-    let regexp = RegExp {
-        matcher,
-        use_last_index: global || sticky,
-        flags: soctxed_flags.clone().into_boxed_str(),
-        dot_all,
-        global,
-        ignore_case,
-        multiline,
-        sticky,
-        unicode,
-        original_source: regex_body,
-        original_flags: regex_flags,
+    // `this` of constructor calls is allocated from new.target so subclasses keep their prototype.
+    let proto = if args.ctor_call && args.this.is_jsobject() {
+        args.this.get_jsobject().prototype().copied()
+    } else {
+        ctx.global_data.regexp_prototype
     };
-    let mut this = JsObject::new(ctx, &structure, RegExp::get_class(), ObjectTag::Regex);
-    *this.data::<RegExp>() = ManuallyDrop::new(regexp);
-    let f = JsString::new(ctx, soctxed_flags);
-    this.put(ctx, "flags".intern(), JsValue::new(f), false)?;
-    this.put(ctx, "global".intern(), JsValue::new(global), false)?;
-    this.put(ctx, "unicode".intern(), JsValue::new(unicode), false)?;
-    this.put(ctx, "lastIndex".intern(), JsValue::new(0), false)?;
-    Ok(JsValue::new(this))
+    Ok(JsValue::new(new_regexp_object(ctx, proto, regexp)?))
 }
 
-pub fn regexp_test(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    if unlikely(!args.this.is_jsobject()) {
-        return Err(JsValue::new(ctx.new_type_error(
-            "RegExp.prototype.exec method called on incompatible value",
-        )));
-    }
-    let mut this = args.this.get_jsobject();
-    let mut last_index = this.get(ctx, "lastIndex".intern())?.to_int32(ctx)? as usize;
+/// Allocates a `RegExp` object with `lastIndex` set to zero.
+pub(crate) fn new_regexp_object(
+    ctx: GcPointer<Context>,
+    proto: Option<GcPointer<JsObject>>,
+    regexp: RegExp,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(structure = stack, Structure::new_indexed(ctx, proto, false));
+    letroot!(
+        this = stack,
+        JsObject::new(ctx, &structure, RegExp::get_class(), ObjectTag::Regex)
+    );
+    *this.data::<RegExp>() = ManuallyDrop::new(regexp);
+    this.define_own_property(
+        ctx,
+        "lastIndex".intern(),
+        &*DataDescriptor::new(JsValue::new(0), W),
+        false,
+    )?;
+    Ok(*this)
+}
 
-    let arg_str = args.at(0).to_string(ctx)?;
-    if this.is_class(RegExp::get_class()) {
-        let result = if let Some(m) = this
-            .data::<RegExp>()
-            .matcher
-            .find_from(arg_str.as_str(), last_index)
-            .next()
-        {
-            if this.data::<RegExp>().use_last_index {
-                last_index = m.end();
-            }
-            true
-        } else {
-            if this.data::<RegExp>().use_last_index {
-                last_index = 0;
-            }
-            false
-        };
-        this.put(
-            ctx,
-            "lastIndex".intern(),
-            JsValue::new(last_index as u32),
-            false,
-        )?;
-        return Ok(JsValue::new(result));
+/// RegExp.prototype.compile(pattern, flags)
+pub fn regexp_compile(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let this = match to_regexp(args.this) {
+        Some(this) => this,
+        None => {
+            return Err(JsValue::new(ctx.new_type_error(
+                "RegExp.prototype.compile method called on incompatible value",
+            )))
+        }
+    };
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this);
+    let (source, flags) = if let Some(pattern) = to_regexp(args.at(0)) {
+        if unlikely(!args.at(1).is_undefined()) {
+            return Err(JsValue::new(ctx.new_type_error(
+                "Cannot supply flags when constructing one RegExp from another",
+            )));
+        }
+        let pattern = pattern.data::<RegExp>();
+        (
+            pattern.original_source.clone(),
+            pattern.original_flags.clone(),
+        )
     } else {
-        return Err(JsValue::new(ctx.new_type_error(
-            "RegExp.prototype.test method called on incompatible value",
-        )));
+        (to_source(ctx, args.at(0))?, to_source(ctx, args.at(1))?)
+    };
+    let regexp = new_regexp(ctx, source, flags)?;
+    unsafe {
+        ManuallyDrop::drop(this.data::<RegExp>());
     }
+    *this.data::<RegExp>() = ManuallyDrop::new(regexp);
+    this.put(ctx, "lastIndex".intern(), JsValue::new(0), true)?;
+    Ok(JsValue::new(*this))
 }
 
-pub fn regexp_exec(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    if unlikely(!args.this.is_jsobject()) {
-        return Err(JsValue::new(ctx.new_type_error(
-            "RegExp.prototype.exec method called on incompatible value",
-        )));
+/// ToLength(value)
+fn to_length(ctx: GcPointer<Context>, value: JsValue) -> Result<f64, JsValue> {
+    Ok(to_integer_or_infinity(ctx, value)?
+        .max(0.0)
+        .min(9007199254740991.0))
+}
+
+/// AdvanceStringIndex(S, index, unicode)
+fn advance_string_index(string: &JsString, index: f64, unicode: bool) -> f64 {
+    if !unicode || index + 1.0 >= string.len() as f64 {
+        return index + 1.0;
     }
-    let mut this = args.this.get_jsobject();
-    let mut last_index = this.get(ctx, "lastIndex".intern())?.to_int32(ctx)? as usize;
-    let mut obj = this;
-    if unlikely(!this.is_class(RegExp::get_class())) {
-        return Err(JsValue::new(ctx.new_type_error(
-            "RegExp.prototype.exec method called on incompatible value",
-        )));
+    index + string.code_point_at(index as u32).1 as f64
+}
+
+/// Converts between UTF-16 indices exposed to JS and byte offsets into the UTF-8 text the matcher
+/// runs on.
+struct Input<'a> {
+    text: &'a str,
+    ascii: bool,
+}
+
+impl<'a> Input<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            ascii: text.is_ascii(),
+        }
     }
-    let regex = obj.data::<RegExp>();
-    let arg_str = args.at(0).to_string(ctx)?;
-    let result = if let Some(m) = regex.matcher.find_from(arg_str.as_str(), last_index).next() {
-        if regex.use_last_index {
-            last_index = m.end();
+
+    /// Byte offset of the code unit at `index`, rounded up to the next character boundary.
+    fn byte_index(&self, index: usize) -> usize {
+        if self.ascii {
+            return index.min(self.text.len());
         }
-        let groups = m.captures.len() + 1;
-        let mut result = Vec::with_capacity(groups);
-        for i in 0..groups {
-            if let Some(range) = m.group(i) {
-                result.push(JsValue::new(JsString::new(
-                    ctx,
-                    arg_str.get(range).expect("Could not get slice"),
-                )));
-            } else {
-                result.push(JsValue::encode_undefined_value());
+        let mut units = 0;
+        for (offset, c) in self.text.char_indices() {
+            if units >= index {
+                return offset;
             }
+            units += c.len_utf16();
         }
-        let v = result;
-        let mut result = JsArray::new(ctx, v.len() as _);
-        for i in 0..v.len() {
-            result.put(ctx, Symbol::Index(i as _), v[i], false)?;
+        self.text.len()
+    }
+
+    /// Code unit index of the byte offset `offset`.
+    fn utf16_index(&self, offset: usize) -> usize {
+        if self.ascii {
+            return offset;
         }
-        result.define_own_property(
+        self.text[..offset].encode_utf16().count()
+    }
+}
+
+/// Object with a null prototype that maps names of the groups to `value` of their captures,
+/// `undefined` when the pattern has no named groups.
+fn named_groups(
+    ctx: GcPointer<Context>,
+    m: &Match,
+    value: impl Fn(Option<Range<usize>>) -> JsValue,
+) -> Result<JsValue, JsValue> {
+    if m.named_groups().next().is_none() {
+        return Ok(JsValue::encode_undefined_value());
+    }
+    let stack = ctx.shadowstack();
+    letroot!(structure = stack, Structure::new_indexed(ctx, None, false));
+    letroot!(
+        groups = stack,
+        JsObject::new(ctx, &structure, JsObject::get_class(), ObjectTag::Ordinary)
+    );
+    for (name, range) in m.named_groups() {
+        groups.define_own_property(
             ctx,
-            "index".intern(),
-            &*DataDescriptor::new(JsValue::new(m.start() as u32), W | C | E),
+            name.intern(),
+            &*DataDescriptor::new(value(range), W | C | E),
             false,
         )?;
-        let input = JsValue::new(JsString::new(ctx, arg_str));
-        result.put(ctx, "input".intern(), input, false)?;
+    }
+    Ok(JsValue::new(*groups))
+}
 
-        JsValue::new(result)
+/// RegExpBuiltinExec(R, S)
+fn builtin_exec(
+    ctx: GcPointer<Context>,
+    regexp: GcPointer<JsObject>,
+    string: GcPointer<JsString>,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, regexp);
+    letroot!(string = stack, string);
+    let last_index = to_length(ctx, this.get(ctx, "lastIndex".intern())?)?;
+    let (use_last_index, sticky, has_indices) = {
+        let regexp = this.data::<RegExp>();
+        (regexp.use_last_index, regexp.sticky, regexp.has_indices)
+    };
+    let last_index = if use_last_index { last_index } else { 0.0 };
+
+    let text = string.as_str().into_owned();
+    let input = Input::new(&text);
+    let found = if last_index <= string.len() as f64 {
+        let start = input.byte_index(last_index as usize);
+        match this.data::<RegExp>().matcher.find_from(&text, start).next() {
+            // sticky matches may only start at lastIndex
+            Some(m) if !sticky || m.start() == start => Some(m),
+            _ => None,
+        }
     } else {
-        if regex.use_last_index {
-            last_index = 0;
+        None
+    };
+    let m = match found {
+        Some(m) => m,
+        None => {
+            if use_last_index {
+                this.put(ctx, "lastIndex".intern(), JsValue::new(0), true)?;
+            }
+            return Ok(JsValue::encode_null_value());
         }
-        JsValue::encode_null_value()
     };
-    this.put(
+    if use_last_index {
+        let end = input.utf16_index(m.end()) as u32;
+        this.put(ctx, "lastIndex".intern(), JsValue::new(end), true)?;
+    }
+
+    letroot!(result = stack, JsArray::new(ctx, 0));
+    let index = input.utf16_index(m.start()) as u32;
+    result.define_own_property(
         ctx,
-        "lastIndex".intern(),
-        JsValue::new(last_index as u32),
+        "index".intern(),
+        &*DataDescriptor::new(JsValue::new(index), W | C | E),
         false,
     )?;
+    result.define_own_property(
+        ctx,
+        "input".intern(),
+        &*DataDescriptor::new(JsValue::new(*string), W | C | E),
+        false,
+    )?;
+    let capture = |range: Option<Range<usize>>| match range {
+        Some(range) => JsValue::new(string.substring(
+            ctx,
+            input.utf16_index(range.start) as u32,
+            input.utf16_index(range.end) as u32,
+        )),
+        None => JsValue::encode_undefined_value(),
+    };
+    for i in 0..=m.captures.len() {
+        result.put(ctx, Symbol::Index(i as _), capture(m.group(i)), false)?;
+    }
+    let groups = named_groups(ctx, &m, capture)?;
+    result.define_own_property(
+        ctx,
+        "groups".intern(),
+        &*DataDescriptor::new(groups, W | C | E),
+        false,
+    )?;
+
+    if has_indices {
+        let pair = |range: Option<Range<usize>>| match range {
+            Some(range) => {
+                let start = JsValue::new(input.utf16_index(range.start) as u32);
+                let end = JsValue::new(input.utf16_index(range.end) as u32);
+                JsValue::new(JsArray::from_slice(ctx, &[start, end]))
+            }
+            None => JsValue::encode_undefined_value(),
+        };
+        letroot!(indices = stack, JsArray::new(ctx, 0));
+        for i in 0..=m.captures.len() {
+            indices.put(ctx, Symbol::Index(i as _), pair(m.group(i)), false)?;
+        }
+        let groups = named_groups(ctx, &m, pair)?;
+        indices.define_own_property(
+            ctx,
+            "groups".intern(),
+            &*DataDescriptor::new(groups, W | C | E),
+            false,
+        )?;
+        result.define_own_property(
+            ctx,
+            "indices".intern(),
+            &*DataDescriptor::new(JsValue::new(*indices), W | C | E),
+            false,
+        )?;
+    }
+    Ok(JsValue::new(*result))
+}
+
+/// RegExpExec(R, S), a callable `exec` property takes precedence over the builtin matcher.
+fn exec(
+    ctx: GcPointer<Context>,
+    regexp: GcPointer<JsObject>,
+    string: GcPointer<JsString>,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, regexp);
+    letroot!(string = stack, string);
+    let exec = this.get(ctx, "exec".intern())?;
+    if exec.is_callable() {
+        let result = call(ctx, exec, JsValue::new(*this), &mut [JsValue::new(*string)])?;
+        if unlikely(!result.is_jsobject() && !result.is_null()) {
+            return Err(JsValue::new(ctx.new_type_error(
+                "The result of a RegExp exec must be null or an object",
+            )));
+        }
+        return Ok(result);
+    }
+    if unlikely(!this.is_class(RegExp::get_class())) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "RegExp.prototype.exec method called on incompatible value",
+        )));
+    }
+    builtin_exec(ctx, *this, *string)
+}
 
-    Ok(result)
+pub fn regexp_test(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if unlikely(!args.this.is_jsobject()) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "RegExp.prototype.test method called on incompatible value",
+        )));
+    }
+    let string = args.at(0).to_jsstring(ctx)?;
+    let result = exec(ctx, args.this.get_jsobject(), string)?;
+    Ok(JsValue::new(!result.is_null()))
+}
+
+pub fn regexp_exec(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let this = match to_regexp(args.this) {
+        Some(this) => this,
+        None => {
+            return Err(JsValue::new(ctx.new_type_error(
+                "RegExp.prototype.exec method called on incompatible value",
+            )))
+        }
+    };
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this);
+    let string = args.at(0).to_jsstring(ctx)?;
+    builtin_exec(ctx, *this, string)
 }
 
 fn to_regexp(val: JsValue) -> Option<GcPointer<JsObject>> {
@@ -433,61 +620,215 @@ fn to_regexp(val: JsValue) -> Option<GcPointer<JsObject>> {
     None
 }
 
-pub fn regexp_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    match to_regexp(args.this) {
-        Some(object) => {
-            let regex = object.data::<RegExp>();
+/// Reads a flag of `this` for the flag accessors of `RegExp.prototype`, which itself has no flags.
+fn flag_getter(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    name: &str,
+    flag: fn(&RegExp) -> bool,
+) -> Result<JsValue, JsValue> {
+    if let Some(object) = to_regexp(args.this) {
+        return Ok(JsValue::new(flag(object.data::<RegExp>())));
+    }
+    if let Some(proto) = ctx.global_data.regexp_prototype {
+        if JsValue::same_value(args.this, JsValue::new(proto)) {
+            return Ok(JsValue::encode_undefined_value());
+        }
+    }
+    Err(JsValue::new(ctx.new_type_error(format!(
+        "RegExp.prototype.{} getter called on incompatible value",
+        name
+    ))))
+}
 
-            Ok(JsValue::new(JsString::new(
-                ctx,
-                format!("/{}/{}", regex.original_source, regex.flags),
-            )))
+pub fn regexp_has_indices(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    flag_getter(ctx, args, "hasIndices", |regexp| regexp.has_indices)
+}
+
+pub fn regexp_global(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    flag_getter(ctx, args, "global", |regexp| regexp.global)
+}
+
+pub fn regexp_ignore_case(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    flag_getter(ctx, args, "ignoreCase", |regexp| regexp.ignore_case)
+}
+
+pub fn regexp_multiline(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    flag_getter(ctx, args, "multiline", |regexp| regexp.multiline)
+}
+
+pub fn regexp_dot_all(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    flag_getter(ctx, args, "dotAll", |regexp| regexp.dot_all)
+}
+
+pub fn regexp_unicode(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    flag_getter(ctx, args, "unicode", |regexp| regexp.unicode)
+}
+
+pub fn regexp_sticky(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    flag_getter(ctx, args, "sticky", |regexp| regexp.sticky)
+}
+
+/// get RegExp.prototype.flags, generic over objects with flag properties.
+pub fn regexp_flags(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if unlikely(!args.this.is_jsobject()) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "RegExp.prototype.flags getter called on non-object",
+        )));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, args.this.get_jsobject());
+    let mut flags = String::new();
+    for &(flag, name) in [
+        ('d', "hasIndices"),
+        ('g', "global"),
+        ('i', "ignoreCase"),
+        ('m', "multiline"),
+        ('s', "dotAll"),
+        ('u', "unicode"),
+        ('y', "sticky"),
+    ]
+    .iter()
+    {
+        if this.get(ctx, name.intern())?.to_boolean() {
+            flags.push(flag);
         }
-        None => Err(JsValue::new(
-            ctx.new_type_error("RegExp.prototype.toString is not generic"),
-        )),
     }
+    Ok(JsValue::new(JsString::new(ctx, flags)))
+}
+
+/// EscapeRegExpPattern(P, F), the result parses back as the same regular expression literal.
+fn escape_pattern(source: &str) -> String {
+    if source.is_empty() {
+        return "(?:)".to_owned();
+    }
+    let mut escaped = String::with_capacity(source.len());
+    let mut in_class = false;
+    let mut chars = source.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                escaped.push(c);
+                if let Some(c) = chars.next() {
+                    escaped.push(c);
+                }
+            }
+            '/' if !in_class => escaped.push_str("\\/"),
+            '[' => {
+                in_class = true;
+                escaped.push(c);
+            }
+            ']' => {
+                in_class = false;
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\u{2028}' => escaped.push_str("\\u2028"),
+            '\u{2029}' => escaped.push_str("\\u2029"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub fn regexp_source(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if let Some(object) = to_regexp(args.this) {
+        let source = escape_pattern(&object.data::<RegExp>().original_source);
+        return Ok(JsValue::new(JsString::new(ctx, source)));
+    }
+    if let Some(proto) = ctx.global_data.regexp_prototype {
+        if JsValue::same_value(args.this, JsValue::new(proto)) {
+            return Ok(JsValue::new(JsString::new(ctx, "(?:)")));
+        }
+    }
+    Err(JsValue::new(ctx.new_type_error(
+        "RegExp.prototype.source getter called on incompatible value",
+    )))
+}
+
+pub fn regexp_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if unlikely(!args.this.is_jsobject()) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "RegExp.prototype.toString method called on incompatible value",
+        )));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, args.this.get_jsobject());
+    let source = this.get(ctx, "source".intern())?.to_string(ctx)?;
+    let flags = this.get(ctx, "flags".intern())?.to_string(ctx)?;
+    Ok(JsValue::new(JsString::new(
+        ctx,
+        format!("/{}/{}", source, flags),
+    )))
 }
 
 /// @@match
 pub fn regexp_match(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let arg_str = args.at(0).to_string(ctx)?;
-    let matches = if let Some(object) = to_regexp(args.this) {
-        let regex = object.data::<RegExp>();
-        let mut matches = vec![];
-        for mat in regex.matcher.find_iter(&arg_str) {
-            let match_vec: Vec<JsValue> = mat
-                .groups()
-                .map(|group| match group {
-                    Some(range) => JsValue::new(JsString::new(ctx, &arg_str[range])),
-                    None => JsValue::encode_undefined_value(),
-                })
-                .collect();
-
-            let mut match_val = JsArray::from_slice(ctx, &match_vec);
-            match_val.put(
-                ctx,
-                "index".intern(),
-                JsValue::new(mat.start() as u32),
-                false,
-            )?;
-            let input = JsString::new(ctx, arg_str.clone());
-            match_val.put(ctx, "input".intern(), JsValue::new(input), false)?;
-            matches.push(JsValue::new(match_val));
-            if !regex.flags.contains('g') {
-                break;
+    if unlikely(!args.this.is_jsobject()) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "RegExp.prototype[Symbol.match] method called on incompatible value",
+        )));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(rx = stack, args.this.get_jsobject());
+    letroot!(string = stack, args.at(0).to_jsstring(ctx)?);
+    let flags = rx.get(ctx, "flags".intern())?.to_jsstring(ctx)?;
+    if !flags.code_units().any(|unit| unit == b'g' as u16) {
+        return exec(ctx, *rx, *string);
+    }
+    let full_unicode = flags.code_units().any(|unit| unit == b'u' as u16);
+    rx.put(ctx, "lastIndex".intern(), JsValue::new(0), true)?;
+    letroot!(matches = stack, JsArray::new(ctx, 0));
+    let mut n = 0;
+    loop {
+        let result = exec(ctx, *rx, *string)?;
+        if result.is_null() {
+            if n == 0 {
+                return Ok(JsValue::encode_null_value());
             }
+            return Ok(JsValue::new(*matches));
         }
+        let matched = result
+            .get_jsobject()
+            .get(ctx, Symbol::Index(0))?
+            .to_jsstring(ctx)?;
+        matches.put(ctx, Symbol::Index(n), JsValue::new(matched), false)?;
+        n += 1;
+        if matched.is_empty() {
+            let this_index = to_length(ctx, rx.get(ctx, "lastIndex".intern())?)?;
+            let next_index = advance_string_index(&string, this_index, full_unicode);
+            rx.put(ctx, "lastIndex".intern(), JsValue::new(next_index), true)?;
+        }
+    }
+}
 
-        matches
-    } else {
-        return Err(JsValue::new(
-            ctx.new_type_error("RegExp.prototype.@@match is not generic"),
-        ));
-    };
-
-    let result = JsArray::from_slice(ctx, &matches);
-    Ok(JsValue::new(result))
+/// @@search
+pub fn regexp_search(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    if unlikely(!args.this.is_jsobject()) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "RegExp.prototype[Symbol.search] method called on incompatible value",
+        )));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(rx = stack, args.this.get_jsobject());
+    letroot!(string = stack, args.at(0).to_jsstring(ctx)?);
+    letroot!(
+        previous_last_index = stack,
+        rx.get(ctx, "lastIndex".intern())?
+    );
+    if !JsValue::same_value(*previous_last_index, JsValue::new(0)) {
+        rx.put(ctx, "lastIndex".intern(), JsValue::new(0), true)?;
+    }
+    letroot!(result = stack, exec(ctx, *rx, *string)?);
+    let current_last_index = rx.get(ctx, "lastIndex".intern())?;
+    if !JsValue::same_value(current_last_index, *previous_last_index) {
+        rx.put(ctx, "lastIndex".intern(), *previous_last_index, true)?;
+    }
+    if result.is_null() {
+        return Ok(JsValue::new(-1));
+    }
+    result.get_jsobject().get(ctx, "index".intern())
 }
 
 use std::str::pattern::{Pattern, SearchStep, Searcher};
@@ -552,3 +893,57 @@ unsafe impl<'r, 't> Searcher<'t> for RegexSearcher<'r, 't> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_replace_substitution() {
+        assert_eval_cases(&[
+            ("'abc'.replace(/b/, \"[$`|$&|$']\")", "a[a|b|c]c"),
+            ("'abc'.replace(/b/, '$$')", "a$c"),
+            ("'abc'.replace(/b/, 'x$')", "ax$c"),
+            ("'abc'.replace(/b/, '$%')", "a$%c"),
+            ("'abc'.replace(/(b)/, '$1$2$01$10$0')", "ab$2bb0$0c"),
+            (
+                "'2026-10'.replace(/(?<y>\\d+)-(?<m>\\d+)/, '$<m>/$<y>|$<z>|$<y')",
+                "10/2026||$<y",
+            ),
+            ("'ab'.replace(/a/, '$<x>')", "$<x>b"),
+            (
+                "'ab'.replace(/(?<x>a)/, function () { return typeof arguments[arguments.length - 1]; })",
+                "objectb",
+            ),
+            ("'abc'.replace(/x*/g, '-')", "-a-b-c-"),
+        ]);
+    }
+
+    #[test]
+    fn test_replace_coerces_groups() {
+        assert_eval_cases(&[
+            (
+                "var re = /./; re.exec = function () { \
+                 return { index: 0, length: 1, 0: 'a', groups: { g: { toString: function () { return 'G'; } } } }; }; \
+                 RegExp.prototype[Symbol.replace].call(re, 'ab', '[$<g>]')",
+                "[G]b",
+            ),
+            (
+                "re.exec = function () { var r = { index: 0, length: 1, 0: 'a' }; \
+                 Object.defineProperty(r, 'groups', { get: function () { throw 'groups'; } }); return r; }; \
+                 try { RegExp.prototype[Symbol.replace].call(re, 'ab', '') } catch (e) { e }",
+                "groups",
+            ),
+            (
+                "re.exec = function () { return { index: 0, length: 1, 0: 'a', groups: null }; }; \
+                 try { RegExp.prototype[Symbol.replace].call(re, 'ab', '$<g>') } catch (e) { e.name }",
+                "TypeError",
+            ),
+            (
+                "re.exec = function () { return { index: 0, length: 1, 0: 'a', groups: { g: 'x' } }; }; \
+                 RegExp.prototype[Symbol.replace].call(re, 'ab', function (m, p, s, groups) { return groups.g; })",
+                "xb",
+            ),
+        ]);
+    }
+}
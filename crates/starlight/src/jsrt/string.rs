@@ -1,4 +1,4 @@
-use regress::Regex;
+use unicode_normalization::UnicodeNormalization;
 
 use crate::{
     constant::S_CONSTURCTOR,
@@ -10,481 +10,768 @@ use crate::{
         context::Context,
         error::{JsRangeError, JsTypeError},
         function::JsNativeFunction,
-        object::JsObject,
+        number::f64_to_string,
+        object::{JsObject, ObjectTag},
         property_descriptor::DataDescriptor,
-        string::{JsString, JsStringObject},
+        slot::Slot,
+        string::{JsString, JsStringBuilder, JsStringIterator, JsStringObject, MAX_STRING_LENGTH},
         structure::Structure,
-        symbol_table::{Internable, Symbol},
+        symbol_table::{Internable, JsSymbol, Symbol},
         value::*,
     },
 };
-use std::{
-    char::{decode_utf16, from_u32},
-    cmp::{max, min},
-    intrinsics::unlikely,
-};
+use std::intrinsics::unlikely;
 
-use super::regexp::RegExp;
+use super::{intl::collator::StringCollator, number::to_integer_or_infinity, regexp::RegExp};
 
-pub fn string_to_string(_ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    Ok(args.this)
+/// RequireObjectCoercible(this value) followed by ToString.
+fn this_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<GcPointer<JsString>, JsValue> {
+    args.this.check_object_coercible(ctx)?;
+    args.this.to_jsstring(ctx)
 }
 
-pub fn string_concat(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let val = args.this;
-    val.check_object_coercible(ctx)?;
-    let mut str = val.to_string(ctx)?;
-    for i in 0..args.size() {
-        let arg = args.at(i);
-        let r = arg.to_string(ctx)?;
-        str.push_str(&r);
+/// thisStringValue(value)
+fn this_string_value(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    name: &str,
+) -> Result<JsValue, JsValue> {
+    if value.is_jsstring() {
+        return Ok(value);
+    }
+    if value.is_jsobject() {
+        let object = value.get_jsobject();
+        if object.is_class(JsStringObject::get_class()) {
+            return Ok(JsValue::new(object.as_string_object().value));
+        }
     }
-    Ok(JsValue::encode_object_value(JsString::new(ctx, str)))
+    Err(JsValue::new(ctx.new_type_error(format!(
+        "String.prototype.{} requires that 'this' be a String",
+        name
+    ))))
 }
 
-pub fn string_value_of(_ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    Ok(args.this)
+/// Clamps integral `position` to `0..=len`.
+fn clamp_position(position: f64, len: u32) -> u32 {
+    position.max(0.0).min(len as f64) as u32
 }
 
-pub fn string_char_at(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let primitive_val = args.this.to_string(ctx)?;
-    let pos = args.at(0).to_int32(ctx)?;
-    if pos < 0 || pos >= primitive_val.len() as i32 {
-        return Ok(JsValue::encode_undefined_value());
+/// Clamps integral `position` to `0..=len`, negative positions count from the end.
+fn relative_position(position: f64, len: u32) -> u32 {
+    if position < 0.0 {
+        (len as f64 + position).max(0.0) as u32
+    } else {
+        position.min(len as f64) as u32
     }
+}
 
-    if let Some(utf16_val) = primitive_val.encode_utf16().nth(pos as usize) {
-        Ok(JsValue::new(JsString::new(
-            ctx,
-            from_u32(utf16_val as u32).unwrap().to_string(),
-        )))
-    } else {
-        Ok(JsValue::new(JsString::new(ctx, "")))
+/// IsRegExp(argument)
+pub(crate) fn is_regexp(ctx: GcPointer<Context>, value: JsValue) -> Result<bool, JsValue> {
+    if !value.is_jsobject() {
+        return Ok(false);
     }
+    let mut object = value.get_jsobject();
+    let matcher = object.get(ctx, "Symbol.match".intern().private())?;
+    if !matcher.is_undefined() {
+        return Ok(matcher.to_boolean());
+    }
+    Ok(object.is_class(RegExp::get_class()))
 }
 
-pub fn string_code_point_at(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let primitive_val = args.this.to_string(ctx)?;
-    let pos = args.at(0).to_int32(ctx)?;
-    if pos < 0 || pos >= primitive_val.len() as i32 {
-        return Ok(JsValue::encode_undefined_value());
+/// GetMethod(value, key)
+fn get_method(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    key: Symbol,
+) -> Result<Option<JsValue>, JsValue> {
+    let func = value.get_slot(ctx, key, &mut Slot::new())?;
+    if func.is_undefined() || func.is_null() {
+        return Ok(None);
     }
-    if let Some((code_point, _, _)) = code_point_at(&primitive_val, pos as _) {
-        Ok(JsValue::new(code_point))
-    } else {
-        Ok(JsValue::encode_undefined_value())
+    if unlikely(!func.is_callable()) {
+        return Err(JsValue::new(ctx.new_type_error(format!(
+            "{} is not a function",
+            ctx.description(key)
+        ))));
     }
+    Ok(Some(func))
 }
 
-pub fn string_char_code_at(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let primitive_val = args.this.to_string(ctx)?;
-    let pos = args.at(0).to_int32(ctx)?;
-    if pos < 0 || pos >= primitive_val.len() as i32 {
-        return Ok(JsValue::encode_nan_value());
+fn call(
+    ctx: GcPointer<Context>,
+    func: JsValue,
+    this: JsValue,
+    args: &mut [JsValue],
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(function = stack, func.get_jsobject());
+    letroot!(args = stack, Arguments::new(this, args));
+    function.as_function_mut().call(ctx, &mut args, func)
+}
+
+fn check_not_regexp(ctx: GcPointer<Context>, value: JsValue, name: &str) -> Result<(), JsValue> {
+    if unlikely(is_regexp(ctx, value)?) {
+        let msg = JsString::new(
+            ctx,
+            format!(
+                "First argument to String.prototype.{} must not be a regular expression",
+                name
+            ),
+        );
+        return Err(JsValue::new(JsTypeError::new(ctx, msg, None)));
     }
+    Ok(())
+}
 
-    if let Some(utf16_val) = primitive_val.encode_utf16().nth(pos as _) {
-        return Ok(JsValue::new(utf16_val));
-    } else {
-        return Ok(JsValue::encode_nan_value());
+pub fn string_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    this_string_value(ctx, args.this, "toString")
+}
+
+pub fn string_concat(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut builder = JsStringBuilder::new();
+    builder.push_string(&this_string(ctx, args)?);
+    for i in 0..args.size() {
+        builder.push_string(&args.at(i).to_jsstring(ctx)?);
     }
+    Ok(JsValue::new(builder.build(ctx)))
 }
 
-pub fn string_replace(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let primitive_val = args.this.to_string(ctx)?;
-    if args.size() == 0 {
-        return Ok(JsValue::new(JsString::new(ctx, primitive_val)));
+pub fn string_value_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    this_string_value(ctx, args.this, "valueOf")
+}
+
+pub fn string_char_at(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let pos = to_integer_or_infinity(ctx, args.at(0))?;
+    if pos < 0.0 || pos >= string.len() as f64 {
+        return Ok(JsValue::new(JsString::new(ctx, "")));
+    }
+    let pos = pos as u32;
+    Ok(JsValue::new(string.substring(ctx, pos, pos + 1)))
+}
+
+pub fn string_code_point_at(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let pos = to_integer_or_infinity(ctx, args.at(0))?;
+    if pos < 0.0 || pos >= string.len() as f64 {
+        return Ok(JsValue::encode_undefined_value());
     }
+    Ok(JsValue::new(string.code_point_at(pos as u32).0))
+}
 
-    let (regex_body, flags) = get_regex_string(ctx, args.at(0))?;
-    let re = Regex::with_flags(&regex_body, flags.as_str())
-        .expect("unable to convectx regex to regex object");
+pub fn string_char_code_at(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let pos = to_integer_or_infinity(ctx, args.at(0))?;
+    if pos < 0.0 || pos >= string.len() as f64 {
+        return Ok(JsValue::encode_nan_value());
+    }
+    Ok(JsValue::new(string.at(pos as u32) as i32))
+}
 
-    let mat = match re.find(&primitive_val) {
-        Some(mat) => mat,
-        None => return Ok(JsValue::new(JsString::new(ctx, primitive_val))),
+/// GetSubstitution(matched, str, position, captures, namedCaptures, replacementTemplate)
+pub(crate) fn get_substitution(
+    ctx: GcPointer<Context>,
+    matched: &JsString,
+    string: &JsString,
+    position: u32,
+    captures: &[JsValue],
+    named_captures: JsValue,
+    replacement: &JsString,
+) -> Result<GcPointer<JsString>, JsValue> {
+    let digit = |unit: u16| {
+        if (b'0' as u16..=b'9' as u16).contains(&unit) {
+            Some((unit - b'0' as u16) as usize)
+        } else {
+            None
+        }
     };
-    let caps = re
-        .find(&primitive_val)
-        .expect("unable to get capture groups from text")
-        .captures;
-
-    let replace_value = if args.size() > 1 {
-        let val = args.at(1).to_string(ctx)?;
-        let mut result = String::new();
-        let mut chars = val.chars().peekable();
-
-        let m = caps.len();
-
-        while let Some(first) = chars.next() {
-            if first == '$' {
-                let second = chars.next();
-                let second_is_digit = second.map_or(false, |ch| ch.is_digit(10));
-                // we use peek so that it is still in the iterator if not used
-                let third = if second_is_digit { chars.peek() } else { None };
-                let third_is_digit = third.map_or(false, |ch| ch.is_digit(10));
-
-                match (second, third) {
-                    (Some('$'), _) => {
-                        // $$
-                        result.push('$');
-                    }
-                    (Some('&'), _) => {
-                        // $&
-                        result.push_str(&primitive_val[mat.range()]);
-                    }
-                    (Some('`'), _) => {
-                        // $`
-                        let start_of_match = mat.start();
-                        result.push_str(&primitive_val[..start_of_match]);
-                    }
-                    (Some('\''), _) => {
-                        // $'
-                        let end_of_match = mat.end();
-                        result.push_str(&primitive_val[end_of_match..]);
-                    }
-                    (Some(second), Some(third)) if second_is_digit && third_is_digit => {
-                        // $nn
-                        let tens = second.to_digit(10).unwrap() as usize;
-                        let units = third.to_digit(10).unwrap() as usize;
-                        let nn = 10 * tens + units;
-                        if nn == 0 || nn > m {
-                            result.push(first);
-                            result.push(second);
-                            if let Some(ch) = chars.next() {
-                                result.push(ch);
-                            }
-                        } else {
-                            let group = match mat.group(nn) {
-                                Some(range) => &primitive_val[range.clone()],
-                                _ => "",
-                            };
-                            result.push_str(group);
-                            chars.next(); // consume third
-                        }
+    let len = replacement.len();
+    let tail_pos = (position + matched.len()).min(string.len());
+    let mut result = JsStringBuilder::new();
+    let mut i = 0;
+    while i < len {
+        let unit = replacement.at(i);
+        if unit != b'$' as u16 || i + 1 == len {
+            result.push(unit);
+            i += 1;
+            continue;
+        }
+        let next = replacement.at(i + 1);
+        match next {
+            _ if next == b'$' as u16 => {
+                result.push(unit);
+                i += 2;
+            }
+            _ if next == b'&' as u16 => {
+                result.push_string(matched);
+                i += 2;
+            }
+            _ if next == b'`' as u16 => {
+                result.push_units(string, 0, position.min(string.len()));
+                i += 2;
+            }
+            _ if next == b'\'' as u16 => {
+                result.push_units(string, tail_pos, string.len());
+                i += 2;
+            }
+            _ if digit(next).is_some() => {
+                let first = digit(next).unwrap();
+                // two digit references win if they refer to an existing capture.
+                let two = if i + 2 < len {
+                    digit(replacement.at(i + 2)).map(|second| first * 10 + second)
+                } else {
+                    None
+                };
+                let (index, consumed) = match two {
+                    Some(index) if (1..=captures.len()).contains(&index) => (index, 3),
+                    _ if (1..=captures.len()).contains(&first) => (first, 2),
+                    _ => {
+                        result.push(unit);
+                        i += 1;
+                        continue;
                     }
-                    (Some(second), _) if second_is_digit => {
-                        // $n
-                        let n = second.to_digit(10).unwrap() as usize;
-                        if n == 0 || n > m {
-                            result.push(first);
-                            result.push(second);
-                        } else {
-                            let group = match mat.group(n) {
-                                Some(range) => &primitive_val[range.clone()],
-                                _ => "",
-                            };
-                            result.push_str(group);
+                };
+                let capture = captures[index - 1];
+                if !capture.is_undefined() {
+                    result.push_string(&capture.to_jsstring(ctx)?);
+                }
+                i += consumed;
+            }
+            _ if next == b'<' as u16 && !named_captures.is_undefined() => {
+                let close = (i + 2..len).find(|&at| replacement.at(at) == b'>' as u16);
+                match close {
+                    Some(close) => {
+                        let group_name = replacement.substring(ctx, i + 2, close);
+                        let mut named_captures = named_captures.to_object(ctx)?;
+                        let capture = named_captures.get(ctx, group_name.intern())?;
+                        if !capture.is_undefined() {
+                            result.push_string(&capture.to_jsstring(ctx)?);
                         }
+                        i = close + 1;
                     }
-                    (Some('<'), _) => {
-                        // $<
-                        // TODO: named capture groups
-                        result.push_str("$<");
-                    }
-                    _ => {
-                        // $?, ? is none of the above
-                        // we can consume second because it isn't $
-                        result.push(first);
-                        if let Some(second) = second {
-                            result.push(second);
-                        }
+                    None => {
+                        result.push(unit);
+                        result.push(next);
+                        i += 2;
                     }
                 }
-            } else {
-                result.push(first);
+            }
+            _ => {
+                result.push(unit);
+                i += 1;
             }
         }
+    }
+    Ok(result.build(ctx))
+}
+
+/// Replacement for occurrence of `search` at `position`, either result of calling `replace_value`
+/// or substitution of `replace_template`.
+fn string_replacement(
+    ctx: GcPointer<Context>,
+    string: GcPointer<JsString>,
+    search: GcPointer<JsString>,
+    position: u32,
+    replace_value: JsValue,
+    replace_template: Option<GcPointer<JsString>>,
+) -> Result<GcPointer<JsString>, JsValue> {
+    match replace_template {
+        Some(template) => get_substitution(
+            ctx,
+            &search,
+            &string,
+            position,
+            &[],
+            JsValue::encode_undefined_value(),
+            &template,
+        ),
+        None => {
+            let mut args = [
+                JsValue::new(search),
+                JsValue::new(position),
+                JsValue::new(string),
+            ];
+            call(
+                ctx,
+                replace_value,
+                JsValue::encode_undefined_value(),
+                &mut args,
+            )?
+            .to_jsstring(ctx)
+        }
+    }
+}
 
-        result
+/// String.prototype.replace with string search value. Search values with `@@replace` are handled
+/// by `String.prototype.replace` in builtins/StringPrototype.js.
+pub fn string_replace(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let search_string = args.at(0).to_jsstring(ctx)?;
+    let replace_value = args.at(1);
+    let replace_template = if replace_value.is_callable() {
+        None
     } else {
-        "undefined".to_string()
+        Some(replace_value.to_jsstring(ctx)?)
+    };
+    let position = match string.index_of(&search_string, 0) {
+        Some(position) => position,
+        None => return Ok(JsValue::new(string)),
     };
-    Ok(JsValue::new(JsString::new(
+    let replacement = string_replacement(
         ctx,
-        primitive_val.replace(&primitive_val[mat.range()], &replace_value),
-    )))
+        string,
+        search_string,
+        position,
+        replace_value,
+        replace_template,
+    )?;
+    let mut builder = JsStringBuilder::new();
+    builder.push_units(&string, 0, position);
+    builder.push_string(&replacement);
+    builder.push_units(&string, position + search_string.len(), string.len());
+    Ok(JsValue::new(builder.build(ctx)))
 }
 
-pub fn string_index_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+pub fn string_replace_all(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     args.this.check_object_coercible(ctx)?;
-    let string = args.this.to_string(ctx)?;
-    let search_string = args.at(0).to_string(ctx)?;
-
-    let length = string.chars().count();
+    let search_value = args.at(0);
+    let replace_value = args.at(1);
+    if !search_value.is_undefined() && !search_value.is_null() {
+        if is_regexp(ctx, search_value)? {
+            let flags = search_value.get_jsobject().get(ctx, "flags".intern())?;
+            flags.check_object_coercible(ctx)?;
+            let flags = flags.to_jsstring(ctx)?;
+            if unlikely(!flags.code_units().any(|unit| unit == b'g' as u16)) {
+                return Err(JsValue::new(
+                    ctx.new_type_error("replaceAll must be called with a global RegExp"),
+                ));
+            }
+        }
+        let key = "Symbol.replace".intern().private();
+        if let Some(replacer) = get_method(ctx, search_value, key)? {
+            return call(ctx, replacer, search_value, &mut [args.this, replace_value]);
+        }
+    }
 
-    let start = if args.size() > 1 {
-        args.at(1).to_int32(ctx)?
+    let string = args.this.to_jsstring(ctx)?;
+    let search_string = search_value.to_jsstring(ctx)?;
+    let replace_template = if replace_value.is_callable() {
+        None
     } else {
-        0
+        Some(replace_value.to_jsstring(ctx)?)
     };
-    let start = (start.max(0).min(length as i32)) as usize;
-
-    if search_string.is_empty() {
-        return Ok(JsValue::new(start as u32));
+    let search_length = search_string.len();
+    let advance_by = search_length.max(1);
+    let mut positions = vec![];
+    let mut position = string.index_of(&search_string, 0);
+    while let Some(found) = position {
+        positions.push(found);
+        position = string.index_of(&search_string, found + advance_by);
     }
 
-    if start < length {
-        if let Some(pos) = string[start..].find(search_string.as_str()) {
-            return Ok(JsValue::new(string[..pos].chars().count() as u32));
-        }
+    let mut end_of_last_match = 0;
+    let mut builder = JsStringBuilder::new();
+    for position in positions {
+        builder.push_units(&string, end_of_last_match, position);
+        let replacement = string_replacement(
+            ctx,
+            string,
+            search_string,
+            position,
+            replace_value,
+            replace_template,
+        )?;
+        builder.push_string(&replacement);
+        end_of_last_match = position + search_length;
     }
-
-    Ok(JsValue::new(-1))
+    if end_of_last_match < string.len() {
+        builder.push_units(&string, end_of_last_match, string.len());
+    }
+    Ok(JsValue::new(builder.build(ctx)))
 }
 
-pub fn string_last_index_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+pub fn string_search(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
     args.this.check_object_coercible(ctx)?;
-    let string: String = args.this.to_string(ctx)?;
-    let search_string: String = args.at(0).to_string(ctx)?;
-
-    let length = string.chars().count();
-    let search_string_length = search_string.chars().count();
-    let max_search_index = (length - search_string_length) as i32;
-    let start = if args.size() > 1 {
-        args.at(1).to_int32(ctx)?
+    let regexp = args.at(0);
+    let key = "Symbol.search".intern().private();
+    if !regexp.is_undefined() && !regexp.is_null() {
+        if let Some(searcher) = get_method(ctx, regexp, key)? {
+            return call(ctx, searcher, regexp, &mut [args.this]);
+        }
+    }
+    let string = args.this.to_jsstring(ctx)?;
+    let pattern = if regexp.is_undefined() {
+        JsString::new(ctx, "")
     } else {
-        max_search_index
+        regexp.to_jsstring(ctx)?
     };
-
-    let start = (start.max(0).min(max_search_index)) as usize;
-    if search_string.is_empty() {
-        return Ok(JsValue::new(start as u32));
+    let mut pattern = [JsValue::new(pattern)];
+    let rx = super::regexp::regexp_constructor(
+        ctx,
+        &Arguments::new(JsValue::encode_undefined_value(), &mut pattern),
+    )?;
+    let searcher = rx.get_slot(ctx, key, &mut Slot::new())?;
+    if unlikely(!searcher.is_callable()) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "RegExp.prototype[Symbol.search] is not a function",
+        )));
     }
+    call(ctx, searcher, rx, &mut [JsValue::new(string)])
+}
 
-    if let Some(pos) = string[..(start + search_string_length)].rfind(search_string.as_str()) {
-        return Ok(JsValue::new(string[..pos].chars().count() as u32));
+pub fn string_at(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let len = string.len() as f64;
+    let relative_index = to_integer_or_infinity(ctx, args.at(0))?;
+    let k = if relative_index >= 0.0 {
+        relative_index
+    } else {
+        len + relative_index
+    };
+    if k < 0.0 || k >= len {
+        return Ok(JsValue::encode_undefined_value());
     }
-
-    Ok(JsValue::new(-1))
+    let k = k as u32;
+    Ok(JsValue::new(string.substring(ctx, k, k + 1)))
 }
 
-pub fn string_repeat(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    args.this.check_object_coercible(ctx)?;
-    let object = args.this.to_string(ctx)?;
-    if args.size() > 0 {
-        let n = args.at(0).to_int32(ctx)?;
-        if unlikely(n < 0) {
-            let msg = JsString::new(ctx, "repeat count cannot be a negative number");
+pub fn string_normalize(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let form = if args.at(0).is_undefined() {
+        "NFC".to_string()
+    } else {
+        args.at(0).to_string(ctx)?
+    };
+    let normalized = match form.as_str() {
+        "NFC" => map_well_formed(ctx, &string, |str| str.nfc().collect()),
+        "NFD" => map_well_formed(ctx, &string, |str| str.nfd().collect()),
+        "NFKC" => map_well_formed(ctx, &string, |str| str.nfkc().collect()),
+        "NFKD" => map_well_formed(ctx, &string, |str| str.nfkd().collect()),
+        _ => {
+            let msg = JsString::new(
+                ctx,
+                "The normalization form should be one of NFC, NFD, NFKC, NFKD.",
+            );
             return Err(JsValue::new(JsRangeError::new(ctx, msg, None)));
         }
+    };
+    Ok(JsValue::new(normalized))
+}
 
-        if unlikely(n as usize * object.len() >= u32::MAX as usize - 1) {
-            let msg = JsString::new(ctx, "repeat count must not overflow max string length");
-            return Err(JsValue::new(JsRangeError::new(ctx, msg, None)));
+pub fn string_locale_compare(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let that = args.at(0).to_jsstring(ctx)?;
+    let collator = StringCollator::new(ctx, args.at(1), args.at(2))?;
+    let ordering = collator.compare(string.as_str(), that.as_str());
+    Ok(JsValue::new(ordering as i32))
+}
+
+pub fn string_is_well_formed(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    Ok(JsValue::new(string.is_well_formed()))
+}
+
+pub fn string_to_well_formed(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    if string.is_well_formed() {
+        return Ok(JsValue::new(string));
+    }
+    let mut builder = JsStringBuilder::new();
+    let mut index = 0;
+    while index < string.len() {
+        let (code_point, size) = string.code_point_at(index);
+        if (0xd800..=0xdfff).contains(&code_point) {
+            builder.push(0xfffd);
+        } else {
+            builder.push_units(&string, index, index + size);
         }
-        Ok(JsValue::new(JsString::new(ctx, object.repeat(n as _))))
-    } else {
-        Ok(JsValue::new(JsString::new(ctx, "")))
+        index += size;
     }
-}
-pub fn string_to_lowercase(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let this = args.this.to_string(ctx)?;
-    Ok(JsValue::new(JsString::new(ctx, this.to_lowercase())))
+    Ok(JsValue::new(builder.build(ctx)))
 }
 
-pub fn string_to_uppercase(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let this = args.this.to_string(ctx)?;
-    Ok(JsValue::new(JsString::new(ctx, this.to_uppercase())))
+pub fn string_iterator(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    Ok(JsValue::new(JsStringIterator::new(ctx, string)))
 }
-pub fn string_starts_with(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let primitive_val = args.this.to_string(ctx)?;
-    let arg = args.at(0);
-    if unlikely(arg.is_jsobject() && arg.get_jsobject().is_class(RegExp::get_class())) {
-        let msg = JsString::new(
-            ctx,
-            "First argument to String.prototype.endsWith must not be a regular expression",
-        );
-        return Err(JsValue::new(JsTypeError::new(ctx, msg, None)));
+
+pub fn string_iterator_next(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let this = args.this;
+    if unlikely(!this.is_jsobject() || !this.get_jsobject().is_class(JsStringIterator::get_class()))
+    {
+        return Err(JsValue::new(ctx.new_type_error(
+            "String Iterator.prototype.next requires that 'this' be a String Iterator",
+        )));
     }
-    let search_string = arg.to_string(ctx)?;
-    let length = primitive_val.chars().count() as i32;
-    let search_length = search_string.chars().count() as i32;
-    let position = if args.size() < 2 {
-        0
-    } else {
-        args.at(1).to_int32(ctx)?
+    let mut iterator = this.get_jsobject();
+    let next = iterator.data::<JsStringIterator>().next(ctx);
+    let mut result = JsObject::new_empty(ctx);
+    let (value, done) = match next {
+        Some(value) => (JsValue::new(value), false),
+        None => (JsValue::encode_undefined_value(), true),
     };
+    result.put(ctx, "value".intern(), value, false)?;
+    result.put(ctx, "done".intern(), JsValue::new(done), false)?;
+    Ok(JsValue::new(result))
+}
 
-    let start = min(max(position, 0), length);
-    let end = start.wrapping_add(search_length);
-    if end > length {
-        Ok(JsValue::new(false))
-    } else {
-        let this_string = primitive_val
-            .chars()
-            .skip(start as usize)
-            .collect::<String>();
-        Ok(JsValue::new(
-            this_string.starts_with(search_string.as_str()),
-        ))
+pub fn string_iterator_iterator(
+    _ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Ok(args.this)
+}
+
+/// ToUint16(value)
+fn to_uint16(ctx: GcPointer<Context>, value: JsValue) -> Result<u16, JsValue> {
+    let number = value.to_number(ctx)?;
+    if !number.is_finite() {
+        return Ok(0);
     }
+    Ok(number.trunc().rem_euclid(65536.0) as u16)
 }
 
-pub fn string_ends_with(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let primitive_val = args.this.to_string(ctx)?;
-    let arg = args.at(0);
-    if unlikely(arg.is_jsobject() && arg.get_jsobject().is_class(RegExp::get_class())) {
-        let msg = JsString::new(
-            ctx,
-            "First argument to String.prototype.startsWith must not be a regular expression",
-        );
-        return Err(JsValue::new(JsTypeError::new(ctx, msg, None)));
+pub fn string_from_char_code(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut builder = JsStringBuilder::new();
+    for i in 0..args.size() {
+        builder.push(to_uint16(ctx, args.at(i))?);
     }
-    let search_string = arg.to_string(ctx)?;
-    let length = primitive_val.chars().count() as i32;
-    let search_length = search_string.chars().count() as i32;
-    let position = if args.size() < 2 {
-        0
-    } else {
-        args.at(1).to_int32(ctx)?
-    };
+    Ok(JsValue::new(builder.build(ctx)))
+}
 
-    let start = min(max(position, 0), length);
-    let end = start.wrapping_add(search_length);
-    if end > length {
-        Ok(JsValue::new(false))
-    } else {
-        let this_string = primitive_val
-            .chars()
-            .skip(start as usize)
-            .collect::<String>();
-        Ok(JsValue::new(
-            this_string.starts_with(search_string.as_str()),
-        ))
+pub fn string_from_code_point(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut builder = JsStringBuilder::new();
+    for i in 0..args.size() {
+        let next = args.at(i).to_number(ctx)?;
+        // NaN fails the first check.
+        if unlikely(next.trunc() != next || !(0.0..=1114111.0).contains(&next)) {
+            let msg = JsString::new(ctx, format!("Invalid code point {}", f64_to_string(next)));
+            return Err(JsValue::new(JsRangeError::new(ctx, msg, None)));
+        }
+        builder.push_code_point(next as u32);
     }
+    Ok(JsValue::new(builder.build(ctx)))
 }
 
-pub fn string_includes(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let primitive_val = args.this.to_string(ctx)?;
-    let arg = args.at(0);
-    if unlikely(arg.is_jsobject() && arg.get_jsobject().is_class(RegExp::get_class())) {
-        let msg = JsString::new(
-            ctx,
-            "First argument to String.prototype.startsWith must not be a regular expression",
-        );
-        return Err(JsValue::new(JsTypeError::new(ctx, msg, None)));
+pub fn string_raw(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(cooked = stack, args.at(0).to_object(ctx)?);
+    letroot!(
+        raw = stack,
+        cooked.get(ctx, "raw".intern())?.to_object(ctx)?
+    );
+    let literal_count = super::get_length(ctx, &mut raw)?;
+    let mut builder = JsStringBuilder::new();
+    for index in 0..literal_count {
+        let segment = raw.get(ctx, Symbol::Index(index))?.to_jsstring(ctx)?;
+        builder.push_string(&segment);
+        if index + 1 == literal_count {
+            break;
+        }
+        if (index as usize + 1) < args.size() {
+            let substitution = args.at(index as usize + 1).to_jsstring(ctx)?;
+            builder.push_string(&substitution);
+        }
     }
-    let search_string = arg.to_string(ctx)?;
-    let length = primitive_val.chars().count() as i32;
+    Ok(JsValue::new(builder.build(ctx)))
+}
 
-    let position = if args.size() < 2 {
-        0
+pub fn string_index_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let search_string = args.at(0).to_jsstring(ctx)?;
+    let pos = to_integer_or_infinity(ctx, args.at(1))?;
+    let start = clamp_position(pos, string.len());
+    Ok(match string.index_of(&search_string, start) {
+        Some(index) => JsValue::new(index),
+        None => JsValue::new(-1),
+    })
+}
+
+pub fn string_last_index_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let search_string = args.at(0).to_jsstring(ctx)?;
+    let pos = args.at(1).to_number(ctx)?;
+    let pos = if pos.is_nan() {
+        f64::INFINITY
     } else {
-        args.at(1).to_int32(ctx)?
+        pos.trunc()
     };
+    let start = clamp_position(pos, string.len());
+    Ok(match string.last_index_of(&search_string, start) {
+        Some(index) => JsValue::new(index),
+        None => JsValue::new(-1),
+    })
+}
 
-    let start = min(max(position, 0), length);
-    let this_string = primitive_val.chars().skip(start as _).collect::<String>();
-    Ok(JsValue::new(this_string.contains(search_string.as_str())))
+pub fn string_repeat(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let n = to_integer_or_infinity(ctx, args.at(0))?;
+    if unlikely(n < 0.0 || n == f64::INFINITY) {
+        let msg = JsString::new(ctx, "repeat count must be a non-negative finite number");
+        return Err(JsValue::new(JsRangeError::new(ctx, msg, None)));
+    }
+    if string.is_empty() || n == 0.0 {
+        return Ok(JsValue::new(JsString::new(ctx, "")));
+    }
+    if unlikely(n * string.len() as f64 > MAX_STRING_LENGTH as f64) {
+        let msg = JsString::new(ctx, "repeat count must not overflow max string length");
+        return Err(JsValue::new(JsRangeError::new(ctx, msg, None)));
+    }
+    let mut builder = JsStringBuilder::new();
+    for _ in 0..n as u32 {
+        builder.push_string(&string);
+    }
+    Ok(JsValue::new(builder.build(ctx)))
 }
-pub fn string_slice(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let primitive_val = args.this.to_string(ctx)?;
-    let length = primitive_val.chars().count() as i32;
-    let start = args.at(0).to_int32(ctx)?;
-    let end = if args.size() > 1 {
-        args.at(1).to_int32(ctx)?
-    } else {
-        length as i32
-    };
-    let from = if start < 0 {
-        max(length.wrapping_add(start as i32), 0)
-    } else {
-        min(start, length as i32)
-    };
-    let to = if end < 0 {
-        max(length.wrapping_add(end as _), 0)
-    } else {
-        min(end, length as i32)
-    };
 
-    let span = max(to.wrapping_sub(from), 0);
+/// Applies `map` to maximal runs of `string` without unpaired surrogates, unpaired surrogates
+/// are kept as is.
+fn map_well_formed(
+    ctx: GcPointer<Context>,
+    string: &JsString,
+    map: impl Fn(&str) -> String,
+) -> GcPointer<JsString> {
+    let mut builder = JsStringBuilder::new();
+    let mut run = String::new();
+    let mut index = 0;
+    while index < string.len() {
+        let (code_point, size) = string.code_point_at(index);
+        match std::char::from_u32(code_point) {
+            Some(c) => run.push(c),
+            None => {
+                builder.push_str(&map(&run));
+                run.clear();
+                builder.push(code_point as u16);
+            }
+        }
+        index += size;
+    }
+    builder.push_str(&map(&run));
+    builder.build(ctx)
+}
 
-    let new_str = primitive_val
-        .chars()
-        .skip(from as usize)
-        .take(span as usize)
-        .collect::<String>();
-    Ok(JsValue::new(JsString::new(ctx, new_str)))
+/// Full Unicode case mapping including special casing, e.g. final sigma and `ß` → `SS`.
+pub fn string_to_lowercase(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    Ok(JsValue::new(map_well_formed(
+        ctx,
+        &string,
+        str::to_lowercase,
+    )))
 }
-pub fn string_substring(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let primitive_val = args.this.to_string(ctx)?;
-    let start = if args.size() == 0 {
-        0
+
+pub fn string_to_uppercase(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    Ok(JsValue::new(map_well_formed(
+        ctx,
+        &string,
+        str::to_uppercase,
+    )))
+}
+
+pub fn string_starts_with(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    check_not_regexp(ctx, args.at(0), "startsWith")?;
+    let search_string = args.at(0).to_jsstring(ctx)?;
+    let pos = to_integer_or_infinity(ctx, args.at(1))?;
+    let start = clamp_position(pos, string.len());
+    Ok(JsValue::new(string.matches_at(&search_string, start)))
+}
+
+pub fn string_ends_with(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    check_not_regexp(ctx, args.at(0), "endsWith")?;
+    let search_string = args.at(0).to_jsstring(ctx)?;
+    let end = if args.at(1).is_undefined() {
+        string.len()
     } else {
-        args.at(0).to_int32(ctx)?
+        clamp_position(to_integer_or_infinity(ctx, args.at(1))?, string.len())
     };
+    if search_string.len() > end {
+        return Ok(JsValue::new(false));
+    }
+    Ok(JsValue::new(
+        string.matches_at(&search_string, end - search_string.len()),
+    ))
+}
+
+pub fn string_includes(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    check_not_regexp(ctx, args.at(0), "includes")?;
+    let search_string = args.at(0).to_jsstring(ctx)?;
+    let pos = to_integer_or_infinity(ctx, args.at(1))?;
+    let start = clamp_position(pos, string.len());
+    Ok(JsValue::new(
+        string.index_of(&search_string, start).is_some(),
+    ))
+}
 
-    let length = primitive_val.encode_utf16().count() as i32;
-    let end = if args.size() < 2 {
+pub fn string_slice(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let length = string.len();
+    let from = relative_position(to_integer_or_infinity(ctx, args.at(0))?, length);
+    let to = if args.at(1).is_undefined() {
         length
     } else {
-        args.at(1).to_int32(ctx)?
+        relative_position(to_integer_or_infinity(ctx, args.at(1))?, length)
     };
-
-    let final_start = min(max(start, 0), length);
-    let final_end = min(max(end, 0), length);
-    let from = min(final_start, final_end) as usize;
-    let to = max(final_start, final_end) as usize;
-
-    let extracted_string: Result<String, _> = decode_utf16(
-        primitive_val
-            .encode_utf16()
-            .skip(from)
-            .take(to.wrapping_sub(from)),
-    )
-    .collect();
-
-    match extracted_string {
-        Ok(val) => return Ok(JsValue::new(JsString::new(ctx, val))),
-        Err(e) => {
-            let decode_err = JsString::new(ctx, format!("{}", e));
-            Err(JsValue::new(decode_err))
-        }
+    if from >= to {
+        return Ok(JsValue::new(JsString::new(ctx, "")));
     }
+    Ok(JsValue::new(string.substring(ctx, from, to)))
 }
 
-pub fn string_substr(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let primitive_val = args.this.to_string(ctx)?;
-    let mut start = if args.size() == 0 {
-        0
+pub fn string_substring(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let length = string.len();
+    let start = clamp_position(to_integer_or_infinity(ctx, args.at(0))?, length);
+    let end = if args.at(1).is_undefined() {
+        length
     } else {
-        args.at(0).to_int32(ctx)?
+        clamp_position(to_integer_or_infinity(ctx, args.at(1))?, length)
     };
+    Ok(JsValue::new(string.substring(
+        ctx,
+        start.min(end),
+        start.max(end),
+    )))
+}
 
-    let length = primitive_val.chars().count() as i32;
-
-    let end = if args.size() < 2 {
-        i32::MAX
+pub fn string_substr(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let string = this_string(ctx, args)?;
+    let size = string.len();
+    let start = relative_position(to_integer_or_infinity(ctx, args.at(0))?, size);
+    let length = if args.at(1).is_undefined() {
+        size as f64
     } else {
-        args.at(1).to_int32(ctx)?
+        to_integer_or_infinity(ctx, args.at(1))?
     };
-
-    if start < 0 {
-        start = max(length.wrapping_add(start), 0);
-    }
-
-    let result_length = min(max(end, 0), length.wrapping_sub(start));
-
-    if result_length <= 0 {
+    let end = (start as f64 + length.max(0.0)).min(size as f64) as u32;
+    if start >= end {
         return Ok(JsValue::new(JsString::new(ctx, "")));
     }
-
-    let extracted_string: String = primitive_val
-        .chars()
-        .skip(start as _)
-        .take(result_length as _)
-        .collect();
-    Ok(JsValue::new(JsString::new(ctx, extracted_string)))
+    Ok(JsValue::new(string.substring(ctx, start, end)))
 }
 
 pub fn string_split(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let val = args.this;
-    val.check_object_coercible(ctx)?;
-    let str = val.to_string(ctx)?;
+    let string = this_string(ctx, args)?;
 
-    let argc = args.size();
-    let lim = if argc < 2 || args.at(1).is_undefined() {
+    let lim = if args.at(1).is_undefined() {
         4294967295u32
     } else {
         args.at(1).to_uint32(ctx)?
@@ -492,24 +779,34 @@ pub fn string_split(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue
     let separator = if args.at(0).is_undefined() || args.at(0).is_null() {
         None
     } else {
-        Some(args.at(0).to_string(ctx)?)
-    };
-    let values = match separator {
-        None if lim == 0 => vec![],
-        None => vec![JsValue::encode_object_value(JsString::new(ctx, str))],
-        Some(separator) if separator.is_empty() => str
-            .encode_utf16()
-            .map(|cp| {
-                JsValue::encode_object_value(JsString::new(ctx, String::from_utf16_lossy(&[cp])))
-            })
-            .take(lim as _)
-            .collect(),
-        Some(separator) => str
-            .split(separator.as_str())
-            .map(|x| JsValue::encode_object_value(JsString::new(ctx, x)))
-            .take(lim as _)
-            .collect(),
+        Some(args.at(0).to_jsstring(ctx)?)
     };
+    let stack = ctx.shadowstack();
+    letroot!(values = stack, Vec::<JsValue>::new());
+    match separator {
+        None if lim == 0 => (),
+        None => values.push(JsValue::new(string)),
+        Some(separator) if separator.is_empty() => {
+            for index in 0..string.len().min(lim) {
+                values.push(JsValue::new(string.substring(ctx, index, index + 1)));
+            }
+        }
+        Some(separator) => {
+            let mut position = 0;
+            while values.len() < lim as usize {
+                match string.index_of(&separator, position) {
+                    Some(index) => {
+                        values.push(JsValue::new(string.substring(ctx, position, index)));
+                        position = index + separator.len();
+                    }
+                    None => {
+                        values.push(JsValue::new(string.substring(ctx, position, string.len())));
+                        break;
+                    }
+                }
+            }
+        }
+    }
 
     let mut arr = JsArray::new(ctx, values.len() as _);
     for (ix, item) in values.iter().enumerate() {
@@ -519,29 +816,20 @@ pub fn string_split(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue
 }
 
 pub fn string_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    if args.ctor_call {
-        let str;
-        if args.size() != 0 {
-            str = args.at(0).to_string(ctx)?;
-        } else {
-            str = "".to_owned();
-        }
-        let _ = str;
-        let msg = JsString::new(
-            ctx,
-            "String.prototype.constructor as constructor is not yet implemented",
-        );
-        Ok(JsValue::encode_object_value(JsTypeError::new(
-            ctx, msg, None,
-        )))
-    } else if args.size() != 0 {
-        let str = args.at(0).to_string(ctx)?;
-        let jsttr = JsString::new(ctx, str);
-        return Ok(JsValue::encode_object_value(jsttr));
+    let string = if args.size() == 0 {
+        JsString::new(ctx, "")
+    } else if !args.ctor_call && args.at(0).is_symbol() {
+        // String(symbol) is the only way to stringify symbol without throwing.
+        let symbol = args.at(0).get_object().downcast::<JsSymbol>().unwrap();
+        let description = ctx.description(symbol.symbol());
+        JsString::new(ctx, format!("Symbol({})", description))
     } else {
-        let jsttr = JsString::new(ctx, "");
-        return Ok(JsValue::encode_object_value(jsttr));
+        args.at(0).to_jsstring(ctx)?
+    };
+    if args.ctor_call {
+        return Ok(JsValue::new(JsStringObject::new(ctx, string)));
     }
+    Ok(JsValue::new(string))
 }
 
 impl GcPointer<Context> {
@@ -554,9 +842,8 @@ impl GcPointer<Context> {
             .get_own_property(self, name)
             .unwrap()
             .value();
-        self.global_object()
-            .put(self, "String".intern(), constructor, false)
-            .unwrap_or_else(|_| panic!());
+        let mut global_object = self.global_object();
+        def_native_property!(self, global_object, String, constructor, W | C)?;
         Ok(())
     }
 
@@ -575,34 +862,92 @@ impl GcPointer<Context> {
         let mut ctor = JsNativeFunction::new(self, "String".intern(), string_constructor, 1);
 
         def_native_property!(self, ctor, prototype, proto, NONE)?;
+        def_native_method!(self, ctor, fromCharCode, string_from_char_code, 1, W | C)?;
+        def_native_method!(self, ctor, fromCodePoint, string_from_code_point, 1, W | C)?;
+        def_native_method!(self, ctor, raw, string_raw, 1, W | C)?;
+        def_native_method!(self, ctor, ___replace, string_replace, 2)?;
+
         def_native_property!(self, proto, constructor, ctor, W | C)?;
-        def_native_method!(self, proto, toString, string_to_string, 0)?;
-        def_native_method!(self, proto, valueOf, string_value_of, 0)?;
+        def_native_method!(self, proto, toString, string_to_string, 0, W | C)?;
+        def_native_method!(self, proto, valueOf, string_value_of, 0, W | C)?;
         def_native_method!(self, proto, ___splitFast, string_split, 0)?;
-        def_native_method!(self, proto, concat, string_concat, 0)?;
-        def_native_method!(self, proto, charAt, string_char_at, 1)?;
-        def_native_method!(self, proto, charCodeAt, string_char_code_at, 1)?;
-        def_native_method!(self, proto, toUpperCase, string_to_uppercase, 0)?;
-        def_native_method!(self, proto, toLowerCase, string_to_lowercase, 0)?;
-        def_native_method!(self, proto, indexOf, string_index_of, 2)?;
-        def_native_method!(self, proto, lastIndexOf, string_last_index_of, 2)?;
-        def_native_method!(self, proto, substr, string_substr, 2)?;
-        def_native_method!(self, proto, substring, string_substring, 2)?;
-        def_native_method!(self, proto, codePointAt, string_code_point_at, 1)?;
-        def_native_method!(self, proto, repeat, string_repeat, 1)?;
-        def_native_method!(self, proto, startsWith, string_starts_with, 1)?;
-        def_native_method!(self, proto, endsWith, string_ends_with, 1)?;
-        def_native_method!(self, proto, includes, string_includes, 1)?;
-        def_native_method!(self, proto, slice, string_slice, 1)?;
-        def_native_method!(self, ctor, ___replace, string_replace, 2)?;
-        def_native_method!(self, proto, trim, string_trim, 0)?;
-        def_native_method!(self, proto, trimStactx, string_trim_start, 0)?;
-        def_native_method!(self, proto, trimEnd, string_trim_end, 0)?;
-        def_native_method!(self, proto, trimLeft, string_trim_start, 0)?;
-        def_native_method!(self, proto, trimRight, string_trim_end, 0)?;
-        def_native_method!(self, proto, padStactx, string_pad_start, 2)?;
-        def_native_method!(self, proto, padEnd, string_pad_end, 2)?;
-        def_native_method!(self, proto, repeat, string_repeat, 1)?;
+        def_native_method!(self, proto, at, string_at, 1, W | C)?;
+        def_native_method!(self, proto, concat, string_concat, 1, W | C)?;
+        def_native_method!(self, proto, charAt, string_char_at, 1, W | C)?;
+        def_native_method!(self, proto, charCodeAt, string_char_code_at, 1, W | C)?;
+        def_native_method!(self, proto, codePointAt, string_code_point_at, 1, W | C)?;
+        def_native_method!(self, proto, toUpperCase, string_to_uppercase, 0, W | C)?;
+        def_native_method!(self, proto, toLowerCase, string_to_lowercase, 0, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toLocaleUpperCase,
+            string_to_uppercase,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            toLocaleLowerCase,
+            string_to_lowercase,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, proto, indexOf, string_index_of, 1, W | C)?;
+        def_native_method!(self, proto, lastIndexOf, string_last_index_of, 1, W | C)?;
+        def_native_method!(self, proto, includes, string_includes, 1, W | C)?;
+        def_native_method!(self, proto, startsWith, string_starts_with, 1, W | C)?;
+        def_native_method!(self, proto, endsWith, string_ends_with, 1, W | C)?;
+        def_native_method!(self, proto, slice, string_slice, 2, W | C)?;
+        def_native_method!(self, proto, substring, string_substring, 2, W | C)?;
+        def_native_method!(self, proto, substr, string_substr, 2, W | C)?;
+        def_native_method!(self, proto, repeat, string_repeat, 1, W | C)?;
+        def_native_method!(self, proto, padStart, string_pad_start, 1, W | C)?;
+        def_native_method!(self, proto, padEnd, string_pad_end, 1, W | C)?;
+        def_native_method!(self, proto, trim, string_trim, 0, W | C)?;
+        def_native_method!(self, proto, trimStart, string_trim_start, 0, W | C)?;
+        def_native_method!(self, proto, trimEnd, string_trim_end, 0, W | C)?;
+        // Annex B aliases are the same function objects.
+        let trim_start = proto.get(self, "trimStart".intern())?;
+        let trim_end = proto.get(self, "trimEnd".intern())?;
+        def_native_property!(self, proto, trimLeft, trim_start, W | C)?;
+        def_native_property!(self, proto, trimRight, trim_end, W | C)?;
+        def_native_method!(self, proto, replaceAll, string_replace_all, 2, W | C)?;
+        def_native_method!(self, proto, search, string_search, 1, W | C)?;
+        def_native_method!(self, proto, normalize, string_normalize, 0, W | C)?;
+        def_native_method!(self, proto, localeCompare, string_locale_compare, 1, W | C)?;
+        def_native_method!(self, proto, isWellFormed, string_is_well_formed, 0, W | C)?;
+        def_native_method!(self, proto, toWellFormed, string_to_well_formed, 0, W | C)?;
+        let iterator = "Symbol.iterator".intern().private();
+        let func = JsNativeFunction::new(self, iterator, string_iterator, 0);
+        proto.define_own_property(
+            self,
+            iterator,
+            &*DataDescriptor::new(JsValue::new(func), W | C),
+            false,
+        )?;
+
+        let structure = Structure::new_unique_indexed(self, Some(obj_proto), false);
+        let mut iter_proto =
+            JsObject::new(self, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+        def_native_method!(self, iter_proto, next, string_iterator_next, 0, W | C)?;
+        let func = JsNativeFunction::new(self, iterator, string_iterator_iterator, 0);
+        iter_proto.define_own_property(
+            self,
+            iterator,
+            &*DataDescriptor::new(JsValue::new(func), W | C),
+            false,
+        )?;
+        let tag = JsString::new(self, "String Iterator");
+        iter_proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+        self.global_data.string_iterator_structure =
+            Some(Structure::new_indexed(self, Some(iter_proto), false));
 
         self.global_data.string_prototype = Some(proto);
 
@@ -610,30 +955,8 @@ impl GcPointer<Context> {
     }
 }
 
-pub(crate) fn code_point_at(string: &str, position: i32) -> Option<(u32, u8, bool)> {
-    let size = string.encode_utf16().count() as i32;
-    if position < 0 || position >= size {
-        return None;
-    }
-    let mut encoded = string.encode_utf16();
-    let first = encoded.nth(position as usize)?;
-    if !is_leading_surrogate(first) && !is_trailing_surrogate(first) {
-        return Some((first as u32, 1, false));
-    }
-    if is_trailing_surrogate(first) || position + 1 == size {
-        return Some((first as u32, 1, true));
-    }
-    let second = encoded.next()?;
-    if !is_trailing_surrogate(second) {
-        return Some((first as u32, 1, true));
-    }
-    let cp = (first as u32 - 0xD800) * 0x400 + (second as u32 - 0xDC00) + 0x10000;
-    Some((cp, 2, false))
-}
-
 /// Helper function to check if a `char` is trimmable.
 #[inline]
-#[allow(dead_code)]
 pub(crate) fn is_trimmable_whitespace(c: char) -> bool {
     // The rust implementation of `trim` does not regard the same characters whitespace as ecma standard does
     //
@@ -653,47 +976,46 @@ pub(crate) fn is_trimmable_whitespace(c: char) -> bool {
     )
 }
 
-fn is_leading_surrogate(value: u16) -> bool {
-    (0xD800..=0xDBFF).contains(&value)
-}
-
-fn is_trailing_surrogate(value: u16) -> bool {
-    (0xDC00..=0xDFFF).contains(&value)
-}
-
-fn get_regex_string(_ctx: GcPointer<Context>, val: JsValue) -> Result<(String, String), JsValue> {
-    if val.is_jsstring() {
-        return Ok((val.get_jsstring().string.clone(), String::new()));
+/// Removes leading and/or trailing white space and line terminators.
+fn trim_string(
+    ctx: GcPointer<Context>,
+    string: &JsString,
+    start: bool,
+    end: bool,
+) -> GcPointer<JsString> {
+    let is_whitespace =
+        |unit: u16| std::char::from_u32(unit as u32).map_or(false, is_trimmable_whitespace);
+    let (mut from, mut to) = (0, string.len());
+    if start {
+        while from < to && is_whitespace(string.at(from)) {
+            from += 1;
+        }
     }
-    if val.is_jsobject() {
-        let obj = val.get_jsobject();
-        if obj.is_class(RegExp::get_class()) {
-            return Ok((
-                obj.data::<RegExp>().original_source.to_string(),
-                obj.data::<RegExp>().original_flags.to_string(),
-            ));
+    if end {
+        while to > from && is_whitespace(string.at(to - 1)) {
+            to -= 1;
         }
     }
-    return Ok(("undefined".to_string(), "".to_string()));
+    string.substring(ctx, from, to)
 }
 
 pub fn string_trim(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let prim = args.this.to_string(ctx)?;
-    Ok(JsValue::new(JsString::new(ctx, prim.trim())))
+    let string = this_string(ctx, args)?;
+    Ok(JsValue::new(trim_string(ctx, &string, true, true)))
 }
 
 pub fn string_trim_start(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let prim = args.this.to_string(ctx)?;
-    Ok(JsValue::new(JsString::new(ctx, prim.trim_start())))
+    let string = this_string(ctx, args)?;
+    Ok(JsValue::new(trim_string(ctx, &string, true, false)))
 }
 
 pub fn string_trim_end(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let prim = args.this.to_string(ctx)?;
-    Ok(JsValue::new(JsString::new(ctx, prim.trim_end())))
+    let string = this_string(ctx, args)?;
+    Ok(JsValue::new(trim_string(ctx, &string, false, true)))
 }
 
 pub enum Alignment {
-    Stactx,
+    Start,
     End,
 }
 
@@ -702,44 +1024,40 @@ pub fn string_pad(
     args: &Arguments,
     alignment: Alignment,
 ) -> Result<JsValue, JsValue> {
-    let mut string = args.this.to_string(ctx)?;
-    let target_length = args.at(0).to_int32(ctx)?;
-    let pad_str_arg = args.at(1);
-    let mut pad_str = String::from(" ");
-    if !pad_str_arg.is_undefined() {
-        pad_str = pad_str_arg.to_string(ctx)?;
-    }
-    let length = string.chars().count();
-    if target_length <= length as i32 || pad_str.is_empty() {
-        Ok(JsValue::new(JsString::new(ctx, string)))
+    let string = this_string(ctx, args)?;
+    let max_length = to_integer_or_infinity(ctx, args.at(0))?;
+    let length = string.len();
+    if max_length <= length as f64 {
+        return Ok(JsValue::new(string));
+    }
+    let fill_string = if args.at(1).is_undefined() {
+        JsString::new(ctx, " ")
     } else {
-        let pad_num = target_length as usize - length;
-
-        let mut pad_str_iter = pad_str.chars();
-        let mut to_pad_str = String::from("");
-        let mut index = 0;
-        while index < pad_num {
-            match pad_str_iter.next() {
-                Some(ch) => {
-                    to_pad_str.push(ch);
-                    index += 1;
-                }
-                None => {
-                    pad_str_iter = pad_str.chars();
-                }
-            }
-        }
-        match alignment {
-            Alignment::Stactx => {
-                to_pad_str.push_str(&string);
-                Ok(JsValue::new(JsString::new(ctx, to_pad_str)))
-            }
-            Alignment::End => {
-                string.push_str(&to_pad_str);
-                Ok(JsValue::new(JsString::new(ctx, string)))
-            }
-        }
+        args.at(1).to_jsstring(ctx)?
+    };
+    if fill_string.is_empty() {
+        return Ok(JsValue::new(string));
     }
+    if unlikely(max_length > MAX_STRING_LENGTH as f64) {
+        let msg = JsString::new(ctx, "Invalid string length");
+        return Err(JsValue::new(JsRangeError::new(ctx, msg, None)));
+    }
+    let fill_len = max_length as u32 - length;
+
+    let mut builder = JsStringBuilder::new();
+    if let Alignment::End = alignment {
+        builder.push_string(&string);
+    }
+    let mut remaining = fill_len;
+    while remaining > 0 {
+        let count = remaining.min(fill_string.len());
+        builder.push_units(&fill_string, 0, count);
+        remaining -= count;
+    }
+    if let Alignment::Start = alignment {
+        builder.push_string(&string);
+    }
+    Ok(JsValue::new(builder.build(ctx)))
 }
 
 pub fn string_pad_end(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
@@ -747,5 +1065,51 @@ pub fn string_pad_end(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsVal
 }
 
 pub fn string_pad_start(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    string_pad(ctx, args, Alignment::Stactx)
+    string_pad(ctx, args, Alignment::Start)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_string_methods() {
+        assert_eval_cases(&[
+            ("'abc'.at(-1) + ',' + 'abc'.at(3)", "c,undefined"),
+            (
+                "var smile = String.fromCodePoint(0x1f600); smile.length + ',' + smile.codePointAt(0) + ',' + smile.codePointAt(1)",
+                "2,128512,56832",
+            ),
+            ("[...('a' + smile + 'b')].length", "3"),
+            (
+                "'abc'.substr(-2, 1) + ',' + 'abc'.substring(2, 0) + ',' + 'abc'.slice(-2)",
+                "b,ab,bc",
+            ),
+            ("'ab'.repeat(0) === ''", "true"),
+            ("try { 'a'.repeat(-1) } catch (e) { e.name }", "RangeError"),
+            ("try { 'a'.repeat(Infinity) } catch (e) { e.name }", "RangeError"),
+            (
+                "'5'.padStart(3, '0') + ',' + 'abc'.padEnd(6, '12') + ',' + 'abc'.padStart(5, '')",
+                "005,abc121,abc",
+            ),
+            ("'\\u00df'.toUpperCase()", "SS"),
+            ("'\\u0130'.toLowerCase().length", "2"),
+            ("'[' + '\\u00a0\\ufeff x \\n'.trim() + ']'", "[x]"),
+            ("'aaa'.replaceAll('a', '$&b')", "ababab"),
+            ("try { 'a'.replaceAll(/a/, '') } catch (e) { e.name }", "TypeError"),
+            ("'abc'.startsWith('b', 1) + ',' + 'abc'.endsWith('b', 2)", "true,true"),
+            ("try { 'abc'.includes(/b/) } catch (e) { e.name }", "TypeError"),
+            ("try { String.fromCodePoint(-1) } catch (e) { e.name }", "RangeError"),
+            ("try { String.fromCodePoint(1.5) } catch (e) { e.name }", "RangeError"),
+            ("String.raw({ raw: ['a', 'b', 'c'] }, 1, 2, 3)", "a1b2c"),
+            (
+                "var lone = 'ab' + String.fromCharCode(0xd800); lone.isWellFormed() + ',' + lone.toWellFormed().charCodeAt(2)",
+                "false,65533",
+            ),
+            ("'\\u0041\\u030a'.normalize().length", "1"),
+            ("'\\u00c5'.normalize('NFD').length", "2"),
+            ("try { 'a'.normalize('nfc') } catch (e) { e.name }", "RangeError"),
+            ("try { String.prototype.trim.call(null) } catch (e) { e.name }", "TypeError"),
+        ]);
+    }
 }
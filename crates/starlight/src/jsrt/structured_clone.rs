@@ -0,0 +1,676 @@
+//! The structured clone algorithm of the HTML Standard and the `structuredClone` global.
+//!
+//! Like the snapshot serializer, cloning first writes the object graph out and then builds it
+//! again. Every object gets an index in write order and references between objects are written
+//! as that index, the way `SnapshotSerializer::write_gcpointer` writes heap references. Reading
+//! allocates all objects before any property is filled in, so cycles and shared references come
+//! out the same. Neither side recurses, which keeps deep graphs off the native stack. Unlike a
+//! snapshot the records do not hold heap pointers, which lets them be read back into any context
+//! of the same runtime.
+//!
+//! Plain objects, arrays, `Map`, `Set`, `Date`, `RegExp`, `ArrayBuffer` and its views and errors
+//! are cloned. Everything else throws a `DataCloneError`.
+use std::{collections::HashMap, mem::ManuallyDrop};
+
+use num::BigInt;
+
+use super::{
+    date::Date,
+    regexp::{new_regexp_object, RegExp},
+};
+use crate::{
+    prelude::*,
+    vm::{
+        array_buffer::JsArrayBuffer,
+        bigint::JsBigInt,
+        context::Context,
+        data_view::JsDataView,
+        interpreter::SpreadValue,
+        map::{JsMap, JsSet},
+        object::TypedJsObject,
+        operations,
+        proxy::JsProxy,
+        typedarray::{JsTypedArray, TypedArrayKind},
+    },
+};
+
+/// Serialized form of a value, objects are written as their index.
+enum Value {
+    Undefined,
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(Vec<u16>),
+    BigInt(BigInt),
+    Object(usize),
+}
+
+/// Serialized form of an object, the index of an object is the position of its record.
+enum Record {
+    /// `ArrayBuffer` from the transfer list, its data block is moved instead.
+    Transferred,
+    Date(f64),
+    RegExp(RegExp),
+    ArrayBuffer(Vec<u8>),
+    TypedArray {
+        kind: TypedArrayKind,
+        buffer: usize,
+        offset: usize,
+        length: usize,
+    },
+    DataView {
+        buffer: usize,
+        offset: usize,
+        length: usize,
+    },
+    Error {
+        name: &'static str,
+        message: Option<Vec<u16>>,
+    },
+    Array {
+        length: u32,
+        properties: Vec<(Symbol, Value)>,
+    },
+    Object(Vec<(Symbol, Value)>),
+    /// Keys and values in turn.
+    Map(Vec<Value>),
+    Set(Vec<Value>),
+}
+
+/// Object whose contents are still being written.
+enum Frame {
+    /// Own properties left to write, the next one last.
+    Properties { index: usize, keys: Vec<Symbol> },
+    /// Number of map or set entries left to write, they are at the end of `pending`.
+    Entries { index: usize, remaining: usize },
+}
+
+/// Data block taken from a transferred `ArrayBuffer`.
+struct Transferred {
+    data: *mut u8,
+    size: usize,
+}
+
+const ERROR_NAMES: [&str; 8] = [
+    "Error",
+    "EvalError",
+    "RangeError",
+    "ReferenceError",
+    "SyntaxError",
+    "TypeError",
+    "URIError",
+    "AggregateError",
+];
+
+fn data_clone_error(ctx: GcPointer<Context>, message: impl AsRef<str>) -> JsValue {
+    let stack = ctx.shadowstack();
+    let message = JsString::new(ctx, message);
+    letroot!(error = stack, JsError::new(ctx, message, None));
+    let name = JsValue::new(JsString::new(ctx, "DataCloneError"));
+    let _ = error.define_own_property(
+        ctx,
+        "name".intern(),
+        &*DataDescriptor::new(name, W | C),
+        false,
+    );
+    JsValue::new(*error)
+}
+
+fn address(object: GcPointer<JsObject>) -> usize {
+    &*object as *const JsObject as usize
+}
+
+struct Serializer<'a> {
+    ctx: GcPointer<Context>,
+    /// Objects written so far in write order. Keeping them here also keeps them alive while
+    /// getters run, so an address in `memory` can not be reused by another object.
+    objects: &'a mut Vec<JsValue>,
+    /// Map and set entries copied when the collection was reached, the next one last.
+    pending: &'a mut Vec<JsValue>,
+    memory: HashMap<usize, usize>,
+    records: Vec<Record>,
+    frames: Vec<Frame>,
+}
+
+impl<'a> Serializer<'a> {
+    /// Assigns the next index to `object`.
+    fn remember(&mut self, object: GcPointer<JsObject>, record: Record) -> usize {
+        let index = self.objects.len();
+        self.memory.insert(address(object), index);
+        self.objects.push(JsValue::new(object));
+        self.records.push(record);
+        index
+    }
+
+    /// Writes `value` and everything reachable from it, depth first like the spec does.
+    fn write(&mut self, value: JsValue) -> Result<Value, JsValue> {
+        let ctx = self.ctx;
+        let root = self.write_value(value)?;
+        while let Some(frame) = self.frames.pop() {
+            match frame {
+                Frame::Properties { index, mut keys } => {
+                    let key = match keys.pop() {
+                        Some(key) => key,
+                        None => continue,
+                    };
+                    self.frames.push(Frame::Properties { index, keys });
+                    let mut object = self.objects[index].get_jsobject();
+                    if operations::get_own_property(ctx, &mut object, key)?.is_none() {
+                        continue;
+                    }
+                    let value = object.get(ctx, key)?;
+                    let value = self.write_value(value)?;
+                    match &mut self.records[index] {
+                        Record::Array { properties, .. } | Record::Object(properties) => {
+                            properties.push((key, value))
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                Frame::Entries { index, remaining } => {
+                    if remaining == 0 {
+                        continue;
+                    }
+                    self.frames.push(Frame::Entries {
+                        index,
+                        remaining: remaining - 1,
+                    });
+                    let value = self.pending.pop().unwrap();
+                    let value = self.write_value(value)?;
+                    match &mut self.records[index] {
+                        Record::Map(entries) | Record::Set(entries) => entries.push(value),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        }
+        Ok(root)
+    }
+
+    /// Writes a primitive or the index of an object. The contents of objects that have any are
+    /// left to `write` through a new frame.
+    fn write_value(&mut self, value: JsValue) -> Result<Value, JsValue> {
+        let ctx = self.ctx;
+        if value.is_undefined() {
+            return Ok(Value::Undefined);
+        }
+        if value.is_null() {
+            return Ok(Value::Null);
+        }
+        if value.is_bool() {
+            return Ok(Value::Boolean(value.get_bool()));
+        }
+        if value.is_number() {
+            return Ok(Value::Number(value.get_number()));
+        }
+        if value.is_string() {
+            return Ok(Value::String(value.get_string().to_utf16().into_owned()));
+        }
+        if value.is_bigint() {
+            return Ok(Value::BigInt(value.get_bigint().value().clone()));
+        }
+        if !value.is_jsobject() {
+            return Err(data_clone_error(ctx, "Symbol could not be cloned"));
+        }
+        self.write_object(value.get_jsobject()).map(Value::Object)
+    }
+
+    fn write_object(&mut self, object: GcPointer<JsObject>) -> Result<usize, JsValue> {
+        let ctx = self.ctx;
+        if let Some(&index) = self.memory.get(&address(object)) {
+            return Ok(index);
+        }
+        if object.is_callable() {
+            return Err(data_clone_error(ctx, "function could not be cloned"));
+        }
+
+        if object.is_class(Date::get_class()) {
+            let time = object.data::<Date>().get_time();
+            return Ok(self.remember(object, Record::Date(time)));
+        }
+        if object.is_class(RegExp::get_class()) {
+            let regexp = (**object.data::<RegExp>()).clone();
+            return Ok(self.remember(object, Record::RegExp(regexp)));
+        }
+        if object.is_class(JsArrayBuffer::get_class()) {
+            let buffer = TypedJsObject::<JsArrayBuffer>::new(object);
+            if !buffer.attached() {
+                return Err(data_clone_error(
+                    ctx,
+                    "detached ArrayBuffer could not be cloned",
+                ));
+            }
+            let bytes = if buffer.size() == 0 {
+                vec![]
+            } else {
+                buffer.data().to_vec()
+            };
+            return Ok(self.remember(object, Record::ArrayBuffer(bytes)));
+        }
+        if JsTypedArray::is_typed_array(&object) {
+            let index = self.remember(object, Record::Transferred);
+            let array = object.data::<JsTypedArray>();
+            let (kind, offset, length) = (array.kind(), array.byte_offset(), array.length());
+            let buffer = self.write_object(array.buffer().object())?;
+            self.records[index] = Record::TypedArray {
+                kind,
+                buffer,
+                offset,
+                length,
+            };
+            return Ok(index);
+        }
+        if object.is_class(JsDataView::get_class()) {
+            let index = self.remember(object, Record::Transferred);
+            let view = object.data::<JsDataView>();
+            let (offset, length) = (view.byte_offset(), view.byte_length());
+            let buffer = self.write_object(view.get_buffer().object())?;
+            self.records[index] = Record::DataView {
+                buffer,
+                offset,
+                length,
+            };
+            return Ok(index);
+        }
+        if is_error(&object) {
+            let index = self.remember(object, Record::Transferred);
+            self.records[index] = self.write_error(object)?;
+            return Ok(index);
+        }
+        if object.is_class(JsMap::get_class()) || object.is_class(JsSet::get_class()) {
+            let is_map = object.is_class(JsMap::get_class());
+            let record = if is_map {
+                Record::Map(vec![])
+            } else {
+                Record::Set(vec![])
+            };
+            let index = self.remember(object, record);
+            let entries = if is_map {
+                object.data::<JsMap>().storage.iter().collect::<Vec<_>>()
+            } else {
+                object.data::<JsSet>().storage.iter().collect::<Vec<_>>()
+            };
+            let start = self.pending.len();
+            for (key, value) in entries.into_iter().rev() {
+                if is_map {
+                    self.pending.push(value);
+                }
+                self.pending.push(key);
+            }
+            let remaining = self.pending.len() - start;
+            self.frames.push(Frame::Entries { index, remaining });
+            return Ok(index);
+        }
+        if object.is_class(JsArray::get_class()) {
+            let mut array = object;
+            let length = array.get(ctx, "length".intern())?.get_number() as u32;
+            let properties = vec![];
+            let index = self.remember(object, Record::Array { length, properties });
+            let keys = self.enumerable_keys(object)?;
+            self.frames.push(Frame::Properties { index, keys });
+            return Ok(index);
+        }
+        if object.is_class(JsObject::get_class())
+            && object.tag() == ObjectTag::Ordinary
+            && !JsProxy::is_proxy(&object)
+        {
+            let index = self.remember(object, Record::Object(vec![]));
+            let keys = self.enumerable_keys(object)?;
+            self.frames.push(Frame::Properties { index, keys });
+            return Ok(index);
+        }
+        Err(data_clone_error(
+            ctx,
+            format!("{} object could not be cloned", object.class().name),
+        ))
+    }
+
+    /// Own enumerable string keys, the first key last. Their values are read one at a time
+    /// afterwards.
+    fn enumerable_keys(&mut self, mut object: GcPointer<JsObject>) -> Result<Vec<Symbol>, JsValue> {
+        let ctx = self.ctx;
+        let mut keys = vec![];
+        for key in operations::own_property_keys(ctx, &mut object)? {
+            if let Symbol::Private(_) = key {
+                continue;
+            }
+            match operations::get_own_property(ctx, &mut object, key)? {
+                Some(desc) if desc.is_enumerable() => keys.push(key),
+                _ => (),
+            }
+        }
+        keys.reverse();
+        Ok(keys)
+    }
+
+    fn write_error(&mut self, mut object: GcPointer<JsObject>) -> Result<Record, JsValue> {
+        let ctx = self.ctx;
+        let name = object.get(ctx, "name".intern())?.to_string(ctx)?;
+        let name = ERROR_NAMES
+            .iter()
+            .find(|known| **known == name)
+            .copied()
+            .unwrap_or("Error");
+        let message = match operations::get_own_property(ctx, &mut object, "message".intern())? {
+            Some(desc) if desc.is_data() => {
+                Some(desc.value().to_jsstring(ctx)?.to_utf16().into_owned())
+            }
+            _ => None,
+        };
+        Ok(Record::Error { name, message })
+    }
+}
+
+struct Deserializer<'a> {
+    ctx: GcPointer<Context>,
+    /// Objects read so far, indexed the same way as on the writing side.
+    objects: &'a mut Vec<JsValue>,
+}
+
+impl<'a> Deserializer<'a> {
+    /// Allocates every object of `records` and then fills in their contents.
+    fn read(&mut self, records: &[Record], root: &Value) -> Result<JsValue, JsValue> {
+        let ctx = self.ctx;
+        let stack = ctx.shadowstack();
+        // Transferred buffers are already in `objects`.
+        for record in &records[self.objects.len()..] {
+            let object = self.allocate(record)?;
+            self.objects.push(object);
+        }
+        // Views go second, the buffers they refer to exist by now.
+        for (index, record) in records.iter().enumerate() {
+            let object = match record {
+                Record::TypedArray {
+                    kind,
+                    buffer,
+                    offset,
+                    length,
+                } => {
+                    let buffer = TypedJsObject::<JsArrayBuffer>::new(self.objects[*buffer]);
+                    JsTypedArray::new(ctx, *kind, buffer, *offset, *length)
+                }
+                Record::DataView {
+                    buffer,
+                    offset,
+                    length,
+                } => {
+                    let buffer = TypedJsObject::<JsArrayBuffer>::new(self.objects[*buffer]);
+                    JsDataView::new(ctx, buffer, *offset, *length)
+                }
+                _ => continue,
+            };
+            self.objects[index] = JsValue::new(object);
+        }
+        for (index, record) in records.iter().enumerate() {
+            let mut object = self.objects[index].get_jsobject();
+            match record {
+                Record::Array { properties, .. } | Record::Object(properties) => {
+                    for (key, value) in properties {
+                        let value = self.value(value);
+                        object.define_own_property(
+                            ctx,
+                            *key,
+                            &*DataDescriptor::new(value, W | E | C),
+                            false,
+                        )?;
+                    }
+                }
+                Record::Map(entries) => {
+                    for entry in entries.chunks(2) {
+                        letroot!(key = stack, self.value(&entry[0]));
+                        let value = self.value(&entry[1]);
+                        object.data::<JsMap>().storage.set(*key, value);
+                    }
+                }
+                Record::Set(values) => {
+                    for value in values {
+                        let value = self.value(value);
+                        object.data::<JsSet>().add(value);
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(self.value(root))
+    }
+
+    /// The object of `record` without its contents, views are left to `read`.
+    fn allocate(&mut self, record: &Record) -> Result<JsValue, JsValue> {
+        let ctx = self.ctx;
+        let stack = ctx.shadowstack();
+        Ok(match record {
+            Record::Transferred | Record::TypedArray { .. } | Record::DataView { .. } => {
+                JsValue::encode_undefined_value()
+            }
+            Record::Date(time) => {
+                let structure = ctx.global_data().date_structure.unwrap();
+                let object = JsObject::new(ctx, &structure, Date::get_class(), ObjectTag::Ordinary);
+                *object.data::<Date>() = ManuallyDrop::new(Date::from_time_value(*time));
+                JsValue::new(object)
+            }
+            Record::RegExp(regexp) => {
+                let proto = ctx.global_data().regexp_prototype;
+                JsValue::new(new_regexp_object(ctx, proto, regexp.clone())?)
+            }
+            Record::ArrayBuffer(bytes) => {
+                letroot!(object = stack, JsArrayBuffer::new(ctx));
+                let mut buffer = TypedJsObject::<JsArrayBuffer>::new(*object);
+                buffer.create_data_block(ctx, bytes.len(), false)?;
+                if !bytes.is_empty() {
+                    buffer.data_mut().copy_from_slice(bytes);
+                }
+                JsValue::new(*object)
+            }
+            Record::Error { name, message } => {
+                let message = match message {
+                    Some(units) => JsString::from_utf16(ctx, units),
+                    None => JsString::new(ctx, ""),
+                };
+                JsValue::new(match *name {
+                    "EvalError" => JsEvalError::new(ctx, message, None),
+                    "RangeError" => JsRangeError::new(ctx, message, None),
+                    "ReferenceError" => JsReferenceError::new(ctx, message, None),
+                    "SyntaxError" => JsSyntaxError::new(ctx, message, None),
+                    "TypeError" => JsTypeError::new(ctx, message, None),
+                    "URIError" => JsURIError::new(ctx, message, None),
+                    "AggregateError" => JsAggregateError::new(ctx, message, None),
+                    _ => JsError::new(ctx, message, None),
+                })
+            }
+            Record::Array { length, .. } => JsValue::new(JsArray::new(ctx, *length)),
+            Record::Object(_) => JsValue::new(JsObject::new_empty(ctx)),
+            Record::Map(_) => {
+                let structure = ctx.global_data().map_structure.unwrap();
+                JsValue::new(JsMap::new(ctx, &structure))
+            }
+            Record::Set(_) => {
+                let structure = ctx.global_data().set_structure.unwrap();
+                JsValue::new(JsSet::new(ctx, &structure))
+            }
+        })
+    }
+
+    fn value(&self, value: &Value) -> JsValue {
+        let ctx = self.ctx;
+        match value {
+            Value::Undefined => JsValue::encode_undefined_value(),
+            Value::Null => JsValue::encode_null_value(),
+            Value::Boolean(value) => JsValue::new(*value),
+            Value::Number(value) => JsValue::new(*value),
+            Value::String(units) => JsValue::new(JsString::from_utf16(ctx, units)),
+            Value::BigInt(value) => JsValue::new(JsBigInt::new(ctx, value.clone())),
+            Value::Object(index) => self.objects[*index],
+        }
+    }
+}
+
+impl GcPointer<Context> {
+    /// Copies `value` from this context into `target` with the structured clone algorithm.
+    /// Both contexts have to belong to the same runtime, the clone uses the prototypes of
+    /// `target`. Array buffers in `transfer` are moved instead of copied and are detached here.
+    pub fn structured_clone(
+        self,
+        value: JsValue,
+        transfer: &[JsValue],
+        target: GcPointer<Context>,
+    ) -> Result<JsValue, JsValue> {
+        let stack = self.shadowstack();
+        letroot!(sources = stack, Vec::<JsValue>::new());
+        letroot!(pending = stack, Vec::<JsValue>::new());
+        let mut serializer = Serializer {
+            ctx: self,
+            objects: &mut *sources,
+            pending: &mut *pending,
+            memory: HashMap::new(),
+            records: vec![],
+            frames: vec![],
+        };
+        for &transferable in transfer {
+            if !transferable.is_jsobject()
+                || !transferable
+                    .get_jsobject()
+                    .is_class(JsArrayBuffer::get_class())
+            {
+                return Err(data_clone_error(self, "value could not be transferred"));
+            }
+            let object = transferable.get_jsobject();
+            if serializer.memory.contains_key(&address(object)) {
+                return Err(data_clone_error(
+                    self,
+                    "ArrayBuffer is listed more than once in the transfer list",
+                ));
+            }
+            if !TypedJsObject::<JsArrayBuffer>::new(object).attached() {
+                return Err(data_clone_error(
+                    self,
+                    "detached ArrayBuffer could not be transferred",
+                ));
+            }
+            serializer.remember(object, Record::Transferred);
+        }
+        let root = serializer.write(value)?;
+        let records = std::mem::take(&mut serializer.records);
+
+        // Only detach once writing has succeeded, so a failed clone leaves the buffers usable.
+        let transferred = sources[..transfer.len()]
+            .iter()
+            .map(|object| {
+                let mut buffer = TypedJsObject::<JsArrayBuffer>::new(*object);
+                let size = buffer.size();
+                let data = std::mem::replace(&mut buffer.data, std::ptr::null_mut());
+                buffer.attached = false;
+                unsafe {
+                    buffer.set_size(0);
+                }
+                Transferred { data, size }
+            })
+            .collect::<Vec<_>>();
+
+        let stack = target.shadowstack();
+        letroot!(clones = stack, Vec::<JsValue>::new());
+        for Transferred { data, size } in transferred {
+            let mut buffer = TypedJsObject::<JsArrayBuffer>::new(JsArrayBuffer::new(target));
+            buffer.data = data;
+            buffer.attached = true;
+            unsafe {
+                buffer.set_size(size);
+            }
+            clones.push(JsValue::new(buffer.object()));
+        }
+        let mut deserializer = Deserializer {
+            ctx: target,
+            objects: &mut *clones,
+        };
+        deserializer.read(&records, &root)
+    }
+}
+
+/// structuredClone(value, { transfer })
+pub fn structured_clone(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(transfer = stack, Vec::<JsValue>::new());
+    let options = args.at(1);
+    if options.is_jsobject() {
+        let list = options.get_jsobject().get(ctx, "transfer".intern())?;
+        if !list.is_undefined() {
+            letroot!(list = stack, SpreadValue::new(ctx, list)?);
+            transfer.extend_from_slice(&list.array);
+        }
+    } else if !options.is_undefined() && !options.is_null() {
+        return Err(JsValue::new(
+            ctx.new_type_error("structuredClone options must be an object"),
+        ));
+    }
+    ctx.structured_clone(args.at(0), &transfer, ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_structured_clone() {
+        assert_eval_cases(&[
+            (
+                "var m = new Map([['a', { n: 1 }]]); var c = structuredClone(m); (c !== m) + ',' + c.get('a').n",
+                "true,1",
+            ),
+            (
+                "var o = { x: 1 }; var c = structuredClone(new Map([[o, o]])); var k = [...c.keys()][0]; (k === c.get(k)) + ',' + (k !== o)",
+                "true,true",
+            ),
+            (
+                "var s = new Set([1, 'b']); s.add(s); var c = structuredClone(s); c.has(c) + ',' + c.has('b') + ',' + c.size",
+                "true,true,3",
+            ),
+            (
+                "var a = []; a.push(a); var c = structuredClone(a); c[0] === c",
+                "true",
+            ),
+            (
+                "var l = null; for (var i = 0; i < 100000; i++) l = { next: l }; var c = structuredClone(l); var n = 0; while (c) { n++; c = c.next; } n",
+                "100000",
+            ),
+            (
+                "var o = {}; var c = structuredClone({ a: [1, o], b: o }); c.a[1] === c.b",
+                "true",
+            ),
+            (
+                "var b = new ArrayBuffer(4); var c = structuredClone([new Uint8Array(b, 1), new DataView(b)]); c[0].buffer === c[1].buffer",
+                "true",
+            ),
+            (
+                "try { structuredClone(new Map([[1, function () {}]])); } catch (e) { e.name }",
+                "DataCloneError",
+            ),
+            (
+                "var b = new ArrayBuffer(8); var c = structuredClone(b, { transfer: [b] }); b.byteLength + ',' + c.byteLength",
+                "0,8",
+            ),
+            (
+                "var b = new ArrayBuffer(8); try { structuredClone(b, { transfer: [b, b] }); } catch (e) { e.name + ',' + b.byteLength }",
+                "DataCloneError,8",
+            ),
+            (
+                "var b = new ArrayBuffer(8); try { structuredClone([b, function () {}], { transfer: [b] }); } catch (e) { e.name + ',' + b.byteLength }",
+                "DataCloneError,8",
+            ),
+            (
+                "try { structuredClone(Symbol()); } catch (e) { e.name }",
+                "DataCloneError",
+            ),
+            (
+                "var d = structuredClone(new Date(5)); (d instanceof Date) + ',' + d.getTime()",
+                "true,5",
+            ),
+            (
+                "var e = structuredClone(new RangeError('r')); (e instanceof RangeError) + ',' + e.message",
+                "true,r",
+            ),
+            (
+                "var o = { get x() { return 1; } }; Object.getOwnPropertyDescriptor(structuredClone(o), 'x').value",
+                "1",
+            ),
+        ]);
+    }
+}
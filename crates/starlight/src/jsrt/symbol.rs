@@ -1,9 +1,4 @@
-use crate::{
-    constant::S_CONSTURCTOR,
-    prelude::*,
-    vm::{context::Context, object::TypedJsObject},
-    JsTryFrom,
-};
+use crate::{constant::S_CONSTURCTOR, prelude::*, vm::context::Context};
 use std::intrinsics::unlikely;
 
 macro_rules! builtin_symbols {
@@ -65,13 +60,34 @@ impl GcPointer<Context> {
             .unwrap()
             .change_prototype_with_no_transition(sym_proto);
         self.global_data.symbol_prototype = Some(sym_proto);
-        def_native_method!(self, sym_proto, toString, symbol_to_string, 0)?;
-        def_native_method!(self, sym_proto, valueOf, symbol_value_of, 0)?;
+        def_native_method!(self, sym_proto, toString, symbol_to_string, 0, W | C)?;
+        def_native_method!(self, sym_proto, valueOf, symbol_value_of, 0, W | C)?;
+        super::define_getter(
+            self,
+            &mut sym_proto,
+            "description".intern(),
+            symbol_description,
+        )?;
+        let to_primitive =
+            JsNativeFunction::new(self, "[Symbol.toPrimitive]".intern(), symbol_value_of, 1);
+        sym_proto.define_own_property(
+            self,
+            "Symbol.toPrimitive".intern().private(),
+            &*DataDescriptor::new(JsValue::new(to_primitive), C),
+            false,
+        )?;
+        let tag = JsString::new(self, "Symbol");
+        sym_proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
 
-        let mut ctor = JsNativeFunction::new(self, "Symbol".intern(), symbol_ctor, 1);
+        let mut ctor = JsNativeFunction::new(self, "Symbol".intern(), symbol_ctor, 0);
 
-        def_native_method!(self, ctor, for, symbol_for, 1)?;
-        def_native_method!(self, ctor, keyFor, symbol_key_for, 1)?;
+        def_native_method!(self, ctor, for, symbol_for, 1, W | C)?;
+        def_native_method!(self, ctor, keyFor, symbol_key_for, 1, W | C)?;
         builtin_symbols!(self, ctor, def_symbols);
         def_native_property!(self, ctor, prototype, sym_proto, NONE)?;
         def_native_property!(self, sym_proto, constructor, ctor, W | C)?;
@@ -87,33 +103,67 @@ pub fn symbol_ctor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue,
         ));
     }
 
-    let arg = args.at(0).to_string(ctx)?.intern();
-    Ok(JsValue::new(JsSymbol::new(ctx, arg)))
+    let description = if args.at(0).is_undefined() {
+        None
+    } else {
+        Some(args.at(0).to_string(ctx)?)
+    };
+    let id = symbol_table().unique(description.as_deref());
+    Ok(JsValue::new(JsSymbol::new(ctx, Symbol::Private(id))))
 }
+
 pub fn symbol_for(mut ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let arg = args.at(0).to_string(ctx)?.intern();
+    let key = args.at(0).to_jsstring(ctx)?;
+    let name = key.intern();
 
-    if let Some(sym) = ctx.symbol_table.get(&arg) {
+    if let Some(sym) = ctx.symbol_table.get(&name) {
         Ok(JsValue::new(*sym))
     } else {
-        let sym = JsSymbol::new(ctx, arg);
-        ctx.symbol_table.insert(arg, sym);
+        let id = symbol_table().unique(Some(&key.as_str()));
+        let sym = JsSymbol::new(ctx, Symbol::Private(id));
+        ctx.symbol_table.insert(name, sym);
         Ok(JsValue::new(sym))
     }
 }
 
 pub fn symbol_key_for(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let sym = TypedJsObject::<JsSymbolObject>::try_from(ctx, args.at(0))?
-        .symbol()
-        .symbol();
-    let desc = ctx.description(sym);
-    Ok(JsValue::new(JsString::new(ctx, desc)))
+    if unlikely(!args.at(0).is_symbol()) {
+        return Err(JsValue::new(
+            ctx.new_type_error("Symbol.keyFor: argument is not a symbol"),
+        ));
+    }
+    let sym = args.at(0).get_object().downcast::<JsSymbol>().unwrap();
+    for (name, registered) in ctx.symbol_table.iter() {
+        if GcPointer::ptr_eq(registered, &sym) {
+            return Ok(JsValue::new(ctx.key_to_jsstring(*name)));
+        }
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// thisSymbolValue
+fn this_symbol_value(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    method: &str,
+) -> Result<GcPointer<JsSymbol>, JsValue> {
+    if value.is_symbol() {
+        return Ok(value.get_object().downcast::<JsSymbol>().unwrap());
+    }
+    if value.is_jsobject() {
+        let mut object = value.get_jsobject();
+        if object.is_class(JsSymbolObject::get_class()) {
+            return Ok(object.data::<JsSymbolObject>().symbol());
+        }
+    }
+    Err(JsValue::new(ctx.new_type_error(format!(
+        "Symbol.prototype.{} requires that 'this' be a Symbol",
+        method
+    ))))
 }
 
 pub fn symbol_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    let sym = TypedJsObject::<JsSymbolObject>::try_from(ctx, args.this)?
-        .symbol()
-        .symbol();
+    let sym = this_symbol_value(ctx, args.this, "toString")?.symbol();
     let desc = ctx.description(sym);
     Ok(JsValue::new(JsString::new(
         ctx,
@@ -121,8 +171,76 @@ pub fn symbol_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsV
     )))
 }
 
-pub fn symbol_value_of(_ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
-    Ok(JsValue::new(
-        TypedJsObject::<JsSymbolObject>::try_from(_ctx, args.this)?.symbol(),
-    ))
+pub fn symbol_value_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(this_symbol_value(ctx, args.this, "valueOf")?))
+}
+
+pub fn symbol_description(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let sym = this_symbol_value(ctx, args.this, "description")?.symbol();
+    if !symbol_table().has_description(sym.get_id()) {
+        return Ok(JsValue::encode_undefined_value());
+    }
+    Ok(JsValue::new(JsString::new(ctx, ctx.description(sym))))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_registry_and_description() {
+        assert_eval_cases(&[
+            ("Symbol.for('a') === Symbol.for('a')", "true"),
+            ("Symbol('a') === Symbol('a')", "false"),
+            ("Symbol.keyFor(Symbol.for('a')) + ',' + Symbol.keyFor(Symbol('a'))", "a,undefined"),
+            ("try { Symbol.keyFor('a') } catch (e) { e.name }", "TypeError"),
+            (
+                "[Symbol('d').description, Symbol().description, Symbol('').description === ''].join()",
+                "d,,true",
+            ),
+            ("Symbol('x').toString()", "Symbol(x)"),
+            ("try { new Symbol() } catch (e) { e.name }", "TypeError"),
+            ("try { Symbol() + '' } catch (e) { e.name }", "TypeError"),
+            ("var sym = Symbol('x'); typeof Object(sym) + ',' + (Object(sym) == sym)", "object,true"),
+        ]);
+    }
+
+    #[test]
+    fn test_well_known_symbols() {
+        assert_eval_cases(&[
+            (
+                "var o = {}; o[Symbol.toPrimitive] = function (hint) { return hint; }; \
+                 [o + '', String(o), o * 1].join()",
+                "default,string,NaN",
+            ),
+            (
+                "var o = {}; o[Symbol.toPrimitive] = function () { return {}; }; \
+                 try { o + '' } catch (e) { e.name }",
+                "TypeError",
+            ),
+            (
+                "var Even = {}; Even[Symbol.hasInstance] = function (n) { return n % 2 === 0; }; \
+                 (2 instanceof Even) + ',' + (1 instanceof Even)",
+                "true,false",
+            ),
+            (
+                "var o = {}; o[Symbol.toStringTag] = 'Custom'; Object.prototype.toString.call(o)",
+                "[object Custom]",
+            ),
+            (
+                "var a = [1]; a[Symbol.isConcatSpreadable] = false; [0].concat(a).length",
+                "2",
+            ),
+            (
+                "var C = function () {}; var a = [1, 2]; a.constructor = {}; a.constructor[Symbol.species] = C; \
+                 a.map(function (x) { return x; }) instanceof C",
+                "true",
+            ),
+            (
+                "var it = {}; it[Symbol.iterator] = function () { var i = 0; \
+                 return { next: function () { return { done: i >= 2, value: i++ }; } }; }; [...it].join()",
+                "0,1",
+            ),
+        ]);
+    }
 }
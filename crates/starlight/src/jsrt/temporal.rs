@@ -0,0 +1,1081 @@
+//! `Temporal`: exact instants, wall-clock dates and times, date-times in time zones read from
+//! tzdata and durations, all in the ISO 8601 calendar.
+//!
+//! The arithmetic lives in [iso] and the string formats in [rfc9557], the modules of the classes
+//! only convert between JS values and those.
+use std::{
+    intrinsics::unlikely,
+    mem::ManuallyDrop,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use num::{BigInt, ToPrimitive};
+
+use crate::{
+    constant::S_CONSTURCTOR,
+    prelude::*,
+    vm::{bigint::JsBigInt, class::JsClass, context::Context},
+};
+
+use self::{
+    iso::{
+        instant_for, instant_with_offset, is_valid_epoch_nanoseconds, Disambiguation, IsoDate,
+        IsoDateTime, IsoTime, Overflow, RoundingMode, RoundingOptions, Unit, NS_PER_DAY,
+        NS_PER_MILLISECOND, NS_PER_MINUTE,
+    },
+    rfc9557::{Offset, Precision},
+};
+use super::date::TimeZone;
+
+/// Defines getters of the calendar and clock fields of the [IsoDateTime] that `$this` reads from
+/// `this`, see [date_time_field] for the field names.
+macro_rules! date_time_getters {
+    ($this: path; $($name: ident => $field: literal),* $(,)?) => {
+        $(
+            pub fn $name(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+                let date_time = $this(ctx, args)?;
+                Ok(super::date_time_field(ctx, date_time, $field))
+            }
+        )*
+    };
+}
+
+mod iso;
+mod rfc9557;
+
+pub mod duration;
+pub mod instant;
+pub mod now;
+pub mod plain_date;
+pub mod plain_date_time;
+pub mod plain_time;
+pub mod zoned_date_time;
+
+pub(crate) use self::rfc9557::parse_time_zone;
+
+/// Epoch nanoseconds in object data, which is not aligned for `i128`.
+#[derive(Clone, Copy)]
+struct EpochNanoseconds([i64; 2]);
+
+impl EpochNanoseconds {
+    fn new(nanoseconds: i128) -> Self {
+        Self([(nanoseconds >> 64) as i64, nanoseconds as i64])
+    }
+
+    fn get(self) -> i128 {
+        ((self.0[0] as i128) << 64) | (self.0[1] as u64 as i128)
+    }
+}
+
+/// Nanoseconds since the epoch of the system clock.
+fn system_nanoseconds() -> i128 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_nanos() as i128,
+        Err(before) => -(before.duration().as_nanos() as i128),
+    }
+}
+
+fn range_error(ctx: GcPointer<Context>, message: impl AsRef<str>) -> JsValue {
+    JsValue::new(ctx.new_range_error(message))
+}
+
+fn type_error(ctx: GcPointer<Context>, message: impl AsRef<str>) -> JsValue {
+    JsValue::new(ctx.new_type_error(message))
+}
+
+fn string_value(ctx: GcPointer<Context>, string: impl AsRef<str>) -> JsValue {
+    JsValue::new(JsString::new(ctx, string))
+}
+
+/// Allocates an object of the Temporal class `T` that inherits from `proto`.
+fn new_object<T: JsClass>(
+    ctx: GcPointer<Context>,
+    proto: Option<GcPointer<JsObject>>,
+    data: T,
+) -> JsValue {
+    let stack = ctx.shadowstack();
+    letroot!(structure = stack, Structure::new_indexed(ctx, proto, false));
+    let object = JsObject::new(ctx, &structure, T::class(), ObjectTag::Ordinary);
+    *object.data::<T>() = ManuallyDrop::new(data);
+    JsValue::new(object)
+}
+
+/// Prototype for the object a Temporal constructor creates. `this` of constructor calls is
+/// allocated from new.target so subclasses keep their prototype.
+fn constructor_prototype(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    name: &str,
+) -> Result<Option<GcPointer<JsObject>>, JsValue> {
+    if unlikely(!args.ctor_call) {
+        return Err(type_error(
+            ctx,
+            format!("Temporal.{} constructor requires 'new'", name),
+        ));
+    }
+    if args.this.is_jsobject() {
+        Ok(args.this.get_jsobject().prototype().copied())
+    } else {
+        Ok(None)
+    }
+}
+
+/// `valueOf` of every Temporal class, comparisons with `<` would silently compare strings.
+pub fn temporal_value_of(ctx: GcPointer<Context>, _args: &Arguments) -> Result<JsValue, JsValue> {
+    Err(type_error(
+        ctx,
+        "Temporal objects cannot be converted to primitives, use compare() or equals() instead",
+    ))
+}
+
+/// ToIntegerWithTruncation
+fn to_integer_with_truncation(ctx: GcPointer<Context>, value: JsValue) -> Result<f64, JsValue> {
+    let number = value.to_number(ctx)?;
+    if unlikely(!number.is_finite()) {
+        return Err(range_error(ctx, "Temporal: value must be a finite number"));
+    }
+    Ok(number.trunc() + 0.0)
+}
+
+/// ToIntegerIfIntegral
+fn to_integer_if_integral(ctx: GcPointer<Context>, value: JsValue) -> Result<f64, JsValue> {
+    let number = value.to_number(ctx)?;
+    if unlikely(!number.is_finite() || number.trunc() != number) {
+        return Err(range_error(ctx, "Temporal: value must be an integer"));
+    }
+    Ok(number + 0.0)
+}
+
+/// Epoch nanoseconds from a BigInt, out of range values are a RangeError.
+fn to_epoch_nanoseconds(ctx: GcPointer<Context>, value: JsValue) -> Result<i128, JsValue> {
+    let bigint = super::bigint::to_bigint(ctx, value)?;
+    match bigint.value().to_i128() {
+        Some(nanoseconds) if is_valid_epoch_nanoseconds(nanoseconds) => Ok(nanoseconds),
+        _ => Err(range_error(
+            ctx,
+            "Temporal: epoch nanoseconds are out of range",
+        )),
+    }
+}
+
+fn epoch_nanoseconds_value(ctx: GcPointer<Context>, nanoseconds: i128) -> JsValue {
+    JsValue::new(JsBigInt::new(ctx, BigInt::from(nanoseconds)))
+}
+
+fn epoch_milliseconds_value(nanoseconds: i128) -> JsValue {
+    JsValue::new(nanoseconds.div_euclid(NS_PER_MILLISECOND) as f64)
+}
+
+/// Values of the getters of [date_time_getters].
+fn date_time_field(ctx: GcPointer<Context>, date_time: IsoDateTime, field: &str) -> JsValue {
+    let IsoDateTime { date, time } = date_time;
+    match field {
+        "calendarId" => string_value(ctx, "iso8601"),
+        "year" => JsValue::new(date.year),
+        "month" => JsValue::new(date.month),
+        "monthCode" => string_value(ctx, format!("M{:02}", date.month)),
+        "day" => JsValue::new(date.day),
+        "dayOfWeek" => JsValue::new(date.day_of_week()),
+        "dayOfYear" => JsValue::new(date.day_of_year()),
+        "weekOfYear" => JsValue::new(date.week_of_year().0),
+        "yearOfWeek" => JsValue::new(date.week_of_year().1),
+        "daysInWeek" => JsValue::new(7),
+        "daysInMonth" => JsValue::new(date.days_in_month()),
+        "daysInYear" => JsValue::new(date.days_in_year()),
+        "monthsInYear" => JsValue::new(12),
+        "inLeapYear" => JsValue::new(date.in_leap_year()),
+        "hour" => JsValue::new(time.hour),
+        "minute" => JsValue::new(time.minute),
+        "second" => JsValue::new(time.second),
+        "millisecond" => JsValue::new(time.millisecond),
+        "microsecond" => JsValue::new(time.microsecond),
+        "nanosecond" => JsValue::new(time.nanosecond),
+        _ => unreachable!("unknown Temporal field {}", field),
+    }
+}
+
+/// GetOptionsObject, `undefined` means no options.
+fn get_options(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+) -> Result<Option<GcPointer<JsObject>>, JsValue> {
+    if value.is_undefined() {
+        Ok(None)
+    } else if value.is_jsobject() {
+        Ok(Some(value.get_jsobject()))
+    } else {
+        Err(type_error(ctx, "Temporal: options must be an object"))
+    }
+}
+
+fn get_option(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+    name: &str,
+) -> Result<JsValue, JsValue> {
+    match options {
+        Some(mut options) => options.get(ctx, name.intern()),
+        None => Ok(JsValue::encode_undefined_value()),
+    }
+}
+
+/// String option that has to be one of `allowed`, `None` when it is not set.
+fn get_string_option(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+    name: &str,
+    allowed: &[&'static str],
+) -> Result<Option<&'static str>, JsValue> {
+    let value = get_option(ctx, options, name)?;
+    if value.is_undefined() {
+        return Ok(None);
+    }
+    let value = value.to_string(ctx)?;
+    match allowed.iter().find(|&&allowed| allowed == value) {
+        Some(&value) => Ok(Some(value)),
+        None => Err(range_error(
+            ctx,
+            format!("Temporal: {} is not a valid value for {}", value, name),
+        )),
+    }
+}
+
+fn get_overflow(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+) -> Result<Overflow, JsValue> {
+    match get_string_option(ctx, options, "overflow", &["constrain", "reject"])? {
+        Some("reject") => Ok(Overflow::Reject),
+        _ => Ok(Overflow::Constrain),
+    }
+}
+
+fn get_disambiguation(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+) -> Result<Disambiguation, JsValue> {
+    let allowed = ["compatible", "earlier", "later", "reject"];
+    Ok(
+        match get_string_option(ctx, options, "disambiguation", &allowed)? {
+            Some("earlier") => Disambiguation::Earlier,
+            Some("later") => Disambiguation::Later,
+            Some("reject") => Disambiguation::Reject,
+            _ => Disambiguation::Compatible,
+        },
+    )
+}
+
+/// How the offset of a zoned date-time string or property bag is used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OffsetOption {
+    /// The offset if the time zone has it at that wall-clock time, the time zone otherwise.
+    Prefer,
+    /// Only the offset, which keeps the exact time.
+    Use,
+    /// Only the time zone, which keeps the wall-clock time.
+    Ignore,
+    /// Offsets the time zone does not have at that wall-clock time are an error.
+    Reject,
+}
+
+fn get_offset_option(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+    default: OffsetOption,
+) -> Result<OffsetOption, JsValue> {
+    let allowed = ["prefer", "use", "ignore", "reject"];
+    Ok(match get_string_option(ctx, options, "offset", &allowed)? {
+        Some("prefer") => OffsetOption::Prefer,
+        Some("use") => OffsetOption::Use,
+        Some("ignore") => OffsetOption::Ignore,
+        Some("reject") => OffsetOption::Reject,
+        _ => default,
+    })
+}
+
+fn get_rounding_mode(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+    default: RoundingMode,
+) -> Result<RoundingMode, JsValue> {
+    let allowed = [
+        "ceil",
+        "floor",
+        "expand",
+        "trunc",
+        "halfCeil",
+        "halfFloor",
+        "halfExpand",
+        "halfTrunc",
+        "halfEven",
+    ];
+    Ok(get_string_option(ctx, options, "roundingMode", &allowed)?
+        .and_then(RoundingMode::from_name)
+        .unwrap_or(default))
+}
+
+/// GetRoundingIncrementOption
+fn get_rounding_increment(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+) -> Result<i128, JsValue> {
+    let value = get_option(ctx, options, "roundingIncrement")?;
+    if value.is_undefined() {
+        return Ok(1);
+    }
+    let increment = to_integer_with_truncation(ctx, value)?;
+    if unlikely(!(1.0..=1e9).contains(&increment)) {
+        return Err(range_error(
+            ctx,
+            "Temporal: roundingIncrement must be between 1 and 10^9",
+        ));
+    }
+    Ok(increment as i128)
+}
+
+/// ValidateTemporalRoundingIncrement, the increment has to divide `dividend` evenly.
+fn validate_increment(
+    ctx: GcPointer<Context>,
+    increment: i128,
+    dividend: i128,
+    inclusive: bool,
+) -> Result<(), JsValue> {
+    let maximum = if inclusive { dividend } else { dividend - 1 };
+    if unlikely(increment > maximum || dividend % increment != 0) {
+        return Err(range_error(
+            ctx,
+            format!("Temporal: roundingIncrement {} is not valid", increment),
+        ));
+    }
+    Ok(())
+}
+
+/// Units an option may name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum UnitGroup {
+    Date,
+    Time,
+    DateTime,
+}
+
+impl UnitGroup {
+    fn contains(self, unit: Unit) -> bool {
+        match self {
+            UnitGroup::Date => unit.is_date(),
+            UnitGroup::Time => !unit.is_date(),
+            UnitGroup::DateTime => true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum UnitChoice {
+    Unset,
+    Auto,
+    Unit(Unit),
+}
+
+/// GetTemporalUnitValuedOption, `auto` is only allowed for `largestUnit`.
+fn get_unit(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+    name: &str,
+    group: UnitGroup,
+) -> Result<UnitChoice, JsValue> {
+    let value = get_option(ctx, options, name)?;
+    if value.is_undefined() {
+        return Ok(UnitChoice::Unset);
+    }
+    let value = value.to_string(ctx)?;
+    if value == "auto" && name == "largestUnit" {
+        return Ok(UnitChoice::Auto);
+    }
+    match Unit::from_name(&value) {
+        Some(unit) if group.contains(unit) => Ok(UnitChoice::Unit(unit)),
+        _ => Err(range_error(
+            ctx,
+            format!("Temporal: {} is not a valid value for {}", value, name),
+        )),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Difference {
+    Until,
+    Since,
+}
+
+/// GetDifferenceSettings, the units and rounding of `until` and `since`. `since` rounds the
+/// difference from the other value, so the rounding mode is flipped.
+fn get_difference_settings(
+    ctx: GcPointer<Context>,
+    operation: Difference,
+    options: JsValue,
+    group: UnitGroup,
+    fallback_smallest: Unit,
+    default_largest: Unit,
+) -> Result<RoundingOptions, JsValue> {
+    let options = get_options(ctx, options)?;
+    let largest = get_unit(ctx, options, "largestUnit", group)?;
+    let increment = get_rounding_increment(ctx, options)?;
+    let mode = get_rounding_mode(ctx, options, RoundingMode::Trunc)?;
+    let smallest = match get_unit(ctx, options, "smallestUnit", group)? {
+        UnitChoice::Unit(unit) => unit,
+        _ => fallback_smallest,
+    };
+    let largest = match largest {
+        UnitChoice::Unit(unit) => unit,
+        _ => default_largest.max(smallest),
+    };
+    if unlikely(largest < smallest) {
+        return Err(range_error(
+            ctx,
+            "Temporal: smallestUnit must not be larger than largestUnit",
+        ));
+    }
+    if let Some(maximum) = smallest.maximum_increment() {
+        validate_increment(ctx, increment, maximum, false)?;
+    }
+    Ok(RoundingOptions {
+        largest,
+        smallest,
+        increment,
+        mode: match operation {
+            Difference::Until => mode,
+            Difference::Since => mode.negate(),
+        },
+    })
+}
+
+/// Settings of `round`, which takes the smallest unit or an options object.
+struct RoundSettings {
+    smallest: Unit,
+    increment: i128,
+    mode: RoundingMode,
+}
+
+fn get_round_settings(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    group: UnitGroup,
+) -> Result<RoundSettings, JsValue> {
+    if value.is_undefined() {
+        return Err(type_error(ctx, "Temporal: round requires smallestUnit"));
+    }
+    let options = if value.is_jsstring() {
+        let mut options = JsObject::new_empty(ctx);
+        options.put(ctx, "smallestUnit".intern(), value, false)?;
+        Some(options)
+    } else {
+        get_options(ctx, value)?
+    };
+    let increment = get_rounding_increment(ctx, options)?;
+    let mode = get_rounding_mode(ctx, options, RoundingMode::HalfExpand)?;
+    match get_unit(ctx, options, "smallestUnit", group)? {
+        UnitChoice::Unit(smallest) => Ok(RoundSettings {
+            smallest,
+            increment,
+            mode,
+        }),
+        _ => Err(range_error(ctx, "Temporal: round requires smallestUnit")),
+    }
+}
+
+/// Settings of `toString`: digits of the seconds and how the value is rounded to them.
+struct ToStringSettings {
+    precision: Precision,
+    unit: Unit,
+    increment: i128,
+    mode: RoundingMode,
+}
+
+/// GetTemporalFractionalSecondDigitsOption, roundingMode and smallestUnit, then
+/// ToSecondsStringPrecisionRecord.
+fn get_to_string_settings(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+) -> Result<ToStringSettings, JsValue> {
+    let digits = get_option(ctx, options, "fractionalSecondDigits")?;
+    let digits = if digits.is_undefined() {
+        None
+    } else if digits.is_number() {
+        let digits = digits.to_number(ctx)?;
+        if unlikely(!(0.0..10.0).contains(&digits.floor())) {
+            return Err(range_error(
+                ctx,
+                "Temporal: fractionalSecondDigits must be 'auto' or 0 to 9",
+            ));
+        }
+        Some(digits.floor() as u8)
+    } else if digits.to_string(ctx)? == "auto" {
+        None
+    } else {
+        return Err(range_error(
+            ctx,
+            "Temporal: fractionalSecondDigits must be 'auto' or 0 to 9",
+        ));
+    };
+    let mode = get_rounding_mode(ctx, options, RoundingMode::Trunc)?;
+    let smallest = get_unit(ctx, options, "smallestUnit", UnitGroup::Time)?;
+    let (precision, unit, increment) = match (smallest, digits) {
+        (UnitChoice::Unit(Unit::Hour), _) => {
+            return Err(range_error(
+                ctx,
+                "Temporal: smallestUnit must not be hour in toString",
+            ))
+        }
+        (UnitChoice::Unit(Unit::Minute), _) => (Precision::Minute, Unit::Minute, 1),
+        (UnitChoice::Unit(Unit::Second), _) => (Precision::Digits(0), Unit::Second, 1),
+        (UnitChoice::Unit(Unit::Millisecond), _) => (Precision::Digits(3), Unit::Millisecond, 1),
+        (UnitChoice::Unit(Unit::Microsecond), _) => (Precision::Digits(6), Unit::Microsecond, 1),
+        (UnitChoice::Unit(_), _) => (Precision::Digits(9), Unit::Nanosecond, 1),
+        (_, None) => (Precision::Auto, Unit::Nanosecond, 1),
+        (_, Some(digits)) => (
+            Precision::Digits(digits),
+            Unit::Nanosecond,
+            10i128.pow(9 - digits as u32),
+        ),
+    };
+    Ok(ToStringSettings {
+        precision,
+        unit,
+        increment,
+        mode,
+    })
+}
+
+impl ToStringSettings {
+    /// Length of the step values are rounded to in nanoseconds.
+    fn step(&self) -> i128 {
+        self.unit.nanoseconds().unwrap() * self.increment
+    }
+}
+
+/// Annotation `toString` writes for the `calendarName` option.
+fn get_calendar_annotation(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+) -> Result<&'static str, JsValue> {
+    let allowed = ["auto", "always", "never", "critical"];
+    Ok(
+        match get_string_option(ctx, options, "calendarName", &allowed)? {
+            Some("always") => "[u-ca=iso8601]",
+            Some("critical") => "[!u-ca=iso8601]",
+            _ => "",
+        },
+    )
+}
+
+/// Only the ISO 8601 calendar is available.
+fn check_calendar_id(ctx: GcPointer<Context>, id: &str) -> Result<(), JsValue> {
+    if id.eq_ignore_ascii_case("iso8601") {
+        Ok(())
+    } else {
+        Err(range_error(
+            ctx,
+            format!(
+                "Temporal: calendar {} is not supported, only iso8601 is",
+                id
+            ),
+        ))
+    }
+}
+
+/// Calendar argument of constructors and `calendar` field of property bags.
+fn to_calendar(ctx: GcPointer<Context>, value: JsValue) -> Result<(), JsValue> {
+    if value.is_undefined() {
+        return Ok(());
+    }
+    if unlikely(!value.is_jsstring()) {
+        return Err(type_error(ctx, "Temporal: calendar must be a string"));
+    }
+    check_calendar_id(ctx, &value.to_string(ctx)?)
+}
+
+fn check_parsed_calendar(
+    ctx: GcPointer<Context>,
+    parsed: &rfc9557::ParsedDateTime,
+) -> Result<(), JsValue> {
+    match &parsed.calendar {
+        Some(id) => check_calendar_id(ctx, id),
+        None => Ok(()),
+    }
+}
+
+/// Time zone of the runtime, the one `Date` uses.
+fn local_time_zone(ctx: GcPointer<Context>) -> Rc<TimeZone> {
+    Rc::new(ctx.vm().time_zone.clone())
+}
+
+/// ToTemporalTimeZoneIdentifier: a zoned date-time, a time zone identifier, or a date-time
+/// string with a time zone annotation or an offset.
+fn to_time_zone(ctx: GcPointer<Context>, value: JsValue) -> Result<Rc<TimeZone>, JsValue> {
+    if let Some(zoned) = zoned_date_time::to_zoned_date_time_object(value) {
+        return Ok(zoned.time_zone.clone());
+    }
+    if unlikely(!value.is_jsstring()) {
+        return Err(type_error(ctx, "Temporal: time zone must be a string"));
+    }
+    let id = value.to_string(ctx)?;
+    let zone = rfc9557::parse_time_zone(&id).or_else(|| {
+        let parsed = rfc9557::parse_date_time(&id)?;
+        match (parsed.time_zone, parsed.offset) {
+            (Some(name), _) => rfc9557::parse_time_zone(&name),
+            (None, Some(Offset::Utc)) => Some(TimeZone::utc()),
+            (None, Some(Offset::Nanoseconds(offset))) if offset % NS_PER_MINUTE == 0 => {
+                rfc9557::parse_time_zone(&rfc9557::format_offset(offset))
+            }
+            _ => None,
+        }
+    });
+    match zone {
+        Some(zone) => Ok(Rc::new(zone)),
+        None => Err(range_error(
+            ctx,
+            format!("Temporal: {} is not a valid time zone", id),
+        )),
+    }
+}
+
+/// InterpretISODateTimeOffset, the instant of the wall-clock date-time `local` in `zone` with the
+/// offset written along with it.
+#[allow(clippy::too_many_arguments)]
+fn interpret_offset(
+    ctx: GcPointer<Context>,
+    zone: &TimeZone,
+    local: IsoDateTime,
+    offset: Option<Offset>,
+    disambiguation: Disambiguation,
+    option: OffsetOption,
+    match_minutes: bool,
+) -> Result<i128, JsValue> {
+    let instant = match (offset, option) {
+        (None, _) | (_, OffsetOption::Ignore) => instant_for(zone, local, disambiguation),
+        (Some(Offset::Utc), _) => Some(local.epoch_nanoseconds()),
+        (Some(Offset::Nanoseconds(offset)), OffsetOption::Use) => {
+            Some(local.epoch_nanoseconds() - offset)
+        }
+        (Some(Offset::Nanoseconds(offset)), _) => {
+            match instant_with_offset(zone, local, offset, !match_minutes) {
+                Some(instant) => Some(instant),
+                None if option == OffsetOption::Reject => {
+                    return Err(range_error(
+                        ctx,
+                        format!(
+                            "Temporal: offset {} is not valid in time zone {}",
+                            rfc9557::format_exact_offset(offset),
+                            zone.name()
+                        ),
+                    ))
+                }
+                None => instant_for(zone, local, disambiguation),
+            }
+        }
+    };
+    match instant {
+        Some(instant) if is_valid_epoch_nanoseconds(instant) => Ok(instant),
+        Some(_) => Err(range_error(ctx, "Temporal: date-time is out of range")),
+        None => Err(range_error(
+            ctx,
+            format!(
+                "Temporal: {} is ambiguous or does not exist in time zone {}",
+                rfc9557::format_date_time(local, Precision::Auto),
+                zone.name()
+            ),
+        )),
+    }
+}
+
+/// Fields of a property bag like `{ year: 2026, month: 10, day: 18, hour: 10 }`.
+#[derive(Default)]
+struct Fields {
+    year: Option<f64>,
+    month: Option<f64>,
+    month_code: Option<String>,
+    day: Option<f64>,
+    /// Hour to nanosecond.
+    time: [Option<f64>; 6],
+    offset: Option<String>,
+}
+
+/// Which fields [read_fields] reads.
+#[derive(Clone, Copy)]
+struct FieldKinds {
+    date: bool,
+    time: bool,
+    offset: bool,
+}
+
+const TIME_FIELDS: [&str; 6] = [
+    "hour",
+    "minute",
+    "second",
+    "millisecond",
+    "microsecond",
+    "nanosecond",
+];
+
+/// PrepareCalendarFields, reads the fields in alphabetical order like the spec does.
+fn read_fields(
+    ctx: GcPointer<Context>,
+    object: GcPointer<JsObject>,
+    kinds: FieldKinds,
+) -> Result<Fields, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, object);
+    if kinds.date {
+        let calendar = object.get(ctx, "calendar".intern())?;
+        to_calendar(ctx, calendar)?;
+    }
+    let mut fields = Fields::default();
+    for &name in &[
+        "day",
+        "hour",
+        "microsecond",
+        "millisecond",
+        "minute",
+        "month",
+        "monthCode",
+        "nanosecond",
+        "offset",
+        "second",
+        "year",
+    ] {
+        let time_index = TIME_FIELDS.iter().position(|&field| field == name);
+        let wanted = match name {
+            "offset" => kinds.offset,
+            _ if time_index.is_some() => kinds.time,
+            _ => kinds.date,
+        };
+        if !wanted {
+            continue;
+        }
+        let value = object.get(ctx, name.intern())?;
+        if value.is_undefined() {
+            continue;
+        }
+        match name {
+            "monthCode" => fields.month_code = Some(value.to_string(ctx)?),
+            "offset" => fields.offset = Some(value.to_string(ctx)?),
+            "year" => fields.year = Some(to_integer_with_truncation(ctx, value)?),
+            "month" => fields.month = Some(to_integer_with_truncation(ctx, value)?),
+            "day" => fields.day = Some(to_integer_with_truncation(ctx, value)?),
+            _ => fields.time[time_index.unwrap()] = Some(to_integer_with_truncation(ctx, value)?),
+        }
+    }
+    Ok(fields)
+}
+
+impl Fields {
+    fn is_empty(&self) -> bool {
+        self.year.is_none()
+            && self.month.is_none()
+            && self.month_code.is_none()
+            && self.day.is_none()
+            && self.time.iter().all(Option::is_none)
+            && self.offset.is_none()
+    }
+
+    /// Fills the date fields that are not set from `date`, for `with`.
+    fn merge_date(&mut self, date: IsoDate) {
+        self.year.get_or_insert(date.year as f64);
+        self.day.get_or_insert(date.day as f64);
+        if self.month.is_none() && self.month_code.is_none() {
+            self.month = Some(date.month as f64);
+        }
+    }
+
+    /// CalendarDateFromFields
+    fn date(&self, ctx: GcPointer<Context>, overflow: Overflow) -> Result<IsoDate, JsValue> {
+        let missing = |name| type_error(ctx, format!("Temporal: {} is required", name));
+        let year = self.year.ok_or_else(|| missing("year"))?;
+        let month_code = match &self.month_code {
+            Some(code) => {
+                let month = code
+                    .strip_prefix('M')
+                    .filter(|digits| digits.len() == 2)
+                    .and_then(|digits| digits.parse::<u8>().ok())
+                    .filter(|month| (1..=12).contains(month));
+                match month {
+                    Some(month) => Some(month as f64),
+                    None => {
+                        return Err(range_error(
+                            ctx,
+                            format!("Temporal: {} is not a valid monthCode", code),
+                        ))
+                    }
+                }
+            }
+            None => None,
+        };
+        let month = match (self.month, month_code) {
+            (Some(month), Some(code)) if month != code => {
+                return Err(range_error(ctx, "Temporal: month and monthCode differ"))
+            }
+            (Some(month), _) | (None, Some(month)) => month,
+            (None, None) => return Err(missing("month")),
+        };
+        let day = self.day.ok_or_else(|| missing("day"))?;
+        let date = IsoDate::regulate(year as i64, month as i64, day as i64, overflow)
+            .filter(|date| date.within_limits());
+        date.ok_or_else(|| range_error(ctx, "Temporal: date is not valid or out of range"))
+    }
+
+    /// RegulateTime, fields that are not set are taken from `base`.
+    fn time(
+        &self,
+        ctx: GcPointer<Context>,
+        overflow: Overflow,
+        base: IsoTime,
+    ) -> Result<IsoTime, JsValue> {
+        let mut values = base.fields();
+        for (value, field) in values.iter_mut().zip(self.time.iter()) {
+            if let Some(field) = field {
+                *value = *field;
+            }
+        }
+        IsoTime::regulate(values, overflow)
+            .ok_or_else(|| range_error(ctx, "Temporal: time is not valid"))
+    }
+
+    /// The offset field parsed as `±HH:MM[:SS[.fffffffff]]`.
+    fn offset(&self, ctx: GcPointer<Context>) -> Result<Option<Offset>, JsValue> {
+        match &self.offset {
+            Some(offset) => match rfc9557::parse_offset(offset) {
+                Some(offset) => Ok(Some(Offset::Nanoseconds(offset))),
+                None => Err(range_error(
+                    ctx,
+                    format!("Temporal: {} is not a valid offset", offset),
+                )),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+/// Property bags passed to `with` must not be Temporal objects or have a calendar or time zone.
+fn to_partial_fields(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    kinds: FieldKinds,
+) -> Result<Fields, JsValue> {
+    if unlikely(!value.is_jsobject()) {
+        return Err(type_error(ctx, "Temporal: with requires an object"));
+    }
+    let mut object = value.get_jsobject();
+    let temporal = [
+        duration::Duration::get_class(),
+        instant::Instant::get_class(),
+        plain_date::PlainDate::get_class(),
+        plain_time::PlainTime::get_class(),
+        plain_date_time::PlainDateTime::get_class(),
+        zoned_date_time::ZonedDateTime::get_class(),
+    ];
+    if temporal.iter().any(|&class| object.is_class(class)) {
+        return Err(type_error(
+            ctx,
+            "Temporal: with requires a property bag, not a Temporal object",
+        ));
+    }
+    for &name in &["calendar", "timeZone"] {
+        if unlikely(!object.get(ctx, name.intern())?.is_undefined()) {
+            return Err(type_error(
+                ctx,
+                format!("Temporal: with does not accept {}", name),
+            ));
+        }
+    }
+    // calendar was rejected above, so reading it again in read_fields passes
+    let fields = read_fields(ctx, object, kinds)?;
+    if fields.is_empty() {
+        return Err(type_error(
+            ctx,
+            "Temporal: with requires at least one known property",
+        ));
+    }
+    Ok(fields)
+}
+
+/// Starting point of durations with calendar units given by the `relativeTo` option.
+enum RelativeTo {
+    None,
+    Plain(IsoDate),
+    Zoned(Rc<TimeZone>, i128),
+}
+
+/// GetTemporalRelativeToOption
+fn get_relative_to(
+    ctx: GcPointer<Context>,
+    options: Option<GcPointer<JsObject>>,
+) -> Result<RelativeTo, JsValue> {
+    let value = get_option(ctx, options, "relativeTo")?;
+    if value.is_undefined() {
+        return Ok(RelativeTo::None);
+    }
+    if let Some(zoned) = zoned_date_time::to_zoned_date_time_object(value) {
+        return Ok(RelativeTo::Zoned(
+            zoned.time_zone.clone(),
+            zoned.epoch_nanoseconds.get(),
+        ));
+    }
+    if let Some(date) = plain_date_time::to_date_time_object(value) {
+        return Ok(RelativeTo::Plain(date.date));
+    }
+    if value.is_jsobject() {
+        let stack = ctx.shadowstack();
+        letroot!(object = stack, value.get_jsobject());
+        let fields = read_fields(
+            ctx,
+            *object,
+            FieldKinds {
+                date: true,
+                time: true,
+                offset: true,
+            },
+        )?;
+        let zone = object.get(ctx, "timeZone".intern())?;
+        let date = fields.date(ctx, Overflow::Constrain)?;
+        if zone.is_undefined() {
+            return Ok(RelativeTo::Plain(date));
+        }
+        let zone = to_time_zone(ctx, zone)?;
+        let time = fields.time(ctx, Overflow::Constrain, IsoTime::default())?;
+        let offset = fields.offset(ctx)?;
+        let instant = interpret_offset(
+            ctx,
+            &zone,
+            IsoDateTime { date, time },
+            offset,
+            Disambiguation::Compatible,
+            OffsetOption::Reject,
+            false,
+        )?;
+        return Ok(RelativeTo::Zoned(zone, instant));
+    }
+    if unlikely(!value.is_jsstring()) {
+        return Err(type_error(
+            ctx,
+            "Temporal: relativeTo must be a Temporal object, property bag or string",
+        ));
+    }
+    let text = value.to_string(ctx)?;
+    let parsed = rfc9557::parse_date_time(&text)
+        .ok_or_else(|| range_error(ctx, format!("Temporal: invalid relativeTo {}", text)))?;
+    check_parsed_calendar(ctx, &parsed)?;
+    let date = parsed.date.unwrap();
+    match &parsed.time_zone {
+        None if parsed.offset == Some(Offset::Utc) => Err(range_error(
+            ctx,
+            "Temporal: relativeTo with Z requires a time zone annotation",
+        )),
+        None => Ok(RelativeTo::Plain(date)),
+        Some(name) => {
+            let zone = to_time_zone(ctx, string_value(ctx, name))?;
+            let instant = match parsed.time {
+                None => iso::start_of_day(&zone, date)
+                    .ok_or_else(|| range_error(ctx, "Temporal: date is out of range"))?,
+                Some(time) => {
+                    let exact = matches!(parsed.offset, Some(Offset::Nanoseconds(offset)) if offset % NS_PER_MINUTE != 0);
+                    interpret_offset(
+                        ctx,
+                        &zone,
+                        IsoDateTime { date, time },
+                        parsed.offset,
+                        Disambiguation::Compatible,
+                        OffsetOption::Reject,
+                        !exact,
+                    )?
+                }
+            };
+            Ok(RelativeTo::Zoned(zone, instant))
+        }
+    }
+}
+
+/// Length in nanoseconds of the step `round` rounds to. Instants may round to any increment that
+/// divides a day (`whole_day`), wall-clock values to increments below the next larger unit.
+fn round_step(
+    ctx: GcPointer<Context>,
+    settings: &RoundSettings,
+    whole_day: bool,
+) -> Result<i128, JsValue> {
+    let length = settings.smallest.nanoseconds().unwrap_or(NS_PER_DAY);
+    if settings.smallest == Unit::Day {
+        validate_increment(ctx, settings.increment, 1, true)?;
+    } else if whole_day {
+        validate_increment(ctx, settings.increment, NS_PER_DAY / length, true)?;
+    } else {
+        let maximum = settings.smallest.maximum_increment().unwrap();
+        validate_increment(ctx, settings.increment, maximum, false)?;
+    }
+    Ok(length * settings.increment)
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_temporal_in_global_object(mut self) -> Result<(), JsValue> {
+        let mut temporal = JsObject::new_empty(self);
+        let constructor = |ctx: GcPointer<Context>, proto: Option<GcPointer<JsObject>>| {
+            proto.unwrap().get(ctx, S_CONSTURCTOR.intern())
+        };
+        let ctor = constructor(self, self.global_data.temporal_duration_prototype)?;
+        def_native_property!(self, temporal, Duration, ctor, W | C)?;
+        let ctor = constructor(self, self.global_data.temporal_instant_prototype)?;
+        def_native_property!(self, temporal, Instant, ctor, W | C)?;
+        let ctor = constructor(self, self.global_data.temporal_plain_date_prototype)?;
+        def_native_property!(self, temporal, PlainDate, ctor, W | C)?;
+        let ctor = constructor(self, self.global_data.temporal_plain_date_time_prototype)?;
+        def_native_property!(self, temporal, PlainDateTime, ctor, W | C)?;
+        let ctor = constructor(self, self.global_data.temporal_plain_time_prototype)?;
+        def_native_property!(self, temporal, PlainTime, ctor, W | C)?;
+        let ctor = constructor(self, self.global_data.temporal_zoned_date_time_prototype)?;
+        def_native_property!(self, temporal, ZonedDateTime, ctor, W | C)?;
+        let now = self.new_temporal_now_object()?;
+        def_native_property!(self, temporal, Now, now, W | C)?;
+        let tag = JsString::new(self, "Temporal");
+        temporal.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+
+        let mut global_object = self.global_object();
+        def_native_property!(self, global_object, Temporal, temporal, W | C)?;
+        Ok(())
+    }
+
+    pub(crate) fn init_temporal_in_global_data(self) -> Result<(), JsValue> {
+        self.init_temporal_duration_in_global_data()?;
+        self.init_temporal_instant_in_global_data()?;
+        self.init_temporal_plain_date_in_global_data()?;
+        self.init_temporal_plain_date_time_in_global_data()?;
+        self.init_temporal_plain_time_in_global_data()?;
+        self.init_temporal_zoned_date_time_in_global_data()?;
+        Ok(())
+    }
+
+    /// Prototype object and constructor of a Temporal class, `Temporal.{name}`.
+    fn new_temporal_class(
+        self,
+        name: &str,
+        constructor: JsAPI,
+        length: u32,
+    ) -> Result<(GcPointer<JsObject>, GcPointer<JsObject>), JsValue> {
+        let obj_proto = self.global_data().get_object_prototype();
+        let structure = Structure::new_unique_indexed(self, Some(obj_proto), false);
+        let mut proto = JsObject::new(self, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+        let mut ctor = JsNativeFunction::new(self, name.intern(), constructor, length);
+        def_native_property!(self, ctor, prototype, proto, NONE)?;
+        def_native_property!(self, proto, constructor, ctor, W | C)?;
+        let tag = JsString::new(self, format!("Temporal.{}", name));
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+        def_native_method!(self, proto, valueOf, temporal_value_of, 0, W | C)?;
+        Ok((proto, ctor))
+    }
+}
@@ -0,0 +1,509 @@
+//! `Temporal.Duration`, an amount of time in years to nanoseconds that share one sign.
+use std::{
+    intrinsics::unlikely,
+    mem::{size_of, ManuallyDrop},
+};
+
+use super::{
+    get_options, get_relative_to, get_rounding_increment, get_rounding_mode,
+    get_to_string_settings, get_unit,
+    iso::{
+        round_to_increment, DurationRecord, IsoDateTime, Relative, RoundingMode, RoundingOptions,
+        Unit,
+    },
+    range_error,
+    rfc9557::{self, Precision},
+    string_value, type_error, validate_increment, RelativeTo, UnitChoice, UnitGroup,
+};
+use crate::{
+    define_jsclass, js_method_table,
+    prelude::*,
+    vm::{class::JsClass, context::Context, object::TypedJsObject},
+    JsTryFrom,
+};
+
+pub struct Duration {
+    pub(super) record: DurationRecord,
+}
+
+extern "C" fn fsz() -> usize {
+    size_of::<Duration>()
+}
+
+extern "C" fn ser(object: &JsObject, serializer: &mut SnapshotSerializer) {
+    for field in object.data::<Duration>().record.fields.iter() {
+        serializer.write_u64(field.to_bits());
+    }
+}
+
+extern "C" fn deser(object: &mut JsObject, deser: &mut Deserializer) {
+    let mut fields = [0.0; 10];
+    for field in fields.iter_mut() {
+        *field = f64::from_bits(deser.get_u64());
+    }
+    *object.data::<Duration>() = ManuallyDrop::new(Duration {
+        record: DurationRecord { fields },
+    });
+}
+
+define_jsclass!(
+    Duration,
+    Duration,
+    Object,
+    None,
+    None,
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl JsClass for Duration {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+/// Names of the fields in the order of [DurationRecord::fields].
+const FIELD_NAMES: [&str; 10] = [
+    "years",
+    "months",
+    "weeks",
+    "days",
+    "hours",
+    "minutes",
+    "seconds",
+    "milliseconds",
+    "microseconds",
+    "nanoseconds",
+];
+
+pub(super) fn new_duration(ctx: GcPointer<Context>, record: DurationRecord) -> JsValue {
+    let proto = ctx.global_data.temporal_duration_prototype;
+    super::new_object(ctx, proto, Duration { record })
+}
+
+fn this_duration(ctx: GcPointer<Context>, args: &Arguments) -> Result<DurationRecord, JsValue> {
+    let duration = TypedJsObject::<Duration>::try_from(ctx, args.this)?;
+    Ok(duration.record)
+}
+
+fn invalid_duration(ctx: GcPointer<Context>) -> JsValue {
+    range_error(
+        ctx,
+        "Temporal: duration fields must share one sign and be in range",
+    )
+}
+
+/// ToTemporalPartialDurationRecord, the fields of a property bag that are set, read in
+/// alphabetical order.
+fn read_partial_duration(
+    ctx: GcPointer<Context>,
+    object: GcPointer<JsObject>,
+) -> Result<[Option<f64>; 10], JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, object);
+    let mut names = FIELD_NAMES;
+    names.sort_unstable();
+    let mut fields = [None; 10];
+    let mut any = false;
+    for name in names.iter() {
+        let value = object.get(ctx, name.intern())?;
+        if value.is_undefined() {
+            continue;
+        }
+        let index = FIELD_NAMES.iter().position(|field| field == name).unwrap();
+        fields[index] = Some(super::to_integer_if_integral(ctx, value)?);
+        any = true;
+    }
+    if unlikely(!any) {
+        return Err(type_error(
+            ctx,
+            "Temporal: duration property bag must have at least one unit",
+        ));
+    }
+    Ok(fields)
+}
+
+/// ToTemporalDuration: a Duration, a property bag or an ISO 8601 duration string.
+pub(super) fn to_duration(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+) -> Result<DurationRecord, JsValue> {
+    if value.is_jsobject() {
+        let object = value.get_jsobject();
+        if object.is_class(Duration::get_class()) {
+            return Ok(object.data::<Duration>().record);
+        }
+        let partial = read_partial_duration(ctx, object)?;
+        let mut fields = [0.0; 10];
+        for (field, value) in fields.iter_mut().zip(partial.iter()) {
+            *field = value.unwrap_or(0.0);
+        }
+        return DurationRecord::new(fields).ok_or_else(|| invalid_duration(ctx));
+    }
+    if unlikely(!value.is_jsstring()) {
+        return Err(type_error(
+            ctx,
+            "Temporal: expected a Duration, an object or a string",
+        ));
+    }
+    let text = value.to_string(ctx)?;
+    rfc9557::parse_duration(&text)
+        .ok_or_else(|| range_error(ctx, format!("Temporal: invalid duration {}", text)))
+}
+
+pub fn duration_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let proto = super::constructor_prototype(ctx, args, "Duration")?;
+    let mut fields = [0.0; 10];
+    for (index, field) in fields.iter_mut().enumerate() {
+        let value = args.at(index);
+        if !value.is_undefined() {
+            *field = super::to_integer_if_integral(ctx, value)?;
+        }
+    }
+    let record = DurationRecord::new(fields).ok_or_else(|| invalid_duration(ctx))?;
+    Ok(super::new_object(ctx, proto, Duration { record }))
+}
+
+pub fn duration_from(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let record = to_duration(ctx, args.at(0))?;
+    Ok(new_duration(ctx, record))
+}
+
+/// Point `duration` after the start of `relative`, out of range results are a RangeError.
+fn add_to(
+    ctx: GcPointer<Context>,
+    relative: &Relative,
+    duration: &DurationRecord,
+) -> Result<i128, JsValue> {
+    relative
+        .add(duration)
+        .ok_or_else(|| range_error(ctx, "Temporal: date-time is out of range"))
+}
+
+pub fn duration_compare(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let one = to_duration(ctx, args.at(0))?;
+    let two = to_duration(ctx, args.at(1))?;
+    let options = get_options(ctx, args.at(2))?;
+    let relative_to = get_relative_to(ctx, options)?;
+    if one == two {
+        return Ok(JsValue::new(0));
+    }
+    let calendar_units = one.has_calendar_units() || two.has_calendar_units();
+    let days = one.get(Unit::Day) != 0.0 || two.get(Unit::Day) != 0.0;
+    let relative = match &relative_to {
+        RelativeTo::Zoned(zone, start) if calendar_units || days => {
+            Some(Relative::zoned(zone, *start))
+        }
+        RelativeTo::Plain(date) if calendar_units => Some(Relative::plain(IsoDateTime {
+            date: *date,
+            time: Default::default(),
+        })),
+        RelativeTo::None if calendar_units => {
+            return Err(range_error(
+                ctx,
+                "Temporal: comparing years, months or weeks requires relativeTo",
+            ))
+        }
+        _ => None,
+    };
+    let ordering = match relative {
+        Some(relative) => add_to(ctx, &relative, &one)?.cmp(&add_to(ctx, &relative, &two)?),
+        None => one.day_time_nanoseconds().cmp(&two.day_time_nanoseconds()),
+    };
+    Ok(JsValue::new(ordering as i32))
+}
+
+macro_rules! duration_getters {
+    ($($name: ident => $index: expr),* $(,)?) => {
+        $(
+            pub fn $name(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+                let duration = this_duration(ctx, args)?;
+                Ok(JsValue::new(duration.fields[$index]))
+            }
+        )*
+    };
+}
+
+duration_getters! {
+    duration_years => 0,
+    duration_months => 1,
+    duration_weeks => 2,
+    duration_days => 3,
+    duration_hours => 4,
+    duration_minutes => 5,
+    duration_seconds => 6,
+    duration_milliseconds => 7,
+    duration_microseconds => 8,
+    duration_nanoseconds => 9,
+}
+
+pub fn duration_sign(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(this_duration(ctx, args)?.sign()))
+}
+
+pub fn duration_blank(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(this_duration(ctx, args)?.sign() == 0))
+}
+
+pub fn duration_with(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let duration = this_duration(ctx, args)?;
+    let value = args.at(0);
+    if unlikely(!value.is_jsobject()) {
+        return Err(type_error(ctx, "Temporal: with requires an object"));
+    }
+    let partial = read_partial_duration(ctx, value.get_jsobject())?;
+    let mut fields = duration.fields;
+    for (field, value) in fields.iter_mut().zip(partial.iter()) {
+        if let Some(value) = value {
+            *field = *value;
+        }
+    }
+    let record = DurationRecord::new(fields).ok_or_else(|| invalid_duration(ctx))?;
+    Ok(new_duration(ctx, record))
+}
+
+pub fn duration_negated(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let duration = this_duration(ctx, args)?;
+    Ok(new_duration(ctx, duration.negated()))
+}
+
+pub fn duration_abs(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let duration = this_duration(ctx, args)?;
+    Ok(new_duration(ctx, duration.abs()))
+}
+
+/// AddDurations, days are 24 hours and calendar units cannot be added without a date.
+fn add_durations(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    negate: bool,
+) -> Result<JsValue, JsValue> {
+    let duration = this_duration(ctx, args)?;
+    let other = to_duration(ctx, args.at(0))?;
+    let other = if negate { other.negated() } else { other };
+    if unlikely(duration.has_calendar_units() || other.has_calendar_units()) {
+        return Err(range_error(
+            ctx,
+            "Temporal: durations with years, months or weeks cannot be added, use a date",
+        ));
+    }
+    let record = duration.add(&other).ok_or_else(|| invalid_duration(ctx))?;
+    Ok(new_duration(ctx, record))
+}
+
+pub fn duration_add(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    add_durations(ctx, args, false)
+}
+
+pub fn duration_subtract(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    add_durations(ctx, args, true)
+}
+
+/// The options argument of `round` and `total`, a string is the value of the option `name`.
+fn unit_or_options(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    name: &str,
+) -> Result<Option<GcPointer<JsObject>>, JsValue> {
+    if value.is_undefined() {
+        return Err(type_error(
+            ctx,
+            format!("Temporal: {} or an options object is required", name),
+        ));
+    }
+    if value.is_jsstring() {
+        let mut options = JsObject::new_empty(ctx);
+        options.put(ctx, name.intern(), value, false)?;
+        return Ok(Some(options));
+    }
+    get_options(ctx, value)
+}
+
+/// Starting point of `relative_to`, or `None` when there is none.
+fn relative_start(relative_to: &RelativeTo) -> Option<Relative<'_>> {
+    match relative_to {
+        RelativeTo::None => None,
+        RelativeTo::Plain(date) => Some(Relative::plain(IsoDateTime {
+            date: *date,
+            time: Default::default(),
+        })),
+        RelativeTo::Zoned(zone, start) => Some(Relative::zoned(zone, *start)),
+    }
+}
+
+fn relative_to_required(ctx: GcPointer<Context>) -> JsValue {
+    range_error(ctx, "Temporal: years, months and weeks require relativeTo")
+}
+
+pub fn duration_round(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let duration = this_duration(ctx, args)?;
+    let options = unit_or_options(ctx, args.at(0), "smallestUnit")?;
+    let largest = get_unit(ctx, options, "largestUnit", UnitGroup::DateTime)?;
+    let relative_to = get_relative_to(ctx, options)?;
+    let increment = get_rounding_increment(ctx, options)?;
+    let mode = get_rounding_mode(ctx, options, RoundingMode::HalfExpand)?;
+    let smallest = get_unit(ctx, options, "smallestUnit", UnitGroup::DateTime)?;
+    if unlikely(smallest == UnitChoice::Unset && largest == UnitChoice::Unset) {
+        return Err(range_error(
+            ctx,
+            "Temporal: round requires smallestUnit or largestUnit",
+        ));
+    }
+    let smallest = match smallest {
+        UnitChoice::Unit(unit) => unit,
+        _ => Unit::Nanosecond,
+    };
+    let largest = match largest {
+        UnitChoice::Unit(unit) => unit,
+        _ => duration.default_largest_unit().max(smallest),
+    };
+    if unlikely(largest < smallest) {
+        return Err(range_error(
+            ctx,
+            "Temporal: smallestUnit must not be larger than largestUnit",
+        ));
+    }
+    if let Some(maximum) = smallest.maximum_increment() {
+        validate_increment(ctx, increment, maximum, false)?;
+    }
+    if unlikely(increment > 1 && largest != smallest && smallest.is_date()) {
+        return Err(range_error(
+            ctx,
+            "Temporal: roundingIncrement of calendar units requires largestUnit to be smallestUnit",
+        ));
+    }
+    let options = RoundingOptions {
+        largest,
+        smallest,
+        increment,
+        mode,
+    };
+    let record = match relative_start(&relative_to) {
+        Some(relative) => {
+            let end = add_to(ctx, &relative, &duration)?;
+            relative.round_until(end, &options)
+        }
+        None => {
+            if unlikely(duration.has_calendar_units() || largest > Unit::Day) {
+                return Err(relative_to_required(ctx));
+            }
+            let step = smallest.nanoseconds().unwrap() * increment;
+            let total = round_to_increment(duration.day_time_nanoseconds(), step, mode);
+            Some(DurationRecord::from_nanoseconds(total, largest))
+        }
+    };
+    match record.filter(DurationRecord::is_valid) {
+        Some(record) => Ok(new_duration(ctx, record)),
+        None => Err(invalid_duration(ctx)),
+    }
+}
+
+pub fn duration_total(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let duration = this_duration(ctx, args)?;
+    let options = unit_or_options(ctx, args.at(0), "unit")?;
+    let relative_to = get_relative_to(ctx, options)?;
+    let unit = match get_unit(ctx, options, "unit", UnitGroup::DateTime)? {
+        UnitChoice::Unit(unit) => unit,
+        _ => return Err(range_error(ctx, "Temporal: total requires unit")),
+    };
+    let total = match relative_start(&relative_to) {
+        Some(relative) => {
+            let end = add_to(ctx, &relative, &duration)?;
+            relative.total_until(end, unit)
+        }
+        None => {
+            if unlikely(duration.has_calendar_units() || unit > Unit::Day) {
+                return Err(relative_to_required(ctx));
+            }
+            let total = duration.day_time_nanoseconds();
+            let length = unit.nanoseconds().unwrap();
+            Some((total / length) as f64 + (total % length) as f64 / length as f64)
+        }
+    };
+    match total {
+        Some(total) => Ok(JsValue::new(total)),
+        None => Err(range_error(ctx, "Temporal: date-time is out of range")),
+    }
+}
+
+/// TemporalDurationToString after rounding the seconds to `precision`.
+fn to_string(
+    ctx: GcPointer<Context>,
+    duration: DurationRecord,
+    options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = get_options(ctx, options)?;
+    let settings = get_to_string_settings(ctx, options)?;
+    if unlikely(settings.precision == Precision::Minute) {
+        return Err(range_error(
+            ctx,
+            "Temporal: smallestUnit must be seconds or smaller in toString",
+        ));
+    }
+    let mut duration = duration;
+    if settings.unit != Unit::Nanosecond || settings.increment != 1 {
+        let time = round_to_increment(duration.time_nanoseconds(), settings.step(), settings.mode);
+        let largest = duration
+            .default_largest_unit()
+            .max(Unit::Second)
+            .min(Unit::Hour);
+        let date = duration.date_part();
+        duration = DurationRecord::from_nanoseconds(time, largest).with_date(
+            date.get(Unit::Year),
+            date.get(Unit::Month),
+            date.get(Unit::Week),
+            date.get(Unit::Day),
+        );
+        if unlikely(!duration.is_valid()) {
+            return Err(invalid_duration(ctx));
+        }
+    }
+    Ok(string_value(
+        ctx,
+        rfc9557::format_duration(&duration, settings.precision),
+    ))
+}
+
+pub fn duration_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let duration = this_duration(ctx, args)?;
+    to_string(ctx, duration, args.at(0))
+}
+
+pub fn duration_to_json(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let duration = this_duration(ctx, args)?;
+    to_string(ctx, duration, JsValue::encode_undefined_value())
+}
+
+impl GcPointer<Context> {
+    pub(super) fn init_temporal_duration_in_global_data(mut self) -> Result<(), JsValue> {
+        let (mut proto, mut ctor) = self.new_temporal_class("Duration", duration_constructor, 0)?;
+        def_native_method!(self, ctor, from, duration_from, 1, W | C)?;
+        def_native_method!(self, ctor, compare, duration_compare, 2, W | C)?;
+
+        def_native_accessor!(self, proto, years, duration_years, C)?;
+        def_native_accessor!(self, proto, months, duration_months, C)?;
+        def_native_accessor!(self, proto, weeks, duration_weeks, C)?;
+        def_native_accessor!(self, proto, days, duration_days, C)?;
+        def_native_accessor!(self, proto, hours, duration_hours, C)?;
+        def_native_accessor!(self, proto, minutes, duration_minutes, C)?;
+        def_native_accessor!(self, proto, seconds, duration_seconds, C)?;
+        def_native_accessor!(self, proto, milliseconds, duration_milliseconds, C)?;
+        def_native_accessor!(self, proto, microseconds, duration_microseconds, C)?;
+        def_native_accessor!(self, proto, nanoseconds, duration_nanoseconds, C)?;
+        def_native_accessor!(self, proto, sign, duration_sign, C)?;
+        def_native_accessor!(self, proto, blank, duration_blank, C)?;
+        def_native_method!(self, proto, with, duration_with, 1, W | C)?;
+        def_native_method!(self, proto, negated, duration_negated, 0, W | C)?;
+        def_native_method!(self, proto, abs, duration_abs, 0, W | C)?;
+        def_native_method!(self, proto, add, duration_add, 1, W | C)?;
+        def_native_method!(self, proto, subtract, duration_subtract, 1, W | C)?;
+        def_native_method!(self, proto, round, duration_round, 1, W | C)?;
+        def_native_method!(self, proto, total, duration_total, 1, W | C)?;
+        def_native_method!(self, proto, toString, duration_to_string, 0, W | C)?;
+        def_native_method!(self, proto, toLocaleString, duration_to_json, 0, W | C)?;
+        def_native_method!(self, proto, toJSON, duration_to_json, 0, W | C)?;
+        self.global_data.temporal_duration_prototype = Some(proto);
+        Ok(())
+    }
+}
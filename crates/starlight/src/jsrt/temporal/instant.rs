@@ -0,0 +1,350 @@
+//! `Temporal.Instant`, an exact point in time with nanosecond precision.
+use std::{
+    intrinsics::unlikely,
+    mem::{size_of, ManuallyDrop},
+};
+
+use super::{
+    duration::{new_duration, to_duration},
+    get_difference_settings, get_options, get_round_settings, get_to_string_settings,
+    iso::{
+        is_valid_epoch_nanoseconds, local_date_time, offset_nanoseconds, round_to_increment,
+        DurationRecord, IsoDateTime, Unit, NS_PER_MILLISECOND,
+    },
+    range_error, rfc9557, round_step, string_value, to_time_zone, type_error,
+    zoned_date_time::{new_zoned_date_time, to_zoned_date_time_object},
+    Difference, EpochNanoseconds, UnitGroup,
+};
+use crate::{
+    define_jsclass, js_method_table,
+    prelude::*,
+    vm::{class::JsClass, context::Context, object::TypedJsObject},
+    JsTryFrom,
+};
+
+pub struct Instant {
+    pub(super) epoch_nanoseconds: EpochNanoseconds,
+}
+
+extern "C" fn fsz() -> usize {
+    size_of::<Instant>()
+}
+
+extern "C" fn ser(object: &JsObject, serializer: &mut SnapshotSerializer) {
+    let Instant { epoch_nanoseconds } = &**object.data::<Instant>();
+    serializer.write_u64(epoch_nanoseconds.0[0] as u64);
+    serializer.write_u64(epoch_nanoseconds.0[1] as u64);
+}
+
+extern "C" fn deser(object: &mut JsObject, deser: &mut Deserializer) {
+    let high = deser.get_u64() as i64;
+    let low = deser.get_u64() as i64;
+    *object.data::<Instant>() = ManuallyDrop::new(Instant {
+        epoch_nanoseconds: EpochNanoseconds([high, low]),
+    });
+}
+
+define_jsclass!(
+    Instant,
+    Instant,
+    Object,
+    None,
+    None,
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl JsClass for Instant {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+pub(super) fn new_instant(ctx: GcPointer<Context>, nanoseconds: i128) -> JsValue {
+    let proto = ctx.global_data.temporal_instant_prototype;
+    super::new_object(
+        ctx,
+        proto,
+        Instant {
+            epoch_nanoseconds: EpochNanoseconds::new(nanoseconds),
+        },
+    )
+}
+
+fn this_instant(ctx: GcPointer<Context>, args: &Arguments) -> Result<i128, JsValue> {
+    let instant = TypedJsObject::<Instant>::try_from(ctx, args.this)?;
+    Ok(instant.epoch_nanoseconds.get())
+}
+
+/// ToTemporalInstant: an Instant, a ZonedDateTime or a date-time string with an offset.
+pub(super) fn to_instant(ctx: GcPointer<Context>, value: JsValue) -> Result<i128, JsValue> {
+    if value.is_jsobject() {
+        let object = value.get_jsobject();
+        if object.is_class(Instant::get_class()) {
+            return Ok(object.data::<Instant>().epoch_nanoseconds.get());
+        }
+        if let Some(zoned) = to_zoned_date_time_object(value) {
+            return Ok(zoned.epoch_nanoseconds.get());
+        }
+    } else if unlikely(!value.is_jsstring()) {
+        return Err(type_error(ctx, "Temporal: expected an Instant or a string"));
+    }
+    let text = value.to_string(ctx)?;
+    let parsed = rfc9557::parse_instant(&text)
+        .ok_or_else(|| range_error(ctx, format!("Temporal: invalid instant {}", text)))?;
+    let offset = match parsed.offset {
+        Some(rfc9557::Offset::Nanoseconds(offset)) => offset,
+        _ => 0,
+    };
+    let nanoseconds = parsed.date_time().unwrap().epoch_nanoseconds() - offset;
+    if unlikely(!is_valid_epoch_nanoseconds(nanoseconds)) {
+        return Err(range_error(ctx, "Temporal: instant is out of range"));
+    }
+    Ok(nanoseconds)
+}
+
+pub fn instant_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let proto = super::constructor_prototype(ctx, args, "Instant")?;
+    let nanoseconds = super::to_epoch_nanoseconds(ctx, args.at(0))?;
+    Ok(super::new_object(
+        ctx,
+        proto,
+        Instant {
+            epoch_nanoseconds: EpochNanoseconds::new(nanoseconds),
+        },
+    ))
+}
+
+pub fn instant_from(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let nanoseconds = to_instant(ctx, args.at(0))?;
+    Ok(new_instant(ctx, nanoseconds))
+}
+
+pub fn instant_from_epoch_milliseconds(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let milliseconds = args.at(0).to_number(ctx)?;
+    if unlikely(milliseconds.trunc() != milliseconds) {
+        return Err(range_error(
+            ctx,
+            "Temporal: epoch milliseconds must be an integer",
+        ));
+    }
+    let nanoseconds = milliseconds as i128 * NS_PER_MILLISECOND;
+    if unlikely(!is_valid_epoch_nanoseconds(nanoseconds)) {
+        return Err(range_error(ctx, "Temporal: instant is out of range"));
+    }
+    Ok(new_instant(ctx, nanoseconds))
+}
+
+pub fn instant_from_epoch_nanoseconds(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let nanoseconds = super::to_epoch_nanoseconds(ctx, args.at(0))?;
+    Ok(new_instant(ctx, nanoseconds))
+}
+
+pub fn instant_compare(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let one = to_instant(ctx, args.at(0))?;
+    let two = to_instant(ctx, args.at(1))?;
+    Ok(JsValue::new(one.cmp(&two) as i32))
+}
+
+pub fn instant_epoch_milliseconds(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Ok(super::epoch_milliseconds_value(this_instant(ctx, args)?))
+}
+
+pub fn instant_epoch_nanoseconds(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let nanoseconds = this_instant(ctx, args)?;
+    Ok(super::epoch_nanoseconds_value(ctx, nanoseconds))
+}
+
+/// AddDurationToInstant, only units of fixed length can be added to an exact time.
+fn add_duration(ctx: GcPointer<Context>, args: &Arguments, sign: i128) -> Result<JsValue, JsValue> {
+    let nanoseconds = this_instant(ctx, args)?;
+    let duration = to_duration(ctx, args.at(0))?;
+    if unlikely(duration.date_part().sign() != 0) {
+        return Err(range_error(
+            ctx,
+            "Temporal: years, months, weeks and days cannot be added to an Instant",
+        ));
+    }
+    let result = nanoseconds + duration.time_nanoseconds() * sign;
+    if unlikely(!is_valid_epoch_nanoseconds(result)) {
+        return Err(range_error(ctx, "Temporal: instant is out of range"));
+    }
+    Ok(new_instant(ctx, result))
+}
+
+pub fn instant_add(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    add_duration(ctx, args, 1)
+}
+
+pub fn instant_subtract(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    add_duration(ctx, args, -1)
+}
+
+/// DifferenceTemporalInstant
+fn difference(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    operation: Difference,
+) -> Result<JsValue, JsValue> {
+    let nanoseconds = this_instant(ctx, args)?;
+    let other = to_instant(ctx, args.at(0))?;
+    let settings = get_difference_settings(
+        ctx,
+        operation,
+        args.at(1),
+        UnitGroup::Time,
+        Unit::Nanosecond,
+        Unit::Second,
+    )?;
+    let step = settings.smallest.nanoseconds().unwrap() * settings.increment;
+    let rounded = round_to_increment(other - nanoseconds, step, settings.mode);
+    let duration = DurationRecord::from_nanoseconds(rounded, settings.largest);
+    Ok(new_duration(
+        ctx,
+        match operation {
+            Difference::Until => duration,
+            Difference::Since => duration.negated(),
+        },
+    ))
+}
+
+pub fn instant_until(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    difference(ctx, args, Difference::Until)
+}
+
+pub fn instant_since(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    difference(ctx, args, Difference::Since)
+}
+
+pub fn instant_round(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let nanoseconds = this_instant(ctx, args)?;
+    let settings = get_round_settings(ctx, args.at(0), UnitGroup::Time)?;
+    let step = round_step(ctx, &settings, true)?;
+    let rounded = round_to_increment(nanoseconds, step, settings.mode);
+    Ok(new_instant(ctx, rounded))
+}
+
+pub fn instant_equals(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let nanoseconds = this_instant(ctx, args)?;
+    let other = to_instant(ctx, args.at(0))?;
+    Ok(JsValue::new(nanoseconds == other))
+}
+
+/// TemporalInstantToString, in UTC unless a time zone is given.
+fn to_string(
+    ctx: GcPointer<Context>,
+    nanoseconds: i128,
+    options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = get_options(ctx, options)?;
+    let settings = get_to_string_settings(ctx, options)?;
+    let zone = super::get_option(ctx, options, "timeZone")?;
+    let zone = if zone.is_undefined() {
+        None
+    } else {
+        Some(to_time_zone(ctx, zone)?)
+    };
+    let rounded = round_to_increment(nanoseconds, settings.step(), settings.mode);
+    if unlikely(!is_valid_epoch_nanoseconds(rounded)) {
+        return Err(range_error(ctx, "Temporal: instant is out of range"));
+    }
+    let text = match zone {
+        None => format!(
+            "{}Z",
+            rfc9557::format_date_time(
+                IsoDateTime::from_epoch_nanoseconds(rounded),
+                settings.precision
+            )
+        ),
+        Some(zone) => format!(
+            "{}{}",
+            rfc9557::format_date_time(local_date_time(&zone, rounded), settings.precision),
+            rfc9557::format_offset(offset_nanoseconds(&zone, rounded))
+        ),
+    };
+    Ok(string_value(ctx, text))
+}
+
+pub fn instant_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let nanoseconds = this_instant(ctx, args)?;
+    to_string(ctx, nanoseconds, args.at(0))
+}
+
+pub fn instant_to_json(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let nanoseconds = this_instant(ctx, args)?;
+    to_string(ctx, nanoseconds, JsValue::encode_undefined_value())
+}
+
+pub fn instant_to_zoned_date_time_iso(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let nanoseconds = this_instant(ctx, args)?;
+    let zone = to_time_zone(ctx, args.at(0))?;
+    Ok(new_zoned_date_time(ctx, nanoseconds, zone))
+}
+
+impl GcPointer<Context> {
+    pub(super) fn init_temporal_instant_in_global_data(mut self) -> Result<(), JsValue> {
+        let (mut proto, mut ctor) = self.new_temporal_class("Instant", instant_constructor, 1)?;
+        def_native_method!(self, ctor, from, instant_from, 1, W | C)?;
+        def_native_method!(
+            self,
+            ctor,
+            fromEpochMilliseconds,
+            instant_from_epoch_milliseconds,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            ctor,
+            fromEpochNanoseconds,
+            instant_from_epoch_nanoseconds,
+            1,
+            W | C
+        )?;
+        def_native_method!(self, ctor, compare, instant_compare, 2, W | C)?;
+
+        def_native_accessor!(
+            self,
+            proto,
+            epochMilliseconds,
+            instant_epoch_milliseconds,
+            C
+        )?;
+        def_native_accessor!(self, proto, epochNanoseconds, instant_epoch_nanoseconds, C)?;
+        def_native_method!(self, proto, add, instant_add, 1, W | C)?;
+        def_native_method!(self, proto, subtract, instant_subtract, 1, W | C)?;
+        def_native_method!(self, proto, until, instant_until, 1, W | C)?;
+        def_native_method!(self, proto, since, instant_since, 1, W | C)?;
+        def_native_method!(self, proto, round, instant_round, 1, W | C)?;
+        def_native_method!(self, proto, equals, instant_equals, 1, W | C)?;
+        def_native_method!(self, proto, toString, instant_to_string, 0, W | C)?;
+        def_native_method!(self, proto, toLocaleString, instant_to_json, 0, W | C)?;
+        def_native_method!(self, proto, toJSON, instant_to_json, 0, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toZonedDateTimeISO,
+            instant_to_zoned_date_time_iso,
+            1,
+            W | C
+        )?;
+        self.global_data.temporal_instant_prototype = Some(proto);
+        Ok(())
+    }
+}
@@ -0,0 +1,1273 @@
+//! Abstract operations of Temporal on ISO 8601 dates, times and durations that do not touch JS
+//! values: calendar arithmetic, balancing, rounding and time zone disambiguation.
+
+use std::cmp::Ordering;
+
+use crate::jsrt::date::{civil_from_days, days_from_civil, days_in_month, is_leap_year, TimeZone};
+
+pub const NS_PER_MICROSECOND: i128 = 1_000;
+pub const NS_PER_MILLISECOND: i128 = 1_000_000;
+pub const NS_PER_SECOND: i128 = 1_000_000_000;
+pub const NS_PER_MINUTE: i128 = 60 * NS_PER_SECOND;
+pub const NS_PER_HOUR: i128 = 60 * NS_PER_MINUTE;
+pub const NS_PER_DAY: i128 = 24 * NS_PER_HOUR;
+/// Instants are limited to 10^8 days on either side of the epoch.
+pub const NS_MAX_INSTANT: i128 = 100_000_000 * NS_PER_DAY;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Unit {
+    Nanosecond,
+    Microsecond,
+    Millisecond,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl Unit {
+    pub const ALL: [Unit; 10] = [
+        Unit::Year,
+        Unit::Month,
+        Unit::Week,
+        Unit::Day,
+        Unit::Hour,
+        Unit::Minute,
+        Unit::Second,
+        Unit::Millisecond,
+        Unit::Microsecond,
+        Unit::Nanosecond,
+    ];
+
+    /// Parses singular and plural unit names like `"hour"` and `"hours"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let singular = name.strip_suffix('s').unwrap_or(name);
+        Some(match singular {
+            "year" => Unit::Year,
+            "month" => Unit::Month,
+            "week" => Unit::Week,
+            "day" => Unit::Day,
+            "hour" => Unit::Hour,
+            "minute" => Unit::Minute,
+            "second" => Unit::Second,
+            "millisecond" => Unit::Millisecond,
+            "microsecond" => Unit::Microsecond,
+            "nanosecond" => Unit::Nanosecond,
+            _ => return None,
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Unit::Year => "year",
+            Unit::Month => "month",
+            Unit::Week => "week",
+            Unit::Day => "day",
+            Unit::Hour => "hour",
+            Unit::Minute => "minute",
+            Unit::Second => "second",
+            Unit::Millisecond => "millisecond",
+            Unit::Microsecond => "microsecond",
+            Unit::Nanosecond => "nanosecond",
+        }
+    }
+
+    /// Whether the length of the unit depends on the calendar or the time zone.
+    pub fn is_date(self) -> bool {
+        self >= Unit::Day
+    }
+
+    /// Length of the unit, days are 24 hours. `None` for weeks, months and years.
+    pub fn nanoseconds(self) -> Option<i128> {
+        Some(match self {
+            Unit::Nanosecond => 1,
+            Unit::Microsecond => NS_PER_MICROSECOND,
+            Unit::Millisecond => NS_PER_MILLISECOND,
+            Unit::Second => NS_PER_SECOND,
+            Unit::Minute => NS_PER_MINUTE,
+            Unit::Hour => NS_PER_HOUR,
+            Unit::Day => NS_PER_DAY,
+            _ => return None,
+        })
+    }
+
+    /// Exclusive upper bound of `roundingIncrement` for the unit, `None` when it is unbounded.
+    pub fn maximum_increment(self) -> Option<i128> {
+        match self {
+            Unit::Hour => Some(24),
+            Unit::Minute | Unit::Second => Some(60),
+            Unit::Millisecond | Unit::Microsecond | Unit::Nanosecond => Some(1000),
+            _ => None,
+        }
+    }
+
+    /// Position in a duration record, `Year` is 0.
+    fn index(self) -> usize {
+        Unit::ALL.iter().position(|&unit| unit == self).unwrap()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    Ceil,
+    Floor,
+    Expand,
+    Trunc,
+    HalfCeil,
+    HalfFloor,
+    HalfExpand,
+    HalfTrunc,
+    HalfEven,
+}
+
+impl RoundingMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "ceil" => RoundingMode::Ceil,
+            "floor" => RoundingMode::Floor,
+            "expand" => RoundingMode::Expand,
+            "trunc" => RoundingMode::Trunc,
+            "halfCeil" => RoundingMode::HalfCeil,
+            "halfFloor" => RoundingMode::HalfFloor,
+            "halfExpand" => RoundingMode::HalfExpand,
+            "halfTrunc" => RoundingMode::HalfTrunc,
+            "halfEven" => RoundingMode::HalfEven,
+            _ => return None,
+        })
+    }
+
+    /// Mode to use when the value is rounded with its sign flipped, as `since` does.
+    pub fn negate(self) -> Self {
+        match self {
+            RoundingMode::Ceil => RoundingMode::Floor,
+            RoundingMode::Floor => RoundingMode::Ceil,
+            RoundingMode::HalfCeil => RoundingMode::HalfFloor,
+            RoundingMode::HalfFloor => RoundingMode::HalfCeil,
+            mode => mode,
+        }
+    }
+
+    /// Whether a value between two multiples rounds to the larger one. `ordering` compares the
+    /// distance to the smaller multiple with half of the increment.
+    fn rounds_up(self, negative: bool, ordering: Ordering, odd: bool) -> bool {
+        match self {
+            RoundingMode::Ceil => true,
+            RoundingMode::Floor => false,
+            RoundingMode::Expand => !negative,
+            RoundingMode::Trunc => negative,
+            _ if ordering != Ordering::Equal => ordering == Ordering::Greater,
+            RoundingMode::HalfCeil => true,
+            RoundingMode::HalfFloor => false,
+            RoundingMode::HalfExpand => !negative,
+            RoundingMode::HalfTrunc => negative,
+            RoundingMode::HalfEven => odd,
+        }
+    }
+}
+
+/// RoundNumberToIncrement for exact integers.
+pub fn round_to_increment(value: i128, increment: i128, mode: RoundingMode) -> i128 {
+    let quotient = value.div_euclid(increment);
+    let remainder = value.rem_euclid(increment);
+    if remainder == 0 {
+        return value;
+    }
+    let ordering = (remainder * 2).cmp(&increment);
+    if mode.rounds_up(value < 0, ordering, quotient % 2 != 0) {
+        (quotient + 1) * increment
+    } else {
+        quotient * increment
+    }
+}
+
+/// RoundNumberToIncrement for a fractional count of units.
+fn round_f64_to_increment(value: f64, increment: f64, mode: RoundingMode) -> f64 {
+    let quotient = (value / increment).floor();
+    let fraction = value / increment - quotient;
+    if fraction == 0.0 {
+        return value;
+    }
+    let ordering = fraction.partial_cmp(&0.5).unwrap_or(Ordering::Equal);
+    if mode.rounds_up(value < 0.0, ordering, quotient % 2.0 != 0.0) {
+        (quotient + 1.0) * increment
+    } else {
+        quotient * increment
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    Constrain,
+    Reject,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Disambiguation {
+    Compatible,
+    Earlier,
+    Later,
+    Reject,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IsoDate {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl IsoDate {
+    /// Validated date, `None` when the month or day does not exist.
+    pub fn new(year: i64, month: i64, day: i64) -> Option<Self> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        if year.abs() > 300_000 {
+            return None;
+        }
+        Some(Self {
+            year: year as i32,
+            month: month as u8,
+            day: day as u8,
+        })
+    }
+
+    /// Date with the month and day clamped into range, or `None` with [Overflow::Reject] when
+    /// they are out of range.
+    pub fn regulate(year: i64, month: i64, day: i64, overflow: Overflow) -> Option<Self> {
+        match overflow {
+            Overflow::Reject => Self::new(year, month, day),
+            Overflow::Constrain => {
+                if month < 1 || day < 1 {
+                    return None;
+                }
+                let month = month.min(12);
+                Self::new(year, month, day.min(days_in_month(year, month)))
+            }
+        }
+    }
+
+    pub fn from_epoch_days(days: i64) -> Self {
+        let (year, month, day) = civil_from_days(days);
+        Self {
+            year: year as i32,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+
+    pub fn epoch_days(self) -> i64 {
+        days_from_civil(self.year as i64, self.month as i64, self.day as i64)
+    }
+
+    /// ISODateWithinLimits, the date at noon is within the range of date-times.
+    pub fn within_limits(self) -> bool {
+        IsoDateTime {
+            date: self,
+            time: IsoTime::noon(),
+        }
+        .within_limits()
+    }
+
+    /// Day of the week, 1 is Monday and 7 is Sunday.
+    pub fn day_of_week(self) -> u8 {
+        // 1970-01-01 was a Thursday
+        ((self.epoch_days() + 3).rem_euclid(7) + 1) as u8
+    }
+
+    pub fn day_of_year(self) -> u16 {
+        (self.epoch_days() - days_from_civil(self.year as i64, 1, 1) + 1) as u16
+    }
+
+    /// ISO week number and the year the week belongs to.
+    pub fn week_of_year(self) -> (u8, i32) {
+        // the week with the year's first Thursday is week 1
+        let thursday = self.epoch_days() - self.day_of_week() as i64 + 4;
+        let year = Self::from_epoch_days(thursday).year;
+        let week = (thursday - days_from_civil(year as i64, 1, 1)) / 7 + 1;
+        (week as u8, year)
+    }
+
+    pub fn days_in_month(self) -> u8 {
+        days_in_month(self.year as i64, self.month as i64) as u8
+    }
+
+    pub fn days_in_year(self) -> u16 {
+        if self.in_leap_year() {
+            366
+        } else {
+            365
+        }
+    }
+
+    pub fn in_leap_year(self) -> bool {
+        is_leap_year(self.year as i64)
+    }
+
+    /// AddISODate
+    pub fn add(
+        self,
+        years: i64,
+        months: i64,
+        weeks: i64,
+        days: i64,
+        overflow: Overflow,
+    ) -> Option<Self> {
+        let months = (self.year as i64)
+            .checked_mul(12)?
+            .checked_add(self.month as i64 - 1)?
+            .checked_add(years.checked_mul(12)?)?
+            .checked_add(months)?;
+        let date = Self::regulate(
+            months.div_euclid(12),
+            months.rem_euclid(12) + 1,
+            self.day as i64,
+            overflow,
+        )?;
+        let days = weeks.checked_mul(7)?.checked_add(days)?;
+        if days.abs() > 200_000_000 {
+            return None;
+        }
+        let date = Self::from_epoch_days(date.epoch_days() + days);
+        if date.within_limits() {
+            Some(date)
+        } else {
+            None
+        }
+    }
+
+    /// Whether `self` moved by `years` and `months` with its day left unconstrained is past
+    /// `other` in the direction `sign`.
+    fn surpasses(self, sign: i64, months: i64, other: Self) -> bool {
+        let total = self.year as i64 * 12 + self.month as i64 - 1 + months;
+        let candidate = (
+            total.div_euclid(12),
+            total.rem_euclid(12) + 1,
+            self.day as i64,
+        );
+        let other = (other.year as i64, other.month as i64, other.day as i64);
+        match candidate.cmp(&other) {
+            Ordering::Greater => sign > 0,
+            Ordering::Less => sign < 0,
+            Ordering::Equal => false,
+        }
+    }
+
+    /// DifferenceISODate, the years, months, weeks and days from `self` to `other`.
+    pub fn until(self, other: Self, largest: Unit) -> [i64; 4] {
+        let sign = match other.cmp(&self) {
+            Ordering::Equal => return [0; 4],
+            Ordering::Greater => 1,
+            Ordering::Less => -1,
+        };
+        match largest {
+            Unit::Year | Unit::Month => {
+                let mut months = (other.year as i64 - self.year as i64) * 12 + other.month as i64
+                    - self.month as i64;
+                while months != 0 && self.surpasses(sign, months, other) {
+                    months -= sign;
+                }
+                let intermediate = self
+                    .add(0, months, 0, 0, Overflow::Constrain)
+                    .unwrap_or(other);
+                let days = other.epoch_days() - intermediate.epoch_days();
+                if largest == Unit::Year {
+                    [months / 12, months % 12, 0, days]
+                } else {
+                    [0, months, 0, days]
+                }
+            }
+            Unit::Week => {
+                let days = other.epoch_days() - self.epoch_days();
+                [0, 0, days / 7, days % 7]
+            }
+            _ => [0, 0, 0, other.epoch_days() - self.epoch_days()],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IsoTime {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub millisecond: u16,
+    pub microsecond: u16,
+    pub nanosecond: u16,
+}
+
+impl IsoTime {
+    pub fn noon() -> Self {
+        Self {
+            hour: 12,
+            ..Self::default()
+        }
+    }
+
+    /// Time from `[hour, minute, second, millisecond, microsecond, nanosecond]`, out of range
+    /// fields are clamped with [Overflow::Constrain] and rejected otherwise.
+    pub fn regulate(fields: [f64; 6], overflow: Overflow) -> Option<Self> {
+        const MAXIMUM: [f64; 6] = [23.0, 59.0, 59.0, 999.0, 999.0, 999.0];
+        let mut values = [0u16; 6];
+        for ((value, field), maximum) in values.iter_mut().zip(fields.iter()).zip(MAXIMUM.iter()) {
+            if !field.is_finite() {
+                return None;
+            }
+            let field = match overflow {
+                Overflow::Constrain => field.max(0.0).min(*maximum),
+                Overflow::Reject if (0.0..=*maximum).contains(field) => *field,
+                Overflow::Reject => return None,
+            };
+            *value = field as u16;
+        }
+        Some(Self {
+            hour: values[0] as u8,
+            minute: values[1] as u8,
+            second: values[2] as u8,
+            millisecond: values[3],
+            microsecond: values[4],
+            nanosecond: values[5],
+        })
+    }
+
+    /// Nanoseconds since midnight.
+    pub fn nanoseconds(self) -> i128 {
+        self.hour as i128 * NS_PER_HOUR
+            + self.minute as i128 * NS_PER_MINUTE
+            + self.second as i128 * NS_PER_SECOND
+            + self.millisecond as i128 * NS_PER_MILLISECOND
+            + self.microsecond as i128 * NS_PER_MICROSECOND
+            + self.nanosecond as i128
+    }
+
+    /// BalanceTime, the time `nanoseconds` after midnight and the number of whole days it
+    /// overflows by.
+    pub fn from_nanoseconds(nanoseconds: i128) -> (i64, Self) {
+        let days = nanoseconds.div_euclid(NS_PER_DAY);
+        let rest = nanoseconds.rem_euclid(NS_PER_DAY);
+        let time = Self {
+            hour: (rest / NS_PER_HOUR) as u8,
+            minute: (rest % NS_PER_HOUR / NS_PER_MINUTE) as u8,
+            second: (rest % NS_PER_MINUTE / NS_PER_SECOND) as u8,
+            millisecond: (rest % NS_PER_SECOND / NS_PER_MILLISECOND) as u16,
+            microsecond: (rest % NS_PER_MILLISECOND / NS_PER_MICROSECOND) as u16,
+            nanosecond: (rest % NS_PER_MICROSECOND) as u16,
+        };
+        (days as i64, time)
+    }
+
+    pub fn fields(self) -> [f64; 6] {
+        [
+            self.hour as f64,
+            self.minute as f64,
+            self.second as f64,
+            self.millisecond as f64,
+            self.microsecond as f64,
+            self.nanosecond as f64,
+        ]
+    }
+
+    /// RoundTime, the rounded time and the days it overflows by.
+    pub fn round(self, unit: Unit, increment: i128, mode: RoundingMode) -> (i64, Self) {
+        let length = unit.nanoseconds().unwrap_or(NS_PER_DAY) * increment;
+        Self::from_nanoseconds(round_to_increment(self.nanoseconds(), length, mode))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IsoDateTime {
+    pub date: IsoDate,
+    pub time: IsoTime,
+}
+
+impl IsoDateTime {
+    /// Nanoseconds since the epoch if this date-time were in UTC.
+    pub fn epoch_nanoseconds(self) -> i128 {
+        self.date.epoch_days() as i128 * NS_PER_DAY + self.time.nanoseconds()
+    }
+
+    pub fn from_epoch_nanoseconds(nanoseconds: i128) -> Self {
+        let (days, time) = IsoTime::from_nanoseconds(nanoseconds);
+        Self {
+            date: IsoDate::from_epoch_days(days),
+            time,
+        }
+    }
+
+    /// ISODateTimeWithinLimits
+    pub fn within_limits(self) -> bool {
+        self.epoch_nanoseconds().abs() < NS_MAX_INSTANT + NS_PER_DAY
+    }
+
+    /// Moves the date-time by a whole number of nanoseconds.
+    pub fn add_nanoseconds(self, nanoseconds: i128) -> Self {
+        Self::from_epoch_nanoseconds(self.epoch_nanoseconds() + nanoseconds)
+    }
+
+    /// DifferenceISODateTime
+    pub fn until(self, other: Self, largest: Unit) -> DurationRecord {
+        let sign: i64 = match other.cmp(&self) {
+            Ordering::Equal => return DurationRecord::default(),
+            Ordering::Greater => 1,
+            Ordering::Less => -1,
+        };
+        let mut time = other.time.nanoseconds() - self.time.nanoseconds();
+        let mut end = other.date;
+        if time.signum() as i64 == -sign {
+            end = IsoDate::from_epoch_days(end.epoch_days() - sign);
+            time += sign as i128 * NS_PER_DAY;
+        }
+        if !largest.is_date() {
+            let days = end.epoch_days() - self.date.epoch_days();
+            return DurationRecord::from_nanoseconds(days as i128 * NS_PER_DAY + time, largest);
+        }
+        let [years, months, weeks, days] = self.date.until(end, largest);
+        DurationRecord::from_nanoseconds(time, Unit::Hour).with_date(
+            years as f64,
+            months as f64,
+            weeks as f64,
+            days as f64,
+        )
+    }
+}
+
+pub fn is_valid_epoch_nanoseconds(nanoseconds: i128) -> bool {
+    nanoseconds.abs() <= NS_MAX_INSTANT
+}
+
+/// Offset of `zone` at the instant `nanoseconds`, in nanoseconds.
+pub fn offset_nanoseconds(zone: &TimeZone, nanoseconds: i128) -> i128 {
+    let seconds = nanoseconds.div_euclid(NS_PER_SECOND) as i64;
+    zone.local_time_type(seconds).offset as i128 * NS_PER_SECOND
+}
+
+/// Wall-clock date-time of the instant `nanoseconds` in `zone`.
+pub fn local_date_time(zone: &TimeZone, nanoseconds: i128) -> IsoDateTime {
+    IsoDateTime::from_epoch_nanoseconds(nanoseconds + offset_nanoseconds(zone, nanoseconds))
+}
+
+/// GetPossibleEpochNanoseconds, the instants at which the wall clock of `zone` shows `local`:
+/// none in a gap, two in an overlap.
+fn possible_instants(zone: &TimeZone, local: i128) -> ([i128; 2], usize, [i128; 2]) {
+    let before = offset_nanoseconds(zone, local - NS_PER_DAY);
+    let after = offset_nanoseconds(zone, local + NS_PER_DAY);
+    let mut instants = [0; 2];
+    let mut count = 0;
+    for &offset in &[before, after] {
+        let instant = local - offset;
+        if offset_nanoseconds(zone, instant) == offset && (count == 0 || instants[0] != instant) {
+            instants[count] = instant;
+            count += 1;
+        }
+    }
+    if count == 2 && instants[1] < instants[0] {
+        instants.swap(0, 1);
+    }
+    (instants, count, [before, after])
+}
+
+/// GetEpochNanosecondsFor, the instant of the wall-clock date-time `local` in `zone`.
+pub fn instant_for(
+    zone: &TimeZone,
+    local: IsoDateTime,
+    disambiguation: Disambiguation,
+) -> Option<i128> {
+    let local = local.epoch_nanoseconds();
+    let (instants, count, [before, after]) = possible_instants(zone, local);
+    let instant = match (count, disambiguation) {
+        (1, _) => instants[0],
+        (_, Disambiguation::Reject) => return None,
+        (2, Disambiguation::Later) => instants[1],
+        (2, _) => instants[0],
+        // the wall clock skipped `local`, move it by the length of the gap
+        (_, Disambiguation::Earlier) => local - after,
+        (_, _) => local - before,
+    };
+    if is_valid_epoch_nanoseconds(instant) {
+        Some(instant)
+    } else {
+        None
+    }
+}
+
+/// The instant at which the wall clock of `zone` shows `local` with the UTC offset `offset`.
+/// Unless `exact` is set, offsets of the zone that round to `offset` in whole minutes match too.
+pub fn instant_with_offset(
+    zone: &TimeZone,
+    local: IsoDateTime,
+    offset: i128,
+    exact: bool,
+) -> Option<i128> {
+    let local = local.epoch_nanoseconds();
+    let (instants, count, _) = possible_instants(zone, local);
+    instants[..count].iter().copied().find(|&instant| {
+        let candidate = local - instant;
+        candidate == offset
+            || (!exact
+                && round_to_increment(candidate, NS_PER_MINUTE, RoundingMode::HalfExpand) == offset)
+    })
+}
+
+/// Instant at which `date` starts in `zone`, which is after midnight when midnight was skipped.
+pub fn start_of_day(zone: &TimeZone, date: IsoDate) -> Option<i128> {
+    let midnight = IsoDateTime {
+        date,
+        time: IsoTime::default(),
+    };
+    instant_for(zone, midnight, Disambiguation::Compatible)
+}
+
+/// A Temporal.Duration, fields are integers that share one sign.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DurationRecord {
+    pub fields: [f64; 10],
+}
+
+impl DurationRecord {
+    pub fn new(fields: [f64; 10]) -> Option<Self> {
+        let duration = Self { fields };
+        if duration.is_valid() {
+            Some(duration)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, unit: Unit) -> f64 {
+        self.fields[unit.index()]
+    }
+
+    pub fn set(&mut self, unit: Unit, value: f64) {
+        self.fields[unit.index()] = value + 0.0;
+    }
+
+    /// IsValidDuration
+    pub fn is_valid(&self) -> bool {
+        let sign = self.sign();
+        for &field in &self.fields {
+            if !field.is_finite() || field.trunc() != field {
+                return false;
+            }
+            if field != 0.0 && (field.signum() as i32) != sign {
+                return false;
+            }
+        }
+        const MAXIMUM_CALENDAR: f64 = 4_294_967_296.0;
+        if self.fields[..3]
+            .iter()
+            .any(|field| field.abs() >= MAXIMUM_CALENDAR)
+        {
+            return false;
+        }
+        // the time part including days has to fit in 2^53 seconds
+        let seconds = (self.get(Unit::Day) * 86_400.0
+            + self.get(Unit::Hour) * 3_600.0
+            + self.get(Unit::Minute) * 60.0
+            + self.get(Unit::Second))
+        .abs()
+            + (self.get(Unit::Millisecond) / 1e3
+                + self.get(Unit::Microsecond) / 1e6
+                + self.get(Unit::Nanosecond) / 1e9)
+                .abs();
+        if seconds >= 9_007_199_254_740_992.0 {
+            return false;
+        }
+        self.day_time_nanoseconds().abs() < 9_007_199_254_740_992 * NS_PER_SECOND
+    }
+
+    /// DurationSign
+    pub fn sign(&self) -> i32 {
+        for &field in &self.fields {
+            if field > 0.0 {
+                return 1;
+            }
+            if field < 0.0 {
+                return -1;
+            }
+        }
+        0
+    }
+
+    pub fn negated(&self) -> Self {
+        let mut fields = self.fields;
+        for field in fields.iter_mut() {
+            *field = -*field + 0.0;
+        }
+        Self { fields }
+    }
+
+    pub fn abs(&self) -> Self {
+        let mut fields = self.fields;
+        for field in fields.iter_mut() {
+            *field = field.abs();
+        }
+        Self { fields }
+    }
+
+    /// DefaultTemporalLargestUnit, the largest unit with a non-zero field.
+    pub fn default_largest_unit(&self) -> Unit {
+        Unit::ALL
+            .iter()
+            .copied()
+            .find(|&unit| self.get(unit) != 0.0)
+            .unwrap_or(Unit::Nanosecond)
+    }
+
+    pub fn has_calendar_units(&self) -> bool {
+        self.fields[..3].iter().any(|&field| field != 0.0)
+    }
+
+    /// The hours to nanoseconds part as one number of nanoseconds.
+    pub fn time_nanoseconds(&self) -> i128 {
+        self.get(Unit::Hour) as i128 * NS_PER_HOUR
+            + self.get(Unit::Minute) as i128 * NS_PER_MINUTE
+            + self.get(Unit::Second) as i128 * NS_PER_SECOND
+            + self.get(Unit::Millisecond) as i128 * NS_PER_MILLISECOND
+            + self.get(Unit::Microsecond) as i128 * NS_PER_MICROSECOND
+            + self.get(Unit::Nanosecond) as i128
+    }
+
+    /// The days to nanoseconds part with days of 24 hours.
+    pub fn day_time_nanoseconds(&self) -> i128 {
+        self.get(Unit::Day) as i128 * NS_PER_DAY + self.time_nanoseconds()
+    }
+
+    /// BalanceTimeDuration, `nanoseconds` split into units no larger than `largest`.
+    pub fn from_nanoseconds(nanoseconds: i128, largest: Unit) -> Self {
+        let mut duration = Self::default();
+        let sign = nanoseconds.signum();
+        let mut rest = nanoseconds.abs();
+        let largest = largest.min(Unit::Day);
+        for &unit in Unit::ALL.iter().filter(|&&unit| unit <= largest) {
+            let length = unit.nanoseconds().unwrap();
+            duration.set(unit, (sign * (rest / length)) as f64);
+            rest %= length;
+        }
+        duration
+    }
+
+    /// The duration with its years, months, weeks and days replaced.
+    pub fn with_date(mut self, years: f64, months: f64, weeks: f64, days: f64) -> Self {
+        self.set(Unit::Year, years);
+        self.set(Unit::Month, months);
+        self.set(Unit::Week, weeks);
+        self.set(Unit::Day, days);
+        self
+    }
+
+    /// The years, months, weeks and days of the duration.
+    pub fn date_part(&self) -> Self {
+        let mut fields = [0.0; 10];
+        fields[..4].copy_from_slice(&self.fields[..4]);
+        Self { fields }
+    }
+
+    /// The duration with the fields of units smaller than `unit` set to zero.
+    fn truncated(&self, unit: Unit) -> Self {
+        let mut duration = *self;
+        for &smaller in Unit::ALL.iter().filter(|&&smaller| smaller < unit) {
+            duration.set(smaller, 0.0);
+        }
+        duration
+    }
+
+    /// Adds two durations without a starting point, days are 24 hours.
+    pub fn add(&self, other: &Self) -> Option<Self> {
+        if self.has_calendar_units() || other.has_calendar_units() {
+            return None;
+        }
+        let largest = self
+            .default_largest_unit()
+            .max(other.default_largest_unit());
+        let total = self.day_time_nanoseconds() + other.day_time_nanoseconds();
+        Self::new(Self::from_nanoseconds(total, largest).fields)
+    }
+}
+
+pub struct RoundingOptions {
+    pub largest: Unit,
+    pub smallest: Unit,
+    pub increment: i128,
+    pub mode: RoundingMode,
+}
+
+/// Starting point of durations with calendar units, a wall-clock date-time that is either plain or
+/// in a time zone. Points along the way are nanoseconds since the epoch: local ones for plain
+/// starts, exact ones for zoned starts.
+pub struct Relative<'a> {
+    pub start: IsoDateTime,
+    /// Time zone and the exact start of zoned starting points.
+    pub zone: Option<(&'a TimeZone, i128)>,
+}
+
+impl<'a> Relative<'a> {
+    pub fn plain(start: IsoDateTime) -> Self {
+        Self { start, zone: None }
+    }
+
+    /// Starting point of a zoned date-time at the instant `nanoseconds`.
+    pub fn zoned(zone: &'a TimeZone, nanoseconds: i128) -> Self {
+        Self {
+            start: local_date_time(zone, nanoseconds),
+            zone: Some((zone, nanoseconds)),
+        }
+    }
+
+    /// The starting point itself.
+    pub fn origin(&self) -> i128 {
+        match self.zone {
+            None => self.start.epoch_nanoseconds(),
+            Some((_, start)) => start,
+        }
+    }
+
+    /// The point `duration` after the start, calendar units move the wall clock.
+    pub fn add(&self, duration: &DurationRecord) -> Option<i128> {
+        self.add_with_overflow(duration, Overflow::Constrain)
+    }
+
+    /// [Relative::add] with days that do not exist in the resulting month handled by `overflow`.
+    pub fn add_with_overflow(&self, duration: &DurationRecord, overflow: Overflow) -> Option<i128> {
+        let point = match self.zone {
+            Some((_, start)) if duration.date_part().sign() == 0 => start,
+            _ => {
+                let date = self.start.date.add(
+                    duration.get(Unit::Year) as i64,
+                    duration.get(Unit::Month) as i64,
+                    duration.get(Unit::Week) as i64,
+                    duration.get(Unit::Day) as i64,
+                    overflow,
+                )?;
+                let wall_clock = IsoDateTime {
+                    date,
+                    time: self.start.time,
+                };
+                match self.zone {
+                    None => wall_clock.epoch_nanoseconds(),
+                    Some((zone, _)) => instant_for(zone, wall_clock, Disambiguation::Compatible)?,
+                }
+            }
+        } + duration.time_nanoseconds();
+        let valid = match self.zone {
+            None => IsoDateTime::from_epoch_nanoseconds(point).within_limits(),
+            Some(_) => is_valid_epoch_nanoseconds(point),
+        };
+        if valid {
+            Some(point)
+        } else {
+            None
+        }
+    }
+
+    /// Difference from the start to `end` with units up to `largest`.
+    pub fn until(&self, end: i128, largest: Unit) -> DurationRecord {
+        let zone = match self.zone {
+            None => {
+                return self
+                    .start
+                    .until(IsoDateTime::from_epoch_nanoseconds(end), largest)
+            }
+            Some((zone, _)) => zone,
+        };
+        let origin = self.origin();
+        if !largest.is_date() || origin == end {
+            return DurationRecord::from_nanoseconds(end - origin, largest);
+        }
+        // DifferenceZonedDateTime: calendar units on the wall clock, the rest is exact time
+        let sign: i64 = if end > origin { 1 } else { -1 };
+        let end_local = local_date_time(zone, end);
+        let mut correction = if (end_local.time.cmp(&self.start.time) as i64) == -sign {
+            1
+        } else {
+            0
+        };
+        loop {
+            let date = IsoDate::from_epoch_days(end_local.date.epoch_days() - correction * sign);
+            let intermediate = IsoDateTime {
+                date,
+                time: self.start.time,
+            };
+            let point = instant_for(zone, intermediate, Disambiguation::Compatible);
+            let rest = point.map(|point| end - point);
+            match rest {
+                Some(rest) if rest.signum() as i64 != -sign || correction >= 2 => {
+                    let [years, months, weeks, days] = self.start.date.until(date, largest);
+                    return DurationRecord::from_nanoseconds(rest, Unit::Hour).with_date(
+                        years as f64,
+                        months as f64,
+                        weeks as f64,
+                        days as f64,
+                    );
+                }
+                _ => correction += 1,
+            }
+            if correction > 2 {
+                return DurationRecord::from_nanoseconds(end - origin, Unit::Hour);
+            }
+        }
+    }
+
+    /// DifferenceZonedDateTimeWithRounding and DifferencePlainDateTimeWithRounding.
+    pub fn round_until(&self, end: i128, options: &RoundingOptions) -> Option<DurationRecord> {
+        let difference = self.until(end, options.largest);
+        if options.smallest == Unit::Nanosecond && options.increment == 1 {
+            return Some(difference);
+        }
+        let sign = difference.sign();
+        if sign == 0 {
+            return Some(difference);
+        }
+        if !options.largest.is_date() {
+            let length = options.smallest.nanoseconds()? * options.increment;
+            let total = round_to_increment(end - self.origin(), length, options.mode);
+            return Some(DurationRecord::from_nanoseconds(total, options.largest));
+        }
+        let (rounded, expanded) = if options.smallest.is_date() {
+            self.nudge_to_calendar_unit(&difference, end, options)?
+        } else {
+            self.nudge_to_day_or_time(&difference, options)?
+        };
+        if !expanded {
+            return Some(rounded);
+        }
+        self.bubble(rounded, options.largest, options.smallest.max(Unit::Day))
+    }
+
+    /// Total of the difference from the start to `end` in `unit`, with the fraction of the unit.
+    pub fn total_until(&self, end: i128, unit: Unit) -> Option<f64> {
+        if !unit.is_date() || (unit == Unit::Day && self.zone.is_none()) {
+            let length = unit.nanoseconds()?;
+            let total = end - self.origin();
+            return Some((total / length) as f64 + (total % length) as f64 / length as f64);
+        }
+        let difference = self.until(end, unit);
+        let sign = if end < self.origin() { -1.0 } else { 1.0 };
+        let value = difference.get(unit);
+        let base = difference.truncated(unit);
+        let mut next = base;
+        next.set(unit, value + sign);
+        let start = self.add(&base)?;
+        let stop = self.add(&next)?;
+        Some(value + (end - start) as f64 / (stop - start) as f64 * sign)
+    }
+
+    /// NudgeToCalendarUnit, rounds `difference` to `options.smallest` by how far `end` is
+    /// between the two candidate results.
+    fn nudge_to_calendar_unit(
+        &self,
+        difference: &DurationRecord,
+        end: i128,
+        options: &RoundingOptions,
+    ) -> Option<(DurationRecord, bool)> {
+        let sign = difference.sign() as f64;
+        let unit = options.smallest;
+        let increment = options.increment as f64;
+        let value = difference.get(unit);
+        let truncated = (value / increment).trunc() * increment;
+        let base = difference.truncated(unit);
+        let mut start = base;
+        start.set(unit, truncated);
+        let mut stop = base;
+        stop.set(unit, truncated + increment * sign);
+        let start_point = self.add(&start)?;
+        let stop_point = self.add(&stop)?;
+        let progress = (end - start_point) as f64 / (stop_point - start_point) as f64;
+        let total = truncated + progress * increment * sign;
+        let mode = if sign < 0.0 {
+            options.mode.negate()
+        } else {
+            options.mode
+        };
+        let rounded =
+            sign * round_f64_to_increment(total.abs(), increment, mode_for_magnitude(mode));
+        let mut result = base;
+        result.set(unit, rounded);
+        Some((result, rounded.abs() > truncated.abs()))
+    }
+
+    /// NudgeToDayOrTime, rounds the time part and carries a whole day into the date part.
+    fn nudge_to_day_or_time(
+        &self,
+        difference: &DurationRecord,
+        options: &RoundingOptions,
+    ) -> Option<(DurationRecord, bool)> {
+        let sign = difference.sign() as i128;
+        let length = options.smallest.nanoseconds()? * options.increment;
+        let date = difference.date_part();
+        let time = difference.time_nanoseconds();
+        let rounded = round_to_increment(time, length, options.mode);
+        let day_start = self.add(&date)?;
+        let mut next_day = date;
+        next_day.set(Unit::Day, date.get(Unit::Day) + sign as f64);
+        let day_end = self.add(&next_day)?;
+        let beyond = rounded - (day_end - day_start);
+        if beyond == 0 || beyond.signum() == sign {
+            let time = round_to_increment(beyond, length, options.mode);
+            let result = DurationRecord::from_nanoseconds(time, Unit::Hour).with_date(
+                next_day.get(Unit::Year),
+                next_day.get(Unit::Month),
+                next_day.get(Unit::Week),
+                next_day.get(Unit::Day),
+            );
+            return Some((result, true));
+        }
+        let result = DurationRecord::from_nanoseconds(rounded, Unit::Hour).with_date(
+            date.get(Unit::Year),
+            date.get(Unit::Month),
+            date.get(Unit::Week),
+            date.get(Unit::Day),
+        );
+        Some((result, false))
+    }
+
+    /// BubbleRelativeDuration, carries a rounded unit that reached the length of the next larger
+    /// unit into it.
+    fn bubble(
+        &self,
+        mut duration: DurationRecord,
+        largest: Unit,
+        smallest: Unit,
+    ) -> Option<DurationRecord> {
+        let sign = duration.sign() as f64;
+        let end = self.add(&duration)?;
+        for &unit in Unit::ALL.iter().rev() {
+            if unit <= smallest || unit > largest {
+                continue;
+            }
+            if unit == Unit::Week && largest != Unit::Week {
+                continue;
+            }
+            let mut candidate = duration.truncated(unit);
+            candidate.set(unit, duration.get(unit) + sign);
+            let point = self.add(&candidate)?;
+            if (end - point) as f64 * sign >= 0.0 {
+                duration = candidate;
+            } else {
+                break;
+            }
+        }
+        Some(duration)
+    }
+}
+
+/// Rounding modes are applied to magnitudes in [Relative::nudge_to_calendar_unit], where the
+/// direction of the value no longer matters.
+fn mode_for_magnitude(mode: RoundingMode) -> RoundingMode {
+    match mode {
+        RoundingMode::Ceil => RoundingMode::Expand,
+        RoundingMode::Floor => RoundingMode::Trunc,
+        RoundingMode::HalfCeil => RoundingMode::HalfExpand,
+        RoundingMode::HalfFloor => RoundingMode::HalfTrunc,
+        mode => mode,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BERLIN: &str = "CET-1CEST,M3.5.0,M10.5.0/3";
+
+    fn date(year: i64, month: i64, day: i64) -> IsoDate {
+        IsoDate::new(year, month, day).unwrap()
+    }
+
+    fn date_time(date: IsoDate, hour: f64, minute: f64) -> IsoDateTime {
+        IsoDateTime {
+            date,
+            time: IsoTime::regulate([hour, minute, 0.0, 0.0, 0.0, 0.0], Overflow::Reject).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_round_to_increment() {
+        use RoundingMode::*;
+        let cases = [
+            (15, [20, 10, 20, 10, 20, 10, 20, 10, 20]),
+            (25, [30, 20, 30, 20, 30, 20, 30, 20, 20]),
+            (-15, [-10, -20, -20, -10, -10, -20, -20, -10, -20]),
+            (14, [20, 10, 20, 10, 10, 10, 10, 10, 10]),
+            (-16, [-10, -20, -20, -10, -20, -20, -20, -20, -20]),
+            (30, [30, 30, 30, 30, 30, 30, 30, 30, 30]),
+        ];
+        let modes = [
+            Ceil, Floor, Expand, Trunc, HalfCeil, HalfFloor, HalfExpand, HalfTrunc, HalfEven,
+        ];
+        for (value, expected) in cases.iter() {
+            for (mode, expected) in modes.iter().zip(expected.iter()) {
+                assert_eq!(
+                    round_to_increment(*value, 10, *mode),
+                    *expected,
+                    "{} {:?}",
+                    value,
+                    mode
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_epoch_days_round_trip() {
+        let cases = [
+            ((1970, 1, 1), 0),
+            ((1969, 12, 31), -1),
+            ((2000, 2, 29), 11_016),
+            ((2026, 10, 18), 20_744),
+            ((1, 1, 1), -719_162),
+            ((275_760, 9, 13), 100_000_000),
+            ((-271_821, 4, 20), -100_000_000),
+        ];
+        for ((year, month, day), days) in cases.iter() {
+            let date = date(*year, *month, *day);
+            assert_eq!(date.epoch_days(), *days, "{:?}", date);
+            assert_eq!(IsoDate::from_epoch_days(*days), date);
+        }
+        for &nanoseconds in &[0, -1, 1, NS_PER_DAY - 1, -NS_PER_DAY, NS_MAX_INSTANT] {
+            let date_time = IsoDateTime::from_epoch_nanoseconds(nanoseconds);
+            assert_eq!(date_time.epoch_nanoseconds(), nanoseconds);
+        }
+        assert_eq!(
+            IsoDateTime::from_epoch_nanoseconds(-1).time.fields(),
+            [23.0, 59.0, 59.0, 999.0, 999.0, 999.0]
+        );
+    }
+
+    #[test]
+    fn test_date_add() {
+        let cases = [
+            (
+                (2026, 1, 31),
+                [0, 1, 0, 0],
+                Overflow::Constrain,
+                Some((2026, 2, 28)),
+            ),
+            ((2026, 1, 31), [0, 1, 0, 0], Overflow::Reject, None),
+            (
+                (2024, 1, 31),
+                [0, 1, 0, 0],
+                Overflow::Constrain,
+                Some((2024, 2, 29)),
+            ),
+            (
+                (2024, 2, 29),
+                [1, 0, 0, 0],
+                Overflow::Constrain,
+                Some((2025, 2, 28)),
+            ),
+            (
+                (2024, 2, 29),
+                [4, 0, 0, 0],
+                Overflow::Reject,
+                Some((2028, 2, 29)),
+            ),
+            (
+                (2026, 3, 1),
+                [0, 0, 0, -1],
+                Overflow::Reject,
+                Some((2026, 2, 28)),
+            ),
+            (
+                (2026, 10, 18),
+                [0, -10, 2, 0],
+                Overflow::Reject,
+                Some((2026, 1, 1)),
+            ),
+            (
+                (2026, 12, 31),
+                [0, 0, 0, 1],
+                Overflow::Reject,
+                Some((2027, 1, 1)),
+            ),
+            ((275_760, 9, 13), [0, 0, 0, 1], Overflow::Reject, None),
+        ];
+        for (start, [years, months, weeks, days], overflow, expected) in cases.iter() {
+            let (year, month, day) = *start;
+            let result = date(year, month, day).add(*years, *months, *weeks, *days, *overflow);
+            let expected = expected.map(|(year, month, day)| date(year, month, day));
+            assert_eq!(result, expected, "{:?} + {:?}", start, [years, months]);
+        }
+    }
+
+    #[test]
+    fn test_date_until() {
+        let cases = [
+            ((2026, 1, 31), (2026, 3, 1), Unit::Year, [0, 1, 0, 1]),
+            ((2026, 3, 1), (2026, 1, 31), Unit::Year, [0, -1, 0, -1]),
+            ((2020, 2, 29), (2024, 2, 28), Unit::Year, [3, 11, 0, 30]),
+            ((2020, 2, 29), (2024, 2, 28), Unit::Month, [0, 47, 0, 30]),
+            ((2026, 10, 1), (2026, 10, 18), Unit::Week, [0, 0, 2, 3]),
+            ((2026, 10, 18), (2026, 10, 1), Unit::Week, [0, 0, -2, -3]),
+            ((2025, 10, 18), (2026, 10, 18), Unit::Day, [0, 0, 0, 365]),
+            ((2026, 10, 18), (2026, 10, 18), Unit::Year, [0, 0, 0, 0]),
+        ];
+        for (start, end, largest, expected) in cases.iter() {
+            let start = date(start.0, start.1, start.2);
+            let end = date(end.0, end.1, end.2);
+            assert_eq!(
+                start.until(end, *largest),
+                *expected,
+                "{:?} until {:?}",
+                start,
+                end
+            );
+        }
+    }
+
+    #[test]
+    fn test_week_of_year() {
+        let cases = [
+            ((2021, 1, 3), 7, (53, 2020)),
+            ((2020, 12, 31), 4, (53, 2020)),
+            ((2024, 12, 30), 1, (1, 2025)),
+            ((2026, 10, 18), 7, (42, 2026)),
+        ];
+        for ((year, month, day), day_of_week, week) in cases.iter() {
+            let date = date(*year, *month, *day);
+            assert_eq!(date.day_of_week(), *day_of_week, "{:?}", date);
+            assert_eq!(date.week_of_year(), *week, "{:?}", date);
+        }
+    }
+
+    #[test]
+    fn test_instant_for_disambiguation() {
+        use Disambiguation::*;
+        let zone = TimeZone::load(Some(BERLIN));
+        // 02:30 was skipped on 2021-03-28 and repeated on 2021-10-31
+        let gap = date_time(date(2021, 3, 28), 2.0, 30.0);
+        let overlap = date_time(date(2021, 10, 31), 2.0, 30.0);
+        let summer = date_time(date(2021, 7, 15), 12.0, 0.0);
+        let cases = [
+            (gap, Compatible, Some(1_616_895_000)),
+            (gap, Earlier, Some(1_616_891_400)),
+            (gap, Later, Some(1_616_895_000)),
+            (gap, Reject, None),
+            (overlap, Compatible, Some(1_635_640_200)),
+            (overlap, Earlier, Some(1_635_640_200)),
+            (overlap, Later, Some(1_635_643_800)),
+            (overlap, Reject, None),
+            (summer, Reject, Some(1_626_343_200)),
+        ];
+        for (local, disambiguation, expected) in cases.iter() {
+            assert_eq!(
+                instant_for(&zone, *local, *disambiguation),
+                expected.map(|seconds: i128| seconds * NS_PER_SECOND),
+                "{:?} {:?}",
+                local,
+                disambiguation
+            );
+        }
+        let hour = NS_PER_HOUR;
+        assert_eq!(
+            instant_with_offset(&zone, overlap, 2 * hour, true),
+            Some(1_635_640_200 * NS_PER_SECOND)
+        );
+        assert_eq!(
+            instant_with_offset(&zone, overlap, hour, true),
+            Some(1_635_643_800 * NS_PER_SECOND)
+        );
+        assert_eq!(instant_with_offset(&zone, gap, hour, true), None);
+    }
+}
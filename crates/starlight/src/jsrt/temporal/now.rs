@@ -0,0 +1,89 @@
+//! `Temporal.Now`, the current time from the system clock.
+use std::rc::Rc;
+
+use super::{
+    instant::new_instant, iso::local_date_time, local_time_zone, plain_date::new_plain_date,
+    plain_date_time::new_plain_date_time, plain_time::new_plain_time, string_value,
+    system_nanoseconds, to_time_zone, zoned_date_time::new_zoned_date_time,
+};
+use crate::{jsrt::date::TimeZone, prelude::*, vm::context::Context};
+
+/// The time zone argument of the `Now` functions, the local time zone when it is left out.
+fn time_zone_or_local(ctx: GcPointer<Context>, value: JsValue) -> Result<Rc<TimeZone>, JsValue> {
+    if value.is_undefined() {
+        Ok(local_time_zone(ctx))
+    } else {
+        to_time_zone(ctx, value)
+    }
+}
+
+pub fn now_instant(ctx: GcPointer<Context>, _args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(new_instant(ctx, system_nanoseconds()))
+}
+
+pub fn now_time_zone_id(ctx: GcPointer<Context>, _args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(string_value(ctx, local_time_zone(ctx).name()))
+}
+
+pub fn now_zoned_date_time_iso(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zone = time_zone_or_local(ctx, args.at(0))?;
+    Ok(new_zoned_date_time(ctx, system_nanoseconds(), zone))
+}
+
+pub fn now_plain_date_time_iso(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zone = time_zone_or_local(ctx, args.at(0))?;
+    let local = local_date_time(&zone, system_nanoseconds());
+    Ok(new_plain_date_time(ctx, local))
+}
+
+pub fn now_plain_date_iso(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let zone = time_zone_or_local(ctx, args.at(0))?;
+    let local = local_date_time(&zone, system_nanoseconds());
+    Ok(new_plain_date(ctx, local.date))
+}
+
+pub fn now_plain_time_iso(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let zone = time_zone_or_local(ctx, args.at(0))?;
+    let local = local_date_time(&zone, system_nanoseconds());
+    Ok(new_plain_time(ctx, local.time))
+}
+
+impl GcPointer<Context> {
+    pub(super) fn new_temporal_now_object(self) -> Result<GcPointer<JsObject>, JsValue> {
+        let mut now = JsObject::new_empty(self);
+        def_native_method!(self, now, instant, now_instant, 0, W | C)?;
+        def_native_method!(self, now, timeZoneId, now_time_zone_id, 0, W | C)?;
+        def_native_method!(
+            self,
+            now,
+            zonedDateTimeISO,
+            now_zoned_date_time_iso,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            now,
+            plainDateTimeISO,
+            now_plain_date_time_iso,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, now, plainDateISO, now_plain_date_iso, 0, W | C)?;
+        def_native_method!(self, now, plainTimeISO, now_plain_time_iso, 0, W | C)?;
+        let tag = JsString::new(self, "Temporal.Now");
+        now.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+        Ok(now)
+    }
+}
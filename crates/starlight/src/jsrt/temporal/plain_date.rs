@@ -0,0 +1,371 @@
+//! `Temporal.PlainDate`, a calendar date without a time or time zone.
+use std::{
+    intrinsics::unlikely,
+    mem::{size_of, ManuallyDrop},
+};
+
+use super::{
+    duration::to_duration,
+    get_calendar_annotation, get_difference_settings, get_options, get_overflow,
+    iso::{
+        instant_for, start_of_day, Disambiguation, IsoDate, IsoDateTime, IsoTime, Unit, NS_PER_DAY,
+    },
+    plain_date_time::{difference_date_time, new_plain_date_time},
+    plain_time::to_plain_time,
+    range_error, read_fields, rfc9557, string_value, to_partial_fields, to_time_zone, type_error,
+    zoned_date_time::{new_zoned_date_time, to_zoned_date_time_object},
+    Difference, FieldKinds, UnitGroup,
+};
+use crate::{
+    define_jsclass, js_method_table,
+    prelude::*,
+    vm::{class::JsClass, context::Context, object::TypedJsObject},
+    JsTryFrom,
+};
+
+pub struct PlainDate {
+    pub(super) date: IsoDate,
+}
+
+extern "C" fn fsz() -> usize {
+    size_of::<PlainDate>()
+}
+
+extern "C" fn ser(object: &JsObject, serializer: &mut SnapshotSerializer) {
+    let PlainDate { date } = **object.data::<PlainDate>();
+    serializer.write_u32(date.year as u32);
+    serializer.write_u8(date.month);
+    serializer.write_u8(date.day);
+}
+
+extern "C" fn deser(object: &mut JsObject, deser: &mut Deserializer) {
+    let date = IsoDate {
+        year: deser.get_u32() as i32,
+        month: deser.get_u8(),
+        day: deser.get_u8(),
+    };
+    *object.data::<PlainDate>() = ManuallyDrop::new(PlainDate { date });
+}
+
+define_jsclass!(
+    PlainDate,
+    PlainDate,
+    Object,
+    None,
+    None,
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl JsClass for PlainDate {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+const DATE_FIELDS: FieldKinds = FieldKinds {
+    date: true,
+    time: false,
+    offset: false,
+};
+
+pub(super) fn new_plain_date(ctx: GcPointer<Context>, date: IsoDate) -> JsValue {
+    let proto = ctx.global_data.temporal_plain_date_prototype;
+    super::new_object(ctx, proto, PlainDate { date })
+}
+
+fn this_date(ctx: GcPointer<Context>, args: &Arguments) -> Result<IsoDate, JsValue> {
+    let date = TypedJsObject::<PlainDate>::try_from(ctx, args.this)?;
+    Ok(date.date)
+}
+
+fn this_date_time(ctx: GcPointer<Context>, args: &Arguments) -> Result<IsoDateTime, JsValue> {
+    Ok(IsoDateTime {
+        date: this_date(ctx, args)?,
+        time: IsoTime::default(),
+    })
+}
+
+/// ToTemporalDate: a Temporal value with a date, a property bag or a date string. `options` is
+/// read after the value, as the spec does.
+pub(super) fn to_plain_date(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    options: JsValue,
+) -> Result<IsoDate, JsValue> {
+    if value.is_jsobject() {
+        if let Some(date_time) = super::plain_date_time::to_date_time_object(value) {
+            get_overflow(ctx, get_options(ctx, options)?)?;
+            return Ok(date_time.date);
+        }
+        if let Some(zoned) = to_zoned_date_time_object(value) {
+            get_overflow(ctx, get_options(ctx, options)?)?;
+            return Ok(zoned.local_date_time().date);
+        }
+        let fields = read_fields(ctx, value.get_jsobject(), DATE_FIELDS)?;
+        let overflow = get_overflow(ctx, get_options(ctx, options)?)?;
+        return fields.date(ctx, overflow);
+    }
+    if unlikely(!value.is_jsstring()) {
+        return Err(type_error(
+            ctx,
+            "Temporal: expected a PlainDate, an object or a string",
+        ));
+    }
+    let text = value.to_string(ctx)?;
+    let parsed = rfc9557::parse_date_time(&text)
+        .filter(|parsed| parsed.offset != Some(rfc9557::Offset::Utc))
+        .ok_or_else(|| range_error(ctx, format!("Temporal: invalid date {}", text)))?;
+    super::check_parsed_calendar(ctx, &parsed)?;
+    let date = parsed.date.unwrap();
+    if unlikely(!date.within_limits()) {
+        return Err(range_error(ctx, "Temporal: date is out of range"));
+    }
+    get_overflow(ctx, get_options(ctx, options)?)?;
+    Ok(date)
+}
+
+pub fn plain_date_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let proto = super::constructor_prototype(ctx, args, "PlainDate")?;
+    let year = super::to_integer_with_truncation(ctx, args.at(0))?;
+    let month = super::to_integer_with_truncation(ctx, args.at(1))?;
+    let day = super::to_integer_with_truncation(ctx, args.at(2))?;
+    super::to_calendar(ctx, args.at(3))?;
+    let date = IsoDate::new(year as i64, month as i64, day as i64)
+        .filter(|date| date.within_limits())
+        .ok_or_else(|| range_error(ctx, "Temporal: date is not valid or out of range"))?;
+    Ok(super::new_object(ctx, proto, PlainDate { date }))
+}
+
+pub fn plain_date_from(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let date = to_plain_date(ctx, args.at(0), args.at(1))?;
+    Ok(new_plain_date(ctx, date))
+}
+
+pub fn plain_date_compare(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let undefined = JsValue::encode_undefined_value();
+    let one = to_plain_date(ctx, args.at(0), undefined)?;
+    let two = to_plain_date(ctx, args.at(1), undefined)?;
+    Ok(JsValue::new(one.cmp(&two) as i32))
+}
+
+date_time_getters! {
+    this_date_time;
+    plain_date_calendar_id => "calendarId",
+    plain_date_year => "year",
+    plain_date_month => "month",
+    plain_date_month_code => "monthCode",
+    plain_date_day => "day",
+    plain_date_day_of_week => "dayOfWeek",
+    plain_date_day_of_year => "dayOfYear",
+    plain_date_week_of_year => "weekOfYear",
+    plain_date_year_of_week => "yearOfWeek",
+    plain_date_days_in_week => "daysInWeek",
+    plain_date_days_in_month => "daysInMonth",
+    plain_date_days_in_year => "daysInYear",
+    plain_date_months_in_year => "monthsInYear",
+    plain_date_in_leap_year => "inLeapYear",
+}
+
+pub fn plain_date_with(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let date = this_date(ctx, args)?;
+    let mut fields = to_partial_fields(ctx, args.at(0), DATE_FIELDS)?;
+    fields.merge_date(date);
+    let overflow = get_overflow(ctx, get_options(ctx, args.at(1))?)?;
+    let date = fields.date(ctx, overflow)?;
+    Ok(new_plain_date(ctx, date))
+}
+
+/// AddDurationToDate, the time part is balanced into whole days.
+fn add_duration(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    negate: bool,
+) -> Result<JsValue, JsValue> {
+    let date = this_date(ctx, args)?;
+    let duration = to_duration(ctx, args.at(0))?;
+    let duration = if negate { duration.negated() } else { duration };
+    let overflow = get_overflow(ctx, get_options(ctx, args.at(1))?)?;
+    let days = duration.get(Unit::Day) as i64 + (duration.time_nanoseconds() / NS_PER_DAY) as i64;
+    let result = date
+        .add(
+            duration.get(Unit::Year) as i64,
+            duration.get(Unit::Month) as i64,
+            duration.get(Unit::Week) as i64,
+            days,
+            overflow,
+        )
+        .ok_or_else(|| range_error(ctx, "Temporal: date is not valid or out of range"))?;
+    Ok(new_plain_date(ctx, result))
+}
+
+pub fn plain_date_add(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    add_duration(ctx, args, false)
+}
+
+pub fn plain_date_subtract(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    add_duration(ctx, args, true)
+}
+
+/// DifferenceTemporalPlainDate
+fn difference(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    operation: Difference,
+) -> Result<JsValue, JsValue> {
+    let date = this_date(ctx, args)?;
+    let other = to_plain_date(ctx, args.at(0), JsValue::encode_undefined_value())?;
+    let settings = get_difference_settings(
+        ctx,
+        operation,
+        args.at(1),
+        UnitGroup::Date,
+        Unit::Day,
+        Unit::Day,
+    )?;
+    let midnight = |date| IsoDateTime {
+        date,
+        time: IsoTime::default(),
+    };
+    difference_date_time(ctx, midnight(date), midnight(other), &settings, operation)
+}
+
+pub fn plain_date_until(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    difference(ctx, args, Difference::Until)
+}
+
+pub fn plain_date_since(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    difference(ctx, args, Difference::Since)
+}
+
+pub fn plain_date_equals(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let date = this_date(ctx, args)?;
+    let other = to_plain_date(ctx, args.at(0), JsValue::encode_undefined_value())?;
+    Ok(JsValue::new(date == other))
+}
+
+pub fn plain_date_to_plain_date_time(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let date = this_date(ctx, args)?;
+    let time = if args.at(0).is_undefined() {
+        IsoTime::default()
+    } else {
+        to_plain_time(ctx, args.at(0), JsValue::encode_undefined_value())?
+    };
+    let date_time = IsoDateTime { date, time };
+    if unlikely(!date_time.within_limits()) {
+        return Err(range_error(ctx, "Temporal: date-time is out of range"));
+    }
+    Ok(new_plain_date_time(ctx, date_time))
+}
+
+/// `toZonedDateTime(timeZone)` or `toZonedDateTime({ timeZone, plainTime })`, the day starts at
+/// midnight or at the first instant after it when the time zone skipped midnight.
+pub fn plain_date_to_zoned_date_time(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let date = this_date(ctx, args)?;
+    let item = args.at(0);
+    let (zone, time) = if item.is_jsobject() && to_zoned_date_time_object(item).is_none() {
+        let mut object = item.get_jsobject();
+        let zone = object.get(ctx, "timeZone".intern())?;
+        if zone.is_undefined() {
+            (to_time_zone(ctx, item)?, JsValue::encode_undefined_value())
+        } else {
+            let zone = to_time_zone(ctx, zone)?;
+            (zone, object.get(ctx, "plainTime".intern())?)
+        }
+    } else {
+        (to_time_zone(ctx, item)?, JsValue::encode_undefined_value())
+    };
+    let instant = if time.is_undefined() {
+        start_of_day(&zone, date)
+    } else {
+        let time = to_plain_time(ctx, time, JsValue::encode_undefined_value())?;
+        instant_for(
+            &zone,
+            IsoDateTime { date, time },
+            Disambiguation::Compatible,
+        )
+    };
+    let instant = instant.ok_or_else(|| range_error(ctx, "Temporal: date-time is out of range"))?;
+    Ok(new_zoned_date_time(ctx, instant, zone))
+}
+
+/// TemporalDateToString
+fn to_string(ctx: GcPointer<Context>, date: IsoDate, options: JsValue) -> Result<JsValue, JsValue> {
+    let options = get_options(ctx, options)?;
+    let calendar = get_calendar_annotation(ctx, options)?;
+    Ok(string_value(
+        ctx,
+        format!("{}{}", rfc9557::format_date(date), calendar),
+    ))
+}
+
+pub fn plain_date_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let date = this_date(ctx, args)?;
+    to_string(ctx, date, args.at(0))
+}
+
+pub fn plain_date_to_json(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let date = this_date(ctx, args)?;
+    to_string(ctx, date, JsValue::encode_undefined_value())
+}
+
+impl GcPointer<Context> {
+    pub(super) fn init_temporal_plain_date_in_global_data(mut self) -> Result<(), JsValue> {
+        let (mut proto, mut ctor) =
+            self.new_temporal_class("PlainDate", plain_date_constructor, 3)?;
+        def_native_method!(self, ctor, from, plain_date_from, 1, W | C)?;
+        def_native_method!(self, ctor, compare, plain_date_compare, 2, W | C)?;
+
+        def_native_accessor!(self, proto, calendarId, plain_date_calendar_id, C)?;
+        def_native_accessor!(self, proto, year, plain_date_year, C)?;
+        def_native_accessor!(self, proto, month, plain_date_month, C)?;
+        def_native_accessor!(self, proto, monthCode, plain_date_month_code, C)?;
+        def_native_accessor!(self, proto, day, plain_date_day, C)?;
+        def_native_accessor!(self, proto, dayOfWeek, plain_date_day_of_week, C)?;
+        def_native_accessor!(self, proto, dayOfYear, plain_date_day_of_year, C)?;
+        def_native_accessor!(self, proto, weekOfYear, plain_date_week_of_year, C)?;
+        def_native_accessor!(self, proto, yearOfWeek, plain_date_year_of_week, C)?;
+        def_native_accessor!(self, proto, daysInWeek, plain_date_days_in_week, C)?;
+        def_native_accessor!(self, proto, daysInMonth, plain_date_days_in_month, C)?;
+        def_native_accessor!(self, proto, daysInYear, plain_date_days_in_year, C)?;
+        def_native_accessor!(self, proto, monthsInYear, plain_date_months_in_year, C)?;
+        def_native_accessor!(self, proto, inLeapYear, plain_date_in_leap_year, C)?;
+        def_native_method!(self, proto, with, plain_date_with, 1, W | C)?;
+        def_native_method!(self, proto, add, plain_date_add, 1, W | C)?;
+        def_native_method!(self, proto, subtract, plain_date_subtract, 1, W | C)?;
+        def_native_method!(self, proto, until, plain_date_until, 1, W | C)?;
+        def_native_method!(self, proto, since, plain_date_since, 1, W | C)?;
+        def_native_method!(self, proto, equals, plain_date_equals, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toPlainDateTime,
+            plain_date_to_plain_date_time,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            toZonedDateTime,
+            plain_date_to_zoned_date_time,
+            1,
+            W | C
+        )?;
+        def_native_method!(self, proto, toString, plain_date_to_string, 0, W | C)?;
+        def_native_method!(self, proto, toLocaleString, plain_date_to_json, 0, W | C)?;
+        def_native_method!(self, proto, toJSON, plain_date_to_json, 0, W | C)?;
+        self.global_data.temporal_plain_date_prototype = Some(proto);
+        Ok(())
+    }
+}
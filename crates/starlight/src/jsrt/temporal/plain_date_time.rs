@@ -0,0 +1,556 @@
+//! `Temporal.PlainDateTime`, a calendar date and a wall-clock time without a time zone.
+use std::{
+    intrinsics::unlikely,
+    mem::{size_of, ManuallyDrop},
+};
+
+use super::{
+    duration::{new_duration, to_duration},
+    get_calendar_annotation, get_difference_settings, get_disambiguation, get_options,
+    get_overflow, get_round_settings, get_to_string_settings,
+    iso::{
+        instant_for, IsoDate, IsoDateTime, IsoTime, Overflow, Relative, RoundingMode,
+        RoundingOptions, Unit,
+    },
+    plain_date::{new_plain_date, PlainDate},
+    plain_time::{new_plain_time, to_plain_time},
+    range_error, read_fields, rfc9557, round_step, string_value, to_partial_fields, to_time_zone,
+    type_error,
+    zoned_date_time::{new_zoned_date_time, to_zoned_date_time_object},
+    Difference, FieldKinds, UnitGroup,
+};
+use crate::{
+    define_jsclass, js_method_table,
+    prelude::*,
+    vm::{class::JsClass, context::Context, object::TypedJsObject},
+    JsTryFrom,
+};
+
+pub struct PlainDateTime {
+    pub(super) date_time: IsoDateTime,
+}
+
+extern "C" fn fsz() -> usize {
+    size_of::<PlainDateTime>()
+}
+
+extern "C" fn ser(object: &JsObject, serializer: &mut SnapshotSerializer) {
+    let IsoDateTime { date, time } = object.data::<PlainDateTime>().date_time;
+    serializer.write_u32(date.year as u32);
+    serializer.write_u8(date.month);
+    serializer.write_u8(date.day);
+    serializer.write_u8(time.hour);
+    serializer.write_u8(time.minute);
+    serializer.write_u8(time.second);
+    serializer.write_u16(time.millisecond);
+    serializer.write_u16(time.microsecond);
+    serializer.write_u16(time.nanosecond);
+}
+
+extern "C" fn deser(object: &mut JsObject, deser: &mut Deserializer) {
+    let date = IsoDate {
+        year: deser.get_u32() as i32,
+        month: deser.get_u8(),
+        day: deser.get_u8(),
+    };
+    let time = IsoTime {
+        hour: deser.get_u8(),
+        minute: deser.get_u8(),
+        second: deser.get_u8(),
+        millisecond: deser.get_u16(),
+        microsecond: deser.get_u16(),
+        nanosecond: deser.get_u16(),
+    };
+    *object.data::<PlainDateTime>() = ManuallyDrop::new(PlainDateTime {
+        date_time: IsoDateTime { date, time },
+    });
+}
+
+define_jsclass!(
+    PlainDateTime,
+    PlainDateTime,
+    Object,
+    None,
+    None,
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl JsClass for PlainDateTime {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+const DATE_TIME_FIELDS: FieldKinds = FieldKinds {
+    date: true,
+    time: true,
+    offset: false,
+};
+
+pub(super) fn new_plain_date_time(ctx: GcPointer<Context>, date_time: IsoDateTime) -> JsValue {
+    let proto = ctx.global_data.temporal_plain_date_time_prototype;
+    super::new_object(ctx, proto, PlainDateTime { date_time })
+}
+
+fn this_date_time(ctx: GcPointer<Context>, args: &Arguments) -> Result<IsoDateTime, JsValue> {
+    let date_time = TypedJsObject::<PlainDateTime>::try_from(ctx, args.this)?;
+    Ok(date_time.date_time)
+}
+
+/// Date-time of a PlainDateTime, or of a PlainDate at midnight.
+pub(super) fn to_date_time_object(value: JsValue) -> Option<IsoDateTime> {
+    if !value.is_jsobject() {
+        return None;
+    }
+    let object = value.get_jsobject();
+    if object.is_class(PlainDateTime::get_class()) {
+        Some(object.data::<PlainDateTime>().date_time)
+    } else if object.is_class(PlainDate::get_class()) {
+        Some(IsoDateTime {
+            date: object.data::<PlainDate>().date,
+            time: IsoTime::default(),
+        })
+    } else {
+        None
+    }
+}
+
+fn out_of_range(ctx: GcPointer<Context>) -> JsValue {
+    range_error(ctx, "Temporal: date-time is out of range")
+}
+
+/// ToTemporalDateTime: a Temporal value with a date, a property bag or a date-time string.
+pub(super) fn to_plain_date_time(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    options: JsValue,
+) -> Result<IsoDateTime, JsValue> {
+    if value.is_jsobject() {
+        let date_time = to_date_time_object(value)
+            .or_else(|| to_zoned_date_time_object(value).map(|zoned| zoned.local_date_time()));
+        if let Some(date_time) = date_time {
+            get_overflow(ctx, get_options(ctx, options)?)?;
+            return Ok(date_time);
+        }
+        let fields = read_fields(ctx, value.get_jsobject(), DATE_TIME_FIELDS)?;
+        let overflow = get_overflow(ctx, get_options(ctx, options)?)?;
+        let date = fields.date(ctx, overflow)?;
+        let time = fields.time(ctx, overflow, IsoTime::default())?;
+        let date_time = IsoDateTime { date, time };
+        if unlikely(!date_time.within_limits()) {
+            return Err(out_of_range(ctx));
+        }
+        return Ok(date_time);
+    }
+    if unlikely(!value.is_jsstring()) {
+        return Err(type_error(
+            ctx,
+            "Temporal: expected a PlainDateTime, an object or a string",
+        ));
+    }
+    let text = value.to_string(ctx)?;
+    let parsed = rfc9557::parse_date_time(&text)
+        .filter(|parsed| parsed.offset != Some(rfc9557::Offset::Utc))
+        .ok_or_else(|| range_error(ctx, format!("Temporal: invalid date-time {}", text)))?;
+    super::check_parsed_calendar(ctx, &parsed)?;
+    let date_time = parsed.date_time().unwrap();
+    if unlikely(!date_time.within_limits()) {
+        return Err(out_of_range(ctx));
+    }
+    get_overflow(ctx, get_options(ctx, options)?)?;
+    Ok(date_time)
+}
+
+/// DifferencePlainDateTimeWithRounding with the result negated for `since`.
+pub(super) fn difference_date_time(
+    ctx: GcPointer<Context>,
+    start: IsoDateTime,
+    end: IsoDateTime,
+    settings: &RoundingOptions,
+    operation: Difference,
+) -> Result<JsValue, JsValue> {
+    let duration = Relative::plain(start)
+        .round_until(end.epoch_nanoseconds(), settings)
+        .filter(|duration| duration.is_valid())
+        .ok_or_else(|| out_of_range(ctx))?;
+    Ok(new_duration(
+        ctx,
+        match operation {
+            Difference::Until => duration,
+            Difference::Since => duration.negated(),
+        },
+    ))
+}
+
+pub fn plain_date_time_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let proto = super::constructor_prototype(ctx, args, "PlainDateTime")?;
+    let mut fields = [0.0; 9];
+    for (index, field) in fields.iter_mut().enumerate() {
+        let value = args.at(index);
+        if index < 3 || !value.is_undefined() {
+            *field = super::to_integer_with_truncation(ctx, value)?;
+        }
+    }
+    super::to_calendar(ctx, args.at(9))?;
+    let date = IsoDate::new(fields[0] as i64, fields[1] as i64, fields[2] as i64);
+    let mut time = [0.0; 6];
+    time.copy_from_slice(&fields[3..]);
+    let time = IsoTime::regulate(time, Overflow::Reject);
+    let date_time = match (date, time) {
+        (Some(date), Some(time)) => IsoDateTime { date, time },
+        _ => return Err(range_error(ctx, "Temporal: date-time is not valid")),
+    };
+    if unlikely(!date_time.within_limits()) {
+        return Err(out_of_range(ctx));
+    }
+    Ok(super::new_object(ctx, proto, PlainDateTime { date_time }))
+}
+
+pub fn plain_date_time_from(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let date_time = to_plain_date_time(ctx, args.at(0), args.at(1))?;
+    Ok(new_plain_date_time(ctx, date_time))
+}
+
+pub fn plain_date_time_compare(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let undefined = JsValue::encode_undefined_value();
+    let one = to_plain_date_time(ctx, args.at(0), undefined)?;
+    let two = to_plain_date_time(ctx, args.at(1), undefined)?;
+    Ok(JsValue::new(one.cmp(&two) as i32))
+}
+
+date_time_getters! {
+    this_date_time;
+    plain_date_time_calendar_id => "calendarId",
+    plain_date_time_year => "year",
+    plain_date_time_month => "month",
+    plain_date_time_month_code => "monthCode",
+    plain_date_time_day => "day",
+    plain_date_time_day_of_week => "dayOfWeek",
+    plain_date_time_day_of_year => "dayOfYear",
+    plain_date_time_week_of_year => "weekOfYear",
+    plain_date_time_year_of_week => "yearOfWeek",
+    plain_date_time_days_in_week => "daysInWeek",
+    plain_date_time_days_in_month => "daysInMonth",
+    plain_date_time_days_in_year => "daysInYear",
+    plain_date_time_months_in_year => "monthsInYear",
+    plain_date_time_in_leap_year => "inLeapYear",
+    plain_date_time_hour => "hour",
+    plain_date_time_minute => "minute",
+    plain_date_time_second => "second",
+    plain_date_time_millisecond => "millisecond",
+    plain_date_time_microsecond => "microsecond",
+    plain_date_time_nanosecond => "nanosecond",
+}
+
+pub fn plain_date_time_with(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let date_time = this_date_time(ctx, args)?;
+    let mut fields = to_partial_fields(ctx, args.at(0), DATE_TIME_FIELDS)?;
+    fields.merge_date(date_time.date);
+    let overflow = get_overflow(ctx, get_options(ctx, args.at(1))?)?;
+    let date = fields.date(ctx, overflow)?;
+    let time = fields.time(ctx, overflow, date_time.time)?;
+    let date_time = IsoDateTime { date, time };
+    if unlikely(!date_time.within_limits()) {
+        return Err(out_of_range(ctx));
+    }
+    Ok(new_plain_date_time(ctx, date_time))
+}
+
+pub fn plain_date_time_with_plain_time(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let date_time = this_date_time(ctx, args)?;
+    let time = if args.at(0).is_undefined() {
+        IsoTime::default()
+    } else {
+        to_plain_time(ctx, args.at(0), JsValue::encode_undefined_value())?
+    };
+    let date_time = IsoDateTime {
+        date: date_time.date,
+        time,
+    };
+    if unlikely(!date_time.within_limits()) {
+        return Err(out_of_range(ctx));
+    }
+    Ok(new_plain_date_time(ctx, date_time))
+}
+
+/// AddDurationToDateTime, calendar units first and then the time.
+fn add_duration(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    negate: bool,
+) -> Result<JsValue, JsValue> {
+    let date_time = this_date_time(ctx, args)?;
+    let duration = to_duration(ctx, args.at(0))?;
+    let duration = if negate { duration.negated() } else { duration };
+    let overflow = get_overflow(ctx, get_options(ctx, args.at(1))?)?;
+    let result = Relative::plain(date_time)
+        .add_with_overflow(&duration, overflow)
+        .ok_or_else(|| out_of_range(ctx))?;
+    Ok(new_plain_date_time(
+        ctx,
+        IsoDateTime::from_epoch_nanoseconds(result),
+    ))
+}
+
+pub fn plain_date_time_add(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    add_duration(ctx, args, false)
+}
+
+pub fn plain_date_time_subtract(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    add_duration(ctx, args, true)
+}
+
+/// DifferenceTemporalPlainDateTime
+fn difference(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    operation: Difference,
+) -> Result<JsValue, JsValue> {
+    let date_time = this_date_time(ctx, args)?;
+    let other = to_plain_date_time(ctx, args.at(0), JsValue::encode_undefined_value())?;
+    let settings = get_difference_settings(
+        ctx,
+        operation,
+        args.at(1),
+        UnitGroup::DateTime,
+        Unit::Nanosecond,
+        Unit::Day,
+    )?;
+    difference_date_time(ctx, date_time, other, &settings, operation)
+}
+
+pub fn plain_date_time_until(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    difference(ctx, args, Difference::Until)
+}
+
+pub fn plain_date_time_since(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    difference(ctx, args, Difference::Since)
+}
+
+/// RoundISODateTime, a time that rounds up to midnight moves to the next day.
+fn round_date_time(
+    ctx: GcPointer<Context>,
+    date_time: IsoDateTime,
+    unit: Unit,
+    increment: i128,
+    mode: RoundingMode,
+) -> Result<IsoDateTime, JsValue> {
+    let (days, time) = date_time.time.round(unit, increment, mode);
+    let date = IsoDate::from_epoch_days(date_time.date.epoch_days() + days);
+    let date_time = IsoDateTime { date, time };
+    if unlikely(!date_time.within_limits()) {
+        return Err(out_of_range(ctx));
+    }
+    Ok(date_time)
+}
+
+pub fn plain_date_time_round(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let date_time = this_date_time(ctx, args)?;
+    let settings = get_round_settings(ctx, args.at(0), UnitGroup::DateTime)?;
+    if unlikely(settings.smallest > Unit::Day) {
+        return Err(range_error(
+            ctx,
+            "Temporal: smallestUnit must be day or smaller",
+        ));
+    }
+    round_step(ctx, &settings, false)?;
+    let rounded = round_date_time(
+        ctx,
+        date_time,
+        settings.smallest,
+        settings.increment,
+        settings.mode,
+    )?;
+    Ok(new_plain_date_time(ctx, rounded))
+}
+
+pub fn plain_date_time_equals(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let date_time = this_date_time(ctx, args)?;
+    let other = to_plain_date_time(ctx, args.at(0), JsValue::encode_undefined_value())?;
+    Ok(JsValue::new(date_time == other))
+}
+
+pub fn plain_date_time_to_plain_date(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let date_time = this_date_time(ctx, args)?;
+    Ok(new_plain_date(ctx, date_time.date))
+}
+
+pub fn plain_date_time_to_plain_time(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let date_time = this_date_time(ctx, args)?;
+    Ok(new_plain_time(ctx, date_time.time))
+}
+
+pub fn plain_date_time_to_zoned_date_time(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let date_time = this_date_time(ctx, args)?;
+    let zone = to_time_zone(ctx, args.at(0))?;
+    let disambiguation = get_disambiguation(ctx, get_options(ctx, args.at(1))?)?;
+    let instant = instant_for(&zone, date_time, disambiguation).ok_or_else(|| {
+        range_error(
+            ctx,
+            format!(
+                "Temporal: {} is ambiguous or does not exist in time zone {}",
+                rfc9557::format_date_time(date_time, rfc9557::Precision::Auto),
+                zone.name()
+            ),
+        )
+    })?;
+    Ok(new_zoned_date_time(ctx, instant, zone))
+}
+
+/// TemporalDateTimeToString after rounding to the requested precision.
+fn to_string(
+    ctx: GcPointer<Context>,
+    date_time: IsoDateTime,
+    options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = get_options(ctx, options)?;
+    let calendar = get_calendar_annotation(ctx, options)?;
+    let settings = get_to_string_settings(ctx, options)?;
+    let rounded = round_date_time(
+        ctx,
+        date_time,
+        settings.unit,
+        settings.increment,
+        settings.mode,
+    )?;
+    Ok(string_value(
+        ctx,
+        format!(
+            "{}{}",
+            rfc9557::format_date_time(rounded, settings.precision),
+            calendar
+        ),
+    ))
+}
+
+pub fn plain_date_time_to_string(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let date_time = this_date_time(ctx, args)?;
+    to_string(ctx, date_time, args.at(0))
+}
+
+pub fn plain_date_time_to_json(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let date_time = this_date_time(ctx, args)?;
+    to_string(ctx, date_time, JsValue::encode_undefined_value())
+}
+
+impl GcPointer<Context> {
+    pub(super) fn init_temporal_plain_date_time_in_global_data(mut self) -> Result<(), JsValue> {
+        let (mut proto, mut ctor) =
+            self.new_temporal_class("PlainDateTime", plain_date_time_constructor, 3)?;
+        def_native_method!(self, ctor, from, plain_date_time_from, 1, W | C)?;
+        def_native_method!(self, ctor, compare, plain_date_time_compare, 2, W | C)?;
+
+        def_native_accessor!(self, proto, calendarId, plain_date_time_calendar_id, C)?;
+        def_native_accessor!(self, proto, year, plain_date_time_year, C)?;
+        def_native_accessor!(self, proto, month, plain_date_time_month, C)?;
+        def_native_accessor!(self, proto, monthCode, plain_date_time_month_code, C)?;
+        def_native_accessor!(self, proto, day, plain_date_time_day, C)?;
+        def_native_accessor!(self, proto, dayOfWeek, plain_date_time_day_of_week, C)?;
+        def_native_accessor!(self, proto, dayOfYear, plain_date_time_day_of_year, C)?;
+        def_native_accessor!(self, proto, weekOfYear, plain_date_time_week_of_year, C)?;
+        def_native_accessor!(self, proto, yearOfWeek, plain_date_time_year_of_week, C)?;
+        def_native_accessor!(self, proto, daysInWeek, plain_date_time_days_in_week, C)?;
+        def_native_accessor!(self, proto, daysInMonth, plain_date_time_days_in_month, C)?;
+        def_native_accessor!(self, proto, daysInYear, plain_date_time_days_in_year, C)?;
+        def_native_accessor!(self, proto, monthsInYear, plain_date_time_months_in_year, C)?;
+        def_native_accessor!(self, proto, inLeapYear, plain_date_time_in_leap_year, C)?;
+        def_native_accessor!(self, proto, hour, plain_date_time_hour, C)?;
+        def_native_accessor!(self, proto, minute, plain_date_time_minute, C)?;
+        def_native_accessor!(self, proto, second, plain_date_time_second, C)?;
+        def_native_accessor!(self, proto, millisecond, plain_date_time_millisecond, C)?;
+        def_native_accessor!(self, proto, microsecond, plain_date_time_microsecond, C)?;
+        def_native_accessor!(self, proto, nanosecond, plain_date_time_nanosecond, C)?;
+        def_native_method!(self, proto, with, plain_date_time_with, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            withPlainTime,
+            plain_date_time_with_plain_time,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, proto, add, plain_date_time_add, 1, W | C)?;
+        def_native_method!(self, proto, subtract, plain_date_time_subtract, 1, W | C)?;
+        def_native_method!(self, proto, until, plain_date_time_until, 1, W | C)?;
+        def_native_method!(self, proto, since, plain_date_time_since, 1, W | C)?;
+        def_native_method!(self, proto, round, plain_date_time_round, 1, W | C)?;
+        def_native_method!(self, proto, equals, plain_date_time_equals, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toPlainDate,
+            plain_date_time_to_plain_date,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            toPlainTime,
+            plain_date_time_to_plain_time,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            toZonedDateTime,
+            plain_date_time_to_zoned_date_time,
+            1,
+            W | C
+        )?;
+        def_native_method!(self, proto, toString, plain_date_time_to_string, 0, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toLocaleString,
+            plain_date_time_to_json,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, proto, toJSON, plain_date_time_to_json, 0, W | C)?;
+        self.global_data.temporal_plain_date_time_prototype = Some(proto);
+        Ok(())
+    }
+}
@@ -0,0 +1,309 @@
+//! `Temporal.PlainTime`, a wall-clock time without a date or time zone.
+use std::{
+    intrinsics::unlikely,
+    mem::{size_of, ManuallyDrop},
+};
+
+use super::{
+    duration::{new_duration, to_duration},
+    get_difference_settings, get_options, get_overflow, get_round_settings, get_to_string_settings,
+    iso::{round_to_increment, DurationRecord, IsoDate, IsoDateTime, IsoTime, Overflow, Unit},
+    plain_date_time::to_date_time_object,
+    range_error, read_fields, rfc9557, round_step, string_value, to_partial_fields, type_error,
+    zoned_date_time::to_zoned_date_time_object,
+    Difference, FieldKinds, UnitGroup,
+};
+use crate::{
+    define_jsclass, js_method_table,
+    prelude::*,
+    vm::{class::JsClass, context::Context, object::TypedJsObject},
+    JsTryFrom,
+};
+
+pub struct PlainTime {
+    pub(super) time: IsoTime,
+}
+
+extern "C" fn fsz() -> usize {
+    size_of::<PlainTime>()
+}
+
+extern "C" fn ser(object: &JsObject, serializer: &mut SnapshotSerializer) {
+    let time = object.data::<PlainTime>().time;
+    serializer.write_u8(time.hour);
+    serializer.write_u8(time.minute);
+    serializer.write_u8(time.second);
+    serializer.write_u16(time.millisecond);
+    serializer.write_u16(time.microsecond);
+    serializer.write_u16(time.nanosecond);
+}
+
+extern "C" fn deser(object: &mut JsObject, deser: &mut Deserializer) {
+    let time = IsoTime {
+        hour: deser.get_u8(),
+        minute: deser.get_u8(),
+        second: deser.get_u8(),
+        millisecond: deser.get_u16(),
+        microsecond: deser.get_u16(),
+        nanosecond: deser.get_u16(),
+    };
+    *object.data::<PlainTime>() = ManuallyDrop::new(PlainTime { time });
+}
+
+define_jsclass!(
+    PlainTime,
+    PlainTime,
+    Object,
+    None,
+    None,
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl JsClass for PlainTime {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+const TIME_FIELDS: FieldKinds = FieldKinds {
+    date: false,
+    time: true,
+    offset: false,
+};
+
+pub(super) fn new_plain_time(ctx: GcPointer<Context>, time: IsoTime) -> JsValue {
+    let proto = ctx.global_data.temporal_plain_time_prototype;
+    super::new_object(ctx, proto, PlainTime { time })
+}
+
+fn this_time(ctx: GcPointer<Context>, args: &Arguments) -> Result<IsoTime, JsValue> {
+    let time = TypedJsObject::<PlainTime>::try_from(ctx, args.this)?;
+    Ok(time.time)
+}
+
+/// The time on the epoch day, for [date_time_getters].
+fn this_date_time(ctx: GcPointer<Context>, args: &Arguments) -> Result<IsoDateTime, JsValue> {
+    Ok(IsoDateTime {
+        date: IsoDate {
+            year: 1970,
+            month: 1,
+            day: 1,
+        },
+        time: this_time(ctx, args)?,
+    })
+}
+
+/// ToTemporalTime: a Temporal value with a time, a property bag or a time string.
+pub(super) fn to_plain_time(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    options: JsValue,
+) -> Result<IsoTime, JsValue> {
+    if value.is_jsobject() {
+        let object = value.get_jsobject();
+        let time = if object.is_class(PlainTime::get_class()) {
+            Some(object.data::<PlainTime>().time)
+        } else if object.is_class(super::plain_date_time::PlainDateTime::get_class()) {
+            to_date_time_object(value).map(|date_time| date_time.time)
+        } else {
+            to_zoned_date_time_object(value).map(|zoned| zoned.local_date_time().time)
+        };
+        if let Some(time) = time {
+            get_overflow(ctx, get_options(ctx, options)?)?;
+            return Ok(time);
+        }
+        let fields = read_fields(ctx, object, TIME_FIELDS)?;
+        if unlikely(fields.is_empty()) {
+            return Err(type_error(
+                ctx,
+                "Temporal: time property bag must have at least one unit",
+            ));
+        }
+        let overflow = get_overflow(ctx, get_options(ctx, options)?)?;
+        return fields.time(ctx, overflow, IsoTime::default());
+    }
+    if unlikely(!value.is_jsstring()) {
+        return Err(type_error(
+            ctx,
+            "Temporal: expected a PlainTime, an object or a string",
+        ));
+    }
+    let text = value.to_string(ctx)?;
+    let parsed = rfc9557::parse_time(&text)
+        .filter(|parsed| parsed.offset != Some(rfc9557::Offset::Utc))
+        .ok_or_else(|| range_error(ctx, format!("Temporal: invalid time {}", text)))?;
+    super::check_parsed_calendar(ctx, &parsed)?;
+    get_overflow(ctx, get_options(ctx, options)?)?;
+    Ok(parsed.time.unwrap())
+}
+
+pub fn plain_time_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let proto = super::constructor_prototype(ctx, args, "PlainTime")?;
+    let mut fields = [0.0; 6];
+    for (index, field) in fields.iter_mut().enumerate() {
+        let value = args.at(index);
+        if !value.is_undefined() {
+            *field = super::to_integer_with_truncation(ctx, value)?;
+        }
+    }
+    let time = IsoTime::regulate(fields, Overflow::Reject)
+        .ok_or_else(|| range_error(ctx, "Temporal: time is not valid"))?;
+    Ok(super::new_object(ctx, proto, PlainTime { time }))
+}
+
+pub fn plain_time_from(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let time = to_plain_time(ctx, args.at(0), args.at(1))?;
+    Ok(new_plain_time(ctx, time))
+}
+
+pub fn plain_time_compare(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let undefined = JsValue::encode_undefined_value();
+    let one = to_plain_time(ctx, args.at(0), undefined)?;
+    let two = to_plain_time(ctx, args.at(1), undefined)?;
+    Ok(JsValue::new(one.cmp(&two) as i32))
+}
+
+date_time_getters! {
+    this_date_time;
+    plain_time_hour => "hour",
+    plain_time_minute => "minute",
+    plain_time_second => "second",
+    plain_time_millisecond => "millisecond",
+    plain_time_microsecond => "microsecond",
+    plain_time_nanosecond => "nanosecond",
+}
+
+pub fn plain_time_with(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let time = this_time(ctx, args)?;
+    let fields = to_partial_fields(ctx, args.at(0), TIME_FIELDS)?;
+    let overflow = get_overflow(ctx, get_options(ctx, args.at(1))?)?;
+    let time = fields.time(ctx, overflow, time)?;
+    Ok(new_plain_time(ctx, time))
+}
+
+/// AddDurationToTime, the clock wraps around midnight.
+fn add_duration(ctx: GcPointer<Context>, args: &Arguments, sign: i128) -> Result<JsValue, JsValue> {
+    let time = this_time(ctx, args)?;
+    let duration = to_duration(ctx, args.at(0))?;
+    let nanoseconds = time.nanoseconds() + duration.time_nanoseconds() * sign;
+    Ok(new_plain_time(
+        ctx,
+        IsoTime::from_nanoseconds(nanoseconds).1,
+    ))
+}
+
+pub fn plain_time_add(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    add_duration(ctx, args, 1)
+}
+
+pub fn plain_time_subtract(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    add_duration(ctx, args, -1)
+}
+
+/// DifferenceTemporalPlainTime
+fn difference(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    operation: Difference,
+) -> Result<JsValue, JsValue> {
+    let time = this_time(ctx, args)?;
+    let other = to_plain_time(ctx, args.at(0), JsValue::encode_undefined_value())?;
+    let settings = get_difference_settings(
+        ctx,
+        operation,
+        args.at(1),
+        UnitGroup::Time,
+        Unit::Nanosecond,
+        Unit::Hour,
+    )?;
+    let step = settings.smallest.nanoseconds().unwrap() * settings.increment;
+    let rounded = round_to_increment(
+        other.nanoseconds() - time.nanoseconds(),
+        step,
+        settings.mode,
+    );
+    let duration = DurationRecord::from_nanoseconds(rounded, settings.largest);
+    Ok(new_duration(
+        ctx,
+        match operation {
+            Difference::Until => duration,
+            Difference::Since => duration.negated(),
+        },
+    ))
+}
+
+pub fn plain_time_until(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    difference(ctx, args, Difference::Until)
+}
+
+pub fn plain_time_since(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    difference(ctx, args, Difference::Since)
+}
+
+pub fn plain_time_round(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let time = this_time(ctx, args)?;
+    let settings = get_round_settings(ctx, args.at(0), UnitGroup::Time)?;
+    round_step(ctx, &settings, false)?;
+    let (_, rounded) = time.round(settings.smallest, settings.increment, settings.mode);
+    Ok(new_plain_time(ctx, rounded))
+}
+
+pub fn plain_time_equals(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let time = this_time(ctx, args)?;
+    let other = to_plain_time(ctx, args.at(0), JsValue::encode_undefined_value())?;
+    Ok(JsValue::new(time == other))
+}
+
+/// TimeRecordToString after rounding to the requested precision.
+fn to_string(ctx: GcPointer<Context>, time: IsoTime, options: JsValue) -> Result<JsValue, JsValue> {
+    let options = get_options(ctx, options)?;
+    let settings = get_to_string_settings(ctx, options)?;
+    let (_, rounded) = time.round(settings.unit, settings.increment, settings.mode);
+    Ok(string_value(
+        ctx,
+        rfc9557::format_time(rounded, settings.precision),
+    ))
+}
+
+pub fn plain_time_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let time = this_time(ctx, args)?;
+    to_string(ctx, time, args.at(0))
+}
+
+pub fn plain_time_to_json(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let time = this_time(ctx, args)?;
+    to_string(ctx, time, JsValue::encode_undefined_value())
+}
+
+impl GcPointer<Context> {
+    pub(super) fn init_temporal_plain_time_in_global_data(mut self) -> Result<(), JsValue> {
+        let (mut proto, mut ctor) =
+            self.new_temporal_class("PlainTime", plain_time_constructor, 0)?;
+        def_native_method!(self, ctor, from, plain_time_from, 1, W | C)?;
+        def_native_method!(self, ctor, compare, plain_time_compare, 2, W | C)?;
+
+        def_native_accessor!(self, proto, hour, plain_time_hour, C)?;
+        def_native_accessor!(self, proto, minute, plain_time_minute, C)?;
+        def_native_accessor!(self, proto, second, plain_time_second, C)?;
+        def_native_accessor!(self, proto, millisecond, plain_time_millisecond, C)?;
+        def_native_accessor!(self, proto, microsecond, plain_time_microsecond, C)?;
+        def_native_accessor!(self, proto, nanosecond, plain_time_nanosecond, C)?;
+        def_native_method!(self, proto, with, plain_time_with, 1, W | C)?;
+        def_native_method!(self, proto, add, plain_time_add, 1, W | C)?;
+        def_native_method!(self, proto, subtract, plain_time_subtract, 1, W | C)?;
+        def_native_method!(self, proto, until, plain_time_until, 1, W | C)?;
+        def_native_method!(self, proto, since, plain_time_since, 1, W | C)?;
+        def_native_method!(self, proto, round, plain_time_round, 1, W | C)?;
+        def_native_method!(self, proto, equals, plain_time_equals, 1, W | C)?;
+        def_native_method!(self, proto, toString, plain_time_to_string, 0, W | C)?;
+        def_native_method!(self, proto, toLocaleString, plain_time_to_json, 0, W | C)?;
+        def_native_method!(self, proto, toJSON, plain_time_to_json, 0, W | C)?;
+        self.global_data.temporal_plain_time_prototype = Some(proto);
+        Ok(())
+    }
+}
@@ -0,0 +1,802 @@
+//! ISO 8601 strings with the RFC 9557 annotations Temporal reads and writes, like
+//! `2026-10-18T10:00:00.5+02:00[Europe/Berlin][u-ca=iso8601]`, and ISO 8601 durations like
+//! `P1Y2M3DT4H5M6.5S`.
+
+use super::iso::{
+    DurationRecord, IsoDate, IsoDateTime, IsoTime, Unit, NS_PER_HOUR, NS_PER_MINUTE, NS_PER_SECOND,
+};
+use crate::jsrt::date::TimeZone;
+
+/// UTC offset written in a date-time string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Offset {
+    /// The `Z` designator, the string names an exact time but not a local offset.
+    Utc,
+    Nanoseconds(i128),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ParsedDateTime {
+    pub date: Option<IsoDate>,
+    pub time: Option<IsoTime>,
+    pub offset: Option<Offset>,
+    /// Contents of the time zone annotation.
+    pub time_zone: Option<String>,
+    /// Value of the first `u-ca` annotation.
+    pub calendar: Option<String>,
+}
+
+impl ParsedDateTime {
+    pub fn date_time(&self) -> Option<IsoDateTime> {
+        Some(IsoDateTime {
+            date: self.date?,
+            time: self.time.unwrap_or_default(),
+        })
+    }
+}
+
+/// A date with an optional time, offset and annotations.
+pub fn parse_date_time(text: &str) -> Option<ParsedDateTime> {
+    let mut cursor = Cursor::new(text);
+    let mut parsed = ParsedDateTime {
+        date: Some(cursor.date()?),
+        ..Default::default()
+    };
+    if matches!(cursor.peek(), Some(b'T') | Some(b't') | Some(b' ')) {
+        cursor.bump();
+        parsed.time = Some(cursor.time()?);
+        parsed.offset = cursor.offset(true);
+    }
+    cursor.annotations(&mut parsed)?;
+    if cursor.is_empty() {
+        Some(parsed)
+    } else {
+        None
+    }
+}
+
+/// A time on its own, optionally prefixed by `T`, or a date-time string with a time.
+pub fn parse_time(text: &str) -> Option<ParsedDateTime> {
+    if let Some(parsed) = parse_date_time(text) {
+        return if parsed.time.is_some() {
+            Some(parsed)
+        } else {
+            None
+        };
+    }
+    let mut cursor = Cursor::new(text);
+    let designator = cursor.eat(b'T') || cursor.eat(b't');
+    let mut parsed = ParsedDateTime {
+        time: Some(cursor.time()?),
+        ..Default::default()
+    };
+    parsed.offset = cursor.offset(true);
+    cursor.annotations(&mut parsed)?;
+    if !cursor.is_empty() {
+        return None;
+    }
+    if !designator && looks_like_date(text) {
+        // `2026-10` or `1018` without `T` are a year-month or a month-day, not a time
+        return None;
+    }
+    Some(parsed)
+}
+
+/// Whether a time without the `T` designator could also be read as a year-month or month-day.
+fn looks_like_date(text: &str) -> bool {
+    let time = text
+        .split(|c| c == '[' || c == 'Z' || c == 'z')
+        .next()
+        .unwrap_or("");
+    let digits = time.bytes().filter(u8::is_ascii_digit).count();
+    let valid_month_day = |month: &str, day: &str| {
+        let month = month.parse::<i64>().unwrap_or(0);
+        let day = day.parse::<i64>().unwrap_or(0);
+        (1..=12).contains(&month) && day >= 1 && day <= crate::jsrt::date::days_in_month(0, month)
+    };
+    match (time.len(), digits) {
+        // HHMM could be MMDD
+        (4, 4) => valid_month_day(&time[..2], &time[2..]),
+        // HH-MM is the offset form, but YYYY-MM and MM-DD are dates
+        (5, 4) if time.as_bytes()[2] == b'-' => valid_month_day(&time[..2], &time[3..]),
+        (7, 6) if time.as_bytes()[4] == b'-' => {
+            let month = time[5..].parse::<i64>().unwrap_or(0);
+            (1..=12).contains(&month)
+        }
+        (6, 6) => {
+            let month = time[4..].parse::<i64>().unwrap_or(0);
+            (1..=12).contains(&month)
+        }
+        _ => false,
+    }
+}
+
+/// A date-time with an offset, which denotes an exact time.
+pub fn parse_instant(text: &str) -> Option<ParsedDateTime> {
+    let parsed = parse_date_time(text)?;
+    if parsed.time.is_some() && parsed.offset.is_some() {
+        Some(parsed)
+    } else {
+        None
+    }
+}
+
+/// UTC offset `±HH[:MM[:SS[.fffffffff]]]` in nanoseconds.
+pub fn parse_offset(text: &str) -> Option<i128> {
+    let mut cursor = Cursor::new(text);
+    match cursor.offset(false)? {
+        Offset::Nanoseconds(offset) if cursor.is_empty() => Some(offset),
+        _ => None,
+    }
+}
+
+/// Time zone identifier: `UTC`, an offset of whole minutes or an IANA name.
+pub fn parse_time_zone(text: &str) -> Option<TimeZone> {
+    if text.eq_ignore_ascii_case("UTC") {
+        return Some(TimeZone::utc());
+    }
+    if let Some(offset) = parse_offset(text) {
+        if offset % NS_PER_MINUTE != 0 || text.len() > 6 {
+            return None;
+        }
+        return Some(TimeZone::fixed(
+            &format_offset(offset),
+            (offset / NS_PER_SECOND) as i32,
+        ));
+    }
+    TimeZone::find(text)
+}
+
+/// ISO 8601 duration `±PnYnMnWnDTnHnMnS`. Only the smallest unit may have a fraction, and only if
+/// it is hours, minutes or seconds.
+pub fn parse_duration(text: &str) -> Option<DurationRecord> {
+    const DATE_UNITS: [(u8, Unit); 4] = [
+        (b'Y', Unit::Year),
+        (b'M', Unit::Month),
+        (b'W', Unit::Week),
+        (b'D', Unit::Day),
+    ];
+    const TIME_UNITS: [(u8, Unit); 3] = [
+        (b'H', Unit::Hour),
+        (b'M', Unit::Minute),
+        (b'S', Unit::Second),
+    ];
+
+    let mut cursor = Cursor::new(text);
+    let negative = match cursor.peek() {
+        Some(b'-') | Some(b'\xe2') => {
+            cursor.minus()?;
+            true
+        }
+        Some(b'+') => {
+            cursor.bump();
+            false
+        }
+        _ => false,
+    };
+    if !(cursor.eat(b'P') || cursor.eat(b'p')) {
+        return None;
+    }
+    let mut duration = DurationRecord::default();
+    let mut components = 0;
+    let mut units: &[(u8, Unit)] = &DATE_UNITS;
+    let mut in_time = false;
+    loop {
+        if cursor.is_empty() {
+            break;
+        }
+        if !in_time && (cursor.eat(b'T') || cursor.eat(b't')) {
+            in_time = true;
+            units = &TIME_UNITS;
+            if cursor.is_empty() {
+                return None;
+            }
+            continue;
+        }
+        let digits = cursor.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() {
+            return None;
+        }
+        let value = digits
+            .iter()
+            .fold(0.0, |value, &digit| value * 10.0 + (digit - b'0') as f64);
+        let fraction = if matches!(cursor.peek(), Some(b'.') | Some(b',')) {
+            if !in_time {
+                return None;
+            }
+            cursor.bump();
+            Some(cursor.fraction()?)
+        } else {
+            None
+        };
+        let designator = cursor.peek()?.to_ascii_uppercase();
+        cursor.bump();
+        let position = units.iter().position(|&(c, _)| c == designator)?;
+        let unit = units[position].1;
+        duration.set(unit, value);
+        units = &units[position + 1..];
+        components += 1;
+        if let Some(fraction) = fraction {
+            // the fraction is spread over the smaller units, nothing may follow it
+            if !cursor.is_empty() {
+                return None;
+            }
+            let nanoseconds = fraction * (unit.nanoseconds()? / NS_PER_SECOND);
+            let rest = DurationRecord::from_nanoseconds(nanoseconds, Unit::Minute);
+            for &smaller in Unit::ALL.iter().filter(|&&smaller| smaller < unit) {
+                duration.set(smaller, rest.get(smaller));
+            }
+        }
+    }
+    if components == 0 || (in_time && units.len() == TIME_UNITS.len()) {
+        return None;
+    }
+    let duration = if negative {
+        duration.negated()
+    } else {
+        duration
+    };
+    if duration.is_valid() {
+        Some(duration)
+    } else {
+        None
+    }
+}
+
+/// Number of digits after the decimal point of seconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precision {
+    /// As many as needed, without trailing zeros.
+    Auto,
+    /// No seconds at all.
+    Minute,
+    Digits(u8),
+}
+
+/// Year with four digits, or with a sign and six digits outside of 0 to 9999.
+pub fn format_year(year: i32) -> String {
+    if (0..=9999).contains(&year) {
+        format!("{:04}", year)
+    } else if year < 0 {
+        format!("-{:06}", -year)
+    } else {
+        format!("+{:06}", year)
+    }
+}
+
+pub fn format_date(date: IsoDate) -> String {
+    format!(
+        "{}-{:02}-{:02}",
+        format_year(date.year),
+        date.month,
+        date.day
+    )
+}
+
+/// Fraction of a second, `nanoseconds` below one second, with a leading `.` unless it is empty.
+fn format_fraction(nanoseconds: i128, precision: Precision) -> String {
+    let digits = format!("{:09}", nanoseconds);
+    match precision {
+        Precision::Auto if nanoseconds == 0 => String::new(),
+        Precision::Auto => format!(".{}", digits.trim_end_matches('0')),
+        Precision::Digits(0) | Precision::Minute => String::new(),
+        Precision::Digits(count) => format!(".{}", &digits[..count as usize]),
+    }
+}
+
+pub fn format_time(time: IsoTime, precision: Precision) -> String {
+    let mut text = format!("{:02}:{:02}", time.hour, time.minute);
+    if precision != Precision::Minute {
+        let fraction = time.nanoseconds() % NS_PER_SECOND;
+        text.push_str(&format!(
+            ":{:02}{}",
+            time.second,
+            format_fraction(fraction, precision)
+        ));
+    }
+    text
+}
+
+pub fn format_date_time(date_time: IsoDateTime, precision: Precision) -> String {
+    format!(
+        "{}T{}",
+        format_date(date_time.date),
+        format_time(date_time.time, precision)
+    )
+}
+
+/// Offset `±HH:MM` rounded to whole minutes, as date-time strings carry it.
+pub fn format_offset(offset: i128) -> String {
+    let minutes = (offset + offset.signum() * NS_PER_MINUTE / 2) / NS_PER_MINUTE;
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.abs();
+    format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+/// Offset `±HH:MM[:SS[.fffffffff]]` without rounding.
+pub fn format_exact_offset(offset: i128) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.abs();
+    let mut text = format!(
+        "{}{:02}:{:02}",
+        sign,
+        offset / NS_PER_HOUR,
+        offset % NS_PER_HOUR / NS_PER_MINUTE
+    );
+    let rest = offset % NS_PER_MINUTE;
+    if rest != 0 {
+        text.push_str(&format!(
+            ":{:02}{}",
+            rest / NS_PER_SECOND,
+            format_fraction(rest % NS_PER_SECOND, Precision::Auto)
+        ));
+    }
+    text
+}
+
+/// TemporalDurationToString, `precision` applies to the seconds.
+pub fn format_duration(duration: &DurationRecord, precision: Precision) -> String {
+    let sign = if duration.sign() < 0 { "-" } else { "" };
+    let duration = duration.abs();
+    let mut date = String::new();
+    for &(unit, designator) in &[
+        (Unit::Year, 'Y'),
+        (Unit::Month, 'M'),
+        (Unit::Week, 'W'),
+        (Unit::Day, 'D'),
+    ] {
+        if duration.get(unit) != 0.0 {
+            date.push_str(&format!("{}{}", duration.get(unit), designator));
+        }
+    }
+    let mut time = String::new();
+    for &(unit, designator) in &[(Unit::Hour, 'H'), (Unit::Minute, 'M')] {
+        if duration.get(unit) != 0.0 {
+            time.push_str(&format!("{}{}", duration.get(unit), designator));
+        }
+    }
+    let seconds = duration.get(Unit::Second) as i128 * NS_PER_SECOND
+        + duration.get(Unit::Millisecond) as i128 * 1_000_000
+        + duration.get(Unit::Microsecond) as i128 * 1_000
+        + duration.get(Unit::Nanosecond) as i128;
+    let zero = date.is_empty() && time.is_empty();
+    if seconds != 0 || zero || precision != Precision::Auto {
+        time.push_str(&format!(
+            "{}{}S",
+            seconds / NS_PER_SECOND,
+            format_fraction(seconds % NS_PER_SECOND, precision)
+        ));
+    }
+    if time.is_empty() {
+        format!("{}P{}", sign, date)
+    } else {
+        format!("{}P{}T{}", sign, date, time)
+    }
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            bytes: text.as_bytes(),
+            position: 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.bytes.get(self.position + offset).copied()
+    }
+
+    fn bump(&mut self) {
+        self.position += 1;
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a [u8] {
+        let start = self.position;
+        while self.peek().map_or(false, &f) {
+            self.bump();
+        }
+        &self.bytes[start..self.position]
+    }
+
+    /// Exactly `count` digits.
+    fn digits(&mut self, count: usize) -> Option<i64> {
+        let mut value = 0;
+        for _ in 0..count {
+            match self.peek()? {
+                digit @ b'0'..=b'9' => value = value * 10 + (digit - b'0') as i64,
+                _ => return None,
+            }
+            self.bump();
+        }
+        Some(value)
+    }
+
+    /// ASCII hyphen-minus or U+2212 MINUS SIGN.
+    fn minus(&mut self) -> Option<()> {
+        if self.eat(b'-') {
+            return Some(());
+        }
+        if self.bytes[self.position..].starts_with("\u{2212}".as_bytes()) {
+            self.position += 3;
+            return Some(());
+        }
+        None
+    }
+
+    /// `+`, `-` or U+2212, `true` when negative.
+    fn sign(&mut self) -> Option<bool> {
+        if self.eat(b'+') {
+            Some(false)
+        } else {
+            self.minus().map(|_| true)
+        }
+    }
+
+    /// 1 to 9 digits after a decimal point, as nanoseconds of a second.
+    fn fraction(&mut self) -> Option<i128> {
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() || digits.len() > 9 {
+            return None;
+        }
+        Some(
+            digits
+                .iter()
+                .chain([b'0'; 9].iter())
+                .take(9)
+                .fold(0, |value, &digit| value * 10 + (digit - b'0') as i128),
+        )
+    }
+
+    /// `YYYY-MM-DD`, `YYYYMMDD` or either with a `±YYYYYY` year.
+    fn date(&mut self) -> Option<IsoDate> {
+        let year = match self.peek()? {
+            b'+' | b'-' | b'\xe2' => {
+                let negative = self.sign()?;
+                let year = self.digits(6)?;
+                // -000000 is not a valid year
+                if negative && year == 0 {
+                    return None;
+                }
+                if negative {
+                    -year
+                } else {
+                    year
+                }
+            }
+            _ => self.digits(4)?,
+        };
+        let extended = self.eat(b'-');
+        let month = self.digits(2)?;
+        if extended && !self.eat(b'-') {
+            return None;
+        }
+        let day = self.digits(2)?;
+        let date = IsoDate::new(year, month, day)?;
+        if date.within_limits() {
+            Some(date)
+        } else {
+            None
+        }
+    }
+
+    /// `HH[:MM[:SS[.fffffffff]]]` or the same without colons. A leap second reads as second 59.
+    fn time(&mut self) -> Option<IsoTime> {
+        let hour = self.digits(2)?;
+        let mut fields = [hour as f64, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let extended = self.peek() == Some(b':');
+        let separator = |cursor: &mut Self| {
+            if extended {
+                cursor.eat(b':')
+            } else {
+                matches!(cursor.peek(), Some(b'0'..=b'9'))
+            }
+        };
+        if separator(self) {
+            fields[1] = self.digits(2)? as f64;
+            if separator(self) {
+                fields[2] = self.digits(2)?.min(59) as f64;
+                if self.eat(b'.') || self.eat(b',') {
+                    let fraction = self.fraction()?;
+                    fields[3] = (fraction / 1_000_000) as f64;
+                    fields[4] = (fraction / 1_000 % 1_000) as f64;
+                    fields[5] = (fraction % 1_000) as f64;
+                }
+            }
+        }
+        if fields[1] > 59.0 {
+            return None;
+        }
+        IsoTime::regulate(fields, super::iso::Overflow::Reject)
+    }
+
+    /// `Z` or a numeric offset, `utc` allows the `Z` designator.
+    fn offset(&mut self, utc: bool) -> Option<Offset> {
+        match self.peek()? {
+            b'Z' | b'z' if utc => {
+                self.bump();
+                Some(Offset::Utc)
+            }
+            b'+' | b'-' | b'\xe2' => {
+                let start = self.position;
+                let offset = self.numeric_offset();
+                if offset.is_none() {
+                    self.position = start;
+                }
+                offset.map(Offset::Nanoseconds)
+            }
+            _ => None,
+        }
+    }
+
+    fn numeric_offset(&mut self) -> Option<i128> {
+        let negative = self.sign()?;
+        let hours = self.digits(2)?;
+        let mut nanoseconds = hours as i128 * NS_PER_HOUR;
+        let extended = self.eat(b':');
+        if extended || matches!(self.peek(), Some(b'0'..=b'9')) {
+            let minutes = self.digits(2)?;
+            nanoseconds += minutes as i128 * NS_PER_MINUTE;
+            if (extended && self.eat(b':'))
+                || (!extended && matches!(self.peek(), Some(b'0'..=b'9')))
+            {
+                let seconds = self.digits(2)?;
+                nanoseconds += seconds as i128 * NS_PER_SECOND;
+                if self.eat(b'.') || self.eat(b',') {
+                    nanoseconds += self.fraction()?;
+                }
+                if seconds > 59 {
+                    return None;
+                }
+            }
+            if minutes > 59 {
+                return None;
+            }
+        }
+        if hours > 23 {
+            return None;
+        }
+        Some(if negative { -nanoseconds } else { nanoseconds })
+    }
+
+    /// `[time zone]` followed by `[key=value]` annotations, a `!` marks them critical.
+    fn annotations(&mut self, parsed: &mut ParsedDateTime) -> Option<()> {
+        let mut first = true;
+        let mut calendar_critical = false;
+        let mut calendars = 0;
+        while self.eat(b'[') {
+            let critical = self.eat(b'!');
+            let content = self.take_while(|c| c != b']');
+            if !self.eat(b']') || content.is_empty() {
+                return None;
+            }
+            let content = std::str::from_utf8(content).ok()?;
+            match content.split_once('=') {
+                None if first => parsed.time_zone = Some(content.to_owned()),
+                None => return None,
+                Some((key, value)) => {
+                    let valid_key = key
+                        .bytes()
+                        .next()
+                        .map_or(false, |c| c.is_ascii_lowercase() || c == b'_')
+                        && key.bytes().all(|c| {
+                            c.is_ascii_lowercase() || c.is_ascii_digit() || c == b'_' || c == b'-'
+                        });
+                    if !valid_key || value.is_empty() {
+                        return None;
+                    }
+                    if key == "u-ca" {
+                        calendars += 1;
+                        calendar_critical |= critical;
+                        if parsed.calendar.is_none() {
+                            parsed.calendar = Some(value.to_owned());
+                        }
+                    } else if critical {
+                        return None;
+                    }
+                }
+            }
+            first = false;
+        }
+        // several calendars are only an error when one of them is critical
+        if calendars > 1 && calendar_critical {
+            return None;
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The parsed string written back in the canonical form.
+    fn canonical(parsed: &ParsedDateTime) -> String {
+        let mut text = String::new();
+        if let Some(date) = parsed.date {
+            text.push_str(&format_date(date));
+        }
+        if let Some(time) = parsed.time {
+            if !text.is_empty() {
+                text.push('T');
+            }
+            text.push_str(&format_time(time, Precision::Auto));
+        }
+        match parsed.offset {
+            Some(Offset::Utc) => text.push('Z'),
+            Some(Offset::Nanoseconds(offset)) => text.push_str(&format_exact_offset(offset)),
+            None => {}
+        }
+        if let Some(time_zone) = &parsed.time_zone {
+            text.push_str(&format!("[{}]", time_zone));
+        }
+        if let Some(calendar) = &parsed.calendar {
+            text.push_str(&format!("[u-ca={}]", calendar));
+        }
+        text
+    }
+
+    #[test]
+    fn test_date_time_round_trip() {
+        let cases = [
+            (
+                "2026-10-18T10:00:00.5+02:00[Europe/Berlin][u-ca=iso8601]",
+                Some("2026-10-18T10:00:00.5+02:00[Europe/Berlin][u-ca=iso8601]"),
+            ),
+            (
+                "20261018T100000,5+0200",
+                Some("2026-10-18T10:00:00.5+02:00"),
+            ),
+            ("2026-10-18t10:00z", Some("2026-10-18T10:00:00Z")),
+            ("2026-10-18 10", Some("2026-10-18T10:00:00")),
+            ("2026-10-18T10:00:60", Some("2026-10-18T10:00:59")),
+            (
+                "2026-10-18T10:00:00.123456789\u{2212}05:30",
+                Some("2026-10-18T10:00:00.123456789-05:30"),
+            ),
+            (
+                "2026-10-18T10:00+01:00:30.5",
+                Some("2026-10-18T10:00:00+01:00:30.5"),
+            ),
+            ("2026-10-18", Some("2026-10-18")),
+            ("+002026-10-18", Some("2026-10-18")),
+            ("-000001-01-01", Some("-000001-01-01")),
+            ("+275760-09-13", Some("+275760-09-13")),
+            ("-271821-04-19", Some("-271821-04-19")),
+            (
+                "2026-10-18[u-ca=iso8601][u-ca=gregory]",
+                Some("2026-10-18[u-ca=iso8601]"),
+            ),
+            ("2026-10-18[UTC][x-foo=bar]", Some("2026-10-18[UTC]")),
+            ("2026-10-18[!u-ca=iso8601][u-ca=gregory]", None),
+            ("2026-10-18[UTC][!x-foo=bar]", None),
+            ("2026-10-18[UTC][Europe/Berlin]", None),
+            ("2026-10-18[X-foo=bar]", None),
+            ("2026-10-18[]", None),
+            ("-000000-01-01", None),
+            ("+275760-09-14", None),
+            ("-271821-04-18", None),
+            ("2026-02-29", None),
+            ("2026-1018", None),
+            ("2026-10-18T24:00", None),
+            ("2026-10-18T10:60", None),
+            ("2026-10-18T10:00+24:00", None),
+            ("2026-10-18T10:00:00.1234567890Z", None),
+        ];
+        for (text, expected) in cases.iter() {
+            let parsed = parse_date_time(text);
+            assert_eq!(
+                parsed.as_ref().map(canonical).as_deref(),
+                *expected,
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_time_round_trip() {
+        let cases = [
+            ("T1018", Some("10:18:00")),
+            ("10:18", Some("10:18:00")),
+            ("1032", Some("10:32:00")),
+            ("10:18:30.25Z", Some("10:18:30.25Z")),
+            ("2026-10-18T10:18", Some("2026-10-18T10:18:00")),
+            // these read as a month-day or a year-month without the `T`
+            ("1018", None),
+            ("10-18", None),
+            ("202610", None),
+            ("2026-10", None),
+            ("2026-10-18", None),
+            ("T24:00", None),
+        ];
+        for (text, expected) in cases.iter() {
+            let parsed = parse_time(text);
+            assert_eq!(
+                parsed.as_ref().map(canonical).as_deref(),
+                *expected,
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_duration_round_trip() {
+        let cases = [
+            (
+                "P1Y2M3W4DT5H6M7.008009010S",
+                Some("P1Y2M3W4DT5H6M7.00800901S"),
+            ),
+            ("-PT1.5H", Some("-PT1H30M")),
+            ("\u{2212}P1D", Some("-P1D")),
+            ("+P1D", Some("P1D")),
+            ("p1w", Some("P1W")),
+            ("PT1,5S", Some("PT1.5S")),
+            ("PT1M90S", Some("PT1M90S")),
+            ("PT0S", Some("PT0S")),
+            ("P0D", Some("PT0S")),
+            ("P", None),
+            ("PT", None),
+            ("P1DT", None),
+            ("P1.5D", None),
+            ("PT1.5H1M", None),
+            ("P1M1Y", None),
+            ("P4294967296Y", None),
+            ("1D", None),
+        ];
+        for (text, expected) in cases.iter() {
+            let formatted =
+                parse_duration(text).map(|duration| format_duration(&duration, Precision::Auto));
+            assert_eq!(formatted.as_deref(), *expected, "{}", text);
+        }
+        let duration = parse_duration("PT1.5S").unwrap();
+        assert_eq!(format_duration(&duration, Precision::Digits(3)), "PT1.500S");
+        assert_eq!(format_duration(&duration, Precision::Digits(0)), "PT1S");
+    }
+
+    #[test]
+    fn test_offset_round_trip() {
+        let cases = [
+            ("+05:30", Some("+05:30")),
+            ("+0530", Some("+05:30")),
+            ("-00:00", Some("+00:00")),
+            ("UTC", Some("UTC")),
+            ("utc", Some("UTC")),
+            ("+05:30:00", None),
+            ("+24:00", None),
+            ("+05:60", None),
+            ("05:30", None),
+        ];
+        for (text, expected) in cases.iter() {
+            let zone = parse_time_zone(text);
+            assert_eq!(zone.as_ref().map(TimeZone::name), *expected, "{}", text);
+        }
+        let offset = parse_offset("+01:00:30.5").unwrap();
+        assert_eq!(format_exact_offset(offset), "+01:00:30.5");
+        assert_eq!(format_offset(offset), "+01:01");
+        assert_eq!(format_offset(-30 * NS_PER_SECOND), "-00:01");
+        assert_eq!(format_offset(-29 * NS_PER_SECOND), "+00:00");
+    }
+}
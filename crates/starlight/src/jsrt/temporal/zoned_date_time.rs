@@ -0,0 +1,768 @@
+//! `Temporal.ZonedDateTime`, an exact time together with the time zone that gives its wall-clock
+//! date and time.
+use std::{
+    intrinsics::unlikely,
+    mem::{size_of, ManuallyDrop},
+    rc::Rc,
+};
+
+use super::{
+    duration::to_duration,
+    get_calendar_annotation, get_difference_settings, get_disambiguation, get_offset_option,
+    get_options, get_overflow, get_round_settings, get_string_option, get_to_string_settings,
+    instant::new_instant,
+    interpret_offset,
+    iso::{
+        instant_for, is_valid_epoch_nanoseconds, local_date_time, offset_nanoseconds,
+        round_to_increment, start_of_day, Disambiguation, IsoDate, IsoDateTime, IsoTime, Relative,
+        Unit, NS_PER_HOUR, NS_PER_MINUTE,
+    },
+    plain_date::new_plain_date,
+    plain_date_time::new_plain_date_time,
+    plain_time::{new_plain_time, to_plain_time},
+    range_error, read_fields, rfc9557, round_step, string_value, to_partial_fields, to_time_zone,
+    type_error, Difference, EpochNanoseconds, FieldKinds, OffsetOption, UnitGroup,
+};
+use crate::{
+    define_jsclass, js_method_table,
+    jsrt::date::TimeZone,
+    prelude::*,
+    vm::{class::JsClass, context::Context, object::TypedJsObject},
+    JsTryFrom,
+};
+
+#[derive(Clone)]
+pub struct ZonedDateTime {
+    pub(super) epoch_nanoseconds: EpochNanoseconds,
+    pub(super) time_zone: Rc<TimeZone>,
+}
+
+impl ZonedDateTime {
+    fn new(nanoseconds: i128, time_zone: Rc<TimeZone>) -> Self {
+        Self {
+            epoch_nanoseconds: EpochNanoseconds::new(nanoseconds),
+            time_zone,
+        }
+    }
+
+    /// Wall-clock date and time in the time zone.
+    pub(super) fn local_date_time(&self) -> IsoDateTime {
+        local_date_time(&self.time_zone, self.epoch_nanoseconds.get())
+    }
+
+    fn offset_nanoseconds(&self) -> i128 {
+        offset_nanoseconds(&self.time_zone, self.epoch_nanoseconds.get())
+    }
+}
+
+extern "C" fn drop_zoned_date_time(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<ZonedDateTime>()) }
+}
+
+extern "C" fn fsz() -> usize {
+    size_of::<ZonedDateTime>()
+}
+
+extern "C" fn ser(object: &JsObject, serializer: &mut SnapshotSerializer) {
+    let zoned = object.data::<ZonedDateTime>();
+    serializer.write_u64(zoned.epoch_nanoseconds.0[0] as u64);
+    serializer.write_u64(zoned.epoch_nanoseconds.0[1] as u64);
+    zoned.time_zone.name().to_string().serialize(serializer);
+}
+
+extern "C" fn deser(object: &mut JsObject, deser: &mut Deserializer) {
+    let high = deser.get_u64() as i64;
+    let low = deser.get_u64() as i64;
+    let name = unsafe { String::deserialize_inplace(deser) };
+    let time_zone = rfc9557::parse_time_zone(&name).unwrap_or_else(|| TimeZone::load(Some(&name)));
+    *object.data::<ZonedDateTime>() = ManuallyDrop::new(ZonedDateTime {
+        epoch_nanoseconds: EpochNanoseconds([high, low]),
+        time_zone: Rc::new(time_zone),
+    });
+}
+
+define_jsclass!(
+    ZonedDateTime,
+    ZonedDateTime,
+    Object,
+    Some(drop_zoned_date_time),
+    None,
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl JsClass for ZonedDateTime {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+const ZONED_FIELDS: FieldKinds = FieldKinds {
+    date: true,
+    time: true,
+    offset: true,
+};
+
+pub(super) fn new_zoned_date_time(
+    ctx: GcPointer<Context>,
+    nanoseconds: i128,
+    time_zone: Rc<TimeZone>,
+) -> JsValue {
+    let proto = ctx.global_data.temporal_zoned_date_time_prototype;
+    super::new_object(ctx, proto, ZonedDateTime::new(nanoseconds, time_zone))
+}
+
+/// The ZonedDateTime `value` is, if it is one.
+pub(super) fn to_zoned_date_time_object(value: JsValue) -> Option<ZonedDateTime> {
+    if !value.is_jsobject() {
+        return None;
+    }
+    let object = value.get_jsobject();
+    if object.is_class(ZonedDateTime::get_class()) {
+        Some((**object.data::<ZonedDateTime>()).clone())
+    } else {
+        None
+    }
+}
+
+fn this_zoned(ctx: GcPointer<Context>, args: &Arguments) -> Result<ZonedDateTime, JsValue> {
+    let zoned = TypedJsObject::<ZonedDateTime>::try_from(ctx, args.this)?;
+    Ok((*zoned).clone())
+}
+
+fn this_local(ctx: GcPointer<Context>, args: &Arguments) -> Result<IsoDateTime, JsValue> {
+    Ok(this_zoned(ctx, args)?.local_date_time())
+}
+
+fn out_of_range(ctx: GcPointer<Context>) -> JsValue {
+    range_error(ctx, "Temporal: date-time is out of range")
+}
+
+/// ToTemporalZonedDateTime: a ZonedDateTime, a property bag with a `timeZone` or a date-time
+/// string with a time zone annotation.
+pub(super) fn to_zoned_date_time(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    options: JsValue,
+) -> Result<ZonedDateTime, JsValue> {
+    if let Some(zoned) = to_zoned_date_time_object(value) {
+        let options = get_options(ctx, options)?;
+        get_disambiguation(ctx, options)?;
+        get_offset_option(ctx, options, OffsetOption::Reject)?;
+        get_overflow(ctx, options)?;
+        return Ok(zoned);
+    }
+    if value.is_jsobject() {
+        let stack = ctx.shadowstack();
+        letroot!(object = stack, value.get_jsobject());
+        let fields = read_fields(ctx, *object, ZONED_FIELDS)?;
+        let zone = object.get(ctx, "timeZone".intern())?;
+        if unlikely(zone.is_undefined()) {
+            return Err(type_error(ctx, "Temporal: timeZone is required"));
+        }
+        let zone = to_time_zone(ctx, zone)?;
+        let options = get_options(ctx, options)?;
+        let disambiguation = get_disambiguation(ctx, options)?;
+        let offset_option = get_offset_option(ctx, options, OffsetOption::Reject)?;
+        let overflow = get_overflow(ctx, options)?;
+        let date = fields.date(ctx, overflow)?;
+        let time = fields.time(ctx, overflow, IsoTime::default())?;
+        let offset = fields.offset(ctx)?;
+        let instant = interpret_offset(
+            ctx,
+            &zone,
+            IsoDateTime { date, time },
+            offset,
+            disambiguation,
+            offset_option,
+            false,
+        )?;
+        return Ok(ZonedDateTime::new(instant, zone));
+    }
+    if unlikely(!value.is_jsstring()) {
+        return Err(type_error(
+            ctx,
+            "Temporal: expected a ZonedDateTime, an object or a string",
+        ));
+    }
+    let text = value.to_string(ctx)?;
+    let invalid = || range_error(ctx, format!("Temporal: invalid zoned date-time {}", text));
+    let parsed = rfc9557::parse_date_time(&text).ok_or_else(invalid)?;
+    let zone = match &parsed.time_zone {
+        Some(name) => rfc9557::parse_time_zone(name).ok_or_else(|| {
+            range_error(ctx, format!("Temporal: {} is not a valid time zone", name))
+        })?,
+        None => return Err(invalid()),
+    };
+    let zone = Rc::new(zone);
+    super::check_parsed_calendar(ctx, &parsed)?;
+    let options = get_options(ctx, options)?;
+    let disambiguation = get_disambiguation(ctx, options)?;
+    let offset_option = get_offset_option(ctx, options, OffsetOption::Reject)?;
+    get_overflow(ctx, options)?;
+    let date = parsed.date.unwrap();
+    let instant = match parsed.time {
+        None => start_of_day(&zone, date).ok_or_else(|| out_of_range(ctx))?,
+        Some(time) => {
+            let exact = matches!(parsed.offset, Some(rfc9557::Offset::Nanoseconds(offset)) if offset % NS_PER_MINUTE != 0);
+            interpret_offset(
+                ctx,
+                &zone,
+                IsoDateTime { date, time },
+                parsed.offset,
+                disambiguation,
+                offset_option,
+                !exact,
+            )?
+        }
+    };
+    Ok(ZonedDateTime::new(instant, zone))
+}
+
+pub fn zoned_date_time_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let proto = super::constructor_prototype(ctx, args, "ZonedDateTime")?;
+    let nanoseconds = super::to_epoch_nanoseconds(ctx, args.at(0))?;
+    let zone = args.at(1);
+    if unlikely(!zone.is_jsstring()) {
+        return Err(type_error(ctx, "Temporal: time zone must be a string"));
+    }
+    let id = zone.to_string(ctx)?;
+    let zone = rfc9557::parse_time_zone(&id)
+        .ok_or_else(|| range_error(ctx, format!("Temporal: {} is not a valid time zone", id)))?;
+    super::to_calendar(ctx, args.at(2))?;
+    Ok(super::new_object(
+        ctx,
+        proto,
+        ZonedDateTime::new(nanoseconds, Rc::new(zone)),
+    ))
+}
+
+pub fn zoned_date_time_from(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let zoned = to_zoned_date_time(ctx, args.at(0), args.at(1))?;
+    Ok(new_zoned_date_time(
+        ctx,
+        zoned.epoch_nanoseconds.get(),
+        zoned.time_zone,
+    ))
+}
+
+pub fn zoned_date_time_compare(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let undefined = JsValue::encode_undefined_value();
+    let one = to_zoned_date_time(ctx, args.at(0), undefined)?;
+    let two = to_zoned_date_time(ctx, args.at(1), undefined)?;
+    let ordering = one
+        .epoch_nanoseconds
+        .get()
+        .cmp(&two.epoch_nanoseconds.get());
+    Ok(JsValue::new(ordering as i32))
+}
+
+date_time_getters! {
+    this_local;
+    zoned_date_time_calendar_id => "calendarId",
+    zoned_date_time_year => "year",
+    zoned_date_time_month => "month",
+    zoned_date_time_month_code => "monthCode",
+    zoned_date_time_day => "day",
+    zoned_date_time_day_of_week => "dayOfWeek",
+    zoned_date_time_day_of_year => "dayOfYear",
+    zoned_date_time_week_of_year => "weekOfYear",
+    zoned_date_time_year_of_week => "yearOfWeek",
+    zoned_date_time_days_in_week => "daysInWeek",
+    zoned_date_time_days_in_month => "daysInMonth",
+    zoned_date_time_days_in_year => "daysInYear",
+    zoned_date_time_months_in_year => "monthsInYear",
+    zoned_date_time_in_leap_year => "inLeapYear",
+    zoned_date_time_hour => "hour",
+    zoned_date_time_minute => "minute",
+    zoned_date_time_second => "second",
+    zoned_date_time_millisecond => "millisecond",
+    zoned_date_time_microsecond => "microsecond",
+    zoned_date_time_nanosecond => "nanosecond",
+}
+
+pub fn zoned_date_time_time_zone_id(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    Ok(string_value(ctx, zoned.time_zone.name()))
+}
+
+pub fn zoned_date_time_epoch_milliseconds(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    Ok(super::epoch_milliseconds_value(
+        zoned.epoch_nanoseconds.get(),
+    ))
+}
+
+pub fn zoned_date_time_epoch_nanoseconds(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    Ok(super::epoch_nanoseconds_value(
+        ctx,
+        zoned.epoch_nanoseconds.get(),
+    ))
+}
+
+pub fn zoned_date_time_offset(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    Ok(string_value(
+        ctx,
+        rfc9557::format_exact_offset(zoned.offset_nanoseconds()),
+    ))
+}
+
+pub fn zoned_date_time_offset_nanoseconds(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    Ok(JsValue::new(zoned.offset_nanoseconds() as f64))
+}
+
+/// Length of the day in hours, 23 or 25 on days with a daylight saving time transition.
+pub fn zoned_date_time_hours_in_day(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    let today = zoned.local_date_time().date;
+    let tomorrow = IsoDate::from_epoch_days(today.epoch_days() + 1);
+    match (
+        start_of_day(&zoned.time_zone, today),
+        start_of_day(&zoned.time_zone, tomorrow),
+    ) {
+        (Some(start), Some(end)) => Ok(JsValue::new((end - start) as f64 / NS_PER_HOUR as f64)),
+        _ => Err(out_of_range(ctx)),
+    }
+}
+
+pub fn zoned_date_time_with(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    let local = zoned.local_date_time();
+    let mut fields = to_partial_fields(ctx, args.at(0), ZONED_FIELDS)?;
+    fields.merge_date(local.date);
+    fields
+        .offset
+        .get_or_insert_with(|| rfc9557::format_exact_offset(zoned.offset_nanoseconds()));
+    let options = get_options(ctx, args.at(1))?;
+    let disambiguation = get_disambiguation(ctx, options)?;
+    let offset_option = get_offset_option(ctx, options, OffsetOption::Prefer)?;
+    let overflow = get_overflow(ctx, options)?;
+    let date = fields.date(ctx, overflow)?;
+    let time = fields.time(ctx, overflow, local.time)?;
+    let offset = fields.offset(ctx)?;
+    let instant = interpret_offset(
+        ctx,
+        &zoned.time_zone,
+        IsoDateTime { date, time },
+        offset,
+        disambiguation,
+        offset_option,
+        false,
+    )?;
+    Ok(new_zoned_date_time(ctx, instant, zoned.time_zone))
+}
+
+pub fn zoned_date_time_with_plain_time(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    let date = zoned.local_date_time().date;
+    let instant = if args.at(0).is_undefined() {
+        start_of_day(&zoned.time_zone, date)
+    } else {
+        let time = to_plain_time(ctx, args.at(0), JsValue::encode_undefined_value())?;
+        instant_for(
+            &zoned.time_zone,
+            IsoDateTime { date, time },
+            Disambiguation::Compatible,
+        )
+    };
+    let instant = instant.ok_or_else(|| out_of_range(ctx))?;
+    Ok(new_zoned_date_time(ctx, instant, zoned.time_zone))
+}
+
+pub fn zoned_date_time_with_time_zone(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    let zone = to_time_zone(ctx, args.at(0))?;
+    Ok(new_zoned_date_time(
+        ctx,
+        zoned.epoch_nanoseconds.get(),
+        zone,
+    ))
+}
+
+/// AddZonedDateTime, calendar units move the wall clock and the rest the exact time.
+fn add_duration(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    negate: bool,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    let duration = to_duration(ctx, args.at(0))?;
+    let duration = if negate { duration.negated() } else { duration };
+    let overflow = get_overflow(ctx, get_options(ctx, args.at(1))?)?;
+    let instant = Relative::zoned(&zoned.time_zone, zoned.epoch_nanoseconds.get())
+        .add_with_overflow(&duration, overflow)
+        .ok_or_else(|| out_of_range(ctx))?;
+    Ok(new_zoned_date_time(ctx, instant, zoned.time_zone))
+}
+
+pub fn zoned_date_time_add(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    add_duration(ctx, args, false)
+}
+
+pub fn zoned_date_time_subtract(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    add_duration(ctx, args, true)
+}
+
+/// DifferenceTemporalZonedDateTime, calendar units are only meaningful in one time zone.
+fn difference(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    operation: Difference,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    let other = to_zoned_date_time(ctx, args.at(0), JsValue::encode_undefined_value())?;
+    let settings = get_difference_settings(
+        ctx,
+        operation,
+        args.at(1),
+        UnitGroup::DateTime,
+        Unit::Nanosecond,
+        Unit::Hour,
+    )?;
+    if unlikely(settings.largest.is_date() && zoned.time_zone.name() != other.time_zone.name()) {
+        return Err(range_error(
+            ctx,
+            "Temporal: differences in days or larger units require the same time zone",
+        ));
+    }
+    let duration = Relative::zoned(&zoned.time_zone, zoned.epoch_nanoseconds.get())
+        .round_until(other.epoch_nanoseconds.get(), &settings)
+        .filter(|duration| duration.is_valid())
+        .ok_or_else(|| out_of_range(ctx))?;
+    Ok(super::duration::new_duration(
+        ctx,
+        match operation {
+            Difference::Until => duration,
+            Difference::Since => duration.negated(),
+        },
+    ))
+}
+
+pub fn zoned_date_time_until(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    difference(ctx, args, Difference::Until)
+}
+
+pub fn zoned_date_time_since(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    difference(ctx, args, Difference::Since)
+}
+
+/// Rounds the wall-clock time and keeps the offset when the result still has it. Days are rounded
+/// between the actual starts of the day and the next one.
+pub fn zoned_date_time_round(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    let settings = get_round_settings(ctx, args.at(0), UnitGroup::DateTime)?;
+    if unlikely(settings.smallest > Unit::Day) {
+        return Err(range_error(
+            ctx,
+            "Temporal: smallestUnit must be day or smaller",
+        ));
+    }
+    round_step(ctx, &settings, false)?;
+    let nanoseconds = zoned.epoch_nanoseconds.get();
+    let local = zoned.local_date_time();
+    let instant = if settings.smallest == Unit::Day {
+        let tomorrow = IsoDate::from_epoch_days(local.date.epoch_days() + 1);
+        match (
+            start_of_day(&zoned.time_zone, local.date),
+            start_of_day(&zoned.time_zone, tomorrow),
+        ) {
+            (Some(start), Some(end)) => {
+                start + round_to_increment(nanoseconds - start, end - start, settings.mode)
+            }
+            _ => return Err(out_of_range(ctx)),
+        }
+    } else {
+        let (days, time) = local
+            .time
+            .round(settings.smallest, settings.increment, settings.mode);
+        let date = IsoDate::from_epoch_days(local.date.epoch_days() + days);
+        interpret_offset(
+            ctx,
+            &zoned.time_zone,
+            IsoDateTime { date, time },
+            Some(rfc9557::Offset::Nanoseconds(zoned.offset_nanoseconds())),
+            Disambiguation::Compatible,
+            OffsetOption::Prefer,
+            false,
+        )?
+    };
+    Ok(new_zoned_date_time(ctx, instant, zoned.time_zone))
+}
+
+pub fn zoned_date_time_equals(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    let other = to_zoned_date_time(ctx, args.at(0), JsValue::encode_undefined_value())?;
+    Ok(JsValue::new(
+        zoned.epoch_nanoseconds.get() == other.epoch_nanoseconds.get()
+            && zoned.time_zone.name() == other.time_zone.name(),
+    ))
+}
+
+pub fn zoned_date_time_start_of_day(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    let date = zoned.local_date_time().date;
+    let instant = start_of_day(&zoned.time_zone, date).ok_or_else(|| out_of_range(ctx))?;
+    Ok(new_zoned_date_time(ctx, instant, zoned.time_zone))
+}
+
+pub fn zoned_date_time_to_instant(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    Ok(new_instant(ctx, zoned.epoch_nanoseconds.get()))
+}
+
+pub fn zoned_date_time_to_plain_date(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let local = this_local(ctx, args)?;
+    Ok(new_plain_date(ctx, local.date))
+}
+
+pub fn zoned_date_time_to_plain_time(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let local = this_local(ctx, args)?;
+    Ok(new_plain_time(ctx, local.time))
+}
+
+pub fn zoned_date_time_to_plain_date_time(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let local = this_local(ctx, args)?;
+    Ok(new_plain_date_time(ctx, local))
+}
+
+/// TemporalZonedDateTimeToString: the wall-clock date-time, the offset and the time zone
+/// annotation, each of which can be left out.
+fn to_string(
+    ctx: GcPointer<Context>,
+    zoned: &ZonedDateTime,
+    options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = get_options(ctx, options)?;
+    let calendar = get_calendar_annotation(ctx, options)?;
+    let settings = get_to_string_settings(ctx, options)?;
+    let show_offset = get_string_option(ctx, options, "offset", &["auto", "never"])?;
+    let zone_name =
+        get_string_option(ctx, options, "timeZoneName", &["auto", "never", "critical"])?;
+    let rounded = round_to_increment(
+        zoned.epoch_nanoseconds.get(),
+        settings.step(),
+        settings.mode,
+    );
+    if unlikely(!is_valid_epoch_nanoseconds(rounded)) {
+        return Err(out_of_range(ctx));
+    }
+    let mut text = rfc9557::format_date_time(
+        local_date_time(&zoned.time_zone, rounded),
+        settings.precision,
+    );
+    if show_offset != Some("never") {
+        text.push_str(&rfc9557::format_offset(offset_nanoseconds(
+            &zoned.time_zone,
+            rounded,
+        )));
+    }
+    match zone_name {
+        Some("never") => {}
+        Some("critical") => text.push_str(&format!("[!{}]", zoned.time_zone.name())),
+        _ => text.push_str(&format!("[{}]", zoned.time_zone.name())),
+    }
+    text.push_str(calendar);
+    Ok(string_value(ctx, text))
+}
+
+pub fn zoned_date_time_to_string(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    to_string(ctx, &zoned, args.at(0))
+}
+
+pub fn zoned_date_time_to_json(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let zoned = this_zoned(ctx, args)?;
+    to_string(ctx, &zoned, JsValue::encode_undefined_value())
+}
+
+impl GcPointer<Context> {
+    pub(super) fn init_temporal_zoned_date_time_in_global_data(mut self) -> Result<(), JsValue> {
+        let (mut proto, mut ctor) =
+            self.new_temporal_class("ZonedDateTime", zoned_date_time_constructor, 2)?;
+        def_native_method!(self, ctor, from, zoned_date_time_from, 1, W | C)?;
+        def_native_method!(self, ctor, compare, zoned_date_time_compare, 2, W | C)?;
+
+        def_native_accessor!(self, proto, calendarId, zoned_date_time_calendar_id, C)?;
+        def_native_accessor!(self, proto, timeZoneId, zoned_date_time_time_zone_id, C)?;
+        def_native_accessor!(self, proto, year, zoned_date_time_year, C)?;
+        def_native_accessor!(self, proto, month, zoned_date_time_month, C)?;
+        def_native_accessor!(self, proto, monthCode, zoned_date_time_month_code, C)?;
+        def_native_accessor!(self, proto, day, zoned_date_time_day, C)?;
+        def_native_accessor!(self, proto, dayOfWeek, zoned_date_time_day_of_week, C)?;
+        def_native_accessor!(self, proto, dayOfYear, zoned_date_time_day_of_year, C)?;
+        def_native_accessor!(self, proto, weekOfYear, zoned_date_time_week_of_year, C)?;
+        def_native_accessor!(self, proto, yearOfWeek, zoned_date_time_year_of_week, C)?;
+        def_native_accessor!(self, proto, daysInWeek, zoned_date_time_days_in_week, C)?;
+        def_native_accessor!(self, proto, daysInMonth, zoned_date_time_days_in_month, C)?;
+        def_native_accessor!(self, proto, daysInYear, zoned_date_time_days_in_year, C)?;
+        def_native_accessor!(self, proto, monthsInYear, zoned_date_time_months_in_year, C)?;
+        def_native_accessor!(self, proto, inLeapYear, zoned_date_time_in_leap_year, C)?;
+        def_native_accessor!(self, proto, hoursInDay, zoned_date_time_hours_in_day, C)?;
+        def_native_accessor!(self, proto, hour, zoned_date_time_hour, C)?;
+        def_native_accessor!(self, proto, minute, zoned_date_time_minute, C)?;
+        def_native_accessor!(self, proto, second, zoned_date_time_second, C)?;
+        def_native_accessor!(self, proto, millisecond, zoned_date_time_millisecond, C)?;
+        def_native_accessor!(self, proto, microsecond, zoned_date_time_microsecond, C)?;
+        def_native_accessor!(self, proto, nanosecond, zoned_date_time_nanosecond, C)?;
+        def_native_accessor!(
+            self,
+            proto,
+            epochMilliseconds,
+            zoned_date_time_epoch_milliseconds,
+            C
+        )?;
+        def_native_accessor!(
+            self,
+            proto,
+            epochNanoseconds,
+            zoned_date_time_epoch_nanoseconds,
+            C
+        )?;
+        def_native_accessor!(self, proto, offset, zoned_date_time_offset, C)?;
+        def_native_accessor!(
+            self,
+            proto,
+            offsetNanoseconds,
+            zoned_date_time_offset_nanoseconds,
+            C
+        )?;
+        def_native_method!(self, proto, with, zoned_date_time_with, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            withPlainTime,
+            zoned_date_time_with_plain_time,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            withTimeZone,
+            zoned_date_time_with_time_zone,
+            1,
+            W | C
+        )?;
+        def_native_method!(self, proto, add, zoned_date_time_add, 1, W | C)?;
+        def_native_method!(self, proto, subtract, zoned_date_time_subtract, 1, W | C)?;
+        def_native_method!(self, proto, until, zoned_date_time_until, 1, W | C)?;
+        def_native_method!(self, proto, since, zoned_date_time_since, 1, W | C)?;
+        def_native_method!(self, proto, round, zoned_date_time_round, 1, W | C)?;
+        def_native_method!(self, proto, equals, zoned_date_time_equals, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            startOfDay,
+            zoned_date_time_start_of_day,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, proto, toInstant, zoned_date_time_to_instant, 0, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toPlainDate,
+            zoned_date_time_to_plain_date,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            toPlainTime,
+            zoned_date_time_to_plain_time,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            toPlainDateTime,
+            zoned_date_time_to_plain_date_time,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, proto, toString, zoned_date_time_to_string, 0, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toLocaleString,
+            zoned_date_time_to_json,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, proto, toJSON, zoned_date_time_to_json, 0, W | C)?;
+        self.global_data.temporal_zoned_date_time_prototype = Some(proto);
+        Ok(())
+    }
+}
@@ -0,0 +1,1586 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+use std::cmp::Ordering;
+
+use super::number::to_integer_or_infinity;
+use crate::{
+    prelude::*,
+    vm::{
+        array_buffer::JsArrayBuffer,
+        context::Context,
+        interpreter::SpreadValue,
+        object::TypedJsObject,
+        operations,
+        typedarray::{JsTypedArray, TypedArrayKind},
+    },
+};
+
+/// Stable merge sort that propagates errors thrown by `compare`. Used by sort methods that
+/// invoke user provided comparator.
+pub(crate) fn merge_sort<T: Copy>(
+    values: &mut [T],
+    compare: &mut dyn FnMut(T, T) -> Result<Ordering, JsValue>,
+) -> Result<(), JsValue> {
+    let len = values.len();
+    if len < 2 {
+        return Ok(());
+    }
+    let mut buffer = values.to_vec();
+    let mut width = 1;
+    while width < len {
+        let mut start = 0;
+        while start < len {
+            let mid = std::cmp::min(start + width, len);
+            let end = std::cmp::min(start + 2 * width, len);
+            let (mut i, mut j, mut k) = (start, mid, start);
+            while i < mid && j < end {
+                if compare(values[j], values[i])? == Ordering::Less {
+                    buffer[k] = values[j];
+                    j += 1;
+                } else {
+                    buffer[k] = values[i];
+                    i += 1;
+                }
+                k += 1;
+            }
+            buffer[k..k + (mid - i)].copy_from_slice(&values[i..mid]);
+            k += mid - i;
+            buffer[k..k + (end - j)].copy_from_slice(&values[j..end]);
+            start = end;
+        }
+        values.copy_from_slice(&buffer);
+        width *= 2;
+    }
+    Ok(())
+}
+
+/// Default typed array order: numeric, `-0` before `+0` and NaN last.
+fn compare_numbers(x: f64, y: f64) -> Ordering {
+    if x.is_nan() {
+        return if y.is_nan() {
+            Ordering::Equal
+        } else {
+            Ordering::Greater
+        };
+    }
+    if y.is_nan() {
+        return Ordering::Less;
+    }
+    if x < y {
+        return Ordering::Less;
+    }
+    if x > y {
+        return Ordering::Greater;
+    }
+    if x == 0.0 && y == 0.0 {
+        return match (x.is_sign_negative(), y.is_sign_negative()) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => Ordering::Equal,
+        };
+    }
+    Ordering::Equal
+}
+
+/// Resolve relative `value` (negative values count from the end) against `len`.
+fn relative_index(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    len: usize,
+    default: usize,
+) -> Result<usize, JsValue> {
+    if value.is_undefined() {
+        return Ok(default);
+    }
+    let relative = value.to_number(ctx)?;
+    if relative.is_nan() {
+        return Ok(0);
+    }
+    let relative = relative.trunc();
+    Ok(if relative < 0.0 {
+        (len as f64 + relative).max(0.0) as usize
+    } else {
+        relative.min(len as f64) as usize
+    })
+}
+
+/// ValidateTypedArray: `this` must be typed array with attached buffer.
+fn this_typed_array(
+    ctx: GcPointer<Context>,
+    this: JsValue,
+    method: &str,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    if this.is_jsobject() && JsTypedArray::is_typed_array(&this.get_jsobject()) {
+        let object = this.get_jsobject();
+        if !object.data::<JsTypedArray>().attached() {
+            return Err(JsValue::new(ctx.new_type_error(format!(
+                "TypedArray.prototype.{} called on a detached ArrayBuffer",
+                method
+            ))));
+        }
+        return Ok(object);
+    }
+    Err(JsValue::new(ctx.new_type_error(format!(
+        "TypedArray.prototype.{} called on a non TypedArray object",
+        method
+    ))))
+}
+
+/// Accessors do not throw on detached buffers, they report zero instead.
+fn this_typed_array_unchecked(
+    ctx: GcPointer<Context>,
+    this: JsValue,
+    method: &str,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    if this.is_jsobject() && JsTypedArray::is_typed_array(&this.get_jsobject()) {
+        return Ok(this.get_jsobject());
+    }
+    Err(JsValue::new(ctx.new_type_error(format!(
+        "TypedArray.prototype.{} called on a non TypedArray object",
+        method
+    ))))
+}
+
+fn detached_error(ctx: GcPointer<Context>, method: &str) -> JsValue {
+    JsValue::new(ctx.new_type_error(format!(
+        "TypedArray.prototype.{}: ArrayBuffer was detached",
+        method
+    )))
+}
+
+fn typed_array_construct(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    kind: TypedArrayKind,
+) -> Result<JsValue, JsValue> {
+    if !args.ctor_call {
+        return Err(JsValue::new(ctx.new_type_error(format!(
+            "{}() called in function context instead of constructor",
+            kind.name()
+        ))));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(result = stack, allocate_typed_array(ctx, args, kind)?);
+    // `this` was created from new.target and has its `prototype`. When that was no object `this`
+    // got Object.prototype instead, the intrinsic prototype of `kind` is kept in that case.
+    if args.this.is_jsobject() {
+        if let Some(proto) = args.this.get_jsobject().prototype().copied() {
+            let default = *kind.structure(ctx).prototype().unwrap();
+            let object_proto = ctx.global_data().get_object_prototype();
+            if !GcPointer::ptr_eq(&proto, &default) && !GcPointer::ptr_eq(&proto, &object_proto) {
+                operations::set_prototype_of(ctx, &mut result, Some(proto))?;
+            }
+        }
+    }
+    Ok(JsValue::new(*result))
+}
+
+/// Typed array of `kind` for constructor arguments `args`, with the intrinsic prototype.
+fn allocate_typed_array(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    kind: TypedArrayKind,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    let first = args.at(0);
+    if !first.is_jsobject() {
+        let length = super::to_index(ctx, first)?;
+        return JsTypedArray::with_length(ctx, kind, length);
+    }
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, first.get_jsobject());
+    let element_size = kind.element_size();
+    if object.is_class(JsArrayBuffer::get_class()) {
+        let buffer = TypedJsObject::<JsArrayBuffer>::new(*object);
+        let offset = super::to_index(ctx, args.at(1))?;
+        if offset % element_size != 0 {
+            return Err(JsValue::new(ctx.new_range_error(format!(
+                "new {}(buffer, [byteOffset], [length]): byteOffset must be a multiple of {}",
+                kind.name(),
+                element_size
+            ))));
+        }
+        let new_length = if args.at(2).is_undefined() {
+            None
+        } else {
+            Some(super::to_index(ctx, args.at(2))?)
+        };
+        if !buffer.attached() {
+            return Err(JsValue::new(ctx.new_type_error(format!(
+                "new {}(buffer, [byteOffset], [length]): buffer is detached",
+                kind.name()
+            ))));
+        }
+        let buffer_length = buffer.byte_length();
+        let length = match new_length {
+            None => {
+                if buffer_length % element_size != 0 {
+                    return Err(JsValue::new(ctx.new_range_error(format!(
+                        "new {}(buffer, [byteOffset], [length]): buffer's byte length must be a multiple of {}",
+                        kind.name(),
+                        element_size
+                    ))));
+                }
+                if offset > buffer_length {
+                    return Err(JsValue::new(ctx.new_range_error(format!(
+                        "new {}(buffer, [byteOffset], [length]): byteOffset must be <= the buffer's byte length",
+                        kind.name()
+                    ))));
+                }
+                (buffer_length - offset) / element_size
+            }
+            Some(length) => {
+                if offset as u128 + length as u128 * element_size as u128 > buffer_length as u128 {
+                    return Err(JsValue::new(ctx.new_range_error(format!(
+                        "new {}(buffer, [byteOffset], [length]): byteOffset + length * {} must be <= the buffer's byte length",
+                        kind.name(),
+                        element_size
+                    ))));
+                }
+                length
+            }
+        };
+        return Ok(JsTypedArray::new(ctx, kind, buffer, offset, length));
+    }
+
+    if JsTypedArray::is_typed_array(&object) {
+        if !object.data::<JsTypedArray>().attached() {
+            return Err(JsValue::new(ctx.new_type_error(format!(
+                "new {}(typedArray): source buffer is detached",
+                kind.name()
+            ))));
+        }
+        let length = object.data::<JsTypedArray>().length();
+        letroot!(
+            result = stack,
+            JsTypedArray::with_length(ctx, kind, length)?
+        );
+        let source = object.data::<JsTypedArray>();
+        let target = result.data::<JsTypedArray>();
+        for i in 0..length {
+            target.set(i, source.get(i).unwrap().get_number());
+        }
+        return Ok(*result);
+    }
+
+    let iterator = object.get(ctx, "Symbol.iterator".intern().private())?;
+    if iterator.is_callable() && !object.is_class(JsArray::get_class()) {
+        letroot!(
+            spread = stack,
+            SpreadValue::new(ctx, JsValue::new(*object))?
+        );
+        let length = spread.array.len();
+        letroot!(
+            result = stack,
+            JsTypedArray::with_length(ctx, kind, length)?
+        );
+        for i in 0..length {
+            let number = spread.array[i].to_number(ctx)?;
+            result.data::<JsTypedArray>().set(i, number);
+        }
+        return Ok(*result);
+    }
+
+    let length = super::get_length(ctx, &mut object)? as usize;
+    letroot!(
+        result = stack,
+        JsTypedArray::with_length(ctx, kind, length)?
+    );
+    for i in 0..length {
+        let number = object.get(ctx, Symbol::Index(i as _))?.to_number(ctx)?;
+        result.data::<JsTypedArray>().set(i, number);
+    }
+    Ok(*result)
+}
+
+macro_rules! typed_array_constructors {
+    ($($name: ident => $kind: ident),*) => {
+        $(
+            pub fn $name(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+                typed_array_construct(ctx, args, TypedArrayKind::$kind)
+            }
+        )*
+
+        fn typed_array_constructor_of(kind: TypedArrayKind) -> JsAPI {
+            match kind {
+                $(TypedArrayKind::$kind => $name,)*
+            }
+        }
+    };
+}
+
+typed_array_constructors!(
+    int8_array_constructor => Int8,
+    uint8_array_constructor => Uint8,
+    uint8_clamped_array_constructor => Uint8Clamped,
+    int16_array_constructor => Int16,
+    uint16_array_constructor => Uint16,
+    int32_array_constructor => Int32,
+    uint32_array_constructor => Uint32,
+    float32_array_constructor => Float32,
+    float64_array_constructor => Float64
+);
+
+/// TypedArraySpeciesCreate. Returns `None` if `exemplar` uses the intrinsic constructor of its
+/// kind so that the caller can allocate the result directly.
+fn typed_array_species_create(
+    ctx: GcPointer<Context>,
+    exemplar: &mut GcPointer<JsObject>,
+    args: &mut [JsValue],
+    method: &str,
+) -> Result<Option<GcPointer<JsObject>>, JsValue> {
+    let kind = exemplar.data::<JsTypedArray>().kind();
+    let constructor =
+        operations::species_constructor(ctx, exemplar, JsValue::encode_undefined_value())?;
+    if constructor.is_undefined()
+        || super::is_native_function(constructor, typed_array_constructor_of(kind))
+    {
+        return Ok(None);
+    }
+    let name = format!("TypedArray.prototype.{}", method);
+    typed_array_create(ctx, constructor, args, &name).map(Some)
+}
+
+/// TypedArrayCreateFromConstructor. The result must be an attached typed array that is at least
+/// as long as requested by a single length argument.
+fn typed_array_create(
+    ctx: GcPointer<Context>,
+    constructor: JsValue,
+    args: &mut [JsValue],
+    name: &str,
+) -> Result<GcPointer<JsObject>, JsValue> {
+    let result = operations::construct(ctx, constructor, args, None)?;
+    if !result.is_jsobject() || !JsTypedArray::is_typed_array(&result.get_jsobject()) {
+        return Err(JsValue::new(ctx.new_type_error(format!(
+            "{}: constructor didn't return a TypedArray",
+            name
+        ))));
+    }
+    let mut result = result.get_jsobject();
+    let array = result.data::<JsTypedArray>();
+    if !array.attached() {
+        return Err(JsValue::new(ctx.new_type_error(format!(
+            "{}: constructor returned a TypedArray with detached ArrayBuffer",
+            name
+        ))));
+    }
+    if args.len() == 1 && (array.length() as f64) < args[0].get_number() {
+        return Err(JsValue::new(ctx.new_type_error(format!(
+            "{}: constructor returned too short TypedArray",
+            name
+        ))));
+    }
+    Ok(result)
+}
+
+/// `%TypedArray%` intrinsic is abstract and exists only to hold shared statics and prototype.
+pub fn typed_array_constructor(
+    ctx: GcPointer<Context>,
+    _args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Err(JsValue::new(ctx.new_type_error(
+        "Abstract class TypedArray not directly constructable",
+    )))
+}
+
+/// %TypedArray%.from(source, [mapFn], [thisArg])
+pub fn typed_array_from(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let constructor = args.this;
+    if !operations::is_constructor(constructor) {
+        return Err(JsValue::new(
+            ctx.new_type_error("TypedArray.from: this is not a constructor"),
+        ));
+    }
+    let map_fn = args.at(1);
+    if !map_fn.is_undefined() && !map_fn.is_callable() {
+        return Err(JsValue::new(
+            ctx.new_type_error("TypedArray.from: mapFn must be a function"),
+        ));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(map_fn = stack, map_fn);
+    let this_arg = args.at(2);
+    letroot!(source = stack, args.at(0).to_object(ctx)?);
+    let iterator = source.get(ctx, "Symbol.iterator".intern().private())?;
+    // Iterables are read completely before the target is created, array-likes element by element.
+    let values = if iterator.is_undefined() || iterator.is_null() {
+        None
+    } else if !iterator.is_callable() {
+        return Err(JsValue::new(ctx.new_type_error(
+            "TypedArray.from: source[Symbol.iterator] is not a function",
+        )));
+    } else {
+        Some(SpreadValue::new(ctx, JsValue::new(*source))?)
+    };
+    letroot!(values = stack, values);
+    let length = match &*values {
+        Some(values) => values.array.len(),
+        None => super::get_length(ctx, &mut source)? as usize,
+    };
+    let mut create_args = [JsValue::new(length as f64)];
+    letroot!(
+        target = stack,
+        typed_array_create(ctx, constructor, &mut create_args, "TypedArray.from")?
+    );
+    for k in 0..length {
+        let value = match &*values {
+            Some(values) => values.array[k],
+            None => source.get(ctx, Symbol::Index(k as _))?,
+        };
+        let value = if map_fn.is_undefined() {
+            value
+        } else {
+            let mut arguments = [value, JsValue::new(k as f64)];
+            operations::call(ctx, *map_fn, this_arg, &mut arguments)?
+        };
+        let number = value.to_number(ctx)?;
+        target.data::<JsTypedArray>().set(k, number);
+    }
+    Ok(JsValue::new(*target))
+}
+
+/// %TypedArray%.of(...items)
+pub fn typed_array_of(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let constructor = args.this;
+    if !operations::is_constructor(constructor) {
+        return Err(JsValue::new(
+            ctx.new_type_error("TypedArray.of: this is not a constructor"),
+        ));
+    }
+    let length = args.size();
+    let mut create_args = [JsValue::new(length as f64)];
+    let stack = ctx.shadowstack();
+    letroot!(
+        target = stack,
+        typed_array_create(ctx, constructor, &mut create_args, "TypedArray.of")?
+    );
+    for k in 0..length {
+        let number = args.at(k).to_number(ctx)?;
+        target.data::<JsTypedArray>().set(k, number);
+    }
+    Ok(JsValue::new(*target))
+}
+
+pub fn typed_array_prototype_buffer(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let this = this_typed_array_unchecked(ctx, args.this, "buffer")?;
+    Ok(JsValue::new(this.data::<JsTypedArray>().buffer().object()))
+}
+
+pub fn typed_array_prototype_byte_length(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let this = this_typed_array_unchecked(ctx, args.this, "byteLength")?;
+    Ok(JsValue::new(
+        this.data::<JsTypedArray>().byte_length() as f64
+    ))
+}
+
+pub fn typed_array_prototype_byte_offset(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let this = this_typed_array_unchecked(ctx, args.this, "byteOffset")?;
+    Ok(JsValue::new(
+        this.data::<JsTypedArray>().byte_offset() as f64
+    ))
+}
+
+pub fn typed_array_prototype_length(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let this = this_typed_array_unchecked(ctx, args.this, "length")?;
+    Ok(JsValue::new(this.data::<JsTypedArray>().length() as f64))
+}
+
+pub fn typed_array_prototype_to_string_tag(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    if args.this.is_jsobject() {
+        if let Some(kind) = TypedArrayKind::from_tag(args.this.get_jsobject().tag) {
+            return Ok(JsValue::new(JsString::new(ctx, kind.name())));
+        }
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn typed_array_prototype_subarray(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "subarray")?);
+    let length = this.data::<JsTypedArray>().length();
+    let begin = relative_index(ctx, args.at(0), length, 0)?;
+    let end = relative_index(ctx, args.at(1), length, length)?;
+    let array = this.data::<JsTypedArray>();
+    if !array.attached() {
+        return Err(detached_error(ctx, "subarray"));
+    }
+    let kind = array.kind();
+    let buffer = array.buffer();
+    let offset = array.byte_offset() + begin * kind.element_size();
+    let length = end.saturating_sub(begin);
+    let mut species_args = [
+        JsValue::new(buffer.object()),
+        JsValue::new(offset as f64),
+        JsValue::new(length as f64),
+    ];
+    match typed_array_species_create(ctx, &mut this, &mut species_args, "subarray")? {
+        Some(result) => Ok(JsValue::new(result)),
+        None => Ok(JsValue::new(JsTypedArray::new(
+            ctx, kind, buffer, offset, length,
+        ))),
+    }
+}
+
+pub fn typed_array_prototype_set(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "set")?);
+    let offset = args.at(1).to_number(ctx)?;
+    let offset = if offset.is_nan() { 0.0 } else { offset.trunc() };
+    if offset < 0.0 {
+        return Err(JsValue::new(
+            ctx.new_range_error("TypedArray.prototype.set: offset must be >= 0"),
+        ));
+    }
+    if !this.data::<JsTypedArray>().attached() {
+        return Err(detached_error(ctx, "set"));
+    }
+    let target_length = this.data::<JsTypedArray>().length();
+    let source = args.at(0);
+    let out_of_bounds = |source_length: usize| offset + source_length as f64 > target_length as f64;
+    let range_error = || {
+        JsValue::new(ctx.new_range_error(
+            "TypedArray.prototype.set: source is too large for the target at given offset",
+        ))
+    };
+    let offset = offset as usize;
+
+    if source.is_jsobject() && JsTypedArray::is_typed_array(&source.get_jsobject()) {
+        let source = source.get_jsobject();
+        let source = source.data::<JsTypedArray>();
+        if !source.attached() {
+            return Err(detached_error(ctx, "set"));
+        }
+        if out_of_bounds(source.length()) {
+            return Err(range_error());
+        }
+        // Source and target may share the same buffer, read all values before writing.
+        let values = (0..source.length())
+            .map(|i| source.get(i).unwrap().get_number())
+            .collect::<Vec<_>>();
+        let target = this.data::<JsTypedArray>();
+        for (i, value) in values.into_iter().enumerate() {
+            target.set(offset + i, value);
+        }
+        return Ok(JsValue::encode_undefined_value());
+    }
+
+    letroot!(source = stack, source.to_object(ctx)?);
+    let source_length = super::get_length(ctx, &mut source)? as usize;
+    if out_of_bounds(source_length) {
+        return Err(range_error());
+    }
+    for i in 0..source_length {
+        let number = source.get(ctx, Symbol::Index(i as _))?.to_number(ctx)?;
+        this.data::<JsTypedArray>().set(offset + i, number);
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn typed_array_prototype_slice(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "slice")?);
+    let length = this.data::<JsTypedArray>().length();
+    let start = relative_index(ctx, args.at(0), length, 0)?;
+    let end = relative_index(ctx, args.at(1), length, length)?;
+    let count = end.saturating_sub(start);
+    let kind = this.data::<JsTypedArray>().kind();
+    let mut species_args = [JsValue::new(count as f64)];
+    let result = match typed_array_species_create(ctx, &mut this, &mut species_args, "slice")? {
+        Some(result) => result,
+        None => JsTypedArray::with_length(ctx, kind, count)?,
+    };
+    letroot!(result = stack, result);
+    if count > 0 {
+        let source = this.data::<JsTypedArray>();
+        if !source.attached() {
+            return Err(detached_error(ctx, "slice"));
+        }
+        let target = result.data::<JsTypedArray>();
+        if target.kind() == kind {
+            let element_size = kind.element_size();
+            JsArrayBuffer::copy_data_block_bytes(
+                target.buffer(),
+                target.byte_offset(),
+                source.buffer(),
+                source.byte_offset() + start * element_size,
+                count * element_size,
+            );
+        } else {
+            for i in 0..count {
+                if let Some(value) = source.get(start + i) {
+                    target.set(i, value.get_number());
+                }
+            }
+        }
+    }
+    Ok(JsValue::new(*result))
+}
+
+pub fn typed_array_prototype_fill(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "fill")?);
+    let length = this.data::<JsTypedArray>().length();
+    let value = args.at(0).to_number(ctx)?;
+    let start = relative_index(ctx, args.at(1), length, 0)?;
+    let end = relative_index(ctx, args.at(2), length, length)?;
+    let array = this.data::<JsTypedArray>();
+    if !array.attached() {
+        return Err(detached_error(ctx, "fill"));
+    }
+    for i in start..end {
+        array.set(i, value);
+    }
+    Ok(JsValue::new(*this))
+}
+
+pub fn typed_array_prototype_map(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "map")?);
+    let callback = args.at(0);
+    if !callback.is_callable() {
+        return Err(JsValue::new(ctx.new_type_error(
+            "TypedArray.prototype.map: callback must be a function",
+        )));
+    }
+    letroot!(callback = stack, callback);
+    let this_arg = args.at(1);
+    let length = this.data::<JsTypedArray>().length();
+    let kind = this.data::<JsTypedArray>().kind();
+    let mut species_args = [JsValue::new(length as f64)];
+    let result = match typed_array_species_create(ctx, &mut this, &mut species_args, "map")? {
+        Some(result) => result,
+        None => JsTypedArray::with_length(ctx, kind, length)?,
+    };
+    letroot!(result = stack, result);
+    for i in 0..length {
+        let value = this
+            .data::<JsTypedArray>()
+            .get(i)
+            .unwrap_or_else(JsValue::encode_undefined_value);
+        let mut tmp = [value, JsValue::new(i as f64), JsValue::new(*this)];
+        letroot!(args = stack, Arguments::new(this_arg, &mut tmp));
+        let mapped = callback
+            .get_jsobject()
+            .as_function_mut()
+            .call(ctx, &mut args, *callback)?;
+        let number = mapped.to_number(ctx)?;
+        result.data::<JsTypedArray>().set(i, number);
+    }
+    Ok(JsValue::new(*result))
+}
+
+/// Sorts element values with `comparator`, or numerically if it is undefined.
+fn sort_values(
+    ctx: GcPointer<Context>,
+    values: &mut [JsValue],
+    comparator: JsValue,
+) -> Result<(), JsValue> {
+    if comparator.is_undefined() {
+        return merge_sort(values, &mut |x, y| {
+            Ok(compare_numbers(x.get_number(), y.get_number()))
+        });
+    }
+    let stack = ctx.shadowstack();
+    letroot!(comparator = stack, comparator);
+    merge_sort(values, &mut |x, y| {
+        let mut tmp = [x, y];
+        letroot!(
+            args = stack,
+            Arguments::new(JsValue::encode_undefined_value(), &mut tmp)
+        );
+        let result = comparator
+            .get_jsobject()
+            .as_function_mut()
+            .call(ctx, &mut args, *comparator)?
+            .to_number(ctx)?;
+        Ok(if result < 0.0 {
+            Ordering::Less
+        } else if result > 0.0 {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        })
+    })
+}
+
+fn check_comparator(
+    ctx: GcPointer<Context>,
+    comparator: JsValue,
+    method: &str,
+) -> Result<(), JsValue> {
+    if !comparator.is_undefined() && !comparator.is_callable() {
+        return Err(JsValue::new(ctx.new_type_error(format!(
+            "TypedArray.prototype.{}: comparator must be a function or undefined",
+            method
+        ))));
+    }
+    Ok(())
+}
+
+pub fn typed_array_prototype_sort(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let comparator = args.at(0);
+    check_comparator(ctx, comparator, "sort")?;
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "sort")?);
+    let array = this.data::<JsTypedArray>();
+    let mut values = (0..array.length())
+        .map(|i| array.get(i).unwrap())
+        .collect::<Vec<_>>();
+    sort_values(ctx, &mut values, comparator)?;
+    let array = this.data::<JsTypedArray>();
+    for (i, value) in values.into_iter().enumerate() {
+        array.set(i, value.get_number());
+    }
+    Ok(JsValue::new(*this))
+}
+
+pub fn typed_array_prototype_to_sorted(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let comparator = args.at(0);
+    check_comparator(ctx, comparator, "toSorted")?;
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "toSorted")?);
+    let array = this.data::<JsTypedArray>();
+    let length = array.length();
+    letroot!(
+        result = stack,
+        JsTypedArray::with_length(ctx, array.kind(), length)?
+    );
+    let array = this.data::<JsTypedArray>();
+    let mut values = (0..length)
+        .map(|i| array.get(i).unwrap())
+        .collect::<Vec<_>>();
+    sort_values(ctx, &mut values, comparator)?;
+    let target = result.data::<JsTypedArray>();
+    for (i, value) in values.into_iter().enumerate() {
+        target.set(i, value.get_number());
+    }
+    Ok(JsValue::new(*result))
+}
+
+/// Callback of iteration methods, TypeError when it is not callable.
+fn callback_function(
+    ctx: GcPointer<Context>,
+    callback: JsValue,
+    method: &str,
+) -> Result<JsValue, JsValue> {
+    if !callback.is_callable() {
+        return Err(JsValue::new(ctx.new_type_error(format!(
+            "TypedArray.prototype.{}: callback must be a function",
+            method
+        ))));
+    }
+    Ok(callback)
+}
+
+/// Element `index` of `array`, undefined once it is out of bounds, e.g. after a callback detached
+/// the buffer.
+fn element_at(array: GcPointer<JsObject>, index: usize) -> JsValue {
+    array
+        .data::<JsTypedArray>()
+        .get(index)
+        .unwrap_or_else(JsValue::encode_undefined_value)
+}
+
+/// Invokes `callback` of an iteration method with `value`, its `index` and `array`.
+fn call_with_element(
+    ctx: GcPointer<Context>,
+    callback: JsValue,
+    this_arg: JsValue,
+    array: GcPointer<JsObject>,
+    index: usize,
+    value: JsValue,
+) -> Result<JsValue, JsValue> {
+    let mut arguments = [value, JsValue::new(index as f64), JsValue::new(array)];
+    operations::call(ctx, callback, this_arg, &mut arguments)
+}
+
+pub fn typed_array_prototype_at(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "at")?);
+    let length = this.data::<JsTypedArray>().length() as f64;
+    let relative = to_integer_or_infinity(ctx, args.at(0))?;
+    let index = if relative < 0.0 {
+        length + relative
+    } else {
+        relative
+    };
+    if index < 0.0 || index >= length {
+        return Ok(JsValue::encode_undefined_value());
+    }
+    Ok(element_at(*this, index as usize))
+}
+
+pub fn typed_array_prototype_copy_within(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        this = stack,
+        this_typed_array(ctx, args.this, "copyWithin")?
+    );
+    let length = this.data::<JsTypedArray>().length();
+    let to = relative_index(ctx, args.at(0), length, 0)?;
+    let from = relative_index(ctx, args.at(1), length, 0)?;
+    let end = relative_index(ctx, args.at(2), length, length)?;
+    let count = std::cmp::min(end.saturating_sub(from), length - to);
+    if count > 0 {
+        let array = this.data::<JsTypedArray>();
+        if !array.attached() {
+            return Err(detached_error(ctx, "copyWithin"));
+        }
+        // Bytes are moved as they are so NaN payloads survive, the ranges may overlap.
+        let element_size = array.kind().element_size();
+        unsafe {
+            let data = array.buffer().get_data_block().add(array.byte_offset());
+            std::ptr::copy(
+                data.add(from * element_size),
+                data.add(to * element_size),
+                count * element_size,
+            );
+        }
+    }
+    Ok(JsValue::new(*this))
+}
+
+/// Shared by `every` and `some`: whether the predicate result of some element is `until`.
+fn typed_array_any(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    method: &str,
+    until: bool,
+) -> Result<bool, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, method)?);
+    let length = this.data::<JsTypedArray>().length();
+    letroot!(
+        callback = stack,
+        callback_function(ctx, args.at(0), method)?
+    );
+    let this_arg = args.at(1);
+    for k in 0..length {
+        let value = element_at(*this, k);
+        let result = call_with_element(ctx, *callback, this_arg, *this, k, value)?;
+        if result.to_boolean() == until {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+pub fn typed_array_prototype_every(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(!typed_array_any(ctx, args, "every", false)?))
+}
+
+pub fn typed_array_prototype_some(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(typed_array_any(ctx, args, "some", true)?))
+}
+
+pub fn typed_array_prototype_for_each(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "forEach")?);
+    let length = this.data::<JsTypedArray>().length();
+    letroot!(
+        callback = stack,
+        callback_function(ctx, args.at(0), "forEach")?
+    );
+    let this_arg = args.at(1);
+    for k in 0..length {
+        let value = element_at(*this, k);
+        call_with_element(ctx, *callback, this_arg, *this, k, value)?;
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+/// Shared by the `find` methods: index and value of the first element that satisfies the
+/// predicate, searching from the end when `last` is set.
+fn typed_array_find(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    method: &str,
+    last: bool,
+) -> Result<Option<(usize, JsValue)>, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, method)?);
+    let length = this.data::<JsTypedArray>().length();
+    letroot!(
+        callback = stack,
+        callback_function(ctx, args.at(0), method)?
+    );
+    let this_arg = args.at(1);
+    for i in 0..length {
+        let k = if last { length - 1 - i } else { i };
+        let value = element_at(*this, k);
+        if call_with_element(ctx, *callback, this_arg, *this, k, value)?.to_boolean() {
+            return Ok(Some((k, value)));
+        }
+    }
+    Ok(None)
+}
+
+pub fn typed_array_prototype_find(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Ok(typed_array_find(ctx, args, "find", false)?
+        .map_or_else(JsValue::encode_undefined_value, |(_, value)| value))
+}
+
+pub fn typed_array_prototype_find_index(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Ok(typed_array_find(ctx, args, "findIndex", false)?
+        .map_or(JsValue::new(-1), |(k, _)| JsValue::new(k as f64)))
+}
+
+pub fn typed_array_prototype_find_last(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Ok(typed_array_find(ctx, args, "findLast", true)?
+        .map_or_else(JsValue::encode_undefined_value, |(_, value)| value))
+}
+
+pub fn typed_array_prototype_find_last_index(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Ok(typed_array_find(ctx, args, "findLastIndex", true)?
+        .map_or(JsValue::new(-1), |(k, _)| JsValue::new(k as f64)))
+}
+
+pub fn typed_array_prototype_filter(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "filter")?);
+    let length = this.data::<JsTypedArray>().length();
+    letroot!(
+        callback = stack,
+        callback_function(ctx, args.at(0), "filter")?
+    );
+    let this_arg = args.at(1);
+    // Elements are numbers or undefined, they need no rooting.
+    let mut kept = vec![];
+    for k in 0..length {
+        let value = element_at(*this, k);
+        if call_with_element(ctx, *callback, this_arg, *this, k, value)?.to_boolean() {
+            kept.push(value);
+        }
+    }
+    let kind = this.data::<JsTypedArray>().kind();
+    let mut species_args = [JsValue::new(kept.len() as f64)];
+    let result = match typed_array_species_create(ctx, &mut this, &mut species_args, "filter")? {
+        Some(result) => result,
+        None => JsTypedArray::with_length(ctx, kind, kept.len())?,
+    };
+    letroot!(result = stack, result);
+    for (i, value) in kept.into_iter().enumerate() {
+        let number = value.to_number(ctx)?;
+        result.data::<JsTypedArray>().set(i, number);
+    }
+    Ok(JsValue::new(*result))
+}
+
+pub fn typed_array_prototype_includes(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "includes")?);
+    let length = this.data::<JsTypedArray>().length();
+    if length == 0 {
+        return Ok(JsValue::new(false));
+    }
+    let start = relative_index(ctx, args.at(1), length, 0)?;
+    let search = args.at(0);
+    // A buffer detached by `fromIndex` makes every element undefined.
+    for k in start..length {
+        if JsValue::same_value_zero(element_at(*this, k), search) {
+            return Ok(JsValue::new(true));
+        }
+    }
+    Ok(JsValue::new(false))
+}
+
+pub fn typed_array_prototype_index_of(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "indexOf")?);
+    let length = this.data::<JsTypedArray>().length();
+    if length == 0 {
+        return Ok(JsValue::new(-1));
+    }
+    let start = relative_index(ctx, args.at(1), length, 0)?;
+    let search = args.at(0);
+    for k in start..length {
+        // Elements of a detached buffer are not present.
+        if let Some(value) = this.data::<JsTypedArray>().get(k) {
+            if value.strict_equal(search) {
+                return Ok(JsValue::new(k as f64));
+            }
+        }
+    }
+    Ok(JsValue::new(-1))
+}
+
+pub fn typed_array_prototype_last_index_of(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        this = stack,
+        this_typed_array(ctx, args.this, "lastIndexOf")?
+    );
+    let length = this.data::<JsTypedArray>().length();
+    if length == 0 {
+        return Ok(JsValue::new(-1));
+    }
+    let from = if args.size() > 1 {
+        to_integer_or_infinity(ctx, args.at(1))?
+    } else {
+        length as f64 - 1.0
+    };
+    let start = if from >= 0.0 {
+        from.min(length as f64 - 1.0)
+    } else {
+        length as f64 + from
+    };
+    if start < 0.0 {
+        return Ok(JsValue::new(-1));
+    }
+    let search = args.at(0);
+    for k in (0..=start as usize).rev() {
+        if let Some(value) = this.data::<JsTypedArray>().get(k) {
+            if value.strict_equal(search) {
+                return Ok(JsValue::new(k as f64));
+            }
+        }
+    }
+    Ok(JsValue::new(-1))
+}
+
+pub fn typed_array_prototype_join(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "join")?);
+    let length = this.data::<JsTypedArray>().length();
+    let separator = if args.at(0).is_undefined() {
+        ",".to_owned()
+    } else {
+        args.at(0).to_string(ctx)?
+    };
+    let mut result = String::new();
+    for k in 0..length {
+        if k > 0 {
+            result.push_str(&separator);
+        }
+        let value = element_at(*this, k);
+        if !value.is_undefined() {
+            result.push_str(&value.to_string(ctx)?);
+        }
+    }
+    Ok(JsValue::new(JsString::new(ctx, result)))
+}
+
+pub fn typed_array_prototype_to_locale_string(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        this = stack,
+        this_typed_array(ctx, args.this, "toLocaleString")?
+    );
+    let length = this.data::<JsTypedArray>().length();
+    let mut result = String::new();
+    for k in 0..length {
+        if k > 0 {
+            result.push(',');
+        }
+        let value = element_at(*this, k);
+        if value.is_undefined() {
+            continue;
+        }
+        letroot!(object = stack, value.to_object(ctx)?);
+        let method = object.get(ctx, "toLocaleString".intern())?;
+        let string = operations::call(ctx, method, value, &mut [args.at(0), args.at(1)])?;
+        result.push_str(&string.to_string(ctx)?);
+    }
+    Ok(JsValue::new(JsString::new(ctx, result)))
+}
+
+/// Shared by `reduce` and `reduceRight`, `right` folds from the last element.
+fn typed_array_reduce(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    method: &str,
+    right: bool,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, method)?);
+    let length = this.data::<JsTypedArray>().length();
+    letroot!(
+        callback = stack,
+        callback_function(ctx, args.at(0), method)?
+    );
+    let mut indices: Box<dyn Iterator<Item = usize>> = if right {
+        Box::new((0..length).rev())
+    } else {
+        Box::new(0..length)
+    };
+    let initial = if args.size() > 1 {
+        args.at(1)
+    } else {
+        match indices.next() {
+            Some(k) => element_at(*this, k),
+            None => {
+                return Err(JsValue::new(ctx.new_type_error(format!(
+                    "TypedArray.prototype.{}: empty array with no initial value",
+                    method
+                ))))
+            }
+        }
+    };
+    letroot!(accumulator = stack, initial);
+    for k in indices {
+        let value = element_at(*this, k);
+        let mut arguments = [
+            *accumulator,
+            value,
+            JsValue::new(k as f64),
+            JsValue::new(*this),
+        ];
+        *accumulator = operations::call(
+            ctx,
+            *callback,
+            JsValue::encode_undefined_value(),
+            &mut arguments,
+        )?;
+    }
+    Ok(*accumulator)
+}
+
+pub fn typed_array_prototype_reduce(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    typed_array_reduce(ctx, args, "reduce", false)
+}
+
+pub fn typed_array_prototype_reduce_right(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    typed_array_reduce(ctx, args, "reduceRight", true)
+}
+
+pub fn typed_array_prototype_reverse(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let this = this_typed_array(ctx, args.this, "reverse")?;
+    let array = this.data::<JsTypedArray>();
+    let length = array.length();
+    let element_size = array.kind().element_size();
+    unsafe {
+        let data = array.buffer().get_data_block().add(array.byte_offset());
+        for lower in 0..length / 2 {
+            let upper = length - 1 - lower;
+            std::ptr::swap_nonoverlapping(
+                data.add(lower * element_size),
+                data.add(upper * element_size),
+                element_size,
+            );
+        }
+    }
+    Ok(JsValue::new(this))
+}
+
+pub fn typed_array_prototype_to_reversed(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(
+        this = stack,
+        this_typed_array(ctx, args.this, "toReversed")?
+    );
+    let array = this.data::<JsTypedArray>();
+    let length = array.length();
+    let result = JsTypedArray::with_length(ctx, array.kind(), length)?;
+    let source = this.data::<JsTypedArray>();
+    let target = result.data::<JsTypedArray>();
+    for k in 0..length {
+        target.set(k, source.get(length - 1 - k).unwrap().get_number());
+    }
+    Ok(JsValue::new(result))
+}
+
+pub fn typed_array_prototype_with(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(this = stack, this_typed_array(ctx, args.this, "with")?);
+    let length = this.data::<JsTypedArray>().length();
+    let relative = to_integer_or_infinity(ctx, args.at(0))?;
+    let index = if relative < 0.0 {
+        length as f64 + relative
+    } else {
+        relative
+    };
+    let value = args.at(1).to_number(ctx)?;
+    // Converting `value` may have detached the buffer.
+    if index < 0.0 || index >= this.data::<JsTypedArray>().length() as f64 {
+        return Err(JsValue::new(
+            ctx.new_range_error("TypedArray.prototype.with: index out of range"),
+        ));
+    }
+    let index = index as usize;
+    let kind = this.data::<JsTypedArray>().kind();
+    let result = JsTypedArray::with_length(ctx, kind, length)?;
+    let source = this.data::<JsTypedArray>();
+    let target = result.data::<JsTypedArray>();
+    for k in 0..length {
+        if k == index {
+            target.set(k, value);
+        } else {
+            target.set(k, source.get(k).unwrap().get_number());
+        }
+    }
+    Ok(JsValue::new(result))
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_typed_array_in_global_object(mut self) -> Result<(), JsValue> {
+        let mut global_object = self.global_object();
+        for kind in TypedArrayKind::ALL.iter().copied() {
+            let structure = kind.structure(self);
+            let mut proto = *structure.prototype().unwrap();
+            let constructor = proto.get(self, S_CONSTURCTOR.intern())?;
+            global_object.define_own_property(
+                self,
+                kind.name().intern(),
+                &*DataDescriptor::new(constructor, W | C),
+                false,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn init_typed_array_in_global_data(mut self) -> Result<(), JsValue> {
+        let obj_proto = self.global_data.object_prototype.unwrap();
+        let proto_map = Structure::new_indexed(self, Some(obj_proto), false);
+        let mut proto = JsObject::new(self, &proto_map, JsObject::get_class(), ObjectTag::Ordinary);
+        let mut ctor =
+            JsNativeFunction::new(self, "TypedArray".intern(), typed_array_constructor, 0);
+
+        def_native_property!(self, ctor, prototype, proto, NONE)?;
+        def_native_property!(self, proto, constructor, ctor, W | C)?;
+        super::define_species(self, &mut ctor)?;
+
+        super::define_getter(
+            self,
+            &mut proto,
+            "buffer".intern(),
+            typed_array_prototype_buffer,
+        )?;
+        super::define_getter(
+            self,
+            &mut proto,
+            "byteLength".intern(),
+            typed_array_prototype_byte_length,
+        )?;
+        super::define_getter(
+            self,
+            &mut proto,
+            "byteOffset".intern(),
+            typed_array_prototype_byte_offset,
+        )?;
+        super::define_getter(
+            self,
+            &mut proto,
+            "length".intern(),
+            typed_array_prototype_length,
+        )?;
+        super::define_getter(
+            self,
+            &mut proto,
+            "Symbol.toStringTag".intern().private(),
+            typed_array_prototype_to_string_tag,
+        )?;
+
+        def_native_method!(
+            self,
+            proto,
+            subarray,
+            typed_array_prototype_subarray,
+            2,
+            W | C
+        )?;
+        def_native_method!(self, proto, set, typed_array_prototype_set, 1, W | C)?;
+        def_native_method!(self, proto, slice, typed_array_prototype_slice, 2, W | C)?;
+        def_native_method!(self, proto, fill, typed_array_prototype_fill, 1, W | C)?;
+        def_native_method!(self, proto, map, typed_array_prototype_map, 1, W | C)?;
+        def_native_method!(self, proto, sort, typed_array_prototype_sort, 1, W | C)?;
+        def_native_method!(self, proto, at, typed_array_prototype_at, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            copyWithin,
+            typed_array_prototype_copy_within,
+            2,
+            W | C
+        )?;
+        def_native_method!(self, proto, every, typed_array_prototype_every, 1, W | C)?;
+        def_native_method!(self, proto, some, typed_array_prototype_some, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            forEach,
+            typed_array_prototype_for_each,
+            1,
+            W | C
+        )?;
+        def_native_method!(self, proto, find, typed_array_prototype_find, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            findIndex,
+            typed_array_prototype_find_index,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            findLast,
+            typed_array_prototype_find_last,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            findLastIndex,
+            typed_array_prototype_find_last_index,
+            1,
+            W | C
+        )?;
+        def_native_method!(self, proto, filter, typed_array_prototype_filter, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            includes,
+            typed_array_prototype_includes,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            indexOf,
+            typed_array_prototype_index_of,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            lastIndexOf,
+            typed_array_prototype_last_index_of,
+            1,
+            W | C
+        )?;
+        def_native_method!(self, proto, join, typed_array_prototype_join, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toLocaleString,
+            typed_array_prototype_to_locale_string,
+            0,
+            W | C
+        )?;
+        def_native_method!(self, proto, reduce, typed_array_prototype_reduce, 1, W | C)?;
+        def_native_method!(
+            self,
+            proto,
+            reduceRight,
+            typed_array_prototype_reduce_right,
+            1,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            reverse,
+            typed_array_prototype_reverse,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            toReversed,
+            typed_array_prototype_to_reversed,
+            0,
+            W | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            toSorted,
+            typed_array_prototype_to_sorted,
+            1,
+            W | C
+        )?;
+        def_native_method!(self, proto, with, typed_array_prototype_with, 2, W | C)?;
+        // %TypedArray%.prototype.toString is the same function object as Array.prototype.toString.
+        let to_string = self
+            .global_data
+            .array_prototype
+            .unwrap()
+            .get(self, "toString".intern())?;
+        def_native_property!(self, proto, toString, to_string, W | C)?;
+        def_native_method!(self, ctor, from, typed_array_from, 1, W | C)?;
+        def_native_method!(self, ctor, of, typed_array_of, 0, W | C)?;
+
+        self.global_data.typed_array_prototype = Some(proto);
+
+        let ctor_map = Structure::new_indexed(self, Some(ctor), false);
+        for kind in TypedArrayKind::ALL.iter().copied() {
+            let kind_proto_map = Structure::new_indexed(self, Some(proto), false);
+            let mut kind_proto = JsObject::new(
+                self,
+                &kind_proto_map,
+                JsObject::get_class(),
+                ObjectTag::Ordinary,
+            );
+            let mut kind_ctor = JsNativeFunction::new_with_struct(
+                self,
+                &ctor_map,
+                kind.name().intern(),
+                typed_array_constructor_of(kind),
+                3,
+            );
+            let bytes_per_element = kind.element_size() as i32;
+            def_native_property!(self, kind_ctor, prototype, kind_proto, NONE)?;
+            def_native_property!(self, kind_proto, constructor, kind_ctor, W | C)?;
+            def_native_property!(self, kind_ctor, BYTES_PER_ELEMENT, bytes_per_element, NONE)?;
+            def_native_property!(self, kind_proto, BYTES_PER_ELEMENT, bytes_per_element, NONE)?;
+
+            let structure = Some(Structure::new_indexed(self, Some(kind_proto), false));
+            match kind {
+                TypedArrayKind::Int8 => self.global_data.int8_array_structure = structure,
+                TypedArrayKind::Uint8 => self.global_data.uint8_array_structure = structure,
+                TypedArrayKind::Uint8Clamped => {
+                    self.global_data.uint8_clamped_array_structure = structure
+                }
+                TypedArrayKind::Int16 => self.global_data.int16_array_structure = structure,
+                TypedArrayKind::Uint16 => self.global_data.uint16_array_structure = structure,
+                TypedArrayKind::Int32 => self.global_data.int32_array_structure = structure,
+                TypedArrayKind::Uint32 => self.global_data.uint32_array_structure = structure,
+                TypedArrayKind::Float32 => self.global_data.float32_array_structure = structure,
+                TypedArrayKind::Float64 => self.global_data.float64_array_structure = structure,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_typed_array_methods() {
+        assert_eval_cases(&[
+            ("var p = Object.getPrototypeOf(Int8Array.prototype); ['keys', 'entries', 'values', 'forEach'].some(function (k) { return Object.getOwnPropertyDescriptor(p, k).enumerable; })", "false"),
+            ("Int8Array.prototype.toString === Array.prototype.toString", "true"),
+            ("var a = new Int8Array([1, 2, 3]); [a.at(-1), a.at(3), a.at(-4), a.at(NaN)].join()", "3,,,1"),
+            ("new Uint8Array([1, 2, 3, 4, 5]).copyWithin(1, 0, 3).join()", "1,1,2,3,5"),
+            ("new Uint8Array([1, 2, 3, 4, 5]).copyWithin(0, 3).join()", "4,5,3,4,5"),
+            ("new Int8Array([1, 2, 3, 4]).findLast(function (v) { return v % 2; })", "3"),
+            ("new Int8Array([1, 2, 3, 4]).findLastIndex(function (v) { return v % 2; })", "2"),
+            ("new Int8Array([1, 2]).findIndex(function (v) { return v > 2; })", "-1"),
+            ("new Int8Array([1, 2]).every(function (v, k, a) { return a[k] === v; })", "true"),
+            ("new Float64Array([1.5, -0, NaN]).filter(function (v) { return v !== 1.5; }).length", "2"),
+            ("new Float32Array([NaN]).includes(NaN) + ',' + new Float32Array([NaN]).indexOf(NaN)", "true,-1"),
+            ("new Float64Array([0]).includes(-0) + ',' + new Int8Array([1]).includes(1, Infinity)", "true,false"),
+            ("var a = new Int8Array([1, 2, 1]); [a.lastIndexOf(1), a.lastIndexOf(1, -2), a.lastIndexOf(1, -4)].join()", "2,0,-1"),
+            ("new Float64Array([-0, 1.5, NaN]).join('|')", "0|1.5|NaN"),
+            ("new Int8Array([1, 2, 3]).reduceRight(function (a, v) { return a + '' + v; })", "321"),
+            ("try { new Int8Array(0).reduce(function () {}); } catch (e) { e instanceof TypeError }", "true"),
+            ("new Int16Array([1, 2, 3]).reverse().join()", "3,2,1"),
+            ("var a = new Int8Array([1, 2]); var b = a.toReversed(); a.join() + ';' + b.join()", "1,2;2,1"),
+            ("var a = new Float64Array([3, NaN, -0, 0, 1]); var b = a.toSorted(); Object.is(b[0], -0) + ';' + b.join() + ';' + a.join()", "true;0,0,1,3,NaN;3,NaN,0,0,1"),
+            ("new Int8Array([1, 2, 3]).with(-1, 300).join()", "1,2,44"),
+            ("try { new Int8Array(2).with(2, 0); } catch (e) { e instanceof RangeError }", "true"),
+            ("new Int8Array([1, 2]).toLocaleString()", "1,2"),
+            ("Int16Array.from([1, 2, 3], function (v, k) { return v * 10 + k; }).join()", "10,21,32"),
+            ("Uint8Array.from(new Set([1, 257])).join()", "1,1"),
+            ("Int8Array.from({ length: 2, 0: 5, 1: '6' }).join()", "5,6"),
+            ("try { Int8Array.from.call({}, []); } catch (e) { e instanceof TypeError }", "true"),
+            ("Float32Array.of(0.5, '2', true).join()", "0.5,2,1"),
+            ("function F() {} F.prototype = Object.create(Int8Array.prototype); var a = Reflect.construct(Int8Array, [2], F); (Object.getPrototypeOf(a) === F.prototype) + ',' + a.length", "true,2"),
+            ("function G() {} G.prototype = 1; Object.getPrototypeOf(Reflect.construct(Int8Array, [], G)) === Int8Array.prototype", "true"),
+            ("var a = new Int8Array([1, 2, 3]); a.constructor = {}; a.constructor[Symbol.species] = Uint8Array; a.filter(function (v) { return v > 1; }) instanceof Uint8Array", "true"),
+        ]);
+    }
+}
@@ -0,0 +1,918 @@
+//! `URL` and `URLSearchParams` of the WHATWG URL Standard.
+//!
+//! Hosts are converted to ASCII without the UTS 46 mapping table: non-ASCII labels are lowercased,
+//! NFC normalized and Punycode encoded, which covers everything but the exotic mappings.
+use std::{
+    intrinsics::unlikely,
+    mem::{size_of, ManuallyDrop},
+};
+
+use self::{
+    percent::{parse_form, serialize_form},
+    record::{Path, State, Url},
+};
+use crate::{
+    constant::S_CONSTURCTOR,
+    define_jsclass,
+    prelude::*,
+    vm::{
+        class::JsClass, context::Context, interpreter::SpreadValue, object::TypedJsObject,
+        operations,
+    },
+    JsTryFrom,
+};
+
+mod host;
+mod percent;
+mod record;
+
+pub struct JsUrl {
+    url: Url,
+    /// The `URLSearchParams` object of `searchParams`, created together with the URL.
+    search_params: Option<GcPointer<JsObject>>,
+}
+
+extern "C" fn url_fsz() -> usize {
+    size_of::<JsUrl>()
+}
+
+extern "C" fn url_ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize a URL");
+}
+
+extern "C" fn url_deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize a URL");
+}
+
+extern "C" fn drop_url(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<JsUrl>()) }
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn url_trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<JsUrl>().search_params.trace(tracer);
+}
+
+define_jsclass!(
+    JsUrl,
+    URL,
+    Object,
+    Some(drop_url),
+    Some(url_trace),
+    Some(url_deser),
+    Some(url_ser),
+    Some(url_fsz)
+);
+
+impl JsClass for JsUrl {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+pub struct JsUrlSearchParams {
+    list: Vec<(String, String)>,
+    /// The `URL` whose query this object reflects.
+    url: Option<GcPointer<JsObject>>,
+}
+
+extern "C" fn search_params_fsz() -> usize {
+    size_of::<JsUrlSearchParams>()
+}
+
+extern "C" fn search_params_ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize a URLSearchParams");
+}
+
+extern "C" fn search_params_deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize a URLSearchParams");
+}
+
+extern "C" fn drop_search_params(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<JsUrlSearchParams>()) }
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn search_params_trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<JsUrlSearchParams>().url.trace(tracer);
+}
+
+define_jsclass!(
+    JsUrlSearchParams,
+    URLSearchParams,
+    Object,
+    Some(drop_search_params),
+    Some(search_params_trace),
+    Some(search_params_deser),
+    Some(search_params_ser),
+    Some(search_params_fsz)
+);
+
+impl JsClass for JsUrlSearchParams {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+impl JsUrlSearchParams {
+    /// The update steps, the query of the associated URL follows the list.
+    fn update(&mut self) {
+        if let Some(url) = self.url {
+            let url = &mut url.data::<JsUrl>().url;
+            let query = serialize_form(&self.list);
+            if query.is_empty() {
+                url.query = None;
+                url.strip_trailing_spaces_from_opaque_path();
+            } else {
+                url.query = Some(query);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum IterationKind {
+    Key,
+    Value,
+    KeyValue,
+}
+
+/// `URLSearchParams` iterators, they see entries appended or removed during iteration.
+pub struct JsUrlSearchParamsIterator {
+    /// `None` once the iterator is exhausted.
+    params: Option<GcPointer<JsObject>>,
+    index: usize,
+    kind: IterationKind,
+}
+
+extern "C" fn iterator_fsz() -> usize {
+    size_of::<JsUrlSearchParamsIterator>()
+}
+
+extern "C" fn iterator_ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize a URLSearchParams Iterator");
+}
+
+extern "C" fn iterator_deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize a URLSearchParams Iterator");
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn iterator_trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<JsUrlSearchParamsIterator>().params.trace(tracer);
+}
+
+define_jsclass!(
+    JsUrlSearchParamsIterator,
+    URLSearchParamsIterator,
+    Object,
+    None,
+    Some(iterator_trace),
+    Some(iterator_deser),
+    Some(iterator_ser),
+    Some(iterator_fsz)
+);
+
+impl JsClass for JsUrlSearchParamsIterator {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+fn type_error(ctx: GcPointer<Context>, message: impl AsRef<str>) -> JsValue {
+    JsValue::new(ctx.new_type_error(message))
+}
+
+fn string_value(ctx: GcPointer<Context>, string: impl AsRef<str>) -> JsValue {
+    JsValue::new(JsString::new(ctx, string))
+}
+
+/// Prototype of the object a constructor creates, from new.target so subclasses keep theirs.
+fn constructor_prototype(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    name: &str,
+) -> Result<Option<GcPointer<JsObject>>, JsValue> {
+    if unlikely(!args.ctor_call) {
+        return Err(type_error(
+            ctx,
+            format!("{} constructor requires 'new'", name),
+        ));
+    }
+    if args.this.is_jsobject() {
+        Ok(args.this.get_jsobject().prototype().copied())
+    } else {
+        Ok(None)
+    }
+}
+
+fn new_object<T: JsClass>(
+    ctx: GcPointer<Context>,
+    proto: Option<GcPointer<JsObject>>,
+    data: T,
+) -> GcPointer<JsObject> {
+    let stack = ctx.shadowstack();
+    letroot!(structure = stack, Structure::new_indexed(ctx, proto, false));
+    let object = JsObject::new(ctx, &structure, T::class(), ObjectTag::Ordinary);
+    *object.data::<T>() = ManuallyDrop::new(data);
+    object
+}
+
+/// The API URL parser with JS arguments, `None` when `url` or `base` do not parse.
+fn parse_url(ctx: GcPointer<Context>, url: JsValue, base: JsValue) -> Result<Option<Url>, JsValue> {
+    let url = url.to_string(ctx)?;
+    if base.is_undefined() {
+        return Ok(Url::parse(&url, None));
+    }
+    let base = base.to_string(ctx)?;
+    Ok(Url::parse(&base, None).and_then(|base| Url::parse(&url, Some(&base))))
+}
+
+/// A `URL` object together with its `URLSearchParams` object.
+fn new_url(ctx: GcPointer<Context>, proto: Option<GcPointer<JsObject>>, url: Url) -> JsValue {
+    let list = parse_form(url.query.as_deref().unwrap_or(""));
+    let stack = ctx.shadowstack();
+    letroot!(
+        object = stack,
+        new_object(
+            ctx,
+            proto,
+            JsUrl {
+                url,
+                search_params: None,
+            },
+        )
+    );
+    let search_params = new_object(
+        ctx,
+        ctx.global_data.url_search_params_prototype,
+        JsUrlSearchParams {
+            list,
+            url: Some(*object),
+        },
+    );
+    object.data::<JsUrl>().search_params = Some(search_params);
+    JsValue::new(*object)
+}
+
+fn this_url(ctx: GcPointer<Context>, args: &Arguments) -> Result<TypedJsObject<JsUrl>, JsValue> {
+    TypedJsObject::<JsUrl>::try_from(ctx, args.this)
+}
+
+fn this_search_params(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<TypedJsObject<JsUrlSearchParams>, JsValue> {
+    TypedJsObject::<JsUrlSearchParams>::try_from(ctx, args.this)
+}
+
+pub fn url_constructor(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let proto = constructor_prototype(ctx, args, "URL")?;
+    match parse_url(ctx, args.at(0), args.at(1))? {
+        Some(url) => Ok(new_url(ctx, proto, url)),
+        None => Err(type_error(ctx, "Invalid URL")),
+    }
+}
+
+pub fn url_can_parse(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    Ok(JsValue::new(
+        parse_url(ctx, args.at(0), args.at(1))?.is_some(),
+    ))
+}
+
+pub fn url_parse(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    match parse_url(ctx, args.at(0), args.at(1))? {
+        Some(url) => Ok(new_url(ctx, ctx.global_data.url_prototype, url)),
+        None => Ok(JsValue::encode_null_value()),
+    }
+}
+
+pub fn url_href(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    Ok(string_value(ctx, url.url.serialize(false)))
+}
+
+pub fn url_set_href(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut url = this_url(ctx, args)?;
+    let href = args.at(0).to_string(ctx)?;
+    url.url = Url::parse(&href, None).ok_or_else(|| type_error(ctx, "Invalid URL"))?;
+    let list = parse_form(url.url.query.as_deref().unwrap_or(""));
+    url.search_params.unwrap().data::<JsUrlSearchParams>().list = list;
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_origin(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    Ok(string_value(ctx, url.url.origin()))
+}
+
+pub fn url_protocol(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    Ok(string_value(ctx, format!("{}:", url.url.scheme)))
+}
+
+pub fn url_set_protocol(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut url = this_url(ctx, args)?;
+    let protocol = format!("{}:", args.at(0).to_string(ctx)?);
+    let _ = url.url.parse_with_override(&protocol, State::SchemeStart);
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_username(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    Ok(string_value(ctx, &url.url.username))
+}
+
+pub fn url_set_username(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut url = this_url(ctx, args)?;
+    let username = args.at(0).to_string(ctx)?;
+    if !url.url.cannot_have_credentials_or_port() {
+        url.url.username = percent::encode(&username, percent::USERINFO);
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_password(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    Ok(string_value(ctx, &url.url.password))
+}
+
+pub fn url_set_password(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut url = this_url(ctx, args)?;
+    let password = args.at(0).to_string(ctx)?;
+    if !url.url.cannot_have_credentials_or_port() {
+        url.url.password = percent::encode(&password, percent::USERINFO);
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_host(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    Ok(string_value(ctx, url.url.host_string()))
+}
+
+pub fn url_set_host(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut url = this_url(ctx, args)?;
+    let host = args.at(0).to_string(ctx)?;
+    if !url.url.has_opaque_path() {
+        let _ = url.url.parse_with_override(&host, State::Host);
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_hostname(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    let hostname = url.url.host.as_ref().map(ToString::to_string);
+    Ok(string_value(ctx, hostname.unwrap_or_default()))
+}
+
+pub fn url_set_hostname(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut url = this_url(ctx, args)?;
+    let hostname = args.at(0).to_string(ctx)?;
+    if !url.url.has_opaque_path() {
+        let _ = url.url.parse_with_override(&hostname, State::Hostname);
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_port(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    let port = url.url.port.map(|port| port.to_string());
+    Ok(string_value(ctx, port.unwrap_or_default()))
+}
+
+pub fn url_set_port(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut url = this_url(ctx, args)?;
+    let port = args.at(0).to_string(ctx)?;
+    if url.url.cannot_have_credentials_or_port() {
+        return Ok(JsValue::encode_undefined_value());
+    }
+    if port.is_empty() {
+        url.url.port = None;
+    } else {
+        let _ = url.url.parse_with_override(&port, State::Port);
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_pathname(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    Ok(string_value(ctx, url.url.path_string()))
+}
+
+pub fn url_set_pathname(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut url = this_url(ctx, args)?;
+    let pathname = args.at(0).to_string(ctx)?;
+    if !url.url.has_opaque_path() {
+        url.url.path = Path::Segments(vec![]);
+        let _ = url.url.parse_with_override(&pathname, State::PathStart);
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_search(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    match url.url.query.as_deref() {
+        None | Some("") => Ok(string_value(ctx, "")),
+        Some(query) => Ok(string_value(ctx, format!("?{}", query))),
+    }
+}
+
+pub fn url_set_search(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut url = this_url(ctx, args)?;
+    let search = args.at(0).to_string(ctx)?;
+    let mut search_params = url.search_params.unwrap();
+    if search.is_empty() {
+        url.url.query = None;
+        url.url.strip_trailing_spaces_from_opaque_path();
+        search_params.data::<JsUrlSearchParams>().list.clear();
+        return Ok(JsValue::encode_undefined_value());
+    }
+    let input = search.strip_prefix('?').unwrap_or(&search);
+    url.url.query = Some(String::new());
+    let _ = url.url.parse_with_override(input, State::Query);
+    search_params.data::<JsUrlSearchParams>().list = parse_form(input);
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_search_params(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    Ok(JsValue::new(url.search_params.unwrap()))
+}
+
+pub fn url_hash(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let url = this_url(ctx, args)?;
+    match url.url.fragment.as_deref() {
+        None | Some("") => Ok(string_value(ctx, "")),
+        Some(fragment) => Ok(string_value(ctx, format!("#{}", fragment))),
+    }
+}
+
+pub fn url_set_hash(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    let mut url = this_url(ctx, args)?;
+    let hash = args.at(0).to_string(ctx)?;
+    if hash.is_empty() {
+        url.url.fragment = None;
+        url.url.strip_trailing_spaces_from_opaque_path();
+        return Ok(JsValue::encode_undefined_value());
+    }
+    let input = hash.strip_prefix('#').unwrap_or(&hash);
+    url.url.fragment = Some(String::new());
+    let _ = url.url.parse_with_override(input, State::Fragment);
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_search_params_constructor(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let proto = constructor_prototype(ctx, args, "URLSearchParams")?;
+    let init = args.at(0);
+    let list = if init.is_jsobject() {
+        let stack = ctx.shadowstack();
+        letroot!(object = stack, init.get_jsobject());
+        let iterator = object.get(ctx, "Symbol.iterator".intern().private())?;
+        if iterator.is_callable() {
+            pairs_from_iterable(ctx, init)?
+        } else {
+            pairs_from_record(ctx, &mut object)?
+        }
+    } else if init.is_undefined() {
+        vec![]
+    } else {
+        let init = init.to_string(ctx)?;
+        parse_form(init.strip_prefix('?').unwrap_or(&init))
+    };
+    Ok(JsValue::new(new_object(
+        ctx,
+        proto,
+        JsUrlSearchParams { list, url: None },
+    )))
+}
+
+/// Name-value pairs of `sequence<sequence<USVString>>` init.
+fn pairs_from_iterable(
+    ctx: GcPointer<Context>,
+    init: JsValue,
+) -> Result<Vec<(String, String)>, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(pairs = stack, SpreadValue::new(ctx, init)?);
+    let mut list = Vec::with_capacity(pairs.array.len());
+    for i in 0..pairs.array.len() {
+        let pair = pairs.array[i];
+        if unlikely(!pair.is_jsobject()) {
+            return Err(type_error(
+                ctx,
+                "URLSearchParams: each name-value pair must be an iterable object",
+            ));
+        }
+        letroot!(pair = stack, SpreadValue::new(ctx, pair)?);
+        if unlikely(pair.array.len() != 2) {
+            return Err(type_error(
+                ctx,
+                "URLSearchParams: each name-value pair must have exactly two elements",
+            ));
+        }
+        list.push((pair.array[0].to_string(ctx)?, pair.array[1].to_string(ctx)?));
+    }
+    Ok(list)
+}
+
+/// Name-value pairs of `record<USVString, USVString>` init, the enumerable own string keys.
+fn pairs_from_record(
+    ctx: GcPointer<Context>,
+    object: &mut GcPointer<JsObject>,
+) -> Result<Vec<(String, String)>, JsValue> {
+    let mut list = vec![];
+    for key in operations::own_property_keys(ctx, object)? {
+        if matches!(key, Symbol::Private(_)) {
+            continue;
+        }
+        match operations::get_own_property(ctx, object, key)? {
+            Some(desc) if desc.is_enumerable() => {
+                let value = object.get(ctx, key)?.to_string(ctx)?;
+                list.push((ctx.description(key), value));
+            }
+            _ => {}
+        }
+    }
+    Ok(list)
+}
+
+pub fn url_search_params_size(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let params = this_search_params(ctx, args)?;
+    Ok(JsValue::new(params.list.len() as u32))
+}
+
+pub fn url_search_params_append(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut params = this_search_params(ctx, args)?;
+    let name = args.at(0).to_string(ctx)?;
+    let value = args.at(1).to_string(ctx)?;
+    params.list.push((name, value));
+    params.update();
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_search_params_delete(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut params = this_search_params(ctx, args)?;
+    let name = args.at(0).to_string(ctx)?;
+    if args.at(1).is_undefined() {
+        params.list.retain(|(n, _)| *n != name);
+    } else {
+        let value = args.at(1).to_string(ctx)?;
+        params.list.retain(|(n, v)| *n != name || *v != value);
+    }
+    params.update();
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_search_params_get(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let params = this_search_params(ctx, args)?;
+    let name = args.at(0).to_string(ctx)?;
+    match params.list.iter().find(|(n, _)| *n == name) {
+        Some((_, value)) => Ok(string_value(ctx, value)),
+        None => Ok(JsValue::encode_null_value()),
+    }
+}
+
+pub fn url_search_params_get_all(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let params = this_search_params(ctx, args)?;
+    let name = args.at(0).to_string(ctx)?;
+    let stack = ctx.shadowstack();
+    letroot!(values = stack, Vec::<JsValue>::new());
+    for (_, value) in params.list.iter().filter(|(n, _)| *n == name) {
+        values.push(string_value(ctx, value));
+    }
+    Ok(JsValue::new(JsArray::from_slice(ctx, &values)))
+}
+
+pub fn url_search_params_has(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let params = this_search_params(ctx, args)?;
+    let name = args.at(0).to_string(ctx)?;
+    let has = if args.at(1).is_undefined() {
+        params.list.iter().any(|(n, _)| *n == name)
+    } else {
+        let value = args.at(1).to_string(ctx)?;
+        params.list.iter().any(|(n, v)| *n == name && *v == value)
+    };
+    Ok(JsValue::new(has))
+}
+
+pub fn url_search_params_set(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut params = this_search_params(ctx, args)?;
+    let name = args.at(0).to_string(ctx)?;
+    let value = args.at(1).to_string(ctx)?;
+    match params.list.iter().position(|(n, _)| *n == name) {
+        Some(first) => {
+            params.list[first].1 = value;
+            let mut i = 0;
+            params.list.retain(|(n, _)| {
+                i += 1;
+                i - 1 <= first || *n != name
+            });
+        }
+        None => params.list.push((name, value)),
+    }
+    params.update();
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_search_params_sort(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let mut params = this_search_params(ctx, args)?;
+    // Stable and by code units, names with astral characters sort the way they do in JS.
+    params
+        .list
+        .sort_by(|(x, _), (y, _)| x.encode_utf16().cmp(y.encode_utf16()));
+    params.update();
+    Ok(JsValue::encode_undefined_value())
+}
+
+pub fn url_search_params_to_string(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let params = this_search_params(ctx, args)?;
+    Ok(string_value(ctx, serialize_form(&params.list)))
+}
+
+pub fn url_search_params_for_each(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let params = this_search_params(ctx, args)?;
+    let callback = args.at(0);
+    if unlikely(!callback.is_callable()) {
+        return Err(type_error(
+            ctx,
+            "URLSearchParams.prototype.forEach: callback is not a function",
+        ));
+    }
+    let mut i = 0;
+    // The list is looked up again on every step, the callback may change it.
+    while let Some((name, value)) = params.list.get(i).cloned() {
+        let mut arguments = [string_value(ctx, value), string_value(ctx, name), args.this];
+        operations::call(ctx, callback, args.at(1), &mut arguments)?;
+        i += 1;
+    }
+    Ok(JsValue::encode_undefined_value())
+}
+
+fn new_iterator(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+    kind: IterationKind,
+) -> Result<JsValue, JsValue> {
+    let params = this_search_params(ctx, args)?;
+    let structure = ctx
+        .global_data()
+        .url_search_params_iterator_structure
+        .unwrap();
+    let mut obj = JsObject::new(
+        ctx,
+        &structure,
+        JsUrlSearchParamsIterator::get_class(),
+        ObjectTag::Ordinary,
+    );
+    *obj.data::<JsUrlSearchParamsIterator>() = ManuallyDrop::new(JsUrlSearchParamsIterator {
+        params: Some(params.object()),
+        index: 0,
+        kind,
+    });
+    Ok(JsValue::new(obj))
+}
+
+pub fn url_search_params_keys(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    new_iterator(ctx, args, IterationKind::Key)
+}
+
+pub fn url_search_params_values(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    new_iterator(ctx, args, IterationKind::Value)
+}
+
+pub fn url_search_params_entries(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    new_iterator(ctx, args, IterationKind::KeyValue)
+}
+
+pub fn url_search_params_iterator_next(
+    ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    let this = args.this;
+    if unlikely(
+        !this.is_jsobject()
+            || !this
+                .get_jsobject()
+                .is_class(JsUrlSearchParamsIterator::get_class()),
+    ) {
+        return Err(type_error(
+            ctx,
+            "URLSearchParams Iterator.prototype.next requires that 'this' be a URLSearchParams Iterator",
+        ));
+    }
+    let iterator = this.get_jsobject();
+    let iterator = iterator.data::<JsUrlSearchParamsIterator>();
+    let entry = iterator.params.and_then(|params| {
+        let list = &params.data::<JsUrlSearchParams>().list;
+        list.get(iterator.index).cloned()
+    });
+    let stack = ctx.shadowstack();
+    letroot!(result = stack, JsObject::new_empty(ctx));
+    let (value, done) = match entry {
+        Some((name, value)) => {
+            iterator.index += 1;
+            let value = match iterator.kind {
+                IterationKind::Key => string_value(ctx, name),
+                IterationKind::Value => string_value(ctx, value),
+                IterationKind::KeyValue => {
+                    letroot!(name = stack, string_value(ctx, name));
+                    let value = string_value(ctx, value);
+                    JsValue::new(JsArray::from_slice(ctx, &[*name, value]))
+                }
+            };
+            (value, false)
+        }
+        None => {
+            iterator.params = None;
+            (JsValue::encode_undefined_value(), true)
+        }
+    };
+    result.put(ctx, "value".intern(), value, false)?;
+    result.put(ctx, "done".intern(), JsValue::new(done), false)?;
+    Ok(JsValue::new(*result))
+}
+
+pub fn url_search_params_iterator_iterator(
+    _ctx: GcPointer<Context>,
+    args: &Arguments,
+) -> Result<JsValue, JsValue> {
+    Ok(args.this)
+}
+
+pub fn url_to_string(ctx: GcPointer<Context>, args: &Arguments) -> Result<JsValue, JsValue> {
+    url_href(ctx, args)
+}
+
+impl GcPointer<Context> {
+    pub(crate) fn init_url_in_global_object(mut self) -> Result<(), JsValue> {
+        let mut global_object = self.global_object();
+        let ctor = self
+            .global_data
+            .url_prototype
+            .unwrap()
+            .get(self, S_CONSTURCTOR.intern())?;
+        def_native_property!(self, global_object, URL, ctor, W | C)?;
+        let ctor = self
+            .global_data
+            .url_search_params_prototype
+            .unwrap()
+            .get(self, S_CONSTURCTOR.intern())?;
+        def_native_property!(self, global_object, URLSearchParams, ctor, W | C)?;
+        Ok(())
+    }
+
+    pub(crate) fn init_url_in_global_data(mut self) -> Result<(), JsValue> {
+        let (mut proto, mut ctor) = self.new_url_class("URL", url_constructor, 1)?;
+        def_native_method!(self, ctor, canParse, url_can_parse, 1, W | C)?;
+        def_native_method!(self, ctor, parse, url_parse, 1, W | C)?;
+        def_native_accessor!(self, proto, href, url_href, url_set_href, E | C)?;
+        def_native_accessor!(self, proto, origin, url_origin, E | C)?;
+        def_native_accessor!(self, proto, protocol, url_protocol, url_set_protocol, E | C)?;
+        def_native_accessor!(self, proto, username, url_username, url_set_username, E | C)?;
+        def_native_accessor!(self, proto, password, url_password, url_set_password, E | C)?;
+        def_native_accessor!(self, proto, host, url_host, url_set_host, E | C)?;
+        def_native_accessor!(self, proto, hostname, url_hostname, url_set_hostname, E | C)?;
+        def_native_accessor!(self, proto, port, url_port, url_set_port, E | C)?;
+        def_native_accessor!(self, proto, pathname, url_pathname, url_set_pathname, E | C)?;
+        def_native_accessor!(self, proto, search, url_search, url_set_search, E | C)?;
+        def_native_accessor!(self, proto, searchParams, url_search_params, E | C)?;
+        def_native_accessor!(self, proto, hash, url_hash, url_set_hash, E | C)?;
+        def_native_method!(self, proto, toString, url_to_string, 0, W | E | C)?;
+        def_native_method!(self, proto, toJSON, url_to_string, 0, W | E | C)?;
+        self.global_data.url_prototype = Some(proto);
+
+        let (mut proto, _) =
+            self.new_url_class("URLSearchParams", url_search_params_constructor, 0)?;
+        def_native_accessor!(self, proto, size, url_search_params_size, E | C)?;
+        def_native_method!(self, proto, append, url_search_params_append, 2, W | E | C)?;
+        def_native_method!(self, proto, delete, url_search_params_delete, 1, W | E | C)?;
+        def_native_method!(self, proto, get, url_search_params_get, 1, W | E | C)?;
+        def_native_method!(self, proto, getAll, url_search_params_get_all, 1, W | E | C)?;
+        def_native_method!(self, proto, has, url_search_params_has, 1, W | E | C)?;
+        def_native_method!(self, proto, set, url_search_params_set, 2, W | E | C)?;
+        def_native_method!(self, proto, sort, url_search_params_sort, 0, W | E | C)?;
+        def_native_method!(
+            self,
+            proto,
+            toString,
+            url_search_params_to_string,
+            0,
+            W | E | C
+        )?;
+        def_native_method!(
+            self,
+            proto,
+            forEach,
+            url_search_params_for_each,
+            1,
+            W | E | C
+        )?;
+        def_native_method!(self, proto, keys, url_search_params_keys, 0, W | E | C)?;
+        def_native_method!(self, proto, values, url_search_params_values, 0, W | E | C)?;
+        let entries = JsNativeFunction::new(self, "entries".intern(), url_search_params_entries, 0);
+        def_native_property!(self, proto, entries, entries, W | E | C)?;
+        let iterator = "Symbol.iterator".intern().private();
+        proto.define_own_property(
+            self,
+            iterator,
+            &*DataDescriptor::new(JsValue::new(entries), W | C),
+            false,
+        )?;
+        self.global_data.url_search_params_prototype = Some(proto);
+
+        let obj_proto = self.global_data().get_object_prototype();
+        let structure = Structure::new_unique_indexed(self, Some(obj_proto), false);
+        let mut iter_proto =
+            JsObject::new(self, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+        def_native_method!(
+            self,
+            iter_proto,
+            next,
+            url_search_params_iterator_next,
+            0,
+            W | C
+        )?;
+        let func = JsNativeFunction::new(self, iterator, url_search_params_iterator_iterator, 0);
+        iter_proto.define_own_property(
+            self,
+            iterator,
+            &*DataDescriptor::new(JsValue::new(func), W | C),
+            false,
+        )?;
+        let tag = JsString::new(self, "URLSearchParams Iterator");
+        iter_proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+        self.global_data.url_search_params_iterator_structure =
+            Some(Structure::new_indexed(self, Some(iter_proto), false));
+        Ok(())
+    }
+
+    /// Prototype object and constructor of `URL` or `URLSearchParams`.
+    fn new_url_class(
+        self,
+        name: &str,
+        constructor: JsAPI,
+        length: u32,
+    ) -> Result<(GcPointer<JsObject>, GcPointer<JsObject>), JsValue> {
+        let obj_proto = self.global_data().get_object_prototype();
+        let structure = Structure::new_unique_indexed(self, Some(obj_proto), false);
+        let mut proto = JsObject::new(self, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+        let mut ctor = JsNativeFunction::new(self, name.intern(), constructor, length);
+        def_native_property!(self, ctor, prototype, proto, NONE)?;
+        def_native_property!(self, proto, constructor, ctor, W | C)?;
+        let tag = JsString::new(self, name);
+        proto.define_own_property(
+            self,
+            "Symbol.toStringTag".intern().private(),
+            &*DataDescriptor::new(JsValue::new(tag), C),
+            false,
+        )?;
+        Ok((proto, ctor))
+    }
+}
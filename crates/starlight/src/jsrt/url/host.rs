@@ -0,0 +1,453 @@
+//! The host parser and serializer.
+use std::fmt::{self, Display, Formatter, Write};
+
+use unicode_normalization::UnicodeNormalization;
+
+use super::percent;
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum Host {
+    Domain(String),
+    Ipv4(u32),
+    Ipv6([u16; 8]),
+    Opaque(String),
+    Empty,
+}
+
+impl Display for Host {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Host::Domain(domain) | Host::Opaque(domain) => f.write_str(domain),
+            Host::Ipv4(address) => {
+                let [a, b, c, d] = address.to_be_bytes();
+                write!(f, "{}.{}.{}.{}", a, b, c, d)
+            }
+            Host::Ipv6(pieces) => {
+                // The first longest run of two or more zero pieces is compressed.
+                let mut compress = None;
+                let mut longest = 1;
+                let mut i = 0;
+                while i < 8 {
+                    let run = pieces[i..].iter().take_while(|&&piece| piece == 0).count();
+                    if run > longest {
+                        longest = run;
+                        compress = Some(i);
+                    }
+                    i += run.max(1);
+                }
+                f.write_char('[')?;
+                let mut i = 0;
+                while i < 8 {
+                    if Some(i) == compress {
+                        f.write_str(if i == 0 { "::" } else { ":" })?;
+                        i += longest;
+                        continue;
+                    }
+                    write!(f, "{:x}", pieces[i])?;
+                    if i != 7 {
+                        f.write_char(':')?;
+                    }
+                    i += 1;
+                }
+                f.write_char(']')
+            }
+            Host::Empty => Ok(()),
+        }
+    }
+}
+
+fn is_forbidden_host_code_point(c: char) -> bool {
+    matches!(
+        c,
+        '\0' | '\t'
+            | '\n'
+            | '\r'
+            | ' '
+            | '#'
+            | '/'
+            | ':'
+            | '<'
+            | '>'
+            | '?'
+            | '@'
+            | '['
+            | '\\'
+            | ']'
+            | '^'
+            | '|'
+    )
+}
+
+fn is_forbidden_domain_code_point(c: char) -> bool {
+    is_forbidden_host_code_point(c) || c <= '\u{1f}' || c == '%' || c == '\u{7f}'
+}
+
+/// The host parser, `None` is failure.
+pub fn parse(input: &str, is_opaque: bool) -> Option<Host> {
+    if let Some(rest) = input.strip_prefix('[') {
+        return parse_ipv6(rest.strip_suffix(']')?).map(Host::Ipv6);
+    }
+    if is_opaque {
+        if input.chars().any(is_forbidden_host_code_point) {
+            return None;
+        }
+        return Some(Host::Opaque(percent::encode(input, percent::C0_CONTROL)));
+    }
+    let domain = String::from_utf8_lossy(&percent::decode(input.as_bytes())).into_owned();
+    let ascii_domain = domain_to_ascii(&domain)?;
+    if ascii_domain.chars().any(is_forbidden_domain_code_point) {
+        return None;
+    }
+    if ends_in_a_number(&ascii_domain) {
+        return parse_ipv4(&ascii_domain).map(Host::Ipv4);
+    }
+    Some(Host::Domain(ascii_domain))
+}
+
+/// domain to ASCII without the UTS 46 mapping table: labels are lowercased and NFC normalized,
+/// labels that are not ASCII then are Punycode encoded.
+fn domain_to_ascii(domain: &str) -> Option<String> {
+    if domain.is_ascii() {
+        return Some(domain.to_ascii_lowercase());
+    }
+    let domain = domain
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            '\u{3002}' | '\u{ff0e}' | '\u{ff61}' => '.',
+            c => c,
+        })
+        .nfc()
+        .collect::<String>();
+    let labels = domain
+        .split('.')
+        .map(|label| {
+            if label.is_ascii() {
+                Some(label.to_owned())
+            } else {
+                punycode_encode(label).map(|encoded| format!("xn--{}", encoded))
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(labels.join("."))
+}
+
+/// Punycode (RFC 3492) encoding of a label.
+fn punycode_encode(label: &str) -> Option<String> {
+    const BASE: u32 = 36;
+    const T_MIN: u32 = 1;
+    const T_MAX: u32 = 26;
+    fn adapt(mut delta: u32, num_points: u32, first_time: bool) -> u32 {
+        delta /= if first_time { 700 } else { 2 };
+        delta += delta / num_points;
+        let mut k = 0;
+        while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+            delta /= BASE - T_MIN;
+            k += BASE;
+        }
+        k + (BASE - T_MIN + 1) * delta / (delta + 38)
+    }
+    fn digit(value: u32) -> char {
+        (if value < 26 {
+            b'a' + value as u8
+        } else {
+            b'0' + (value - 26) as u8
+        }) as char
+    }
+
+    let input = label.chars().map(|c| c as u32).collect::<Vec<_>>();
+    let mut output = label.chars().filter(char::is_ascii).collect::<String>();
+    let basic = output.len() as u32;
+    if basic > 0 {
+        output.push('-');
+    }
+    let mut n = 0x80;
+    let mut delta: u32 = 0;
+    let mut bias = 72;
+    let mut handled = basic;
+    while (handled as usize) < input.len() {
+        let m = *input.iter().filter(|&&c| c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for &c in &input {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = if k <= bias {
+                        T_MIN
+                    } else if k >= bias + T_MAX {
+                        T_MAX
+                    } else {
+                        k - bias
+                    };
+                    if q < t {
+                        break;
+                    }
+                    output.push(digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta += 1;
+        n += 1;
+    }
+    Some(output)
+}
+
+fn ends_in_a_number(input: &str) -> bool {
+    let mut parts = input.split('.').collect::<Vec<_>>();
+    if parts.last() == Some(&"") {
+        if parts.len() == 1 {
+            return false;
+        }
+        parts.pop();
+    }
+    let last = parts.last().unwrap();
+    (!last.is_empty() && last.bytes().all(|c| c.is_ascii_digit()))
+        || parse_ipv4_number(last).is_some()
+}
+
+fn parse_ipv4_number(mut input: &str) -> Option<u64> {
+    if input.is_empty() {
+        return None;
+    }
+    let mut radix = 10;
+    if input.len() >= 2 && (input.starts_with("0x") || input.starts_with("0X")) {
+        input = &input[2..];
+        radix = 16;
+    } else if input.len() >= 2 && input.starts_with('0') {
+        input = &input[1..];
+        radix = 8;
+    }
+    // Numbers too large for an address saturate, the IPv4 parser rejects them anyway.
+    input.chars().try_fold(0u64, |value, c| {
+        let digit = c.to_digit(radix)?;
+        Some(
+            value
+                .saturating_mul(radix as u64)
+                .saturating_add(digit as u64),
+        )
+    })
+}
+
+fn parse_ipv4(input: &str) -> Option<u32> {
+    let mut parts = input.split('.').collect::<Vec<_>>();
+    if parts.last() == Some(&"") && parts.len() > 1 {
+        parts.pop();
+    }
+    if parts.len() > 4 {
+        return None;
+    }
+    let numbers = parts
+        .into_iter()
+        .map(parse_ipv4_number)
+        .collect::<Option<Vec<_>>>()?;
+    let (&last, rest) = numbers.split_last()?;
+    if rest.iter().any(|&n| n > 255) || last >= 1 << (8 * (5 - numbers.len())) {
+        return None;
+    }
+    let mut address = last;
+    for (counter, &n) in rest.iter().enumerate() {
+        address += n << (8 * (3 - counter));
+    }
+    Some(address as u32)
+}
+
+fn parse_ipv6(input: &str) -> Option<[u16; 8]> {
+    let input = input.as_bytes();
+    let mut address = [0u16; 8];
+    let mut piece_index = 0;
+    let mut compress = None;
+    let mut pointer = 0;
+    let c = |pointer: usize| input.get(pointer).copied();
+    if c(pointer) == Some(b':') {
+        if c(pointer + 1) != Some(b':') {
+            return None;
+        }
+        pointer += 2;
+        piece_index += 1;
+        compress = Some(piece_index);
+    }
+    while c(pointer).is_some() {
+        if piece_index == 8 {
+            return None;
+        }
+        if c(pointer) == Some(b':') {
+            if compress.is_some() {
+                return None;
+            }
+            pointer += 1;
+            piece_index += 1;
+            compress = Some(piece_index);
+            continue;
+        }
+        let mut value = 0;
+        let mut length = 0;
+        while length < 4 {
+            match c(pointer).and_then(|c| (c as char).to_digit(16)) {
+                Some(digit) => value = value * 0x10 + digit,
+                None => break,
+            }
+            pointer += 1;
+            length += 1;
+        }
+        if c(pointer) == Some(b'.') {
+            if length == 0 {
+                return None;
+            }
+            pointer -= length;
+            if piece_index > 6 {
+                return None;
+            }
+            let mut numbers_seen = 0;
+            while let Some(first) = c(pointer) {
+                if numbers_seen > 0 {
+                    if first == b'.' && numbers_seen < 4 {
+                        pointer += 1;
+                    } else {
+                        return None;
+                    }
+                }
+                if !c(pointer)?.is_ascii_digit() {
+                    return None;
+                }
+                let mut ipv4_piece: Option<u16> = None;
+                while let Some(digit) = c(pointer).filter(u8::is_ascii_digit) {
+                    let number = (digit - b'0') as u16;
+                    ipv4_piece = match ipv4_piece {
+                        None => Some(number),
+                        Some(0) => return None,
+                        Some(piece) => Some(piece * 10 + number),
+                    };
+                    if ipv4_piece > Some(255) {
+                        return None;
+                    }
+                    pointer += 1;
+                }
+                address[piece_index] = address[piece_index] * 0x100 + ipv4_piece?;
+                numbers_seen += 1;
+                if numbers_seen == 2 || numbers_seen == 4 {
+                    piece_index += 1;
+                }
+            }
+            if numbers_seen != 4 {
+                return None;
+            }
+            break;
+        } else if c(pointer) == Some(b':') {
+            pointer += 1;
+            c(pointer)?;
+        } else if c(pointer).is_some() {
+            return None;
+        }
+        address[piece_index] = value as u16;
+        piece_index += 1;
+    }
+    if let Some(compress) = compress {
+        let mut swaps = piece_index - compress;
+        piece_index = 7;
+        while piece_index != 0 && swaps > 0 {
+            address.swap(piece_index, compress + swaps - 1);
+            piece_index -= 1;
+            swaps -= 1;
+        }
+    } else if piece_index != 8 {
+        return None;
+    }
+    Some(address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_domain_and_ipv4() {
+        let cases = [
+            ("EXAMPLE.com", Some("example.com")),
+            ("example.com.", Some("example.com.")),
+            ("%41.com", Some("a.com")),
+            ("example\u{3002}com", Some("example.com")),
+            ("münchen.de", Some("xn--mnchen-3ya.de")),
+            ("ÖBB.at", Some("xn--bb-eka.at")),
+            ("例え.テスト", Some("xn--r8jz45g.xn--zckzah")),
+            ("exa mple.com", None),
+            ("exa%20mple.com", None),
+            ("a%b.com", None),
+            ("a<b", None),
+            ("127.0.0.1", Some("127.0.0.1")),
+            ("1.2.3.4.", Some("1.2.3.4")),
+            ("0x7f.1", Some("127.0.0.1")),
+            ("0300.0250.0.1", Some("192.168.0.1")),
+            ("1.2.3", Some("1.2.0.3")),
+            ("4294967295", Some("255.255.255.255")),
+            ("4294967296", None),
+            ("192.168.0.257", None),
+            ("256.0.0.1", None),
+            ("1.2.3.4.5", None),
+            ("1.2.3.09", None),
+            ("foo.09", None),
+            ("foo.0x", None),
+            ("09.foo", Some("09.foo")),
+        ];
+        for (input, expected) in cases.iter() {
+            let host = parse(input, false).map(|host| host.to_string());
+            assert_eq!(host.as_deref(), *expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_ipv6() {
+        let cases = [
+            ("[::1]", Some("[::1]")),
+            ("[0:0:0:0:0:0:0:1]", Some("[::1]")),
+            ("[::]", Some("[::]")),
+            ("[1::]", Some("[1::]")),
+            ("[1:0:0:2:0:0:0:3]", Some("[1:0:0:2::3]")),
+            ("[1:0:0:2:3:0:0:4]", Some("[1::2:3:0:0:4]")),
+            ("[0:1:2:3:4:5:6:7]", Some("[0:1:2:3:4:5:6:7]")),
+            ("[ABCD::ef]", Some("[abcd::ef]")),
+            ("[::ffff:192.168.0.1]", Some("[::ffff:c0a8:1]")),
+            ("[1::2::3]", None),
+            ("[1:2:3:4:5:6:7:8:9]", None),
+            ("[1:2:3:4:5:6:7]", None),
+            ("[12345::]", None),
+            ("[::1.2.3]", None),
+            ("[::1.2.3.04]", None),
+            ("[::1.2.3.256]", None),
+            ("[:1]", None),
+            ("[1:]", None),
+            ("[::1", None),
+        ];
+        for (input, expected) in cases.iter() {
+            let host = parse(input, false).map(|host| host.to_string());
+            assert_eq!(host.as_deref(), *expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_opaque() {
+        let cases = [
+            ("EXAMPLE.com", Some("EXAMPLE.com")),
+            ("ü", Some("%C3%BC")),
+            ("%zz", Some("%zz")),
+            ("1.2.3", Some("1.2.3")),
+            ("[::1]", Some("[::1]")),
+            ("a b", None),
+            ("a<b", None),
+        ];
+        for (input, expected) in cases.iter() {
+            let host = parse(input, true).map(|host| host.to_string());
+            assert_eq!(host.as_deref(), *expected, "{}", input);
+        }
+    }
+}
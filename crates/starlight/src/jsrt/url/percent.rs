@@ -0,0 +1,98 @@
+//! Percent-encode sets, percent-decoding and the `application/x-www-form-urlencoded` format.
+
+/// A percent-encode set, the ASCII code points that are encoded in addition to the C0 controls
+/// and everything above `~`.
+#[derive(Clone, Copy)]
+pub struct EncodeSet(&'static [u8]);
+
+pub const C0_CONTROL: EncodeSet = EncodeSet(b"");
+pub const FRAGMENT: EncodeSet = EncodeSet(b" \"<>`");
+pub const QUERY: EncodeSet = EncodeSet(b" \"#<>");
+pub const SPECIAL_QUERY: EncodeSet = EncodeSet(b" \"#<>'");
+pub const PATH: EncodeSet = EncodeSet(b" \"#<>?^`{}");
+pub const USERINFO: EncodeSet = EncodeSet(b" \"#<>?^`{}/:;=@[\\]|");
+pub const FORM_URLENCODED: EncodeSet = EncodeSet(b" \"#<>?^`{}/:;=@[\\]|$%&+,!'()~");
+
+impl EncodeSet {
+    fn contains(self, c: char) -> bool {
+        c < ' ' || c > '~' || self.0.contains(&(c as u8))
+    }
+}
+
+/// UTF-8 percent-encodes `c` with `set` and appends it to `output`.
+pub fn encode_char(c: char, set: EncodeSet, output: &mut String) {
+    if !set.contains(c) {
+        output.push(c);
+        return;
+    }
+    let mut bytes = [0; 4];
+    for byte in c.encode_utf8(&mut bytes).bytes() {
+        output.push_str(&format!("%{:02X}", byte));
+    }
+}
+
+pub fn encode(input: &str, set: EncodeSet) -> String {
+    let mut output = String::with_capacity(input.len());
+    for c in input.chars() {
+        encode_char(c, set, &mut output);
+    }
+    output
+}
+
+/// Percent-decodes `input`, `%` that does not start an escape is kept as it is.
+pub fn decode(input: &[u8]) -> Vec<u8> {
+    let hex = |byte: Option<&u8>| byte.and_then(|&byte| (byte as char).to_digit(16));
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] == b'%' {
+            if let (Some(high), Some(low)) = (hex(input.get(i + 1)), hex(input.get(i + 2))) {
+                output.push((high << 4 | low) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        output.push(input[i]);
+        i += 1;
+    }
+    output
+}
+
+/// The application/x-www-form-urlencoded parser.
+pub fn parse_form(input: &str) -> Vec<(String, String)> {
+    let decode = |bytes: &str| {
+        let bytes = bytes.replace('+', " ");
+        String::from_utf8_lossy(&decode(bytes.as_bytes())).into_owned()
+    };
+    input
+        .split('&')
+        .filter(|sequence| !sequence.is_empty())
+        .map(|sequence| match sequence.find('=') {
+            Some(at) => (decode(&sequence[..at]), decode(&sequence[at + 1..])),
+            None => (decode(sequence), String::new()),
+        })
+        .collect()
+}
+
+/// The application/x-www-form-urlencoded serializer.
+pub fn serialize_form(list: &[(String, String)]) -> String {
+    let encode = |input: &str, output: &mut String| {
+        for c in input.chars() {
+            if c == ' ' {
+                output.push('+');
+            } else {
+                encode_char(c, FORM_URLENCODED, output);
+            }
+        }
+    };
+    let mut output = String::new();
+    for (name, value) in list {
+        if !output.is_empty() {
+            output.push('&');
+        }
+        encode(name, &mut output);
+        output.push('=');
+        encode(value, &mut output);
+    }
+    output
+}
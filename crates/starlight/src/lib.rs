@@ -70,10 +70,26 @@ macro_rules! def_native_property {
 
 #[macro_export]
 macro_rules! def_native_accessor {
-    ($vm: expr,$obj: expr,$name: ident,$get: expr,$name_set: ident,$set: expr) => {{
+    ($vm: expr,$obj: expr,$name: ident,$get: expr,$attr: expr) => {{
         let name = stringify!($name).intern();
-        let m = $crate::vm::function::JsNativeFunction::new($vm, name, $func, $argc);
-        $obj.put($vm, name, JsValue::new(m), true)
+        let get = $crate::vm::function::JsNativeFunction::new($vm, name, $get, 0);
+        $obj.define_own_property(
+            $vm,
+            name,
+            &*AccessorDescriptor::new(JsValue::new(get), JsValue::encode_undefined_value(), $attr),
+            false,
+        )
+    }};
+    ($vm: expr,$obj: expr,$name: ident,$get: expr,$set: expr,$attr: expr) => {{
+        let name = stringify!($name).intern();
+        let get = $crate::vm::function::JsNativeFunction::new($vm, name, $get, 0);
+        let set = $crate::vm::function::JsNativeFunction::new($vm, name, $set, 1);
+        $obj.define_own_property(
+            $vm,
+            name,
+            &*AccessorDescriptor::new(JsValue::new(get), JsValue::new(set), $attr),
+            false,
+        )
     }};
 }
 
@@ -44,6 +44,11 @@ pub struct Options {
     pub codegen_plugins: bool,
     #[structopt(long = "verboseGC", help = "Verbose GC cycle")]
     pub verbose_gc: bool,
+    #[structopt(
+        long = "timezone",
+        help = "IANA time zone or POSIX TZ string for local time, overrides TZ"
+    )]
+    pub timezone: Option<String>,
 }
 
 impl Default for Options {
@@ -61,6 +66,7 @@ impl Default for Options {
             gc_threads: 4,
             verbose_gc: false,
             codegen_plugins: false,
+            timezone: None,
         }
     }
 }
@@ -116,6 +122,11 @@ impl Options {
         self.dump_stats = enable;
         self
     }
+
+    pub fn with_timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.timezone = Some(timezone.into());
+        self
+    }
 }
 
 fn parse_size_from_str(s: &str) -> Result<usize, ParseIntError> {
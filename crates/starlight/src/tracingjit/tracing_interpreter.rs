@@ -900,10 +900,10 @@ pub unsafe fn eval(
                 }
 
                 let it = if enumerable.is_jsstring() {
-                    NativeIterator::new(rt, enumerable.get_object())
+                    NativeIterator::new(rt, enumerable.get_object())?
                 } else {
                     let obj = enumerable.to_object(rt)?;
-                    NativeIterator::new(rt, obj.as_dyn())
+                    NativeIterator::new(rt, obj.as_dyn())?
                 };
                 frame.push(JsValue::new(it));
                 assert!(ip.cast::<Opcode>().read_unaligned() == Opcode::OP_FORIN_ENUMERATE);
@@ -916,7 +916,7 @@ pub unsafe fn eval(
                     .get_object()
                     .downcast_unchecked::<NativeIterator>();
                 frame.push(JsValue::new(it));
-                if let Some(sym) = it.next() {
+                if let Some(sym) = it.next(rt) {
                     let desc = rt.description(sym);
                     frame.push(JsValue::new(JsString::new(rt, desc)));
                 } else {
@@ -14,10 +14,11 @@ use crate::{
         SimpleMarkingConstraint,
     },
     gc::{safepoint::GlobalSafepoint, snapshot::Snapshot},
+    jsrt::date::TimeZone,
     options::Options,
 };
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     ops::{Deref, DerefMut},
     u32, u8, usize,
 };
@@ -60,6 +61,7 @@ pub mod object;
 pub mod operations;
 pub mod perf;
 pub mod property_descriptor;
+pub mod proxy;
 pub mod slot;
 pub mod string;
 pub mod structure;
@@ -151,17 +153,26 @@ pub struct Runtime {
     pub(crate) eval_history: String,
     pub(crate) persistent_roots: Rc<RefCell<HashMap<usize, JsValue>>>,
     pub(crate) sched_async_func: Option<Box<dyn Fn(Box<dyn FnOnce(GcPointer<Context>)>)>>,
+    /// Job queue used when no async scheduler is installed, drained by [Runtime::run_jobs].
+    pub(crate) jobs: VecDeque<(GcPointer<Context>, Box<dyn FnOnce(GcPointer<Context>)>)>,
+    /// Set while [Runtime::run_jobs] drains the queue so that nested checkpoints do nothing.
+    pub(crate) running_jobs: bool,
+    /// Receives exceptions thrown by jobs, see [Runtime::with_uncaught_exception_handler].
+    pub(crate) uncaught_exception_func: Option<Box<dyn Fn(GcPointer<Context>, JsValue)>>,
     pub(crate) safepoint: GlobalSafepoint,
 
     pub(crate) contexts: Vec<GcPointer<Context>>,
 
     pub(crate) context_snapshot: Rc<Box<[u8]>>,
+    /// Local time zone of `Date`, loaded from tzdata once per runtime.
+    pub(crate) time_zone: TimeZone,
 }
 
 impl Runtime {
     /// initialize a Runtime with an async scheduler
     /// the async scheduler is used to asynchronously run jobs with the Runtime
     /// this can be used for things like Promises, setImmediate, async functions
+    /// without a scheduler jobs go to the built-in queue, see [Runtime::run_jobs]
     /// # Example
     /// ```rust
     /// use starlight::Platform;
@@ -187,6 +198,39 @@ impl Runtime {
         self.sched_async_func = Some(scheduler);
         self
     }
+
+    /// Installs `handler` for exceptions that jobs throw and nothing can catch, e.g. from a
+    /// `queueMicrotask` or `FinalizationRegistry` callback. Without a handler they are printed to
+    /// stderr.
+    pub fn with_uncaught_exception_handler(
+        mut self: Box<Self>,
+        handler: Box<dyn Fn(GcPointer<Context>, JsValue)>,
+    ) -> Box<Self> {
+        self.uncaught_exception_func = Some(handler);
+        self
+    }
+
+    /// Runs queued jobs in FIFO order until the queue is empty, jobs queued by a running job
+    /// run in the same call. Scripts and modules evaluated with [Context::eval] and
+    /// [Context::evalm] already do this once the execution stack is empty, embedders that call
+    /// functions directly should call it when they are done.
+    pub fn run_jobs(&mut self) {
+        if self.running_jobs {
+            return;
+        }
+        self.running_jobs = true;
+        // Clears the flag on unwind too, a panicking job would stop every later drain otherwise.
+        struct Reset(*mut bool);
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                unsafe { *self.0 = false }
+            }
+        }
+        let _reset = Reset(&mut self.running_jobs);
+        while let Some((ctx, job)) = self.jobs.pop_front() {
+            job(ctx);
+        }
+    }
     pub fn add_persistent_root(&mut self, obj: JsValue) -> PersistentRooted {
         // for PoC only, todo use something like AutoIdMap for persistent_roots
 
@@ -212,6 +256,7 @@ impl Runtime {
         options: Options,
         external_references: Option<&'static [usize]>,
     ) -> Self {
+        let time_zone = TimeZone::load(options.timezone.as_deref());
         Self {
             gc,
             options,
@@ -223,9 +268,13 @@ impl Runtime {
             eval_history: String::new(),
             persistent_roots: Default::default(),
             sched_async_func: None,
+            jobs: VecDeque::new(),
+            running_jobs: false,
+            uncaught_exception_func: None,
             codegen_plugins: HashMap::new(),
             contexts: vec![],
             context_snapshot: Rc::new(Box::new([])),
+            time_zone,
         }
     }
 
@@ -415,10 +464,49 @@ pub struct GlobalData {
     pub(crate) spread_builtin: Option<GcPointer<JsObject>>,
     pub(crate) weak_ref_structure: Option<GcPointer<Structure>>,
     pub(crate) weak_ref_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) finalization_registry_structure: Option<GcPointer<Structure>>,
+    pub(crate) finalization_registry_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) typed_array_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) int8_array_structure: Option<GcPointer<Structure>>,
+    pub(crate) uint8_array_structure: Option<GcPointer<Structure>>,
+    pub(crate) uint8_clamped_array_structure: Option<GcPointer<Structure>>,
+    pub(crate) int16_array_structure: Option<GcPointer<Structure>>,
+    pub(crate) uint16_array_structure: Option<GcPointer<Structure>>,
+    pub(crate) int32_array_structure: Option<GcPointer<Structure>>,
+    pub(crate) uint32_array_structure: Option<GcPointer<Structure>>,
+    pub(crate) float32_array_structure: Option<GcPointer<Structure>>,
+    pub(crate) float64_array_structure: Option<GcPointer<Structure>>,
     pub(crate) symbol_structure: Option<GcPointer<Structure>>,
     pub(crate) date_structure: Option<GcPointer<Structure>>,
     pub(crate) date_prototype: Option<GcPointer<JsObject>>,
     pub(crate) boolean_structure: Option<GcPointer<Structure>>,
+    pub(crate) proxy_structure: Option<GcPointer<Structure>>,
+    pub(crate) bigint_structure: Option<GcPointer<Structure>>,
+    pub(crate) bigint_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) string_iterator_structure: Option<GcPointer<Structure>>,
+    pub(crate) aggregate_error: Option<GcPointer<JsObject>>,
+    pub(crate) aggregate_error_structure: Option<GcPointer<Structure>>,
+    pub(crate) error_constructor: Option<GcPointer<JsObject>>,
+    pub(crate) call_site_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) call_site_structure: Option<GcPointer<Structure>>,
+    pub(crate) regexp_constructor: Option<GcPointer<JsObject>>,
+    pub(crate) temporal_duration_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) temporal_instant_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) temporal_plain_date_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) temporal_plain_date_time_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) temporal_plain_time_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) temporal_zoned_date_time_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) intl_collator_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) intl_date_time_format_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) intl_number_format_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) intl_plural_rules_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) url_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) url_search_params_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) url_search_params_iterator_structure: Option<GcPointer<Structure>>,
+    pub(crate) text_encoder_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) text_decoder_prototype: Option<GcPointer<JsObject>>,
+    pub(crate) map_iterator_structure: Option<GcPointer<Structure>>,
+    pub(crate) set_iterator_structure: Option<GcPointer<Structure>>,
 }
 
 impl GlobalData {
@@ -541,6 +629,25 @@ pub mod tests {
     use std::cell::RefCell;
     use std::rc::Rc;
 
+    /// Evaluates the script of each case in one context and compares the string value of its
+    /// completion with the expected string.
+    pub(crate) fn assert_eval_cases(cases: &[(&str, &str)]) {
+        Platform::initialize();
+        let mut rt = Platform::new_runtime(Options::default(), None);
+        assert_eval_cases_in(Context::new(&mut rt), cases);
+    }
+
+    /// Same as [assert_eval_cases], but evaluates in `ctx` so the runtime can have custom hooks.
+    pub(crate) fn assert_eval_cases_in(mut ctx: GcPointer<Context>, cases: &[(&str, &str)]) {
+        for (script, expected) in cases.iter() {
+            let result = ctx
+                .eval(script)
+                .and_then(|value| value.to_string(ctx))
+                .unwrap_or_else(|_| panic!("{} threw", script));
+            assert_eq!(result, *expected, "{}", script);
+        }
+    }
+
     #[test]
     fn test_simple_async() {
         // start a runtime
@@ -1,4 +1,257 @@
 /* This Source Code Form is subject to the terms of the Mozilla Public
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
-// TODO
+//! BigInt primitive values.
+//!
+//! BigInts are immutable heap cells ([JsBigInt]) so `JsValue` can hold them like strings and symbols.
+//! `BigInt` wrapper objects created by `ToObject` are [BigIntObject]s.
+use std::{cmp::Ordering, intrinsics::unlikely, mem::ManuallyDrop};
+
+use num::{BigInt, FromPrimitive, One, Signed, ToPrimitive, Zero};
+
+use super::context::Context;
+use crate::prelude::*;
+
+/// Maximum bit length of BigInt produced by left shift or `BigInt.asUintN`. Operations that would
+/// produce larger values throw RangeError instead of exhausting memory.
+pub const MAX_BIGINT_BITS: u64 = 1 << 30;
+
+pub struct JsBigInt {
+    pub(crate) value: BigInt,
+}
+
+/// Binary operators supported by [JsBigInt::binary].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BigIntOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Shl,
+    Shr,
+    UShr,
+    And,
+    Or,
+    Xor,
+}
+
+impl JsBigInt {
+    pub fn new(mut ctx: GcPointer<Context>, value: BigInt) -> GcPointer<Self> {
+        ctx.heap().allocate(Self { value })
+    }
+
+    pub fn value(&self) -> &BigInt {
+        &self.value
+    }
+
+    /// Converts BigInt to the closest number.
+    pub fn to_f64(&self) -> f64 {
+        self.value.to_f64().unwrap_or(f64::NAN)
+    }
+
+    /// NumberToBigInt. Returns `None` if `number` is not an integer.
+    pub fn from_f64(number: f64) -> Option<BigInt> {
+        if number.is_finite() && number.trunc() == number {
+            BigInt::from_f64(number)
+        } else {
+            None
+        }
+    }
+
+    /// StringToBigInt. Returns `None` if `string` is not a valid StringIntegerLiteral.
+    pub fn parse(string: &str) -> Option<BigInt> {
+        let string = string.trim_matches(|c: char| c.is_whitespace() || c == '\u{feff}');
+        if string.is_empty() {
+            return Some(BigInt::zero());
+        }
+        let (radix, digits, negative) = match string.get(0..2) {
+            Some("0x") | Some("0X") => (16, &string[2..], false),
+            Some("0o") | Some("0O") => (8, &string[2..], false),
+            Some("0b") | Some("0B") => (2, &string[2..], false),
+            _ => match string.as_bytes()[0] {
+                b'-' => (10, &string[1..], true),
+                b'+' => (10, &string[1..], false),
+                _ => (10, string, false),
+            },
+        };
+        // `parse_bytes` accepts signs and `_` separators, literals don't.
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return None;
+        }
+        let value = BigInt::parse_bytes(digits.as_bytes(), radix)?;
+        Some(if negative { -value } else { value })
+    }
+
+    /// Compares `value` with `number` without converting either of them. Returns `None` if
+    /// `number` is NaN.
+    pub fn compare_with_number(value: &BigInt, number: f64) -> Option<Ordering> {
+        if number.is_nan() {
+            return None;
+        }
+        if number.is_infinite() {
+            return Some(if number > 0.0 {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            });
+        }
+        let integral = number.trunc();
+        match value.cmp(&BigInt::from_f64(integral)?) {
+            Ordering::Equal if number > integral => Some(Ordering::Less),
+            Ordering::Equal if number < integral => Some(Ordering::Greater),
+            ordering => Some(ordering),
+        }
+    }
+
+    /// `value` modulo 2^`bits`. Returns `None` if result can't be represented.
+    pub fn as_uint_n(value: &BigInt, bits: u64) -> Option<BigInt> {
+        if !value.is_negative() && value.bits() <= bits {
+            return Some(value.clone());
+        }
+        if bits > MAX_BIGINT_BITS {
+            return None;
+        }
+        let modulus = BigInt::one() << bits as usize;
+        let result = value % &modulus;
+        Some(if result.is_negative() {
+            result + modulus
+        } else {
+            result
+        })
+    }
+
+    /// `value` modulo 2^`bits` interpreted as `bits`-wide two's complement integer.
+    pub fn as_int_n(value: &BigInt, bits: u64) -> Option<BigInt> {
+        if bits == 0 {
+            return Some(BigInt::zero());
+        }
+        if value.bits() < bits {
+            return Some(value.clone());
+        }
+        let result = Self::as_uint_n(value, bits)?;
+        if result.bits() == bits {
+            Some(result - (BigInt::one() << bits as usize))
+        } else {
+            Some(result)
+        }
+    }
+
+    /// Applies binary operator to operands converted by [JsValue::to_numeric]. Throws TypeError
+    /// when BigInt is mixed with number.
+    pub fn binary(
+        ctx: GcPointer<Context>,
+        op: BigIntOp,
+        lhs: JsValue,
+        rhs: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        if unlikely(!lhs.is_bigint() || !rhs.is_bigint()) {
+            return Err(JsValue::new(ctx.new_type_error(
+                "Cannot mix BigInt and other types, use explicit conversions",
+            )));
+        }
+        let (lhs, rhs) = (lhs.get_bigint(), rhs.get_bigint());
+        let (x, y) = (lhs.value(), rhs.value());
+        let result = match op {
+            BigIntOp::Add => x + y,
+            BigIntOp::Sub => x - y,
+            BigIntOp::Mul => x * y,
+            BigIntOp::Div | BigIntOp::Rem => {
+                if unlikely(y.is_zero()) {
+                    return Err(JsValue::new(ctx.new_range_error("Division by zero")));
+                }
+                // both truncate towards zero like JS does.
+                if op == BigIntOp::Div {
+                    x / y
+                } else {
+                    x % y
+                }
+            }
+            BigIntOp::Shl => Self::shift_left(ctx, x, y)?,
+            BigIntOp::Shr => Self::shift_left(ctx, x, &-y)?,
+            BigIntOp::UShr => {
+                return Err(JsValue::new(ctx.new_type_error(
+                    "BigInts have no unsigned right shift, use >> instead",
+                )))
+            }
+            BigIntOp::And => x & y,
+            BigIntOp::Or => x | y,
+            BigIntOp::Xor => x ^ y,
+        };
+        Ok(JsValue::new(Self::new(ctx, result)))
+    }
+
+    fn shift_left(ctx: GcPointer<Context>, x: &BigInt, shift: &BigInt) -> Result<BigInt, JsValue> {
+        if x.is_zero() {
+            return Ok(BigInt::zero());
+        }
+        if shift.is_negative() {
+            // `>>` on BigInt rounds towards negative infinity.
+            return Ok(match (-shift).to_u64() {
+                Some(n) if n < x.bits() => x >> n as usize,
+                _ if x.is_negative() => -BigInt::one(),
+                _ => BigInt::zero(),
+            });
+        }
+        match shift.to_u64() {
+            Some(n) if x.bits().saturating_add(n) <= MAX_BIGINT_BITS => Ok(x << n as usize),
+            _ => Err(JsValue::new(
+                ctx.new_range_error("Maximum BigInt size exceeded"),
+            )),
+        }
+    }
+}
+
+unsafe impl Trace for JsBigInt {}
+impl GcCell for JsBigInt {
+    fn deser_pair(&self) -> (usize, usize) {
+        (Self::deserialize as _, Self::allocate as _)
+    }
+}
+
+pub struct BigIntObject {
+    value: GcPointer<JsBigInt>,
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<BigIntObject>().value.trace(tracer);
+}
+
+extern "C" fn deser(obj: &mut JsObject, deser: &mut Deserializer) {
+    *obj.data::<BigIntObject>() = ManuallyDrop::new(BigIntObject {
+        value: unsafe { GcPointer::<JsBigInt>::deserialize_inplace(deser) },
+    });
+}
+
+extern "C" fn ser(obj: &JsObject, serializer: &mut SnapshotSerializer) {
+    obj.data::<BigIntObject>().value.serialize(serializer);
+}
+
+extern "C" fn fsz() -> usize {
+    std::mem::size_of::<BigIntObject>()
+}
+
+define_jsclass!(
+    BigIntObject,
+    BigInt,
+    Object,
+    None,
+    Some(trace),
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+impl BigIntObject {
+    pub fn new(ctx: GcPointer<Context>, value: GcPointer<JsBigInt>) -> GcPointer<JsObject> {
+        let structure = ctx.global_data().bigint_structure.unwrap();
+        let mut obj = JsObject::new(ctx, &structure, Self::get_class(), ObjectTag::Ordinary);
+        *obj.data::<Self>() = ManuallyDrop::new(Self { value });
+        obj
+    }
+
+    pub fn value(&self) -> GcPointer<JsBigInt> {
+        self.value
+    }
+}
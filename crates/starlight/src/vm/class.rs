@@ -62,7 +62,9 @@ macro_rules! js_class_list {
             Reflect, 36,
             Symbol, 37,
             NOT_CACHED, 38,
-            NUM_OF_CLASS,39
+            AggregateError, 39,
+            CallSite, 40,
+            NUM_OF_CLASS,41
         }
     };
 }
@@ -57,6 +57,7 @@ pub struct CodeBlock {
 
     pub is_constructor: bool,
 
+    /// Source locations of bytecode ranges. Ranges nest the same way statements and calls do.
     pub loc: Vec<(Range<usize>, FileLocation)>,
     pub path: Rc<str>,
     pub is_generator: bool,
@@ -72,6 +73,14 @@ unsafe impl Trace for CodeBlock {
 }
 
 impl CodeBlock {
+    /// Returns source location of the instruction at offset `pc`, the innermost range wins.
+    pub fn location(&self, pc: usize) -> Option<&FileLocation> {
+        self.loc
+            .iter()
+            .filter(|(range, _)| range.contains(&pc))
+            .min_by_key(|(range, _)| range.len())
+            .map(|(_, loc)| loc)
+    }
     /// Print bytecode to `output`.
     pub fn display_to<T: Write>(&self, output: &mut T) -> std::fmt::Result {
         unsafe {
@@ -402,6 +411,15 @@ impl CodeBlock {
                     Opcode::OP_NEG => {
                         writeln!(output, "neg")?;
                     }
+                    Opcode::OP_INC => {
+                        writeln!(output, "inc")?;
+                    }
+                    Opcode::OP_DEC => {
+                        writeln!(output, "dec")?;
+                    }
+                    Opcode::OP_TO_NUMERIC => {
+                        writeln!(output, "to_numeric")?;
+                    }
                     Opcode::OP_DELETE_BY_ID => {
                         let id = pc.cast::<u32>().read_unaligned();
                         pc = pc.add(4);
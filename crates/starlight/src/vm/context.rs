@@ -10,7 +10,7 @@ use crate::{
         shadowstack::ShadowStack,
         Heap,
     },
-    jsrt,
+    jsrt::{self, console::ConsoleState},
     vm::{
         arguments::Arguments, environment::Environment, error::JsSyntaxError,
         function::JsVMFunction, init_es_config, BufferedError,
@@ -18,7 +18,7 @@ use crate::{
 };
 
 use super::{
-    error::{JsRangeError, JsReferenceError, JsTypeError},
+    error::{CallSite, JsRangeError, JsReferenceError, JsTypeError, JsURIError},
     function::JsNativeFunction,
     global::JsGlobal,
     interpreter::{frame::CallFrame, stack::Stack},
@@ -41,7 +41,17 @@ pub struct Context {
     pub(crate) stacktrace: String,
     pub(crate) module_loader: Option<GcPointer<JsObject>>,
     pub(crate) modules: HashMap<String, ModuleKind>,
+    /// `Symbol.for` registry.
     pub(crate) symbol_table: HashMap<Symbol, GcPointer<JsSymbol>>,
+    /// Symbol values created by [JsSymbol::new].
+    pub(crate) symbols: HashMap<Symbol, GcPointer<JsSymbol>>,
+    /// Set while `Error.prepareStackTrace` runs so that errors created by it don't call it again.
+    pub(crate) preparing_stack_trace: bool,
+    /// Counters, timers and group indentation of `console`.
+    pub(crate) console: ConsoleState,
+    /// Exception thrown by a proxy trap called from an internal method that can't fail, e.g.
+    /// `has_property`. It is rethrown when the running native function returns.
+    pub(crate) pending_exception: Option<JsValue>,
 }
 impl Context {
     pub fn global_object(&mut self) -> GcPointer<JsObject> {
@@ -55,6 +65,25 @@ impl Context {
     pub fn global_data(&self) -> &GlobalData {
         &self.global_data
     }
+
+    /// Records `error` to be rethrown by `take_pending_exception`. Only the first
+    /// exception is kept, the ones after it would not have run if it was thrown right away.
+    pub(crate) fn set_pending_exception(&mut self, error: JsValue) {
+        if self.pending_exception.is_none() {
+            self.pending_exception = Some(error);
+        }
+    }
+
+    /// Replaces `result` with the pending exception if there is one.
+    pub(crate) fn take_pending_exception<T>(
+        &mut self,
+        result: Result<T, JsValue>,
+    ) -> Result<T, JsValue> {
+        match self.pending_exception.take() {
+            Some(error) => Err(error),
+            None => result,
+        }
+    }
     pub fn vm(&self) -> RuntimeRef {
         self.vm
     }
@@ -82,6 +111,10 @@ impl Context {
             module_loader: None,
             modules: HashMap::new(),
             symbol_table: HashMap::new(),
+            symbols: HashMap::new(),
+            preparing_stack_trace: false,
+            console: ConsoleState::default(),
+            pending_exception: None,
         }
     }
 
@@ -95,6 +128,10 @@ impl Context {
             module_loader: None,
             modules: HashMap::new(),
             symbol_table: HashMap::new(),
+            symbols: HashMap::new(),
+            preparing_stack_trace: false,
+            console: ConsoleState::default(),
+            pending_exception: None,
         };
         let ctx = vm.heap().allocate(context);
         ctx
@@ -124,6 +161,8 @@ impl GcPointer<Context> {
         self.init_number_in_global_object()?;
         self.init_array_in_global_object()?;
         self.init_math_in_global_object()?;
+        self.init_reflect_in_global_object()?;
+        self.init_proxy_in_global_object()?;
         self.init_error_in_global_object()?;
         self.init_string_in_global_object()?;
         self.init_builtin_in_global_object()?;
@@ -132,9 +171,18 @@ impl GcPointer<Context> {
         self.init_promise_in_global_object()?;
         self.init_array_buffer_in_global_object()?;
         self.init_data_view_in_global_object()?;
+        self.init_typed_array_in_global_object()?;
         self.init_weak_ref_in_global_object()?;
+        self.init_finalization_registry_in_global_object()?;
         self.init_date_in_global_object()?;
         self.init_boolean_in_global_object()?;
+        self.init_bigint_in_global_object()?;
+        self.init_temporal_in_global_object()?;
+        self.init_intl_in_global_object()?;
+        self.init_map_in_global_object()?;
+        self.init_url_in_global_object()?;
+        self.init_encoding_in_global_object()?;
+        self.init_console_in_global_object()?;
         self.init_self_hosted();
         self.init_module_loader();
         self.init_internal_modules();
@@ -175,10 +223,19 @@ impl GcPointer<Context> {
         self.init_generator_in_global_data(proto)?;
         self.init_array_buffer_in_global_data()?;
         self.init_data_view_in_global_data()?;
+        self.init_typed_array_in_global_data()?;
         self.init_string_in_global_data(proto)?;
         self.init_weak_ref_in_global_data()?;
+        self.init_finalization_registry_in_global_data()?;
         self.init_date_in_global_data()?;
         self.init_boolean_in_global_data()?;
+        self.init_proxy_in_global_data()?;
+        self.init_bigint_in_global_data()?;
+        self.init_temporal_in_global_data()?;
+        self.init_intl_in_global_data()?;
+        self.init_map_in_global_data()?;
+        self.init_url_in_global_data()?;
+        self.init_encoding_in_global_data()?;
         Ok(())
     }
 }
@@ -204,6 +261,11 @@ impl GcPointer<Context> {
         let msg = JsString::new(self, msg);
         JsRangeError::new(self, msg, None)
     }
+    /// Construct new URI error from provided string.
+    pub fn new_uri_error(self, msg: impl AsRef<str>) -> GcPointer<JsObject> {
+        let msg = JsString::new(self, msg);
+        JsURIError::new(self, msg, None)
+    }
 }
 
 impl GcPointer<Context> {
@@ -262,6 +324,7 @@ impl GcPointer<Context> {
                 .unwrap_or_else(|| "".to_string()),
             path.to_owned(),
             builtins,
+            &cm,
         )?;
         code.name = name.intern();
         //code.display_to(&mut OutBuf).unwrap();
@@ -310,6 +373,7 @@ impl GcPointer<Context> {
                 .unwrap_or_else(|| "".to_string()),
             name,
             &module,
+            &cm,
         )
         .map_err(|e| self.new_syntax_error(format!("Compile Error {:?}", e)))?;
         code.name = name.intern();
@@ -372,6 +436,7 @@ impl GcPointer<Context> {
                     .unwrap_or_else(|| "".to_string()),
                 path.map(|x| x.to_owned()).unwrap_or_else(String::new),
                 builtins,
+                &cm,
             )
             .map_err(|e| self.new_syntax_error(format!("Compile Error {:?}", &e)))?;
             code.strict = code.strict || force_strict;
@@ -390,6 +455,7 @@ impl GcPointer<Context> {
             fun.as_function_mut()
                 .call(self, &mut args, JsValue::new(*func))
         };
+        self.perform_microtask_checkpoint();
         res
     }
     pub fn evalm(
@@ -437,6 +503,7 @@ impl GcPointer<Context> {
                     .unwrap_or_else(|| "".to_string()),
                 &path.map(|x| x.to_owned()).unwrap_or_else(String::new),
                 &script,
+                &cm,
             )
             .map_err(|e| self.new_syntax_error(format!("Compile Error {:?}", &e)))?;
             code.strict = code.strict || force_strict;
@@ -463,25 +530,82 @@ impl GcPointer<Context> {
             fun.as_function_mut()
                 .call(self, &mut args, JsValue::new(*func))
         };
+        self.perform_microtask_checkpoint();
         res
     }
 
-    /// Collect stacktrace.
+    /// Runs queued jobs when nothing is executing anymore, evaluation that is nested in another
+    /// call leaves them to the outermost one.
+    fn perform_microtask_checkpoint(mut self) {
+        if self.stack.current.is_null() {
+            self.vm.run_jobs();
+        }
+    }
+
+    /// Collect stacktrace. Every frame is printed on its own line as `at name (file:line:column)`.
     pub fn stacktrace(&mut self) -> String {
         let mut result = String::new();
+        for site in self.call_sites(None, usize::MAX) {
+            result.push_str(&format!("    at {}\n", site));
+        }
+        result
+    }
+
+    /// Collects at most `limit` frames of the call stack, innermost first. When `until` is set
+    /// frames above and including the topmost call of `until` are skipped.
+    pub fn call_sites(&mut self, until: Option<JsValue>, limit: usize) -> Vec<CallSite> {
+        let mut sites = vec![];
+        let mut skip = until.is_some();
         let mut frame = self.stack.current;
         unsafe {
-            while !frame.is_null() {
-                if let Some(cb) = (*frame).code_block {
-                    let name = self.description(cb.name);
-                    result.push_str(&format!("  at '{}':'{}'\n", cb.file_name, name));
-                } else {
-                    result.push_str(" at '<native code>\n");
+            while !frame.is_null() && sites.len() < limit {
+                let current = &*frame;
+                frame = current.prev;
+                if skip {
+                    skip = Some(current.callee) != until;
+                    continue;
                 }
-                frame = (*frame).prev;
+                let cb = match current.code_block {
+                    Some(cb) => cb,
+                    None => {
+                        sites.push(CallSite {
+                            function: current.callee,
+                            this: current.this,
+                            function_name: None,
+                            file_name: None,
+                            location: None,
+                            native: true,
+                            constructor: current.ctor,
+                            top_level: false,
+                        });
+                        continue;
+                    }
+                };
+                let name = self.description(cb.name);
+                // `ip` points past the opcode of the instruction being executed.
+                let start = cb.code.as_ptr() as usize;
+                let pc = (current.ip as usize).wrapping_sub(start).wrapping_sub(1);
+                sites.push(CallSite {
+                    function: current.callee,
+                    this: current.this,
+                    function_name: if cb.top_level || name == "<anonymous>" {
+                        None
+                    } else {
+                        Some(name)
+                    },
+                    file_name: if cb.file_name.is_empty() {
+                        None
+                    } else {
+                        Some(cb.file_name.clone())
+                    },
+                    location: cb.location(pc).map(|loc| (loc.line, loc.col)),
+                    native: false,
+                    constructor: current.ctor,
+                    top_level: cb.top_level,
+                });
             }
         }
-        result
+        sites
     }
 
     pub fn init_module_loader(mut self) {
@@ -539,15 +663,39 @@ impl GcPointer<Context> {
         }
     }
 
+    /// Returns property key `sym` as a string value. Unlike [Context::description] it keeps
+    /// unpaired surrogates of keys created from such strings.
+    pub fn key_to_jsstring(self, sym: Symbol) -> GcPointer<JsString> {
+        if let Symbol::Key(key) = sym {
+            if let Some(units) = symbol_table::symbol_table().code_units(key) {
+                return JsString::from_utf16(self, units);
+            }
+        }
+        JsString::new(self, self.description(sym))
+    }
+
     pub(crate) fn schedule_async<F>(mut self, job: F) -> Result<(), JsValue>
     where
         F: FnOnce(GcPointer<Context>) + 'static,
     {
         if let Some(scheduler) = &self.vm.sched_async_func {
             scheduler(Box::new(job));
-            Ok(())
         } else {
-            Err(JsValue::encode_object_value(JsString::new(self, "In order to use async you have to init the RuntimeOptions with with_async_scheduler()")))
+            self.vm.jobs.push_back((self, Box::new(job)));
+        }
+        Ok(())
+    }
+
+    /// Hands `error` thrown by a job to the handler of the runtime, see
+    /// [Runtime::with_uncaught_exception_handler].
+    pub(crate) fn report_uncaught_exception(self, error: JsValue) {
+        if let Some(handler) = &self.vm.uncaught_exception_func {
+            handler(self, error);
+            return;
+        }
+        match error.to_string(self) {
+            Ok(str) => eprintln!("Uncaught exception: {}", str),
+            Err(_) => eprintln!("Uncaught exception"),
         }
     }
 
@@ -570,6 +718,8 @@ unsafe impl Trace for Context {
         self.stack.trace(visitor);
         self.module_loader.trace(visitor);
         self.modules.trace(visitor);
-        // self.symbol_table.trace(visitor);
+        self.symbol_table.trace(visitor);
+        self.symbols.trace(visitor);
+        self.pending_exception.trace(visitor);
     }
 }
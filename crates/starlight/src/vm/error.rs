@@ -5,7 +5,12 @@ use super::{
     attributes::*, method_table::*, object::*, property_descriptor::*, string::JsString,
     structure::*, symbol_table::*, value::JsValue, Context,
 };
+use crate::constant::*;
 use crate::gc::cell::GcPointer;
+use std::{
+    fmt,
+    mem::{size_of, ManuallyDrop},
+};
 
 use crate::prelude::*;
 
@@ -16,11 +21,48 @@ pub struct JsReferenceError;
 pub struct JsSyntaxError;
 pub struct JsTypeError;
 pub struct JsURIError;
+pub struct JsAggregateError;
+
+/// Defines `message` and `stack` of newly created error object. `stack` is formatted by
+/// [format_stack_trace] and is not defined when `Error.stackTraceLimit` is not a number.
+fn init_error(
+    mut ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+    name: &str,
+    s: GcPointer<JsString>,
+) {
+    if !s.as_str().is_empty() {
+        let _ = obj.define_own_property(
+            ctx,
+            "message".intern(),
+            &*DataDescriptor::new(JsValue::encode_object_value(s), W | C),
+            false,
+        );
+    }
+    if let Some(limit) = stack_trace_limit(ctx) {
+        let header = if s.as_str().is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}: {}", name, s.as_str())
+        };
+        let sites = ctx.call_sites(None, limit);
+        let stack = match format_stack_trace(ctx, *obj, &header, sites) {
+            Ok(stack) => stack,
+            Err(_) => JsValue::new(JsString::new(ctx, header)),
+        };
+        let _ = obj.define_own_property(
+            ctx,
+            "stack".intern(),
+            &*DataDescriptor::new(stack, W | C),
+            false,
+        );
+    }
+}
 
 define_jsclass!(JsError, Error);
 impl JsError {
     pub fn new(
-        mut ctx: GcPointer<Context>,
+        ctx: GcPointer<Context>,
         s: GcPointer<JsString>,
         structure: Option<GcPointer<Structure>>,
     ) -> GcPointer<JsObject> {
@@ -30,22 +72,7 @@ impl JsError {
             structure.unwrap_or_else(|| ctx.global_data().error_structure.unwrap())
         );
         let mut obj = JsObject::new(ctx, &shape, Self::get_class(), ObjectTag::Ordinary);
-        let stack = ctx.stacktrace();
-        let str = JsString::new(ctx, stack);
-        let _ = obj.define_own_property(
-            ctx,
-            "stack".intern(),
-            &*DataDescriptor::new(JsValue::new(str), W | C),
-            false,
-        );
-        if !s.as_str().is_empty() {
-            let _ = obj.define_own_property(
-                ctx,
-                "message".intern(),
-                &*DataDescriptor::new(JsValue::encode_object_value(s), W | C),
-                false,
-            );
-        }
+        init_error(ctx, &mut obj, S_ERROR, s);
 
         obj
     }
@@ -54,7 +81,7 @@ impl JsError {
 define_jsclass!(JsEvalError, Error, EvalError);
 impl JsEvalError {
     pub fn new(
-        mut ctx: GcPointer<Context>,
+        ctx: GcPointer<Context>,
         s: GcPointer<JsString>,
         structure: Option<GcPointer<Structure>>,
     ) -> GcPointer<JsObject> {
@@ -64,22 +91,7 @@ impl JsEvalError {
             structure.unwrap_or_else(|| ctx.global_data().eval_error_structure.unwrap())
         );
         let mut obj = JsObject::new(ctx, &shape, Self::get_class(), ObjectTag::Ordinary);
-        let stack = ctx.stacktrace();
-        let str = JsString::new(ctx, stack);
-        let _ = obj.define_own_property(
-            ctx,
-            "stack".intern(),
-            &*DataDescriptor::new(JsValue::new(str), W | C),
-            false,
-        );
-        if !s.as_str().is_empty() {
-            let _ = obj.define_own_property(
-                ctx,
-                "message".intern(),
-                &*DataDescriptor::new(JsValue::encode_object_value(s), W | C),
-                false,
-            );
-        }
+        init_error(ctx, &mut obj, S_EVAL_ERROR, s);
 
         obj
     }
@@ -89,7 +101,7 @@ define_jsclass!(JsRangeError, Error, RangeError);
 
 impl JsRangeError {
     pub fn new(
-        mut ctx: GcPointer<Context>,
+        ctx: GcPointer<Context>,
         s: GcPointer<JsString>,
         structure: Option<GcPointer<Structure>>,
     ) -> GcPointer<JsObject> {
@@ -99,22 +111,7 @@ impl JsRangeError {
             structure.unwrap_or_else(|| ctx.global_data().range_error_structure.unwrap())
         );
         let mut obj = JsObject::new(ctx, &shape, Self::get_class(), ObjectTag::Ordinary);
-        let stack = ctx.stacktrace();
-        let str = JsString::new(ctx, stack);
-        let _ = obj.define_own_property(
-            ctx,
-            "stack".intern(),
-            &*DataDescriptor::new(JsValue::new(str), W | C),
-            false,
-        );
-        if !s.as_str().is_empty() {
-            let _ = obj.define_own_property(
-                ctx,
-                "message".intern(),
-                &*DataDescriptor::new(JsValue::encode_object_value(s), W | C),
-                false,
-            );
-        }
+        init_error(ctx, &mut obj, S_RANGE_ERROR, s);
 
         obj
     }
@@ -124,7 +121,7 @@ define_jsclass!(JsReferenceError, Error, ReferenceError);
 
 impl JsReferenceError {
     pub fn new(
-        mut ctx: GcPointer<Context>,
+        ctx: GcPointer<Context>,
         s: GcPointer<JsString>,
         structure: Option<GcPointer<Structure>>,
     ) -> GcPointer<JsObject> {
@@ -134,22 +131,7 @@ impl JsReferenceError {
             structure.unwrap_or_else(|| ctx.global_data().reference_error_structure.unwrap())
         );
         let mut obj = JsObject::new(ctx, &shape, Self::get_class(), ObjectTag::Ordinary);
-        let stack = ctx.stacktrace();
-        let str = JsString::new(ctx, stack);
-        let _ = obj.define_own_property(
-            ctx,
-            "stack".intern(),
-            &*DataDescriptor::new(JsValue::new(str), W | C),
-            false,
-        );
-        if !s.as_str().is_empty() {
-            let _ = obj.define_own_property(
-                ctx,
-                "message".intern(),
-                &*DataDescriptor::new(JsValue::encode_object_value(s), W | C),
-                false,
-            );
-        }
+        init_error(ctx, &mut obj, S_REFERENCE_ERROR, s);
 
         obj
     }
@@ -159,7 +141,7 @@ define_jsclass!(JsSyntaxError, Error, SyntaxError);
 
 impl JsSyntaxError {
     pub fn new(
-        mut ctx: GcPointer<Context>,
+        ctx: GcPointer<Context>,
         s: GcPointer<JsString>,
         structure: Option<GcPointer<Structure>>,
     ) -> GcPointer<JsObject> {
@@ -169,22 +151,7 @@ impl JsSyntaxError {
             structure.unwrap_or_else(|| ctx.global_data().syntax_error_structure.unwrap())
         );
         let mut obj = JsObject::new(ctx, &shape, Self::get_class(), ObjectTag::Ordinary);
-        let stack = ctx.stacktrace();
-        let str = JsString::new(ctx, stack);
-        let _ = obj.define_own_property(
-            ctx,
-            "stack".intern(),
-            &*DataDescriptor::new(JsValue::new(str), W | C),
-            false,
-        );
-        if !s.as_str().is_empty() {
-            let _ = obj.define_own_property(
-                ctx,
-                "message".intern(),
-                &*DataDescriptor::new(JsValue::encode_object_value(s), W | C),
-                false,
-            );
-        }
+        init_error(ctx, &mut obj, S_SYNTAX_ERROR, s);
 
         obj
     }
@@ -193,7 +160,7 @@ impl JsSyntaxError {
 define_jsclass!(JsTypeError, Error, TypeError);
 impl JsTypeError {
     pub fn new(
-        mut ctx: GcPointer<Context>,
+        ctx: GcPointer<Context>,
         s: GcPointer<JsString>,
         structure: Option<GcPointer<Structure>>,
     ) -> GcPointer<JsObject> {
@@ -203,22 +170,7 @@ impl JsTypeError {
             structure.unwrap_or_else(|| ctx.global_data().type_error_structure.unwrap())
         );
         let mut obj = JsObject::new(ctx, &shape, Self::get_class(), ObjectTag::Ordinary);
-        let stack = ctx.stacktrace();
-        let str = JsString::new(ctx, stack);
-        let _ = obj.define_own_property(
-            ctx,
-            "stack".intern(),
-            &*DataDescriptor::new(JsValue::new(str), W | C),
-            false,
-        );
-        if !s.as_str().is_empty() {
-            let _ = obj.define_own_property(
-                ctx,
-                "message".intern(),
-                &*DataDescriptor::new(JsValue::encode_object_value(s), W | C),
-                false,
-            );
-        }
+        init_error(ctx, &mut obj, S_TYPE_ERROR, s);
 
         obj
     }
@@ -227,7 +179,7 @@ impl JsTypeError {
 define_jsclass!(JsURIError, Error, URIError);
 impl JsURIError {
     pub fn new(
-        mut ctx: GcPointer<Context>,
+        ctx: GcPointer<Context>,
         s: GcPointer<JsString>,
         structure: Option<GcPointer<Structure>>,
     ) -> GcPointer<JsObject> {
@@ -237,22 +189,191 @@ impl JsURIError {
             structure.unwrap_or_else(|| ctx.global_data().uri_error_structure.unwrap())
         );
         let mut obj = JsObject::new(ctx, &shape, Self::get_class(), ObjectTag::Ordinary);
-        let stack = ctx.stacktrace();
-        let str = JsString::new(ctx, stack);
-        let _ = obj.define_own_property(
-            ctx,
-            "stack".intern(),
-            &*DataDescriptor::new(JsValue::new(str), W | C),
-            false,
+        init_error(ctx, &mut obj, S_URI_ERROR, s);
+        obj
+    }
+}
+
+define_jsclass!(JsAggregateError, Error, AggregateError);
+impl JsAggregateError {
+    pub fn new(
+        ctx: GcPointer<Context>,
+        s: GcPointer<JsString>,
+        structure: Option<GcPointer<Structure>>,
+    ) -> GcPointer<JsObject> {
+        let stack = ctx.shadowstack();
+        letroot!(
+            shape = stack,
+            structure.unwrap_or_else(|| ctx.global_data().aggregate_error_structure.unwrap())
         );
-        if !s.as_str().is_empty() {
-            let _ = obj.define_own_property(
-                ctx,
-                "message".intern(),
-                &*DataDescriptor::new(JsValue::encode_object_value(s), W | C),
-                false,
-            );
-        }
+        let mut obj = JsObject::new(ctx, &shape, Self::get_class(), ObjectTag::Ordinary);
+        init_error(ctx, &mut obj, S_AGGREGATE_ERROR, s);
         obj
     }
 }
+
+/// Returns true if `object` is an instance of `Error` or one of the native error types.
+pub fn is_error(object: &JsObject) -> bool {
+    object.is_class(JsError::get_class())
+        || object.is_class(JsEvalError::get_class())
+        || object.is_class(JsRangeError::get_class())
+        || object.is_class(JsReferenceError::get_class())
+        || object.is_class(JsSyntaxError::get_class())
+        || object.is_class(JsTypeError::get_class())
+        || object.is_class(JsURIError::get_class())
+        || object.is_class(JsAggregateError::get_class())
+}
+
+/// Single frame of a captured stack trace. Exposed to `Error.prepareStackTrace` as V8 `CallSite`
+/// object.
+pub struct CallSite {
+    pub(crate) function: JsValue,
+    pub(crate) this: JsValue,
+    /// `None` for top level code and anonymous functions.
+    pub(crate) function_name: Option<String>,
+    pub(crate) file_name: Option<String>,
+    /// Line and column, both start at 1.
+    pub(crate) location: Option<(u32, u32)>,
+    pub(crate) native: bool,
+    pub(crate) constructor: bool,
+    pub(crate) top_level: bool,
+}
+
+extern "C" fn drop_call_site(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<CallSite>()) }
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn trace_call_site(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<CallSite>().trace(tracer);
+}
+
+extern "C" fn deser_call_site(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize a CallSite");
+}
+
+extern "C" fn ser_call_site(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize a CallSite");
+}
+
+extern "C" fn call_site_size() -> usize {
+    size_of::<CallSite>()
+}
+
+define_jsclass!(
+    CallSite,
+    CallSite,
+    CallSite,
+    Some(drop_call_site),
+    Some(trace_call_site),
+    Some(deser_call_site),
+    Some(ser_call_site),
+    Some(call_site_size)
+);
+
+unsafe impl Trace for CallSite {
+    fn trace(&mut self, visitor: &mut dyn Tracer) {
+        self.function.trace(visitor);
+        self.this.trace(visitor);
+    }
+}
+
+impl CallSite {
+    /// Wraps call site into `CallSite` object.
+    pub fn into_object(self, ctx: GcPointer<Context>) -> GcPointer<JsObject> {
+        let stack = ctx.shadowstack();
+        letroot!(
+            structure = stack,
+            ctx.global_data().call_site_structure.unwrap()
+        );
+        let mut obj = JsObject::new(ctx, &structure, Self::get_class(), ObjectTag::Ordinary);
+        *obj.data::<Self>() = ManuallyDrop::new(self);
+        obj
+    }
+}
+
+/// Formats call site the same way V8 does: `name (file:line:column)`.
+impl fmt::Display for CallSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut location = if self.native {
+            "native".to_owned()
+        } else {
+            self.file_name
+                .clone()
+                .unwrap_or_else(|| "<anonymous>".to_owned())
+        };
+        if let Some((line, column)) = self.location {
+            location = format!("{}:{}:{}", location, line, column);
+        }
+        match self.function_name {
+            Some(ref name) if self.constructor => write!(f, "new {} ({})", name, location),
+            Some(ref name) => write!(f, "{} ({})", name, location),
+            None if self.top_level => write!(f, "{}", location),
+            None => write!(f, "<anonymous> ({})", location),
+        }
+    }
+}
+
+/// Returns `Error.stackTraceLimit`. `None` means that stack trace is not captured at all.
+pub(crate) fn stack_trace_limit(ctx: GcPointer<Context>) -> Option<usize> {
+    let mut ctor = ctx.global_data().error_constructor?;
+    let limit = ctor
+        .get_own_property(ctx, "stackTraceLimit".intern())
+        .filter(|desc| desc.is_data())?
+        .value();
+    if !limit.is_number() {
+        return None;
+    }
+    let limit = limit.get_number();
+    if limit.is_nan() || limit <= 0.0 {
+        Some(0)
+    } else if limit >= usize::MAX as f64 {
+        Some(usize::MAX)
+    } else {
+        Some(limit as usize)
+    }
+}
+
+/// Builds `stack` value of `error`. `Error.prepareStackTrace(error, callSites)` is used when it is
+/// set, otherwise result is `header` followed by a line per call site.
+pub(crate) fn format_stack_trace(
+    mut ctx: GcPointer<Context>,
+    error: GcPointer<JsObject>,
+    header: &str,
+    sites: Vec<CallSite>,
+) -> Result<JsValue, JsValue> {
+    let prepare = match ctx.global_data().error_constructor {
+        // errors thrown by `prepareStackTrace` itself get the default formatting
+        Some(mut ctor) if !ctx.preparing_stack_trace => {
+            ctor.get(ctx, "prepareStackTrace".intern())?
+        }
+        _ => JsValue::encode_undefined_value(),
+    };
+    if !prepare.is_callable() {
+        let mut stack = header.to_owned();
+        for site in sites.iter() {
+            stack.push_str(&format!("\n    at {}", site));
+        }
+        return Ok(JsValue::new(JsString::new(ctx, stack)));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(array = stack, JsArray::new(ctx, sites.len() as _));
+    for (i, site) in sites.into_iter().enumerate() {
+        let site = site.into_object(ctx);
+        array.put(ctx, Symbol::Index(i as _), JsValue::new(site), false)?;
+    }
+    letroot!(prepare = stack, prepare.get_jsobject());
+    let mut buf = [JsValue::new(error), JsValue::new(*array)];
+    letroot!(
+        args = stack,
+        Arguments::new(
+            JsValue::new(ctx.global_data().error_constructor.unwrap()),
+            &mut buf
+        )
+    );
+    ctx.preparing_stack_trace = true;
+    let func = JsValue::new(*prepare);
+    let result = prepare.as_function_mut().call(ctx, &mut args, func);
+    ctx.preparing_stack_trace = false;
+    result
+}
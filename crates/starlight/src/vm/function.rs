@@ -12,7 +12,7 @@ use super::{attributes::*, symbol_table::Internable};
 use super::{environment::Environment, object::*};
 use super::{error::JsRangeError, string::*};
 use super::{error::JsTypeError, method_table::*};
-use super::{interpreter::frame::CallFrame, slot::*};
+use super::{interpreter::frame::CallFrame, proxy::JsProxy, slot::*};
 use crate::constant::S_CONSTURCTOR;
 use crate::gc::{
     cell::{GcPointer, Trace, Tracer},
@@ -32,6 +32,7 @@ pub enum FuncType {
     User(JsVMFunction),
     Bound(JsBoundFunction),
     Generator(JsGeneratorFunction),
+    Proxy(JsProxy),
 }
 
 define_jsclass!(JsFunction, Function);
@@ -50,12 +51,29 @@ impl JsFunction {
     pub fn is_generator(&self) -> bool {
         matches!(self.ty, FuncType::Generator(_))
     }
+    pub fn is_proxy(&self) -> bool {
+        matches!(self.ty, FuncType::Proxy(_))
+    }
+    /// Returns true if function has `[[Construct]]` internal method.
+    pub fn is_constructor(&self) -> bool {
+        match self.ty {
+            FuncType::Native(_) => true,
+            FuncType::User(ref x) => x.code.is_constructor,
+            FuncType::Bound(ref x) => x.target.as_function().is_constructor(),
+            FuncType::Proxy(ref x) => x.is_constructor(),
+            _ => false,
+        }
+    }
+    /// `OrdinaryHasInstance(C, O)`, `this` is the object of this function.
     pub fn has_instance(
         &self,
         this: &mut GcPointer<JsObject>,
         ctx: GcPointer<Context>,
         val: JsValue,
     ) -> Result<bool, JsValue> {
+        if let FuncType::Bound(ref bound) = self.ty {
+            return super::operations::instance_of(ctx, val, JsValue::new(bound.target));
+        }
         if !val.is_jsobject() {
             return Ok(false);
         }
@@ -68,14 +86,14 @@ impl JsFunction {
             )));
         }
 
-        let proto = got.get_jsobject();
-        let mut obj = val.get_jsobject().prototype().copied();
-        while let Some(obj_) = obj {
+        let stack = ctx.shadowstack();
+        letroot!(proto = stack, got.get_jsobject());
+        letroot!(obj = stack, val.get_jsobject());
+        while let Some(obj_) = super::operations::get_prototype_of(ctx, &mut obj)? {
             if GcPointer::ptr_eq(&obj_, &proto) {
                 return Ok(true);
-            } else {
-                obj = obj_.prototype().copied();
             }
+            *obj = obj_;
         }
         Ok(false)
     }
@@ -86,6 +104,7 @@ impl JsFunction {
             FuncType::User(ref x) => x.code.strict,
             FuncType::Bound(ref x) => x.target.as_function().is_strict(),
             FuncType::Generator(ref x) => x.function.as_function().is_strict(),
+            FuncType::Proxy(_) => false,
         }
     }
 
@@ -168,13 +187,19 @@ impl JsFunction {
 
     pub fn call(
         &mut self,
-        ctx: GcPointer<Context>,
+        mut ctx: GcPointer<Context>,
         args: &mut Arguments,
         this: JsValue,
     ) -> Result<JsValue, JsValue> {
         match self.ty {
-            FuncType::Native(ref x) => (x.func)(ctx, args),
-            FuncType::Closure(ref x) => (x.func)(ctx, args),
+            FuncType::Native(ref x) => {
+                let result = (x.func)(ctx, args);
+                ctx.take_pending_exception(result)
+            }
+            FuncType::Closure(ref x) => {
+                let result = (x.func)(ctx, args);
+                ctx.take_pending_exception(result)
+            }
             FuncType::User(ref x) => {
                 ctx.perform_vm_call(x, JsValue::encode_object_value(x.scope), args, this)
             }
@@ -192,6 +217,7 @@ impl JsFunction {
                 target.as_function_mut().call(ctx, &mut args, this)
             }
             FuncType::Generator(ref mut x) => x.call(ctx, args, this),
+            FuncType::Proxy(_) => JsProxy::call(ctx, this, args),
         }
     } /*
       pub fn call_with_env<'a>(
@@ -580,6 +606,7 @@ unsafe impl Trace for JsFunction {
             FuncType::Generator(ref mut x) => {
                 x.function.trace(tracer);
             }
+            FuncType::Proxy(ref mut x) => x.trace(tracer),
             _ => (),
         }
     }
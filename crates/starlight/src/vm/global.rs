@@ -98,8 +98,16 @@ impl JsGlobal {
         collector: &mut dyn FnMut(Symbol, u32),
         mode: EnumerationMode,
     ) {
-        for it in obj.as_global().sym_map.iter() {
-            collector(*it.0, *it.1);
+        // variables are numbered in declaration order.
+        let mut variables = obj
+            .as_global()
+            .sym_map
+            .iter()
+            .map(|it| (*it.0, *it.1))
+            .collect::<Vec<_>>();
+        variables.sort_unstable_by_key(|it| it.1);
+        for (name, index) in variables {
+            collector(name, index);
         }
         JsObject::GetOwnPropertyNamesMethod(obj, ctx, collector, mode)
     }
@@ -4,8 +4,22 @@
 use self::{frame::CallFrame, stack::Stack};
 use super::function::*;
 use super::{
-    arguments::*, array::*, code_block::CodeBlock, environment::*, error::JsTypeError, error::*,
-    native_iterator::*, object::*, slot::*, string::JsString, symbol_table::*, value::*,
+    arguments::*,
+    array::*,
+    bigint::{BigIntOp, JsBigInt},
+    code_block::CodeBlock,
+    environment::*,
+    error::JsTypeError,
+    error::*,
+    native_iterator::*,
+    object::*,
+    operations,
+    proxy::JsProxy,
+    slot::*,
+    string::JsString,
+    symbol_table::*,
+    typedarray::JsTypedArray,
+    value::*,
 };
 use crate::letroot;
 use crate::vm::context::Context;
@@ -231,6 +245,8 @@ unsafe fn eval_internal(
 
 pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result<JsValue, JsValue> {
     ctx.heap().collect_if_necessary();
+    // `ip` lives in a local. Instructions write it back to the frame only before they may throw
+    // or call out, so that stack traces can map the frame to its source location.
     let mut ip = (*frame).ip;
 
     let mut frame: &'static mut CallFrame = &mut *frame;
@@ -285,6 +301,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 debug_assert!(index < env.as_slice_mut().len() as u32);
                 let val = frame.pop();
                 if unlikely(!env.as_slice_mut()[index as usize].mutable) {
+                    frame.ip = ip;
                     return Err(JsValue::new(
                         ctx.new_type_error("Cannot assign to immutable variable".to_string()),
                     ));
@@ -312,6 +329,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 debug_assert!(index < env.as_slice_mut().len() as u32);
                 let val = frame.pop();
                 if unlikely(!env.as_slice_mut()[index as usize].mutable) {
+                    frame.ip = ip;
                     return Err(JsValue::new(
                         ctx.new_type_error("Cannot assign to immutable variable".to_string()),
                     ));
@@ -442,21 +460,28 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                             lhs: JsValue,
                             rhs: JsValue,
                         ) -> Result<JsValue, JsValue> {
-                            let lhs = lhs.to_string(ctx)?;
-                            let rhs = rhs.to_string(ctx)?;
-                            let string = format!("{}{}", lhs, rhs);
-                            Ok(JsValue::encode_object_value(JsString::new(ctx, string)))
+                            let lhs = lhs.to_jsstring(ctx)?;
+                            let rhs = rhs.to_jsstring(ctx)?;
+                            JsString::concat(ctx, lhs, rhs).map(JsValue::encode_object_value)
                         }
 
                         let result = concat(ctx, lhs, rhs)?;
                         frame.push(result);
                     } else {
-                        let lhs = lhs.to_number(ctx)?;
-                        let rhs = rhs.to_number(ctx)?;
-                        frame.push(JsValue::new(lhs + rhs));
+                        let lhs = lhs.to_numeric(ctx)?;
+                        let rhs = rhs.to_numeric(ctx)?;
+                        if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                            frame.push(JsBigInt::binary(ctx, BigIntOp::Add, lhs, rhs)?);
+                        } else {
+                            frame.push(JsValue::new(lhs.get_number() + rhs.get_number()));
+                        }
                     }
                     Ok(())
                 }
+                frame.ip = ip;
+                if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                    profile.set_observed_heap_bigint();
+                }
                 add_slowpath(ctx, frame, lhs, rhs)?;
             }
             Opcode::OP_SUB => {
@@ -483,10 +508,15 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
 
                     continue;
                 }
-                // profile.observe_lhs_and_rhs(lhs, rhs);
-                let lhs = lhs.to_number(ctx)?;
-                let rhs = rhs.to_number(ctx)?;
-                frame.push(JsValue::new(lhs - rhs));
+                frame.ip = ip;
+                let lhs = lhs.to_numeric(ctx)?;
+                let rhs = rhs.to_numeric(ctx)?;
+                if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                    profile.set_observed_heap_bigint();
+                    frame.push(JsBigInt::binary(ctx, BigIntOp::Sub, lhs, rhs)?);
+                    continue;
+                }
+                frame.push(JsValue::new(lhs.get_number() - rhs.get_number()));
             }
             Opcode::OP_DIV => {
                 let profile = &mut *ip.cast::<ArithProfile>();
@@ -500,9 +530,15 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     continue;
                 }
 
-                let lhs = lhs.to_number(ctx)?;
-                let rhs = rhs.to_number(ctx)?;
-                frame.push(JsValue::new(lhs / rhs));
+                frame.ip = ip;
+                let lhs = lhs.to_numeric(ctx)?;
+                let rhs = rhs.to_numeric(ctx)?;
+                if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                    profile.set_observed_heap_bigint();
+                    frame.push(JsBigInt::binary(ctx, BigIntOp::Div, lhs, rhs)?);
+                    continue;
+                }
+                frame.push(JsValue::new(lhs.get_number() / rhs.get_number()));
             }
             Opcode::OP_MUL => {
                 let profile = &mut *ip.cast::<ArithProfile>();
@@ -523,9 +559,15 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     frame.push(JsValue::new(lhs.get_number() * rhs.get_number()));
                     continue;
                 }
-                let lhs = lhs.to_number(ctx)?;
-                let rhs = rhs.to_number(ctx)?;
-                frame.push(JsValue::new(lhs * rhs));
+                frame.ip = ip;
+                let lhs = lhs.to_numeric(ctx)?;
+                let rhs = rhs.to_numeric(ctx)?;
+                if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                    profile.set_observed_heap_bigint();
+                    frame.push(JsBigInt::binary(ctx, BigIntOp::Mul, lhs, rhs)?);
+                    continue;
+                }
+                frame.push(JsValue::new(lhs.get_number() * rhs.get_number()));
             }
             Opcode::OP_REM => {
                 let profile = &mut *ip.cast::<ArithProfile>();
@@ -538,31 +580,49 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     frame.push(JsValue::new(lhs.get_number() % rhs.get_number()));
                     continue;
                 }
-                let lhs = lhs.to_number(ctx)?;
-                let rhs = rhs.to_number(ctx)?;
-                frame.push(JsValue::new(lhs % rhs));
+                frame.ip = ip;
+                let lhs = lhs.to_numeric(ctx)?;
+                let rhs = rhs.to_numeric(ctx)?;
+                if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                    profile.set_observed_heap_bigint();
+                    frame.push(JsBigInt::binary(ctx, BigIntOp::Rem, lhs, rhs)?);
+                    continue;
+                }
+                frame.push(JsValue::new(lhs.get_number() % rhs.get_number()));
             }
             Opcode::OP_SHL => {
-                let lhs = frame.pop();
-                let rhs = frame.pop();
-
+                frame.ip = ip;
+                let lhs = frame.pop().to_numeric(ctx)?;
+                let rhs = frame.pop().to_numeric(ctx)?;
+                if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                    frame.push(JsBigInt::binary(ctx, BigIntOp::Shl, lhs, rhs)?);
+                    continue;
+                }
                 let left = lhs.to_int32(ctx)?;
                 let right = rhs.to_uint32(ctx)?;
                 frame.push(JsValue::new((left << (right & 0x1f)) as f64));
             }
             Opcode::OP_SHR => {
-                let lhs = frame.pop();
-                let rhs = frame.pop();
-
+                frame.ip = ip;
+                let lhs = frame.pop().to_numeric(ctx)?;
+                let rhs = frame.pop().to_numeric(ctx)?;
+                if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                    frame.push(JsBigInt::binary(ctx, BigIntOp::Shr, lhs, rhs)?);
+                    continue;
+                }
                 let left = lhs.to_int32(ctx)?;
                 let right = rhs.to_uint32(ctx)?;
                 frame.push(JsValue::new((left >> (right & 0x1f)) as f64));
             }
 
             Opcode::OP_USHR => {
-                let lhs = frame.pop();
-                let rhs = frame.pop();
-
+                frame.ip = ip;
+                let lhs = frame.pop().to_numeric(ctx)?;
+                let rhs = frame.pop().to_numeric(ctx)?;
+                if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                    frame.push(JsBigInt::binary(ctx, BigIntOp::UShr, lhs, rhs)?);
+                    continue;
+                }
                 let left = lhs.to_uint32(ctx)?;
                 let right = rhs.to_uint32(ctx)?;
                 frame.push(JsValue::new((left >> (right & 0x1f)) as f64));
@@ -574,6 +634,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     frame.push(JsValue::new(lhs.get_int32() < rhs.get_int32()));
                     continue;
                 }
+                frame.ip = ip;
                 frame.push(JsValue::encode_bool_value(
                     lhs.compare(rhs, true, ctx)? == CMP_TRUE,
                 ));
@@ -585,6 +646,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     frame.push(JsValue::new(lhs.get_int32() <= rhs.get_int32()));
                     continue;
                 }
+                frame.ip = ip;
                 frame.push(JsValue::encode_bool_value(
                     rhs.compare(lhs, false, ctx)? == CMP_FALSE,
                 ));
@@ -597,6 +659,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     frame.push(JsValue::new(lhs.get_int32() > rhs.get_int32()));
                     continue;
                 }
+                frame.ip = ip;
                 frame.push(JsValue::encode_bool_value(
                     rhs.compare(lhs, false, ctx)? == CMP_TRUE,
                 ));
@@ -608,6 +671,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     frame.push(JsValue::new(lhs.get_int32() >= rhs.get_int32()));
                     continue;
                 }
+                frame.ip = ip;
                 frame.push(JsValue::encode_bool_value(
                     lhs.compare(rhs, true, ctx)? == CMP_FALSE,
                 ));
@@ -666,6 +730,10 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                         fdbk: u32,
                         is_try: bool,
                     ) -> Result<(), JsValue> {
+                        if JsProxy::is_proxy(obj) || operations::has_proxy_prototype(obj) {
+                            frame.push(obj.get(ctx, name)?);
+                            return Ok(());
+                        }
                         let mut slot = Slot::new();
                         if name == length_id() && obj.is_class(JsArray::get_class()) {
                             *unwrap_unchecked(frame.code_block)
@@ -734,6 +802,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                         }
                         Ok(())
                     }
+                    frame.ip = ip;
                     slow_get_by_id(
                         ctx,
                         frame,
@@ -744,6 +813,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     )?;
                     continue;
                 }
+                frame.ip = ip;
                 frame.push(get_by_id_slow(ctx, name, object)?)
             }
             Opcode::OP_PUT_BY_ID => {
@@ -800,6 +870,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                             }
                         }
 
+                        frame.ip = ip;
                         put_by_id_slow(ctx, frame, &mut obj, name, value, fdbk)?;
                         break 'exit;
                     }
@@ -811,6 +882,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 ctx.heap().collect_if_necessary();
                 let argc = ip.cast::<u32>().read();
                 ip = ip.add(4);
+                frame.ip = ip;
 
                 let args_start = frame.sp.sub(argc as _);
 
@@ -829,7 +901,6 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 let func = func_object.as_function_mut();
                 letroot!(args_ = gcstack, Arguments::new(this, &mut args));
 
-                frame.ip = ip;
                 stack.cursor = frame.sp;
 
                 if func.is_vm() {
@@ -872,6 +943,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 ctx.heap().collect_if_necessary();
                 let argc = ip.cast::<u32>().read();
                 ip = ip.add(4);
+                frame.ip = ip;
 
                 let args_start = frame.sp.sub(argc as _);
                 frame.sp = args_start;
@@ -897,7 +969,6 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 );
 
                 args_.ctor_call = true;
-                frame.ip = ip;
 
                 if func.is_vm() {
                     let vm_fn = func.as_vm_mut();
@@ -947,15 +1018,50 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 if v1.is_number() {
                     frame.push(JsValue::new(-v1.get_number()));
                 } else {
-                    let n = v1.to_number(ctx)?;
-                    frame.push(JsValue::new(-n));
+                    frame.ip = ip;
+                    let n = v1.to_numeric(ctx)?;
+                    if n.is_bigint() {
+                        let result = -n.get_bigint().value();
+                        frame.push(JsValue::new(JsBigInt::new(ctx, result)));
+                    } else {
+                        frame.push(JsValue::new(-n.get_number()));
+                    }
+                }
+            }
+            Opcode::OP_INC | Opcode::OP_DEC => {
+                let delta = if opcode == Opcode::OP_INC { 1 } else { -1 };
+                let v1 = frame.pop();
+                if likely(v1.is_int32()) {
+                    if let Some(result) = v1.get_int32().checked_add(delta) {
+                        frame.push(JsValue::encode_int32(result));
+                        continue;
+                    }
+                }
+                frame.ip = ip;
+                let n = v1.to_numeric(ctx)?;
+                if n.is_bigint() {
+                    let result = n.get_bigint().value() + delta;
+                    frame.push(JsValue::new(JsBigInt::new(ctx, result)));
+                } else {
+                    frame.push(JsValue::new(n.get_number() + delta as f64));
+                }
+            }
+            Opcode::OP_TO_NUMERIC => {
+                let value = frame.pop();
+                if likely(value.is_number()) {
+                    frame.push(value);
+                    continue;
                 }
+                frame.ip = ip;
+                let n = value.to_numeric(ctx)?;
+                frame.push(n);
             }
 
             Opcode::OP_EQ => {
                 let lhs = frame.pop();
                 let rhs = frame.pop();
 
+                frame.ip = ip;
                 frame.push(JsValue::encode_bool_value(lhs.abstract_equal(rhs, ctx)?));
             }
             Opcode::OP_STRICTEQ => {
@@ -966,6 +1072,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
             Opcode::OP_NEQ => {
                 let lhs = frame.pop();
                 let rhs = frame.pop();
+                frame.ip = ip;
                 frame.push(JsValue::encode_bool_value(!lhs.abstract_equal(rhs, ctx)?));
             }
             Opcode::OP_NSTRICTEQ => {
@@ -993,7 +1100,17 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                         *object.indexed.vector.at_mut(index) = value;
                         continue;
                     }
+                    if key.is_int32()
+                        && value.is_number()
+                        && JsTypedArray::is_typed_array(&object)
+                        && object
+                            .data::<JsTypedArray>()
+                            .set(index as _, value.get_number())
+                    {
+                        continue;
+                    }
                 }
+                frame.ip = ip;
                 let key = key.to_symbol(ctx)?;
 
                 if likely(object.is_jsobject()) {
@@ -1046,7 +1163,17 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
 
                         continue;
                     }
+                    if key.is_int32() && JsTypedArray::is_typed_array(&object) {
+                        if let Some(value) = object.data::<JsTypedArray>().get(index) {
+                            if opcode == Opcode::OP_GET_BY_VAL_PUSH_OBJ {
+                                frame.push(JsValue::new(object));
+                            }
+                            frame.push(value);
+                            continue;
+                        }
+                    }
                 }
+                frame.ip = ip;
                 let key = key.to_symbol(ctx)?;
                 let mut slot = Slot::new();
                 let _ = object.get_slot(ctx, key, &mut slot)?;
@@ -1061,29 +1188,15 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
             Opcode::OP_INSTANCEOF => {
                 let lhs = frame.pop();
                 let rhs = frame.pop();
-                if unlikely(!rhs.is_jsobject()) {
-                    let msg = JsString::new(ctx, "'instanceof' requires object");
-                    return Err(JsValue::encode_object_value(JsTypeError::new(
-                        ctx, msg, None,
-                    )));
-                }
-
-                letroot!(robj = gcstack, rhs.get_jsobject());
-                letroot!(robj2 = gcstack, *robj);
-                if unlikely(!robj.is_callable()) {
-                    let msg = JsString::new(ctx, "'instanceof' requires constructor");
-                    return Err(JsValue::encode_object_value(JsTypeError::new(
-                        ctx, msg, None,
-                    )));
-                }
-
-                frame.push(JsValue::encode_bool_value(
-                    robj.as_function().has_instance(&mut robj2, ctx, lhs)?,
-                ));
+                frame.ip = ip;
+                frame.push(JsValue::encode_bool_value(operations::instance_of(
+                    ctx, lhs, rhs,
+                )?));
             }
             Opcode::OP_IN => {
                 let lhs = frame.pop();
                 let rhs = frame.pop();
+                frame.ip = ip;
                 if unlikely(!rhs.is_jsobject()) {
                     let msg = JsString::new(ctx, "'in' requires object");
                     return Err(JsValue::encode_object_value(JsTypeError::new(
@@ -1091,9 +1204,12 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     )));
                 }
                 let sym = lhs.to_symbol(ctx)?;
-                frame.push(JsValue::encode_bool_value(
-                    rhs.get_jsobject().has_own_property(ctx, sym),
-                ));
+                letroot!(object = gcstack, rhs.get_jsobject());
+                frame.push(JsValue::encode_bool_value(operations::has_property(
+                    ctx,
+                    &mut object,
+                    sym,
+                )?));
             }
 
             Opcode::OP_FORIN_SETUP => {
@@ -1107,11 +1223,12 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     continue;
                 }
 
+                frame.ip = ip;
                 let it = if enumerable.is_jsstring() {
-                    NativeIterator::new(ctx, enumerable.get_object())
+                    NativeIterator::new(ctx, enumerable.get_object())?
                 } else {
                     let obj = enumerable.to_object(ctx)?;
-                    NativeIterator::new(ctx, obj.as_dyn())
+                    NativeIterator::new(ctx, obj.as_dyn())?
                 };
                 frame.push(JsValue::new(it));
                 assert!(ip.cast::<Opcode>().read_unaligned() == Opcode::OP_FORIN_ENUMERATE);
@@ -1124,9 +1241,8 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     .get_object()
                     .downcast_unchecked::<NativeIterator>();
                 frame.push(JsValue::new(it));
-                if let Some(sym) = it.next() {
-                    let desc = ctx.description(sym);
-                    frame.push(JsValue::new(JsString::new(ctx, desc)));
+                if let Some(sym) = it.next(ctx) {
+                    frame.push(JsValue::new(ctx.key_to_jsstring(sym)));
                 } else {
                     frame.push(JsValue::encode_empty_value());
                     ip = ip.offset(offset as _);
@@ -1138,6 +1254,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
 
             Opcode::OP_THROW => {
                 let val = frame.pop();
+                frame.ip = ip;
                 return Err(val);
             }
 
@@ -1174,15 +1291,24 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     let n = v1.get_number() as i32;
                     frame.push(JsValue::new((!n) as i32));
                 } else {
-                    let n = v1.to_number(ctx)? as i32;
-                    frame.push(JsValue::new((!n) as i32));
+                    frame.ip = ip;
+                    let n = v1.to_numeric(ctx)?;
+                    if n.is_bigint() {
+                        // ~x == -x - 1
+                        let result = -n.get_bigint().value() - 1;
+                        frame.push(JsValue::new(JsBigInt::new(ctx, result)));
+                    } else {
+                        frame.push(JsValue::new(!(n.get_number() as i32)));
+                    }
                 }
             }
             Opcode::OP_POS => {
                 let value = frame.pop();
                 if value.is_number() {
                     frame.push(value);
+                    continue;
                 }
+                frame.ip = ip;
                 let x = value.to_number(ctx)?;
                 frame.push(JsValue::new(x));
             }
@@ -1213,6 +1339,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 ip = ip.add(4);
                 let name = unwrap_unchecked(frame.code_block).names[name as usize];
                 let object = frame.pop();
+                frame.ip = ip;
                 object.check_object_coercible(ctx)?;
                 letroot!(object = gcstack, object.to_object(ctx)?);
                 frame.push(JsValue::new(object.delete(
@@ -1222,6 +1349,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 )?));
             }
             Opcode::OP_DELETE_BY_VAL => {
+                frame.ip = ip;
                 let object = frame.pop();
                 let name = frame.pop().to_symbol(ctx)?;
                 object.check_object_coercible(ctx)?;
@@ -1233,18 +1361,39 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 )?));
             }
             Opcode::OP_AND => {
-                let lhs = frame.pop().to_int32(ctx)?;
-                let rhs = frame.pop().to_int32(ctx)?;
+                frame.ip = ip;
+                let lhs = frame.pop().to_numeric(ctx)?;
+                let rhs = frame.pop().to_numeric(ctx)?;
+                if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                    frame.push(JsBigInt::binary(ctx, BigIntOp::And, lhs, rhs)?);
+                    continue;
+                }
+                let lhs = lhs.to_int32(ctx)?;
+                let rhs = rhs.to_int32(ctx)?;
                 frame.push(JsValue::new(lhs & rhs));
             }
             Opcode::OP_OR => {
-                let lhs = frame.pop().to_int32(ctx)?;
-                let rhs = frame.pop().to_int32(ctx)?;
+                frame.ip = ip;
+                let lhs = frame.pop().to_numeric(ctx)?;
+                let rhs = frame.pop().to_numeric(ctx)?;
+                if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                    frame.push(JsBigInt::binary(ctx, BigIntOp::Or, lhs, rhs)?);
+                    continue;
+                }
+                let lhs = lhs.to_int32(ctx)?;
+                let rhs = rhs.to_int32(ctx)?;
                 frame.push(JsValue::new(lhs | rhs));
             }
             Opcode::OP_XOR => {
-                let lhs = frame.pop().to_int32(ctx)?;
-                let rhs = frame.pop().to_int32(ctx)?;
+                frame.ip = ip;
+                let lhs = frame.pop().to_numeric(ctx)?;
+                let rhs = frame.pop().to_numeric(ctx)?;
+                if unlikely(lhs.is_bigint() || rhs.is_bigint()) {
+                    frame.push(JsBigInt::binary(ctx, BigIntOp::Xor, lhs, rhs)?);
+                    continue;
+                }
+                let lhs = lhs.to_int32(ctx)?;
+                let rhs = rhs.to_int32(ctx)?;
                 frame.push(JsValue::new(lhs ^ rhs));
             }
             Opcode::OP_GET_FUNCTION => {
@@ -1271,6 +1420,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 let count = ip.cast::<u32>().read_unaligned();
 
                 ip = ip.add(4);
+                frame.ip = ip;
                 letroot!(arr = gcstack, JsArray::new(ctx, count));
                 let mut index = 0;
                 let mut did_put = 0;
@@ -1303,6 +1453,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 ip = ip.add(4);
                 let effect = ip.cast::<u32>().read();
                 ip = ip.add(4);
+                frame.ip = ip;
                 super::builtins::BUILTINS[builtin_id as usize](
                     ctx,
                     frame,
@@ -1318,6 +1469,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                     User code can't get access to this value, if it does this should be repoctxed.
                 */
                 let value = frame.pop();
+                frame.ip = ip;
                 let spread = SpreadValue::new(ctx, value)?;
                 frame.push(JsValue::encode_object_value(spread));
             }
@@ -1327,6 +1479,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 frame.push(JsValue::new(str));
             }
             Opcode::OP_TO_INTEGER_OR_INFINITY | Opcode::OP_TO_LENGTH => {
+                frame.ip = ip;
                 let number = frame.pop().to_number(ctx)?;
                 if number.is_nan() || number == 0.0 {
                     frame.push(JsValue::encode_int32(0));
@@ -1335,6 +1488,7 @@ pub unsafe fn eval(mut ctx: GcPointer<Context>, frame: *mut CallFrame) -> Result
                 }
             }
             Opcode::OP_TO_OBJECT => {
+                frame.ip = ip;
                 let target = frame.pop();
                 let message = frame.pop();
                 if unlikely(target.is_null() || target.is_undefined()) {
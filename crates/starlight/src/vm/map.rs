@@ -1,113 +1,215 @@
-use super::context::Context;
 /* This Source Code Form is subject to the terms of the Mozilla Public
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+use super::class::JsClass;
+use super::context::Context;
 use super::value::HashValueZero;
+use crate::define_jsclass;
 use crate::prelude::*;
 use std::collections::HashMap;
-use std::intrinsics::*;
-pub type MapInternal = HashMap<HashValueZero, JsValue>;
+use std::mem::{size_of, ManuallyDrop};
 
-pub struct JsMap {
-    storage: MapInternal,
+/// Entries of a `Map` or `Set` in insertion order. Deleted entries leave a hole behind so the
+/// position of an iterator stays valid while the collection changes under it.
+#[derive(Default)]
+pub struct MapStorage {
+    entries: Vec<Option<(JsValue, JsValue)>>,
+    indices: HashMap<HashValueZero, usize>,
 }
 
-impl JsMap {
-    pub fn storage_mut(&mut self) -> &mut MapInternal {
-        &mut self.storage
+impl MapStorage {
+    /// Keys are compared with SameValueZero, `-0` is stored as `+0`.
+    fn normalize(key: JsValue) -> JsValue {
+        if key.is_number() && key.get_number() == 0.0 {
+            JsValue::new(0i32)
+        } else {
+            key
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.indices.len()
     }
 
-    pub fn storage(&self) -> &MapInternal {
-        &self.storage
+    pub fn has(&self, key: JsValue) -> bool {
+        self.indices.contains_key(&HashValueZero(key))
     }
 
-    pub fn has(&self, val: JsValue) -> bool {
-        let val = HashValueZero(val);
-        self.storage.contains_key(&val)
+    pub fn get(&self, key: JsValue) -> JsValue {
+        match self.indices.get(&HashValueZero(key)) {
+            Some(&index) => self.entries[index].unwrap().1,
+            None => JsValue::encode_undefined_value(),
+        }
     }
 
-    pub fn get(&self, val: JsValue) -> JsValue {
-        let key = HashValueZero(val);
-        self.storage
-            .get(&key)
-            .copied()
-            .unwrap_or(JsValue::encode_undefined_value())
+    pub fn set(&mut self, key: JsValue, value: JsValue) -> Option<JsValue> {
+        let key = Self::normalize(key);
+        match self.indices.get(&HashValueZero(key)) {
+            Some(&index) => self.entries[index]
+                .replace((key, value))
+                .map(|(_, old)| old),
+            None => {
+                self.indices.insert(HashValueZero(key), self.entries.len());
+                self.entries.push(Some((key, value)));
+                None
+            }
+        }
     }
 
-    pub fn set(&mut self, key: JsValue, val: JsValue) -> Option<JsValue> {
-        self.storage.insert(HashValueZero(key), val)
+    pub fn delete(&mut self, key: JsValue) -> Option<JsValue> {
+        let index = self.indices.remove(&HashValueZero(key))?;
+        self.entries[index].take().map(|(_, value)| value)
     }
 
+    /// Removes every entry. Iterators created before stay past the cleared entries.
     pub fn clear(&mut self) {
-        self.storage.clear();
+        self.indices.clear();
+        for entry in self.entries.iter_mut() {
+            *entry = None;
+        }
     }
 
-    pub fn delete(&mut self, key: JsValue) -> Option<JsValue> {
-        self.storage.remove(&HashValueZero(key))
+    /// First live entry at or after `index` together with its position.
+    pub fn entry_from(&self, index: usize) -> Option<(usize, JsValue, JsValue)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .skip(index)
+            .find_map(|(index, entry)| entry.map(|(key, value)| (index, key, value)))
     }
 
-    pub fn initialize(mut ctx: GcPointer<Context>, input: JsValue, it: JsValue) -> Result<JsValue, JsValue> {
-        if unlikely(!input.is_jsobject()) {
-            return Err(JsValue::new(
-                ctx.new_type_error("MapInitialize to non-object"),
-            ));
-        }
+    /// Live entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (JsValue, JsValue)> + '_ {
+        self.entries.iter().filter_map(|entry| *entry)
+    }
+}
 
-        let stack = ctx.shadowstack();
-        letroot!(obj = stack, input.get_jsobject());
-        if unlikely(!obj.is_extensible()) {
-            return Err(JsValue::new(
-                ctx.new_type_error("MapInitialize to un-extensible object"),
-            ));
-        }
-        let mut iterable = None;
-        let mut adder = None;
-        if !it.is_undefined() {
-            iterable = Some(it.to_object(ctx)?);
-            let val = obj.get(ctx, "set".intern())?;
-            if unlikely(!val.is_callable()) {
-                return Err(JsValue::new(
-                    ctx.new_type_error("MapInitialize adder, `obj.set` is not callable"),
-                ));
-            }
-            adder = Some(val.get_jsobject());
+unsafe impl Trace for MapStorage {
+    fn trace(&mut self, visitor: &mut dyn Tracer) {
+        for (key, value) in self.entries.iter_mut().flatten() {
+            key.trace(visitor);
+            value.trace(visitor);
         }
+    }
+}
 
-        let mut data = ctx.heap().allocate(MapInternal::new());
-        obj.define_own_property(
-            ctx,
-            "[[MapData]]".intern().private(),
-            &*DataDescriptor::new(JsValue::new(data), W | C | E),
-            false,
-        )?;
-
-        if let Some(mut iterable) = iterable {
-            let mut names = vec![];
-            iterable.get_own_property_names(
-                ctx,
-                &mut |name, _| {
-                    names.push(name);
-                },
-                EnumerationMode::Default,
-            );
-
-            for name in names {
-                let v = iterable.get(ctx, name)?;
-                letroot!(item = stack, v.to_object(ctx)?);
-                let key = item.get(ctx, Symbol::Index(0))?;
-                let value = item.get(ctx, Symbol::Index(1))?;
-                let mut slice = [key, value];
-                letroot!(
-                    arg_list = stack,
-                    Arguments::new(JsValue::encode_undefined_value(), &mut slice)
-                );
-                adder.unwrap().as_function_mut().call(
-                    ctx,
-                    &mut arg_list,
-                    JsValue::encode_undefined_value(),
-                )?;
-            }
-        }
-        Ok(JsValue::new(*obj))
+pub struct JsMap {
+    pub(crate) storage: MapStorage,
+}
+
+pub struct JsSet {
+    /// Values are stored as keys, the value of each entry is the key again.
+    pub(crate) storage: MapStorage,
+}
+
+extern "C" fn map_fsz() -> usize {
+    size_of::<JsMap>()
+}
+
+extern "C" fn set_fsz() -> usize {
+    size_of::<JsSet>()
+}
+
+extern "C" fn map_ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize a Map");
+}
+
+extern "C" fn map_deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize a Map");
+}
+
+extern "C" fn set_ser(_: &JsObject, _: &mut SnapshotSerializer) {
+    unreachable!("Cannot serialize a Set");
+}
+
+extern "C" fn set_deser(_: &mut JsObject, _: &mut Deserializer) {
+    unreachable!("Cannot deserialize a Set");
+}
+
+extern "C" fn drop_map(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<JsMap>()) }
+}
+
+extern "C" fn drop_set(obj: GcPointer<JsObject>) {
+    unsafe { ManuallyDrop::drop(obj.data::<JsSet>()) }
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn map_trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<JsMap>().storage.trace(tracer);
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn set_trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<JsSet>().storage.trace(tracer);
+}
+
+define_jsclass!(
+    JsMap,
+    Map,
+    Object,
+    Some(drop_map),
+    Some(map_trace),
+    Some(map_deser),
+    Some(map_ser),
+    Some(map_fsz)
+);
+
+define_jsclass!(
+    JsSet,
+    Set,
+    Set,
+    Some(drop_set),
+    Some(set_trace),
+    Some(set_deser),
+    Some(set_ser),
+    Some(set_fsz)
+);
+
+impl JsClass for JsMap {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+impl JsClass for JsSet {
+    fn class() -> &'static Class {
+        Self::get_class()
+    }
+}
+
+impl JsMap {
+    pub fn new(ctx: GcPointer<Context>, structure: &GcPointer<Structure>) -> GcPointer<JsObject> {
+        let object = JsObject::new(ctx, structure, Self::get_class(), ObjectTag::Ordinary);
+        *object.data::<JsMap>() = ManuallyDrop::new(JsMap {
+            storage: MapStorage::default(),
+        });
+        object
+    }
+}
+
+impl JsSet {
+    pub fn new(ctx: GcPointer<Context>, structure: &GcPointer<Structure>) -> GcPointer<JsObject> {
+        let object = JsObject::new(ctx, structure, Self::get_class(), ObjectTag::Ordinary);
+        *object.data::<JsSet>() = ManuallyDrop::new(JsSet {
+            storage: MapStorage::default(),
+        });
+        object
+    }
+
+    pub fn add(&mut self, value: JsValue) {
+        let value = MapStorage::normalize(value);
+        self.storage.set(value, value);
+    }
+}
+
+/// Entries of a `Map` or `Set` object.
+pub fn storage_of(object: &mut GcPointer<JsObject>) -> Option<&mut MapStorage> {
+    if object.is_class(JsMap::get_class()) {
+        Some(&mut object.data::<JsMap>().storage)
+    } else if object.is_class(JsSet::get_class()) {
+        Some(&mut object.data::<JsSet>().storage)
+    } else {
+        None
     }
 }
@@ -1,44 +1,81 @@
 /* This Source Code Form is subject to the terms of the Mozilla Public
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+use std::collections::HashSet;
+
 use crate::{
     gc::{cell::GcCell, snapshot::serializer::Serializable},
     prelude::*,
 };
 
-use super::context::Context;
+use super::{context::Context, operations};
+/// Iterator used by `for-in`. Keys are snapshotted when the loop starts, properties added during
+/// enumeration are not visited and properties deleted before they are visited are skipped.
 pub struct NativeIterator {
+    object: Option<GcPointer<JsObject>>,
     names: Vec<Symbol>,
     at: u32,
 }
 
 impl NativeIterator {
     #[allow(clippy::should_implement_trait)]
-    pub fn next(&mut self) -> Option<Symbol> {
-        if self.at != self.names.len() as u32 {
+    pub fn next(&mut self, ctx: GcPointer<Context>) -> Option<Symbol> {
+        while self.at != self.names.len() as u32 {
             let result = self.names[self.at as usize];
             self.at += 1;
-            return Some(result);
+            match self.object {
+                Some(ref mut object) if !object.has_property(ctx, result) => continue,
+                _ => return Some(result),
+            }
         }
         None
     }
 
-    pub fn new(mut ctx: GcPointer<Context>, obj: GcPointer<dyn GcCell>) -> GcPointer<Self> {
+    /// EnumerateObjectProperties. Keys of each object in the prototype chain are visited in
+    /// `[[OwnPropertyKeys]]` order, a key shadowed by an earlier object is not reported again even
+    /// if the shadowing property is not enumerable.
+    pub fn new(
+        mut ctx: GcPointer<Context>,
+        obj: GcPointer<dyn GcCell>,
+    ) -> Result<GcPointer<Self>, JsValue> {
         let mut names = vec![];
-        if let Some(mut obj) = obj.downcast::<JsObject>() {
-            obj.get_property_names(
-                ctx,
-                &mut |name, _| names.push(name),
-                EnumerationMode::Default,
-            );
+        let mut object = None;
+        if let Some(obj) = obj.downcast::<JsObject>() {
+            let stack = ctx.shadowstack();
+            letroot!(target = stack, obj);
+            letroot!(current = stack, obj);
+            let mut visited = HashSet::new();
+            loop {
+                for key in operations::own_property_keys(ctx, &mut current)? {
+                    if let Symbol::Private(_) = key {
+                        continue;
+                    }
+                    if !visited.insert(key) {
+                        continue;
+                    }
+                    match operations::get_own_property(ctx, &mut current, key)? {
+                        Some(desc) if desc.is_enumerable() => names.push(key),
+                        _ => (),
+                    }
+                }
+                match operations::get_prototype_of(ctx, &mut current)? {
+                    Some(proto) => *current = proto,
+                    None => break,
+                }
+            }
+            object = Some(*target);
         } else if let Some(string) = obj.downcast::<JsString>() {
-            for i in 0..string.as_str().len() {
+            for i in 0..string.len() {
                 names.push(Symbol::Index(i as _));
             }
         } else {
             todo!()
         }
-        ctx.heap().allocate(Self { names, at: 0 })
+        Ok(ctx.heap().allocate(Self {
+            object,
+            names,
+            at: 0,
+        }))
     }
 }
 
@@ -54,5 +91,7 @@ impl Serializable for NativeIterator {
 }
 
 unsafe impl Trace for NativeIterator {
-    fn trace(&mut self, _visitor: &mut dyn Tracer) {}
+    fn trace(&mut self, visitor: &mut dyn Tracer) {
+        self.object.trace(visitor);
+    }
 }
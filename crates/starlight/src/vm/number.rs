@@ -5,6 +5,13 @@ use crate::prelude::*;
 use std::mem::{size_of, ManuallyDrop};
 
 use super::context::Context;
+
+/// Number::toString(value) with radix 10: `NaN`, `Infinity`, `0` for `-0` and exponent form
+/// outside of `[1e-7, 1e21)`.
+pub fn f64_to_string(value: f64) -> String {
+    ryu_js::Buffer::new().format(value).to_string()
+}
+
 pub struct NumberObject {
     value: f64,
 }
@@ -10,8 +10,10 @@ use super::{
     function::*,
     global::JsGlobal,
     indexed_elements::IndexedElements,
+    operations,
     property_descriptor::StoredSlot,
     property_descriptor::{DataDescriptor, PropertyDescriptor},
+    proxy::JsProxy,
     slot::*,
     string::*,
     structure::Structure,
@@ -282,6 +284,10 @@ impl JsObject {
                 Some(proto) => *obj = *proto,
                 _ => break false,
             }
+            // proxy in prototype chain answers with its `has` trap.
+            if JsProxy::is_proxy(&obj) {
+                break obj.get_non_indexed_property_slot(ctx, name, slot);
+            }
         }
     }
 
@@ -314,6 +320,9 @@ impl JsObject {
         slot: &mut Slot,
         throwable: bool,
     ) -> Result<(), JsValue> {
+        if unlikely(operations::has_proxy_prototype(obj)) {
+            return obj.put_through_proxy_prototype(ctx, name, val, slot, throwable);
+        }
         let stack = ctx.shadowstack();
         if !obj.can_put(ctx, name, slot) {
             if throwable {
@@ -407,6 +416,15 @@ impl JsObject {
         slot: &mut Slot,
         throwable: bool,
     ) -> Result<(), JsValue> {
+        if unlikely(operations::has_proxy_prototype(obj)) {
+            return obj.put_through_proxy_prototype(
+                ctx,
+                Symbol::Index(index),
+                val,
+                slot,
+                throwable,
+            );
+        }
         if index < MAX_VECTOR_SIZE as u32
             && obj.indexed.dense()
             && obj.class.method_table.GetOwnIndexedPropertySlot as usize
@@ -493,6 +511,9 @@ impl JsObject {
                 Some(proto) => *obj = *proto,
                 None => break false,
             }
+            if JsProxy::is_proxy(&obj) {
+                break obj.get_indexed_property_slot(ctx, index, slot);
+            }
         }
     }
 
@@ -644,6 +665,10 @@ impl JsObject {
         name: Symbol,
         slot: &mut Slot,
     ) -> Result<JsValue, JsValue> {
+        if unlikely(operations::has_proxy_prototype(obj)) {
+            slot.make_uncacheable();
+            return operations::get_with_receiver(ctx, obj, name, JsValue::new(*obj));
+        }
         if obj.get_non_indexed_property_slot(ctx, name, slot) {
             return slot.get(ctx, JsValue::encode_object_value(obj.as_dyn()));
         }
@@ -655,6 +680,11 @@ impl JsObject {
         index: u32,
         slot: &mut Slot,
     ) -> Result<JsValue, JsValue> {
+        if unlikely(operations::has_proxy_prototype(obj)) {
+            slot.make_uncacheable();
+            let receiver = JsValue::new(*obj);
+            return operations::get_with_receiver(ctx, obj, Symbol::Index(index), receiver);
+        }
         if obj.get_indexed_property_slot(ctx, index, slot) {
             return slot.get(ctx, JsValue::encode_object_value(obj.as_dyn()));
         }
@@ -805,12 +835,17 @@ impl JsObject {
         }
 
         if let Some(map) = &obj.indexed.map {
-            for it in map.iter() {
-                if mode == EnumerationMode::IncludeNotEnumerable
-                    || it.1.attributes().is_enumerable()
-                {
-                    collector(Symbol::Index(*it.0), u32::MAX);
-                }
+            let mut indices = map
+                .iter()
+                .filter(|it| {
+                    mode == EnumerationMode::IncludeNotEnumerable
+                        || it.1.attributes().is_enumerable()
+                })
+                .map(|it| *it.0)
+                .collect::<Vec<_>>();
+            indices.sort_unstable();
+            for index in indices {
+                collector(Symbol::Index(index), u32::MAX);
             }
         }
 
@@ -1000,33 +1035,33 @@ impl GcPointer<JsObject> {
         hint: JsHint,
     ) -> Result<JsValue, JsValue> {
         let stack = ctx.shadowstack();
-        let exotic_to_prim = self.get_method(ctx, "toPrimitive".intern());
-
         letroot!(obj = stack, *self);
-        match exotic_to_prim {
-            Ok(val) => {
-                // downcast_unchecked here is safe because `get_method` returns `Err` if property is not a function.
-                letroot!(func = stack, unsafe {
-                    val.get_object().downcast_unchecked::<JsObject>()
-                });
-                let f = func.as_function_mut();
-                let mut tmp = [JsValue::encode_undefined_value()];
-                letroot!(
-                    args = stack,
-                    Arguments::new(JsValue::encode_object_value(*obj), &mut tmp,)
-                );
-
-                *args.at_mut(0) = match hint {
-                    JsHint::Number | JsHint::None => {
-                        JsValue::encode_object_value(JsString::new(ctx, "number"))
-                    }
-                    JsHint::String => JsValue::encode_object_value(JsString::new(ctx, "string")),
-                };
-
-                f.call(ctx, &mut args, val)
-            }
-            _ => (self.class.method_table.DefaultValue)(&mut obj, ctx, hint),
-        }
+        let exotic_to_prim = obj.get(ctx, "Symbol.toPrimitive".intern().private())?;
+        if exotic_to_prim.is_undefined() || exotic_to_prim.is_null() {
+            return (self.class.method_table.DefaultValue)(&mut obj, ctx, hint);
+        }
+        if unlikely(!exotic_to_prim.is_callable()) {
+            return Err(JsValue::new(
+                ctx.new_type_error("Symbol.toPrimitive is not a function"),
+            ));
+        }
+        letroot!(func = stack, exotic_to_prim.get_jsobject());
+        let hint = match hint {
+            JsHint::Number => "number",
+            JsHint::String => "string",
+            JsHint::None => "default",
+        };
+        let mut tmp = [JsValue::new(JsString::new(ctx, hint))];
+        letroot!(args = stack, Arguments::new(JsValue::new(*obj), &mut tmp));
+        let result = func
+            .as_function_mut()
+            .call(ctx, &mut args, exotic_to_prim)?;
+        if unlikely(result.is_jsobject()) {
+            return Err(JsValue::new(ctx.new_type_error(
+                "Symbol.toPrimitive must return a primitive value",
+            )));
+        }
+        Ok(result)
     }
     pub fn delete_non_indexed(
         &mut self,
@@ -1093,6 +1128,26 @@ impl GcPointer<JsObject> {
     ) -> bool {
         (self.class.method_table.GetOwnNonIndexedPropertySlot)(self, ctx, name, slot)
     }
+    /// `[[Set]]` of an object with a proxy in its prototype chain, the proxy's `set` trap is called
+    /// with this object as receiver if the property is not found before it.
+    fn put_through_proxy_prototype(
+        &mut self,
+        ctx: GcPointer<Context>,
+        name: Symbol,
+        val: JsValue,
+        slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<(), JsValue> {
+        slot.make_put_uncacheable();
+        let receiver = JsValue::new(*self);
+        if !operations::set_with_receiver(ctx, self, name, val, receiver)? && throwable {
+            return Err(JsValue::new(ctx.new_type_error(format!(
+                "Cannot assign to read only property '{}'",
+                ctx.description(name)
+            ))));
+        }
+        Ok(())
+    }
     pub fn can_put(&mut self, ctx: GcPointer<Context>, name: Symbol, slot: &mut Slot) -> bool {
         if let Symbol::Index(index) = name {
             self.can_put_indexed(ctx, index, slot)
@@ -1602,6 +1657,48 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_property_order() {
+        crate::vm::tests::assert_eval_cases(&[
+            ("Object.keys({ b: 1, 2: 1, a: 1, 1: 1 }).join()", "1,2,b,a"),
+            ("var o = { 10: 1, 9: 1 }; o['08'] = 1; Object.keys(o).join()", "9,10,08"),
+            (
+                "var o = { a: 1, b: 2, c: 3 }; delete o.a; o.a = 4; Object.keys(o).join()",
+                "b,c,a",
+            ),
+            (
+                "var o = {}; for (var i = 0; i < 200; i++) o['k' + i] = i; \
+                 for (var i = 0; i < 200; i += 2) delete o['k' + i]; \
+                 o.k0 = 0; var k = Object.keys(o); k.length + ',' + k[0] + ',' + k[99] + ',' + k[100]",
+                "101,k1,k199,k0",
+            ),
+            (
+                "var p = { a: 1, x: 1 }; var o = Object.create(p); o.b = 1; o.a = 2; \
+                 var r = []; for (var k in o) r.push(k); r.join()",
+                "b,a,x",
+            ),
+            (
+                "var o = { a: 1, b: 2, c: 3 }; var r = []; for (var k in o) { r.push(k); delete o.b; } r.join()",
+                "a,c",
+            ),
+            (
+                "var p = { x: 1 }; var o = Object.create(p); \
+                 Object.defineProperty(o, 'x', { value: 1, enumerable: false }); \
+                 var r = []; for (var k in o) r.push(k); r.length",
+                "0",
+            ),
+            (
+                "var s = Symbol('s'); var o = {}; o[s] = 1; o.b = 1; o[1] = 1; \
+                 Reflect.ownKeys(o).map(String).join()",
+                "1,b,Symbol(s)",
+            ),
+            (
+                "var r = []; for (var k in 'ab') r.push(k); r.join()",
+                "0,1",
+            ),
+        ]);
+    }
 }
 
 impl JsClass for JsObject {
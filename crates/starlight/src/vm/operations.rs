@@ -1,9 +1,11 @@
 /* This Source Code Form is subject to the terms of the Mozilla Public
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
-use crate::prelude::*;
+use std::intrinsics::unlikely;
 
-use super::context::Context;
+use crate::{constant::S_CONSTURCTOR, prelude::*};
+
+use super::{context::Context, proxy::JsProxy};
 
 pub fn normalize_prototype_chain(ctx: GcPointer<Context>, base: &GcPointer<JsObject>) -> (usize, bool) {
     let mut saw_poly_proto = false;
@@ -30,3 +32,402 @@ pub fn normalize_prototype_chain(ctx: GcPointer<Context>, base: &GcPointer<JsObj
         count += 1;
     }
 }
+
+/// Object internal methods that may run user code. Proxies dispatch to their handler traps, other
+/// objects use the ordinary behaviour through their `method_table`.
+///
+/// `[[GetPrototypeOf]]`
+pub fn get_prototype_of(
+    ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+) -> Result<Option<GcPointer<JsObject>>, JsValue> {
+    if JsProxy::is_proxy(obj) {
+        return JsProxy::get_prototype_of(ctx, obj);
+    }
+    Ok(obj.prototype().copied())
+}
+
+/// `[[SetPrototypeOf]]`
+pub fn set_prototype_of(
+    ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+    proto: Option<GcPointer<JsObject>>,
+) -> Result<bool, JsValue> {
+    if JsProxy::is_proxy(obj) {
+        return JsProxy::set_prototype_of(ctx, obj, proto);
+    }
+    let same = match (obj.prototype(), proto) {
+        (Some(current), Some(proto)) => GcPointer::ptr_eq(current, &proto),
+        (None, None) => true,
+        _ => false,
+    };
+    if same {
+        return Ok(true);
+    }
+    if !obj.is_extensible() {
+        return Ok(false);
+    }
+    let mut p = proto;
+    while let Some(object) = p {
+        if GcPointer::ptr_eq(&object, obj) {
+            return Ok(false);
+        }
+        // proxies have their own [[GetPrototypeOf]], stop looking for cycles there.
+        if JsProxy::is_proxy(&object) {
+            break;
+        }
+        p = object.prototype().copied();
+    }
+    let structure = obj.structure.change_prototype_transition(ctx, proto);
+    obj.structure = structure;
+    Ok(true)
+}
+
+/// `[[IsExtensible]]`
+pub fn is_extensible(
+    ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+) -> Result<bool, JsValue> {
+    if JsProxy::is_proxy(obj) {
+        return JsProxy::is_extensible(ctx, obj);
+    }
+    Ok(obj.is_extensible())
+}
+
+/// `[[PreventExtensions]]`
+pub fn prevent_extensions(
+    ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+) -> Result<bool, JsValue> {
+    if JsProxy::is_proxy(obj) {
+        return JsProxy::prevent_extensions(ctx, obj);
+    }
+    obj.change_extensible(ctx, false);
+    Ok(true)
+}
+
+/// `[[GetOwnProperty]]`
+pub fn get_own_property(
+    ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+    key: Symbol,
+) -> Result<Option<PropertyDescriptor>, JsValue> {
+    if JsProxy::is_proxy(obj) {
+        return JsProxy::get_own_property(ctx, obj, key);
+    }
+    Ok(obj.get_own_property(ctx, key))
+}
+
+/// `[[HasProperty]]`
+pub fn has_property(
+    ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+    key: Symbol,
+) -> Result<bool, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(obj = stack, *obj);
+    loop {
+        if JsProxy::is_proxy(&obj) {
+            return JsProxy::has(ctx, &mut obj, key);
+        }
+        if obj.has_own_property(ctx, key) {
+            return Ok(true);
+        }
+        match obj.prototype() {
+            Some(proto) => *obj = *proto,
+            None => return Ok(false),
+        }
+    }
+}
+
+/// `[[OwnPropertyKeys]]`. Integer indices in ascending order are followed by string keys and then
+/// symbol keys in property creation order.
+pub fn own_property_keys(
+    ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+) -> Result<Vec<Symbol>, JsValue> {
+    if JsProxy::is_proxy(obj) {
+        return JsProxy::own_keys(ctx, obj);
+    }
+    let mut names = vec![];
+    obj.get_own_property_names(
+        ctx,
+        &mut |name, _| names.push(name),
+        EnumerationMode::IncludeNotEnumerable,
+    );
+    // named properties are already reported in creation order, keep it with a stable sort.
+    names.sort_by_key(|&name| match name {
+        Symbol::Index(index) => (0, index),
+        Symbol::Key(_) => (1, 0),
+        Symbol::Private(_) => (2, 0),
+    });
+    Ok(names)
+}
+
+/// `[[Get]]` with explicit receiver.
+pub fn get_with_receiver(
+    ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+    key: Symbol,
+    receiver: JsValue,
+) -> Result<JsValue, JsValue> {
+    if JsProxy::is_proxy(obj) {
+        return JsProxy::get(ctx, obj, key, receiver);
+    }
+    let stack = ctx.shadowstack();
+    let mut slot = Slot::new();
+    match lookup_property(ctx, obj, key, &mut slot) {
+        Lookup::Found => slot.get(ctx, receiver),
+        Lookup::NotFound => Ok(JsValue::encode_undefined_value()),
+        Lookup::Proxy(proxy) => {
+            letroot!(proxy = stack, proxy);
+            JsProxy::get(ctx, &mut proxy, key, receiver)
+        }
+    }
+}
+
+/// Returns `true` if there is a proxy in the prototype chain of `obj`. Slot lookups can't see
+/// through it so `[[Get]]` and `[[Set]]` have to go through [get_with_receiver] and
+/// [set_with_receiver] then.
+pub fn has_proxy_prototype(obj: &JsObject) -> bool {
+    let mut proto = obj.prototype();
+    while let Some(object) = proto {
+        if JsProxy::is_proxy(object) {
+            return true;
+        }
+        proto = object.prototype();
+    }
+    false
+}
+
+enum Lookup {
+    Found,
+    NotFound,
+    /// Lookup reached a proxy without finding the property, its traps have to be called with the
+    /// original receiver.
+    Proxy(GcPointer<JsObject>),
+}
+
+/// Finds `key` in `obj` or its prototypes like `get_property_slot` but stops at the first proxy.
+fn lookup_property(
+    ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+    key: Symbol,
+    slot: &mut Slot,
+) -> Lookup {
+    if !has_proxy_prototype(obj) {
+        return if obj.get_property_slot(ctx, key, slot) {
+            Lookup::Found
+        } else {
+            Lookup::NotFound
+        };
+    }
+    let stack = ctx.shadowstack();
+    letroot!(object = stack, *obj);
+    loop {
+        if object.get_own_property_slot(ctx, key, slot) {
+            return Lookup::Found;
+        }
+        match object.prototype() {
+            Some(proto) if JsProxy::is_proxy(proto) => return Lookup::Proxy(*proto),
+            Some(proto) => *object = *proto,
+            None => return Lookup::NotFound,
+        }
+    }
+}
+
+/// `[[Set]]` with explicit receiver. Returns `false` instead of throwing when assignment is
+/// not allowed.
+pub fn set_with_receiver(
+    ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+    key: Symbol,
+    value: JsValue,
+    receiver: JsValue,
+) -> Result<bool, JsValue> {
+    if JsProxy::is_proxy(obj) {
+        return JsProxy::set(ctx, obj, key, value, receiver);
+    }
+    let stack = ctx.shadowstack();
+    let mut slot = Slot::new();
+    let found = match lookup_property(ctx, obj, key, &mut slot) {
+        Lookup::Found => true,
+        Lookup::NotFound => false,
+        Lookup::Proxy(proxy) => {
+            letroot!(proxy = stack, proxy);
+            return JsProxy::set(ctx, &mut proxy, key, value, receiver);
+        }
+    };
+    if found && slot.attributes().is_accessor() {
+        letroot!(setter = stack, slot.accessor().setter());
+        if !setter.is_callable() {
+            return Ok(false);
+        }
+        let mut tmp = [value];
+        letroot!(args = stack, Arguments::new(receiver, &mut tmp));
+        setter
+            .get_jsobject()
+            .as_function_mut()
+            .call(ctx, &mut args, *setter)?;
+        return Ok(true);
+    }
+    if !slot.is_not_found() && !slot.attributes().is_writable() {
+        return Ok(false);
+    }
+    if !receiver.is_jsobject() {
+        return Ok(false);
+    }
+    letroot!(receiver = stack, receiver.get_jsobject());
+    match get_own_property(ctx, &mut receiver, key)? {
+        Some(existing) => {
+            if existing.is_accessor() || !existing.is_writable() {
+                return Ok(false);
+            }
+            receiver.define_own_property(
+                ctx,
+                key,
+                &*DataDescriptor::new(
+                    value,
+                    UNDEF_ENUMERABLE | UNDEF_CONFIGURABLE | UNDEF_WRITABLE,
+                ),
+                false,
+            )
+        }
+        None => {
+            receiver.define_own_property(ctx, key, &*DataDescriptor::new(value, W | C | E), false)
+        }
+    }
+}
+
+/// `IsConstructor(argument)`
+pub fn is_constructor(value: JsValue) -> bool {
+    if !value.is_callable() {
+        return false;
+    }
+    value.get_jsobject().as_function().is_constructor()
+}
+
+/// `Call(F, V, argumentsList)`
+pub fn call(
+    ctx: GcPointer<Context>,
+    func: JsValue,
+    this: JsValue,
+    args: &mut [JsValue],
+) -> Result<JsValue, JsValue> {
+    if unlikely(!func.is_callable()) {
+        return Err(JsValue::new(ctx.new_type_error("not a function")));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(function = stack, func.get_jsobject());
+    letroot!(args = stack, Arguments::new(this, args));
+    function.as_function_mut().call(ctx, &mut args, func)
+}
+
+/// `Construct(F, argumentsList, newTarget)`, both `constructor` and `new_target` must satisfy
+/// [is_constructor]. `new_target` defaults to `constructor`.
+pub fn construct(
+    ctx: GcPointer<Context>,
+    constructor: JsValue,
+    args: &mut [JsValue],
+    new_target: Option<JsValue>,
+) -> Result<JsValue, JsValue> {
+    let stack = ctx.shadowstack();
+    letroot!(func = stack, constructor.get_jsobject());
+    letroot!(
+        new_target = stack,
+        new_target.unwrap_or(constructor).get_jsobject()
+    );
+    letroot!(structure = stack, new_target.func_construct_map(ctx)?);
+    let object = JsObject::new(ctx, &structure, JsObject::get_class(), ObjectTag::Ordinary);
+    letroot!(args = stack, Arguments::new(JsValue::new(object), args));
+    args.ctor_call = true;
+    let result = func.as_function_mut().call(ctx, &mut args, constructor)?;
+    // Native constructors create their object from their own prototype, the one of new.target
+    // has to replace it. Proxies take no prototype from new.target.
+    if func.as_function().is_native()
+        && !GcPointer::ptr_eq(&*func, &*new_target)
+        && result.is_jsobject()
+        && !JsProxy::is_proxy(&result.get_jsobject())
+    {
+        letroot!(result_object = stack, result.get_jsobject());
+        let proto = new_target.get(ctx, "prototype".intern())?;
+        if proto.is_jsobject() {
+            set_prototype_of(ctx, &mut result_object, Some(proto.get_jsobject()))?;
+        }
+    }
+    Ok(result)
+}
+
+/// `SpeciesConstructor(O, defaultConstructor)`
+pub fn species_constructor(
+    ctx: GcPointer<Context>,
+    obj: &mut GcPointer<JsObject>,
+    default: JsValue,
+) -> Result<JsValue, JsValue> {
+    let constructor = obj.get(ctx, S_CONSTURCTOR.intern())?;
+    if constructor.is_undefined() {
+        return Ok(default);
+    }
+    if unlikely(!constructor.is_jsobject()) {
+        return Err(JsValue::new(
+            ctx.new_type_error("object.constructor is not an object"),
+        ));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(constructor = stack, constructor.get_jsobject());
+    let species = constructor.get(ctx, "Symbol.species".intern().private())?;
+    if species.is_undefined() || species.is_null() {
+        return Ok(default);
+    }
+    if unlikely(!is_constructor(species)) {
+        return Err(JsValue::new(ctx.new_type_error(
+            "object.constructor[Symbol.species] is not a constructor",
+        )));
+    }
+    Ok(species)
+}
+
+/// `InstanceofOperator(V, target)`, `target[Symbol.hasInstance]` is used when present.
+pub fn instance_of(
+    ctx: GcPointer<Context>,
+    value: JsValue,
+    target: JsValue,
+) -> Result<bool, JsValue> {
+    if unlikely(!target.is_jsobject()) {
+        return Err(JsValue::new(
+            ctx.new_type_error("'instanceof' requires object"),
+        ));
+    }
+    let stack = ctx.shadowstack();
+    letroot!(target_object = stack, target.get_jsobject());
+    let handler = target_object.get(ctx, "Symbol.hasInstance".intern().private())?;
+    if !handler.is_undefined() && !handler.is_null() {
+        if unlikely(!handler.is_callable()) {
+            return Err(JsValue::new(
+                ctx.new_type_error("Symbol.hasInstance is not a function"),
+            ));
+        }
+        return Ok(call(ctx, handler, target, &mut [value])?.to_boolean());
+    }
+    if unlikely(!target.is_callable()) {
+        return Err(JsValue::new(
+            ctx.new_type_error("'instanceof' requires constructor"),
+        ));
+    }
+    letroot!(function = stack, *target_object);
+    target_object
+        .as_function()
+        .has_instance(&mut function, ctx, value)
+}
+
+/// `IsArray(argument)`, proxies of arrays are arrays too.
+pub fn is_array(ctx: GcPointer<Context>, value: JsValue) -> Result<bool, JsValue> {
+    if !value.is_jsobject() {
+        return Ok(false);
+    }
+    let object = value.get_jsobject();
+    if JsProxy::is_proxy(&object) {
+        return JsProxy::is_array(ctx, &object);
+    }
+    Ok(object.tag() == ObjectTag::Array)
+}
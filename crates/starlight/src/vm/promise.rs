@@ -17,6 +17,21 @@ use crate::vm::function::JsClosureFunction;
 use crate::vm::structure::Structure;
 use std::mem::ManuallyDrop;
 
+/// Creates `AggregateError` that `Promise.any` rejects with.
+fn all_rejected_error(ctx: GcPointer<Context>, errors: &[JsValue]) -> JsValue {
+    let msg = JsString::new(ctx, "All promises were rejected");
+    let stack = ctx.shadowstack();
+    letroot!(error = stack, JsAggregateError::new(ctx, msg, None));
+    let errors = JsArray::from_slice(ctx, errors);
+    let _ = error.define_own_property(
+        ctx,
+        "errors".intern(),
+        &*DataDescriptor::new(JsValue::new(errors), W | C),
+        false,
+    );
+    JsValue::new(*error)
+}
+
 pub enum TrackingMode {
     All,
     Race,
@@ -152,6 +167,8 @@ impl JsPromise {
         // todo for array.length add None to results vec
         // todo add handler to every promise with index, resolve that index in vec, check followup action based on mode
 
+        // `Promise.any` of nothing can never be fulfilled
+        let reject_empty = length == 0 && matches!(mode, TrackingMode::Any);
         let structure = Structure::new_indexed(ctx, Some(proto), false);
         let mut obj = JsObject::new(ctx, &structure, JsPromise::get_class(), ObjectTag::Ordinary);
 
@@ -197,6 +214,10 @@ impl JsPromise {
 
             sub_prom_jsprom.then(ctx, None, None, Some(sub_finally))?;
         }
+        if reject_empty {
+            let error = all_rejected_error(ctx, &[]);
+            obj.as_promise_mut().reject(ctx, promise_value, error)?;
+        }
 
         Ok(promise_value)
     }
@@ -252,7 +273,23 @@ impl JsPromise {
                     Ok(JsValue::encode_null_value())
                 }
                 TrackingMode::AllSettled => Ok(JsValue::encode_null_value()),
-                TrackingMode::Any => Ok(JsValue::encode_null_value()),
+                TrackingMode::Any => {
+                    match resolution {
+                        Ok(ok_res) => self.resolve(ctx, prom_this, ok_res)?,
+                        Err(_) => {
+                            // reject once every promise has been rejected
+                            if !tracking_results.contains(&None) {
+                                let errors = tracking_results
+                                    .iter()
+                                    .map(|x| x.unwrap().err().unwrap())
+                                    .collect::<Vec<_>>();
+                                let error = all_rejected_error(ctx, &errors);
+                                self.reject(ctx, prom_this, error)?;
+                            }
+                        }
+                    }
+                    Ok(JsValue::encode_null_value())
+                }
             }
         } else {
             Ok(JsValue::encode_null_value())
@@ -478,8 +515,10 @@ unsafe impl Trace for JsPromise {
 #[cfg(test)]
 pub mod tests {
 
+    use crate::gc::cell::GcPointer;
     use crate::options::Options;
     use crate::vm::context::Context;
+    use crate::vm::tests::{assert_eval_cases, assert_eval_cases_in};
     use crate::Platform;
     use std::cell::RefCell;
     use std::rc::Rc;
@@ -565,4 +604,93 @@ pub mod tests {
         }
         println!("done running todos");
     }
+
+    #[test]
+    fn test_promise_default_job_queue() {
+        assert_eval_cases(&[
+            (
+                "var log = []; \
+                 Promise.resolve().then(() => { log.push('then'); queueMicrotask(() => log.push('nested')); }); \
+                 queueMicrotask(() => log.push('microtask')); \
+                 log.push('sync'); log.join()",
+                "sync",
+            ),
+            ("log.join()", "sync,then,microtask,nested"),
+            (
+                "var order = []; \
+                 Promise.reject(1).catch(() => order.push('catch')).then(() => order.push('after')); \
+                 Promise.resolve().then(() => { throw 2; }); \
+                 queueMicrotask(() => order.push('microtask')); \
+                 order.length",
+                "0",
+            ),
+            ("order.join()", "catch,microtask,after"),
+            (
+                "try { queueMicrotask(1); } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_run_jobs_after_panicking_job() {
+        Platform::initialize();
+        let mut starlight_runtime = Platform::new_runtime(Options::default(), None);
+        let ctx = Context::new(&mut starlight_runtime);
+
+        starlight_runtime
+            .jobs
+            .push_back((ctx, Box::new(|_: GcPointer<Context>| panic!("job failed"))));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            starlight_runtime.run_jobs();
+        }));
+        assert!(result.is_err());
+        assert!(!starlight_runtime.running_jobs);
+
+        assert_eval_cases_in(
+            ctx,
+            &[
+                (
+                    "var ran = false; queueMicrotask(() => { ran = true; }); ran",
+                    "false",
+                ),
+                ("ran", "true"),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_microtask_exception_is_reported() {
+        Platform::initialize();
+        let errors = Rc::new(RefCell::new(vec![]));
+        let errors2 = errors.clone();
+        let mut starlight_runtime = Platform::new_runtime(Options::default(), None)
+            .with_uncaught_exception_handler(Box::new(move |ctx, error| {
+                let message = error.to_string(ctx).unwrap_or_default();
+                errors2.borrow_mut().push(message);
+            }));
+        let ctx = Context::new(&mut starlight_runtime);
+
+        // a throwing job does not stop the ones queued after it
+        assert_eval_cases_in(
+            ctx,
+            &[
+                (
+                    "var ran = false; \
+                     queueMicrotask(() => { throw new TypeError('first'); }); \
+                     queueMicrotask(() => { ran = true; }); ran",
+                    "false",
+                ),
+                ("ran", "true"),
+                (
+                    "queueMicrotask(() => { throw 'second'; }); Promise.reject(3).catch(() => {}); 1",
+                    "1",
+                ),
+            ],
+        );
+        assert_eq!(
+            *errors.borrow(),
+            vec!["TypeError: first".to_owned(), "second".to_owned()]
+        );
+    }
 }
@@ -55,7 +55,7 @@ impl PropertyDescriptor {
     pub fn accessor_setter(setter: JsValue, attrs: u32) -> Self {
         Self {
             attrs: AttrExternal::new(Some(
-                attrs | ACCESSOR | UNDEF_VALUE | UNDEF_SETTER | UNDEF_WRITABLE,
+                attrs | ACCESSOR | UNDEF_VALUE | UNDEF_GETTER | UNDEF_WRITABLE,
             )),
             value: PropertyLayout {
                 accessors: (JsValue::encode_undefined_value(), setter),
@@ -0,0 +1,1182 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+//! Proxy exotic objects.
+//!
+//! Non-callable proxies are objects with [ObjectTag::Proxy] tag and [JsProxy] as object data.
+//! Proxies whose target is callable are functions ([FuncType::Proxy]) so they can be passed to
+//! `OP_CALL`/`OP_NEW` and native code as any other function. Both kinds use the proxy class
+//! which dispatches the method table to handler traps.
+//!
+//! Method table entries that can't report an exception (`GetOwnPropertySlot`,
+//! `GetPropertySlot`, `GetOwnPropertyNames`...) treat throwing traps as "property not found".
+//! Code that must observe these exceptions uses functions from [crate::vm::operations].
+use std::collections::HashSet;
+use std::mem::ManuallyDrop;
+
+use super::{context::Context, operations};
+use crate::jsrt::{from_property_descriptor, to_property_descriptor};
+use crate::prelude::*;
+
+pub struct JsProxy {
+    /// `None` once proxy is revoked.
+    target: Option<GcPointer<JsObject>>,
+    handler: Option<GcPointer<JsObject>>,
+    constructor: bool,
+}
+
+extern "C" fn fsz() -> usize {
+    std::mem::size_of::<JsProxy>()
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    // callable proxies are traced as part of `JsFunction`.
+    if obj.tag() == ObjectTag::Proxy {
+        obj.data::<JsProxy>().trace(tracer);
+    }
+}
+
+extern "C" fn ser(obj: &JsObject, serializer: &mut SnapshotSerializer) {
+    if obj.tag() == ObjectTag::Proxy {
+        obj.data::<JsProxy>().serialize(serializer);
+    }
+}
+
+extern "C" fn deser(obj: &mut JsObject, deser: &mut Deserializer) {
+    if obj.tag() == ObjectTag::Proxy {
+        unsafe {
+            let proxy = JsProxy::deserialize_inplace(deser);
+            (obj.data::<JsProxy>() as *mut ManuallyDrop<JsProxy> as *mut JsProxy).write(proxy);
+        }
+    }
+}
+
+unsafe impl Trace for JsProxy {
+    fn trace(&mut self, visitor: &mut dyn Tracer) {
+        self.target.trace(visitor);
+        self.handler.trace(visitor);
+    }
+}
+
+impl Serializable for JsProxy {
+    fn serialize(&self, serializer: &mut SnapshotSerializer) {
+        self.target.serialize(serializer);
+        self.handler.serialize(serializer);
+        self.constructor.serialize(serializer);
+    }
+}
+
+impl JsProxy {
+    pub(crate) unsafe fn deserialize_inplace(deser: &mut Deserializer) -> Self {
+        Self {
+            target: Option::<GcPointer<JsObject>>::deserialize_inplace(deser),
+            handler: Option::<GcPointer<JsObject>>::deserialize_inplace(deser),
+            constructor: bool::deserialize_inplace(deser),
+        }
+    }
+}
+
+define_jsclass!(
+    JsProxy,
+    Object,
+    Object,
+    None,
+    Some(trace),
+    Some(deser),
+    Some(ser),
+    Some(fsz)
+);
+
+fn key_to_value(ctx: GcPointer<Context>, key: Symbol) -> JsValue {
+    match key {
+        Symbol::Private(_) => JsValue::new(JsSymbol::new(ctx, key)),
+        _ => JsValue::new(ctx.key_to_jsstring(key)),
+    }
+}
+
+fn invariant_error(ctx: GcPointer<Context>, msg: &str) -> JsValue {
+    JsValue::new(ctx.new_type_error(format!("Proxy invariant violated: {}", msg)))
+}
+
+/// Complete property descriptor returned by `getOwnPropertyDescriptor` trap.
+fn complete_descriptor(desc: &PropertyDescriptor) -> PropertyDescriptor {
+    let mut attrs = NONE;
+    if desc.is_enumerable() {
+        attrs |= E;
+    }
+    if desc.is_configurable() {
+        attrs |= C;
+    }
+    if desc.is_accessor() {
+        let getter = if desc.is_getter_absent() {
+            JsValue::encode_undefined_value()
+        } else {
+            desc.getter()
+        };
+        let setter = if desc.is_setter_absent() {
+            JsValue::encode_undefined_value()
+        } else {
+            desc.setter()
+        };
+        *AccessorDescriptor::new(getter, setter, attrs)
+    } else {
+        if desc.is_writable() {
+            attrs |= W;
+        }
+        let value = if desc.is_value_absent() {
+            JsValue::encode_undefined_value()
+        } else {
+            desc.value()
+        };
+        *DataDescriptor::new(value, attrs)
+    }
+}
+
+fn is_undefined_accessor(value: JsValue) -> bool {
+    value.is_undefined() || value.is_empty()
+}
+
+/// IsCompatiblePropertyDescriptor
+fn is_compatible_descriptor(
+    extensible: bool,
+    desc: &PropertyDescriptor,
+    current: Option<&PropertyDescriptor>,
+) -> bool {
+    let current = match current {
+        Some(current) => current,
+        None => return extensible,
+    };
+    if current.is_configurable() {
+        return true;
+    }
+    if !desc.is_configurable_absent() && desc.is_configurable() {
+        return false;
+    }
+    if !desc.is_enumerable_absent() && desc.is_enumerable() != current.is_enumerable() {
+        return false;
+    }
+    if desc.is_generic() {
+        return true;
+    }
+    if desc.is_accessor() != current.is_accessor() {
+        return false;
+    }
+    if current.is_accessor() {
+        let same = |a: JsValue, b: JsValue| {
+            (is_undefined_accessor(a) && is_undefined_accessor(b)) || JsValue::same_value(a, b)
+        };
+        if !desc.is_getter_absent() && !same(desc.getter(), current.getter()) {
+            return false;
+        }
+        if !desc.is_setter_absent() && !same(desc.setter(), current.setter()) {
+            return false;
+        }
+    } else if !current.is_writable() {
+        if !desc.is_writable_absent() && desc.is_writable() {
+            return false;
+        }
+        if !desc.is_value_absent() && !JsValue::same_value(desc.value(), current.value()) {
+            return false;
+        }
+    }
+    true
+}
+
+#[allow(non_snake_case)]
+impl JsProxy {
+    /// Create new proxy for `target` with `handler`.
+    pub fn new(
+        ctx: GcPointer<Context>,
+        target: GcPointer<JsObject>,
+        handler: GcPointer<JsObject>,
+    ) -> GcPointer<JsObject> {
+        let stack = ctx.shadowstack();
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        let structure = ctx.global_data().proxy_structure.unwrap();
+        if target.is_callable() {
+            let constructor = target.as_function().is_constructor();
+            let mut obj = JsObject::new(ctx, &structure, Self::get_class(), ObjectTag::Function);
+            obj.set_callable(true);
+            *obj.data::<JsFunction>() = ManuallyDrop::new(JsFunction {
+                construct_struct: None,
+                ty: FuncType::Proxy(JsProxy {
+                    target: Some(*target),
+                    handler: Some(*handler),
+                    constructor,
+                }),
+            });
+            obj
+        } else {
+            let mut obj = JsObject::new(ctx, &structure, Self::get_class(), ObjectTag::Proxy);
+            *obj.data::<JsProxy>() = ManuallyDrop::new(JsProxy {
+                target: Some(*target),
+                handler: Some(*handler),
+                constructor: false,
+            });
+            obj
+        }
+    }
+
+    pub fn is_proxy(obj: &JsObject) -> bool {
+        std::ptr::eq(obj.class(), Self::get_class())
+    }
+
+    fn data(obj: &JsObject) -> &mut JsProxy {
+        if obj.tag() == ObjectTag::Function {
+            let func: &mut JsFunction = obj.data::<JsFunction>();
+            match func.ty {
+                FuncType::Proxy(ref mut proxy) => proxy,
+                _ => unreachable!(),
+            }
+        } else {
+            obj.data::<JsProxy>()
+        }
+    }
+
+    pub fn is_constructor(&self) -> bool {
+        self.constructor
+    }
+
+    pub fn is_revoked(obj: &JsObject) -> bool {
+        Self::data(obj).handler.is_none()
+    }
+
+    /// Returns target of proxy `obj`, `None` once it is revoked.
+    pub fn target(obj: &JsObject) -> Option<GcPointer<JsObject>> {
+        Self::data(obj).target
+    }
+
+    pub fn revoke(obj: &mut JsObject) {
+        let data = Self::data(obj);
+        data.target = None;
+        data.handler = None;
+    }
+
+    /// `IsArray` of proxy `obj` which is answered by its target.
+    pub fn is_array(ctx: GcPointer<Context>, obj: &JsObject) -> Result<bool, JsValue> {
+        let (target, _) = Self::parts(ctx, obj, "IsArray")?;
+        super::operations::is_array(ctx, JsValue::new(target))
+    }
+
+    /// Target and handler of `obj`, throws TypeError if proxy is revoked.
+    fn parts(
+        ctx: GcPointer<Context>,
+        obj: &JsObject,
+        op: &str,
+    ) -> Result<(GcPointer<JsObject>, GcPointer<JsObject>), JsValue> {
+        let data = Self::data(obj);
+        match (data.target, data.handler) {
+            (Some(target), Some(handler)) => Ok((target, handler)),
+            _ => Err(JsValue::new(ctx.new_type_error(format!(
+                "Cannot perform '{}' on a proxy that has been revoked",
+                op
+            )))),
+        }
+    }
+
+    /// GetMethod(handler, name)
+    fn trap(
+        ctx: GcPointer<Context>,
+        handler: &mut GcPointer<JsObject>,
+        name: &str,
+    ) -> Result<Option<JsValue>, JsValue> {
+        let trap = handler.get(ctx, name.intern())?;
+        if trap.is_undefined() || trap.is_null() {
+            return Ok(None);
+        }
+        if !trap.is_callable() {
+            return Err(JsValue::new(ctx.new_type_error(format!(
+                "Proxy handler's '{}' trap is not a function",
+                name
+            ))));
+        }
+        Ok(Some(trap))
+    }
+
+    fn call_trap(
+        ctx: GcPointer<Context>,
+        trap: JsValue,
+        handler: GcPointer<JsObject>,
+        args: &mut [JsValue],
+    ) -> Result<JsValue, JsValue> {
+        let stack = ctx.shadowstack();
+        letroot!(func = stack, trap.get_jsobject());
+        letroot!(args = stack, Arguments::new(JsValue::new(handler), args));
+        func.as_function_mut().call(ctx, &mut args, trap)
+    }
+
+    pub fn get_prototype_of(
+        ctx: GcPointer<Context>,
+        obj: &mut GcPointer<JsObject>,
+    ) -> Result<Option<GcPointer<JsObject>>, JsValue> {
+        let stack = ctx.shadowstack();
+        let (target, handler) = Self::parts(ctx, obj, "getPrototypeOf")?;
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        let trap = match Self::trap(ctx, &mut handler, "getPrototypeOf")? {
+            Some(trap) => trap,
+            None => return operations::get_prototype_of(ctx, &mut target),
+        };
+        let mut args = [JsValue::new(*target)];
+        let result = Self::call_trap(ctx, trap, *handler, &mut args)?;
+        let proto = if result.is_jsobject() {
+            Some(result.get_jsobject())
+        } else if result.is_null() {
+            None
+        } else {
+            return Err(JsValue::new(ctx.new_type_error(
+                "'getPrototypeOf' on proxy: trap returned neither object nor null",
+            )));
+        };
+        letroot!(proto = stack, proto);
+        if operations::is_extensible(ctx, &mut target)? {
+            return Ok(*proto);
+        }
+        let target_proto = operations::get_prototype_of(ctx, &mut target)?;
+        if !same_object(*proto, target_proto) {
+            return Err(invariant_error(
+                ctx,
+                "'getPrototypeOf' must return prototype of non-extensible target",
+            ));
+        }
+        Ok(*proto)
+    }
+
+    pub fn set_prototype_of(
+        ctx: GcPointer<Context>,
+        obj: &mut GcPointer<JsObject>,
+        proto: Option<GcPointer<JsObject>>,
+    ) -> Result<bool, JsValue> {
+        let stack = ctx.shadowstack();
+        let (target, handler) = Self::parts(ctx, obj, "setPrototypeOf")?;
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        letroot!(proto = stack, proto);
+        let trap = match Self::trap(ctx, &mut handler, "setPrototypeOf")? {
+            Some(trap) => trap,
+            None => return operations::set_prototype_of(ctx, &mut target, *proto),
+        };
+        let mut args = [
+            JsValue::new(*target),
+            match *proto {
+                Some(proto) => JsValue::new(proto),
+                None => JsValue::encode_null_value(),
+            },
+        ];
+        if !Self::call_trap(ctx, trap, *handler, &mut args)?.to_boolean() {
+            return Ok(false);
+        }
+        if operations::is_extensible(ctx, &mut target)? {
+            return Ok(true);
+        }
+        let target_proto = operations::get_prototype_of(ctx, &mut target)?;
+        if !same_object(*proto, target_proto) {
+            return Err(invariant_error(
+                ctx,
+                "'setPrototypeOf' can't change prototype of non-extensible target",
+            ));
+        }
+        Ok(true)
+    }
+
+    pub fn is_extensible(
+        ctx: GcPointer<Context>,
+        obj: &mut GcPointer<JsObject>,
+    ) -> Result<bool, JsValue> {
+        let stack = ctx.shadowstack();
+        let (target, handler) = Self::parts(ctx, obj, "isExtensible")?;
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        let trap = match Self::trap(ctx, &mut handler, "isExtensible")? {
+            Some(trap) => trap,
+            None => return operations::is_extensible(ctx, &mut target),
+        };
+        let mut args = [JsValue::new(*target)];
+        let result = Self::call_trap(ctx, trap, *handler, &mut args)?.to_boolean();
+        if result != operations::is_extensible(ctx, &mut target)? {
+            return Err(invariant_error(
+                ctx,
+                "'isExtensible' must return same value as target",
+            ));
+        }
+        Ok(result)
+    }
+
+    pub fn prevent_extensions(
+        ctx: GcPointer<Context>,
+        obj: &mut GcPointer<JsObject>,
+    ) -> Result<bool, JsValue> {
+        let stack = ctx.shadowstack();
+        let (target, handler) = Self::parts(ctx, obj, "preventExtensions")?;
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        let trap = match Self::trap(ctx, &mut handler, "preventExtensions")? {
+            Some(trap) => trap,
+            None => return operations::prevent_extensions(ctx, &mut target),
+        };
+        let mut args = [JsValue::new(*target)];
+        let result = Self::call_trap(ctx, trap, *handler, &mut args)?.to_boolean();
+        if result && operations::is_extensible(ctx, &mut target)? {
+            return Err(invariant_error(
+                ctx,
+                "'preventExtensions' returned true but target is extensible",
+            ));
+        }
+        Ok(result)
+    }
+
+    pub fn get_own_property(
+        ctx: GcPointer<Context>,
+        obj: &mut GcPointer<JsObject>,
+        key: Symbol,
+    ) -> Result<Option<PropertyDescriptor>, JsValue> {
+        let stack = ctx.shadowstack();
+        let (target, handler) = Self::parts(ctx, obj, "getOwnPropertyDescriptor")?;
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        let trap = match Self::trap(ctx, &mut handler, "getOwnPropertyDescriptor")? {
+            Some(trap) => trap,
+            None => return operations::get_own_property(ctx, &mut target, key),
+        };
+        let mut args = [JsValue::new(*target), key_to_value(ctx, key)];
+        let result = Self::call_trap(ctx, trap, *handler, &mut args)?;
+        if !result.is_jsobject() && !result.is_undefined() {
+            return Err(JsValue::new(ctx.new_type_error(
+                "'getOwnPropertyDescriptor' on proxy: trap returned neither object nor undefined",
+            )));
+        }
+        letroot!(result = stack, result);
+        let target_desc = operations::get_own_property(ctx, &mut target, key)?;
+        if result.is_undefined() {
+            let target_desc = match target_desc {
+                Some(desc) => desc,
+                None => return Ok(None),
+            };
+            if !target_desc.is_configurable() {
+                return Err(invariant_error(
+                    ctx,
+                    "'getOwnPropertyDescriptor' can't report non-configurable property as non-existent",
+                ));
+            }
+            if !operations::is_extensible(ctx, &mut target)? {
+                return Err(invariant_error(
+                    ctx,
+                    "'getOwnPropertyDescriptor' can't report existing property of non-extensible target as non-existent",
+                ));
+            }
+            return Ok(None);
+        }
+        let extensible = operations::is_extensible(ctx, &mut target)?;
+        let desc = complete_descriptor(&to_property_descriptor(ctx, *result)?);
+        if !is_compatible_descriptor(extensible, &desc, target_desc.as_ref()) {
+            return Err(invariant_error(
+                ctx,
+                "'getOwnPropertyDescriptor' returned descriptor incompatible with target property",
+            ));
+        }
+        if !desc.is_configurable() {
+            match target_desc {
+                Some(ref target_desc) if !target_desc.is_configurable() => {
+                    if target_desc.is_data()
+                        && target_desc.is_writable()
+                        && desc.is_data()
+                        && !desc.is_writable()
+                    {
+                        return Err(invariant_error(
+                            ctx,
+                            "'getOwnPropertyDescriptor' can't report writable property as non-writable",
+                        ));
+                    }
+                }
+                _ => {
+                    return Err(invariant_error(
+                        ctx,
+                        "'getOwnPropertyDescriptor' can't report configurable or missing property as non-configurable",
+                    ))
+                }
+            }
+        }
+        Ok(Some(desc))
+    }
+
+    pub fn define_own_property(
+        ctx: GcPointer<Context>,
+        obj: &mut GcPointer<JsObject>,
+        key: Symbol,
+        desc: &PropertyDescriptor,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        let stack = ctx.shadowstack();
+        let (target, handler) = Self::parts(ctx, obj, "defineProperty")?;
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        let trap = match Self::trap(ctx, &mut handler, "defineProperty")? {
+            Some(trap) => trap,
+            None => return target.define_own_property(ctx, key, desc, throwable),
+        };
+        let key_value = key_to_value(ctx, key);
+        letroot!(key_value = stack, key_value);
+        let desc_object = from_property_descriptor(ctx, desc)?;
+        let mut args = [JsValue::new(*target), *key_value, desc_object];
+        if !Self::call_trap(ctx, trap, *handler, &mut args)?.to_boolean() {
+            if throwable {
+                return Err(JsValue::new(ctx.new_type_error(format!(
+                    "'defineProperty' on proxy: trap returned false for property '{}'",
+                    ctx.description(key)
+                ))));
+            }
+            return Ok(false);
+        }
+        let target_desc = operations::get_own_property(ctx, &mut target, key)?;
+        let extensible = operations::is_extensible(ctx, &mut target)?;
+        let setting_config_false = !desc.is_configurable_absent() && !desc.is_configurable();
+        match target_desc {
+            None => {
+                if !extensible {
+                    return Err(invariant_error(
+                        ctx,
+                        "'defineProperty' can't add property to non-extensible target",
+                    ));
+                }
+                if setting_config_false {
+                    return Err(invariant_error(
+                        ctx,
+                        "'defineProperty' can't define non-configurable property which doesn't exist on target",
+                    ));
+                }
+            }
+            Some(ref target_desc) => {
+                if !is_compatible_descriptor(extensible, desc, Some(target_desc)) {
+                    return Err(invariant_error(
+                        ctx,
+                        "'defineProperty' descriptor is incompatible with target property",
+                    ));
+                }
+                if setting_config_false && target_desc.is_configurable() {
+                    return Err(invariant_error(
+                        ctx,
+                        "'defineProperty' can't define configurable target property as non-configurable",
+                    ));
+                }
+                if target_desc.is_data()
+                    && !target_desc.is_configurable()
+                    && target_desc.is_writable()
+                    && !desc.is_writable_absent()
+                    && !desc.is_writable()
+                {
+                    return Err(invariant_error(
+                        ctx,
+                        "'defineProperty' can't define writable target property as non-writable",
+                    ));
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn has(
+        ctx: GcPointer<Context>,
+        obj: &mut GcPointer<JsObject>,
+        key: Symbol,
+    ) -> Result<bool, JsValue> {
+        let stack = ctx.shadowstack();
+        let (target, handler) = Self::parts(ctx, obj, "has")?;
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        let trap = match Self::trap(ctx, &mut handler, "has")? {
+            Some(trap) => trap,
+            None => return operations::has_property(ctx, &mut target, key),
+        };
+        let mut args = [JsValue::new(*target), key_to_value(ctx, key)];
+        let result = Self::call_trap(ctx, trap, *handler, &mut args)?.to_boolean();
+        if !result {
+            if let Some(target_desc) = operations::get_own_property(ctx, &mut target, key)? {
+                if !target_desc.is_configurable() {
+                    return Err(invariant_error(
+                        ctx,
+                        "'has' can't report non-configurable property as non-existent",
+                    ));
+                }
+                if !operations::is_extensible(ctx, &mut target)? {
+                    return Err(invariant_error(
+                        ctx,
+                        "'has' can't report existing property of non-extensible target as non-existent",
+                    ));
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    pub fn get(
+        ctx: GcPointer<Context>,
+        obj: &mut GcPointer<JsObject>,
+        key: Symbol,
+        receiver: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let stack = ctx.shadowstack();
+        let (target, handler) = Self::parts(ctx, obj, "get")?;
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        letroot!(receiver = stack, receiver);
+        let trap = match Self::trap(ctx, &mut handler, "get")? {
+            Some(trap) => trap,
+            None => return operations::get_with_receiver(ctx, &mut target, key, *receiver),
+        };
+        let mut args = [JsValue::new(*target), key_to_value(ctx, key), *receiver];
+        let result = Self::call_trap(ctx, trap, *handler, &mut args)?;
+        letroot!(result = stack, result);
+        if let Some(target_desc) = operations::get_own_property(ctx, &mut target, key)? {
+            if !target_desc.is_configurable() {
+                if target_desc.is_data()
+                    && !target_desc.is_writable()
+                    && !JsValue::same_value(*result, target_desc.value())
+                {
+                    return Err(invariant_error(
+                        ctx,
+                        "'get' must report the value of non-writable, non-configurable property",
+                    ));
+                }
+                if target_desc.is_accessor()
+                    && is_undefined_accessor(target_desc.getter())
+                    && !result.is_undefined()
+                {
+                    return Err(invariant_error(
+                        ctx,
+                        "'get' must report undefined for non-configurable accessor without getter",
+                    ));
+                }
+            }
+        }
+        Ok(*result)
+    }
+
+    pub fn set(
+        ctx: GcPointer<Context>,
+        obj: &mut GcPointer<JsObject>,
+        key: Symbol,
+        value: JsValue,
+        receiver: JsValue,
+    ) -> Result<bool, JsValue> {
+        let stack = ctx.shadowstack();
+        let (target, handler) = Self::parts(ctx, obj, "set")?;
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        letroot!(value = stack, value);
+        letroot!(receiver = stack, receiver);
+        let trap = match Self::trap(ctx, &mut handler, "set")? {
+            Some(trap) => trap,
+            None => return operations::set_with_receiver(ctx, &mut target, key, *value, *receiver),
+        };
+        let mut args = [
+            JsValue::new(*target),
+            key_to_value(ctx, key),
+            *value,
+            *receiver,
+        ];
+        if !Self::call_trap(ctx, trap, *handler, &mut args)?.to_boolean() {
+            return Ok(false);
+        }
+        if let Some(target_desc) = operations::get_own_property(ctx, &mut target, key)? {
+            if !target_desc.is_configurable() {
+                if target_desc.is_data()
+                    && !target_desc.is_writable()
+                    && !JsValue::same_value(*value, target_desc.value())
+                {
+                    return Err(invariant_error(
+                        ctx,
+                        "'set' can't change value of non-writable, non-configurable property",
+                    ));
+                }
+                if target_desc.is_accessor() && is_undefined_accessor(target_desc.setter()) {
+                    return Err(invariant_error(
+                        ctx,
+                        "'set' can't succeed for non-configurable accessor without setter",
+                    ));
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn delete(
+        ctx: GcPointer<Context>,
+        obj: &mut GcPointer<JsObject>,
+        key: Symbol,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        let stack = ctx.shadowstack();
+        let (target, handler) = Self::parts(ctx, obj, "deleteProperty")?;
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        let trap = match Self::trap(ctx, &mut handler, "deleteProperty")? {
+            Some(trap) => trap,
+            None => return target.delete(ctx, key, throwable),
+        };
+        let mut args = [JsValue::new(*target), key_to_value(ctx, key)];
+        if !Self::call_trap(ctx, trap, *handler, &mut args)?.to_boolean() {
+            if throwable {
+                return Err(JsValue::new(ctx.new_type_error(format!(
+                    "'deleteProperty' on proxy: trap returned false for property '{}'",
+                    ctx.description(key)
+                ))));
+            }
+            return Ok(false);
+        }
+        if let Some(target_desc) = operations::get_own_property(ctx, &mut target, key)? {
+            if !target_desc.is_configurable() {
+                return Err(invariant_error(
+                    ctx,
+                    "'deleteProperty' can't delete non-configurable property",
+                ));
+            }
+            if !operations::is_extensible(ctx, &mut target)? {
+                return Err(invariant_error(
+                    ctx,
+                    "'deleteProperty' can't delete property of non-extensible target",
+                ));
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn own_keys(
+        ctx: GcPointer<Context>,
+        obj: &mut GcPointer<JsObject>,
+    ) -> Result<Vec<Symbol>, JsValue> {
+        let stack = ctx.shadowstack();
+        let (target, handler) = Self::parts(ctx, obj, "ownKeys")?;
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        let trap = match Self::trap(ctx, &mut handler, "ownKeys")? {
+            Some(trap) => trap,
+            None => return operations::own_property_keys(ctx, &mut target),
+        };
+        let mut args = [JsValue::new(*target)];
+        let result = Self::call_trap(ctx, trap, *handler, &mut args)?;
+        if !result.is_jsobject() {
+            return Err(JsValue::new(
+                ctx.new_type_error("'ownKeys' on proxy: trap returned non-object"),
+            ));
+        }
+        letroot!(result = stack, result.get_jsobject());
+        let len = crate::jsrt::get_length(ctx, &mut result)?;
+        let mut keys = Vec::with_capacity(len as usize);
+        let mut seen = HashSet::with_capacity(len as usize);
+        for i in 0..len {
+            let key = result.get(ctx, Symbol::Index(i))?;
+            if !key.is_jsstring() && !key.is_symbol() {
+                return Err(JsValue::new(ctx.new_type_error(
+                    "'ownKeys' on proxy: trap result elements must be strings or symbols",
+                )));
+            }
+            let key = key.to_symbol(ctx)?;
+            if !seen.insert(key) {
+                return Err(JsValue::new(ctx.new_type_error(format!(
+                    "'ownKeys' on proxy: trap returned duplicate entry '{}'",
+                    ctx.description(key)
+                ))));
+            }
+            keys.push(key);
+        }
+
+        let extensible = operations::is_extensible(ctx, &mut target)?;
+        let target_keys = operations::own_property_keys(ctx, &mut target)?;
+        let mut configurable = vec![];
+        let mut non_configurable = vec![];
+        for key in target_keys {
+            match operations::get_own_property(ctx, &mut target, key)? {
+                Some(desc) if !desc.is_configurable() => non_configurable.push(key),
+                _ => configurable.push(key),
+            }
+        }
+        if extensible && non_configurable.is_empty() {
+            return Ok(keys);
+        }
+        for key in non_configurable {
+            if !seen.remove(&key) {
+                return Err(invariant_error(
+                    ctx,
+                    &format!(
+                        "'ownKeys' result must include non-configurable key '{}'",
+                        ctx.description(key)
+                    ),
+                ));
+            }
+        }
+        if extensible {
+            return Ok(keys);
+        }
+        for key in configurable {
+            if !seen.remove(&key) {
+                return Err(invariant_error(
+                    ctx,
+                    &format!(
+                        "'ownKeys' result must include key '{}' of non-extensible target",
+                        ctx.description(key)
+                    ),
+                ));
+            }
+        }
+        if !seen.is_empty() {
+            return Err(invariant_error(
+                ctx,
+                "'ownKeys' can't report new keys for non-extensible target",
+            ));
+        }
+        Ok(keys)
+    }
+
+    /// `[[Call]]` and `[[Construct]]` of callable proxies. `callee` is the proxy function
+    /// itself and is used as `newTarget` when constructing.
+    pub fn call(
+        ctx: GcPointer<Context>,
+        callee: JsValue,
+        args: &mut Arguments,
+    ) -> Result<JsValue, JsValue> {
+        let stack = ctx.shadowstack();
+        let op = if args.ctor_call { "construct" } else { "apply" };
+        let (target, handler) = Self::parts(ctx, &callee.get_jsobject(), op)?;
+        if args.ctor_call && !target.as_function().is_constructor() {
+            return Err(JsValue::new(
+                ctx.new_type_error("Proxy target is not a constructor"),
+            ));
+        }
+        letroot!(target = stack, target);
+        letroot!(handler = stack, handler);
+        let trap = match Self::trap(ctx, &mut handler, op)? {
+            Some(trap) => trap,
+            None => {
+                let target_value = JsValue::new(*target);
+                return target.as_function_mut().call(ctx, args, target_value);
+            }
+        };
+        let array = JsArray::from_slice(ctx, args.values);
+        letroot!(array = stack, array);
+        if args.ctor_call {
+            let mut trap_args = [JsValue::new(*target), JsValue::new(*array), callee];
+            let result = Self::call_trap(ctx, trap, *handler, &mut trap_args)?;
+            if !result.is_jsobject() {
+                return Err(JsValue::new(
+                    ctx.new_type_error("'construct' on proxy: trap returned non-object"),
+                ));
+            }
+            Ok(result)
+        } else {
+            let mut trap_args = [JsValue::new(*target), args.this, JsValue::new(*array)];
+            Self::call_trap(ctx, trap, *handler, &mut trap_args)
+        }
+    }
+
+    pub fn GetNonIndexedSlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        name: Symbol,
+        slot: &mut Slot,
+    ) -> Result<JsValue, JsValue> {
+        let receiver = JsValue::new(*obj);
+        let value = Self::get(ctx, obj, name, receiver)?;
+        slot.set_1(value, object_data(), Some(obj.as_dyn()));
+        Ok(value)
+    }
+
+    pub fn GetIndexedSlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        index: u32,
+        slot: &mut Slot,
+    ) -> Result<JsValue, JsValue> {
+        Self::GetNonIndexedSlotMethod(obj, ctx, Symbol::Index(index), slot)
+    }
+
+    /// `[[HasProperty]]`. Slot is filled with `undefined`, use `get` to read the value. Exceptions
+    /// thrown by the trap are left pending in `ctx` and rethrown when the native function
+    /// that asked returns.
+    pub fn GetNonIndexedPropertySlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        mut ctx: GcPointer<Context>,
+        name: Symbol,
+        slot: &mut Slot,
+    ) -> bool {
+        match Self::has(ctx, obj, name) {
+            Ok(true) => {
+                slot.set_1(
+                    JsValue::encode_undefined_value(),
+                    object_data(),
+                    Some(obj.as_dyn()),
+                );
+                true
+            }
+            Ok(false) => false,
+            Err(error) => {
+                ctx.set_pending_exception(error);
+                false
+            }
+        }
+    }
+
+    pub fn GetIndexedPropertySlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        index: u32,
+        slot: &mut Slot,
+    ) -> bool {
+        Self::GetNonIndexedPropertySlotMethod(obj, ctx, Symbol::Index(index), slot)
+    }
+
+    pub fn GetOwnNonIndexedPropertySlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        mut ctx: GcPointer<Context>,
+        name: Symbol,
+        slot: &mut Slot,
+    ) -> bool {
+        match Self::get_own_property(ctx, obj, name) {
+            Ok(Some(desc)) => {
+                let stored = StoredSlot::new(ctx, &desc);
+                slot.set_from_slot(&stored, Some(obj.as_dyn()));
+                true
+            }
+            Ok(None) => false,
+            Err(error) => {
+                ctx.set_pending_exception(error);
+                false
+            }
+        }
+    }
+
+    pub fn GetOwnIndexedPropertySlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        index: u32,
+        slot: &mut Slot,
+    ) -> bool {
+        Self::GetOwnNonIndexedPropertySlotMethod(obj, ctx, Symbol::Index(index), slot)
+    }
+
+    pub fn PutNonIndexedSlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        name: Symbol,
+        val: JsValue,
+        slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<(), JsValue> {
+        slot.make_put_uncacheable();
+        let receiver = JsValue::new(*obj);
+        if !Self::set(ctx, obj, name, val, receiver)? && throwable {
+            return Err(JsValue::new(ctx.new_type_error(format!(
+                "'set' on proxy: trap returned false for property '{}'",
+                ctx.description(name)
+            ))));
+        }
+        Ok(())
+    }
+
+    pub fn PutIndexedSlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        index: u32,
+        val: JsValue,
+        slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<(), JsValue> {
+        Self::PutNonIndexedSlotMethod(obj, ctx, Symbol::Index(index), val, slot, throwable)
+    }
+
+    pub fn DeleteNonIndexedMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        name: Symbol,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        Self::delete(ctx, obj, name, throwable)
+    }
+
+    pub fn DeleteIndexedMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        index: u32,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        Self::delete(ctx, obj, Symbol::Index(index), throwable)
+    }
+
+    pub fn DefineOwnNonIndexedPropertySlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        name: Symbol,
+        desc: &PropertyDescriptor,
+        slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        slot.make_put_uncacheable();
+        Self::define_own_property(ctx, obj, name, desc, throwable)
+    }
+
+    pub fn DefineOwnIndexedPropertySlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        index: u32,
+        desc: &PropertyDescriptor,
+        slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        Self::DefineOwnNonIndexedPropertySlotMethod(
+            obj,
+            ctx,
+            Symbol::Index(index),
+            desc,
+            slot,
+            throwable,
+        )
+    }
+
+    pub fn GetOwnPropertyNamesMethod(
+        obj: &mut GcPointer<JsObject>,
+        mut ctx: GcPointer<Context>,
+        collector: &mut dyn FnMut(Symbol, u32),
+        mode: EnumerationMode,
+    ) {
+        let keys = match Self::own_keys(ctx, obj) {
+            Ok(keys) => keys,
+            Err(error) => {
+                ctx.set_pending_exception(error);
+                return;
+            }
+        };
+        for key in keys {
+            if mode != EnumerationMode::IncludeNotEnumerable {
+                match Self::get_own_property(ctx, obj, key) {
+                    Ok(Some(desc)) if desc.is_enumerable() => (),
+                    Ok(_) => continue,
+                    Err(error) => {
+                        ctx.set_pending_exception(error);
+                        return;
+                    }
+                }
+            }
+            collector(key, u32::MAX);
+        }
+    }
+
+    pub fn GetPropertyNamesMethod(
+        obj: &mut GcPointer<JsObject>,
+        mut ctx: GcPointer<Context>,
+        collector: &mut dyn FnMut(Symbol, u32),
+        mode: EnumerationMode,
+    ) {
+        Self::GetOwnPropertyNamesMethod(obj, ctx, collector, mode);
+        if ctx.pending_exception.is_some() {
+            return;
+        }
+        match Self::get_prototype_of(ctx, obj) {
+            Ok(Some(mut proto)) => proto.get_property_names(ctx, collector, mode),
+            Ok(None) => (),
+            Err(error) => ctx.set_pending_exception(error),
+        }
+    }
+}
+
+fn same_object(x: Option<GcPointer<JsObject>>, y: Option<GcPointer<JsObject>>) -> bool {
+    match (x, y) {
+        (Some(x), Some(y)) => GcPointer::ptr_eq(&x, &y),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vm::tests::assert_eval_cases;
+
+    #[test]
+    fn test_proxy_prototype_and_trap_errors() {
+        assert_eval_cases(&[
+            (
+                "Object.create(new Proxy({}, { get(t, k, r) { return k + 42 } })).x",
+                "x42",
+            ),
+            (
+                "var log = []; \
+                 var o = Object.create(new Proxy({}, { set(t, k, v, r) { log.push(k, v, r === o); return true } })); \
+                 o.y = 1; o[0] = 2; [log, Object.keys(o)].join(';')",
+                "y,1,true,0,2,true;",
+            ),
+            (
+                "var o = Object.create(new Proxy({}, {})); o.z = 1; Object.keys(o)",
+                "z",
+            ),
+            (
+                "'w' in Object.create(new Proxy({}, { has(t, k) { return k == 'w' } }))",
+                "true",
+            ),
+            (
+                "try { Object.isFrozen(new Proxy({}, { ownKeys() { throw 'ownKeys' } })) } catch (e) { e }",
+                "ownKeys",
+            ),
+            (
+                "try { [].indexOf.call(new Proxy([1], { has() { throw 'has' } }), 1) } catch (e) { e }",
+                "has",
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_proxy_invariants_and_revocation() {
+        assert_eval_cases(&[
+            (
+                "var t = {}; Object.defineProperty(t, 'a', { value: 1 }); \
+                 try { new Proxy(t, { get() { return 2 } }).a } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "var t = {}; Object.defineProperty(t, 'a', { value: 1 }); new Proxy(t, { get() { return 1 } }).a",
+                "1",
+            ),
+            (
+                "try { Object.keys(new Proxy({}, { ownKeys() { return ['a', 'a'] } })) } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "try { Reflect.ownKeys(new Proxy({}, { ownKeys() { return [1] } })) } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "var t = Object.preventExtensions({ a: 1 }); \
+                 try { Reflect.ownKeys(new Proxy(t, { ownKeys() { return [] } })) } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "var p = new Proxy({}, { set() { return false } }); p.a = 1; Reflect.set(p, 'a', 1)",
+                "false",
+            ),
+            (
+                "try { new (new Proxy(function () {}, { construct() { return 1 } }))() } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "var r = Proxy.revocable({}, {}); r.revoke(); r.revoke(); \
+                 try { r.proxy.a } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "var r = Proxy.revocable(function () {}, {}); r.revoke(); typeof r.proxy",
+                "function",
+            ),
+            (
+                "try { new Proxy({}, { get: 1 }).a } catch (e) { e instanceof TypeError }",
+                "true",
+            ),
+            (
+                "new Proxy({ a: 1 }, { get: undefined }).a",
+                "1",
+            ),
+            (
+                "new Proxy(function (a, b) { return a + b }, { apply(t, self, args) { return t.apply(self, args) * 2 } })(1, 2)",
+                "6",
+            ),
+        ]);
+    }
+}
@@ -7,7 +7,7 @@ use super::{
     object::{EnumerationMode, JsObject, ObjectTag},
     slot::*,
     structure::Structure,
-    symbol_table::{Internable, Symbol},
+    symbol_table::{symbol_table, Internable, Symbol},
     value::*,
     Context,
 };
@@ -18,37 +18,491 @@ use crate::gc::{
     snapshot::serializer::{Serializable, SnapshotSerializer},
 };
 use crate::prelude::*;
-use std::mem::size_of;
+use std::{
+    borrow::Cow,
+    cell::UnsafeCell,
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    intrinsics::unlikely,
+    mem::{size_of, ManuallyDrop},
+};
+
+/// Maximum length of string in code units. Operations that would produce longer strings throw
+/// RangeError.
+pub const MAX_STRING_LENGTH: u32 = (1 << 30) - 25;
+
+/// Backing store of [JsString]. Strings are sequences of UTF-16 code units. Strings whose code
+/// units all fit into a byte are stored as Latin-1, the rest as UTF-16. Neither store is UTF-8 so
+/// indexing by code unit is O(1) and unpaired surrogates survive round trips.
+pub(crate) enum StringData {
+    Latin1(Box<[u8]>),
+    Utf16(Box<[u16]>),
+}
+
+impl StringData {
+    fn len(&self) -> u32 {
+        match self {
+            StringData::Latin1(chars) => chars.len() as _,
+            StringData::Utf16(units) => units.len() as _,
+        }
+    }
+}
+
+/// Concatenation of two strings that isn't copied until its contents are needed. Makes repeated
+/// `s += piece` linear instead of quadratic.
+pub(crate) struct Rope {
+    left: GcPointer<JsString>,
+    right: GcPointer<JsString>,
+    len: u32,
+}
+
+pub(crate) enum StringRepr {
+    Flat(StringData),
+    Rope(Rope),
+}
 
 #[repr(C)]
 pub struct JsString {
-    pub string: String,
+    /// Ropes are replaced by their flat contents in place, strings are otherwise immutable.
+    pub(crate) repr: UnsafeCell<StringRepr>,
 }
 
+/// Concatenations shorter than this are copied right away, ropes would only add overhead.
+const MIN_ROPE_LENGTH: u32 = 13;
+
 impl JsString {
     pub fn is_empty(&self) -> bool {
-        self.string.is_empty()
+        self.len() == 0
     }
-    pub fn new(mut ctx: GcPointer<Context>, as_str: impl AsRef<str>) -> GcPointer<Self> {
+
+    /// Creates string from UTF-8 string.
+    pub fn new(ctx: GcPointer<Context>, as_str: impl AsRef<str>) -> GcPointer<Self> {
         let str = as_str.as_ref();
-        let proto = Self {
-            string: str.to_owned(),
+        let data = if str.chars().all(|c| (c as u32) <= 0xff) {
+            StringData::Latin1(str.chars().map(|c| c as u8).collect())
+        } else {
+            StringData::Utf16(str.encode_utf16().collect())
+        };
+        Self::from_data(ctx, data)
+    }
+
+    /// Creates string from UTF-16 code units. Code units don't have to be well-formed UTF-16.
+    pub fn from_utf16(ctx: GcPointer<Context>, units: &[u16]) -> GcPointer<Self> {
+        let data = if units.iter().all(|&unit| unit <= 0xff) {
+            StringData::Latin1(units.iter().map(|&unit| unit as u8).collect())
+        } else {
+            StringData::Utf16(units.into())
         };
-        let cell = ctx.heap().allocate(proto);
+        Self::from_data(ctx, data)
+    }
 
-        cell
+    /// Creates string from Latin-1 characters.
+    pub fn from_latin1(ctx: GcPointer<Context>, chars: &[u8]) -> GcPointer<Self> {
+        Self::from_data(ctx, StringData::Latin1(chars.into()))
     }
 
-    pub fn as_str(&self) -> &str {
-        &self.string
+    pub(crate) fn from_data(mut ctx: GcPointer<Context>, data: StringData) -> GcPointer<Self> {
+        ctx.heap().allocate(Self {
+            repr: UnsafeCell::new(StringRepr::Flat(data)),
+        })
     }
 
+    /// Concatenates `left` and `right`. Long results are ropes that are flattened on first access
+    /// to their contents.
+    pub fn concat(
+        mut ctx: GcPointer<Context>,
+        left: GcPointer<Self>,
+        right: GcPointer<Self>,
+    ) -> Result<GcPointer<Self>, JsValue> {
+        if left.is_empty() {
+            return Ok(right);
+        }
+        if right.is_empty() {
+            return Ok(left);
+        }
+        let len = left.len() as u64 + right.len() as u64;
+        if unlikely(len > MAX_STRING_LENGTH as u64) {
+            return Err(JsValue::new(ctx.new_range_error("Invalid string length")));
+        }
+        if (len as u32) < MIN_ROPE_LENGTH {
+            let mut builder = JsStringBuilder::new();
+            builder.push_string(&left);
+            builder.push_string(&right);
+            return Ok(builder.build(ctx));
+        }
+        Ok(ctx.heap().allocate(Self {
+            repr: UnsafeCell::new(StringRepr::Rope(Rope {
+                left,
+                right,
+                len: len as u32,
+            })),
+        }))
+    }
+
+    /// Returns `true` if the string is an unflattened rope.
+    pub fn is_rope(&self) -> bool {
+        matches!(unsafe { &*self.repr.get() }, StringRepr::Rope(_))
+    }
+
+    /// Contents of the string, flattening it first if it is a rope.
+    pub(crate) fn flat(&self) -> &StringData {
+        if self.is_rope() {
+            self.flatten();
+        }
+        match unsafe { &*self.repr.get() } {
+            StringRepr::Flat(data) => data,
+            StringRepr::Rope(_) => unreachable!(),
+        }
+    }
+
+    #[cold]
+    fn flatten(&self) {
+        let mut builder = JsStringBuilder::new();
+        // left-deep ropes are as deep as number of concatenations, don't recurse.
+        let mut stack: Vec<&JsString> = vec![self];
+        while let Some(string) = stack.pop() {
+            match unsafe { &*string.repr.get() } {
+                StringRepr::Flat(_) => builder.push_string(string),
+                StringRepr::Rope(rope) => {
+                    stack.push(&*rope.right);
+                    stack.push(&*rope.left);
+                }
+            }
+        }
+        // no references to the rope are alive, children are kept alive by `stack` until here.
+        unsafe {
+            *self.repr.get() = StringRepr::Flat(builder.into_data());
+        }
+    }
+
+    /// Number of UTF-16 code units.
     pub fn len(&self) -> u32 {
-        self.string.len() as _
+        match unsafe { &*self.repr.get() } {
+            StringRepr::Flat(data) => data.len(),
+            StringRepr::Rope(rope) => rope.len,
+        }
+    }
+
+    pub fn is_latin1(&self) -> bool {
+        matches!(self.flat(), StringData::Latin1(_))
+    }
+
+    /// Latin-1 backing store, if the string has one.
+    pub fn as_latin1(&self) -> Option<&[u8]> {
+        match self.flat() {
+            StringData::Latin1(chars) => Some(&chars[..]),
+            StringData::Utf16(_) => None,
+        }
+    }
+
+    /// UTF-16 backing store, if the string has one.
+    pub fn as_utf16(&self) -> Option<&[u16]> {
+        match self.flat() {
+            StringData::Latin1(_) => None,
+            StringData::Utf16(units) => Some(&units[..]),
+        }
+    }
+
+    /// Code unit at `index`. Panics if `index` is out of bounds.
+    pub fn at(&self, index: u32) -> u16 {
+        match self.flat() {
+            StringData::Latin1(chars) => chars[index as usize] as u16,
+            StringData::Utf16(units) => units[index as usize],
+        }
+    }
+
+    pub fn code_units(&self) -> CodeUnits<'_> {
+        CodeUnits {
+            string: self,
+            at: 0,
+            end: self.len(),
+        }
+    }
+
+    /// Contents of the string as UTF-16, borrowed when the string is stored as UTF-16.
+    pub fn to_utf16(&self) -> Cow<'_, [u16]> {
+        match self.flat() {
+            StringData::Latin1(chars) => Cow::Owned(chars.iter().map(|&c| c as u16).collect()),
+            StringData::Utf16(units) => Cow::Borrowed(&units[..]),
+        }
+    }
+
+    /// Converts string to UTF-8, replacing unpaired surrogates with U+FFFD. Borrows when the string
+    /// is ASCII.
+    pub fn as_str(&self) -> Cow<'_, str> {
+        match self.flat() {
+            StringData::Latin1(chars) if chars.is_ascii() => {
+                Cow::Borrowed(unsafe { std::str::from_utf8_unchecked(chars) })
+            }
+            StringData::Latin1(chars) => Cow::Owned(chars.iter().map(|&c| c as char).collect()),
+            StringData::Utf16(units) => Cow::Owned(String::from_utf16_lossy(units)),
+        }
+    }
+
+    /// CodePointAt(string, position). Returns code point and number of code units it occupies;
+    /// unpaired surrogates are returned as is.
+    pub fn code_point_at(&self, index: u32) -> (u32, u32) {
+        let first = self.at(index);
+        if !is_leading_surrogate(first) || index + 1 == self.len() {
+            return (first as u32, 1);
+        }
+        let second = self.at(index + 1);
+        if !is_trailing_surrogate(second) {
+            return (first as u32, 1);
+        }
+        (
+            (first as u32 - 0xd800) * 0x400 + (second as u32 - 0xdc00) + 0x10000,
+            2,
+        )
+    }
+
+    /// Returns `false` if string contains unpaired surrogates.
+    pub fn is_well_formed(&self) -> bool {
+        let mut index = 0;
+        while index < self.len() {
+            let (code_point, size) = self.code_point_at(index);
+            if (0xd800..=0xdfff).contains(&code_point) {
+                return false;
+            }
+            index += size;
+        }
+        true
+    }
+
+    /// Code units in `start..end`.
+    pub fn substring(&self, ctx: GcPointer<Context>, start: u32, end: u32) -> GcPointer<Self> {
+        let (start, end) = (start as usize, end as usize);
+        match self.flat() {
+            StringData::Latin1(chars) => Self::from_latin1(ctx, &chars[start..end]),
+            StringData::Utf16(units) => Self::from_utf16(ctx, &units[start..end]),
+        }
+    }
+
+    /// Index of the first occurrence of `search` at or after `from`.
+    pub fn index_of(&self, search: &JsString, from: u32) -> Option<u32> {
+        let (len, search_len) = (self.len(), search.len());
+        if search_len > len {
+            return None;
+        }
+        (from..=len - search_len).find(|&index| self.matches_at(search, index))
+    }
+
+    /// Index of the last occurrence of `search` at or before `from`.
+    pub fn last_index_of(&self, search: &JsString, from: u32) -> Option<u32> {
+        let (len, search_len) = (self.len(), search.len());
+        if search_len > len {
+            return None;
+        }
+        (0..=from.min(len - search_len))
+            .rev()
+            .find(|&index| self.matches_at(search, index))
+    }
+
+    /// Returns `true` if `search` occurs in this string at `index`.
+    pub fn matches_at(&self, search: &JsString, index: u32) -> bool {
+        if index as u64 + search.len() as u64 > self.len() as u64 {
+            return false;
+        }
+        match (self.flat(), search.flat()) {
+            (StringData::Latin1(x), StringData::Latin1(y)) => {
+                x[index as usize..index as usize + y.len()] == y[..]
+            }
+            _ => (0..search.len()).all(|i| self.at(index + i) == search.at(i)),
+        }
+    }
+}
+
+impl Internable for JsString {
+    /// Strings with unpaired surrogates are interned by their code units so that they stay
+    /// distinct property keys and convert back to the same string.
+    fn intern(&self) -> Symbol {
+        if self.is_latin1() || self.is_well_formed() {
+            return self.as_str().intern();
+        }
+        Symbol::Key(symbol_table().intern_utf16(&self.to_utf16()))
+    }
+}
+
+impl PartialEq for JsString {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.flat(), other.flat()) {
+            (StringData::Latin1(x), StringData::Latin1(y)) => x == y,
+            (StringData::Utf16(x), StringData::Utf16(y)) => x == y,
+            _ => self.len() == other.len() && self.code_units().eq(other.code_units()),
+        }
     }
 }
 
-unsafe impl Trace for JsString {}
+impl Eq for JsString {}
+
+impl PartialOrd for JsString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Strings are ordered by code units, not by code points.
+impl Ord for JsString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.flat(), other.flat()) {
+            (StringData::Latin1(x), StringData::Latin1(y)) => x.cmp(y),
+            (StringData::Utf16(x), StringData::Utf16(y)) => x.cmp(y),
+            _ => self.code_units().cmp(other.code_units()),
+        }
+    }
+}
+
+impl Hash for JsString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // must not depend on the backing store.
+        state.write_u32(self.len());
+        for unit in self.code_units() {
+            state.write_u16(unit);
+        }
+    }
+}
+
+pub struct CodeUnits<'a> {
+    string: &'a JsString,
+    at: u32,
+    end: u32,
+}
+
+impl<'a> Iterator for CodeUnits<'a> {
+    type Item = u16;
+    fn next(&mut self) -> Option<u16> {
+        if self.at == self.end {
+            return None;
+        }
+        self.at += 1;
+        Some(self.string.at(self.at - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.at) as usize;
+        (len, Some(len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for CodeUnits<'a> {
+    fn next_back(&mut self) -> Option<u16> {
+        if self.at == self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(self.string.at(self.end))
+    }
+}
+
+impl<'a> ExactSizeIterator for CodeUnits<'a> {}
+
+/// Builds [JsString] from code units. Stays Latin-1 until a wider code unit is pushed.
+pub struct JsStringBuilder {
+    latin1: Vec<u8>,
+    utf16: Option<Vec<u16>>,
+}
+
+impl Default for JsStringBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsStringBuilder {
+    pub fn new() -> Self {
+        Self {
+            latin1: Vec::new(),
+            utf16: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.utf16 {
+            Some(units) => units.len(),
+            None => self.latin1.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, unit: u16) {
+        if let Some(units) = &mut self.utf16 {
+            units.push(unit);
+        } else if unit <= 0xff {
+            self.latin1.push(unit as u8);
+        } else {
+            let mut units: Vec<u16> = self.latin1.iter().map(|&c| c as u16).collect();
+            units.push(unit);
+            self.latin1 = Vec::new();
+            self.utf16 = Some(units);
+        }
+    }
+
+    /// Pushes code point, encoding it as surrogate pair if necessary.
+    pub fn push_code_point(&mut self, code_point: u32) {
+        if code_point < 0x10000 {
+            self.push(code_point as u16);
+        } else {
+            let code_point = code_point - 0x10000;
+            self.push(0xd800 + (code_point >> 10) as u16);
+            self.push(0xdc00 + (code_point & 0x3ff) as u16);
+        }
+    }
+
+    pub fn push_str(&mut self, str: &str) {
+        for unit in str.encode_utf16() {
+            self.push(unit);
+        }
+    }
+
+    pub fn push_string(&mut self, string: &JsString) {
+        self.push_units(string, 0, string.len());
+    }
+
+    /// Pushes code units of `string` in `start..end`.
+    pub fn push_units(&mut self, string: &JsString, start: u32, end: u32) {
+        let range = start as usize..end as usize;
+        match (string.flat(), &mut self.utf16) {
+            (StringData::Latin1(chars), None) => self.latin1.extend_from_slice(&chars[range]),
+            (StringData::Utf16(other), Some(units)) => units.extend_from_slice(&other[range]),
+            (_, _) => {
+                for index in start..end {
+                    self.push(string.at(index));
+                }
+            }
+        }
+    }
+
+    pub fn build(self, ctx: GcPointer<Context>) -> GcPointer<JsString> {
+        JsString::from_data(ctx, self.into_data())
+    }
+
+    fn into_data(self) -> StringData {
+        match self.utf16 {
+            Some(units) => StringData::Utf16(units.into_boxed_slice()),
+            None => StringData::Latin1(self.latin1.into_boxed_slice()),
+        }
+    }
+}
+
+pub fn is_leading_surrogate(unit: u16) -> bool {
+    (0xd800..=0xdbff).contains(&unit)
+}
+
+pub fn is_trailing_surrogate(unit: u16) -> bool {
+    (0xdc00..=0xdfff).contains(&unit)
+}
+
+unsafe impl Trace for JsString {
+    fn trace(&mut self, visitor: &mut dyn Tracer) {
+        if let StringRepr::Rope(rope) = self.repr.get_mut() {
+            rope.left.trace(visitor);
+            rope.right.trace(visitor);
+        }
+    }
+}
 impl GcCell for JsString {
     fn deser_pair(&self) -> (usize, usize) {
         (Self::deserialize as _, Self::allocate as _)
@@ -96,9 +550,8 @@ impl JsStringObject {
     ) -> bool {
         let value = obj.as_string_object().value;
         if index < value.len() {
-            let ch = value.as_str().chars().nth(index as usize).unwrap();
             slot.set(
-                JsValue::encode_object_value(JsString::new(ctx, ch.to_string())),
+                JsValue::new(value.substring(ctx, index, index + 1)),
                 string_indexed(),
             );
             return true;
@@ -141,3 +594,133 @@ impl Serializable for JsStringObject {
         self.value.serialize(serializer);
     }
 }
+
+/// `%StringIteratorPrototype%` instances, iterate over code points of a string.
+pub struct JsStringIterator {
+    /// `None` once the iterator is exhausted.
+    string: Option<GcPointer<JsString>>,
+    position: u32,
+}
+
+#[allow(improper_ctypes_definitions)]
+extern "C" fn iterator_trace(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<JsStringIterator>().string.trace(tracer);
+}
+
+extern "C" fn iterator_deser(obj: &mut JsObject, deser: &mut Deserializer) {
+    *obj.data::<JsStringIterator>() = ManuallyDrop::new(JsStringIterator {
+        string: unsafe { Option::<GcPointer<JsString>>::deserialize_inplace(deser) },
+        position: deser.get_u32(),
+    });
+}
+
+extern "C" fn iterator_ser(obj: &JsObject, serializer: &mut SnapshotSerializer) {
+    let iterator = obj.data::<JsStringIterator>();
+    iterator.string.serialize(serializer);
+    iterator.position.serialize(serializer);
+}
+
+extern "C" fn iterator_fsz() -> usize {
+    size_of::<JsStringIterator>()
+}
+
+define_jsclass!(
+    JsStringIterator,
+    StringIterator,
+    Object,
+    None,
+    Some(iterator_trace),
+    Some(iterator_deser),
+    Some(iterator_ser),
+    Some(iterator_fsz)
+);
+
+impl JsStringIterator {
+    pub fn new(ctx: GcPointer<Context>, string: GcPointer<JsString>) -> GcPointer<JsObject> {
+        let structure = ctx.global_data().string_iterator_structure.unwrap();
+        let mut obj = JsObject::new(ctx, &structure, Self::get_class(), ObjectTag::Ordinary);
+        *obj.data::<Self>() = ManuallyDrop::new(Self {
+            string: Some(string),
+            position: 0,
+        });
+        obj
+    }
+
+    /// Next code point as a string or `None` when exhausted.
+    pub fn next(&mut self, ctx: GcPointer<Context>) -> Option<GcPointer<JsString>> {
+        let string = self.string?;
+        if self.position >= string.len() {
+            self.string = None;
+            return None;
+        }
+        let start = self.position;
+        self.position += string.code_point_at(start).1;
+        Some(string.substring(ctx, start, self.position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{options::Options, vm::tests::assert_eval_cases, Platform};
+
+    #[test]
+    fn test_rope_flattening() {
+        Platform::initialize();
+        let mut rt = Platform::new_runtime(Options::default(), None);
+        let ctx = Context::new(&mut rt);
+        let stack = ctx.shadowstack();
+        letroot!(first = stack, JsString::new(ctx, "ab"));
+        letroot!(second = stack, JsString::new(ctx, "cd"));
+        let short =
+            JsString::concat(ctx, *first, *second).unwrap_or_else(|_| panic!("concat threw"));
+        assert!(!short.is_rope());
+        assert_eq!(short.as_str(), "abcd");
+
+        letroot!(left = stack, JsString::new(ctx, "abcdefgh"));
+        letroot!(
+            right = stack,
+            JsString::from_utf16(ctx, &[0x100, 0x61, 0xd800, 0x62, 0x63, 0x64])
+        );
+        letroot!(
+            rope = stack,
+            JsString::concat(ctx, *left, *right).unwrap_or_else(|_| panic!("concat threw"))
+        );
+        assert!(rope.is_rope());
+        assert_eq!(rope.len(), 14);
+        assert_eq!(rope.at(8), 0x100);
+        assert_eq!(rope.at(10), 0xd800);
+        assert!(!rope.is_rope());
+        assert!(!rope.is_latin1());
+        // children are untouched by flattening
+        assert!(left.is_latin1());
+        assert_eq!(left.len(), 8);
+    }
+
+    #[test]
+    fn test_rope_behavior() {
+        assert_eval_cases(&[
+            (
+                "var s = ''; for (var i = 0; i < 100000; i++) s += 'ab'; \
+                 s.length + ',' + s.charAt(199999) + ',' + s.indexOf('ba', 100)",
+                "200000,b,101",
+            ),
+            (
+                "var t = ''; for (var i = 0; i < 100000; i++) t = 'ab' + t; t === s",
+                "true",
+            ),
+            (
+                "var k = 'abcdefghij' + 'klmnopqrst'; var o = {}; o[k] = 1; o.abcdefghijklmnopqrst",
+                "1",
+            ),
+            (
+                "var u = '\\u00e9abcdefghijkl' + '\\u0100'; u.length + ',' + u.charCodeAt(0) + ',' + u.charCodeAt(13)",
+                "14,233,256",
+            ),
+            (
+                "var r = 'abcdefghij' + 'klmnopqrst'; (r < 'abcdefghijklmnopqrsz') + ',' + r.slice(8, 12)",
+                "true,ijkl",
+            ),
+        ]);
+    }
+}
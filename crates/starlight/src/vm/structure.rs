@@ -48,6 +48,8 @@ pub struct Structure {
     pub(crate) transit_count: u32,
     pub(crate) has_been_flattened_before: bool,
     pub(crate) cached_prototype_chain: Option<GcPointer<StructureChain>>,
+    /// Creation order of the next added property. Unlike offsets it is never reused after deletion.
+    pub(crate) next_order: u32,
 }
 
 pub type StructureID = u32;
@@ -56,6 +58,8 @@ pub type StructureID = u32;
 pub struct MapEntry {
     pub offset: u32,
     pub attrs: AttrSafe,
+    /// Position of the property in creation order, used to enumerate properties in spec order.
+    pub order: u32,
 }
 
 impl MapEntry {
@@ -63,6 +67,7 @@ impl MapEntry {
         Self {
             offset: u32::MAX,
             attrs: AttrSafe::not_found(),
+            order: u32::MAX,
         }
     }
 
@@ -305,18 +310,13 @@ impl Structure {
             },
             transitions: TransitionsTable::new(!unique, previous.transitions.is_indexed()),
             deleted: previous.deleted.clone(),
-            added: (
-                DUMMY_SYMBOL,
-                MapEntry {
-                    offset: u32::MAX,
-                    attrs: AttrSafe::not_found(),
-                },
-            ),
+            added: (DUMMY_SYMBOL, MapEntry::not_found()),
             id: 0,
             calculated_size: previous.get_slots_size() as _,
             transit_count: 0,
             has_been_flattened_before: previous.has_been_flattened_before,
             cached_prototype_chain: None,
+            next_order: previous.next_order,
         });
         this.calculated_size = this.get_slots_size() as _;
         assert!(this.previous.is_some());
@@ -340,16 +340,11 @@ impl Structure {
                 entry: None,
                 size: 0,
             },
-            added: (
-                DUMMY_SYMBOL,
-                MapEntry {
-                    offset: u32::MAX,
-                    attrs: AttrSafe::not_found(),
-                },
-            ),
+            added: (DUMMY_SYMBOL, MapEntry::not_found()),
             id: 0,
             calculated_size: 0,
             transit_count: 0,
+            next_order: 0,
         })
     }
     #[allow(dead_code)]
@@ -363,6 +358,7 @@ impl Structure {
         let mut this = Self::ctor1(ctx, prototype, unique, indexed);
         this.table = table;
         this.calculated_size = this.get_slots_size() as _;
+        this.next_order = this.table_next_order();
         this
     }
 
@@ -380,18 +376,14 @@ impl Structure {
                 entry: None,
                 size: 0,
             },
-            added: (
-                DUMMY_SYMBOL,
-                MapEntry {
-                    offset: u32::MAX,
-                    attrs: AttrSafe::not_found(),
-                },
-            ),
+            added: (DUMMY_SYMBOL, MapEntry::not_found()),
             id: 0,
             calculated_size: 0,
             transit_count: 0,
+            next_order: 0,
         });
         this.calculated_size = this.get_slots_size() as _;
+        this.next_order = this.table_next_order();
         this
     }
 
@@ -616,6 +608,16 @@ impl GcPointer<Structure> {
         }
     }
 
+    fn table_next_order(&self) -> u32 {
+        self.table.as_ref().map_or(0, |table| {
+            table
+                .values()
+                .map(|entry| entry.order + 1)
+                .max()
+                .unwrap_or(0)
+        })
+    }
+
     pub fn get_slots_size(&self) -> usize {
         if let Some(table) = self.table.as_ref() {
             table.len() + self.deleted.size as usize
@@ -698,16 +700,19 @@ impl GcPointer<Structure> {
         mut collector: impl FnMut(Symbol, u32),
     ) {
         if self.allocate_table_if_needed(ctx) {
-            for entry in self.table.as_ref().unwrap().iter() {
-                /*if entry.0.is_private() {
-                    continue;
-                }
-                if entry.0.is_public() {
-                    continue;
-                }*/
-                if include || entry.1.attrs.is_enumerable() {
-                    collector(*entry.0, entry.1.offset);
-                }
+            // table is unordered and offsets of deleted properties are reused, report properties
+            // in creation order instead.
+            let mut entries = self
+                .table
+                .as_ref()
+                .unwrap()
+                .iter()
+                .filter(|entry| include || entry.1.attrs.is_enumerable())
+                .map(|(name, entry)| (*name, *entry))
+                .collect::<Vec<_>>();
+            entries.sort_unstable_by_key(|entry| entry.1.order);
+            for (name, entry) in entries {
+                collector(name, entry.offset);
             }
         }
     }
@@ -722,6 +727,7 @@ impl GcPointer<Structure> {
         let mut entry = MapEntry {
             offset: 0,
             attrs: attributes,
+            order: 0,
         };
 
         if self.is_unique() {
@@ -739,6 +745,8 @@ impl GcPointer<Structure> {
             } else {
                 entry.offset = self.get_slots_size() as _;
             }
+            entry.order = map.next_order;
+            map.next_order += 1;
             unwrap_unchecked(map.table.as_mut()).insert(name, entry);
             *offset = entry.offset;
             return map;
@@ -765,6 +773,7 @@ impl GcPointer<Structure> {
                 MapEntry {
                     offset: slot,
                     attrs: attributes,
+                    order: self.next_order,
                 },
             );
             map.calculated_size = self.get_slots_size() as _;
@@ -774,11 +783,13 @@ impl GcPointer<Structure> {
                 MapEntry {
                     offset: self.get_slots_size() as _,
                     attrs: attributes,
+                    order: self.next_order,
                 },
             );
             map.calculated_size = self.get_slots_size() as u32 + 1;
         }
         map.transit_count += 1;
+        map.next_order = self.next_order + 1;
         self.transitions.insert(ctx, name, attributes, map);
         *offset = map.added.1.offset;
         assert!(map.get_slots_size() as u32 > map.added.1.offset);
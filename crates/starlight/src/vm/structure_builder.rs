@@ -36,17 +36,13 @@ impl StructureBuilder {
 
     pub fn add_at(&mut self, symbol: Symbol, index: usize, attributes: AttrSafe) -> MapEntry {
         assert!(self.find(symbol).is_none());
-        self.target_table.insert(
-            symbol,
-            MapEntry {
-                attrs: attributes,
-                offset: index as _,
-            },
-        );
-        MapEntry {
+        let entry = MapEntry {
             attrs: attributes,
             offset: index as _,
-        }
+            order: self.target_table.len() as _,
+        };
+        self.target_table.insert(symbol, entry);
+        entry
     }
 
     pub fn add(&mut self, symbol: Symbol, attributes: AttrSafe) -> MapEntry {
@@ -17,6 +17,13 @@ use std::{mem::MaybeUninit, sync::atomic::AtomicU32};
 pub struct SymbolTable {
     pub(crate) symbols: DashMap<&'static str, u32>,
     pub(crate) ids: DashMap<u32, &'static str>,
+    /// IDs created by [SymbolTable::unique] mapped to `true` if symbol has a description.
+    pub(crate) unique: DashMap<u32, bool>,
+    /// Strings with unpaired surrogates can't be `str` keys, they are interned by their code units
+    /// instead. `ids` maps their IDs to the lossy UTF-8 conversion used by
+    /// [SymbolTable::description].
+    pub(crate) utf16: DashMap<&'static [u16], u32>,
+    pub(crate) utf16_ids: DashMap<u32, &'static [u16]>,
     key: AtomicU32,
 }
 impl Drop for SymbolTable {
@@ -27,8 +34,17 @@ impl Drop for SymbolTable {
                 let _ = Box::from_raw((*key) as *const _ as *mut str);
             }
         }
+        for entry in self.utf16_ids.iter_mut() {
+            let units = entry.value();
+            unsafe {
+                let _ = Box::from_raw((*units) as *const _ as *mut [u16]);
+            }
+        }
         self.symbols.clear();
         self.ids.clear();
+        self.unique.clear();
+        self.utf16.clear();
+        self.utf16_ids.clear();
     }
 }
 
@@ -42,6 +58,9 @@ impl SymbolTable {
         Self {
             symbols: DashMap::with_capacity(0),
             ids: DashMap::with_capacity(0),
+            unique: DashMap::with_capacity(0),
+            utf16: DashMap::with_capacity(0),
+            utf16_ids: DashMap::with_capacity(0),
             key: AtomicU32::new(128),
         }
     }
@@ -65,6 +84,45 @@ impl SymbolTable {
         self.ids.insert(key, string);
         SymbolID(key)
     }
+
+    /// Interns string given as UTF-16 code units that are not well-formed, well-formed strings
+    /// have to be interned with [SymbolTable::intern] so that equal strings get equal IDs.
+    pub fn intern_utf16(&self, units: &[u16]) -> SymbolID {
+        if let Some(key) = self.utf16.get(units) {
+            return SymbolID(*key.value());
+        }
+
+        let units: &'static [u16] = Box::leak(units.to_vec().into_boxed_slice());
+        let make_new_key = || self.key.fetch_add(1, Ordering::Relaxed);
+        let key = *self.utf16.entry(units).or_insert_with(make_new_key).value();
+        let description = Box::leak(String::from_utf16_lossy(units).into_boxed_str());
+        self.ids.insert(key, description);
+        self.utf16_ids.insert(key, units);
+        SymbolID(key)
+    }
+
+    /// Code units of symbol created by [SymbolTable::intern_utf16].
+    pub fn code_units(&self, symbol: SymbolID) -> Option<&'static [u16]> {
+        self.utf16_ids.get(&symbol.0).map(|entry| *entry.value())
+    }
+
+    /// Creates ID that is never returned by [SymbolTable::intern], used by `Symbol()` so that
+    /// symbols with equal descriptions are still different property keys.
+    pub fn unique(&self, description: Option<&str>) -> SymbolID {
+        let string = Box::leak(description.unwrap_or("").to_string().into_boxed_str());
+        let key = self.key.fetch_add(1, Ordering::Relaxed);
+        self.ids.insert(key, string);
+        self.unique.insert(key, description.is_some());
+        SymbolID(key)
+    }
+
+    /// Returns `false` for symbols created by `Symbol()` without a description.
+    pub fn has_description(&self, symbol: SymbolID) -> bool {
+        self.unique
+            .get(&symbol.0)
+            .map(|entry| *entry.value())
+            .unwrap_or(true)
+    }
 }
 
 macro_rules! builtin_symbols {
@@ -202,15 +260,39 @@ pub trait Internable {
     fn intern(&self) -> Symbol;
 }
 
+/// Returns index represented by `string` if it is a canonical numeric string of u32 (`"0"`, `"10"` but
+/// not `"010"` or `"+1"`). Such strings are interned as [Symbol::Index] so `obj["10"]` and `obj[10]`
+/// refer to the same property and are enumerated as integer keys.
+pub fn parse_index(string: &str) -> Option<u32> {
+    let bytes = string.as_bytes();
+    if bytes.is_empty() || bytes.len() > 10 || (bytes[0] == b'0' && bytes.len() > 1) {
+        return None;
+    }
+    let mut index = 0u64;
+    for &byte in bytes {
+        if !byte.is_ascii_digit() {
+            return None;
+        }
+        index = index * 10 + (byte - b'0') as u64;
+    }
+    if index > u32::MAX as u64 {
+        return None;
+    }
+    Some(index as u32)
+}
+
 impl Internable for str {
     fn intern(&self) -> Symbol {
+        if let Some(index) = parse_index(self) {
+            return Symbol::Index(index);
+        }
         Symbol::Key(symbol_table().intern(self))
     }
 }
 
 impl Internable for String {
     fn intern(&self) -> Symbol {
-        Symbol::Key(symbol_table().intern(self))
+        self.as_str().intern()
     }
 }
 
@@ -234,8 +316,15 @@ pub struct JsSymbol {
 }
 
 impl JsSymbol {
+    /// Returns symbol value of `sym`. Values are cached in context so that the same property key
+    /// is always represented by the same (`===`) symbol.
     pub fn new(mut ctx: GcPointer<Context>, sym: Symbol) -> GcPointer<Self> {
-        ctx.heap().allocate(Self { sym })
+        if let Some(symbol) = ctx.symbols.get(&sym) {
+            return *symbol;
+        }
+        let symbol = ctx.heap().allocate(Self { sym });
+        ctx.symbols.insert(sym, symbol);
+        symbol
     }
 
     pub fn symbol(&self) -> Symbol {
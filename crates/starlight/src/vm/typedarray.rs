@@ -1,446 +1,476 @@
-/*
-pub trait TypedArrayType: Default + Copy + Deserializable + Serializable + GcCell + Unpin {
-    fn from_jsvalue(ctx: GcPointer<Context>, val: JsValue) -> Result<Self, JsValue>;
-    fn into_jsvalue(self, ctx: GcPointer<Context>) -> Result<JsValue, JsValue>;
-
-    #[inline]
-    unsafe fn fill(start: *mut Self, end: *mut Self, fill: Self) {
-        let mut cur = start;
-        while cur != end {
-            cur.write(fill);
-            cur = cur.add(1);
-        }
-    }
-
-    #[inline]
-    unsafe fn uninit_copy(
-        mut first: *mut Self,
-        last: *mut Self,
-        mut result: *mut Self,
-    ) -> *mut Self {
-        while first != last {
-            result.write(first.read());
-            first = first.add(1);
-            result = result.add(1);
-        }
-        result
-    }
-
-    #[inline]
-    unsafe fn copy_backward(
-        first: *mut Self,
-        mut last: *mut Self,
-        mut result: *mut Self,
-    ) -> *mut Self {
-        while first != last {
-            last = last.sub(1);
-            result = result.sub(1);
-            result.write(last.read());
-        }
-        result
-    }
-    #[inline]
-    unsafe fn copy(mut first: *mut Self, last: *mut Self, mut result: *mut Self) -> *mut Self {
-        while first != last {
-            result.write(first.read());
-            first = first.add(1);
-            result = result.add(1);
-        }
-        result
-    }
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
+use super::{
+    array_buffer::JsArrayBuffer,
+    class::{Class, JsClassType},
+    context::Context,
+    object::{EnumerationMode, JsObject, ObjectTag, TypedJsObject},
+    property_descriptor::*,
+    slot::*,
+    structure::Structure,
+    symbol_table::Symbol,
+    value::JsValue,
+};
+use crate::gc::cell::{GcPointer, Trace, Tracer};
+use crate::prelude::*;
+use std::mem::{size_of, ManuallyDrop};
+
+/// Element type of typed array. Every kind has its own constructor, prototype and class.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum TypedArrayKind {
+    Int8,
+    Uint8,
+    Uint8Clamped,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Float32,
+    Float64,
 }
-/// A GC-managed resizable vector of values. It is used for storage of property
-/// values in objects and also indexed property values in arrays. It supports
-/// resizing on both ends which is necessary for the simplest implementation of
-/// JavaScript arrays (using a base offset and length).
-#[repr(C)]
-pub struct TypedArrayStorage<T: TypedArrayType> {
-    pub(crate) size: u32,
-    pub(crate) capacity: u32,
-    pub(crate) data: [T; 0],
-}
-
-impl<T: TypedArrayType> GcPointer<TypedArrayStorage<T>> {
-    pub fn resize_within_capacity(&mut self, _rt: &mut Heap, new_size: u32) {
-        assert!(
-            new_size <= self.capacity(),
-            "new_size must be <= capacity in resize_Within_capacity"
-        );
 
-        let sz = self.size();
-        unsafe {
-            if new_size > sz {
-                T::fill(
-                    self.data_mut().add(sz as _),
-                    self.data_mut().add(new_size as _),
-                    T::default(),
-                );
-            }
+impl TypedArrayKind {
+    pub const ALL: [TypedArrayKind; 9] = [
+        TypedArrayKind::Int8,
+        TypedArrayKind::Uint8,
+        TypedArrayKind::Uint8Clamped,
+        TypedArrayKind::Int16,
+        TypedArrayKind::Uint16,
+        TypedArrayKind::Int32,
+        TypedArrayKind::Uint32,
+        TypedArrayKind::Float32,
+        TypedArrayKind::Float64,
+    ];
+
+    pub fn element_size(self) -> usize {
+        match self {
+            Self::Int8 | Self::Uint8 | Self::Uint8Clamped => 1,
+            Self::Int16 | Self::Uint16 => 2,
+            Self::Int32 | Self::Uint32 | Self::Float32 => 4,
+            Self::Float64 => 8,
         }
-        self.size = new_size;
     }
 
-    pub fn ensure_capacity(&mut self, rt: &mut Heap, capacity: u32) {
-        assert!(
-            capacity <= u32::MAX as u32,
-            "capacity overflows 32-bit storage"
-        );
-
-        if capacity <= self.capacity() {
-            return;
-        }
-
-        unsafe { self.reallocate_to_larger(rt, capacity, 0, 0, self.size()) }
-    }
-    pub fn resize(&mut self, rt: &mut Heap, new_size: u32) {
-        self.shift(rt, 0, 0, new_size)
+    pub fn name(self) -> &'static str {
+        self.class().name
     }
 
-    #[cold]
-    pub fn push_back_slowpath(&mut self, rt: &mut Heap, value: T) {
-        let size = self.size();
-
-        self.resize(rt, self.size() + 1);
-        *self.at_mut(size) = value;
-    }
-
-    pub fn push_back(&mut self, rt: &mut Heap, value: T) {
-        let currsz = self.size();
-        if currsz < self.capacity() {
-            unsafe {
-                self.data_mut().add(currsz as _).write(value);
-                self.size = currsz + 1;
-            }
-            return;
+    pub fn tag(self) -> ObjectTag {
+        match self {
+            Self::Int8 => ObjectTag::Int8Array,
+            Self::Uint8 => ObjectTag::Uint8Array,
+            Self::Uint8Clamped => ObjectTag::Uint8ClampedArray,
+            Self::Int16 => ObjectTag::Int16Array,
+            Self::Uint16 => ObjectTag::Uint16Array,
+            Self::Int32 => ObjectTag::Int32Array,
+            Self::Uint32 => ObjectTag::Uint32Array,
+            Self::Float32 => ObjectTag::Float32Array,
+            Self::Float64 => ObjectTag::Float64Array,
         }
-        self.push_back_slowpath(rt, value)
     }
 
-    pub fn pop_back(&mut self, _rt: &mut Heap) -> T {
-        let sz = self.size();
-        assert!(sz > 0, "empty ArrayStorage");
-
-        unsafe {
-            let val = self.data().add(sz as usize - 1).read();
-            self.size = sz - 1;
-            val
+    pub fn from_tag(tag: ObjectTag) -> Option<Self> {
+        Some(match tag {
+            ObjectTag::Int8Array => Self::Int8,
+            ObjectTag::Uint8Array => Self::Uint8,
+            ObjectTag::Uint8ClampedArray => Self::Uint8Clamped,
+            ObjectTag::Int16Array => Self::Int16,
+            ObjectTag::Uint16Array => Self::Uint16,
+            ObjectTag::Int32Array => Self::Int32,
+            ObjectTag::Uint32Array => Self::Uint32,
+            ObjectTag::Float32Array => Self::Float32,
+            ObjectTag::Float64Array => Self::Float64,
+            _ => return None,
+        })
+    }
+
+    pub fn structure(self, ctx: GcPointer<Context>) -> GcPointer<Structure> {
+        let data = ctx.global_data();
+        match self {
+            Self::Int8 => data.int8_array_structure,
+            Self::Uint8 => data.uint8_array_structure,
+            Self::Uint8Clamped => data.uint8_clamped_array_structure,
+            Self::Int16 => data.int16_array_structure,
+            Self::Uint16 => data.uint16_array_structure,
+            Self::Int32 => data.int32_array_structure,
+            Self::Uint32 => data.uint32_array_structure,
+            Self::Float32 => data.float32_array_structure,
+            Self::Float64 => data.float64_array_structure,
         }
-    }
-
-    pub fn shift(&mut self, rt: &mut Heap, from_first: u32, to_first: u32, to_last: u32) {
-        assert!(to_first <= to_last, "First must be before last");
-        assert!(from_first <= self.size, "from_first must be before size");
-        unsafe {
-            if to_last <= self.capacity() {
-                let copy_size = std::cmp::min(self.size() - from_first, to_last - to_first);
-                if from_first > to_first {
-                    T::copy(
-                        self.data_mut().add(from_first as usize),
-                        self.data_mut()
-                            .add(from_first as usize + copy_size as usize),
-                        self.data_mut().add(to_first as usize),
-                    );
-                } else if from_first < to_first {
-                    T::copy_backward(
-                        self.data_mut().add(from_first as usize),
-                        self.data_mut()
-                            .add(from_first as usize + copy_size as usize),
-                        self.data_mut().add(to_first as _),
-                    );
+        .unwrap()
+    }
+
+    /// Read element stored at `ptr`.
+    ///
+    /// # Safety
+    /// `ptr` must point to at least `element_size()` readable bytes.
+    pub unsafe fn load(self, ptr: *const u8) -> JsValue {
+        match self {
+            Self::Int8 => JsValue::new(ptr.cast::<i8>().read()),
+            Self::Uint8 | Self::Uint8Clamped => JsValue::new(ptr.read()),
+            Self::Int16 => JsValue::new(ptr.cast::<i16>().read_unaligned()),
+            Self::Uint16 => JsValue::new(ptr.cast::<u16>().read_unaligned()),
+            Self::Int32 => JsValue::new(ptr.cast::<i32>().read_unaligned()),
+            Self::Uint32 => {
+                let value = ptr.cast::<u32>().read_unaligned();
+                if value <= i32::MAX as u32 {
+                    JsValue::encode_int32(value as i32)
+                } else {
+                    JsValue::new(value as f64)
                 }
-                T::fill(
-                    self.data_mut().add(to_first as usize + copy_size as usize),
-                    self.data_mut().add(to_last as usize),
-                    T::default(),
-                );
-                self.size = to_last;
-                return;
-            }
-
-            let mut capacity = self.capacity();
-            if capacity < TypedArrayStorage::<T>::max_elements() as u32 / 2 {
-                capacity = std::cmp::max(capacity * 2, to_last);
-            } else {
-                capacity = TypedArrayStorage::<T>::max_elements() as u32;
             }
-            self.reallocate_to_larger(rt, capacity, from_first, to_first, to_last)
+            Self::Float32 => JsValue::new(ptr.cast::<f32>().read_unaligned()),
+            Self::Float64 => JsValue::new(ptr.cast::<f64>().read_unaligned()),
         }
     }
 
-    pub unsafe fn reallocate_to_larger(
-        &mut self,
-        rt: &mut Heap,
-        capacity: u32,
-        from_first: u32,
-        to_first: u32,
-        to_last: u32,
-    ) {
-        assert!(capacity > self.capacity());
-
-        let mut arr_res = TypedArrayStorage::<T>::new(rt, capacity);
-        let copy_size = std::cmp::min(self.size() - from_first, to_last - to_first);
-
-        {
-            let from = self.data_mut().add(from_first as _);
-            let to = arr_res.data_mut().add(to_first as _);
-            T::uninit_copy(from, from.add(copy_size as _), to);
-        }
-
-        T::fill(
-            arr_res.data_mut(),
-            arr_res.data_mut().add(to_first as _),
-            T::default(),
-        );
-
-        if to_first + copy_size < to_last {
-            T::fill(
-                arr_res
-                    .data_mut()
-                    .add(to_first as usize + copy_size as usize),
-                arr_res.data_mut().add(to_last as usize),
-                T::default(),
-            );
+    /// Convert `number` to element type and write it to `ptr`.
+    ///
+    /// # Safety
+    /// `ptr` must point to at least `element_size()` writable bytes.
+    pub unsafe fn store(self, ptr: *mut u8, number: f64) {
+        match self {
+            Self::Int8 => ptr.cast::<i8>().write(number_to_uint32(number) as i8),
+            Self::Uint8 => ptr.write(number_to_uint32(number) as u8),
+            Self::Uint8Clamped => ptr.write(number_to_uint8_clamp(number)),
+            Self::Int16 => ptr
+                .cast::<i16>()
+                .write_unaligned(number_to_uint32(number) as i16),
+            Self::Uint16 => ptr
+                .cast::<u16>()
+                .write_unaligned(number_to_uint32(number) as u16),
+            Self::Int32 => ptr
+                .cast::<i32>()
+                .write_unaligned(number_to_uint32(number) as i32),
+            Self::Uint32 => ptr.cast::<u32>().write_unaligned(number_to_uint32(number)),
+            Self::Float32 => ptr.cast::<f32>().write_unaligned(number as f32),
+            Self::Float64 => ptr.cast::<f64>().write_unaligned(number),
         }
-
-        arr_res.size = to_last;
-        *self = arr_res;
     }
 }
 
-impl<T: TypedArrayType> TypedArrayStorage<T> {
-    pub fn max_elements() -> usize {
-        (u32::MAX as usize - 8) / size_of::<T>()
-    }
-    pub fn size(&self) -> u32 {
-        self.size
-    }
-
-    pub fn capacity(&self) -> u32 {
-        self.capacity
+/// Modular integer conversion shared by ToInt8..ToUint32. Result is truncated by the caller.
+pub fn number_to_uint32(number: f64) -> u32 {
+    if !number.is_finite() {
+        return 0;
     }
+    number.trunc().rem_euclid(4294967296.0) as u32
+}
 
-    pub fn is_empty(&self) -> bool {
-        self.size == 0
-    }
-    pub fn with_size(ctx: GcPointer<Context>, size: u32, capacity: u32) -> GcPointer<Self> {
-        let stack = rt.shadowstack();
-        crate::letroot!(this = stack, Self::new(rt.heap(), capacity));
-        this.resize_within_capacity(rt.heap(), size);
-        *this
-    }
-    pub fn new(rt: &mut Heap, capacity: u32) -> GcPointer<Self> {
-        let cell = rt.allocate(Self {
-            capacity,
-            size: 0,
-            data: [],
-        });
-
-        cell
-    }
-    pub fn data(&self) -> *const T {
-        self.data.as_ptr()
+/// ECMA-262 7.1.12 ToUint8Clamp
+pub fn number_to_uint8_clamp(number: f64) -> u8 {
+    if number.is_nan() || number <= 0.0 {
+        return 0;
     }
-    pub fn as_slice(&self) -> &[T] {
-        unsafe { std::slice::from_raw_parts(self.data(), self.size as _) }
-    }
-
-    pub fn data_mut(&mut self) -> *mut T {
-        self.data.as_mut_ptr()
+    if number >= 255.0 {
+        return 255;
     }
-    pub fn as_slice_mut(&mut self) -> &mut [T] {
-        unsafe { std::slice::from_raw_parts_mut(self.data_mut(), self.size as _) }
+    let floor = number.floor();
+    if floor + 0.5 < number {
+        return floor as u8 + 1;
     }
-    pub fn at(&self, index: u32) -> &T {
-        assert!(index < self.size(), "index out of range");
-        unsafe { &*self.data().add(index as _) }
+    if number < floor + 0.5 {
+        return floor as u8;
     }
-    pub fn at_mut(&mut self, index: u32) -> &mut T {
-        assert!(index < self.size(), "index out of range");
-        unsafe { &mut *self.data_mut().add(index as _) }
+    if floor as u8 % 2 == 1 {
+        floor as u8 + 1
+    } else {
+        floor as u8
     }
 }
 
-unsafe impl<T: TypedArrayType> Trace for TypedArrayStorage<T> {
-    fn trace(&mut self, visitor: &mut dyn Tracer) {
-        self.as_slice_mut().iter_mut().for_each(|value| {
-            value.trace(visitor);
-        });
-    }
+/// Integer-indexed exotic object that views `length` elements of `buffer` starting at byte `offset`.
+pub struct JsTypedArray {
+    buffer: TypedJsObject<JsArrayBuffer>,
+    offset: usize,
+    length: usize,
+    kind: TypedArrayKind,
 }
 
-impl<T: TypedArrayType> GcCell for TypedArrayStorage<T> {
-    fn deser_pair(&self) -> (usize, usize) {
-        (Self::deserialize as _, Self::allocate as _)
-    }
-    fn compute_size(&self) -> usize {
-        (self.capacity as usize * size_of::<T>()) + size_of::<Self>()
-    }
+#[allow(improper_ctypes_definitions)]
+extern "C" fn trace_typed_array(tracer: &mut dyn Tracer, obj: &mut JsObject) {
+    obj.data::<JsTypedArray>().buffer.trace(tracer);
 }
 
-impl<T: TypedArrayType> Serializable for TypedArrayStorage<T> {
-    fn serialize(&self, serializer: &mut SnapshotSerializer) {
-        self.capacity.serialize(serializer);
-        self.size.serialize(serializer);
-        for item in self.as_slice().iter() {
-            item.serialize(serializer);
-        }
-    }
+extern "C" fn typed_array_size() -> usize {
+    size_of::<JsTypedArray>()
 }
 
-impl<T: TypedArrayType> Deserializable for TypedArrayStorage<T> {
-    unsafe fn allocate(ctx: GcPointer<Context>, deser: &mut Deserializer) -> *mut GcPointerBase {
-        let cap = u32::deserialize_inplace(deser);
-        deser.pc -= 4;
-        rt.heap().allocate_raw(
-            vtable_of_type::<Self>() as _,
-            cap as usize * size_of::<T>() + size_of::<Self>() + 16,
-            TypeId::of::<Self>(),
-        )
-    }
-    unsafe fn deserialize(at: *mut u8, deser: &mut Deserializer) {
-        let cap = u32::deserialize_inplace(deser);
-        let size = u32::deserialize_inplace(deser);
-        let mut arr = GcPointer::<TypedArrayStorage<T>> {
-            base: NonNull::new_unchecked(at.sub(size_of::<GcPointerBase>()).cast()),
-            marker: PhantomData,
-        };
-        arr.capacity = cap;
-
-        for _ in 0..size {
-            let item = T::deserialize_inplace(deser);
-            arr.push_back((&mut *deser.rt).heap(), item);
-        }
-        assert_eq!(
-            arr.size, size,
-            "cap {}, size {}, found {},{}",
-            cap, size, arr.size, arr.capacity
-        );
-        assert_eq!(arr.capacity, cap);
-    }
-    unsafe fn deserialize_inplace(_deser: &mut Deserializer) -> Self {
-        unreachable!()
-    }
-    unsafe fn dummy_read(_deser: &mut Deserializer) {
-        unreachable!()
-    }
+extern "C" fn typed_array_serialize(obj: &JsObject, serializer: &mut SnapshotSerializer) {
+    let data = obj.data::<JsTypedArray>();
+    data.buffer.serialize(serializer);
+    (data.offset as u64).serialize(serializer);
+    (data.length as u64).serialize(serializer);
+    (data.kind as u8).serialize(serializer);
 }
 
-impl TypedArrayType for u32 {
-    fn into_jsvalue(self, _ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
-        Ok(JsValue::encode_int32(self as i32))
-    }
-    fn from_jsvalue(ctx: GcPointer<Context>, val: JsValue) -> Result<Self, JsValue> {
-        val.to_uint32(vm)
+extern "C" fn typed_array_deserialize(obj: &mut JsObject, deser: &mut Deserializer) {
+    unsafe {
+        let buffer = TypedJsObject::<JsArrayBuffer>::deserialize_inplace(deser);
+        let offset = u64::deserialize_inplace(deser) as usize;
+        let length = u64::deserialize_inplace(deser) as usize;
+        let kind = TypedArrayKind::ALL[u8::deserialize_inplace(deser) as usize];
+        *obj.data::<JsTypedArray>() = ManuallyDrop::new(JsTypedArray {
+            buffer,
+            offset,
+            length,
+            kind,
+        });
     }
 }
 
-impl TypedArrayType for u16 {
-    fn into_jsvalue(self, _ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
-        Ok(JsValue::encode_int32(self as i32))
-    }
-    fn from_jsvalue(ctx: GcPointer<Context>, val: JsValue) -> Result<Self, JsValue> {
-        val.to_uint32(vm).map(|x| x as Self)
-    }
+macro_rules! typed_array_classes {
+    ($($kind: ident => $name: ident),*) => {
+        impl TypedArrayKind {
+            /// Class of typed arrays with this element type.
+            pub fn class(self) -> &'static Class {
+                match self {
+                    $(TypedArrayKind::$kind => {
+                        static CLASS: Class = Class {
+                            name: stringify!($name),
+                            ty: JsClassType::$name as _,
+                            method_table: js_method_table!(JsTypedArray),
+                            drop: None,
+                            trace: Some(trace_typed_array),
+                            deserialize: Some(typed_array_deserialize),
+                            serialize: Some(typed_array_serialize),
+                            additional_size: Some(typed_array_size),
+                        };
+                        &CLASS
+                    })*
+                }
+            }
+        }
+    };
 }
-impl TypedArrayType for u8 {
-    fn into_jsvalue(self, _ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
-        Ok(JsValue::encode_int32(self as i32))
+
+typed_array_classes!(
+    Int8 => Int8Array,
+    Uint8 => Uint8Array,
+    Uint8Clamped => Uint8ClampedArray,
+    Int16 => Int16Array,
+    Uint16 => Uint16Array,
+    Int32 => Int32Array,
+    Uint32 => Uint32Array,
+    Float32 => Float32Array,
+    Float64 => Float64Array
+);
+
+impl JsClassMethodTable for JsTypedArray {}
+
+#[allow(non_snake_case)]
+impl JsTypedArray {
+    /// Create new typed array object that views `buffer`. Caller is responsible for range checks.
+    pub fn new(
+        ctx: GcPointer<Context>,
+        kind: TypedArrayKind,
+        buffer: TypedJsObject<JsArrayBuffer>,
+        offset: usize,
+        length: usize,
+    ) -> GcPointer<JsObject> {
+        let stack = ctx.shadowstack();
+        letroot!(structure = stack, kind.structure(ctx));
+        let mut obj = JsObject::new(ctx, &structure, kind.class(), kind.tag());
+        *obj.data::<Self>() = ManuallyDrop::new(Self {
+            buffer,
+            offset,
+            length,
+            kind,
+        });
+        obj
+    }
+
+    /// Create new typed array with fresh zeroed buffer for `length` elements.
+    pub fn with_length(
+        ctx: GcPointer<Context>,
+        kind: TypedArrayKind,
+        length: usize,
+    ) -> Result<GcPointer<JsObject>, JsValue> {
+        let byte_length = match length.checked_mul(kind.element_size()) {
+            Some(byte_length) => byte_length,
+            None => {
+                return Err(JsValue::new(
+                    ctx.new_range_error("Invalid typed array length"),
+                ))
+            }
+        };
+        let stack = ctx.shadowstack();
+        letroot!(buffer = stack, JsArrayBuffer::new(ctx));
+        TypedJsObject::<JsArrayBuffer>::new(*buffer).create_data_block(ctx, byte_length, true)?;
+        Ok(Self::new(ctx, kind, TypedJsObject::new(*buffer), 0, length))
     }
-    fn from_jsvalue(ctx: GcPointer<Context>, val: JsValue) -> Result<Self, JsValue> {
-        val.to_uint32(vm).map(|x| x as Self)
+
+    #[inline]
+    pub fn is_typed_array(obj: &JsObject) -> bool {
+        TypedArrayKind::from_tag(obj.tag).is_some()
     }
-}
 
-impl TypedArrayType for i8 {
-    fn into_jsvalue(self, _ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
-        Ok(JsValue::encode_int32(self as i32))
+    pub fn kind(&self) -> TypedArrayKind {
+        self.kind
     }
-    fn from_jsvalue(ctx: GcPointer<Context>, val: JsValue) -> Result<Self, JsValue> {
-        val.to_int32(vm).map(|x| x as Self)
+
+    pub fn buffer(&self) -> TypedJsObject<JsArrayBuffer> {
+        self.buffer
     }
-}
 
-impl TypedArrayType for i16 {
-    fn into_jsvalue(self, _ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
-        Ok(JsValue::encode_int32(self as i32))
+    pub fn attached(&self) -> bool {
+        self.buffer.attached()
     }
-    fn from_jsvalue(ctx: GcPointer<Context>, val: JsValue) -> Result<Self, JsValue> {
-        val.to_int32(vm).map(|x| x as Self)
+
+    /// Element count. Views of detached buffers are always empty.
+    pub fn length(&self) -> usize {
+        if self.attached() {
+            self.length
+        } else {
+            0
+        }
     }
-}
 
-impl TypedArrayType for i32 {
-    fn into_jsvalue(self, _ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
-        Ok(JsValue::encode_int32(self as i32))
+    pub fn byte_length(&self) -> usize {
+        self.length() * self.kind.element_size()
     }
-    fn from_jsvalue(ctx: GcPointer<Context>, val: JsValue) -> Result<Self, JsValue> {
-        val.to_int32(vm).map(|x| x as Self)
+
+    pub fn byte_offset(&self) -> usize {
+        if self.attached() {
+            self.offset
+        } else {
+            0
+        }
     }
-}
-impl TypedArrayType for i64 {
-    fn into_jsvalue(self, _ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
-        Ok(JsValue::new(self))
+
+    fn element_ptr(&self, index: usize) -> *mut u8 {
+        unsafe {
+            self.buffer
+                .get_data_block()
+                .add(self.offset + index * self.kind.element_size())
+        }
     }
 
-    fn from_jsvalue(ctx: GcPointer<Context>, val: JsValue) -> Result<Self, JsValue> {
-        Ok(val.to_number(vm)? as _)
+    /// Read element at `index`, `None` is returned for out of bounds indices.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<JsValue> {
+        if index < self.length() {
+            return Some(unsafe { self.kind.load(self.element_ptr(index)) });
+        }
+        None
     }
-}
 
-impl TypedArrayType for u64 {
-    fn into_jsvalue(self, _ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
-        Ok(JsValue::new(self as f64))
+    /// Write already converted `number` to `index`. Returns false if `index` is out of bounds.
+    #[inline]
+    pub fn set(&self, index: usize, number: f64) -> bool {
+        if index < self.length() {
+            unsafe {
+                self.kind.store(self.element_ptr(index), number);
+            }
+            return true;
+        }
+        false
+    }
+
+    pub fn GetOwnIndexedPropertySlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        _ctx: GcPointer<Context>,
+        index: u32,
+        slot: &mut Slot,
+    ) -> bool {
+        match obj.data::<Self>().get(index as _) {
+            Some(value) => {
+                slot.set_1(value, object_data(), Some(obj.as_dyn()));
+                true
+            }
+            None => false,
+        }
     }
-    fn from_jsvalue(ctx: GcPointer<Context>, val: JsValue) -> Result<Self, JsValue> {
-        Ok(val.to_number(vm)? as u64)
+
+    /// Integer indices never reach prototype chain of typed array.
+    pub fn GetIndexedPropertySlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        index: u32,
+        slot: &mut Slot,
+    ) -> bool {
+        Self::GetOwnIndexedPropertySlotMethod(obj, ctx, index, slot)
+    }
+
+    pub fn PutIndexedSlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        index: u32,
+        val: JsValue,
+        _slot: &mut Slot,
+        _throwable: bool,
+    ) -> Result<(), JsValue> {
+        let number = val.to_number(ctx)?;
+        obj.data::<Self>().set(index as _, number);
+        Ok(())
+    }
+
+    pub fn DefineOwnIndexedPropertySlotMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        index: u32,
+        desc: &PropertyDescriptor,
+        _slot: &mut Slot,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        let reject = |msg: &str| -> Result<bool, JsValue> {
+            if throwable {
+                return Err(JsValue::new(ctx.new_type_error(msg)));
+            }
+            Ok(false)
+        };
+        if index as usize >= obj.data::<Self>().length() {
+            return reject("Typed array index is out of bounds");
+        }
+        if desc.is_accessor() {
+            return reject("Cannot define accessor on typed array element");
+        }
+        if (!desc.is_configurable_absent() && !desc.is_configurable())
+            || (!desc.is_enumerable_absent() && !desc.is_enumerable())
+        {
+            return reject("Typed array element must be configurable and enumerable");
+        }
+        if desc.is_data() {
+            let data = DataDescriptor { parent: *desc };
+            if !data.is_writable_absent() && !data.is_writable() {
+                return reject("Typed array element must be writable");
+            }
+            if !data.is_value_absent() {
+                let number = desc.value().to_number(ctx)?;
+                obj.data::<Self>().set(index as _, number);
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn DeleteIndexedMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        index: u32,
+        throwable: bool,
+    ) -> Result<bool, JsValue> {
+        if (index as usize) < obj.data::<Self>().length() {
+            if throwable {
+                return Err(JsValue::new(
+                    ctx.new_type_error("Cannot delete typed array element"),
+                ));
+            }
+            return Ok(false);
+        }
+        Ok(true)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::gc::migc::MiGC;
-
-    use super::*;
-    #[test]
-    fn test_ser_deser() {
-        let mut rt = Platform::new_runtime(RuntimeParams::default(), GcParams::default(), None);
-
-        let mut my_typed_array = TypedArrayStorage::<u32>::new(rt.heap(), 100);
-        my_typed_array.push_back(rt.heap(), 42);
-
-        assert_eq!(*my_typed_array.at(0), 42);
-        rt.global_object()
-            .put(
-                &mut rt,
-                "myTypedArray".intern(),
-                JsValue::encode_object_value(my_typed_array),
-                false,
-            )
-            .unwrap_or_else(|_| unreachable!());
-
-        let snapshot = Snapshot::take(false, &mut rt, |_, _| {});
-
-        let mut rt = Deserializer::deserialize(
-            false,
-            &snapshot.buffer,
-            RuntimeParams::default(),
-            Heap::new(MiGC::new(GcParams::default())),
-            None,
-            |_, _| {},
-        );
-
-        let my_typed_array = rt.get_global("myTypedArray").unwrap();
-        let object = my_typed_array
-            .get_object()
-            .downcast::<TypedArrayStorage<u32>>()
-            .unwrap();
-        assert_eq!(*object.at(0), 42);
+    pub fn GetOwnPropertyNamesMethod(
+        obj: &mut GcPointer<JsObject>,
+        ctx: GcPointer<Context>,
+        collector: &mut dyn FnMut(Symbol, u32),
+        mode: EnumerationMode,
+    ) {
+        for i in 0..obj.data::<Self>().length() as u32 {
+            collector(Symbol::Index(i), u32::MAX);
+        }
+        JsObject::GetOwnPropertyNamesMethod(obj, ctx, collector, mode)
     }
 }
-*/
-#[allow(dead_code)]
-pub struct JsTypedArrayBase {
-    length: usize,
-    byte_width: u8,
-    offset: usize,
-}
@@ -13,8 +13,10 @@ use crate::{
     vm::interpreter::SpreadValue,
 };
 
+use num::Zero;
 use std::{
     any::TypeId,
+    cmp::Ordering,
     convert::TryFrom,
     hash::{Hash, Hasher},
     hint::unreachable_unchecked,
@@ -23,6 +25,7 @@ use std::{
 
 use super::{
     attributes::*,
+    bigint::{BigIntObject, JsBigInt},
     class::JsClass,
     error::*,
     number::*,
@@ -305,8 +308,24 @@ impl JsValue {
     /// See: <https://tc39.es/ecma262/#sec-tonumeric>
     pub fn to_numeric_number(self, ctx: GcPointer<Context>) -> Result<f64, JsValue> {
         let primitive = self.to_primitive(ctx, JsHint::Number)?;
+        if primitive.is_bigint() {
+            return Ok(primitive.get_bigint().to_f64());
+        }
         primitive.to_number(ctx)
     }
+    /// ToNumeric(value). Result is either number or BigInt.
+    ///
+    /// See: <https://tc39.es/ecma262/#sec-tonumeric>
+    pub fn to_numeric(self, ctx: GcPointer<Context>) -> Result<JsValue, JsValue> {
+        if likely(self.is_number()) || self.is_bigint() {
+            return Ok(self);
+        }
+        let primitive = self.to_primitive(ctx, JsHint::Number)?;
+        if primitive.is_bigint() {
+            return Ok(primitive);
+        }
+        primitive.to_number(ctx).map(JsValue::new)
+    }
     #[inline]
     pub unsafe fn fill(start: *mut Self, end: *mut Self, fill: JsValue) {
         let mut cur = start;
@@ -374,14 +393,17 @@ impl JsValue {
         if !lhs.is_object() || !rhs.is_object() {
             return lhs.get_raw() == rhs.get_raw();
         }
+        if lhs.is_bigint() && rhs.is_bigint() {
+            return lhs.get_bigint().value() == rhs.get_bigint().value();
+        }
         if lhs.is_object()
             && rhs.is_object()
             && lhs.get_object().is::<JsString>()
             && rhs.get_object().is::<JsString>()
         {
             return unsafe {
-                lhs.get_object().downcast_unchecked::<JsString>().as_str()
-                    == rhs.get_object().downcast_unchecked::<JsString>().as_str()
+                *lhs.get_object().downcast_unchecked::<JsString>()
+                    == *rhs.get_object().downcast_unchecked::<JsString>()
             };
         }
         lhs.get_raw() == rhs.get_raw()
@@ -416,6 +438,9 @@ impl JsValue {
         if self.is_bool() {
             return Ok(BooleanObject::new(ctx, self.get_bool()));
         }
+        if self.is_bigint() {
+            return Ok(BigIntObject::new(ctx, self.get_bigint()));
+        }
         Err(JsValue::new(
             ctx.new_type_error("NYI: JsValue::to_object cases"),
         ))
@@ -426,6 +451,13 @@ impl JsValue {
     pub fn is_symbol(self) -> bool {
         self.is_object() && self.get_object().is::<JsSymbol>()
     }
+    pub fn is_bigint(self) -> bool {
+        self.is_object() && self.get_object().is::<JsBigInt>()
+    }
+    pub fn get_bigint(self) -> GcPointer<JsBigInt> {
+        assert!(self.is_bigint());
+        unsafe { self.get_object().downcast_unchecked() }
+    }
     pub fn to_primitive(self, ctx: GcPointer<Context>, hint: JsHint) -> Result<JsValue, JsValue> {
         if self.is_object() && self.get_object().is::<JsObject>() {
             let mut object = unsafe { self.get_object().downcast_unchecked::<JsObject>() };
@@ -434,6 +466,13 @@ impl JsValue {
             Ok(self)
         }
     }
+    fn ordering_compare(ordering: Option<Ordering>) -> i32 {
+        match ordering {
+            None => CMP_UNDEF,
+            Some(Ordering::Less) => CMP_TRUE,
+            Some(_) => CMP_FALSE,
+        }
+    }
     fn number_compare(x: f64, y: f64) -> i32 {
         if x.is_nan() || y.is_nan() {
             return CMP_UNDEF;
@@ -475,7 +514,7 @@ impl JsValue {
             }
 
             if lhs.is_jsstring() && rhs.is_jsstring() {
-                return Ok(lhs.get_string().as_str() == rhs.get_string().as_str());
+                return Ok(*lhs.get_string() == *rhs.get_string());
             }
 
             if lhs.is_symbol() && rhs.is_symbol() {
@@ -484,6 +523,29 @@ impl JsValue {
             if lhs.is_jsobject() && rhs.is_jsobject() {
                 return Ok(lhs.get_raw() == rhs.get_raw());
             }
+            if lhs.is_bigint() && rhs.is_bigint() {
+                return Ok(lhs.get_bigint().value() == rhs.get_bigint().value());
+            }
+            if lhs.is_bigint() && rhs.is_number() {
+                return Ok(JsBigInt::compare_with_number(
+                    lhs.get_bigint().value(),
+                    rhs.get_number(),
+                ) == Some(Ordering::Equal));
+            }
+            if lhs.is_number() && rhs.is_bigint() {
+                return Ok(JsBigInt::compare_with_number(
+                    rhs.get_bigint().value(),
+                    lhs.get_number(),
+                ) == Some(Ordering::Equal));
+            }
+            if lhs.is_bigint() && rhs.is_jsstring() {
+                return Ok(JsBigInt::parse(&rhs.get_string().as_str())
+                    .map_or(false, |value| *lhs.get_bigint().value() == value));
+            }
+            if lhs.is_jsstring() && rhs.is_bigint() {
+                return Ok(JsBigInt::parse(&lhs.get_string().as_str())
+                    .map_or(false, |value| *rhs.get_bigint().value() == value));
+            }
             if lhs.is_number() && rhs.is_jsstring() {
                 rhs = JsValue::new(rhs.to_number(ctx)?);
                 continue;
@@ -503,12 +565,12 @@ impl JsValue {
                 continue;
             }
 
-            if (lhs.is_jsstring() || lhs.is_number()) && rhs.is_object() {
+            if (lhs.is_jsstring() || lhs.is_number() || lhs.is_bigint()) && rhs.is_jsobject() {
                 rhs = rhs.to_primitive(ctx, JsHint::None)?;
                 continue;
             }
 
-            if lhs.is_object() && (rhs.is_jsstring() || rhs.is_number()) {
+            if lhs.is_jsobject() && (rhs.is_jsstring() || rhs.is_number() || rhs.is_bigint()) {
                 lhs = lhs.to_primitive(ctx, JsHint::None)?;
                 continue;
             }
@@ -526,7 +588,10 @@ impl JsValue {
         }
 
         if self.is_jsstring() && other.is_jsstring() {
-            return self.get_string().as_str() == other.get_string().as_str();
+            return *self.get_string() == *other.get_string();
+        }
+        if self.is_bigint() && other.is_bigint() {
+            return self.get_bigint().value() == other.get_bigint().value();
         }
         self.get_raw() == other.get_raw()
     }
@@ -556,30 +621,50 @@ impl JsValue {
         }
         if likely(px.is_jsstring() && py.is_jsstring()) {
             #[inline(never)]
-            fn slow_string_cmp(x: &str, y: &str) -> Result<i32, JsValue> {
-                if x.starts_with(y) {
-                    return Ok(CMP_FALSE);
-                }
-                if y.starts_with(x) {
-                    return Ok(CMP_TRUE);
-                }
-                for (x, y) in x.chars().zip(y.chars()) {
-                    if x != y {
-                        return Ok(if x < y { CMP_TRUE } else { CMP_FALSE });
-                    }
-                }
-                unreachable!()
+            fn slow_string_cmp(x: &JsString, y: &JsString) -> Result<i32, JsValue> {
+                // code unit order, not code point order.
+                Ok(if x < y { CMP_TRUE } else { CMP_FALSE })
             }
             let x = px.get_string();
             let y = py.get_string();
-            let (x, y) = (x.as_str(), y.as_str());
-            return slow_string_cmp(x, y);
+            return slow_string_cmp(&x, &y);
+        } else if px.is_bigint() || py.is_bigint() {
+            Self::bigint_compare(ctx, px, py)
         } else {
             let nx = px.to_number(ctx)?;
             let ny = py.to_number(ctx)?;
             Ok(Self::number_compare(nx, ny))
         }
     }
+    #[inline(never)]
+    fn bigint_compare(ctx: GcPointer<Context>, px: JsValue, py: JsValue) -> Result<i32, JsValue> {
+        if px.is_bigint() && py.is_bigint() {
+            return Ok(Self::ordering_compare(Some(
+                px.get_bigint().value().cmp(py.get_bigint().value()),
+            )));
+        }
+        if px.is_bigint() && py.is_jsstring() {
+            return Ok(Self::ordering_compare(
+                JsBigInt::parse(&py.get_string().as_str()).map(|y| px.get_bigint().value().cmp(&y)),
+            ));
+        }
+        if px.is_jsstring() && py.is_bigint() {
+            return Ok(Self::ordering_compare(
+                JsBigInt::parse(&px.get_string().as_str()).map(|x| x.cmp(py.get_bigint().value())),
+            ));
+        }
+        if px.is_bigint() {
+            let ny = py.to_number(ctx)?;
+            return Ok(Self::ordering_compare(JsBigInt::compare_with_number(
+                px.get_bigint().value(),
+                ny,
+            )));
+        }
+        let nx = px.to_number(ctx)?;
+        Ok(Self::ordering_compare(
+            JsBigInt::compare_with_number(py.get_bigint().value(), nx).map(Ordering::reverse),
+        ))
+    }
     pub fn compare_left(self, rhs: Self, ctx: GcPointer<Context>) -> Result<i32, JsValue> {
         Self::compare(self, rhs, true, ctx)
     }
@@ -630,14 +715,15 @@ impl JsValue {
                 self.get_object().downcast_unchecked::<JsObject>()
             });
 
-            match (obj.class().method_table.DefaultValue)(&mut obj, ctx, JsHint::Number) {
-                Ok(val) => val.to_number(ctx),
-                Err(e) => Err(e),
-            }
+            obj.to_primitive(ctx, JsHint::Number)?.to_number(ctx)
         } else if unlikely(self.is_symbol()) {
             return Err(JsValue::new(
                 ctx.new_type_error("Cannot convectx Symbol to number"),
             ));
+        } else if unlikely(self.is_bigint()) {
+            return Err(JsValue::new(
+                ctx.new_type_error("Cannot convert a BigInt value to a number"),
+            ));
         } else {
             unsafe { unreachable_unchecked() }
         }
@@ -657,11 +743,25 @@ impl JsValue {
             || self.is_bool()
             || (self.is_object() && self.get_object().is::<JsString>())
             || (self.is_object() && self.get_object().is::<JsSymbol>())
+            || self.is_bigint()
+    }
+
+    /// ToString(value) as [JsString]. Unlike [JsValue::to_string] this doesn't lose unpaired
+    /// surrogates and doesn't copy strings.
+    pub fn to_jsstring(self, ctx: GcPointer<Context>) -> Result<GcPointer<JsString>, JsValue> {
+        if self.is_jsstring() {
+            return Ok(self.get_jsstring());
+        }
+        if self.is_jsobject() {
+            let prim = self.to_primitive(ctx, JsHint::String)?;
+            return prim.to_jsstring(ctx);
+        }
+        Ok(JsString::new(ctx, self.to_string(ctx)?))
     }
 
     pub fn to_string(&self, ctx: GcPointer<Context>) -> Result<String, JsValue> {
         if self.is_number() {
-            Ok(self.get_number().to_string())
+            Ok(f64_to_string(self.get_number()))
         } else if self.is_null() {
             Ok("null".to_string())
         } else if self.is_undefined() {
@@ -671,7 +771,7 @@ impl JsValue {
         } else if self.is_object() {
             let object = self.get_object();
             if let Some(jsstr) = object.downcast::<JsString>() {
-                return Ok(jsstr.as_str().to_owned());
+                return Ok(jsstr.as_str().into_owned());
             } else if let Some(object) = object.downcast::<JsObject>() {
                 let stack = ctx.shadowstack();
                 letroot!(object = stack, object);
@@ -683,6 +783,9 @@ impl JsValue {
             if object.is::<SpreadValue>() {
                 return Ok("spread".to_string());
             }
+            if let Some(bigint) = object.downcast::<JsBigInt>() {
+                return Ok(bigint.value().to_string());
+            }
             if object.is::<JsSymbol>() {
                 return Err(JsValue::new(
                     ctx.new_type_error("Cannot perform ToString on Symbol"),
@@ -703,10 +806,10 @@ impl JsValue {
             if n as u32 as f64 == n {
                 return Ok(Symbol::Index(n as u32));
             }
-            return Ok(n.to_string().intern());
+            return Ok(f64_to_string(n).intern());
         }
         if self.is_jsstring() {
-            return Ok(self.get_string().as_str().intern());
+            return Ok(self.get_string().intern());
         }
         if self.is_null() {
             return Ok("null".intern());
@@ -726,6 +829,9 @@ impl JsValue {
         if self.is_undefined() {
             return Ok("undefined".intern());
         }
+        if self.is_bigint() {
+            return Ok(self.get_bigint().value().to_string().intern());
+        }
         let mut obj = self.get_object().downcast::<JsObject>().unwrap();
         let prim = obj.to_primitive(ctx, JsHint::String)?;
         prim.to_symbol(ctx)
@@ -740,6 +846,8 @@ impl JsValue {
             return ctx.global_data().number_prototype.unwrap();
         } else if self.is_bool() {
             return ctx.global_data().boolean_prototype.unwrap();
+        } else if self.is_bigint() {
+            return ctx.global_data().bigint_prototype.unwrap();
         } else {
             return ctx.global_data().symbol_prototype.unwrap();
         }
@@ -769,6 +877,8 @@ impl JsValue {
             return "undefined";
         } else if self.is_null() {
             return "object";
+        } else if self.is_bigint() {
+            return "bigint";
         } else {
             return "symbol";
         }
@@ -814,14 +924,7 @@ impl JsValue {
 
                 if let Symbol::Index(index) = name {
                     if index < str.len() {
-                        let char = str
-                            .as_str()
-                            .chars()
-                            .nth(index as usize)
-                            .map(|x| {
-                                JsValue::encode_object_value(JsString::new(ctx, x.to_string()))
-                            })
-                            .unwrap_or_else(JsValue::encode_undefined_value);
+                        let char = JsValue::new(str.substring(ctx, index, index + 1));
                         slot.set_1(char, string_indexed(), Some(str.as_dyn()));
                         return Ok(slot.value());
                     }
@@ -847,6 +950,8 @@ impl JsValue {
             return false;
         } else if self.is_bool() {
             return self.get_bool();
+        } else if self.is_bigint() {
+            return !self.get_bigint().value().is_zero();
         } else {
             true
         }
@@ -1013,6 +1118,42 @@ mod tests {
         assert!(val.is_number());
         assert!(val.get_number().is_nan());
     }
+
+    #[test]
+    fn test_lone_surrogate_keys() {
+        use crate::vm::tests::assert_eval_cases;
+        assert_eval_cases(&[
+            (
+                "var hi = String.fromCharCode(0xd800), lo = String.fromCharCode(0xdc00); \
+                 var o = {}; o[hi] = 1; o[lo] = 2; o['\\ufffd'] = 3; Object.keys(o).length",
+                "3",
+            ),
+            (
+                "Object.keys(o)[0] === hi && Reflect.ownKeys(o)[1] === lo",
+                "true",
+            ),
+            ("o[hi] + o[lo] + o['\\ufffd']", "6"),
+            (
+                "var keys = []; for (var k in o) keys.push(k); keys[1] === lo",
+                "true",
+            ),
+            (
+                "Symbol.for(hi) !== Symbol.for(lo) && Symbol.keyFor(Symbol.for(hi)) === hi",
+                "true",
+            ),
+            (
+                "var pair = hi + lo; o[pair] = 4; pair === '\\ud800\\udc00' && o[pair] === 4 && o[hi] === 1",
+                "true",
+            ),
+            ("pair.length + ',' + pair.codePointAt(0)", "2,65536"),
+            (
+                "var mixed = '\\u00ff' + '\\u0100'; mixed.length + ',' + mixed.charCodeAt(0) + ',' + mixed.charCodeAt(1)",
+                "2,255,256",
+            ),
+            ("mixed.slice(0, 1) === '\\u00ff'", "true"),
+            ("(hi + 'a').charCodeAt(0) + ',' + (hi + 'a')[1]", "55296,a"),
+        ]);
+    }
 }
 
 pub struct HashValueZero(pub JsValue);
@@ -1029,15 +1170,20 @@ impl Hash for HashValueZero {
             if d.is_nan() {
                 return std::f64::NAN.to_bits().hash(state);
             }
-            if d == 0.0 {
-                return 0i32.hash(state);
+            // Doubles that are equal to an int32 have to hash like the int32.
+            if d as i32 as f64 == d {
+                return (d as i32).hash(state);
             }
             return d.to_bits().hash(state);
         }
 
         if value.is_jsstring() {
             let string = value.get_jsstring();
-            return string.as_str().hash(state);
+            return (*string).hash(state);
+        }
+
+        if value.is_bigint() {
+            return value.get_bigint().value().hash(state);
         }
 
         value.get_raw().hash(state);